@@ -0,0 +1,691 @@
+//! Reading [GGUF](https://github.com/ggerganov/ggml/blob/master/docs/gguf.md) files, the
+//! checkpoint format of ggml and llama.cpp.
+//!
+//! A [Gguf] parses the header, the metadata and the tensor infos, and memory maps the
+//! tensor data like `LoadSafeTensors` does. Tensors that aren't quantized are loaded with
+//! [Gguf::tensor()], and the quantized ones ([GgmlType::Q4_0], [GgmlType::Q8_0] and so on)
+//! are read as a [QuantizedTensor], which is only dequantized to `f32` when asked to.
+//!
+//! ggml lists dimensions from the fastest varying one, so shapes are reversed to be
+//! row major like the rest of dfdx: a `[4096, 32000]` ggml matrix is a `(32000, 4096)`
+//! tensor.
+
+mod quant;
+
+use std::{
+    format,
+    path::Path,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{
+    shapes::{Dtype, DynShape},
+    tensor::{Tensor, TensorFromVec},
+};
+
+pub use quant::GgmlType;
+
+const MAGIC: &[u8] = b"GGUF";
+const DEFAULT_ALIGNMENT: usize = 32;
+
+#[derive(Debug)]
+pub enum GgufError {
+    IoError(std::io::Error),
+    /// A version or tensor type that can't be read.
+    Unsupported(String),
+    /// The file is malformed.
+    Invalid(String),
+    /// There is no tensor with this name.
+    MissingTensor(String),
+}
+
+impl std::fmt::Display for GgufError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "{err}"),
+            Self::Unsupported(msg) => write!(f, "unsupported: {msg}"),
+            Self::Invalid(msg) => write!(f, "invalid: {msg}"),
+            Self::MissingTensor(name) => write!(f, "no tensor named {name}"),
+        }
+    }
+}
+
+impl std::error::Error for GgufError {}
+
+impl From<std::io::Error> for GgufError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+/// A metadata value.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GgufValue {
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Bool(bool),
+    String(String),
+    Array(Vec<GgufValue>),
+}
+
+impl GgufValue {
+    /// The value of any of the integer types.
+    pub fn to_u64(&self) -> Option<u64> {
+        match *self {
+            Self::U8(x) => Some(x as u64),
+            Self::U16(x) => Some(x as u64),
+            Self::U32(x) => Some(x as u64),
+            Self::U64(x) => Some(x),
+            Self::I8(x) => u64::try_from(x).ok(),
+            Self::I16(x) => u64::try_from(x).ok(),
+            Self::I32(x) => u64::try_from(x).ok(),
+            Self::I64(x) => u64::try_from(x).ok(),
+            _ => None,
+        }
+    }
+
+    /// The value of either of the float types.
+    pub fn to_f64(&self) -> Option<f64> {
+        match *self {
+            Self::F32(x) => Some(x as f64),
+            Self::F64(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[GgufValue]> {
+        match self {
+            Self::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// The name, type and location of a tensor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GgufTensorInfo {
+    pub name: String,
+    pub dtype: GgmlType,
+    /// Row major, i.e. the reverse of the dimensions in the file.
+    pub shape: Vec<usize>,
+    /// The offset of the data from the start of the tensor data of the file.
+    pub offset: usize,
+}
+
+impl GgufTensorInfo {
+    pub fn numel(&self) -> usize {
+        self.shape.iter().product()
+    }
+}
+
+enum Buffer {
+    Mmap(memmap2::Mmap),
+    Owned(Vec<u8>),
+}
+
+impl std::ops::Deref for Buffer {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mmap(m) => m,
+            Self::Owned(v) => v,
+        }
+    }
+}
+
+/// A GGUF file.
+///
+/// Example:
+/// ```ignore
+/// let gguf = Gguf::load("llama-7b.Q4_0.gguf")?;
+/// let n_layers = gguf.metadata("llama.block_count").and_then(GgufValue::to_u64);
+/// let norm: Tensor<DynShape, f32, _> = gguf.tensor("output_norm.weight", &dev)?;
+/// let wq = gguf.quantized("blk.0.attn_q.weight")?;
+/// assert_eq!(wq.dtype(), GgmlType::Q4_0);
+/// let wq: Tensor<DynShape, f32, _> = wq.dequantize(&dev)?;
+/// ```
+pub struct Gguf {
+    buffer: Buffer,
+    version: u32,
+    metadata: Vec<(String, GgufValue)>,
+    tensors: Vec<GgufTensorInfo>,
+    data_offset: usize,
+}
+
+impl std::fmt::Debug for Gguf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gguf")
+            .field("version", &self.version)
+            .field("metadata", &self.metadata)
+            .field("tensors", &self.tensors)
+            .finish()
+    }
+}
+
+impl Gguf {
+    /// Memory maps the file at `path`, and reads its header.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GgufError> {
+        let f = std::fs::File::open(path)?;
+        let mmap = unsafe { memmap2::MmapOptions::new().map(&f)? };
+        Self::parse(Buffer::Mmap(mmap))
+    }
+
+    /// Reads a file that is already in memory.
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, GgufError> {
+        Self::parse(Buffer::Owned(bytes))
+    }
+
+    fn parse(buffer: Buffer) -> Result<Self, GgufError> {
+        let mut r = Reader {
+            bytes: &buffer,
+            pos: 0,
+        };
+        if r.take(4)? != MAGIC {
+            return Err(GgufError::Invalid("not a GGUF file".to_string()));
+        }
+        let version = match r.u32()? {
+            v @ (2 | 3) => v,
+            v if v.swap_bytes() <= 3 => {
+                return Err(GgufError::Unsupported("big endian files".to_string()))
+            }
+            v => return Err(GgufError::Unsupported(format!("version {v} files"))),
+        };
+        let n_tensors = r.u64()?;
+        let n_metadata = r.u64()?;
+
+        let mut metadata = Vec::new();
+        for _ in 0..n_metadata {
+            let key = r.string()?;
+            let t = r.u32()?;
+            let value = r.value(t)?;
+            metadata.push((key, value));
+        }
+
+        let mut tensors = Vec::new();
+        for _ in 0..n_tensors {
+            let name = r.string()?;
+            let n_dims = r.u32()?;
+            let mut shape = (0..n_dims)
+                .map(|_| r.usize())
+                .collect::<Result<Vec<_>, _>>()?;
+            shape.reverse();
+            let t = r.u32()?;
+            let dtype = GgmlType::from_u32(t)
+                .ok_or_else(|| GgufError::Unsupported(format!("tensor type {t} of {name}")))?;
+            let offset = r.usize()?;
+            tensors.push(GgufTensorInfo {
+                name,
+                dtype,
+                shape,
+                offset,
+            });
+        }
+
+        let invalid_alignment = || GgufError::Invalid("general.alignment".to_string());
+        let alignment = match metadata.iter().find(|(k, _)| k == "general.alignment") {
+            Some((_, v)) => v
+                .to_u64()
+                .and_then(|a| usize::try_from(a).ok())
+                .filter(|&a| a > 0)
+                .ok_or_else(invalid_alignment)?,
+            None => DEFAULT_ALIGNMENT,
+        };
+        let data_offset = r
+            .pos
+            .checked_add(alignment - 1)
+            .map(|end| end / alignment * alignment)
+            .ok_or_else(invalid_alignment)?;
+
+        for t in tensors.iter() {
+            let numel = t.shape.iter().try_fold(1usize, |n, &d| n.checked_mul(d));
+            let numel =
+                numel.ok_or_else(|| GgufError::Invalid(format!("the shape of {}", t.name)))?;
+            let size = t.dtype.bytes(numel).ok_or_else(|| {
+                GgufError::Invalid(format!("{} is not a whole number of blocks", t.name))
+            })?;
+            let end = data_offset
+                .checked_add(t.offset)
+                .and_then(|start| start.checked_add(size));
+            if end.map_or(true, |end| end > buffer.len()) {
+                return Err(GgufError::Invalid(format!("{} is out of bounds", t.name)));
+            }
+        }
+
+        Ok(Self {
+            buffer,
+            version,
+            metadata,
+            tensors,
+            data_offset,
+        })
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// All of the metadata, in the order of the file.
+    pub fn metadata_kvs(&self) -> &[(String, GgufValue)] {
+        &self.metadata
+    }
+
+    /// The metadata value of `key`, like `general.architecture`.
+    pub fn metadata(&self, key: &str) -> Option<&GgufValue> {
+        self.metadata.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// The infos of all tensors, in the order of the file.
+    pub fn tensor_infos(&self) -> &[GgufTensorInfo] {
+        &self.tensors
+    }
+
+    pub fn tensor_info(&self, name: &str) -> Option<&GgufTensorInfo> {
+        self.tensors.iter().find(|t| t.name == name)
+    }
+
+    /// Copies a tensor that isn't quantized to `device`, converting its elements to `E`.
+    /// Use [Gguf::quantized()] for quantized tensors.
+    pub fn tensor<E: Dtype, D: TensorFromVec<E>>(
+        &self,
+        name: &str,
+        device: &D,
+    ) -> Result<Tensor<DynShape, E, D>, GgufError> {
+        let t = self.quantized(name)?;
+        if t.dtype().is_quantized() {
+            return Err(GgufError::Unsupported(format!(
+                "loading {name} of type {:?} without dequantizing",
+                t.dtype()
+            )));
+        }
+        let size = t.dtype().block_bytes();
+        let buf = t
+            .as_bytes()
+            .chunks_exact(size)
+            .map(|b| E::from_f64(t.dtype().read(b)).unwrap_or_default())
+            .collect();
+        Ok(device.tensor_from_vec(buf, t.dyn_shape()?))
+    }
+
+    /// The raw blocks of a tensor, of any type.
+    pub fn quantized(&self, name: &str) -> Result<QuantizedTensor<'_>, GgufError> {
+        let info = self
+            .tensor_info(name)
+            .ok_or_else(|| GgufError::MissingTensor(name.to_string()))?;
+        let start = self.data_offset + info.offset;
+        let size = info.dtype.bytes(info.numel()).unwrap();
+        Ok(QuantizedTensor {
+            info,
+            data: &self.buffer[start..start + size],
+        })
+    }
+}
+
+/// The blocks of a tensor in a [Gguf], which are dequantized to `f32` on demand.
+#[derive(Copy, Clone, Debug)]
+pub struct QuantizedTensor<'a> {
+    info: &'a GgufTensorInfo,
+    data: &'a [u8],
+}
+
+impl<'a> QuantizedTensor<'a> {
+    pub fn dtype(&self) -> GgmlType {
+        self.info.dtype
+    }
+
+    /// Row major, like [GgufTensorInfo::shape].
+    pub fn shape(&self) -> &'a [usize] {
+        &self.info.shape
+    }
+
+    /// The blocks as they are stored in the file.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Dequantizes all blocks, in row major order.
+    pub fn to_f32_vec(&self) -> Result<Vec<f32>, GgufError> {
+        self.info.dtype.dequantize(self.data)
+    }
+
+    /// Dequantizes all blocks to a `f32` tensor on `device`.
+    pub fn dequantize<D: TensorFromVec<f32>>(
+        &self,
+        device: &D,
+    ) -> Result<Tensor<DynShape, f32, D>, GgufError> {
+        Ok(device.tensor_from_vec(self.to_f32_vec()?, self.dyn_shape()?))
+    }
+
+    fn dyn_shape(&self) -> Result<DynShape, GgufError> {
+        DynShape::try_new(self.shape())
+            .ok_or_else(|| GgufError::Unsupported(format!("the rank of {}", self.info.name)))
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], GgufError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or_else(|| GgufError::Invalid("unexpected end of file".to_string()))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], GgufError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u32(&mut self) -> Result<u32, GgufError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, GgufError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn usize(&mut self) -> Result<usize, GgufError> {
+        usize::try_from(self.u64()?).map_err(|_| GgufError::Invalid("size overflow".to_string()))
+    }
+
+    fn string(&mut self) -> Result<String, GgufError> {
+        let len = self.usize()?;
+        Ok(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+
+    fn value(&mut self, t: u32) -> Result<GgufValue, GgufError> {
+        Ok(match t {
+            0 => GgufValue::U8(self.array::<1>()?[0]),
+            1 => GgufValue::I8(self.array::<1>()?[0] as i8),
+            2 => GgufValue::U16(u16::from_le_bytes(self.array()?)),
+            3 => GgufValue::I16(i16::from_le_bytes(self.array()?)),
+            4 => GgufValue::U32(self.u32()?),
+            5 => GgufValue::I32(i32::from_le_bytes(self.array()?)),
+            6 => GgufValue::F32(f32::from_le_bytes(self.array()?)),
+            7 => GgufValue::Bool(self.array::<1>()?[0] != 0),
+            8 => GgufValue::String(self.string()?),
+            9 => {
+                let t = self.u32()?;
+                let len = self.usize()?;
+                // every element is at least a byte, which bounds the allocation
+                if len > self.bytes.len() - self.pos {
+                    return Err(GgufError::Invalid("array length".to_string()));
+                }
+                let items = (0..len).map(|_| self.value(t)).collect::<Result<_, _>>()?;
+                GgufValue::Array(items)
+            }
+            10 => GgufValue::U64(self.u64()?),
+            11 => GgufValue::I64(i64::from_le_bytes(self.array()?)),
+            12 => GgufValue::F64(f64::from_le_bytes(self.array()?)),
+            _ => return Err(GgufError::Invalid(format!("metadata value type {t}"))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tensor::Cpu;
+    use std::vec;
+
+    /// Writes a GGUF file with the given (already encoded) metadata values.
+    fn gguf(
+        kvs: &[(&str, u32, Vec<u8>)],
+        tensors: &[(&str, GgmlType, &[u64], Vec<u8>)],
+    ) -> Vec<u8> {
+        fn string(out: &mut Vec<u8>, s: &str) {
+            out.extend((s.len() as u64).to_le_bytes());
+            out.extend(s.bytes());
+        }
+        let mut out = MAGIC.to_vec();
+        out.extend(3u32.to_le_bytes());
+        out.extend((tensors.len() as u64).to_le_bytes());
+        out.extend((kvs.len() as u64).to_le_bytes());
+        let mut alignment = DEFAULT_ALIGNMENT;
+        for (key, t, value) in kvs {
+            string(&mut out, key);
+            out.extend(t.to_le_bytes());
+            out.extend(value);
+            if *key == "general.alignment" && *t == 4 {
+                alignment = u32::from_le_bytes(value[..].try_into().unwrap()) as usize;
+            }
+        }
+        let mut data = Vec::new();
+        for (name, dtype, dims, bytes) in tensors {
+            string(&mut out, name);
+            out.extend((dims.len() as u32).to_le_bytes());
+            dims.iter().for_each(|d| out.extend(d.to_le_bytes()));
+            out.extend((*dtype as u32).to_le_bytes());
+            out.extend((data.len() as u64).to_le_bytes());
+            data.extend(bytes);
+            data.resize((data.len() + alignment - 1) / alignment * alignment, 0);
+        }
+        out.resize((out.len() + alignment - 1) / alignment * alignment, 0);
+        out.extend(data);
+        out
+    }
+
+    fn f16s(xs: &[f32]) -> Vec<u8> {
+        xs.iter()
+            .flat_map(|&x| half::f16::from_f32(x).to_le_bytes())
+            .collect()
+    }
+
+    fn str_value(s: &str) -> Vec<u8> {
+        let mut v = (s.len() as u64).to_le_bytes().to_vec();
+        v.extend(s.bytes());
+        v
+    }
+
+    #[test]
+    fn test_gguf_header() {
+        let dev: Cpu = Default::default();
+        let mut array = 4u32.to_le_bytes().to_vec();
+        array.extend(2u64.to_le_bytes());
+        array.extend([7u32.to_le_bytes(), 9u32.to_le_bytes()].concat());
+        let bytes = gguf(
+            &[
+                ("general.architecture", 8, str_value("llama")),
+                ("general.alignment", 4, 64u32.to_le_bytes().to_vec()),
+                ("llama.rope.freq_base", 6, 0.5f32.to_le_bytes().to_vec()),
+                ("ids", 9, array),
+            ],
+            &[
+                (
+                    "a",
+                    GgmlType::F32,
+                    &[3, 2],
+                    [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]
+                        .iter()
+                        .flat_map(|x| x.to_le_bytes())
+                        .collect(),
+                ),
+                ("b", GgmlType::F16, &[2], f16s(&[0.5, -2.0])),
+            ],
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("model.gguf");
+        std::fs::write(&path, &bytes).unwrap();
+        let gguf = Gguf::load(&path).unwrap();
+        assert_eq!(gguf.version(), 3);
+        assert_eq!(gguf.metadata_kvs().len(), 4);
+        let arch = gguf.metadata("general.architecture");
+        assert_eq!(arch.and_then(GgufValue::as_str), Some("llama"));
+        let base = gguf.metadata("llama.rope.freq_base");
+        assert_eq!(base.and_then(GgufValue::to_f64), Some(0.5));
+        let ids = gguf.metadata("ids").and_then(GgufValue::as_array).unwrap();
+        assert_eq!(ids, [GgufValue::U32(7), GgufValue::U32(9)]);
+
+        let names: Vec<_> = gguf.tensor_infos().iter().map(|t| &t.name).collect();
+        assert_eq!(names, ["a", "b"]);
+        let a = gguf.tensor_info("a").unwrap();
+        assert_eq!(
+            (a.dtype, a.shape.as_slice()),
+            (GgmlType::F32, [2, 3].as_slice())
+        );
+
+        let a: Tensor<DynShape, f32, _> = gguf.tensor("a", &dev).unwrap();
+        assert_eq!(a.shape, DynShape::new(&[2, 3]));
+        assert_eq!(a.as_vec(), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        let b: Tensor<DynShape, f64, _> = gguf.tensor("b", &dev).unwrap();
+        assert_eq!(b.as_vec(), [0.5, -2.0]);
+        let b = gguf.quantized("b").unwrap();
+        assert_eq!(b.to_f32_vec().unwrap(), [0.5, -2.0]);
+        assert!(matches!(
+            gguf.tensor::<f32, _>("c", &dev),
+            Err(GgufError::MissingTensor(_))
+        ));
+    }
+
+    #[test]
+    fn test_gguf_errors() {
+        assert!(matches!(
+            Gguf::from_bytes(b"GGML\x03\0\0\0".to_vec()),
+            Err(GgufError::Invalid(_))
+        ));
+        let mut bytes = gguf(&[], &[]);
+        bytes[4..8].copy_from_slice(&1u32.to_le_bytes());
+        assert!(matches!(
+            Gguf::from_bytes(bytes),
+            Err(GgufError::Unsupported(_))
+        ));
+
+        let mut bytes = gguf(&[], &[("a", GgmlType::F32, &[4], vec![0; 16])]);
+        bytes.truncate(bytes.len() - 20);
+        assert!(matches!(
+            Gguf::from_bytes(bytes),
+            Err(GgufError::Invalid(_))
+        ));
+        let bytes = gguf(&[], &[("a", GgmlType::Q8_0, &[16], vec![0; 34])]);
+        assert!(matches!(
+            Gguf::from_bytes(bytes),
+            Err(GgufError::Invalid(_))
+        ));
+
+        for alignment in [0, u64::MAX] {
+            let kv = ("general.alignment", 10, alignment.to_le_bytes().to_vec());
+            assert!(matches!(
+                Gguf::from_bytes(gguf(&[kv], &[])),
+                Err(GgufError::Invalid(_))
+            ));
+        }
+    }
+
+    /// Dequantizes a single tensor of type `dtype` stored as `blocks`.
+    fn dequantize(dtype: GgmlType, blocks: Vec<u8>) -> Vec<f32> {
+        let n = (blocks.len() / dtype.block_bytes() * dtype.block_size()) as u64;
+        let gguf = Gguf::from_bytes(gguf(&[], &[("q", dtype, &[n], blocks)])).unwrap();
+        let dev: Cpu = Default::default();
+        let q = gguf.quantized("q").unwrap();
+        assert_eq!(q.dtype(), dtype);
+        assert!(gguf.tensor::<f32, _>("q", &dev).is_err());
+        q.dequantize(&dev).unwrap().as_vec()
+    }
+
+    #[test]
+    fn test_dequantize_32_blocks() {
+        let mut q8_0 = f16s(&[0.25]);
+        q8_0.extend((-16i8..16).map(|q| q as u8));
+        let expected: Vec<f32> = (-16..16).map(|q| q as f32 * 0.25).collect();
+        assert_eq!(dequantize(GgmlType::Q8_0, q8_0), expected);
+
+        let mut q4_0 = f16s(&[2.0]);
+        q4_0.extend((0..16).map(|j| j | (15 - j) << 4));
+        let mut expected: Vec<f32> = (0..16).map(|j| (j - 8) as f32 * 2.0).collect();
+        expected.extend((0..16).map(|j| (7 - j) as f32 * 2.0));
+        assert_eq!(dequantize(GgmlType::Q4_0, q4_0), expected);
+
+        let mut q4_1 = f16s(&[0.5, -1.0]);
+        q4_1.extend((0..16).map(|j| j | (15 - j) << 4));
+        let mut expected: Vec<f32> = (0..16).map(|j| j as f32 * 0.5 - 1.0).collect();
+        expected.extend((0..16).map(|j| (15 - j) as f32 * 0.5 - 1.0));
+        assert_eq!(dequantize(GgmlType::Q4_1, q4_1), expected);
+
+        // the fifth bit is set for elements 0 and 31
+        let mut q5_0 = f16s(&[2.0]);
+        q5_0.extend((1u32 | 1 << 31).to_le_bytes());
+        q5_0.extend([0x88; 16]);
+        let mut expected = vec![-16.0; 32];
+        expected[0] = 16.0;
+        expected[31] = 16.0;
+        assert_eq!(dequantize(GgmlType::Q5_0, q5_0), expected);
+
+        // the fifth bit is set for elements 1 and 16
+        let mut q5_1 = f16s(&[1.0, 0.5]);
+        q5_1.extend((2u32 | 1 << 16).to_le_bytes());
+        q5_1.extend([0x21; 16]);
+        let mut expected = [vec![1.5; 16], vec![2.5; 16]].concat();
+        expected[1] = 17.5;
+        expected[16] = 18.5;
+        assert_eq!(dequantize(GgmlType::Q5_1, q5_1), expected);
+    }
+
+    #[test]
+    fn test_dequantize_k_quants() {
+        // scales 1..=8 & mins 0, 0, 0, 0, 1, 2, 3, 4 for the 8 sub-blocks
+        let mut q4_k = f16s(&[1.0, 1.0]);
+        q4_k.extend([1, 2, 3, 4, 0, 0, 0, 0, 0x15, 0x26, 0x37, 0x48]);
+        q4_k.extend([0x21; 128]);
+        let expected: Vec<f32> = [1.0, 4.0, 3.0, 8.0, 4.0, 10.0, 4.0, 12.0]
+            .iter()
+            .flat_map(|&x| [x; 32])
+            .collect();
+        assert_eq!(dequantize(GgmlType::Q4_K, q4_k.clone()), expected);
+
+        // the same, with the fifth bit set for the first element of each sub-block
+        let mut q5_k = q4_k[..16].to_vec();
+        let mut qh = [0u8; 32];
+        qh[0] = 0xff;
+        q5_k.extend(qh);
+        q5_k.extend([0x21; 128]);
+        let mut expected = expected;
+        let scales = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        for (i, scale) in scales.iter().enumerate() {
+            expected[32 * i] += 16.0 * scale;
+        }
+        assert_eq!(dequantize(GgmlType::Q5_K, q5_k), expected);
+
+        let mut q6_k = vec![0x21; 128];
+        q6_k.extend([0b11_10_01_00; 64]);
+        q6_k.extend((1..=16).map(|s| s as u8));
+        q6_k.extend(f16s(&[0.5]));
+        let expected: Vec<f32> = (0..256)
+            .map(|e| {
+                let (half, k, l) = (e / 128, e % 128 / 32, e % 32);
+                let scale = 8 * half + l / 16 + 2 * k + 1;
+                0.5 * scale as f32 * [-31.0, -15.0, 2.0, 18.0][k]
+            })
+            .collect();
+        assert_eq!(dequantize(GgmlType::Q6_K, q6_k), expected);
+    }
+
+    #[test]
+    fn test_dequantize_unsupported() {
+        let bytes = gguf(&[], &[("q", GgmlType::Q2_K, &[256], vec![0; 84])]);
+        let gguf = Gguf::from_bytes(bytes).unwrap();
+        let q = gguf.quantized("q").unwrap();
+        assert!(matches!(q.to_f32_vec(), Err(GgufError::Unsupported(_))));
+    }
+}
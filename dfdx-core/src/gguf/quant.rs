@@ -0,0 +1,294 @@
+//! The tensor types of ggml, and dequantizing their blocks to `f32`.
+
+use std::{format, vec::Vec};
+
+use super::GgufError;
+
+/// The type of a tensor in a GGUF file, with the same discriminants as `ggml_type`.
+///
+/// The quantized types store elements in blocks of [GgmlType::block_size()], which share
+/// a scale (and for some types, a minimum).
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GgmlType {
+    F32 = 0,
+    F16 = 1,
+    Q4_0 = 2,
+    Q4_1 = 3,
+    Q5_0 = 6,
+    Q5_1 = 7,
+    Q8_0 = 8,
+    Q8_1 = 9,
+    Q2_K = 10,
+    Q3_K = 11,
+    Q4_K = 12,
+    Q5_K = 13,
+    Q6_K = 14,
+    Q8_K = 15,
+    I8 = 24,
+    I16 = 25,
+    I32 = 26,
+    I64 = 27,
+    F64 = 28,
+    BF16 = 30,
+}
+
+/// The number of elements in the blocks of the k-quants.
+const QK_K: usize = 256;
+
+impl GgmlType {
+    pub(super) fn from_u32(t: u32) -> Option<Self> {
+        Some(match t {
+            0 => Self::F32,
+            1 => Self::F16,
+            2 => Self::Q4_0,
+            3 => Self::Q4_1,
+            6 => Self::Q5_0,
+            7 => Self::Q5_1,
+            8 => Self::Q8_0,
+            9 => Self::Q8_1,
+            10 => Self::Q2_K,
+            11 => Self::Q3_K,
+            12 => Self::Q4_K,
+            13 => Self::Q5_K,
+            14 => Self::Q6_K,
+            15 => Self::Q8_K,
+            24 => Self::I8,
+            25 => Self::I16,
+            26 => Self::I32,
+            27 => Self::I64,
+            28 => Self::F64,
+            30 => Self::BF16,
+            _ => return None,
+        })
+    }
+
+    /// Whether elements are stored in blocks with a shared scale.
+    pub fn is_quantized(&self) -> bool {
+        self.block_size() > 1
+    }
+
+    /// The number of elements in a block, which is 1 for types that aren't quantized.
+    pub fn block_size(&self) -> usize {
+        match self {
+            Self::Q4_0 | Self::Q4_1 | Self::Q5_0 | Self::Q5_1 | Self::Q8_0 | Self::Q8_1 => 32,
+            Self::Q2_K | Self::Q3_K | Self::Q4_K | Self::Q5_K | Self::Q6_K | Self::Q8_K => QK_K,
+            _ => 1,
+        }
+    }
+
+    /// The number of bytes of a block.
+    pub fn block_bytes(&self) -> usize {
+        match self {
+            Self::F32 | Self::I32 => 4,
+            Self::F16 | Self::BF16 | Self::I16 => 2,
+            Self::I8 => 1,
+            Self::I64 | Self::F64 => 8,
+            Self::Q4_0 => 2 + 16,
+            Self::Q4_1 => 2 * 2 + 16,
+            Self::Q5_0 => 2 + 4 + 16,
+            Self::Q5_1 => 2 * 2 + 4 + 16,
+            Self::Q8_0 => 2 + 32,
+            Self::Q8_1 => 2 * 2 + 32,
+            Self::Q2_K => QK_K / 16 + QK_K / 4 + 2 * 2,
+            Self::Q3_K => QK_K / 8 + QK_K / 4 + 12 + 2,
+            Self::Q4_K => 2 * 2 + 12 + QK_K / 2,
+            Self::Q5_K => 2 * 2 + 12 + QK_K / 8 + QK_K / 2,
+            Self::Q6_K => QK_K / 2 + QK_K / 4 + QK_K / 16 + 2,
+            Self::Q8_K => 4 + QK_K + QK_K / 16 * 2,
+        }
+    }
+
+    /// The number of bytes of `numel` elements, if `numel` is a whole number of blocks.
+    pub(super) fn bytes(&self, numel: usize) -> Option<usize> {
+        (numel % self.block_size() == 0).then(|| numel / self.block_size() * self.block_bytes())
+    }
+
+    /// Reads the elements that aren't quantized.
+    pub(super) fn read(&self, b: &[u8]) -> f64 {
+        match self {
+            Self::F32 => f32::from_le_bytes(b.try_into().unwrap()) as f64,
+            Self::F16 => f16(b).to_f64(),
+            Self::BF16 => half::bf16::from_le_bytes([b[0], b[1]]).to_f64(),
+            Self::F64 => f64::from_le_bytes(b.try_into().unwrap()),
+            Self::I8 => b[0] as i8 as f64,
+            Self::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            Self::I32 => i32::from_le_bytes(b.try_into().unwrap()) as f64,
+            Self::I64 => i64::from_le_bytes(b.try_into().unwrap()) as f64,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Dequantizes whole blocks in `data` to `f32`s.
+    pub(super) fn dequantize(&self, data: &[u8]) -> Result<Vec<f32>, GgufError> {
+        let dequantize_block: fn(&[u8], &mut [f32]) = match self {
+            Self::Q4_0 => q4_0,
+            Self::Q4_1 => q4_1,
+            Self::Q5_0 => q5_0,
+            Self::Q5_1 => q5_1,
+            Self::Q8_0 => q8_0,
+            Self::Q4_K => q4_k,
+            Self::Q5_K => q5_k,
+            Self::Q6_K => q6_k,
+            t if !t.is_quantized() => {
+                let size = self.block_bytes();
+                return Ok(data.chunks_exact(size).map(|b| t.read(b) as f32).collect());
+            }
+            t => return Err(GgufError::Unsupported(format!("dequantizing {t:?}"))),
+        };
+        let mut out = std::vec![0.0; data.len() / self.block_bytes() * self.block_size()];
+        for (block, out) in data
+            .chunks_exact(self.block_bytes())
+            .zip(out.chunks_exact_mut(self.block_size()))
+        {
+            dequantize_block(block, out);
+        }
+        Ok(out)
+    }
+}
+
+fn f16(b: &[u8]) -> half::f16 {
+    half::f16::from_le_bytes([b[0], b[1]])
+}
+
+/// `d: f16, qs: [u8; 16]`, with `x = d * (q - 8)`. The low nibbles are the first half of
+/// the block, and the high nibbles the second half.
+fn q4_0(b: &[u8], out: &mut [f32]) {
+    let d = f16(b).to_f32();
+    let qs = &b[2..];
+    for j in 0..16 {
+        out[j] = ((qs[j] & 0xf) as i32 - 8) as f32 * d;
+        out[j + 16] = ((qs[j] >> 4) as i32 - 8) as f32 * d;
+    }
+}
+
+/// `d: f16, m: f16, qs: [u8; 16]`, with `x = d * q + m`.
+fn q4_1(b: &[u8], out: &mut [f32]) {
+    let d = f16(b).to_f32();
+    let m = f16(&b[2..]).to_f32();
+    let qs = &b[4..];
+    for j in 0..16 {
+        out[j] = (qs[j] & 0xf) as f32 * d + m;
+        out[j + 16] = (qs[j] >> 4) as f32 * d + m;
+    }
+}
+
+/// Like [q4_0], with the fifth bit of each element in `qh: u32`, and `x = d * (q - 16)`.
+fn q5_0(b: &[u8], out: &mut [f32]) {
+    let d = f16(b).to_f32();
+    let qh = u32::from_le_bytes(b[2..6].try_into().unwrap());
+    let qs = &b[6..];
+    for j in 0..16 {
+        let h0 = ((qh >> j) << 4) & 0x10;
+        let h1 = (qh >> (j + 12)) & 0x10;
+        out[j] = (((qs[j] & 0xf) as u32 | h0) as i32 - 16) as f32 * d;
+        out[j + 16] = (((qs[j] >> 4) as u32 | h1) as i32 - 16) as f32 * d;
+    }
+}
+
+/// Like [q4_1], with the fifth bit of each element in `qh: u32`.
+fn q5_1(b: &[u8], out: &mut [f32]) {
+    let d = f16(b).to_f32();
+    let m = f16(&b[2..]).to_f32();
+    let qh = u32::from_le_bytes(b[4..8].try_into().unwrap());
+    let qs = &b[8..];
+    for j in 0..16 {
+        let h0 = ((qh >> j) << 4) & 0x10;
+        let h1 = (qh >> (j + 12)) & 0x10;
+        out[j] = ((qs[j] & 0xf) as u32 | h0) as f32 * d + m;
+        out[j + 16] = ((qs[j] >> 4) as u32 | h1) as f32 * d + m;
+    }
+}
+
+/// `d: f16, qs: [i8; 32]`, with `x = d * q`.
+fn q8_0(b: &[u8], out: &mut [f32]) {
+    let d = f16(b).to_f32();
+    for (o, &q) in out.iter_mut().zip(&b[2..]) {
+        *o = q as i8 as f32 * d;
+    }
+}
+
+/// The 6 bit scale and min of sub-block `j` of a [q4_k] block.
+fn scale_min_k4(j: usize, q: &[u8]) -> (f32, f32) {
+    let (d, m) = if j < 4 {
+        (q[j] & 63, q[j + 4] & 63)
+    } else {
+        (
+            (q[j + 4] & 0xf) | ((q[j - 4] >> 6) << 4),
+            (q[j + 4] >> 4) | ((q[j] >> 6) << 4),
+        )
+    };
+    (d as f32, m as f32)
+}
+
+/// `d: f16, dmin: f16, scales: [u8; 12], qs: [u8; 128]`, with 8 sub-blocks of 32 elements
+/// that each have a 6 bit scale and min, and `x = d * scale * q - dmin * min`.
+fn q4_k(b: &[u8], out: &mut [f32]) {
+    let d = f16(b).to_f32();
+    let dmin = f16(&b[2..]).to_f32();
+    let scales = &b[4..16];
+    let qs = &b[16..];
+    for (i, (q, out)) in qs
+        .chunks_exact(32)
+        .zip(out.chunks_exact_mut(64))
+        .enumerate()
+    {
+        let (sc1, m1) = scale_min_k4(2 * i, scales);
+        let (sc2, m2) = scale_min_k4(2 * i + 1, scales);
+        let (d1, m1) = (d * sc1, dmin * m1);
+        let (d2, m2) = (d * sc2, dmin * m2);
+        for l in 0..32 {
+            out[l] = d1 * (q[l] & 0xf) as f32 - m1;
+            out[l + 32] = d2 * (q[l] >> 4) as f32 - m2;
+        }
+    }
+}
+
+/// Like [q4_k], with the fifth bit of each element in `qh: [u8; 32]` after the scales.
+fn q5_k(b: &[u8], out: &mut [f32]) {
+    let d = f16(b).to_f32();
+    let dmin = f16(&b[2..]).to_f32();
+    let scales = &b[4..16];
+    let qh = &b[16..48];
+    let qs = &b[48..];
+    for (i, (q, out)) in qs
+        .chunks_exact(32)
+        .zip(out.chunks_exact_mut(64))
+        .enumerate()
+    {
+        let (sc1, m1) = scale_min_k4(2 * i, scales);
+        let (sc2, m2) = scale_min_k4(2 * i + 1, scales);
+        let (d1, m1) = (d * sc1, dmin * m1);
+        let (d2, m2) = (d * sc2, dmin * m2);
+        for l in 0..32 {
+            let h1 = (qh[l] >> (2 * i)) & 1;
+            let h2 = (qh[l] >> (2 * i + 1)) & 1;
+            out[l] = d1 * ((q[l] & 0xf) | (h1 << 4)) as f32 - m1;
+            out[l + 32] = d2 * ((q[l] >> 4) | (h2 << 4)) as f32 - m2;
+        }
+    }
+}
+
+/// `ql: [u8; 128], qh: [u8; 64], scales: [i8; 16], d: f16`, with 16 sub-blocks of 16
+/// elements that each have an 8 bit scale, and `x = d * scale * (q - 32)`.
+fn q6_k(b: &[u8], out: &mut [f32]) {
+    let d = f16(&b[208..]).to_f32();
+    for half in 0..2 {
+        let ql = &b[64 * half..];
+        let qh = &b[128 + 32 * half..];
+        let sc = &b[192 + 8 * half..];
+        let out = &mut out[128 * half..];
+        for l in 0..32 {
+            let is = l / 16;
+            let q1 = ((ql[l] & 0xf) | ((qh[l] & 3) << 4)) as i32 - 32;
+            let q2 = ((ql[l + 32] & 0xf) | (((qh[l] >> 2) & 3) << 4)) as i32 - 32;
+            let q3 = ((ql[l] >> 4) | (((qh[l] >> 4) & 3) << 4)) as i32 - 32;
+            let q4 = ((ql[l + 32] >> 4) | (((qh[l] >> 6) & 3) << 4)) as i32 - 32;
+            out[l] = d * (sc[is] as i8) as f32 * q1 as f32;
+            out[l + 32] = d * (sc[is + 2] as i8) as f32 * q2 as f32;
+            out[l + 64] = d * (sc[is + 4] as i8) as f32 * q3 as f32;
+            out[l + 96] = d * (sc[is + 6] as i8) as f32 * q4 as f32;
+        }
+    }
+}
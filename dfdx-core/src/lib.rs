@@ -114,8 +114,14 @@ extern crate self as dfdx_core;
 
 pub mod data;
 pub mod dtypes;
+#[cfg(feature = "gguf")]
+pub mod gguf;
 pub mod losses;
 pub mod nn_traits;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+#[cfg(feature = "pytorch")]
+pub mod pytorch;
 pub mod shapes;
 pub mod tensor;
 pub mod tensor_ops;
@@ -3,7 +3,9 @@ mod vecs;
 
 use std::vec::Vec;
 
-use crate::prelude::{Device, Dtype, Error, Gradients, Shape, Tensor, UniqueId};
+use crate::prelude::{
+    Device, Dtype, Error, Gradients, Shape, SumTo, Tensor, TryAdd, TryMul, UniqueId,
+};
 
 /// Mutable & Immutable forward of `Input` that produces [Module::Output].
 pub trait Module<X> {
@@ -113,6 +115,232 @@ pub trait ZeroGrads<E: Dtype, D: Device<E>> {
     }
 }
 
+/// Something that can visit the gradients of a [WithGrads]. At minimum [GradsVisitor::visit_grad()] must be implemented.
+pub trait GradsVisitor<E: Dtype, D: Device<E>> {
+    fn visit_grad<S: Shape>(
+        &mut self,
+        t: &Tensor<S, E, D>,
+        gradients: &mut Gradients<E, D>,
+    ) -> Result<(), Error>;
+}
+
+/// Something that can have the gradients of all of its parameters visited by a [GradsVisitor],
+/// for example to clip them with [WithGrads::clip_grad_norm()] or [WithGrads::clip_grad_value()].
+///
+/// Parameters that don't have a gradient in [Gradients] are skipped.
+pub trait WithGrads<E: Dtype, D: Device<E>> {
+    fn try_visit_grads<V: GradsVisitor<E, D>>(
+        &self,
+        visitor: &mut V,
+        gradients: &mut Gradients<E, D>,
+    ) -> Result<(), crate::tensor::Error>;
+
+    /// Computes the L2 norm of all parameter gradients, as if they were concatenated into a single vector.
+    fn grad_norm(&self, gradients: &mut Gradients<E, D>) -> E {
+        self.try_grad_norm(gradients).unwrap()
+    }
+    fn try_grad_norm(&self, gradients: &mut Gradients<E, D>) -> Result<E, crate::tensor::Error> {
+        let mut visitor = GradNormSquared(None);
+        self.try_visit_grads(&mut visitor, gradients)?;
+        match visitor.0 {
+            Some(norm_squared) => Ok(norm_squared.try_sqrt()?.as_vec()[0]),
+            None => Ok(E::default()),
+        }
+    }
+
+    /// Rescales all parameter gradients in place so that their global L2 norm
+    /// (see [WithGrads::grad_norm()]) is at most `max_norm`. Returns the norm before clipping.
+    ///
+    /// **Pytorch equivalent**: `torch.nn.utils.clip_grad_norm_(model.parameters(), max_norm)`
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # use dfdx_core::nn_traits::*;
+    /// # let dev: Cpu = Default::default();
+    /// let w: Tensor<Rank1<2>, f32, _> = dev.zeros();
+    /// let mut grads = (w.leaky_trace() * dev.tensor([3.0, 4.0])).sum().backward();
+    /// let norm = w.clip_grad_norm(&mut grads, 1.0);
+    /// assert_eq!(norm, 5.0);
+    /// let [g0, g1] = grads.get(&w).array();
+    /// assert!((g0 - 0.6).abs() < 1e-6 && (g1 - 0.8).abs() < 1e-6);
+    /// ```
+    fn clip_grad_norm(&self, gradients: &mut Gradients<E, D>, max_norm: impl Into<f64>) -> E {
+        self.try_clip_grad_norm(gradients, max_norm).unwrap()
+    }
+    fn try_clip_grad_norm(
+        &self,
+        gradients: &mut Gradients<E, D>,
+        max_norm: impl Into<f64>,
+    ) -> Result<E, crate::tensor::Error> {
+        let norm = self.try_grad_norm(gradients)?;
+        let scale = max_norm.into() / (norm.to_f64().unwrap() + 1e-6);
+        if scale < 1.0 {
+            self.try_visit_grads(&mut GradScale(scale), gradients)?;
+        }
+        Ok(norm)
+    }
+
+    /// Clamps all parameter gradients in place to the range `[-clip_value, clip_value]`.
+    ///
+    /// **Pytorch equivalent**: `torch.nn.utils.clip_grad_value_(model.parameters(), clip_value)`
+    fn clip_grad_value(&self, gradients: &mut Gradients<E, D>, clip_value: impl Into<f64>) {
+        self.try_clip_grad_value(gradients, clip_value).unwrap()
+    }
+    fn try_clip_grad_value(
+        &self,
+        gradients: &mut Gradients<E, D>,
+        clip_value: impl Into<f64>,
+    ) -> Result<(), crate::tensor::Error> {
+        self.try_visit_grads(&mut GradClamp(clip_value.into()), gradients)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> WithGrads<E, D> for Tensor<S, E, D> {
+    fn try_visit_grads<V: GradsVisitor<E, D>>(
+        &self,
+        visitor: &mut V,
+        gradients: &mut Gradients<E, D>,
+    ) -> Result<(), crate::tensor::Error> {
+        visitor.visit_grad(self, gradients)
+    }
+}
+
+/// Accumulates the sum of squares of all gradients on the device.
+struct GradNormSquared<E: Dtype, D: Device<E>>(Option<Tensor<(), E, D>>);
+
+impl<E: Dtype, D: Device<E>> GradsVisitor<E, D> for GradNormSquared<E, D> {
+    fn visit_grad<S: Shape>(
+        &mut self,
+        t: &Tensor<S, E, D>,
+        gradients: &mut Gradients<E, D>,
+    ) -> Result<(), Error> {
+        if gradients.get_ref_checked(t).is_none() {
+            return Ok(());
+        }
+        let norm_squared = gradients.get(t).try_square()?.try_sum()?;
+        self.0 = Some(match self.0.take() {
+            Some(total) => total.try_add(norm_squared)?,
+            None => norm_squared,
+        });
+        Ok(())
+    }
+}
+
+/// Multiplies all gradients by a scalar.
+struct GradScale(f64);
+
+impl<E: Dtype, D: Device<E>> GradsVisitor<E, D> for GradScale {
+    fn visit_grad<S: Shape>(
+        &mut self,
+        t: &Tensor<S, E, D>,
+        gradients: &mut Gradients<E, D>,
+    ) -> Result<(), Error> {
+        if gradients.get_ref_checked(t).is_none() {
+            return Ok(());
+        }
+        let grad = gradients.get(t).try_mul(self.0)?;
+        gradients.set(t, grad);
+        Ok(())
+    }
+}
+
+/// Clamps all gradients to `[-self.0, self.0]`.
+struct GradClamp(f64);
+
+impl<E: Dtype, D: Device<E>> GradsVisitor<E, D> for GradClamp {
+    fn visit_grad<S: Shape>(
+        &mut self,
+        t: &Tensor<S, E, D>,
+        gradients: &mut Gradients<E, D>,
+    ) -> Result<(), Error> {
+        if gradients.get_ref_checked(t).is_none() {
+            return Ok(());
+        }
+        let grad = gradients.get(t).try_clamp(-self.0, self.0)?;
+        gradients.set(t, grad);
+        Ok(())
+    }
+}
+
+/// Something that can visit the parameters of a [VisitParams]. At minimum [ParamsVisitor::visit_param()] must be implemented.
+pub trait ParamsVisitor<E: Dtype, D: Device<E>> {
+    /// `location` is the same key the parameter is saved under by `SaveSafeTensors`.
+    fn visit_param<S: Shape>(&mut self, location: &str, t: &Tensor<S, E, D>) -> Result<(), Error>;
+
+    /// Called before the params of a module (but not a tuple or [Vec] of modules) are visited.
+    /// `type_name` is the [core::any::type_name] of the module.
+    fn enter_module(&mut self, _location: &str, _type_name: &'static str) {}
+
+    /// Called after all the params of the most recently entered module were visited.
+    fn exit_module(&mut self) {}
+}
+
+/// Something that can visit all of its parameters along with their location in the module,
+/// e.g. `0.weight` for the weight of the first layer of a tuple.
+pub trait VisitParams<E: Dtype, D: Device<E>> {
+    fn visit_params<V: ParamsVisitor<E, D>>(&self, visitor: &mut V) {
+        self.try_visit_params("", visitor).unwrap()
+    }
+    fn try_visit_params<V: ParamsVisitor<E, D>>(
+        &self,
+        location: &str,
+        visitor: &mut V,
+    ) -> Result<(), Error>;
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> VisitParams<E, D> for Tensor<S, E, D> {
+    fn try_visit_params<V: ParamsVisitor<E, D>>(
+        &self,
+        location: &str,
+        visitor: &mut V,
+    ) -> Result<(), Error> {
+        visitor.visit_param(location, self)
+    }
+}
+
+#[cfg(feature = "onnx")]
+/// Something that can be exported to a .onnx file, by tracing its forward pass on an
+/// example input (see [crate::onnx]). Implemented for everything with [VisitParams].
+///
+/// Example:
+/// ```ignore
+/// let model = dev.build_module::<f32>(arch);
+/// let x: Tensor<Rank2<1, 784>, f32, _> = dev.zeros();
+/// model.save_onnx(x, "model.onnx")?;
+/// ```
+pub trait SaveOnnx<E: Dtype, D: Device<E>>: VisitParams<E, D> {
+    fn save_onnx<X: crate::onnx::OnnxInput, O: Shape, P: AsRef<std::path::Path>>(
+        &self,
+        x: X,
+        path: P,
+    ) -> Result<(), crate::onnx::OnnxError>
+    where
+        Self: Module<X::Traced, Output = Tensor<O, E, D, crate::tensor::CaptureTape>>,
+    {
+        use crate::onnx::prost::Message;
+        let model = self.to_onnx(x)?;
+        std::fs::write(path, model.encode_to_vec())?;
+        Ok(())
+    }
+
+    /// Traces the forward pass of `x`, and turns it into an ONNX model.
+    fn to_onnx<X: crate::onnx::OnnxInput, O: Shape>(
+        &self,
+        x: X,
+    ) -> Result<crate::onnx::proto::ModelProto, crate::onnx::OnnxError>
+    where
+        Self: Module<X::Traced, Output = Tensor<O, E, D, crate::tensor::CaptureTape>>,
+    {
+        let (x, inputs) = x.trace();
+        let (_, graph) = self.try_forward(x)?.split_graph();
+        crate::onnx::try_export(self, &graph, &inputs)
+    }
+}
+
+#[cfg(feature = "onnx")]
+impl<E: Dtype, D: Device<E>, M: VisitParams<E, D>> SaveOnnx<E, D> for M {}
+
 #[cfg(feature = "safetensors")]
 /// Something that can be saved to a .safetensors file.
 pub trait SaveSafeTensors {
@@ -179,7 +407,7 @@ impl<S: Shape, E: Dtype, D: Device<E>, T> SaveSafeTensors for Tensor<S, E, D, T>
         tensors.push((
             location.to_string(),
             <E as crate::dtypes::SafeTensorsDtype>::DTYPE,
-            self.shape.concrete().into(),
+            self.shape.dims(),
             self.as_vec().iter().flat_map(|e| e.to_le_bytes()).collect(),
         ));
     }
@@ -236,6 +464,163 @@ unit_safetensors!(i64);
 unit_safetensors!(isize);
 unit_safetensors!(usize);
 
+#[cfg(feature = "pytorch")]
+/// Something that can be loaded from a PyTorch `state_dict` checkpoint (see [crate::pytorch]).
+/// Implemented for everything with [VisitParams], [SaveSafeTensors] and [LoadSafeTensors].
+///
+/// Keys are matched against the same locations as `SaveSafeTensors` uses, so a tuple of layers
+/// lines up with a `nn.Sequential`. The params that dfdx names differently than torch are also
+/// found under their torch name, e.g. `weight` & `bias` for the `gamma` & `beta` of a
+/// `LayerNorm1D`. Every param must be in the checkpoint, but extra keys are ignored.
+///
+/// Example:
+/// ```ignore
+/// let mut model = dev.build_module::<f32>(arch);
+/// model.load_pytorch("model.pt")?;
+/// // or, for a checkpoint of a `nn.Module` with different names:
+/// model.load_pytorch_with("model.pt", |k| Some(k.replace("layers.", "")))?;
+/// ```
+pub trait LoadPyTorch<E: Dtype, D: Device<E>>:
+    VisitParams<E, D> + SaveSafeTensors + LoadSafeTensors
+{
+    fn load_pytorch<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> Result<(), crate::pytorch::PyTorchError> {
+        self.load_state_dict(&crate::pytorch::StateDict::load(path)?)
+    }
+
+    /// Like [LoadPyTorch::load_pytorch()], but renames every key of the checkpoint with
+    /// `rename` first. Keys that are renamed to [None] are skipped.
+    fn load_pytorch_with<P: AsRef<std::path::Path>, F: FnMut(&str) -> Option<String>>(
+        &mut self,
+        path: P,
+        rename: F,
+    ) -> Result<(), crate::pytorch::PyTorchError> {
+        let mut state_dict = crate::pytorch::StateDict::load(path)?;
+        state_dict.rename(rename);
+        self.load_state_dict(&state_dict)
+    }
+
+    fn load_state_dict(
+        &mut self,
+        state_dict: &crate::pytorch::StateDict,
+    ) -> Result<(), crate::pytorch::PyTorchError> {
+        use crate::pytorch::PyTorchError;
+
+        let mut params = TorchNames {
+            modules: Vec::new(),
+            params: Vec::new(),
+        };
+        self.try_visit_params("", &mut params).unwrap();
+
+        let mut tensors = Vec::new();
+        self.write_safetensors("", &mut tensors);
+
+        let mut missing = Vec::new();
+        for (location, dtype, shape, data) in tensors.iter_mut() {
+            let param = params.params.iter().find(|(l, _)| l == location);
+            // scalars like `epsilon` aren't in checkpoints
+            if shape.is_empty() && param.is_none() {
+                continue;
+            }
+            let alias = param.and_then(|(_, alias)| alias.as_deref());
+            let key = match [Some(location.as_str()), alias]
+                .into_iter()
+                .flatten()
+                .find(|k| state_dict.contains_key(k))
+            {
+                Some(key) => key,
+                None => {
+                    if param.is_some() {
+                        missing.push(location.clone());
+                    }
+                    continue;
+                }
+            };
+            let found = state_dict.shape(key).unwrap();
+            if found != shape.as_slice() {
+                return Err(PyTorchError::ShapeMismatch {
+                    key: key.to_string(),
+                    expected: shape.clone(),
+                    found: found.to_vec(),
+                });
+            }
+            if !state_dict.write_as(key, *dtype, data)? && param.is_some() {
+                return Err(PyTorchError::Unsupported(std::format!(
+                    "loading {location} as {dtype:?}"
+                )));
+            }
+        }
+        if !missing.is_empty() {
+            return Err(PyTorchError::MissingKeys(missing));
+        }
+
+        let views = tensors.iter().map(|(k, dtype, shape, data)| {
+            (
+                k.clone(),
+                safetensors::tensor::TensorView::new(*dtype, shape.clone(), data).unwrap(),
+            )
+        });
+        let buffer = safetensors::serialize(views, &None)?;
+        let tensors = safetensors::SafeTensors::deserialize(&buffer)?;
+        self.read_safetensors("", &tensors)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "pytorch")]
+impl<E: Dtype, D: Device<E>, M: VisitParams<E, D> + SaveSafeTensors + LoadSafeTensors>
+    LoadPyTorch<E, D> for M
+{
+}
+
+/// The params that torch names differently, by the name of the module they are in.
+#[cfg(feature = "pytorch")]
+const TORCH_NAMES: &[(&str, &str, &str)] = &[
+    ("LayerNorm1D", "gamma", "weight"),
+    ("LayerNorm1D", "beta", "bias"),
+    ("BatchNorm1D", "scale", "weight"),
+    ("BatchNorm2D", "scale", "weight"),
+    ("GroupNorm", "gamma", "weight"),
+    ("GroupNorm", "beta", "bias"),
+    ("InstanceNorm2D", "gamma", "weight"),
+    ("InstanceNorm2D", "beta", "bias"),
+    ("RMSNorm", "gamma", "weight"),
+];
+
+/// Collects the location of every param, along with its torch name from [TORCH_NAMES].
+#[cfg(feature = "pytorch")]
+struct TorchNames {
+    modules: Vec<(String, &'static str)>,
+    params: Vec<(String, Option<String>)>,
+}
+
+#[cfg(feature = "pytorch")]
+impl<E: Dtype, D: Device<E>> ParamsVisitor<E, D> for TorchNames {
+    fn visit_param<S: Shape>(&mut self, location: &str, _: &Tensor<S, E, D>) -> Result<(), Error> {
+        let alias = self.modules.last().and_then(|(module, type_name)| {
+            let name = location.strip_prefix(module.as_str())?;
+            // `path::to::LayerNorm1D<...>` to `LayerNorm1D`
+            let ty = type_name.split('<').next()?.rsplit("::").next()?;
+            TORCH_NAMES
+                .iter()
+                .find(|(t, n, _)| *t == ty && *n == name)
+                .map(|(_, _, torch)| std::format!("{module}{torch}"))
+        });
+        self.params.push((location.to_string(), alias));
+        Ok(())
+    }
+
+    fn enter_module(&mut self, location: &str, type_name: &'static str) {
+        self.modules.push((location.to_string(), type_name));
+    }
+
+    fn exit_module(&mut self) {
+        self.modules.pop();
+    }
+}
+
 /// Extension method that calls [BuildOnDevice] and then [ResetParams].
 pub trait BuildModuleExt<M>: Sized {
     fn build_module<E: Dtype>(&self, m: M) -> M::Built
@@ -259,3 +644,63 @@ pub trait BuildModuleExt<M>: Sized {
     }
 }
 impl<D, M> BuildModuleExt<M> for D {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, tests::*};
+
+    #[allow(clippy::type_complexity)]
+    fn grads_fixture() -> (
+        (
+            Tensor<Rank1<3>, TestDtype, TestDevice>,
+            Tensor<Rank2<1, 2>, TestDtype, TestDevice>,
+            Tensor<Rank1<2>, TestDtype, TestDevice>,
+        ),
+        Gradients<TestDtype, TestDevice>,
+    ) {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank1<3>, TestDtype, _> = dev.ones();
+        let b: Tensor<Rank2<1, 2>, TestDtype, _> = dev.ones();
+        let unused: Tensor<Rank1<2>, TestDtype, _> = dev.ones();
+        let wa = dev.tensor([3.0, 0.0, 4.0]).to_dtype::<TestDtype>();
+        let wb = dev.tensor([[0.0, 12.0]]).to_dtype::<TestDtype>();
+        let loss = (a.leaky_trace() * wa).sum() + (b.leaky_trace() * wb).sum();
+        let grads = loss.backward();
+        ((a, b, unused), grads)
+    }
+
+    #[test]
+    fn test_clip_grad_norm() {
+        let (model, mut grads) = grads_fixture();
+        assert!(grads.get_ref_checked(&model.2).is_none());
+
+        let norm = model.clip_grad_norm(&mut grads, 6.5);
+        let expected: TestDtype = NumCast::from(13.0).unwrap();
+        assert_close!(norm, expected);
+        assert_close_to_literal!(grads.get(&model.0), [1.5, 0.0, 2.0]);
+        assert_close_to_literal!(grads.get(&model.1), [[0.0, 6.0]]);
+        assert!(grads.get_ref_checked(&model.2).is_none());
+        let expected: TestDtype = NumCast::from(6.5).unwrap();
+        assert_close!(model.grad_norm(&mut grads), expected);
+    }
+
+    #[test]
+    fn test_clip_grad_norm_below_max_norm() {
+        let (model, mut grads) = grads_fixture();
+        let norm = model.clip_grad_norm(&mut grads, 100.0);
+        let expected: TestDtype = NumCast::from(13.0).unwrap();
+        assert_close!(norm, expected);
+        assert_close_to_literal!(grads.get(&model.0), [3.0, 0.0, 4.0]);
+        assert_close_to_literal!(grads.get(&model.1), [[0.0, 12.0]]);
+    }
+
+    #[test]
+    fn test_clip_grad_value() {
+        let (model, mut grads) = grads_fixture();
+        model.clip_grad_value(&mut grads, 3.5);
+        assert_close_to_literal!(grads.get(&model.0), [3.0, 0.0, 3.5]);
+        assert_close_to_literal!(grads.get(&model.1), [[0.0, 3.5]]);
+        assert!(grads.get_ref_checked(&model.2).is_none());
+    }
+}
@@ -67,6 +67,28 @@ macro_rules! tuple_impls {
             }
         }
 
+        impl<Dev: Device<Elem>, Elem: Dtype, $($name: crate::nn_traits::WithGrads<Elem, Dev>),+> crate::nn_traits::WithGrads<Elem, Dev> for ($($name,)+) {
+            fn try_visit_grads<V: crate::nn_traits::GradsVisitor<Elem, Dev>>(
+                &self,
+                visitor: &mut V,
+                gradients: &mut crate::prelude::Gradients<Elem, Dev>,
+            ) -> Result<(), Error> {
+                $(self.$idx.try_visit_grads(visitor, gradients)?;)+
+                Ok(())
+            }
+        }
+
+        impl<Dev: Device<Elem>, Elem: Dtype, $($name: crate::nn_traits::VisitParams<Elem, Dev>),+> crate::nn_traits::VisitParams<Elem, Dev> for ($($name,)+) {
+            fn try_visit_params<V: crate::nn_traits::ParamsVisitor<Elem, Dev>>(
+                &self,
+                location: &str,
+                visitor: &mut V,
+            ) -> Result<(), Error> {
+                $(self.$idx.try_visit_params(&std::format!("{location}{}.", $idx), visitor)?;)+
+                Ok(())
+            }
+        }
+
         /*This macro expands like this for a 4-tuple:
 
         impl<
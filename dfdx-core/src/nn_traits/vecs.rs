@@ -58,6 +58,36 @@ impl<E: Dtype, D: Device<E>, T: crate::nn_traits::ZeroGrads<E, D>> crate::nn_tra
     }
 }
 
+impl<E: Dtype, D: Device<E>, T: crate::nn_traits::WithGrads<E, D>> crate::nn_traits::WithGrads<E, D>
+    for Vec<T>
+{
+    fn try_visit_grads<V: crate::nn_traits::GradsVisitor<E, D>>(
+        &self,
+        visitor: &mut V,
+        gradients: &mut crate::tensor::Gradients<E, D>,
+    ) -> Result<(), crate::tensor::Error> {
+        for m_i in self.iter() {
+            m_i.try_visit_grads(visitor, gradients)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Dtype, D: Device<E>, T: crate::nn_traits::VisitParams<E, D>>
+    crate::nn_traits::VisitParams<E, D> for Vec<T>
+{
+    fn try_visit_params<V: crate::nn_traits::ParamsVisitor<E, D>>(
+        &self,
+        location: &str,
+        visitor: &mut V,
+    ) -> Result<(), crate::tensor::Error> {
+        for (i, m_i) in self.iter().enumerate() {
+            m_i.try_visit_params(&std::format!("{location}{i}."), visitor)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(feature = "safetensors")]
 impl<T: crate::nn_traits::SaveSafeTensors> crate::nn_traits::SaveSafeTensors for Vec<T> {
     fn write_safetensors(
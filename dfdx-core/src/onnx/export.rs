@@ -0,0 +1,786 @@
+use std::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use super::{proto::*, OnnxError};
+use crate::{
+    dtypes::ToLeBytes,
+    nn_traits::{ParamsVisitor, VisitParams},
+    shapes::{Dtype, Shape, Unit},
+    tensor::{Attr, Error, OpGraph, OpNode, Source, Tensor, TensorRef, UniqueId, Value},
+    tensor_ops::{kernel_ops::*, ops::KernelOpInfo, Device},
+};
+
+/// Turns the graph of the forward pass of `model` into an ONNX model. `inputs` are the
+/// tensors the forward was called with, in order.
+pub(crate) fn try_export<E: Dtype, D: Device<E>, M: VisitParams<E, D> + ?Sized>(
+    model: &M,
+    graph: &OpGraph,
+    inputs: &[TensorRef],
+) -> Result<ModelProto, OnnxError> {
+    let mut params = BTreeMap::new();
+    model.try_visit_params("", &mut ParamCollector(&mut params))?;
+
+    let mut exporter = Exporter::<E, D> {
+        graph,
+        params,
+        inputs: Vec::new(),
+        views: Vec::new(),
+        outputs: Vec::new(),
+        nodes: Vec::new(),
+        initializers: Vec::new(),
+        num_values: 0,
+        marker: Default::default(),
+    };
+    for (i, input) in inputs.iter().enumerate() {
+        // attention is often called with the same tensor as query, key & value
+        if exporter.inputs.iter().any(|(_, t)| t.id == input.id) {
+            continue;
+        }
+        if input.strides != contiguous_strides(&input.shape) {
+            return Err(OnnxError::Unsupported(format!(
+                "input {i} is not contiguous"
+            )));
+        }
+        let name = match inputs.len() {
+            1 => "input".to_string(),
+            _ => format!("input{i}"),
+        };
+        exporter.inputs.push((name, input.clone()));
+    }
+
+    let live = live_nodes(graph);
+    for (i, node) in graph.nodes.iter().enumerate() {
+        if !live[i] {
+            exporter.outputs.push(String::new());
+            continue;
+        }
+        let mut args = Vec::with_capacity(node.inputs.len());
+        for (value, tensor) in graph.node_inputs[i].iter().zip(node.inputs.iter()) {
+            args.push(exporter.value(value, tensor)?);
+        }
+        let out = match graph.output {
+            Value::Node(j) if i == j => "output".to_string(),
+            _ => format!("{}_{i}", node.op),
+        };
+        exporter.node(node, args, out.clone())?;
+        exporter.outputs.push(out);
+    }
+
+    let output_tensor = match graph.output {
+        Value::Node(i) => graph.nodes[i].output.clone(),
+        Value::Source(i) => {
+            let tensor = graph.sources[i].tensor.clone();
+            let x = exporter.source_view(i, &tensor)?;
+            exporter.add_to("Identity", vec![x], vec![], "output".to_string());
+            tensor
+        }
+    };
+
+    for (name, input) in exporter.inputs.iter() {
+        let used = graph.sources.iter().enumerate().any(|(i, s)| {
+            s.tensor.id == input.id && exporter.views.iter().any(|(j, _, _)| *j == i)
+        });
+        if !used {
+            return Err(OnnxError::Unsupported(format!(
+                "`{name}` does not reach the output through ops that can be captured"
+            )));
+        }
+    }
+
+    let input = exporter
+        .inputs
+        .iter()
+        .map(|(name, t)| Ok(ValueInfoProto::tensor(name, data_type(t.dtype)?, &t.shape)))
+        .collect::<Result<_, OnnxError>>()?;
+    let output = vec![ValueInfoProto::tensor(
+        "output",
+        data_type(output_tensor.dtype)?,
+        &output_tensor.shape,
+    )];
+    Ok(ModelProto {
+        ir_version: IR_VERSION,
+        opset_import: vec![OperatorSetIdProto {
+            domain: String::new(),
+            version: OPSET_VERSION,
+        }],
+        producer_name: "dfdx".into(),
+        producer_version: env!("CARGO_PKG_VERSION").into(),
+        graph: Some(GraphProto {
+            node: exporter.nodes,
+            name: "dfdx".into(),
+            initializer: exporter.initializers,
+            input,
+            output,
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+struct Param {
+    name: String,
+    shape: Vec<usize>,
+    data: Option<Vec<u8>>,
+}
+
+struct ParamCollector<'a>(&'a mut BTreeMap<UniqueId, Param>);
+
+impl<'a, E: Dtype, D: Device<E>> ParamsVisitor<E, D> for ParamCollector<'a> {
+    fn visit_param<S: Shape>(&mut self, location: &str, t: &Tensor<S, E, D>) -> Result<(), Error> {
+        let name = match location {
+            "" => format!("param{}", self.0.len()),
+            _ => location.to_string(),
+        };
+        // tied parameters are only stored once
+        self.0.entry(t.id).or_insert_with(|| Param {
+            name,
+            shape: t.shape.dims(),
+            data: Some(raw_data(&t.as_vec())),
+        });
+        Ok(())
+    }
+}
+
+/// Which nodes the output depends on. Ops that only ran for their side effects, or on
+/// branches that were thrown away, are left out of the exported graph.
+fn live_nodes(graph: &OpGraph) -> Vec<bool> {
+    let mut live = vec![false; graph.nodes.len()];
+    if let Value::Node(i) = graph.output {
+        live[i] = true;
+    }
+    for i in (0..graph.nodes.len()).rev() {
+        if live[i] {
+            for value in graph.node_inputs[i].iter() {
+                if let Value::Node(j) = value {
+                    live[*j] = true;
+                }
+            }
+        }
+    }
+    live
+}
+
+struct Exporter<'a, E, D> {
+    graph: &'a OpGraph,
+    params: BTreeMap<UniqueId, Param>,
+    inputs: Vec<(String, TensorRef)>,
+    /// The views of sources that were already lowered, with their names.
+    views: Vec<(usize, TensorRef, String)>,
+    /// The name of the output of each node of the graph.
+    outputs: Vec<String>,
+    nodes: Vec<NodeProto>,
+    initializers: Vec<TensorProto>,
+    num_values: usize,
+    marker: std::marker::PhantomData<(E, D)>,
+}
+
+impl<'a, E: Dtype, D: Device<E>> Exporter<'a, E, D> {
+    fn value(&mut self, value: &Value, tensor: &TensorRef) -> Result<String, OnnxError> {
+        match value {
+            Value::Node(i) => Ok(self.outputs[*i].clone()),
+            Value::Source(i) => self.source_view(*i, tensor),
+        }
+    }
+
+    /// The name of `view` of a source. Inputs & parameters are stored contiguously, so
+    /// their views are made with reshapes, transposes & expands. Other sources are
+    /// constants, which are stored as they are viewed.
+    fn source_view(&mut self, i: usize, view: &TensorRef) -> Result<String, OnnxError> {
+        if let Some((_, _, name)) = self.views.iter().find(|(j, t, _)| *j == i && t == view) {
+            return Ok(name.clone());
+        }
+        let source: &Source = &self.graph.sources[i];
+        let name = if let Some((name, input)) = self.inputs.iter().find(|(_, t)| t.id == view.id) {
+            let (name, shape) = (name.clone(), input.shape.clone());
+            self.lower_view(name, &shape, view)?
+        } else if let Some(param) = self.params.get_mut(&view.id) {
+            let (name, shape) = (param.name.clone(), param.shape.clone());
+            // stored the first time it is used
+            if let Some(raw_data) = param.data.take() {
+                self.initializers.push(TensorProto {
+                    dims: shape.iter().map(|&d| d as i64).collect(),
+                    data_type: data_type(view.dtype)? as i32,
+                    name: name.clone(),
+                    raw_data,
+                    ..Default::default()
+                });
+            }
+            self.lower_view(name, &shape, view)?
+        } else if let Some(buffer) = source.buffer.as_ref() {
+            if let Some(t) = buffer.get::<E, D>() {
+                self.constant(&t.as_vec(), view)?
+            } else if let Some(t) = buffer.get::<usize, D>() {
+                self.constant(&t.as_vec(), view)?
+            } else {
+                return Err(OnnxError::Unsupported(format!(
+                    "constants of dtype {}",
+                    view.dtype
+                )));
+            }
+        } else {
+            return Err(OnnxError::Unsupported(format!(
+                "tensor with shape {:?} that is neither an input, a parameter or a constant",
+                view.shape
+            )));
+        };
+        self.views.push((i, view.clone(), name.clone()));
+        Ok(name)
+    }
+
+    /// Views the contiguous tensor `x` of shape `dims` like `view`.
+    fn lower_view(
+        &mut self,
+        mut x: String,
+        dims: &[usize],
+        view: &TensorRef,
+    ) -> Result<String, OnnxError> {
+        if view.shape == dims && view.strides == contiguous_strides(dims) {
+            return Ok(x);
+        }
+        let unsupported = || {
+            OnnxError::Unsupported(format!(
+                "view of a tensor with shape {dims:?} as shape {:?} and strides {:?}",
+                view.shape, view.strides
+            ))
+        };
+
+        // the axes that are read, from outermost to innermost in memory
+        let mut axes: Vec<usize> = (0..view.shape.len())
+            .filter(|&i| view.shape[i] > 1 && view.strides[i] > 0)
+            .collect();
+        axes.sort_by_key(|&i| std::cmp::Reverse(view.strides[i]));
+        let mut numel = 1;
+        for &i in axes.iter().rev() {
+            if view.strides[i] != numel {
+                return Err(unsupported());
+            }
+            numel *= view.shape[i];
+        }
+        if numel != dims.iter().product::<usize>() {
+            return Err(unsupported());
+        }
+
+        let compact: Vec<usize> = axes.iter().map(|&i| view.shape[i]).collect();
+        if compact != dims {
+            x = self.reshape(x, &compact);
+        }
+        let mut perm: Vec<usize> = (0..axes.len()).collect();
+        perm.sort_by_key(|&j| axes[j]);
+        if perm.iter().enumerate().any(|(i, &j)| i != j) {
+            let perm = perm.iter().map(|&j| j as i64).collect();
+            x = self.add(
+                "Transpose",
+                vec![x],
+                vec![AttributeProto::ints("perm", perm)],
+            );
+        }
+        let unexpanded: Vec<usize> = (0..view.shape.len())
+            .map(|i| {
+                if view.strides[i] == 0 {
+                    1
+                } else {
+                    view.shape[i]
+                }
+            })
+            .collect();
+        axes.sort();
+        let transposed: Vec<usize> = axes.iter().map(|&i| view.shape[i]).collect();
+        if unexpanded != transposed {
+            x = self.reshape(x, &unexpanded);
+        }
+        if unexpanded != view.shape {
+            x = self.expand(x, &view.shape);
+        }
+        Ok(x)
+    }
+
+    /// Stores the elements of `view` of `data` as an initializer. Broadcasted axes are
+    /// stored once and expanded.
+    fn constant<F: Unit>(&mut self, data: &[F], view: &TensorRef) -> Result<String, OnnxError> {
+        let dims: Vec<usize> = (0..view.shape.len())
+            .map(|i| {
+                if view.strides[i] == 0 {
+                    1
+                } else {
+                    view.shape[i]
+                }
+            })
+            .collect();
+        let numel: usize = dims.iter().product();
+        let mut index = vec![0; dims.len()];
+        let mut elems = Vec::with_capacity(numel);
+        for _ in 0..numel {
+            let offset: usize = index
+                .iter()
+                .zip(view.strides.iter())
+                .map(|(i, s)| i * s)
+                .sum();
+            elems.push(data[offset]);
+            for d in (0..dims.len()).rev() {
+                index[d] += 1;
+                if index[d] < dims[d] {
+                    break;
+                }
+                index[d] = 0;
+            }
+        }
+        let name = self.fresh("Constant");
+        self.initializers.push(TensorProto {
+            dims: dims.iter().map(|&d| d as i64).collect(),
+            data_type: data_type(view.dtype)? as i32,
+            name: name.clone(),
+            raw_data: raw_data(&elems),
+            ..Default::default()
+        });
+        Ok(match dims == view.shape {
+            true => name,
+            false => self.expand(name, &view.shape),
+        })
+    }
+
+    fn fresh(&mut self, op: &str) -> String {
+        self.num_values += 1;
+        format!("{op}_v{}", self.num_values)
+    }
+
+    fn add_to(&mut self, op: &str, inputs: Vec<String>, attrs: Vec<AttributeProto>, out: String) {
+        self.nodes.push(NodeProto {
+            input: inputs,
+            output: vec![out.clone()],
+            name: out,
+            op_type: op.into(),
+            attribute: attrs,
+            ..Default::default()
+        });
+    }
+
+    fn add(&mut self, op: &str, inputs: Vec<String>, attrs: Vec<AttributeProto>) -> String {
+        let out = self.fresh(op);
+        self.add_to(op, inputs, attrs, out.clone());
+        out
+    }
+
+    fn ints(&mut self, values: &[i64]) -> String {
+        let name = self.fresh("Constant");
+        self.initializers.push(TensorProto {
+            dims: vec![values.len() as i64],
+            data_type: DataType::Int64 as i32,
+            name: name.clone(),
+            raw_data: raw_data(values),
+            ..Default::default()
+        });
+        name
+    }
+
+    fn scalar(&mut self, value: f64) -> String {
+        let name = self.fresh("Constant");
+        self.initializers.push(TensorProto {
+            data_type: data_type(core::any::type_name::<E>()).unwrap() as i32,
+            name: name.clone(),
+            raw_data: raw_data(&[E::from_f64(value).unwrap()]),
+            ..Default::default()
+        });
+        name
+    }
+
+    fn reshape(&mut self, x: String, shape: &[usize]) -> String {
+        let shape = self.ints(&shape.iter().map(|&d| d as i64).collect::<Vec<_>>());
+        self.add("Reshape", vec![x, shape], vec![])
+    }
+
+    fn expand(&mut self, x: String, shape: &[usize]) -> String {
+        let shape = self.ints(&shape.iter().map(|&d| d as i64).collect::<Vec<_>>());
+        self.add("Expand", vec![x, shape], vec![])
+    }
+
+    /// Adds the ONNX ops that compute `node` from `args`, with the last one writing `out`.
+    fn node(&mut self, node: &OpNode, args: Vec<String>, out: String) -> Result<(), OnnxError> {
+        let mut args = args.into_iter();
+        let mut x = args.next().unwrap_or_default();
+        let y = args.next().unwrap_or_default();
+        let rank = node.inputs.first().map_or(0, |t| t.shape.len());
+        match node.op.as_str() {
+            <AbsKernelOp as KernelOpInfo>::NAME => self.add_to("Abs", vec![x], vec![], out),
+            <ExpKernelOp as KernelOpInfo>::NAME => self.add_to("Exp", vec![x], vec![], out),
+            <SqrtKernelOp as KernelOpInfo>::NAME => self.add_to("Sqrt", vec![x], vec![], out),
+            <SinKernelOp as KernelOpInfo>::NAME => self.add_to("Sin", vec![x], vec![], out),
+            <CosKernelOp as KernelOpInfo>::NAME => self.add_to("Cos", vec![x], vec![], out),
+            <TanhKernelOp as KernelOpInfo>::NAME => self.add_to("Tanh", vec![x], vec![], out),
+            <SigmoidKernelOp as KernelOpInfo>::NAME => self.add_to("Sigmoid", vec![x], vec![], out),
+            <LnKernelOp as KernelOpInfo>::NAME => self.add_to("Log", vec![x], vec![], out),
+            <ReLUKernelOp as KernelOpInfo>::NAME => self.add_to("Relu", vec![x], vec![], out),
+            <NegateKernelOp as KernelOpInfo>::NAME => self.add_to("Neg", vec![x], vec![], out),
+            <RecipKernelOp as KernelOpInfo>::NAME => {
+                self.add_to("Reciprocal", vec![x], vec![], out)
+            }
+            <SquareKernelOp as KernelOpInfo>::NAME => {
+                self.add_to("Mul", vec![x.clone(), x], vec![], out)
+            }
+            // the names of ops with a dtype parameter don't depend on the dtype
+            <PowiKernelOp as KernelOpInfo>::NAME | <PowfKernelOp<f32> as KernelOpInfo>::NAME => {
+                let exponent = self.scalar(float_attr(node, "exponent")?);
+                self.add_to("Pow", vec![x, exponent], vec![], out);
+            }
+            <ClampKernelOp<f32> as KernelOpInfo>::NAME => {
+                let min = self.scalar(float_attr(node, "min")?);
+                let max = self.scalar(float_attr(node, "max")?);
+                self.add_to("Clip", vec![x, min, max], vec![], out);
+            }
+            <NansToKernelOp<f32> as KernelOpInfo>::NAME => {
+                let value = self.scalar(float_attr(node, "value")?);
+                let is_nan = self.add("IsNaN", vec![x.clone()], vec![]);
+                self.add_to("Where", vec![is_nan, value, x], vec![], out);
+            }
+            <ScalarAddKernelOp<f32> as KernelOpInfo>::NAME => {
+                let scalar = self.scalar(float_attr(node, "scalar")?);
+                self.add_to("Add", vec![x, scalar], vec![], out);
+            }
+            <ScalarSubKernelOp<f32> as KernelOpInfo>::NAME => {
+                let scalar = self.scalar(float_attr(node, "scalar")?);
+                self.add_to("Sub", vec![x, scalar], vec![], out);
+            }
+            <ScalarMulKernelOp<f32> as KernelOpInfo>::NAME => {
+                let scalar = self.scalar(float_attr(node, "scalar")?);
+                self.add_to("Mul", vec![x, scalar], vec![], out);
+            }
+            <ScalarDivKernelOp<f32> as KernelOpInfo>::NAME => {
+                let scalar = self.scalar(float_attr(node, "scalar")?);
+                self.add_to("Div", vec![x, scalar], vec![], out);
+            }
+            <BinaryAddKernelOp as KernelOpInfo>::NAME => {
+                self.add_to("Add", vec![x, y], vec![], out)
+            }
+            <BinarySubKernelOp as KernelOpInfo>::NAME => {
+                self.add_to("Sub", vec![x, y], vec![], out)
+            }
+            <BinaryMulKernelOp as KernelOpInfo>::NAME => {
+                self.add_to("Mul", vec![x, y], vec![], out)
+            }
+            <BinaryDivKernelOp as KernelOpInfo>::NAME => {
+                self.add_to("Div", vec![x, y], vec![], out)
+            }
+            <MaximumKernelOp as KernelOpInfo>::NAME => self.add_to("Max", vec![x, y], vec![], out),
+            <MinimumKernelOp as KernelOpInfo>::NAME => self.add_to("Min", vec![x, y], vec![], out),
+            <FastGeLUKernelOp as KernelOpInfo>::NAME => {
+                // 0.5 * x * (1 + tanh(sqrt(2 / pi) * (x + 0.044715 * x^3)))
+                let three = self.scalar(3.0);
+                let cube = self.add("Pow", vec![x.clone(), three], vec![]);
+                let c = self.scalar(0.044715);
+                let cube = self.add("Mul", vec![cube, c], vec![]);
+                let inner = self.add("Add", vec![x.clone(), cube], vec![]);
+                let c = self.scalar(std::f64::consts::FRAC_2_PI.sqrt());
+                let inner = self.add("Mul", vec![inner, c], vec![]);
+                let tanh = self.add("Tanh", vec![inner], vec![]);
+                self.gelu_tail(x, tanh, out);
+            }
+            <AccurateGeLUKernelOp as KernelOpInfo>::NAME => {
+                // 0.5 * x * (1 + erf(x / sqrt(2)))
+                let c = self.scalar(std::f64::consts::FRAC_1_SQRT_2);
+                let inner = self.add("Mul", vec![x.clone(), c], vec![]);
+                let erf = self.add("Erf", vec![inner], vec![]);
+                self.gelu_tail(x, erf, out);
+            }
+            "PReLU" => self.add_to("PRelu", vec![x, y], vec![], out),
+            "LeakyReLU" => {
+                let alpha = AttributeProto::float("alpha", float_attr(node, "alpha")? as f32);
+                self.add_to("LeakyRelu", vec![x], vec![alpha], out);
+            }
+            "MatMul" => self.add_to("MatMul", vec![x, y], vec![], out),
+            "Sum" => {
+                let axes = ints_attr(node, "axes")?;
+                let axes = self.ints(&axes);
+                let keepdims = AttributeProto::int("keepdims", 0);
+                self.add_to("ReduceSum", vec![x, axes], vec![keepdims], out);
+            }
+            "Max" | "Min" => {
+                let axes = AttributeProto::ints("axes", ints_attr(node, "axes")?);
+                let keepdims = AttributeProto::int("keepdims", 0);
+                let op = format!("Reduce{}", node.op);
+                self.add_to(&op, vec![x], vec![axes, keepdims], out);
+            }
+            "Reshape" => {
+                let shape: Vec<i64> = node.output.shape.iter().map(|&d| d as i64).collect();
+                let shape = self.ints(&shape);
+                self.add_to("Reshape", vec![x, shape], vec![], out);
+            }
+            "Permute" => {
+                let perm = AttributeProto::ints("perm", ints_attr(node, "axes")?);
+                self.add_to("Transpose", vec![x], vec![perm], out);
+            }
+            "Broadcast" => {
+                let axes = ints_attr(node, "axes")?;
+                if !axes.is_empty() {
+                    let axes = self.ints(&axes);
+                    x = self.add("Unsqueeze", vec![x, axes], vec![]);
+                }
+                let shape: Vec<i64> = node.output.shape.iter().map(|&d| d as i64).collect();
+                let shape = self.ints(&shape);
+                self.add_to("Expand", vec![x, shape], vec![], out);
+            }
+            "Select" | "Gather" if ints_attr(node, "axes")? == [0] => {
+                let axis = AttributeProto::int("axis", 0);
+                self.add_to("Gather", vec![x, y], vec![axis], out);
+            }
+            "Conv1D" | "Conv2D" | "Conv3D" => {
+                let filters = &node.inputs[1].shape;
+                let spatial = filters.len() - 2;
+                let batched = rank == filters.len();
+                let n = |v: i64| vec![v; spatial];
+                let attrs = vec![
+                    AttributeProto::ints("kernel_shape", n(filters[2] as i64)),
+                    AttributeProto::ints("strides", n(int_attr(node, "stride")?)),
+                    AttributeProto::ints("pads", vec![int_attr(node, "padding")?; 2 * spatial]),
+                    AttributeProto::ints("dilations", n(int_attr(node, "dilation")?)),
+                    AttributeProto::int("group", int_attr(node, "groups")?),
+                ];
+                self.batched(x, batched, out, |e, x, out| {
+                    e.add_to("Conv", vec![x, y], attrs, out)
+                });
+            }
+            "Pool1D" | "Pool2D" | "Pool3D" => {
+                let kind = string_attr(node, "kind")?;
+                let dilation = int_attr(node, "dilation")?;
+                let spatial = match node.op.as_str() {
+                    "Pool1D" => 1,
+                    "Pool2D" => 2,
+                    _ => 3,
+                };
+                let n = |v: i64| vec![v; spatial];
+                let mut attrs = vec![
+                    AttributeProto::ints("kernel_shape", n(int_attr(node, "kernel")?)),
+                    AttributeProto::ints("strides", n(int_attr(node, "stride")?)),
+                    AttributeProto::ints("pads", vec![int_attr(node, "padding")?; 2 * spatial]),
+                ];
+                let batched = rank == spatial + 2;
+                match kind {
+                    "Avg" => {
+                        // AveragePool only supports dilations since opset 19
+                        if dilation != 1 {
+                            return Err(OnnxError::Unsupported("dilated average pooling".into()));
+                        }
+                        attrs.push(AttributeProto::int("count_include_pad", 1));
+                        self.batched(x, batched, out, |e, x, out| {
+                            e.add_to("AveragePool", vec![x], attrs, out)
+                        });
+                    }
+                    "Max" | "Min" => {
+                        attrs.push(AttributeProto::ints("dilations", n(dilation)));
+                        let negate = kind == "Min";
+                        self.batched(x, batched, out, |e, mut x, out| {
+                            if negate {
+                                // min(x) = -max(-x)
+                                x = e.add("Neg", vec![x], vec![]);
+                                let x = e.add("MaxPool", vec![x], attrs);
+                                e.add_to("Neg", vec![x], vec![], out);
+                            } else {
+                                e.add_to("MaxPool", vec![x], attrs, out);
+                            }
+                        });
+                    }
+                    _ => return Err(OnnxError::Unsupported(format!("{kind} pooling"))),
+                }
+            }
+            _ => return Err(OnnxError::Unsupported(format!("the {} op", node.op))),
+        }
+        Ok(())
+    }
+
+    /// `0.5 * x * (1 + t)`
+    fn gelu_tail(&mut self, x: String, t: String, out: String) {
+        let one = self.scalar(1.0);
+        let t = self.add("Add", vec![t, one], vec![]);
+        let x = self.add("Mul", vec![x, t], vec![]);
+        let half = self.scalar(0.5);
+        self.add_to("Mul", vec![x, half], vec![], out);
+    }
+
+    /// Runs `f`, which adds ops that need a batch axis, on `x`, adding a batch axis
+    /// of size 1 to it if it doesn't have one.
+    fn batched<F: FnOnce(&mut Self, String, String)>(
+        &mut self,
+        x: String,
+        batched: bool,
+        out: String,
+        f: F,
+    ) {
+        if batched {
+            f(self, x, out);
+        } else {
+            let axes = self.ints(&[0]);
+            let x = self.add("Unsqueeze", vec![x, axes.clone()], vec![]);
+            let y = self.fresh("Batched");
+            f(self, x, y.clone());
+            self.add_to("Squeeze", vec![y, axes], vec![], out);
+        }
+    }
+}
+
+pub(super) fn contiguous_strides(shape: &[usize]) -> Vec<usize> {
+    let mut strides = vec![1; shape.len()];
+    for i in (0..shape.len().saturating_sub(1)).rev() {
+        strides[i] = strides[i + 1] * shape[i + 1];
+    }
+    strides
+}
+
+fn raw_data<E: Unit + ToLeBytes>(data: &[E]) -> Vec<u8> {
+    data.iter().flat_map(|&e| e.to_le_bytes()).collect()
+}
+
+/// The ONNX type of a dtype, from its [core::any::type_name].
+fn data_type(dtype: &str) -> Result<DataType, OnnxError> {
+    let name = dtype
+        .trim_end_matches('>')
+        .rsplit("::")
+        .next()
+        .unwrap_or(dtype);
+    Ok(match name {
+        "f32" => DataType::Float,
+        "f64" => DataType::Double,
+        "f16" => DataType::Float16,
+        "usize" | "isize" | "i64" => DataType::Int64,
+        "u64" => DataType::Uint64,
+        "i32" => DataType::Int32,
+        "u32" => DataType::Uint32,
+        "u8" => DataType::Uint8,
+        "i8" => DataType::Int8,
+        "bool" => DataType::Bool,
+        _ => return Err(OnnxError::Unsupported(format!("tensors of {dtype}"))),
+    })
+}
+
+fn attr<'a>(node: &'a OpNode, key: &str) -> Result<&'a Attr, OnnxError> {
+    node.attrs
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v)
+        .ok_or_else(|| OnnxError::Unsupported(format!("{} without `{key}`", node.op)))
+}
+
+fn int_attr(node: &OpNode, key: &str) -> Result<i64, OnnxError> {
+    match attr(node, key)? {
+        Attr::Int(v) => Ok(*v),
+        v => Err(OnnxError::Unsupported(format!(
+            "{} with {key}={v:?}",
+            node.op
+        ))),
+    }
+}
+
+fn float_attr(node: &OpNode, key: &str) -> Result<f64, OnnxError> {
+    match attr(node, key)? {
+        Attr::Int(v) => Ok(*v as f64),
+        Attr::Float(v) => Ok(*v),
+        v => Err(OnnxError::Unsupported(format!(
+            "{} with {key}={v:?}",
+            node.op
+        ))),
+    }
+}
+
+fn ints_attr(node: &OpNode, key: &str) -> Result<Vec<i64>, OnnxError> {
+    match attr(node, key)? {
+        Attr::Ints(v) => Ok(v.clone()),
+        v => Err(OnnxError::Unsupported(format!(
+            "{} with {key}={v:?}",
+            node.op
+        ))),
+    }
+}
+
+fn string_attr<'a>(node: &'a OpNode, key: &str) -> Result<&'a str, OnnxError> {
+    match attr(node, key)? {
+        Attr::String(v) => Ok(v.as_str()),
+        v => Err(OnnxError::Unsupported(format!(
+            "{} with {key}={v:?}",
+            node.op
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn no_params() -> Vec<Tensor<Rank0, TestDtype, TestDevice>> {
+        Vec::new()
+    }
+
+    fn ops(model: &ModelProto) -> Vec<&str> {
+        let graph = model.graph.as_ref().unwrap();
+        graph.node.iter().map(|n| n.op_type.as_str()).collect()
+    }
+
+    #[test]
+    fn test_export_param_views() {
+        let dev: TestDevice = Default::default();
+        let w: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank1<4>, TestDtype, _> = dev.sample_normal();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+
+        // the parameters are used as views without the tape
+        let y = x.clone().capture().matmul(w.clone().permute()) + b.clone().broadcast();
+        let (_, graph) = y.split_graph();
+        let model = try_export(&(w, b), &graph, &[TensorRef::new(&x)]).unwrap();
+
+        assert_eq!(
+            ops(&model),
+            ["Transpose", "MatMul", "Reshape", "Expand", "Add"]
+        );
+        let graph = model.graph.unwrap();
+        assert_eq!(graph.node[0].input, ["0."]);
+        assert_eq!(graph.node[1].input[0], "input");
+        assert_eq!(graph.node[2].input[0], "1.");
+        assert_eq!(graph.node[4].output, ["output"]);
+        let dims: Vec<_> = graph.initializer.iter().map(|t| t.dims.clone()).collect();
+        assert_eq!(dims, [vec![4, 3], vec![4], vec![2], vec![2]]);
+        assert_eq!(graph.initializer[2].raw_data, raw_data(&[1i64, 4]));
+        assert_eq!(graph.initializer[3].raw_data, raw_data(&[2i64, 4]));
+    }
+
+    #[test]
+    fn test_export_constants() {
+        let dev: TestDevice = Default::default();
+        let c: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let x: Tensor<Rank2<3, 2>, TestDtype, _> = dev.sample_normal();
+
+        let (_, graph) = (x.clone().capture().clamp(-1.0, 1.0) * c.clone().permute())
+            .reshape::<Rank1<6>>()
+            .split_graph();
+        let model = try_export(&no_params(), &graph, &[TensorRef::new(&x)]).unwrap();
+        assert_eq!(ops(&model), ["Clip", "Mul", "Reshape"]);
+
+        // the constant is stored as it was viewed
+        let graph = model.graph.unwrap();
+        let constant = graph
+            .initializer
+            .iter()
+            .find(|t| t.name == graph.node[1].input[1])
+            .unwrap();
+        assert_eq!(constant.dims, [3, 2]);
+        let expected: Vec<TestDtype> = c.permute::<Rank2<3, 2>, _>().as_vec();
+        assert_eq!(constant.raw_data, raw_data(&expected));
+    }
+
+    #[test]
+    fn test_export_uncaptured_input() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let (_, graph) = x.clone().capture().split_graph();
+        let model = try_export(&no_params(), &graph, &[TensorRef::new(&x)]).unwrap();
+        assert_eq!(ops(&model), ["Identity"]);
+
+        // the output does not depend on the input, like when it is only used by ops
+        // that are not recorded
+        let y: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let (_, graph) = y.capture().exp().split_graph();
+        let err = try_export(&no_params(), &graph, &[TensorRef::new(&x)]).unwrap_err();
+        assert!(matches!(err, OnnxError::Unsupported(_)));
+    }
+}
@@ -0,0 +1,1781 @@
+use std::{
+    collections::HashMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use super::{export::contiguous_strides, load_model, proto::*, OnnxError};
+use crate::{
+    shapes::{Axis, Shape},
+    tensor::{Cpu, Error, Tensor, TensorFromVec},
+    tensor_ops::*,
+};
+
+/// The ops an [OnnxModel] can run, from the default `ai.onnx` domain.
+pub const SUPPORTED_OPS: &[&str] = &[
+    "Abs",
+    "Add",
+    "AveragePool",
+    "BatchNormalization",
+    "Cast",
+    "Clip",
+    "Concat",
+    "Constant",
+    "ConstantOfShape",
+    "Conv",
+    "Cos",
+    "Div",
+    "Dropout",
+    "Erf",
+    "Exp",
+    "Expand",
+    "Flatten",
+    "Gather",
+    "Gelu",
+    "Gemm",
+    "GlobalAveragePool",
+    "GlobalMaxPool",
+    "Identity",
+    "IsNaN",
+    "LayerNormalization",
+    "LeakyRelu",
+    "Log",
+    "LogSoftmax",
+    "MatMul",
+    "Max",
+    "MaxPool",
+    "Min",
+    "Mul",
+    "Neg",
+    "PRelu",
+    "Pow",
+    "Reciprocal",
+    "ReduceMax",
+    "ReduceMean",
+    "ReduceMin",
+    "ReduceSum",
+    "Relu",
+    "Reshape",
+    "Shape",
+    "Sigmoid",
+    "Sin",
+    "Slice",
+    "Softmax",
+    "Split",
+    "Sqrt",
+    "Squeeze",
+    "Sub",
+    "Tanh",
+    "Transpose",
+    "Unsqueeze",
+    "Where",
+];
+
+type Flat = Tensor<(usize,), f32, Cpu>;
+
+/// A tensor flowing through an [OnnxModel], with a shape that is only known at runtime.
+///
+/// Floating point tensors are stored as a flat [Tensor] on the [Cpu], and converted to
+/// `f32`. Integer and boolean tensors, which are mostly indices and shapes, are stored
+/// on the host as `i64`.
+///
+/// Create one from a [Tensor] with [From], and get the results back with
+/// [OnnxValue::to_tensor]:
+/// ```rust
+/// # use dfdx_core::{prelude::*, onnx::OnnxValue};
+/// # let dev: Cpu = Default::default();
+/// let x: OnnxValue = dev.tensor([[1.0f32, 2.0, 3.0], [4.0, 5.0, 6.0]]).into();
+/// assert_eq!(x.shape(), &[2, 3]);
+/// let y: Tensor<Rank2<2, 3>, f32, _> = x.to_tensor(Default::default()).unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct OnnxValue {
+    shape: Vec<usize>,
+    data: Data,
+}
+
+#[derive(Clone, Debug)]
+enum Data {
+    Float(Flat),
+    Int(Vec<i64>),
+}
+
+impl<S: Shape> From<Tensor<S, f32, Cpu>> for OnnxValue {
+    fn from(t: Tensor<S, f32, Cpu>) -> Self {
+        let shape = t.shape.concrete().into_iter().collect();
+        let numel = t.shape.num_elements();
+        Self {
+            shape,
+            data: Data::Float(t.reshape_like(&(numel,))),
+        }
+    }
+}
+
+impl<S: Shape> From<Tensor<S, usize, Cpu>> for OnnxValue {
+    fn from(t: Tensor<S, usize, Cpu>) -> Self {
+        let shape = t.shape.concrete().into_iter().collect();
+        let data = t.as_vec().into_iter().map(|i| i as i64).collect();
+        Self::int(data, shape)
+    }
+}
+
+impl OnnxValue {
+    fn float(t: Flat, shape: Vec<usize>) -> Self {
+        Self {
+            shape,
+            data: Data::Float(t),
+        }
+    }
+
+    fn int(data: Vec<i64>, shape: Vec<usize>) -> Self {
+        Self {
+            shape,
+            data: Data::Int(data),
+        }
+    }
+
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Whether this holds a floating point tensor.
+    pub fn is_float(&self) -> bool {
+        matches!(self.data, Data::Float(_))
+    }
+
+    /// The elements in row major order, with integers converted to `f32`.
+    pub fn as_vec(&self) -> Vec<f32> {
+        match &self.data {
+            Data::Float(t) => t.as_vec(),
+            Data::Int(v) => v.iter().map(|&i| i as f32).collect(),
+        }
+    }
+
+    /// The elements in row major order, with floats truncated.
+    pub fn to_ints(&self) -> Vec<i64> {
+        match &self.data {
+            Data::Float(t) => t.as_vec().into_iter().map(|f| f as i64).collect(),
+            Data::Int(v) => v.clone(),
+        }
+    }
+
+    /// Converts this into a tensor of `shape`, which has to have the same dimensions.
+    pub fn to_tensor<S: Shape>(&self, shape: S) -> Result<Tensor<S, f32, Cpu>, OnnxError> {
+        let dims: Vec<usize> = shape.concrete().into_iter().collect();
+        if dims != self.shape {
+            return Err(OnnxError::Invalid(format!(
+                "a value of shape {:?} can't be converted to shape {dims:?}",
+                self.shape
+            )));
+        }
+        match &self.data {
+            Data::Float(t) => Ok(t.clone().try_reshape_like(&shape)?),
+            Data::Int(v) => {
+                let data = v.iter().map(|&i| i as f32).collect();
+                Ok(Cpu::default().try_tensor_from_vec(data, shape)?)
+            }
+        }
+    }
+
+    fn numel(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// The same data with another shape with as many elements.
+    fn reshaped(&self, shape: Vec<usize>) -> Self {
+        debug_assert_eq!(shape.iter().product::<usize>(), self.numel());
+        Self {
+            shape,
+            data: self.data.clone(),
+        }
+    }
+
+    /// A new value made of the elements at `indices`.
+    fn take(&self, indices: Vec<usize>, shape: Vec<usize>) -> Result<Self, OnnxError> {
+        let data = match &self.data {
+            Data::Float(t) => {
+                let indices = t
+                    .device
+                    .try_tensor_from_vec(indices, (shape.iter().product(),))?;
+                Data::Float(t.clone().try_gather(indices)?)
+            }
+            Data::Int(v) => Data::Int(indices.into_iter().map(|i| v[i]).collect()),
+        };
+        Ok(Self { shape, data })
+    }
+
+    /// A strided view of the elements, like `torch.as_strided`.
+    fn view(&self, shape: Vec<usize>, strides: &[isize], offset: isize) -> Result<Self, OnnxError> {
+        self.take(view_indices(&shape, strides, offset), shape)
+    }
+
+    /// Broadcasts this to `shape` like numpy, where `shape` has to be compatible.
+    fn expand(&self, shape: &[usize]) -> Result<Self, OnnxError> {
+        if self.shape == shape {
+            return Ok(self.clone());
+        }
+        let pad = shape.len() - self.shape.len();
+        let strides = contiguous_strides(&self.shape);
+        let strides: Vec<isize> = (0..shape.len())
+            .map(|i| match i.checked_sub(pad) {
+                Some(j) if self.shape[j] != 1 => strides[j] as isize,
+                _ => 0,
+            })
+            .collect();
+        self.view(shape.to_vec(), &strides, 0)
+    }
+
+    /// `len` elements along `axis` starting at `start`.
+    fn narrow(&self, axis: usize, start: usize, len: usize) -> Result<Self, OnnxError> {
+        let strides: Vec<isize> = contiguous_strides(&self.shape)
+            .into_iter()
+            .map(|s| s as isize)
+            .collect();
+        let mut shape = self.shape.clone();
+        shape[axis] = len;
+        self.view(shape, &strides, start as isize * strides[axis])
+    }
+}
+
+/// A graph read from an ONNX file, that runs on the [Cpu] for inference.
+///
+/// Each ONNX op is run with the equivalent dfdx op on a tensor with dynamic dimensions,
+/// and all the initializers are loaded into tensors up front. Ops that only move data
+/// around, like `Transpose`, `Expand` or `Slice`, are run as a gather of the elements.
+///
+/// ```ignore
+/// # use dfdx_core::{prelude::*, onnx::OnnxModel};
+/// let dev: Cpu = Default::default();
+/// let model = OnnxModel::load("model.onnx", &dev)?;
+/// let x: Tensor<Rank2<1, 784>, f32, _> = dev.sample_normal();
+/// let y = model.run(vec![x.into()])?;
+/// let y: Tensor<Rank2<1, 10>, f32, _> = y[0].to_tensor(Default::default())?;
+/// ```
+///
+/// Loading fails with [OnnxError::UnsupportedOps] listing every op of the graph that
+/// isn't in [SUPPORTED_OPS]. Some ops are also only supported with some attributes,
+/// like `Conv` with the same padding on every side, which is an
+/// [OnnxError::Unsupported] error when the op is run.
+#[derive(Clone, Debug)]
+pub struct OnnxModel {
+    dev: Cpu,
+    opset: i64,
+    nodes: Vec<NodeProto>,
+    initializers: HashMap<String, OnnxValue>,
+    inputs: Vec<Input>,
+    outputs: Vec<String>,
+    /// The values that aren't needed anymore after each node.
+    dropped: Vec<Vec<String>>,
+}
+
+#[derive(Clone, Debug)]
+struct Input {
+    name: String,
+    float: bool,
+    /// The dimensions, with `None` for dynamic ones like `batch`.
+    dims: Option<Vec<Option<usize>>>,
+}
+
+impl OnnxModel {
+    /// Reads a `.onnx` file, see [OnnxModel::from_proto].
+    pub fn load<P: AsRef<std::path::Path>>(path: P, dev: &Cpu) -> Result<Self, OnnxError> {
+        Self::from_proto(&load_model(path)?, dev)
+    }
+
+    /// Checks that all the ops of `model` are supported, and loads its initializers.
+    pub fn from_proto(model: &ModelProto, dev: &Cpu) -> Result<Self, OnnxError> {
+        let graph = model
+            .graph
+            .as_ref()
+            .ok_or_else(|| OnnxError::Invalid("the model has no graph".to_string()))?;
+        let opset = model
+            .opset_import
+            .iter()
+            .find(|o| o.domain.is_empty() || o.domain == "ai.onnx")
+            .map_or(OPSET_VERSION, |o| o.version);
+
+        let mut unsupported: Vec<String> = Vec::new();
+        for node in graph.node.iter() {
+            let op = match node.domain.as_str() {
+                "" | "ai.onnx" if SUPPORTED_OPS.contains(&node.op_type.as_str()) => continue,
+                "" | "ai.onnx" => node.op_type.clone(),
+                domain => format!("{domain}.{}", node.op_type),
+            };
+            if !unsupported.contains(&op) {
+                unsupported.push(op);
+            }
+        }
+        if !unsupported.is_empty() {
+            return Err(OnnxError::UnsupportedOps(unsupported));
+        }
+
+        let mut initializers = HashMap::new();
+        for t in graph.initializer.iter() {
+            initializers.insert(t.name.clone(), tensor_value(t, dev)?);
+        }
+
+        // older files also list the initializers as inputs
+        let inputs = graph
+            .input
+            .iter()
+            .filter(|i| !initializers.contains_key(&i.name))
+            .map(|i| {
+                let t = match i.r#type.as_ref().and_then(|t| t.value.as_ref()) {
+                    Some(type_proto::Value::TensorType(t)) => t,
+                    None => {
+                        return Err(OnnxError::Unsupported(format!(
+                            "input `{}` that is not a tensor",
+                            i.name
+                        )))
+                    }
+                };
+                let dims = t.shape.as_ref().map(|s| {
+                    s.dim
+                        .iter()
+                        .map(|d| match d.value {
+                            Some(tensor_shape_proto::dimension::Value::DimValue(v)) => {
+                                Some(v as usize)
+                            }
+                            _ => None,
+                        })
+                        .collect()
+                });
+                Ok(Input {
+                    name: i.name.clone(),
+                    float: is_float(t.elem_type),
+                    dims,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let outputs: Vec<String> = graph.output.iter().map(|o| o.name.clone()).collect();
+
+        let mut last_use: HashMap<&str, usize> = HashMap::new();
+        for (i, node) in graph.node.iter().enumerate() {
+            for name in node.input.iter().chain(node.output.iter()) {
+                last_use.insert(name.as_str(), i);
+            }
+        }
+        let mut dropped = vec![Vec::new(); graph.node.len()];
+        for (name, i) in last_use {
+            if !name.is_empty() && !outputs.iter().any(|o| o == name) {
+                dropped[i].push(name.to_string());
+            }
+        }
+
+        Ok(Self {
+            dev: dev.clone(),
+            opset,
+            nodes: graph.node.clone(),
+            initializers,
+            inputs,
+            outputs,
+            dropped,
+        })
+    }
+
+    /// The names of the inputs [OnnxModel::run] expects, in order.
+    pub fn input_names(&self) -> impl Iterator<Item = &str> {
+        self.inputs.iter().map(|i| i.name.as_str())
+    }
+
+    /// The names of the outputs [OnnxModel::run] returns, in order.
+    pub fn output_names(&self) -> impl Iterator<Item = &str> {
+        self.outputs.iter().map(|o| o.as_str())
+    }
+
+    /// Runs the graph on `inputs`, which are in the order of [OnnxModel::input_names].
+    pub fn run(&self, inputs: Vec<OnnxValue>) -> Result<Vec<OnnxValue>, OnnxError> {
+        if inputs.len() != self.inputs.len() {
+            return Err(OnnxError::Invalid(format!(
+                "the model has {} inputs, but was run with {}",
+                self.inputs.len(),
+                inputs.len()
+            )));
+        }
+        let mut values: HashMap<&str, OnnxValue> = HashMap::new();
+        for (input, value) in self.inputs.iter().zip(inputs) {
+            let shape_matches = input.dims.as_ref().map_or(true, |dims| {
+                dims.len() == value.shape.len()
+                    && dims
+                        .iter()
+                        .zip(value.shape.iter())
+                        .all(|(d, s)| d.map_or(true, |d| d == *s))
+            });
+            if !shape_matches || input.float != value.is_float() {
+                return Err(OnnxError::Invalid(format!(
+                    "input `{}` expects {} of shape {:?}, but got {} of shape {:?}",
+                    input.name,
+                    if input.float { "floats" } else { "integers" },
+                    input.dims,
+                    if value.is_float() {
+                        "floats"
+                    } else {
+                        "integers"
+                    },
+                    value.shape
+                )));
+            }
+            values.insert(&input.name, value);
+        }
+
+        for (i, node) in self.nodes.iter().enumerate() {
+            let args = node
+                .input
+                .iter()
+                .map(|name| match name.as_str() {
+                    "" => Ok(None),
+                    name => self.lookup(&values, name).map(Some),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let outputs = self.eval(node, &args)?;
+            for (name, value) in node.output.iter().zip(outputs) {
+                if !name.is_empty() {
+                    values.insert(name, value);
+                }
+            }
+            for name in self.dropped[i].iter() {
+                values.remove(name.as_str());
+            }
+        }
+
+        self.outputs
+            .iter()
+            .map(|name| self.lookup(&values, name))
+            .collect()
+    }
+
+    fn lookup(
+        &self,
+        values: &HashMap<&str, OnnxValue>,
+        name: &str,
+    ) -> Result<OnnxValue, OnnxError> {
+        values
+            .get(name)
+            .or_else(|| self.initializers.get(name))
+            .cloned()
+            .ok_or_else(|| OnnxError::Invalid(format!("`{name}` is used before it is computed")))
+    }
+
+    fn to_float(&self, x: &OnnxValue) -> Result<Flat, OnnxError> {
+        match &x.data {
+            Data::Float(t) => Ok(t.clone()),
+            Data::Int(v) => {
+                let data = v.iter().map(|&i| i as f32).collect();
+                Ok(self.dev.try_tensor_from_vec(data, (v.len(),))?)
+            }
+        }
+    }
+
+    fn unary<F>(&self, x: &OnnxValue, f: F) -> Result<OnnxValue, OnnxError>
+    where
+        F: FnOnce(Flat) -> Result<Flat, Error>,
+    {
+        Ok(OnnxValue::float(f(self.to_float(x)?)?, x.shape.clone()))
+    }
+
+    /// An elementwise op with numpy broadcasting. Integers stay on the host if there is
+    /// an `int` version of the op.
+    fn binary<F>(
+        &self,
+        a: &OnnxValue,
+        b: &OnnxValue,
+        f: F,
+        int: Option<fn(i64, i64) -> i64>,
+    ) -> Result<OnnxValue, OnnxError>
+    where
+        F: FnOnce(Flat, Flat) -> Result<Flat, Error>,
+    {
+        let shape = broadcast_shape(&a.shape, &b.shape)?;
+        let (a, b) = (a.expand(&shape)?, b.expand(&shape)?);
+        match (&a.data, &b.data, int) {
+            (Data::Int(a), Data::Int(b), Some(int)) => {
+                let data = a.iter().zip(b.iter()).map(|(&a, &b)| int(a, b)).collect();
+                Ok(OnnxValue::int(data, shape))
+            }
+            _ => Ok(OnnxValue::float(
+                f(self.to_float(&a)?, self.to_float(&b)?)?,
+                shape,
+            )),
+        }
+    }
+
+    fn eval(&self, node: &NodeProto, x: &[Option<OnnxValue>]) -> Result<Vec<OnnxValue>, OnnxError> {
+        let op = node.op_type.as_str();
+        if op != "Split" && node.output.iter().skip(1).any(|o| !o.is_empty()) {
+            return Err(unsupported(node, "more than one output"));
+        }
+        let y = match op {
+            "Identity" | "Dropout" => arg(node, x, 0)?.clone(),
+            "Abs" => self.unary(arg(node, x, 0)?, |t| t.try_abs())?,
+            "Exp" => self.unary(arg(node, x, 0)?, |t| t.try_exp())?,
+            "Log" => self.unary(arg(node, x, 0)?, |t| t.try_ln())?,
+            "Sqrt" => self.unary(arg(node, x, 0)?, |t| t.try_sqrt())?,
+            "Sin" => self.unary(arg(node, x, 0)?, |t| t.try_sin())?,
+            "Cos" => self.unary(arg(node, x, 0)?, |t| t.try_cos())?,
+            "Tanh" => self.unary(arg(node, x, 0)?, |t| t.try_tanh())?,
+            "Sigmoid" => self.unary(arg(node, x, 0)?, |t| t.try_sigmoid())?,
+            "Relu" => self.unary(arg(node, x, 0)?, |t| t.try_relu())?,
+            "Neg" => self.unary(arg(node, x, 0)?, |t| t.try_negate())?,
+            "Reciprocal" => self.unary(arg(node, x, 0)?, |t| t.try_recip())?,
+            "Gelu" => match string_attr(node, "approximate", "none").as_str() {
+                "tanh" => self.unary(arg(node, x, 0)?, |t| t.try_fast_gelu())?,
+                _ => self.unary(arg(node, x, 0)?, |t| t.try_accurate_gelu())?,
+            },
+            "Erf" => {
+                // there is no erf op, so this is computed on the host
+                let x = arg(node, x, 0)?;
+                let data = x.as_vec().into_iter().map(libm::erff).collect();
+                let t = self.dev.try_tensor_from_vec(data, (x.numel(),))?;
+                OnnxValue::float(t, x.shape.clone())
+            }
+            "LeakyRelu" => {
+                let alpha = float_attr(node, "alpha", 0.01);
+                self.unary(arg(node, x, 0)?, |t| t.try_prelu(alpha))?
+            }
+            "PRelu" => {
+                let x0 = arg(node, x, 0)?;
+                let slope = arg(node, x, 1)?;
+                self.binary(x0, slope, |t, a| t.try_prelu(a), None)?
+            }
+            "Clip" => {
+                let (min, max) = if self.opset >= 11 {
+                    let bound = |i| match opt(x, i) {
+                        Some(v) if v.numel() != 1 => {
+                            Err(unsupported(node, "a min or max that is not a scalar"))
+                        }
+                        v => Ok(v.map(|v| v.as_vec()[0])),
+                    };
+                    (bound(1)?, bound(2)?)
+                } else {
+                    (
+                        attr(node, "min").map(|a| a.f),
+                        attr(node, "max").map(|a| a.f),
+                    )
+                };
+                let (min, max) = (min.unwrap_or(f32::MIN), max.unwrap_or(f32::MAX));
+                self.unary(arg(node, x, 0)?, |t| t.try_clamp(min, max))?
+            }
+            "Pow" => {
+                let exponent = arg(node, x, 1)?;
+                if exponent.numel() != 1 {
+                    return Err(unsupported(node, "an exponent that is not a scalar"));
+                }
+                let exponent = exponent.as_vec()[0];
+                self.unary(arg(node, x, 0)?, |t| t.try_powf(exponent))?
+            }
+            "IsNaN" => {
+                let x = arg(node, x, 0)?;
+                let data = x.as_vec().into_iter().map(|f| f.is_nan() as i64).collect();
+                OnnxValue::int(data, x.shape.clone())
+            }
+            "Cast" => {
+                let x = arg(node, x, 0)?;
+                match DataType::from_i32(int_attr(node, "to", 0) as i32) {
+                    Some(DataType::Float | DataType::Double | DataType::Float16) => {
+                        OnnxValue::float(self.to_float(x)?, x.shape.clone())
+                    }
+                    Some(DataType::Bool) => {
+                        let data = x.as_vec().into_iter().map(|f| (f != 0.0) as i64).collect();
+                        OnnxValue::int(data, x.shape.clone())
+                    }
+                    Some(to) if !matches!(to, DataType::String | DataType::Undefined) => {
+                        OnnxValue::int(x.to_ints(), x.shape.clone())
+                    }
+                    to => return Err(unsupported(node, &format!("to={to:?}"))),
+                }
+            }
+            "Add" => self.binary(
+                arg(node, x, 0)?,
+                arg(node, x, 1)?,
+                |a, b| a.try_add(b),
+                Some(|a, b| a + b),
+            )?,
+            "Sub" => self.binary(
+                arg(node, x, 0)?,
+                arg(node, x, 1)?,
+                |a, b| a.try_sub(b),
+                Some(|a, b| a - b),
+            )?,
+            "Mul" => self.binary(
+                arg(node, x, 0)?,
+                arg(node, x, 1)?,
+                |a, b| a.try_mul(b),
+                Some(|a, b| a * b),
+            )?,
+            "Div" => self.binary(
+                arg(node, x, 0)?,
+                arg(node, x, 1)?,
+                |a, b| a.try_div(b),
+                Some(|a, b| a / b),
+            )?,
+            "Max" | "Min" => {
+                let mut y = arg(node, x, 0)?.clone();
+                for i in 1..x.len() {
+                    let b = arg(node, x, i)?;
+                    y = match op {
+                        "Max" => self.binary(&y, b, |a, b| a.try_maximum(b), Some(i64::max))?,
+                        _ => self.binary(&y, b, |a, b| a.try_minimum(b), Some(i64::min))?,
+                    };
+                }
+                y
+            }
+            "Where" => {
+                let (cond, a, b) = (arg(node, x, 0)?, arg(node, x, 1)?, arg(node, x, 2)?);
+                let shape = broadcast_shape(&cond.shape, &broadcast_shape(&a.shape, &b.shape)?)?;
+                let cond: Vec<bool> = cond
+                    .expand(&shape)?
+                    .to_ints()
+                    .iter()
+                    .map(|&c| c != 0)
+                    .collect();
+                let (a, b) = (a.expand(&shape)?, b.expand(&shape)?);
+                match (&a.data, &b.data) {
+                    (Data::Int(a), Data::Int(b)) => {
+                        let data = (0..cond.len())
+                            .map(|i| if cond[i] { a[i] } else { b[i] })
+                            .collect();
+                        OnnxValue::int(data, shape)
+                    }
+                    _ => {
+                        let cond = self.dev.try_tensor_from_vec(cond, (a.numel(),))?;
+                        let t = cond.try_choose(self.to_float(&a)?, self.to_float(&b)?)?;
+                        OnnxValue::float(t, shape)
+                    }
+                }
+            }
+            "MatMul" => self.matmul(arg(node, x, 0)?, arg(node, x, 1)?)?,
+            "Gemm" => {
+                let transpose = |v: &OnnxValue, key: &str| match int_attr(node, key, 0) {
+                    0 => Ok(v.clone()),
+                    _ => transpose(v, &[1, 0]),
+                };
+                let a = transpose(arg(node, x, 0)?, "transA")?;
+                let b = transpose(arg(node, x, 1)?, "transB")?;
+                let mut y = self.matmul(&a, &b)?;
+                let alpha = float_attr(node, "alpha", 1.0);
+                if alpha != 1.0 {
+                    y = self.unary(&y, |t| t.try_mul(alpha))?;
+                }
+                if let Some(c) = opt(x, 2) {
+                    let beta = float_attr(node, "beta", 1.0);
+                    let c = self.unary(&c.expand(&y.shape)?, |t| t.try_mul(beta))?;
+                    y = self.binary(&y, &c, |a, b| a.try_add(b), None)?;
+                }
+                y
+            }
+            "Conv" => self.conv(node, arg(node, x, 0)?, arg(node, x, 1)?, opt(x, 2))?,
+            "MaxPool" | "AveragePool" => self.pool(node, arg(node, x, 0)?)?,
+            "GlobalAveragePool" | "GlobalMaxPool" => {
+                let x = arg(node, x, 0)?;
+                if x.shape.len() < 3 {
+                    return Err(unsupported(node, &format!("shape {:?}", x.shape)));
+                }
+                let rows = x.shape[0] * x.shape[1];
+                let cols: usize = x.shape[2..].iter().product();
+                let t = self.to_float(x)?.try_reshape_like(&(rows, cols))?;
+                let t = match op {
+                    "GlobalAveragePool" => t.try_mean::<(usize,), Axis<1>>()?,
+                    _ => t.try_max::<(usize,), Axis<1>>()?,
+                };
+                let mut shape = vec![1; x.shape.len()];
+                shape[..2].copy_from_slice(&x.shape[..2]);
+                OnnxValue::float(t, shape)
+            }
+            "ReduceSum" | "ReduceMean" | "ReduceMax" | "ReduceMin" => {
+                self.reduce(node, arg(node, x, 0)?, axes(node, x, 1))?
+            }
+            "Softmax" | "LogSoftmax" => {
+                let x = arg(node, x, 0)?;
+                let rank = x.shape.len();
+                let default = if self.opset >= 13 { -1 } else { 1 };
+                let axis = norm_axis(node, int_attr(node, "axis", default), rank)?;
+                let outer: usize = x.shape[..axis].iter().product();
+                // before opset 13, the input was coerced to 2d around the axis
+                let (n, inner) = match self.opset {
+                    v if v >= 13 => (x.shape[axis], x.shape[axis + 1..].iter().product()),
+                    _ => (x.shape[axis..].iter().product(), 1),
+                };
+                let t = self.to_float(x)?.try_reshape_like(&(outer, n, inner))?;
+                let t = match op {
+                    "Softmax" => t.try_softmax::<Axis<1>>()?,
+                    _ => t.try_log_softmax::<Axis<1>>()?,
+                };
+                OnnxValue::float(t.try_reshape_like(&(x.numel(),))?, x.shape.clone())
+            }
+            "LayerNormalization" => {
+                let x0 = arg(node, x, 0)?;
+                let axis = norm_axis(node, int_attr(node, "axis", -1), x0.shape.len())?;
+                let eps = float_attr(node, "epsilon", 1e-5);
+                let outer: usize = x0.shape[..axis].iter().product();
+                let inner: usize = x0.shape[axis..].iter().product();
+                let t = self.to_float(x0)?.try_reshape_like(&(outer, inner))?;
+                let t = t.try_normalize::<Axis<1>>(eps)?;
+                let mut y = OnnxValue::float(t.try_reshape_like(&(x0.numel(),))?, x0.shape.clone());
+                y = self.binary(&y, arg(node, x, 1)?, |a, b| a.try_mul(b), None)?;
+                if let Some(bias) = opt(x, 2) {
+                    y = self.binary(&y, bias, |a, b| a.try_add(b), None)?;
+                }
+                y
+            }
+            "BatchNormalization" => {
+                if int_attr(node, "training_mode", 0) != 0 {
+                    return Err(unsupported(node, "training_mode=1"));
+                }
+                let x0 = arg(node, x, 0)?;
+                if x0.shape.len() < 2 {
+                    return Err(unsupported(node, &format!("shape {:?}", x0.shape)));
+                }
+                let eps = float_attr(node, "epsilon", 1e-5);
+                let (scale, bias) = (arg(node, x, 1)?, arg(node, x, 2)?);
+                let (mean, var) = (arg(node, x, 3)?, arg(node, x, 4)?);
+                // y = x * scale / sqrt(var + eps) + (bias - mean * scale / sqrt(var + eps))
+                let std = self.unary(var, |t| t.try_add(eps)?.try_sqrt())?;
+                let factor = self.binary(scale, &std, |a, b| a.try_div(b), None)?;
+                let shift = self.binary(mean, &factor, |a, b| a.try_mul(b), None)?;
+                let shift = self.binary(bias, &shift, |a, b| a.try_sub(b), None)?;
+                let mut shape = vec![1; x0.shape.len() - 1];
+                shape[0] = x0.shape[1];
+                let y = self.binary(
+                    x0,
+                    &factor.reshaped(shape.clone()),
+                    |a, b| a.try_mul(b),
+                    None,
+                )?;
+                self.binary(&y, &shift.reshaped(shape), |a, b| a.try_add(b), None)?
+            }
+            "Constant" => {
+                if let Some(t) = attr(node, "value").and_then(|a| a.t.as_ref()) {
+                    tensor_value(t, &self.dev)?
+                } else if let Some(a) = attr(node, "value_float") {
+                    OnnxValue::float(self.dev.try_tensor_from_vec(vec![a.f], (1,))?, vec![])
+                } else if let Some(a) = attr(node, "value_floats") {
+                    let t = self
+                        .dev
+                        .try_tensor_from_vec(a.floats.clone(), (a.floats.len(),))?;
+                    OnnxValue::float(t, vec![a.floats.len()])
+                } else if let Some(a) = attr(node, "value_int") {
+                    OnnxValue::int(vec![a.i], vec![])
+                } else if let Some(a) = attr(node, "value_ints") {
+                    OnnxValue::int(a.ints.clone(), vec![a.ints.len()])
+                } else {
+                    return Err(unsupported(node, "a value that is not numeric"));
+                }
+            }
+            "ConstantOfShape" => {
+                let shape = to_dims(node, &arg(node, x, 0)?.to_ints())?;
+                let value = match attr(node, "value").and_then(|a| a.t.as_ref()) {
+                    Some(t) => tensor_value(t, &self.dev)?,
+                    None => {
+                        OnnxValue::float(self.dev.try_tensor_from_vec(vec![0.0], (1,))?, vec![1])
+                    }
+                };
+                value.reshaped(vec![]).expand(&shape)?
+            }
+            "Shape" => {
+                let x = arg(node, x, 0)?;
+                let rank = x.shape.len() as i64;
+                let clamp = |i: i64| (if i < 0 { i + rank } else { i }).clamp(0, rank) as usize;
+                let start = clamp(int_attr(node, "start", 0));
+                let end = clamp(int_attr(node, "end", rank)).max(start);
+                let data: Vec<i64> = x.shape[start..end].iter().map(|&d| d as i64).collect();
+                OnnxValue::int(data, vec![end - start])
+            }
+            "Reshape" => {
+                let x0 = arg(node, x, 0)?;
+                let target = arg(node, x, 1)?.to_ints();
+                let allow_zero = int_attr(node, "allowzero", 0) != 0;
+                let mut shape = Vec::with_capacity(target.len());
+                let mut inferred = None;
+                for (i, &d) in target.iter().enumerate() {
+                    shape.push(match d {
+                        0 if !allow_zero => {
+                            *x0.shape.get(i).ok_or_else(|| invalid(node, "shape"))?
+                        }
+                        -1 if inferred.is_none() => {
+                            inferred = Some(i);
+                            1
+                        }
+                        d if d >= 0 => d as usize,
+                        _ => return Err(invalid(node, &format!("shape {target:?}"))),
+                    });
+                }
+                if let Some(i) = inferred {
+                    let known: usize = shape.iter().product();
+                    shape[i] = x0.numel().checked_div(known).unwrap_or(0);
+                }
+                if shape.iter().product::<usize>() != x0.numel() {
+                    return Err(invalid(
+                        node,
+                        &format!("shape {target:?} for an input of shape {:?}", x0.shape),
+                    ));
+                }
+                x0.reshaped(shape)
+            }
+            "Flatten" => {
+                let x = arg(node, x, 0)?;
+                let rank = x.shape.len();
+                let axis = int_attr(node, "axis", 1);
+                let axis = norm_axis(node, axis, rank + 1)?;
+                let outer = x.shape[..axis].iter().product();
+                x.reshaped(vec![outer, x.numel() / outer.max(1)])
+            }
+            "Squeeze" => {
+                let x0 = arg(node, x, 0)?;
+                let rank = x0.shape.len();
+                let axes = match axes(node, x, 1) {
+                    Some(axes) => axes
+                        .into_iter()
+                        .map(|a| norm_axis(node, a, rank))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None => (0..rank).filter(|&i| x0.shape[i] == 1).collect(),
+                };
+                if axes.iter().any(|&a| x0.shape[a] != 1) {
+                    return Err(invalid(
+                        node,
+                        &format!("axes {axes:?} of shape {:?}", x0.shape),
+                    ));
+                }
+                let shape = (0..rank)
+                    .filter(|i| !axes.contains(i))
+                    .map(|i| x0.shape[i])
+                    .collect();
+                x0.reshaped(shape)
+            }
+            "Unsqueeze" => {
+                let x0 = arg(node, x, 0)?;
+                let axes = axes(node, x, 1).ok_or_else(|| invalid(node, "missing axes"))?;
+                let rank = x0.shape.len() + axes.len();
+                let mut axes = axes
+                    .into_iter()
+                    .map(|a| norm_axis(node, a, rank))
+                    .collect::<Result<Vec<_>, _>>()?;
+                axes.sort_unstable();
+                let mut shape = x0.shape.clone();
+                for a in axes {
+                    shape.insert(a, 1);
+                }
+                x0.reshaped(shape)
+            }
+            "Transpose" => {
+                let x = arg(node, x, 0)?;
+                let rank = x.shape.len();
+                let perm = match ints_attr(node, "perm") {
+                    Some(perm) => perm
+                        .iter()
+                        .map(|&a| norm_axis(node, a, rank))
+                        .collect::<Result<Vec<_>, _>>()?,
+                    None => (0..rank).rev().collect(),
+                };
+                transpose(x, &perm)?
+            }
+            "Expand" => {
+                let x0 = arg(node, x, 0)?;
+                let shape = to_dims(node, &arg(node, x, 1)?.to_ints())?;
+                x0.expand(&broadcast_shape(&x0.shape, &shape)?)?
+            }
+            "Concat" => {
+                let args = (0..x.len())
+                    .map(|i| arg(node, x, i))
+                    .collect::<Result<Vec<_>, _>>()?;
+                self.concat(node, &args)?
+            }
+            "Gather" => {
+                let (data, indices) = (arg(node, x, 0)?, arg(node, x, 1)?);
+                let axis = norm_axis(node, int_attr(node, "axis", 0), data.shape.len())?;
+                let n = data.shape[axis];
+                let outer: usize = data.shape[..axis].iter().product();
+                let inner: usize = data.shape[axis + 1..].iter().product();
+                let ids = indices
+                    .to_ints()
+                    .into_iter()
+                    .map(|i| match if i < 0 { i + n as i64 } else { i } {
+                        i if (0..n as i64).contains(&i) => Ok(i as usize),
+                        _ => Err(invalid(node, &format!("index {i} for a dimension of {n}"))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut flat = Vec::with_capacity(outer * ids.len() * inner);
+                for o in 0..outer {
+                    for &i in ids.iter() {
+                        flat.extend((0..inner).map(|j| (o * n + i) * inner + j));
+                    }
+                }
+                let mut shape = data.shape[..axis].to_vec();
+                shape.extend_from_slice(&indices.shape);
+                shape.extend_from_slice(&data.shape[axis + 1..]);
+                data.take(flat, shape)?
+            }
+            "Slice" => self.slice(node, x)?,
+            "Split" => return self.split(node, x),
+            _ => return Err(unsupported(node, "this op")),
+        };
+        Ok(vec![y])
+    }
+
+    /// Numpy style matmul, where the batch dimensions are broadcasted, and vectors get
+    /// a dimension of 1 for the duration of the op.
+    fn matmul(&self, a: &OnnxValue, b: &OnnxValue) -> Result<OnnxValue, OnnxError> {
+        let (mut a_shape, mut b_shape) = (a.shape.clone(), b.shape.clone());
+        if a_shape.is_empty() || b_shape.is_empty() {
+            return Err(OnnxError::Invalid("MatMul of a scalar".to_string()));
+        }
+        let (a_vec, b_vec) = (a_shape.len() == 1, b_shape.len() == 1);
+        if a_vec {
+            a_shape.insert(0, 1);
+        }
+        if b_vec {
+            b_shape.push(1);
+        }
+        let (m, k) = (a_shape[a_shape.len() - 2], a_shape[a_shape.len() - 1]);
+        let (k2, n) = (b_shape[b_shape.len() - 2], b_shape[b_shape.len() - 1]);
+        if k != k2 {
+            return Err(OnnxError::Invalid(format!(
+                "MatMul of shapes {:?} and {:?}",
+                a.shape, b.shape
+            )));
+        }
+        let mut shape =
+            broadcast_shape(&a_shape[..a_shape.len() - 2], &b_shape[..b_shape.len() - 2])?;
+        let batch: usize = shape.iter().product();
+
+        let t = if b_shape.len() == 2 {
+            // the batch dimensions of a can be merged with its rows
+            let a = self.to_float(a)?.try_reshape_like(&(batch * m, k))?;
+            let b = self.to_float(b)?.try_reshape_like(&(k, n))?;
+            a.try_matmul(b)?.try_reshape_like(&(batch * m * n,))?
+        } else {
+            let mut a_batched = shape.clone();
+            a_batched.extend([m, k]);
+            let mut b_batched = shape.clone();
+            b_batched.extend([k, n]);
+            let a = a.reshaped(a_shape).expand(&a_batched)?;
+            let b = b.reshaped(b_shape).expand(&b_batched)?;
+            let a = self.to_float(&a)?.try_reshape_like(&(batch, m, k))?;
+            let b = self.to_float(&b)?.try_reshape_like(&(batch, k, n))?;
+            a.try_matmul(b)?.try_reshape_like(&(batch * m * n,))?
+        };
+        if !a_vec {
+            shape.push(m);
+        }
+        if !b_vec {
+            shape.push(n);
+        }
+        Ok(OnnxValue::float(t, shape))
+    }
+
+    fn conv(
+        &self,
+        node: &NodeProto,
+        x: &OnnxValue,
+        w: &OnnxValue,
+        bias: Option<&OnnxValue>,
+    ) -> Result<OnnxValue, OnnxError> {
+        let spatial = x.shape.len().saturating_sub(2);
+        if !(1..=3).contains(&spatial) || w.shape.len() != x.shape.len() {
+            return Err(unsupported(node, &format!("shape {:?}", x.shape)));
+        }
+        if !matches!(
+            string_attr(node, "auto_pad", "NOTSET").as_str(),
+            "NOTSET" | "VALID"
+        ) {
+            return Err(unsupported(node, "auto_pad"));
+        }
+        let stride = uniform_attr(node, "strides", 1, 1)?;
+        let padding = uniform_attr(node, "pads", 0, 0)?;
+        let dilation = uniform_attr(node, "dilations", 1, 1)?;
+        let groups = match int_attr(node, "group", 1) {
+            g if g >= 1 => g as usize,
+            g => return Err(invalid(node, &format!("group={g}"))),
+        };
+        let (batch, chan) = (x.shape[0], x.shape[1]);
+        let (out_chan, kernel) = (w.shape[0], w.shape[2]);
+        if w.shape[2..].iter().any(|&k| k != kernel) {
+            return Err(unsupported(
+                node,
+                &format!("kernel of shape {:?}", &w.shape[2..]),
+            ));
+        }
+        if kernel == 0 || Some(chan) != w.shape[1].checked_mul(groups) || out_chan % groups != 0 {
+            return Err(invalid(
+                node,
+                &format!("input of shape {:?} with weight {:?}", x.shape, w.shape),
+            ));
+        }
+        check_window(node, &x.shape, kernel, padding, dilation)?;
+
+        let (t, shape) = match spatial {
+            1 => {
+                let img = self
+                    .to_float(x)?
+                    .try_reshape_like(&(batch, chan, x.shape[2]))?;
+                let w = self
+                    .to_float(w)?
+                    .try_reshape_like(&(out_chan, w.shape[1], kernel))?;
+                let y = (img, w).try_conv1d(stride, padding, dilation, groups)?;
+                let shape = y.shape.concrete().to_vec();
+                (y.try_reshape_like(&(shape.iter().product(),))?, shape)
+            }
+            2 => {
+                let img = self
+                    .to_float(x)?
+                    .try_reshape_like(&(batch, chan, x.shape[2], x.shape[3]))?;
+                let w = self
+                    .to_float(w)?
+                    .try_reshape_like(&(out_chan, w.shape[1], kernel, kernel))?;
+                let y = (img, w).try_conv2d(stride, padding, dilation, groups)?;
+                let shape = y.shape.concrete().to_vec();
+                (y.try_reshape_like(&(shape.iter().product(),))?, shape)
+            }
+            _ => {
+                let img = self
+                    .to_float(x)?
+                    .try_reshape_like(&(batch, chan, x.shape[2], x.shape[3], x.shape[4]))?;
+                let w = self
+                    .to_float(w)?
+                    .try_reshape_like(&(out_chan, w.shape[1], kernel, kernel, kernel))?;
+                let y = (img, w).try_conv3d(stride, padding, dilation, groups)?;
+                let shape = y.shape.concrete().to_vec();
+                (y.try_reshape_like(&(shape.iter().product(),))?, shape)
+            }
+        };
+        let y = OnnxValue::float(t, shape);
+        match bias {
+            Some(bias) => {
+                let mut bias_shape = vec![1; spatial + 1];
+                bias_shape[0] = out_chan;
+                self.binary(&y, &bias.reshaped(bias_shape), |a, b| a.try_add(b), None)
+            }
+            None => Ok(y),
+        }
+    }
+
+    fn pool(&self, node: &NodeProto, x: &OnnxValue) -> Result<OnnxValue, OnnxError> {
+        if !(3..=5).contains(&x.shape.len()) {
+            return Err(unsupported(node, &format!("shape {:?}", x.shape)));
+        }
+        if !matches!(
+            string_attr(node, "auto_pad", "NOTSET").as_str(),
+            "NOTSET" | "VALID"
+        ) {
+            return Err(unsupported(node, "auto_pad"));
+        }
+        if int_attr(node, "ceil_mode", 0) != 0 {
+            return Err(unsupported(node, "ceil_mode=1"));
+        }
+        let kernel = uniform_attr(node, "kernel_shape", 1, 1)?;
+        let stride = uniform_attr(node, "strides", 1, 1)?;
+        let padding = uniform_attr(node, "pads", 0, 0)?;
+        let dilation = uniform_attr(node, "dilations", 1, 1)?;
+        check_window(node, &x.shape, kernel, padding, dilation)?;
+        let kind = match node.op_type.as_str() {
+            "MaxPool" => Pool2DKind::Max,
+            _ if padding > 0 && int_attr(node, "count_include_pad", 0) == 0 => {
+                return Err(unsupported(node, "count_include_pad=0"))
+            }
+            _ => Pool2DKind::Avg,
+        };
+        let s = &x.shape;
+        let t = self.to_float(x)?;
+        let (t, shape) = match s.len() {
+            3 => {
+                let seq = t.try_reshape_like(&(s[0], s[1], s[2]))?;
+                let y = seq.try_pool1d(kind, kernel, stride, padding, dilation)?;
+                let shape = y.shape.concrete().to_vec();
+                (y.try_reshape_like(&(shape.iter().product(),))?, shape)
+            }
+            4 => {
+                let img = t.try_reshape_like(&(s[0], s[1], s[2], s[3]))?;
+                let y = img.try_pool2d(kind, kernel, stride, padding, dilation)?;
+                let shape = y.shape.concrete().to_vec();
+                (y.try_reshape_like(&(shape.iter().product(),))?, shape)
+            }
+            _ => {
+                let vol = t.try_reshape_like(&(s[0], s[1], s[2], s[3], s[4]))?;
+                let y = vol.try_pool3d(kind, kernel, stride, padding, dilation)?;
+                let shape = y.shape.concrete().to_vec();
+                (y.try_reshape_like(&(shape.iter().product(),))?, shape)
+            }
+        };
+        Ok(OnnxValue::float(t, shape))
+    }
+
+    /// Reduces one axis at a time, keeping it as a dimension of 1 until the end.
+    fn reduce(
+        &self,
+        node: &NodeProto,
+        x: &OnnxValue,
+        axes: Option<Vec<i64>>,
+    ) -> Result<OnnxValue, OnnxError> {
+        let rank = x.shape.len();
+        let mut axes = match axes {
+            Some(axes) if !axes.is_empty() => axes
+                .into_iter()
+                .map(|a| norm_axis(node, a, rank))
+                .collect::<Result<Vec<_>, _>>()?,
+            _ if int_attr(node, "noop_with_empty_axes", 0) != 0 => return Ok(x.clone()),
+            _ => (0..rank).collect(),
+        };
+        axes.sort_unstable();
+        axes.dedup();
+
+        let mut shape = x.shape.clone();
+        let mut t = self.to_float(x)?;
+        for &axis in axes.iter() {
+            let outer = shape[..axis].iter().product();
+            let inner = shape[axis + 1..].iter().product();
+            let t3 = t.try_reshape_like(&(outer, shape[axis], inner))?;
+            let t2 = match node.op_type.as_str() {
+                "ReduceSum" => t3.try_sum::<(usize, usize), Axis<1>>()?,
+                "ReduceMean" => t3.try_mean::<(usize, usize), Axis<1>>()?,
+                "ReduceMax" => t3.try_max::<(usize, usize), Axis<1>>()?,
+                _ => t3.try_min::<(usize, usize), Axis<1>>()?,
+            };
+            t = t2.try_reshape_like(&(outer * inner,))?;
+            shape[axis] = 1;
+        }
+        if int_attr(node, "keepdims", 1) == 0 {
+            shape = (0..rank)
+                .filter(|i| !axes.contains(i))
+                .map(|i| shape[i])
+                .collect();
+        }
+        Ok(OnnxValue::float(t, shape))
+    }
+
+    fn concat(&self, node: &NodeProto, args: &[&OnnxValue]) -> Result<OnnxValue, OnnxError> {
+        let first = args.first().ok_or_else(|| invalid(node, "no inputs"))?;
+        let rank = first.shape.len();
+        let axis = norm_axis(node, int_attr(node, "axis", 0), rank)?;
+        let mut shape = first.shape.clone();
+        shape[axis] = 0;
+        for a in args.iter() {
+            let same = a.shape.len() == rank
+                && (0..rank).all(|i| i == axis || a.shape[i] == first.shape[i]);
+            if !same {
+                return Err(invalid(
+                    node,
+                    &format!("inputs of shapes {:?} and {:?}", first.shape, a.shape),
+                ));
+            }
+            shape[axis] += a.shape[axis];
+        }
+        let outer: usize = shape[..axis].iter().product();
+        let inner: usize = shape[axis + 1..].iter().product();
+
+        if args.iter().all(|a| !a.is_float()) {
+            let mut data = Vec::with_capacity(outer * shape[axis] * inner);
+            for o in 0..outer {
+                for a in args.iter() {
+                    let len = a.shape[axis] * inner;
+                    data.extend_from_slice(&a.to_ints()[o * len..(o + 1) * len]);
+                }
+            }
+            return Ok(OnnxValue::int(data, shape));
+        }
+        let mut t = self
+            .to_float(first)?
+            .try_reshape_like(&(outer, first.shape[axis], inner))?;
+        for a in args[1..].iter() {
+            let b = self
+                .to_float(a)?
+                .try_reshape_like(&(outer, a.shape[axis], inner))?;
+            t = (t, b).try_concat_tensor_along(Axis::<1>)?;
+        }
+        let numel = shape.iter().product();
+        Ok(OnnxValue::float(t.try_reshape_like(&(numel,))?, shape))
+    }
+
+    fn slice(&self, node: &NodeProto, x: &[Option<OnnxValue>]) -> Result<OnnxValue, OnnxError> {
+        let data = arg(node, x, 0)?;
+        let (starts, ends, axes, steps) = if self.opset >= 10 {
+            let ints = |i| opt(x, i).map(|v: &OnnxValue| v.to_ints());
+            (
+                arg(node, x, 1)?.to_ints(),
+                arg(node, x, 2)?.to_ints(),
+                ints(3),
+                ints(4),
+            )
+        } else {
+            let ints = |key| ints_attr(node, key).ok_or_else(|| invalid(node, key));
+            (
+                ints("starts")?,
+                ints("ends")?,
+                ints_attr(node, "axes"),
+                None,
+            )
+        };
+        let rank = data.shape.len();
+        let strides: Vec<isize> = contiguous_strides(&data.shape)
+            .into_iter()
+            .map(|s| s as isize)
+            .collect();
+        let mut view_strides = strides.clone();
+        let mut shape = data.shape.clone();
+        let mut offset = 0;
+        for (i, (&start, &end)) in starts.iter().zip(ends.iter()).enumerate() {
+            let axis = match &axes {
+                Some(axes) => norm_axis(node, axes[i], rank)?,
+                None => i,
+            };
+            let step = steps.as_ref().map_or(1, |s| s[i]);
+            if step == 0 {
+                return Err(invalid(node, "a step of 0"));
+            }
+            let dim = data.shape[axis] as i64;
+            let wrap = |i: i64| if i < 0 { i + dim } else { i };
+            let (start, len) = if step > 0 {
+                let (start, end) = (wrap(start).clamp(0, dim), wrap(end).clamp(0, dim));
+                (start, (end - start + step - 1) / step)
+            } else {
+                let (start, end) = (wrap(start).clamp(0, dim - 1), wrap(end).clamp(-1, dim - 1));
+                (start, (start - end - step - 1) / -step)
+            };
+            shape[axis] = len.max(0) as usize;
+            offset += start as isize * strides[axis];
+            view_strides[axis] = strides[axis] * step as isize;
+        }
+        data.view(shape, &view_strides, offset)
+    }
+
+    fn split(
+        &self,
+        node: &NodeProto,
+        x: &[Option<OnnxValue>],
+    ) -> Result<Vec<OnnxValue>, OnnxError> {
+        let data = arg(node, x, 0)?;
+        let axis = norm_axis(node, int_attr(node, "axis", 0), data.shape.len())?;
+        let dim = data.shape[axis];
+        let sizes = match opt(x, 1)
+            .map(|v| v.to_ints())
+            .or_else(|| ints_attr(node, "split"))
+        {
+            Some(sizes) => match sizes.iter().map(|&s| usize::try_from(s)).collect() {
+                Ok(sizes) => sizes,
+                Err(_) => return Err(invalid(node, &format!("split {sizes:?}"))),
+            },
+            None => {
+                // equal parts, with a smaller last one if it doesn't divide evenly
+                let parts = node.output.len();
+                let size = (dim + parts - 1) / parts;
+                (0..parts)
+                    .map(|i| size.min(dim.saturating_sub(i * size)))
+                    .collect::<Vec<_>>()
+            }
+        };
+        if sizes.iter().try_fold(0usize, |n, &s| n.checked_add(s)) != Some(dim) {
+            return Err(invalid(
+                node,
+                &format!("split {sizes:?} of a dimension of {dim}"),
+            ));
+        }
+        let mut start = 0;
+        let mut outputs = Vec::with_capacity(sizes.len());
+        for size in sizes {
+            outputs.push(data.narrow(axis, start, size)?);
+            start += size;
+        }
+        Ok(outputs)
+    }
+}
+
+/// Loads the data of an initializer or constant.
+fn tensor_value(t: &TensorProto, dev: &Cpu) -> Result<OnnxValue, OnnxError> {
+    if t.data_location == 1 {
+        return Err(OnnxError::Unsupported(format!(
+            "`{}` has its data in an external file",
+            t.name
+        )));
+    }
+    let shape: Vec<usize> = match t.dims.iter().map(|&d| usize::try_from(d)).collect() {
+        Ok(shape) => shape,
+        Err(_) => {
+            return Err(OnnxError::Invalid(format!(
+                "`{}` has a negative dimension in {:?}",
+                t.name, t.dims
+            )))
+        }
+    };
+    if checked_numel(&shape).is_none() {
+        return Err(OnnxError::Invalid(format!(
+            "`{}` has too many elements for its shape {shape:?}",
+            t.name
+        )));
+    }
+    let raw = t.raw_data.as_slice();
+    let data_type = DataType::from_i32(t.data_type).unwrap_or(DataType::Undefined);
+    let value = match data_type {
+        DataType::Float => {
+            let data = match raw.is_empty() {
+                true => t.float_data.clone(),
+                false => from_raw(raw, f32::from_le_bytes),
+            };
+            OnnxValue::float(dev.try_tensor_from_vec(data.clone(), (data.len(),))?, shape)
+        }
+        DataType::Double => {
+            let data: Vec<f32> = match raw.is_empty() {
+                true => t.double_data.iter().map(|&f| f as f32).collect(),
+                false => from_raw(raw, |b| f64::from_le_bytes(b) as f32),
+            };
+            OnnxValue::float(dev.try_tensor_from_vec(data.clone(), (data.len(),))?, shape)
+        }
+        DataType::Int64 => match raw.is_empty() {
+            true => OnnxValue::int(t.int64_data.clone(), shape),
+            false => OnnxValue::int(from_raw(raw, i64::from_le_bytes), shape),
+        },
+        DataType::Uint32 | DataType::Uint64 if raw.is_empty() => {
+            OnnxValue::int(t.uint64_data.iter().map(|&i| i as i64).collect(), shape)
+        }
+        DataType::Uint64 => OnnxValue::int(from_raw(raw, |b| u64::from_le_bytes(b) as i64), shape),
+        DataType::Uint32 => OnnxValue::int(from_raw(raw, |b| u32::from_le_bytes(b) as i64), shape),
+        DataType::Int32
+        | DataType::Int16
+        | DataType::Uint16
+        | DataType::Int8
+        | DataType::Uint8
+        | DataType::Bool
+            if raw.is_empty() =>
+        {
+            OnnxValue::int(t.int32_data.iter().map(|&i| i as i64).collect(), shape)
+        }
+        DataType::Int32 => OnnxValue::int(from_raw(raw, |b| i32::from_le_bytes(b) as i64), shape),
+        DataType::Int16 => OnnxValue::int(from_raw(raw, |b| i16::from_le_bytes(b) as i64), shape),
+        DataType::Uint16 => OnnxValue::int(from_raw(raw, |b| u16::from_le_bytes(b) as i64), shape),
+        DataType::Int8 => OnnxValue::int(from_raw(raw, |b| i8::from_le_bytes(b) as i64), shape),
+        DataType::Uint8 | DataType::Bool => {
+            OnnxValue::int(from_raw(raw, |b| u8::from_le_bytes(b) as i64), shape)
+        }
+        dtype => {
+            return Err(OnnxError::Unsupported(format!(
+                "`{}` with data type {dtype:?}",
+                t.name
+            )))
+        }
+    };
+    let numel = match &value.data {
+        Data::Float(t) => t.shape.0,
+        Data::Int(v) => v.len(),
+    };
+    if numel != value.numel() {
+        return Err(OnnxError::Invalid(format!(
+            "`{}` has {numel} elements, but its shape is {:?}",
+            t.name, value.shape
+        )));
+    }
+    Ok(value)
+}
+
+fn from_raw<const N: usize, T>(raw: &[u8], f: fn([u8; N]) -> T) -> Vec<T> {
+    raw.chunks_exact(N)
+        .map(|b| f(b.try_into().unwrap()))
+        .collect()
+}
+
+fn is_float(data_type: i32) -> bool {
+    matches!(
+        DataType::from_i32(data_type),
+        Some(DataType::Float | DataType::Double | DataType::Float16 | DataType::Bfloat16)
+    )
+}
+
+/// The indices into a contiguous buffer of the elements of a strided view of it.
+fn view_indices(shape: &[usize], strides: &[isize], offset: isize) -> Vec<usize> {
+    let numel = shape.iter().product();
+    let mut indices = Vec::with_capacity(numel);
+    let mut index = vec![0; shape.len()];
+    for _ in 0..numel {
+        let i: isize = index
+            .iter()
+            .zip(strides)
+            .map(|(&i, &s)| i as isize * s)
+            .sum();
+        indices.push((offset + i) as usize);
+        for d in (0..shape.len()).rev() {
+            index[d] += 1;
+            if index[d] < shape[d] {
+                break;
+            }
+            index[d] = 0;
+        }
+    }
+    indices
+}
+
+fn broadcast_shape(a: &[usize], b: &[usize]) -> Result<Vec<usize>, OnnxError> {
+    let rank = a.len().max(b.len());
+    let dim = |s: &[usize], i: usize| match (i + s.len()).checked_sub(rank) {
+        Some(j) => s[j],
+        None => 1,
+    };
+    (0..rank)
+        .map(|i| match (dim(a, i), dim(b, i)) {
+            (x, y) if x == y || y == 1 => Ok(x),
+            (1, y) => Ok(y),
+            _ => Err(OnnxError::Invalid(format!(
+                "shapes {a:?} and {b:?} can't be broadcasted together"
+            ))),
+        })
+        .collect()
+}
+
+fn transpose(x: &OnnxValue, perm: &[usize]) -> Result<OnnxValue, OnnxError> {
+    let strides = contiguous_strides(&x.shape);
+    let shape = perm.iter().map(|&p| x.shape[p]).collect();
+    let strides: Vec<isize> = perm.iter().map(|&p| strides[p] as isize).collect();
+    x.view(shape, &strides, 0)
+}
+
+fn to_dims(node: &NodeProto, ints: &[i64]) -> Result<Vec<usize>, OnnxError> {
+    ints.iter()
+        .map(|&d| usize::try_from(d).map_err(|_| invalid(node, &format!("shape {ints:?}"))))
+        .collect()
+}
+
+fn norm_axis(node: &NodeProto, axis: i64, rank: usize) -> Result<usize, OnnxError> {
+    let rank = rank as i64;
+    match if axis < 0 { axis + rank } else { axis } {
+        a if (0..rank).contains(&a) => Ok(a as usize),
+        _ => Err(invalid(node, &format!("axis {axis} for rank {rank}"))),
+    }
+}
+
+/// The axes of a reduction or (un)squeeze, which moved from an attribute to an input.
+fn axes(node: &NodeProto, x: &[Option<OnnxValue>], i: usize) -> Option<Vec<i64>> {
+    opt(x, i)
+        .map(|v| v.to_ints())
+        .or_else(|| ints_attr(node, "axes"))
+}
+
+fn arg<'a>(
+    node: &NodeProto,
+    x: &'a [Option<OnnxValue>],
+    i: usize,
+) -> Result<&'a OnnxValue, OnnxError> {
+    opt(x, i).ok_or_else(|| invalid(node, &format!("missing input {i}")))
+}
+
+fn opt(x: &[Option<OnnxValue>], i: usize) -> Option<&OnnxValue> {
+    x.get(i).and_then(|v| v.as_ref())
+}
+
+fn unsupported(node: &NodeProto, what: &str) -> OnnxError {
+    OnnxError::Unsupported(format!("{} `{}` with {what}", node.op_type, node.name))
+}
+
+fn invalid(node: &NodeProto, what: &str) -> OnnxError {
+    OnnxError::Invalid(format!("{} `{}` has {what}", node.op_type, node.name))
+}
+
+fn attr<'a>(node: &'a NodeProto, key: &str) -> Option<&'a AttributeProto> {
+    node.attribute.iter().find(|a| a.name == key)
+}
+
+fn int_attr(node: &NodeProto, key: &str, default: i64) -> i64 {
+    attr(node, key).map_or(default, |a| a.i)
+}
+
+fn float_attr(node: &NodeProto, key: &str, default: f32) -> f32 {
+    attr(node, key).map_or(default, |a| a.f)
+}
+
+fn ints_attr(node: &NodeProto, key: &str) -> Option<Vec<i64>> {
+    attr(node, key).map(|a| a.ints.clone())
+}
+
+fn string_attr(node: &NodeProto, key: &str, default: &str) -> String {
+    attr(node, key).map_or(default.to_string(), |a| {
+        String::from_utf8_lossy(&a.s).into_owned()
+    })
+}
+
+/// An attribute like `strides` or `pads`, which dfdx only supports with the same value
+/// for every dimension. The value has to be at least `min`.
+fn uniform_attr(
+    node: &NodeProto,
+    key: &str,
+    default: usize,
+    min: usize,
+) -> Result<usize, OnnxError> {
+    let value = match ints_attr(node, key) {
+        Some(v) if v.iter().any(|&i| i != v[0]) => {
+            return Err(unsupported(node, &format!("{key}={v:?}")))
+        }
+        Some(v) if !v.is_empty() => v[0],
+        _ => return Ok(default),
+    };
+    match usize::try_from(value) {
+        Ok(v) if v >= min => Ok(v),
+        _ => Err(invalid(node, &format!("{key}={value}"))),
+    }
+}
+
+/// Checks that a window of `kernel` with `dilation` fits in every spatial dimension of
+/// `shape` once it's padded, so the output has at least one element per dimension.
+fn check_window(
+    node: &NodeProto,
+    shape: &[usize],
+    kernel: usize,
+    padding: usize,
+    dilation: usize,
+) -> Result<(), OnnxError> {
+    let window = (kernel - 1)
+        .checked_mul(dilation)
+        .and_then(|w| w.checked_add(1));
+    for &dim in shape[2..].iter() {
+        let padded = padding.checked_mul(2).and_then(|p| p.checked_add(dim));
+        match (window, padded) {
+            (Some(window), Some(padded)) if window <= padded => {}
+            _ => {
+                let what = format!("a kernel of {kernel} that doesn't fit in a shape of {shape:?}");
+                return Err(invalid(node, &what));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The number of elements of `shape`, or `None` if it overflows.
+fn checked_numel(shape: &[usize]) -> Option<usize> {
+    shape.iter().try_fold(1usize, |n, &d| n.checked_mul(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor::*};
+
+    fn node(
+        op: &str,
+        inputs: &[&str],
+        outputs: &[&str],
+        attribute: Vec<AttributeProto>,
+    ) -> NodeProto {
+        NodeProto {
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: outputs.iter().map(|s| s.to_string()).collect(),
+            op_type: op.into(),
+            attribute,
+            ..Default::default()
+        }
+    }
+
+    fn floats(name: &str, dims: &[i64], float_data: Vec<f32>) -> TensorProto {
+        TensorProto {
+            name: name.into(),
+            dims: dims.to_vec(),
+            data_type: DataType::Float as i32,
+            float_data,
+            ..Default::default()
+        }
+    }
+
+    fn ints(name: &str, data: Vec<i64>) -> TensorProto {
+        TensorProto {
+            name: name.into(),
+            dims: vec![data.len() as i64],
+            data_type: DataType::Int64 as i32,
+            raw_data: data.iter().flat_map(|i| i.to_le_bytes()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn model(
+        opset: i64,
+        node: Vec<NodeProto>,
+        initializer: Vec<TensorProto>,
+        x: &[usize],
+    ) -> ModelProto {
+        ModelProto {
+            ir_version: IR_VERSION,
+            opset_import: vec![OperatorSetIdProto {
+                domain: String::new(),
+                version: opset,
+            }],
+            graph: Some(GraphProto {
+                node,
+                initializer,
+                input: vec![ValueInfoProto::tensor("x", DataType::Float, x)],
+                output: vec![ValueInfoProto::tensor("y", DataType::Float, &[])],
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn run(model: &ModelProto, x: OnnxValue) -> OnnxValue {
+        let dev: Cpu = Default::default();
+        let model = OnnxModel::from_proto(model, &dev).unwrap();
+        model.run(vec![x]).unwrap().remove(0)
+    }
+
+    #[test]
+    fn test_import_gemm() {
+        let dev: Cpu = Default::default();
+        let gemm = node(
+            "Gemm",
+            &["x", "w", "c"],
+            &["y"],
+            vec![
+                AttributeProto::int("transB", 1),
+                AttributeProto::float("alpha", 2.0),
+                AttributeProto::float("beta", 0.5),
+            ],
+        );
+        let w = floats("w", &[3, 2], vec![1.0, 0.0, 0.0, 1.0, 1.0, 1.0]);
+        let c = floats("c", &[3], vec![2.0, 4.0, 6.0]);
+        let m = model(OPSET_VERSION, vec![gemm], vec![w, c], &[2, 2]);
+        let y = run(&m, dev.tensor([[1.0f32, 2.0], [3.0, 4.0]]).into());
+        assert_eq!(y.shape(), &[2, 3]);
+        assert_eq!(
+            y.to_tensor(Rank2::<2, 3>::default()).unwrap().array(),
+            [[3.0, 6.0, 9.0], [7.0, 10.0, 17.0]]
+        );
+    }
+
+    #[test]
+    fn test_import_shape_ops() {
+        let dev: Cpu = Default::default();
+        let nodes = vec![
+            node("Shape", &["x"], &["s"], vec![]),
+            node("Gather", &["s", "zero"], &["rows"], vec![]),
+            node(
+                "Concat",
+                &["rows", "minus_one"],
+                &["shape"],
+                vec![AttributeProto::int("axis", 0)],
+            ),
+            node("Reshape", &["x", "shape"], &["flat"], vec![]),
+            node(
+                "Slice",
+                &["flat", "start", "end", "axis", "step"],
+                &["sliced"],
+                vec![],
+            ),
+            node(
+                "Split",
+                &["sliced"],
+                &["a", "b"],
+                vec![AttributeProto::int("axis", 1)],
+            ),
+            node(
+                "Concat",
+                &["b", "a"],
+                &["y"],
+                vec![AttributeProto::int("axis", 1)],
+            ),
+        ];
+        let inits = vec![
+            ints("zero", vec![0]),
+            ints("minus_one", vec![-1]),
+            ints("start", vec![-1]),
+            ints("end", vec![i64::MIN]),
+            ints("axis", vec![1]),
+            ints("step", vec![-1]),
+        ];
+        let m = model(OPSET_VERSION, nodes, inits, &[2, 2, 2]);
+        let x = dev.tensor([[[0.0f32, 1.0], [2.0, 3.0]], [[4.0, 5.0], [6.0, 7.0]]]);
+        let y = run(&m, x.into());
+        // reshaped to [2, 4], reversed along the columns, and the halves swapped
+        assert_eq!(y.shape(), &[2, 4]);
+        assert_eq!(y.as_vec(), [1.0, 0.0, 3.0, 2.0, 5.0, 4.0, 7.0, 6.0]);
+    }
+
+    #[test]
+    fn test_import_softmax_opsets() {
+        let dev: Cpu = Default::default();
+        let x: Tensor<Rank2<2, 2>, f32, _> = dev.tensor([[0.0, 1.0], [2.0, 3.0]]);
+        let softmax = |opset, axis| {
+            let attrs = vec![AttributeProto::int("axis", axis)];
+            let m = model(
+                opset,
+                vec![node("Softmax", &["x"], &["y"], attrs)],
+                vec![],
+                &[2, 2],
+            );
+            run(&m, x.clone().into()).as_vec()
+        };
+        let expected = x.clone().softmax::<Axis<0>>().as_vec();
+        assert_eq!(softmax(OPSET_VERSION, 0), expected);
+        // before opset 13 the axis is flattened with all the dimensions after it
+        let expected = x.clone().reshape::<Rank1<4>>().softmax().as_vec();
+        assert_eq!(softmax(11, 0), expected);
+    }
+
+    #[test]
+    fn test_import_unsupported_ops() {
+        let dev: Cpu = Default::default();
+        let nodes = vec![
+            node("Relu", &["x"], &["a"], vec![]),
+            node("Celu", &["a"], &["b"], vec![]),
+            NodeProto {
+                domain: "com.microsoft".into(),
+                ..node("FusedMatMul", &["b", "b"], &["c"], vec![])
+            },
+            node("Celu", &["c"], &["y"], vec![]),
+        ];
+        let m = model(OPSET_VERSION, nodes, vec![], &[2]);
+        match OnnxModel::from_proto(&m, &dev) {
+            Err(OnnxError::UnsupportedOps(ops)) => {
+                assert_eq!(ops, ["Celu", "com.microsoft.FusedMatMul"])
+            }
+            r => panic!("{r:?}"),
+        }
+
+        // attributes dfdx doesn't support are reported when the op runs
+        let pool = node(
+            "MaxPool",
+            &["x"],
+            &["y"],
+            vec![AttributeProto::ints("kernel_shape", vec![2, 3])],
+        );
+        let m = model(OPSET_VERSION, vec![pool], vec![], &[1, 1, 4, 4]);
+        let model = OnnxModel::from_proto(&m, &dev).unwrap();
+        let x: Tensor<Rank4<1, 1, 4, 4>, f32, _> = dev.zeros();
+        let err = model.run(vec![x.into()]).unwrap_err();
+        assert!(matches!(err, OnnxError::Unsupported(_)), "{err}");
+    }
+
+    #[test]
+    fn test_import_malformed() {
+        let dev: Cpu = Default::default();
+        let x: Tensor<Rank4<1, 1, 4, 4>, f32, _> = dev.zeros();
+        let run_err = |m: &ModelProto| {
+            let model = OnnxModel::from_proto(m, &dev).unwrap();
+            model.run(vec![x.clone().into()]).unwrap_err()
+        };
+
+        for attr in [
+            AttributeProto::ints("strides", vec![0, 0]),
+            AttributeProto::ints("dilations", vec![-1, -1]),
+            AttributeProto::ints("pads", vec![-1, -1, -1, -1]),
+            AttributeProto::ints("kernel_shape", vec![5, 5]),
+        ] {
+            let mut attrs = vec![AttributeProto::ints("kernel_shape", vec![2, 2])];
+            attrs.retain(|a| a.name != attr.name);
+            attrs.push(attr);
+            let pool = node("MaxPool", &["x"], &["y"], attrs);
+            let m = model(OPSET_VERSION, vec![pool], vec![], &[1, 1, 4, 4]);
+            let err = run_err(&m);
+            assert!(matches!(err, OnnxError::Invalid(_)), "{err}");
+        }
+
+        let conv = node(
+            "Conv",
+            &["x", "w"],
+            &["y"],
+            vec![AttributeProto::int("group", 0)],
+        );
+        let w = floats("w", &[1, 1, 2, 2], vec![0.0; 4]);
+        let m = model(OPSET_VERSION, vec![conv], vec![w], &[1, 1, 4, 4]);
+        let err = run_err(&m);
+        assert!(matches!(err, OnnxError::Invalid(_)), "{err}");
+
+        for dims in [vec![-1, 4], vec![i64::MAX, 4]] {
+            let c = floats("c", &dims, vec![]);
+            let add = node("Add", &["x", "c"], &["y"], vec![]);
+            let m = model(OPSET_VERSION, vec![add], vec![c], &[1, 1, 4, 4]);
+            let err = OnnxModel::from_proto(&m, &dev).unwrap_err();
+            assert!(matches!(err, OnnxError::Invalid(_)), "{err}");
+        }
+    }
+}
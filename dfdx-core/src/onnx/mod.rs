@@ -0,0 +1,124 @@
+//! Exporting models to [ONNX](https://onnx.ai), so they can be deployed with ONNX Runtime
+//! and friends, and running ONNX models with dfdx.
+//!
+//! A model is exported by running its forward pass on an example input with a
+//! [crate::tensor::CaptureTape], and turning the recorded [crate::tensor::OpGraph] into
+//! ONNX ops. The parameters are embedded as initializers, named after their location in
+//! the model like with `SaveSafeTensors`. See [crate::nn_traits::SaveOnnx].
+//!
+//! Shapes are fixed to the ones of the example input, and the ops use opset
+//! [proto::OPSET_VERSION].
+//!
+//! The other way around, an [OnnxModel] runs the graph of an ONNX file on the
+//! [crate::tensor::Cpu] for inference, see [SUPPORTED_OPS] for the ops it knows.
+
+mod export;
+mod import;
+pub mod proto;
+
+use std::{string::String, vec::Vec};
+
+use crate::{
+    shapes::{Shape, Unit},
+    tensor::{CaptureTape, Error, Storage, Tensor, TensorRef},
+};
+
+pub(crate) use export::try_export;
+pub use import::{OnnxModel, OnnxValue, SUPPORTED_OPS};
+pub use prost;
+
+#[derive(Debug)]
+pub enum OnnxError {
+    IoError(std::io::Error),
+    DecodeError(prost::DecodeError),
+    TensorError(Error),
+    /// The model ran an op that can't be expressed in ONNX yet, or an [OnnxModel] ran an
+    /// op with attributes dfdx doesn't support.
+    Unsupported(String),
+    /// The ops of a graph that an [OnnxModel] can't run.
+    UnsupportedOps(Vec<String>),
+    /// The graph or the values an [OnnxModel] was run with are malformed, like an input
+    /// with the wrong shape.
+    Invalid(String),
+}
+
+impl std::fmt::Display for OnnxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "{err}"),
+            Self::DecodeError(err) => write!(f, "{err}"),
+            Self::TensorError(err) => write!(f, "{err}"),
+            Self::Unsupported(msg) => write!(f, "unsupported: {msg}"),
+            Self::UnsupportedOps(ops) => write!(f, "unsupported ops: {}", ops.join(", ")),
+            Self::Invalid(msg) => write!(f, "invalid: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for OnnxError {}
+
+impl From<std::io::Error> for OnnxError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl From<prost::DecodeError> for OnnxError {
+    fn from(err: prost::DecodeError) -> Self {
+        Self::DecodeError(err)
+    }
+}
+
+impl From<Error> for OnnxError {
+    fn from(err: Error) -> Self {
+        Self::TensorError(err)
+    }
+}
+
+/// Reads a `.onnx` file.
+pub fn load_model<P: AsRef<std::path::Path>>(path: P) -> Result<proto::ModelProto, OnnxError> {
+    use prost::Message;
+    let bytes = std::fs::read(path)?;
+    Ok(proto::ModelProto::decode(bytes.as_slice())?)
+}
+
+/// The example input of a model that is exported. The first tensor gets a
+/// [CaptureTape], and every tensor becomes an input of the ONNX graph, named
+/// `input` if there is only one, and `input0`, `input1`, ... otherwise.
+///
+/// Implemented for a tensor, and for tuples of 2 or 3 tensors, like the `(query, key,
+/// value)` of attention or the `(src, tgt)` of a transformer.
+pub trait OnnxInput {
+    /// The input with the first tensor traced.
+    type Traced;
+    fn trace(self) -> (Self::Traced, Vec<TensorRef>);
+}
+
+impl<S: Shape, E: Unit, D: Storage<E>> OnnxInput for Tensor<S, E, D> {
+    type Traced = Tensor<S, E, D, CaptureTape>;
+    fn trace(self) -> (Self::Traced, Vec<TensorRef>) {
+        let input = TensorRef::new(&self);
+        (self.capture(), std::vec![input])
+    }
+}
+
+impl<A: OnnxInput, S: Shape, E: Unit, D: Storage<E>> OnnxInput for (A, Tensor<S, E, D>) {
+    type Traced = (A::Traced, Tensor<S, E, D>);
+    fn trace(self) -> (Self::Traced, Vec<TensorRef>) {
+        let (a, mut inputs) = self.0.trace();
+        inputs.push(TensorRef::new(&self.1));
+        ((a, self.1), inputs)
+    }
+}
+
+impl<A: OnnxInput, S1: Shape, E1: Unit, S2: Shape, E2: Unit, D: Storage<E1> + Storage<E2>> OnnxInput
+    for (A, Tensor<S1, E1, D>, Tensor<S2, E2, D>)
+{
+    type Traced = (A::Traced, Tensor<S1, E1, D>, Tensor<S2, E2, D>);
+    fn trace(self) -> (Self::Traced, Vec<TensorRef>) {
+        let (a, mut inputs) = self.0.trace();
+        inputs.push(TensorRef::new(&self.1));
+        inputs.push(TensorRef::new(&self.2));
+        ((a, self.1, self.2), inputs)
+    }
+}
@@ -0,0 +1,495 @@
+//! Loading PyTorch `state_dict` checkpoints, the `.pt`/`.pth` files written by
+//! `torch.save(model.state_dict(), path)`.
+//!
+//! These files are zip archives with a pickled `data.pkl`, and the raw storage of each
+//! tensor in `data/`. Only the part of the pickle format that rebuilds tensors is
+//! understood, so no python code is ever run. Checkpoints saved with
+//! `_use_new_zipfile_serialization=False` are not supported.
+//!
+//! A [StateDict] gives access to the tensors by their `state_dict` key, and
+//! [crate::nn_traits::LoadPyTorch] loads one into a model, mapping keys onto the same
+//! locations that `SaveSafeTensors` uses.
+
+mod pickle;
+
+use std::{
+    collections::HashMap,
+    format,
+    io::{Read, Seek},
+    path::Path,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+
+use crate::{
+    shapes::{Dtype, DynShape},
+    tensor::{Tensor, TensorFromVec},
+};
+
+use pickle::Object;
+
+#[derive(Debug)]
+pub enum PyTorchError {
+    IoError(std::io::Error),
+    ZipError(zip::result::ZipError),
+    SafeTensorError(safetensors::SafeTensorError),
+    /// A part of the file format that can't be loaded, like a storage type.
+    Unsupported(String),
+    /// The file is malformed.
+    Invalid(String),
+    /// The params of the model that are not in the checkpoint.
+    MissingKeys(Vec<String>),
+    ShapeMismatch {
+        key: String,
+        expected: Vec<usize>,
+        found: Vec<usize>,
+    },
+}
+
+impl std::fmt::Display for PyTorchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::IoError(err) => write!(f, "{err}"),
+            Self::ZipError(err) => write!(f, "{err}"),
+            Self::SafeTensorError(err) => write!(f, "{err:?}"),
+            Self::Unsupported(msg) => write!(f, "unsupported: {msg}"),
+            Self::Invalid(msg) => write!(f, "invalid: {msg}"),
+            Self::MissingKeys(keys) => write!(f, "missing keys: {}", keys.join(", ")),
+            Self::ShapeMismatch {
+                key,
+                expected,
+                found,
+            } => write!(f, "{key} has shape {found:?}, expected {expected:?}"),
+        }
+    }
+}
+
+impl std::error::Error for PyTorchError {}
+
+impl From<std::io::Error> for PyTorchError {
+    fn from(err: std::io::Error) -> Self {
+        Self::IoError(err)
+    }
+}
+
+impl From<zip::result::ZipError> for PyTorchError {
+    fn from(err: zip::result::ZipError) -> Self {
+        Self::ZipError(err)
+    }
+}
+
+impl From<safetensors::SafeTensorError> for PyTorchError {
+    fn from(err: safetensors::SafeTensorError) -> Self {
+        Self::SafeTensorError(err)
+    }
+}
+
+/// The element type of a tensor in a checkpoint.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TorchDtype {
+    F64,
+    F32,
+    F16,
+    BF16,
+    I64,
+    I32,
+    I16,
+    I8,
+    U8,
+    Bool,
+}
+
+impl TorchDtype {
+    /// From the name of a `torch.*Storage` class.
+    fn from_storage(name: &str) -> Option<Self> {
+        Some(match name {
+            "DoubleStorage" => Self::F64,
+            "FloatStorage" => Self::F32,
+            "HalfStorage" => Self::F16,
+            "BFloat16Storage" => Self::BF16,
+            "LongStorage" => Self::I64,
+            "IntStorage" => Self::I32,
+            "ShortStorage" => Self::I16,
+            "CharStorage" => Self::I8,
+            "ByteStorage" => Self::U8,
+            "BoolStorage" => Self::Bool,
+            _ => return None,
+        })
+    }
+
+    /// The number of bytes of one element.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::F64 | Self::I64 => 8,
+            Self::F32 | Self::I32 => 4,
+            Self::F16 | Self::BF16 | Self::I16 => 2,
+            Self::I8 | Self::U8 | Self::Bool => 1,
+        }
+    }
+
+    /// Reads the little endian element in `b`, which is [Self::size()] bytes long.
+    fn read(&self, b: &[u8]) -> f64 {
+        match self {
+            Self::F64 => f64::from_le_bytes(b.try_into().unwrap()),
+            Self::F32 => f32::from_le_bytes(b.try_into().unwrap()) as f64,
+            Self::F16 => f16_to_f32(u16::from_le_bytes([b[0], b[1]])) as f64,
+            Self::BF16 => f32::from_bits((u16::from_le_bytes([b[0], b[1]]) as u32) << 16) as f64,
+            Self::I64 => i64::from_le_bytes(b.try_into().unwrap()) as f64,
+            Self::I32 => i32::from_le_bytes(b.try_into().unwrap()) as f64,
+            Self::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            Self::I8 => b[0] as i8 as f64,
+            Self::U8 | Self::Bool => b[0] as f64,
+        }
+    }
+}
+
+fn f16_to_f32(h: u16) -> f32 {
+    let sign = if h & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exp = ((h >> 10) & 0x1f) as i32;
+    let frac = (h & 0x3ff) as f32;
+    sign * match exp {
+        0 => frac * 2f32.powi(-24),
+        0x1f if frac == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + frac / 1024.0) * 2f32.powi(exp - 15),
+    }
+}
+
+#[derive(Clone, Debug)]
+struct StoredTensor {
+    dtype: TorchDtype,
+    shape: Vec<usize>,
+    /// Contiguous little endian elements.
+    data: Vec<u8>,
+}
+
+impl StoredTensor {
+    fn to_f64s(&self) -> impl Iterator<Item = f64> + '_ {
+        let size = self.dtype.size();
+        self.data.chunks_exact(size).map(|b| self.dtype.read(b))
+    }
+}
+
+/// The tensors of a PyTorch `state_dict`, in the order they were saved.
+///
+/// Nested dicts are flattened by joining keys with `.`, and anything else in the
+/// checkpoint that isn't a tensor (like `_metadata`) is skipped.
+///
+/// Example:
+/// ```ignore
+/// let state_dict = StateDict::load("model.pt")?;
+/// for key in state_dict.keys() {
+///     println!("{key}: {:?}", state_dict.shape(key).unwrap());
+/// }
+/// let w: Tensor<DynShape, f32, _> = state_dict.tensor("0.weight", &dev)?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct StateDict {
+    tensors: Vec<(String, StoredTensor)>,
+}
+
+impl StateDict {
+    /// Reads a `.pt`/`.pth` file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PyTorchError> {
+        let f = std::fs::File::open(path)?;
+        Self::from_reader(std::io::BufReader::new(f))
+    }
+
+    /// Reads a checkpoint from the bytes of a zip archive.
+    pub fn from_reader<R: Read + Seek>(mut r: R) -> Result<Self, PyTorchError> {
+        let mut magic = [0; 2];
+        r.read_exact(&mut magic)?;
+        if &magic != b"PK" {
+            return Err(PyTorchError::Unsupported(
+                "checkpoints saved with `_use_new_zipfile_serialization=False`".to_string(),
+            ));
+        }
+        r.rewind()?;
+        let mut archive = zip::ZipArchive::new(r)?;
+
+        // every entry is inside of a directory named after the file it was saved to
+        let pkl = archive
+            .file_names()
+            .find(|name| *name == "data.pkl" || name.ends_with("/data.pkl"))
+            .ok_or_else(|| PyTorchError::Invalid("no data.pkl".to_string()))?
+            .to_string();
+        let prefix = &pkl[..pkl.len() - "data.pkl".len()];
+
+        if let Ok(byteorder) = read_entry(&mut archive, &format!("{prefix}byteorder")) {
+            if byteorder.starts_with(b"big") {
+                return Err(PyTorchError::Unsupported(
+                    "big endian checkpoints".to_string(),
+                ));
+            }
+        }
+
+        let root = pickle::unpickle(&read_entry(&mut archive, &pkl)?)?;
+        let mut leaves = Vec::new();
+        flatten(&root, "", &mut leaves);
+
+        let mut storages: HashMap<String, Vec<u8>> = HashMap::new();
+        let mut tensors = Vec::with_capacity(leaves.len());
+        for (key, obj) in leaves {
+            let rebuild = match Rebuild::parse(obj)? {
+                Some(rebuild) => rebuild,
+                None => continue,
+            };
+            if !storages.contains_key(&rebuild.storage) {
+                let name = format!("{prefix}data/{}", rebuild.storage);
+                storages.insert(rebuild.storage.clone(), read_entry(&mut archive, &name)?);
+            }
+            let tensor = rebuild.materialize(&storages[&rebuild.storage], &key)?;
+            tensors.push((key, tensor));
+        }
+        Ok(Self { tensors })
+    }
+
+    /// The keys of all tensors, in the order they were saved.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.tensors.iter().map(|(k, _)| k.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.tensors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tensors.is_empty()
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// The shape of the tensor at `key`.
+    pub fn shape(&self, key: &str) -> Option<&[usize]> {
+        self.get(key).map(|t| t.shape.as_slice())
+    }
+
+    /// The element type of the tensor at `key`.
+    pub fn dtype(&self, key: &str) -> Option<TorchDtype> {
+        self.get(key).map(|t| t.dtype)
+    }
+
+    /// Renames every key with `f`. Keys that `f` maps to [None] are removed.
+    ///
+    /// Example:
+    /// ```ignore
+    /// // checkpoints of `nn.DataParallel` models have this prefix on every key
+    /// state_dict.rename(|k| Some(k.strip_prefix("module.").unwrap_or(k).to_string()));
+    /// ```
+    pub fn rename<F: FnMut(&str) -> Option<String>>(&mut self, mut f: F) {
+        let tensors = std::mem::take(&mut self.tensors);
+        self.tensors = tensors
+            .into_iter()
+            .filter_map(|(k, t)| f(&k).map(|k| (k, t)))
+            .collect();
+    }
+
+    /// Copies the tensor at `key` to `device`, converting its elements to `E`.
+    pub fn tensor<E: Dtype, D: TensorFromVec<E>>(
+        &self,
+        key: &str,
+        device: &D,
+    ) -> Result<Tensor<DynShape, E, D>, PyTorchError> {
+        let t = self
+            .get(key)
+            .ok_or_else(|| PyTorchError::MissingKeys(vec![key.to_string()]))?;
+        let shape = DynShape::try_new(&t.shape)
+            .ok_or_else(|| PyTorchError::Unsupported(format!("the rank of {key}")))?;
+        let buf = t
+            .to_f64s()
+            .map(|x| E::from_f64(x).unwrap_or_default())
+            .collect();
+        Ok(device.tensor_from_vec(buf, shape))
+    }
+
+    fn get(&self, key: &str) -> Option<&StoredTensor> {
+        self.tensors.iter().find(|(k, _)| k == key).map(|(_, t)| t)
+    }
+
+    /// Writes the tensor at `key` over the data of a safetensors entry with `dtype`.
+    /// Returns `false` if `dtype` is not a float type, in which case nothing is written.
+    pub(crate) fn write_as(
+        &self,
+        key: &str,
+        dtype: safetensors::Dtype,
+        data: &mut Vec<u8>,
+    ) -> Result<bool, PyTorchError> {
+        use safetensors::Dtype as St;
+        let t = self.get(key).unwrap();
+        let values = t.to_f64s();
+        *data = match dtype {
+            St::F64 => values.flat_map(f64::to_le_bytes).collect(),
+            St::F32 => values.flat_map(|x| (x as f32).to_le_bytes()).collect(),
+            #[cfg(feature = "f16")]
+            St::F16 => values
+                .flat_map(|x| half::f16::from_f64(x).to_le_bytes())
+                .collect(),
+            _ => return Ok(false),
+        };
+        Ok(true)
+    }
+}
+
+fn read_entry<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Vec<u8>, PyTorchError> {
+    let mut entry = archive.by_name(name)?;
+    let mut buf = Vec::with_capacity(entry.size() as usize);
+    entry.read_to_end(&mut buf)?;
+    Ok(buf)
+}
+
+/// Collects everything in nested dicts, with keys joined by `.`.
+fn flatten<'a>(obj: &'a Object, prefix: &str, out: &mut Vec<(String, &'a Object)>) {
+    match obj {
+        Object::Dict(items) => {
+            for (k, v) in items {
+                let k = match k {
+                    Object::String(s) => s.clone(),
+                    Object::Int(i) => i.to_string(),
+                    _ => continue,
+                };
+                let key = if prefix.is_empty() {
+                    k
+                } else {
+                    format!("{prefix}.{k}")
+                };
+                flatten(v, &key, out);
+            }
+        }
+        _ => out.push((prefix.to_string(), obj)),
+    }
+}
+
+/// A call of `torch._utils._rebuild_tensor_v2`, which creates a strided view of a
+/// storage.
+struct Rebuild {
+    dtype: TorchDtype,
+    storage: String,
+    offset: usize,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+impl Rebuild {
+    /// Returns [None] for anything that isn't a tensor.
+    fn parse(obj: &Object) -> Result<Option<Self>, PyTorchError> {
+        let (func, args) = match obj {
+            Object::Reduce(func, args) => (func.as_ref(), args.as_tuple().unwrap_or(&[])),
+            _ => return Ok(None),
+        };
+        let is_utils = |name| func.is_global("torch._utils", name);
+        if is_utils("_rebuild_parameter") || is_utils("_rebuild_parameter_with_state") {
+            return match args.first() {
+                Some(t) => Self::parse(t),
+                None => Ok(None),
+            };
+        }
+        if !is_utils("_rebuild_tensor_v2") && !is_utils("_rebuild_tensor") {
+            return Ok(None);
+        }
+
+        let invalid = || PyTorchError::Invalid(format!("arguments of {func:?}"));
+        let pid = match args.first() {
+            Some(Object::PersistentId(pid)) => pid.as_tuple().ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+        let (dtype, storage) = match pid {
+            [kind, Object::Global(module, name), key, ..] if kind.as_str() == Some("storage") => {
+                let dtype = match TorchDtype::from_storage(name) {
+                    Some(dtype) if module == "torch" => dtype,
+                    _ => return Err(PyTorchError::Unsupported(format!("{module}.{name}"))),
+                };
+                let key = match key {
+                    Object::String(s) => s.clone(),
+                    Object::Int(i) => i.to_string(),
+                    _ => return Err(invalid()),
+                };
+                (dtype, key)
+            }
+            _ => return Err(invalid()),
+        };
+        let ints = |obj: Option<&Object>| -> Result<Vec<usize>, PyTorchError> {
+            let items = obj.and_then(Object::as_tuple).ok_or_else(invalid)?;
+            items
+                .iter()
+                .map(|x| x.as_int().and_then(|x| usize::try_from(x).ok()))
+                .collect::<Option<_>>()
+                .ok_or_else(invalid)
+        };
+        let offset = args.get(1).and_then(Object::as_int).ok_or_else(invalid)?;
+        let shape = ints(args.get(2))?;
+        let strides = ints(args.get(3))?;
+        if shape.len() != strides.len() {
+            return Err(invalid());
+        }
+        Ok(Some(Self {
+            dtype,
+            storage,
+            offset: usize::try_from(offset).map_err(|_| invalid())?,
+            shape,
+            strides,
+        }))
+    }
+
+    /// Copies the elements of the view out of `storage`, in row major order.
+    fn materialize(&self, storage: &[u8], key: &str) -> Result<StoredTensor, PyTorchError> {
+        let size = self.dtype.size();
+        let out_of_bounds =
+            || PyTorchError::Invalid(format!("{key} is out of bounds of its storage"));
+        let numel = self
+            .shape
+            .iter()
+            .try_fold(1usize, |n, &d| n.checked_mul(d))
+            .ok_or_else(out_of_bounds)?;
+        let num_bytes = numel.checked_mul(size).ok_or_else(out_of_bounds)?;
+        if numel > 0 {
+            // the largest element index of the view has to fit in the storage
+            let last = self
+                .shape
+                .iter()
+                .zip(&self.strides)
+                .try_fold(self.offset, |i, (d, s)| {
+                    i.checked_add((d - 1).checked_mul(*s)?)
+                })
+                .and_then(|i| i.checked_add(1)?.checked_mul(size))
+                .ok_or_else(out_of_bounds)?;
+            if last > storage.len() {
+                return Err(out_of_bounds());
+            }
+        }
+        // views with a stride of 0 can be much larger than their storage
+        let mut data = Vec::new();
+        data.try_reserve_exact(num_bytes).map_err(|_| {
+            PyTorchError::Invalid(format!("{key} is too large to load ({num_bytes} bytes)"))
+        })?;
+        if numel > 0 {
+            let mut index = vec![0; self.shape.len()];
+            for _ in 0..numel {
+                let i = self.offset
+                    + index
+                        .iter()
+                        .zip(&self.strides)
+                        .map(|(i, s)| i * s)
+                        .sum::<usize>();
+                data.extend_from_slice(&storage[i * size..(i + 1) * size]);
+                for d in (0..index.len()).rev() {
+                    index[d] += 1;
+                    if index[d] < self.shape[d] {
+                        break;
+                    }
+                    index[d] = 0;
+                }
+            }
+        }
+        Ok(StoredTensor {
+            dtype: self.dtype,
+            shape: self.shape.clone(),
+            data,
+        })
+    }
+}
@@ -0,0 +1,420 @@
+//! An unpickler for the subset of python's [pickle](https://docs.python.org/3/library/pickle.html)
+//! format that `torch.save` writes. No python code is run: classes and functions are kept as
+//! [Object::Global], and calling them as [Object::Reduce], to be interpreted by the caller.
+
+use std::{
+    boxed::Box,
+    collections::HashMap,
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use super::PyTorchError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Object {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Tuple(Vec<Object>),
+    /// Also used for sets.
+    List(Vec<Object>),
+    /// The items in insertion order, like an `OrderedDict`.
+    Dict(Vec<(Object, Object)>),
+    /// A class or function, as `(module, name)`.
+    Global(String, String),
+    /// An object stored outside of the pickle, like the storages of tensors.
+    PersistentId(Box<Object>),
+    /// A call of a [Object::Global] with a tuple of args.
+    Reduce(Box<Object>, Box<Object>),
+}
+
+impl Object {
+    pub(super) fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_int(&self) -> Option<i64> {
+        match self {
+            Self::Int(i) => Some(*i),
+            Self::Bool(b) => Some(*b as i64),
+            _ => None,
+        }
+    }
+
+    pub(super) fn as_tuple(&self) -> Option<&[Object]> {
+        match self {
+            Self::Tuple(items) | Self::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Whether this is `module.name`.
+    pub(super) fn is_global(&self, module: &str, name: &str) -> bool {
+        matches!(self, Self::Global(m, n) if m == module && n == name)
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], PyTorchError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len());
+        let end = end.ok_or_else(|| invalid("unexpected end of data"))?;
+        let bytes = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], PyTorchError> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8, PyTorchError> {
+        Ok(self.array::<1>()?[0])
+    }
+
+    fn u32(&mut self) -> Result<usize, PyTorchError> {
+        Ok(u32::from_le_bytes(self.array()?) as usize)
+    }
+
+    fn u64(&mut self) -> Result<usize, PyTorchError> {
+        Ok(u64::from_le_bytes(self.array()?) as usize)
+    }
+
+    /// A newline terminated line, without the newline.
+    fn line(&mut self) -> Result<String, PyTorchError> {
+        let len = self.bytes[self.pos..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .ok_or_else(|| invalid("unterminated line"))?;
+        let line = String::from_utf8_lossy(self.take(len)?).into_owned();
+        self.pos += 1;
+        Ok(line)
+    }
+
+    fn string(&mut self, len: usize) -> Result<Object, PyTorchError> {
+        let bytes = self.take(len)?;
+        let s = std::str::from_utf8(bytes).map_err(|e| invalid(&e.to_string()))?;
+        Ok(Object::String(s.to_string()))
+    }
+}
+
+/// Runs the pickle program in `bytes`, and returns the object it builds.
+pub(super) fn unpickle(bytes: &[u8]) -> Result<Object, PyTorchError> {
+    let mut r = Reader { bytes, pos: 0 };
+    let mut stack: Vec<Object> = Vec::new();
+    let mut marks: Vec<usize> = Vec::new();
+    let mut memo: HashMap<usize, Object> = HashMap::new();
+
+    loop {
+        let op = r.u8()?;
+        match op {
+            // PROTO & FRAME
+            0x80 => {
+                r.u8()?;
+            }
+            0x95 => {
+                r.take(8)?;
+            }
+            // STOP
+            b'.' => return stack.pop().ok_or_else(|| invalid("empty stack")),
+            // MARK
+            b'(' => marks.push(stack.len()),
+            b'N' => stack.push(Object::None),
+            0x88 => stack.push(Object::Bool(true)),
+            0x89 => stack.push(Object::Bool(false)),
+            // BININT, BININT1, BININT2 & LONG1
+            b'J' => stack.push(Object::Int(i32::from_le_bytes(r.array()?) as i64)),
+            b'K' => stack.push(Object::Int(r.u8()? as i64)),
+            b'M' => stack.push(Object::Int(u16::from_le_bytes(r.array()?) as i64)),
+            0x8a => {
+                let n = r.u8()? as usize;
+                if n > 8 {
+                    return Err(unsupported("integers with more than 64 bits"));
+                }
+                let bytes = r.take(n)?;
+                // little endian two's complement, sign extended from the last byte
+                let fill = match bytes.last() {
+                    Some(b) if b & 0x80 != 0 => 0xff,
+                    _ => 0,
+                };
+                let mut buf = [fill; 8];
+                buf[..n].copy_from_slice(bytes);
+                stack.push(Object::Int(i64::from_le_bytes(buf)));
+            }
+            // BINFLOAT is big endian
+            b'G' => stack.push(Object::Float(f64::from_be_bytes(r.array()?))),
+            // BINUNICODE, SHORT_BINUNICODE & BINUNICODE8
+            b'X' => {
+                let len = r.u32()?;
+                stack.push(r.string(len)?);
+            }
+            0x8c => {
+                let len = r.u8()? as usize;
+                stack.push(r.string(len)?);
+            }
+            0x8d => {
+                let len = r.u64()?;
+                stack.push(r.string(len)?);
+            }
+            // BINSTRING & SHORT_BINSTRING, python 2 strings
+            b'T' => {
+                let len = r.u32()?;
+                let s = String::from_utf8_lossy(r.take(len)?).into_owned();
+                stack.push(Object::String(s));
+            }
+            b'U' => {
+                let len = r.u8()? as usize;
+                let s = String::from_utf8_lossy(r.take(len)?).into_owned();
+                stack.push(Object::String(s));
+            }
+            // BINBYTES, SHORT_BINBYTES & BINBYTES8
+            b'B' => {
+                let len = r.u32()?;
+                stack.push(Object::Bytes(r.take(len)?.to_vec()));
+            }
+            b'C' => {
+                let len = r.u8()? as usize;
+                stack.push(Object::Bytes(r.take(len)?.to_vec()));
+            }
+            0x8e => {
+                let len = r.u64()?;
+                stack.push(Object::Bytes(r.take(len)?.to_vec()));
+            }
+            b')' => stack.push(Object::Tuple(Vec::new())),
+            b']' | 0x8f => stack.push(Object::List(Vec::new())),
+            b'}' => stack.push(Object::Dict(Vec::new())),
+            // TUPLE, TUPLE1, TUPLE2 & TUPLE3
+            b't' => {
+                let items = pop_mark(&mut stack, &mut marks)?;
+                stack.push(Object::Tuple(items));
+            }
+            0x85..=0x87 => {
+                let n = (op - 0x84) as usize;
+                let start = stack
+                    .len()
+                    .checked_sub(n)
+                    .ok_or_else(|| invalid("stack underflow"))?;
+                let items = stack.split_off(start);
+                stack.push(Object::Tuple(items));
+            }
+            // LIST, FROZENSET & DICT
+            b'l' | 0x91 => {
+                let items = pop_mark(&mut stack, &mut marks)?;
+                stack.push(Object::List(items));
+            }
+            b'd' => {
+                let items = pop_mark(&mut stack, &mut marks)?;
+                stack.push(Object::Dict(pairs(items)?));
+            }
+            // APPEND, APPENDS & ADDITEMS
+            b'a' => {
+                let item = pop(&mut stack)?;
+                match stack.last_mut() {
+                    Some(Object::List(items)) => items.push(item),
+                    _ => return Err(invalid("APPEND to something that is not a list")),
+                }
+            }
+            b'e' | 0x90 => {
+                let new_items = pop_mark(&mut stack, &mut marks)?;
+                match stack.last_mut() {
+                    Some(Object::List(items)) => items.extend(new_items),
+                    _ => return Err(invalid("APPENDS to something that is not a list")),
+                }
+            }
+            // SETITEM & SETITEMS
+            b's' => {
+                let value = pop(&mut stack)?;
+                let key = pop(&mut stack)?;
+                match stack.last_mut() {
+                    Some(Object::Dict(items)) => items.push((key, value)),
+                    _ => return Err(invalid("SETITEM on something that is not a dict")),
+                }
+            }
+            b'u' => {
+                let new_items = pairs(pop_mark(&mut stack, &mut marks)?)?;
+                match stack.last_mut() {
+                    Some(Object::Dict(items)) => items.extend(new_items),
+                    _ => return Err(invalid("SETITEMS on something that is not a dict")),
+                }
+            }
+            // GLOBAL & STACK_GLOBAL
+            b'c' => {
+                let module = r.line()?;
+                let name = r.line()?;
+                stack.push(Object::Global(module, name));
+            }
+            0x93 => {
+                let name = pop(&mut stack)?;
+                let module = pop(&mut stack)?;
+                match (module, name) {
+                    (Object::String(module), Object::String(name)) => {
+                        stack.push(Object::Global(module, name))
+                    }
+                    _ => return Err(invalid("STACK_GLOBAL of something that is not a string")),
+                }
+            }
+            // REDUCE & NEWOBJ
+            b'R' | 0x81 => {
+                let args = pop(&mut stack)?;
+                let callable = pop(&mut stack)?;
+                stack.push(reduce(callable, args));
+            }
+            // BUILD sets the state of the object, which isn't needed for anything we load
+            b'b' => {
+                pop(&mut stack)?;
+            }
+            // BINPERSID
+            b'Q' => {
+                let pid = pop(&mut stack)?;
+                stack.push(Object::PersistentId(Box::new(pid)));
+            }
+            // PUT, BINPUT, LONG_BINPUT & MEMOIZE
+            b'p' => {
+                let i = r.line()?.parse().map_err(|_| invalid("PUT index"))?;
+                memo.insert(i, top(&stack)?.clone());
+            }
+            b'q' => {
+                let i = r.u8()? as usize;
+                memo.insert(i, top(&stack)?.clone());
+            }
+            b'r' => {
+                let i = r.u32()?;
+                memo.insert(i, top(&stack)?.clone());
+            }
+            0x94 => {
+                memo.insert(memo.len(), top(&stack)?.clone());
+            }
+            // GET, BINGET & LONG_BINGET
+            b'g' | b'h' | b'j' => {
+                let i = match op {
+                    b'g' => r.line()?.parse().map_err(|_| invalid("GET index"))?,
+                    b'h' => r.u8()? as usize,
+                    _ => r.u32()?,
+                };
+                let obj = memo
+                    .get(&i)
+                    .ok_or_else(|| invalid(&format!("memo {i} is not set")))?;
+                stack.push(obj.clone());
+            }
+            // POP, POP_MARK & DUP
+            b'0' => {
+                if marks.last() == Some(&stack.len()) {
+                    marks.pop();
+                } else {
+                    pop(&mut stack)?;
+                }
+            }
+            b'1' => {
+                pop_mark(&mut stack, &mut marks)?;
+            }
+            b'2' => {
+                let obj = top(&stack)?.clone();
+                stack.push(obj);
+            }
+            _ => return Err(unsupported(&format!("pickle opcode {op:#04x}"))),
+        }
+    }
+}
+
+/// Calls `callable`, where the only call that is evaluated is creating an empty
+/// `OrderedDict`, since items are added to it afterwards.
+fn reduce(callable: Object, args: Object) -> Object {
+    let empty_args = matches!(&args, Object::Tuple(a) if a.is_empty());
+    if empty_args && callable.is_global("collections", "OrderedDict") {
+        Object::Dict(Vec::new())
+    } else {
+        Object::Reduce(Box::new(callable), Box::new(args))
+    }
+}
+
+fn pop(stack: &mut Vec<Object>) -> Result<Object, PyTorchError> {
+    stack.pop().ok_or_else(|| invalid("stack underflow"))
+}
+
+fn top(stack: &[Object]) -> Result<&Object, PyTorchError> {
+    stack.last().ok_or_else(|| invalid("stack underflow"))
+}
+
+fn pop_mark(stack: &mut Vec<Object>, marks: &mut Vec<usize>) -> Result<Vec<Object>, PyTorchError> {
+    let mark = marks.pop().ok_or_else(|| invalid("missing MARK"))?;
+    if mark > stack.len() {
+        return Err(invalid("stack underflow"));
+    }
+    Ok(stack.split_off(mark))
+}
+
+fn pairs(items: Vec<Object>) -> Result<Vec<(Object, Object)>, PyTorchError> {
+    if items.len() % 2 != 0 {
+        return Err(invalid("a key without a value"));
+    }
+    let mut pairs = Vec::with_capacity(items.len() / 2);
+    let mut items = items.into_iter();
+    while let (Some(k), Some(v)) = (items.next(), items.next()) {
+        pairs.push((k, v));
+    }
+    Ok(pairs)
+}
+
+fn invalid(msg: &str) -> PyTorchError {
+    PyTorchError::Invalid(format!("pickle: {msg}"))
+}
+
+fn unsupported(msg: &str) -> PyTorchError {
+    PyTorchError::Unsupported(msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec;
+
+    #[test]
+    fn test_unpickle_ordered_dict() {
+        // pickle.dumps(OrderedDict(a=(1, -2.5), b=[True, None], c=2**40, d=-1), protocol=2)
+        let bytes = b"\x80\x02ccollections\nOrderedDict\nq\x00)Rq\x01(X\x01\x00\x00\x00aq\x02K\x01G\xc0\x04\x00\x00\x00\x00\x00\x00\x86q\x03X\x01\x00\x00\x00bq\x04]q\x05(\x88Nh\x02eX\x01\x00\x00\x00cq\x06\x8a\x06\x00\x00\x00\x00\x00\x01X\x01\x00\x00\x00dq\x07J\xff\xff\xff\xffu.";
+        let obj = unpickle(bytes).unwrap();
+        let s = |s: &str| Object::String(s.to_string());
+        assert_eq!(
+            obj,
+            Object::Dict(vec![
+                (
+                    s("a"),
+                    Object::Tuple(vec![Object::Int(1), Object::Float(-2.5)])
+                ),
+                (
+                    s("b"),
+                    Object::List(vec![Object::Bool(true), Object::None, s("a")])
+                ),
+                (s("c"), Object::Int(1 << 40)),
+                (s("d"), Object::Int(-1)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unpickle_errors() {
+        assert!(matches!(
+            unpickle(b"\x80\x02K\x01"),
+            Err(PyTorchError::Invalid(_))
+        ));
+        assert!(matches!(
+            unpickle(b"\x80\x02\xff."),
+            Err(PyTorchError::Unsupported(_))
+        ));
+    }
+}
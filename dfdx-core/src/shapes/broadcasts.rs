@@ -74,7 +74,7 @@ macro_rules! broadcast_to_all {
     }
 }
 
-broadcast_to_all!([] [] [] [A B C D E F] [() Axis Axes2 Axes3 Axes4 Axes5 Axes6]);
+broadcast_to_all!([] [] [] [A B C D E F G H] [() Axis Axes2 Axes3 Axes4 Axes5 Axes6 Axes7 Axes8]);
 
 /// Internal implementation for broadcasting strides
 pub trait BroadcastStridesTo<S: Shape, Ax>: Shape + BroadcastShapeTo<S, Ax> {
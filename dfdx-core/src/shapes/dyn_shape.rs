@@ -0,0 +1,264 @@
+use super::{axes::*, broadcasts::*, realize::RealizeShapeTo, shape::*};
+
+/// The maximum number of dimensions a [DynShape] can have.
+pub const MAX_DYN_RANK: usize = 8;
+
+/// A [Shape] whose rank is only known at runtime, e.g. for tensors loaded from a file.
+///
+/// Kernels need the number of dimensions at compile time, so a [DynShape] always has
+/// [MAX_DYN_RANK] dimensions as far as they are concerned. The actual dimensions are
+/// right aligned, and padded at the front with dimensions of size 1, which doesn't change
+/// how data is laid out. [Shape::concrete] & [Shape::strides] include this padding, while
+/// [DynShape::rank] & [Shape::dims] don't.
+///
+/// Elementwise ops, reducing all axes and reducing the last axis (e.g. softmax) work as
+/// usual. Reducing the last axis gives the padded `[usize; MAX_DYN_RANK - 1]` shape.
+/// To do anything else, convert to a static shape with [crate::tensor_ops::RealizeTo]:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let a: Tensor<DynShape, f32, _> = dev.ones_like(&DynShape::new(&[2, 3]));
+/// let a = (a * 2.0).exp();
+/// let b: Tensor<(usize, Const<3>), f32, _> = a.realize();
+/// let a: Tensor<DynShape, f32, _> = b.realize();
+/// assert_eq!(a.shape().dims(), [2, 3]);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DynShape {
+    rank: usize,
+    dims: [usize; MAX_DYN_RANK],
+}
+
+impl DynShape {
+    /// Panics if `dims` has more than [MAX_DYN_RANK] dimensions.
+    pub fn new(dims: &[usize]) -> Self {
+        Self::try_new(dims).unwrap_or_else(|| {
+            panic!(
+                "DynShape supports at most {MAX_DYN_RANK} dimensions, found {}",
+                dims.len()
+            )
+        })
+    }
+
+    /// Returns `None` if `dims` has more than [MAX_DYN_RANK] dimensions.
+    pub fn try_new(dims: &[usize]) -> Option<Self> {
+        if dims.len() > MAX_DYN_RANK {
+            return None;
+        }
+        let mut shape = Self {
+            rank: dims.len(),
+            ..Default::default()
+        };
+        shape.dims[MAX_DYN_RANK - dims.len()..].copy_from_slice(dims);
+        Some(shape)
+    }
+
+    /// The number of actual dimensions.
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+}
+
+impl Default for DynShape {
+    /// A shape with no dimensions.
+    fn default() -> Self {
+        Self {
+            rank: 0,
+            dims: [1; MAX_DYN_RANK],
+        }
+    }
+}
+
+impl std::fmt::Debug for DynShape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("DynShape")
+            .field(&&self.dims[MAX_DYN_RANK - self.rank..])
+            .finish()
+    }
+}
+
+/// The [Shape::Concrete] of a [DynShape], i.e. its padded dimensions or strides.
+///
+/// This also keeps track of the rank, so that [Shape::from_concrete] can recover it. Only
+/// the padded dimensions are compared though.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DynConcrete {
+    rank: usize,
+    dims: [usize; MAX_DYN_RANK],
+}
+
+impl PartialEq for DynConcrete {
+    fn eq(&self, other: &Self) -> bool {
+        self.dims == other.dims
+    }
+}
+
+impl Eq for DynConcrete {}
+
+impl std::ops::Index<usize> for DynConcrete {
+    type Output = usize;
+    #[inline(always)]
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.dims[index]
+    }
+}
+
+impl std::ops::IndexMut<usize> for DynConcrete {
+    #[inline(always)]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.dims[index]
+    }
+}
+
+impl IntoIterator for DynConcrete {
+    type Item = usize;
+    type IntoIter = std::array::IntoIter<usize, MAX_DYN_RANK>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.dims.into_iter()
+    }
+}
+
+impl From<DynConcrete> for std::vec::Vec<usize> {
+    fn from(concrete: DynConcrete) -> Self {
+        concrete.dims.into()
+    }
+}
+
+impl AsRef<[usize]> for DynConcrete {
+    fn as_ref(&self) -> &[usize] {
+        &self.dims
+    }
+}
+
+impl Shape for DynShape {
+    const NUM_DIMS: usize = MAX_DYN_RANK;
+    type Concrete = DynConcrete;
+    type AllAxes = Axes8<0, 1, 2, 3, 4, 5, 6, 7>;
+    type LastAxis = Axis<7>;
+
+    #[inline(always)]
+    fn concrete(&self) -> Self::Concrete {
+        DynConcrete {
+            rank: self.rank,
+            dims: self.dims,
+        }
+    }
+
+    #[inline(always)]
+    fn from_concrete(concrete: &Self::Concrete) -> Option<Self> {
+        Some(Self {
+            rank: concrete.rank,
+            dims: concrete.dims,
+        })
+    }
+
+    fn dims(&self) -> std::vec::Vec<usize> {
+        self.dims[MAX_DYN_RANK - self.rank..].into()
+    }
+}
+
+macro_rules! dyn_has_axis {
+    ($($Axis:tt),*) => {
+        $(
+            impl HasAxes<Axis<$Axis>> for DynShape {
+                #[inline(always)]
+                fn size(&self) -> usize {
+                    self.dims[$Axis]
+                }
+            }
+        )*
+    };
+}
+
+dyn_has_axis!(0, 1, 2, 3, 4, 5, 6, 7);
+
+impl ReduceShapeTo<(), Axes8<0, 1, 2, 3, 4, 5, 6, 7>> for DynShape {}
+impl ReduceShape<Axes8<0, 1, 2, 3, 4, 5, 6, 7>> for DynShape {
+    type Reduced = ();
+}
+
+impl ReduceShapeTo<[usize; MAX_DYN_RANK - 1], Axis<7>> for DynShape {}
+impl ReduceShape<Axis<7>> for DynShape {
+    type Reduced = [usize; MAX_DYN_RANK - 1];
+}
+
+macro_rules! realize_dyn {
+    (($($D:tt $Idx:tt),*), rank=$Num:expr) => {
+        impl<$($D: Dim, )*> RealizeShapeTo<DynShape> for ($($D, )*) {
+            #[inline(always)]
+            fn realized(&self) -> Option<DynShape> {
+                DynShape::try_new(&self.concrete())
+            }
+
+            #[inline(always)]
+            fn realized_strides(&self, strides: &Self::Concrete) -> DynConcrete {
+                let mut dst_strides = DynShape::new(&self.concrete()).strides();
+                dst_strides.dims[MAX_DYN_RANK - $Num..].copy_from_slice(strides);
+                dst_strides
+            }
+        }
+
+        impl<$($D: Dim, )*> RealizeShapeTo<($($D, )*)> for DynShape {
+            #[inline(always)]
+            fn realized(&self) -> Option<($($D, )*)> {
+                if self.rank != $Num {
+                    return None;
+                }
+                Some(($($D::from_size(self.dims[MAX_DYN_RANK - $Num + $Idx])?, )*))
+            }
+
+            #[inline(always)]
+            fn realized_strides(&self, strides: &DynConcrete) -> [usize; $Num] {
+                let mut dst_strides = [0; $Num];
+                dst_strides.copy_from_slice(&strides.dims[MAX_DYN_RANK - $Num..]);
+                dst_strides
+            }
+        }
+    };
+}
+
+realize_dyn!((), rank = 0);
+realize_dyn!((D1 0), rank = 1);
+realize_dyn!((D1 0, D2 1), rank = 2);
+realize_dyn!((D1 0, D2 1, D3 2), rank = 3);
+realize_dyn!((D1 0, D2 1, D3 2, D4 3), rank = 4);
+realize_dyn!((D1 0, D2 1, D3 2, D4 3, D5 4), rank = 5);
+realize_dyn!((D1 0, D2 1, D3 2, D4 3, D5 4, D6 5), rank = 6);
+realize_dyn!((D1 0, D2 1, D3 2, D4 3, D5 4, D6 5, D7 6), rank = 7);
+realize_dyn!((D1 0, D2 1, D3 2, D4 3, D5 4, D6 5, D7 6, D8 7), rank = 8);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dyn_shape_padding() {
+        let s = DynShape::new(&[2, 3, 4]);
+        assert_eq!(s.rank(), 3);
+        assert_eq!(s.dims(), [2, 3, 4]);
+        assert_eq!(s.num_elements(), 24);
+        assert_eq!(s.concrete().as_ref(), [1, 1, 1, 1, 1, 2, 3, 4]);
+        assert_eq!(s.strides().as_ref(), [24, 24, 24, 24, 24, 12, 4, 1]);
+        assert_ne!(s, DynShape::new(&[1, 2, 3, 4]));
+        assert_eq!(format!("{s:?}"), "DynShape([2, 3, 4])");
+
+        let s = DynShape::default();
+        assert_eq!(s.rank(), 0);
+        assert_eq!(s.num_elements(), 1);
+        assert!(DynShape::try_new(&[1; MAX_DYN_RANK + 1]).is_none());
+    }
+
+    #[test]
+    fn test_realize_dyn_shape() {
+        let s: DynShape = (Const::<2>, 3).realized().unwrap();
+        assert_eq!(s.dims(), [2, 3]);
+        let d: Option<(usize, Const<3>)> = s.realized();
+        assert_eq!(d, Some((2, Const)));
+        let d: Option<(usize, Const<4>)> = s.realized();
+        assert_eq!(d, None);
+        let d: Option<(usize, usize, usize)> = s.realized();
+        assert_eq!(d, None);
+        let d: Option<()> = DynShape::default().realized();
+        assert_eq!(d, Some(()));
+    }
+}
@@ -11,6 +11,7 @@
 
 mod axes;
 mod broadcasts;
+mod dyn_shape;
 mod permutes;
 mod realize;
 mod replace_dim;
@@ -21,16 +22,17 @@ mod slice;
 pub use broadcasts::{
     BroadcastShapeTo, BroadcastStridesTo, ReduceShape, ReduceShapeTo, ReduceStridesTo,
 };
-pub use permutes::{PermuteShapeTo, PermuteStridesTo};
+pub use dyn_shape::{DynConcrete, DynShape, MAX_DYN_RANK};
+pub use permutes::{DimAt, PermuteShapeTo, PermuteStridesTo};
 pub use realize::RealizeShapeTo;
 pub use replace_dim::{RemoveDimTo, ReplaceDimTo};
 
 pub use same_numel::AssertSameNumel;
 pub use slice::SliceShape;
 
-pub use axes::{Axes, Axes2, Axes3, Axes4, Axes5, Axes6, Axis, HasAxes};
+pub use axes::{Axes, Axes2, Axes3, Axes4, Axes5, Axes6, Axes7, Axes8, Axis, HasAxes};
 pub use shape::{Array, Const, ConstDim, Dim};
 pub use shape::{ConstShape, HasShape, Shape};
-pub use shape::{Rank0, Rank1, Rank2, Rank3, Rank4, Rank5, Rank6};
+pub use shape::{Rank0, Rank1, Rank2, Rank3, Rank4, Rank5, Rank6, Rank7, Rank8};
 
 pub use crate::dtypes::{Dtype, HasDtype, HasUnitType, SafeZeros, Unit};
@@ -5,7 +5,11 @@ use super::*;
 ///
 /// E.g. `PermuteShapeTo<_, Axes2<1, 0>>` would mean you can reverse
 /// axes 0 and 1.
-pub trait PermuteShapeTo<Dst, Ax> {}
+pub trait PermuteShapeTo<Dst, Ax> {
+    /// Fails to compile if `Ax` isn't a valid permutation. Only the impls that can't
+    /// enumerate their permutations need this.
+    const CHECK: () = ();
+}
 
 pub trait PermuteStridesTo<S: Shape, Ax>: Shape + PermuteShapeTo<S, Ax> {
     fn permuted(&self) -> S;
@@ -18,6 +22,7 @@ where
 {
     #[inline(always)]
     fn permuted(&self) -> Dst {
+        let () = <Self as PermuteShapeTo<Dst, Ax>>::CHECK;
         let src_dims = self.concrete();
         let mut dst_dims: Dst::Concrete = Default::default();
         for (i_dst, i_src) in Ax::as_array().into_iter().enumerate() {
@@ -170,6 +175,85 @@ permutations!([0, 1, 2, 3]);
 permutations!([0, 1, 2, 3, 4]);
 permutations!([0, 1, 2, 3, 4, 5]);
 
+/// The [Dim] at index `I` of a shape.
+///
+/// There are too many permutations of 7d and 8d shapes to enumerate like the smaller ranks,
+/// so their [PermuteShapeTo] impls select each dimension of the permuted shape with this
+/// instead. This means the [Axes] have to be specified when permuting them, e.g.
+/// `t.permute::<_, Axes7<6, 5, 4, 3, 2, 1, 0>>()`. Repeating an axis fails to compile:
+/// ```compile_fail
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t: Tensor<Rank7<1, 2, 3, 4, 5, 6, 7>, f32, _> = dev.zeros();
+/// let _ = t.permute::<_, Axes7<0, 0, 1, 2, 3, 4, 5>>();
+/// ```
+pub trait DimAt<const I: isize> {
+    type Dim: Dim;
+}
+
+macro_rules! dim_at {
+    ($Dims:tt, [$($Idx:tt $At:ident),*]) => {
+        $(dim_at!(@ $Dims, $Idx, $At);)*
+    };
+    (@ ($($D:ident),*), $Idx:tt, $At:ident) => {
+        impl<$($D: Dim, )*> DimAt<$Idx> for ($($D, )*) {
+            type Dim = $At;
+        }
+    };
+}
+
+dim_at!((D1, D2, D3, D4, D5, D6, D7), [0 D1, 1 D2, 2 D3, 3 D4, 4 D5, 5 D6, 6 D7]);
+dim_at!((D1, D2, D3, D4, D5, D6, D7, D8), [0 D1, 1 D2, 2 D3, 3 D4, 4 D5, 5 D6, 6 D7, 7 D8]);
+
+const fn unique_axes<const N: usize>(axes: [isize; N]) -> bool {
+    let mut i = 0;
+    while i < N {
+        let mut j = i + 1;
+        while j < N {
+            if axes[i] == axes[j] {
+                return false;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[rustfmt::skip]
+impl<
+    D1: Dim, D2: Dim, D3: Dim, D4: Dim, D5: Dim, D6: Dim, D7: Dim,
+    const I: isize, const J: isize, const K: isize, const L: isize, const M: isize, const N: isize, const O: isize,
+> PermuteShapeTo<
+    (
+        <Self as DimAt<I>>::Dim, <Self as DimAt<J>>::Dim, <Self as DimAt<K>>::Dim, <Self as DimAt<L>>::Dim,
+        <Self as DimAt<M>>::Dim, <Self as DimAt<N>>::Dim, <Self as DimAt<O>>::Dim,
+    ),
+    Axes7<I, J, K, L, M, N, O>,
+> for (D1, D2, D3, D4, D5, D6, D7)
+where
+    Self: DimAt<I> + DimAt<J> + DimAt<K> + DimAt<L> + DimAt<M> + DimAt<N> + DimAt<O>,
+{
+    const CHECK: () = assert!(unique_axes([I, J, K, L, M, N, O]), "Axes of a permutation must be unique");
+}
+
+#[rustfmt::skip]
+impl<
+    D1: Dim, D2: Dim, D3: Dim, D4: Dim, D5: Dim, D6: Dim, D7: Dim, D8: Dim,
+    const I: isize, const J: isize, const K: isize, const L: isize, const M: isize, const N: isize, const O: isize, const P: isize,
+> PermuteShapeTo<
+    (
+        <Self as DimAt<I>>::Dim, <Self as DimAt<J>>::Dim, <Self as DimAt<K>>::Dim, <Self as DimAt<L>>::Dim,
+        <Self as DimAt<M>>::Dim, <Self as DimAt<N>>::Dim, <Self as DimAt<O>>::Dim, <Self as DimAt<P>>::Dim,
+    ),
+    Axes8<I, J, K, L, M, N, O, P>,
+> for (D1, D2, D3, D4, D5, D6, D7, D8)
+where
+    Self: DimAt<I> + DimAt<J> + DimAt<K> + DimAt<L> + DimAt<M> + DimAt<N> + DimAt<O> + DimAt<P>,
+{
+    const CHECK: () = assert!(unique_axes([I, J, K, L, M, N, O, P]), "Axes of a permutation must be unique");
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,4 +277,20 @@ mod tests {
         assert_eq!(src.strides(), [6, 3, 1]);
         assert_eq!(dst_strides, [3, 1, 6]);
     }
+
+    #[test]
+    fn test_permute_8d() {
+        let src = (1, Const::<2>, 3, Const::<4>, 5, Const::<6>, 7, Const::<8>);
+        let dst = PermuteStridesTo::<_, Axes8<7, 6, 5, 4, 3, 2, 1, 0>>::permuted(&src);
+        assert_eq!(
+            dst,
+            (Const::<8>, 7, Const::<6>, 5, Const::<4>, 3, Const::<2>, 1)
+        );
+
+        let src = (1, Const::<2>, 3, Const::<4>, 5, Const::<6>, 7);
+        let dst_strides =
+            PermuteStridesTo::<_, Axes7<1, 0, 2, 3, 4, 6, 5>>::permute_strides(&src, src.strides());
+        assert_eq!(src.strides(), [5040, 2520, 840, 210, 42, 7, 1]);
+        assert_eq!(dst_strides, [2520, 5040, 840, 210, 42, 1, 7]);
+    }
 }
@@ -3,6 +3,9 @@ use super::*;
 /// Marker for shapes that can be converted using their concrete types.
 pub trait RealizeShapeTo<Dst: Shape>: Shape {
     fn realized(&self) -> Option<Dst>;
+
+    /// Converts `strides` of `self` into strides of the realized shape.
+    fn realized_strides(&self, strides: &Self::Concrete) -> Dst::Concrete;
 }
 
 impl<Src: Shape<Concrete = Dst::Concrete>, Dst: Shape> RealizeShapeTo<Dst> for Src {
@@ -10,4 +13,9 @@ impl<Src: Shape<Concrete = Dst::Concrete>, Dst: Shape> RealizeShapeTo<Dst> for S
     fn realized(&self) -> Option<Dst> {
         Dst::from_concrete(&self.concrete())
     }
+
+    #[inline(always)]
+    fn realized_strides(&self, strides: &Self::Concrete) -> Dst::Concrete {
+        *strides
+    }
 }
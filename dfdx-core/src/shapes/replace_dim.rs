@@ -120,7 +120,7 @@ macro_rules! replace_and_remove_all {
     }
 }
 
-replace_and_remove_all!([A B C D E F] [0 1 2 3 4 5]);
+replace_and_remove_all!([A B C D E F G H] [0 1 2 3 4 5 6 7]);
 
 // batched select
 impl<Batch: Dim, Seq: Dim, S1: Dim, S2: Dim> ReplaceDimTo<(Batch, Seq, S2), (Batch, Seq)>
@@ -93,6 +93,15 @@ where
     }
 }
 
+/// Without `generic_const_exprs` the product can only be known at runtime.
+#[cfg(not(feature = "nightly"))]
+impl<const N: usize, const M: usize> core::ops::Mul<Const<N>> for Const<M> {
+    type Output = usize;
+    fn mul(self, _: Const<N>) -> Self::Output {
+        M * N
+    }
+}
+
 impl<const N: usize> core::ops::Div<Const<N>> for usize {
     type Output = usize;
     fn div(self, _: Const<N>) -> Self::Output {
@@ -117,6 +126,15 @@ where
     }
 }
 
+/// Without `generic_const_exprs` the quotient can only be known at runtime.
+#[cfg(not(feature = "nightly"))]
+impl<const N: usize, const M: usize> core::ops::Div<Const<N>> for Const<M> {
+    type Output = usize;
+    fn div(self, _: Const<N>) -> Self::Output {
+        M / N
+    }
+}
+
 /// Represents either `[T; N]` or `Vec<T>`
 pub trait Array<T>: IntoIterator<Item = T> {
     type Dim: Dim;
@@ -195,6 +213,12 @@ pub trait Shape:
         }
         strides
     }
+
+    /// The size of each dimension. Unlike [Shape::concrete], this doesn't include the
+    /// padding of [super::DynShape].
+    fn dims(&self) -> std::vec::Vec<usize> {
+        self.concrete().into()
+    }
 }
 
 /// Represents a [Shape] that has all [ConstDim]s
@@ -235,6 +259,14 @@ pub type Rank5<const M: usize, const N: usize, const O: usize, const P: usize, c
 /// Compile time known shape with 6 dimensions
 pub type Rank6<const M: usize, const N: usize, const O: usize, const P: usize, const Q: usize, const R: usize> =
     (Const<M>, Const<N>, Const<O>, Const<P>, Const<Q>, Const<R>);
+#[rustfmt::skip]
+/// Compile time known shape with 7 dimensions
+pub type Rank7<const M: usize, const N: usize, const O: usize, const P: usize, const Q: usize, const R: usize, const S: usize> =
+    (Const<M>, Const<N>, Const<O>, Const<P>, Const<Q>, Const<R>, Const<S>);
+#[rustfmt::skip]
+/// Compile time known shape with 8 dimensions
+pub type Rank8<const M: usize, const N: usize, const O: usize, const P: usize, const Q: usize, const R: usize, const S: usize, const T: usize> =
+    (Const<M>, Const<N>, Const<O>, Const<P>, Const<Q>, Const<R>, Const<S>, Const<T>);
 
 macro_rules! shape {
     (($($D:tt $Idx:tt),*), rank=$Num:expr, all=$All:tt) => {
@@ -301,3 +333,5 @@ shape!((D1 0, D2 1, D3 2), rank=3, all=Axes3);
 shape!((D1 0, D2 1, D3 2, D4 3), rank=4, all=Axes4);
 shape!((D1 0, D2 1, D3 2, D4 3, D5 4), rank=5, all=Axes5);
 shape!((D1 0, D2 1, D3 2, D4 3, D5 4, D6 5), rank=6, all=Axes6);
+shape!((D1 0, D2 1, D3 2, D4 3, D5 4, D6 5, D7 6), rank=7, all=Axes7);
+shape!((D1 0, D2 1, D3 2, D4 3, D5 4, D6 5, D7 6, D8 7), rank=8, all=Axes8);
@@ -117,3 +117,5 @@ slice_shape!([D1 D2 D3] [R1 R2 R3] [0 1 2]);
 slice_shape!([D1 D2 D3 D4] [R1 R2 R3 R4] [0 1 2 3]);
 slice_shape!([D1 D2 D3 D4 D5] [R1 R2 R3 R4 R5] [0 1 2 3 4]);
 slice_shape!([D1 D2 D3 D4 D5 D6] [R1 R2 R3 R4 R5 R6] [0 1 2 3 4 5]);
+slice_shape!([D1 D2 D3 D4 D5 D6 D7] [R1 R2 R3 R4 R5 R6 R7] [0 1 2 3 4 5 6]);
+slice_shape!([D1 D2 D3 D4 D5 D6 D7 D8] [R1 R2 R3 R4 R5 R6 R7 R8] [0 1 2 3 4 5 6 7]);
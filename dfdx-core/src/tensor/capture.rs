@@ -0,0 +1,595 @@
+//! Recording the ops of a forward pass as a graph, which can be inspected or exported.
+
+use std::collections::BTreeMap;
+use std::{format, string::String, vec::Vec};
+
+use super::*;
+use crate::{
+    nn_traits::{ParamsVisitor, VisitParams},
+    shapes::{Axes, Dtype, Shape, Unit},
+    tensor_ops::{ops::KernelOpInfo, Device},
+};
+
+/// A tensor that an [OpNode] reads or writes. Views like permutes & broadcasts have the
+/// same id as the tensor they view, so they are told apart by their shape & strides.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TensorRef {
+    pub id: UniqueId,
+    pub shape: Vec<usize>,
+    pub strides: Vec<usize>,
+    /// The [core::any::type_name] of the dtype, e.g. `f32`.
+    pub dtype: &'static str,
+}
+
+impl TensorRef {
+    pub fn new<S: Shape, E, D: Storage<E>>(t: &impl Tensorlike<S, E, D>) -> Self {
+        let shape = t.shape().dims();
+        let strides: Vec<usize> = t.strides().into();
+        Self {
+            id: t.id(),
+            strides: strides[strides.len() - shape.len()..].into(),
+            shape,
+            dtype: core::any::type_name::<E>(),
+        }
+    }
+}
+
+/// The value of an attribute of an [OpNode], like the stride of a convolution.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Attr {
+    Int(i64),
+    Float(f64),
+    Ints(Vec<i64>),
+    String(String),
+}
+
+/// The whole data buffer of an input of an [OpNode], as a `Tensor<(usize,), E, D>`.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+pub(crate) struct Buffer(std::rc::Rc<dyn std::any::Any>);
+
+impl std::fmt::Debug for Buffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Buffer")
+    }
+}
+
+impl Buffer {
+    fn new<S: Shape, E: Unit, D: Storage<E>, T>(t: &Tensor<S, E, D, T>) -> Self {
+        let len = t.device.len(&t.data);
+        Self(std::rc::Rc::new(Tensor {
+            id: t.id,
+            data: t.data.clone(),
+            shape: (len,),
+            strides: [1],
+            device: t.device.clone(),
+            tape: NoneTape,
+        }))
+    }
+
+    #[cfg_attr(not(feature = "onnx"), allow(dead_code))]
+    pub(crate) fn get<E: Unit, D: Storage<E>>(&self) -> Option<&Tensor<(usize,), E, D>> {
+        self.0.downcast_ref()
+    }
+}
+
+/// An op recorded by a [CaptureTape].
+#[derive(Clone, Debug)]
+pub struct OpNode {
+    /// The name of the op, e.g. `MatMul` or `Exp`.
+    pub op: String,
+    pub inputs: Vec<TensorRef>,
+    pub output: TensorRef,
+    pub attrs: Vec<(String, Attr)>,
+    /// An estimate of the number of floating point operations.
+    pub flops: usize,
+    /// The data of the inputs, for the ones that are [Source]s of the graph.
+    pub(crate) buffers: Vec<Option<Buffer>>,
+}
+
+impl OpNode {
+    pub(crate) fn new(op: impl Into<String>) -> Self {
+        Self {
+            op: op.into(),
+            inputs: Vec::new(),
+            // set with [OpNode::output] once the op ran
+            output: TensorRef {
+                id: unique_id(),
+                shape: Vec::new(),
+                strides: Vec::new(),
+                dtype: "",
+            },
+            attrs: Vec::new(),
+            flops: 0,
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Names the op after an elementwise kernel op, with the op's attributes.
+    pub(crate) fn from_kernel_op<Op: KernelOpInfo>(op: &Op) -> Self {
+        let mut node = Self::new(Op::NAME);
+        for (key, value) in op.attrs() {
+            node = node.attr(key, value);
+        }
+        node
+    }
+
+    /// Adds an input, and keeps its data alive in case it isn't created by the graph.
+    pub(crate) fn input<S: Shape, E: Unit, D: Storage<E>, T>(
+        mut self,
+        t: &Tensor<S, E, D, T>,
+    ) -> Self {
+        self.inputs.push(TensorRef::new(t));
+        self.buffers.push(Some(Buffer::new(t)));
+        self
+    }
+
+    /// Adds an input without keeping its data, for views that don't compute anything.
+    pub(crate) fn view_input<S: Shape, E, D: Storage<E>>(
+        mut self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Self {
+        self.inputs.push(TensorRef::new(t));
+        self.buffers.push(None);
+        self
+    }
+
+    pub(crate) fn attr(mut self, key: impl Into<String>, value: Attr) -> Self {
+        self.attrs.push((key.into(), value));
+        self
+    }
+
+    pub(crate) fn axes<Ax: Axes>(self) -> Self {
+        let axes = Ax::as_array().into_iter().map(|a| a as i64).collect();
+        self.attr("axes", Attr::Ints(axes))
+    }
+
+    pub(crate) fn flops(mut self, flops: usize) -> Self {
+        self.flops = flops;
+        self
+    }
+
+    pub(crate) fn output<S: Shape, E, D: Storage<E>>(
+        mut self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Self {
+        self.output = TensorRef::new(t);
+        self
+    }
+
+    /// Sets the output, and counts one flop per element of it.
+    pub(crate) fn elementwise<S: Shape, E, D: Storage<E>>(
+        self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Self {
+        let flops = t.shape().num_elements();
+        self.output(t).flops(flops)
+    }
+}
+
+/// A [Tape] that records every op as an [OpNode] instead of recording backward ops,
+/// so the ops a model runs can be inspected, e.g. to visualize it or count its flops.
+/// Start with [Tensor::capture], and get the [OpGraph] with [Tensor::split_graph].
+/// Like [OwnedTape], this keeps the inputs of the recorded ops alive until then.
+///
+/// Like with [NoneTape], modules run in inference mode. Elementwise ops, matmuls,
+/// reductions, reshapes, permutes, broadcasts, convolutions, pooling and select/gather
+/// are recorded; other ops only show up through the tensors they create. Tensors without
+/// the tape, like parameters, are [Source]s of the graph, in the view they were used with.
+///
+/// Example:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let w: Tensor<Rank2<3, 4>, f32, _> = dev.sample_normal();
+/// let x: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+/// let y = x.capture().matmul(w.clone()).relu().sum::<Rank0, _>();
+/// let (y, mut graph) = y.split_graph();
+/// graph.name(&w, "w");
+/// let ops: Vec<_> = graph.nodes.iter().map(|n| n.op.as_str()).collect();
+/// assert_eq!(ops, ["MatMul", "ReLU", "Sum"]);
+/// assert_eq!(graph.total_flops(), 2 * 2 * 3 * 4 + 8 + 8);
+/// println!("{}", graph.to_dot());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CaptureTape {
+    /// Keyed by when the op ran, so that merging the tapes of two branches keeps the order
+    /// and drops the ops they have in common.
+    nodes: BTreeMap<UniqueId, OpNode>,
+}
+
+impl<E, D: Storage<E>> Tape<E, D> for CaptureTape {
+    const OWNS_TAPE: bool = false;
+    fn add_backward_op<F>(&mut self, _: F)
+    where
+        F: 'static + FnOnce(&mut Gradients<E, D>) -> Result<(), Error>,
+    {
+    }
+
+    fn captures_ops(&self) -> bool {
+        true
+    }
+
+    fn add_op_node(&mut self, node: OpNode) {
+        self.nodes.insert(unique_id(), node);
+    }
+}
+
+impl Merge<NoneTape> for CaptureTape {
+    fn merge(self, _: NoneTape) -> Self {
+        self
+    }
+}
+
+impl Merge<CaptureTape> for CaptureTape {
+    fn merge(mut self, mut other: Self) -> Self {
+        self.nodes.append(&mut other.nodes);
+        self
+    }
+}
+
+impl<S: Shape, E, D: Storage<E>> Tensor<S, E, D, NoneTape> {
+    /// Starts recording the ops that use this tensor, see [CaptureTape].
+    pub fn capture(self) -> Tensor<S, E, D, CaptureTape> {
+        self.put_tape(Default::default())
+    }
+}
+
+impl<S: Shape, E: Clone, D: Storage<E>> Tensor<S, E, D, CaptureTape> {
+    /// Splits into the tensor and the graph of the ops that computed it.
+    pub fn split_graph(self) -> (Tensor<S, E, D>, OpGraph) {
+        let (t, tape) = self.split_tape();
+        let graph = OpGraph::new(tape.nodes.into_values().collect(), TensorRef::new(&t));
+        (t, graph)
+    }
+}
+
+/// Where an input of an [OpNode] comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Value {
+    /// An index into [OpGraph::sources].
+    Source(usize),
+    /// The output of the node at this index of [OpGraph::nodes].
+    Node(usize),
+}
+
+/// A tensor that was used by the graph without being created by it, like the input
+/// or a parameter.
+#[derive(Clone, Debug)]
+pub struct Source {
+    /// Set with [OpGraph::name] or [OpGraph::name_params].
+    pub name: Option<String>,
+    /// How the tensor was first used. Views of it have the same id.
+    pub tensor: TensorRef,
+    pub(crate) buffer: Option<Buffer>,
+}
+
+/// The ops recorded by a [CaptureTape], in the order they ran.
+#[derive(Clone, Debug)]
+pub struct OpGraph {
+    pub nodes: Vec<OpNode>,
+    pub sources: Vec<Source>,
+    /// Where each input of each node comes from.
+    pub node_inputs: Vec<Vec<Value>>,
+    /// The tensor [Tensor::split_graph] was called on.
+    pub output: Value,
+}
+
+impl OpGraph {
+    fn new(nodes: Vec<OpNode>, output: TensorRef) -> Self {
+        let mut sources: Vec<Source> = Vec::new();
+        let mut value_of = |tensor: &TensorRef, buffer: Option<&Buffer>, nodes: &[OpNode]| {
+            // the last node before this one that created the exact same view
+            if let Some(i) = nodes.iter().rposition(|n| &n.output == tensor) {
+                return Value::Node(i);
+            }
+            match sources.iter().position(|s| s.tensor.id == tensor.id) {
+                Some(i) => {
+                    if sources[i].buffer.is_none() {
+                        sources[i].buffer = buffer.cloned();
+                    }
+                    Value::Source(i)
+                }
+                None => {
+                    sources.push(Source {
+                        name: None,
+                        tensor: tensor.clone(),
+                        buffer: buffer.cloned(),
+                    });
+                    Value::Source(sources.len() - 1)
+                }
+            }
+        };
+        let node_inputs = (0..nodes.len())
+            .map(|i| {
+                let node = &nodes[i];
+                let inputs = node.inputs.iter().zip(node.buffers.iter());
+                inputs
+                    .map(|(t, b)| value_of(t, b.as_ref(), &nodes[..i]))
+                    .collect()
+            })
+            .collect();
+        let output = value_of(&output, None, &nodes);
+        Self {
+            nodes,
+            sources,
+            node_inputs,
+            output,
+        }
+    }
+
+    /// Names the source with the same id as `t`, e.g. the input of a model.
+    pub fn name<S: Shape, E, D: Storage<E>>(
+        &mut self,
+        t: &impl Tensorlike<S, E, D>,
+        name: impl Into<String>,
+    ) {
+        let name = name.into();
+        for source in self.sources.iter_mut().filter(|s| s.tensor.id == t.id()) {
+            source.name = Some(name.clone());
+        }
+    }
+
+    /// Names the sources that are parameters of `model` after their location in it,
+    /// e.g. `0.weight`.
+    pub fn name_params<E: Dtype, D: Device<E>, M: VisitParams<E, D>>(&mut self, model: &M) {
+        struct Namer<'a>(&'a mut OpGraph);
+        impl<'a, E: Dtype, D: Device<E>> ParamsVisitor<E, D> for Namer<'a> {
+            fn visit_param<S: Shape>(
+                &mut self,
+                location: &str,
+                t: &Tensor<S, E, D>,
+            ) -> Result<(), Error> {
+                self.0.name(t, location);
+                Ok(())
+            }
+        }
+        model.visit_params(&mut Namer(self));
+    }
+
+    /// The sum of the flops of every node.
+    pub fn total_flops(&self) -> usize {
+        self.nodes.iter().map(|n| n.flops).sum()
+    }
+
+    fn source_label(&self, i: usize) -> String {
+        match &self.sources[i].name {
+            Some(name) => name.clone(),
+            None => format!("input {i}"),
+        }
+    }
+
+    /// Exports the graph in the [Graphviz](https://graphviz.org) DOT format, with
+    /// the sources as ellipses and the ops as boxes. Edges are labeled with the shape of
+    /// the tensor.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph {\n    node [shape=box];\n");
+        for (i, source) in self.sources.iter().enumerate() {
+            dot += &format!(
+                "    s{i} [shape=ellipse, label=\"{}\\n{:?} {}\"];\n",
+                escape(&self.source_label(i)),
+                source.tensor.shape,
+                source.tensor.dtype
+            );
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            let mut label = escape(&node.op);
+            for (key, value) in node.attrs.iter() {
+                let value = match value {
+                    Attr::Int(v) => format!("{v}"),
+                    Attr::Float(v) => format!("{v}"),
+                    Attr::Ints(v) => format!("{v:?}"),
+                    Attr::String(v) => escape(v),
+                };
+                label += &format!("\\n{}={value}", escape(key));
+            }
+            dot += &format!("    n{i} [label=\"{label}\"];\n");
+            for (value, tensor) in self.node_inputs[i].iter().zip(node.inputs.iter()) {
+                let from = match value {
+                    Value::Source(j) => format!("s{j}"),
+                    Value::Node(j) => format!("n{j}"),
+                };
+                dot += &format!("    {from} -> n{i} [label=\"{:?}\"];\n", tensor.shape);
+            }
+        }
+        dot += "}\n";
+        dot
+    }
+
+    /// Exports the graph as JSON, which looks like:
+    /// ```json
+    /// {
+    ///   "sources": [{"name": "0.weight", "tensor": {"shape": [4, 3], "strides": [3, 1], "dtype": "f32"}}],
+    ///   "nodes": [{
+    ///     "op": "MatMul",
+    ///     "inputs": [{"source": 1, "tensor": {...}}, {"node": 0, "tensor": {...}}],
+    ///     "output": {"shape": [2, 4], "strides": [4, 1], "dtype": "f32"},
+    ///     "attrs": {},
+    ///     "flops": 48
+    ///   }],
+    ///   "output": {"node": 1}
+    /// }
+    /// ```
+    /// Sources without a name have a `null` name.
+    pub fn to_json(&self) -> String {
+        let sources: Vec<String> = self
+            .sources
+            .iter()
+            .map(|s| {
+                let name = match &s.name {
+                    Some(name) => format!("\"{}\"", escape(name)),
+                    None => "null".into(),
+                };
+                format!(
+                    "{{\"name\": {name}, \"tensor\": {}}}",
+                    tensor_json(&s.tensor)
+                )
+            })
+            .collect();
+        let nodes: Vec<String> = self
+            .nodes
+            .iter()
+            .zip(self.node_inputs.iter())
+            .map(|(node, values)| {
+                let inputs: Vec<String> = values
+                    .iter()
+                    .zip(node.inputs.iter())
+                    .map(|(value, tensor)| {
+                        format!("{{{}, \"tensor\": {}}}", value_json(value), tensor_json(tensor))
+                    })
+                    .collect();
+                let attrs: Vec<String> = node
+                    .attrs
+                    .iter()
+                    .map(|(key, value)| {
+                        let value = match value {
+                            Attr::Int(v) => format!("{v}"),
+                            Attr::Float(v) if v.is_finite() => format!("{v:?}"),
+                            Attr::Float(v) => format!("\"{v}\""),
+                            Attr::Ints(v) => format!("{v:?}"),
+                            Attr::String(v) => format!("\"{}\"", escape(v)),
+                        };
+                        format!("\"{}\": {value}", escape(key))
+                    })
+                    .collect();
+                format!(
+                    "{{\"op\": \"{}\", \"inputs\": [{}], \"output\": {}, \"attrs\": {{{}}}, \"flops\": {}}}",
+                    escape(&node.op),
+                    inputs.join(", "),
+                    tensor_json(&node.output),
+                    attrs.join(", "),
+                    node.flops
+                )
+            })
+            .collect();
+        format!(
+            "{{\"sources\": [{}], \"nodes\": [{}], \"output\": {{{}}}}}",
+            sources.join(", "),
+            nodes.join(", "),
+            value_json(&self.output)
+        )
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn tensor_json(t: &TensorRef) -> String {
+    format!(
+        "{{\"shape\": {:?}, \"strides\": {:?}, \"dtype\": \"{}\"}}",
+        t.shape,
+        t.strides,
+        escape(t.dtype)
+    )
+}
+
+fn value_json(value: &Value) -> String {
+    match value {
+        Value::Source(i) => format!("\"source\": {i}"),
+        Value::Node(i) => format!("\"node\": {i}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_capture_linear() {
+        let dev: TestDevice = Default::default();
+        let w: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank1<4>, TestDtype, _> = dev.sample_normal();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+
+        let y = x.clone().capture().matmul(w.clone().permute()) + b.clone().broadcast();
+        let (y, mut graph) = y.tanh().split_graph();
+        assert_close_to_tensor!(
+            y,
+            (x.clone().matmul(w.clone().permute()) + b.clone().broadcast()).tanh()
+        );
+        graph.name(&x, "x");
+        graph.name(&w, "weight");
+        graph.name(&b, "bias");
+
+        let ops: Vec<_> = graph.nodes.iter().map(|n| n.op.as_str()).collect();
+        assert_eq!(ops, ["MatMul", "BinaryAdd", "Tanh"]);
+        let names: Vec<_> = graph.sources.iter().map(|s| s.name.clone()).collect();
+        assert_eq!(
+            names,
+            [Some("x".into()), Some("weight".into()), Some("bias".into())]
+        );
+        assert_eq!(
+            graph.node_inputs,
+            [
+                vec![Value::Source(0), Value::Source(1)],
+                vec![Value::Node(0), Value::Source(2)],
+                vec![Value::Node(1)],
+            ]
+        );
+        assert_eq!(graph.output, Value::Node(2));
+
+        // the weight & bias don't have the tape, so they show up as the views that are used
+        assert_eq!(graph.sources[1].tensor.shape, [3, 4]);
+        assert_eq!(graph.sources[1].tensor.strides, [1, 3]);
+        assert_eq!(graph.sources[2].tensor.shape, [2, 4]);
+        assert_eq!(graph.sources[2].tensor.strides, [0, 1]);
+        assert_eq!(graph.nodes[2].output.shape, [2, 4]);
+        assert_eq!(graph.total_flops(), 2 * 2 * 3 * 4 + 8 + 8);
+    }
+
+    #[test]
+    fn test_capture_branches_and_attrs() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+
+        let x = x.capture().exp();
+        let a = x.clone().sum::<Rank1<2>, _>();
+        let b = (x * 2.0).max::<Rank1<2>, _>();
+        let (_, graph) = (a - b).reshape::<Rank2<1, 2>>().split_graph();
+
+        // the exp both branches have in common is only recorded once
+        let ops: Vec<_> = graph.nodes.iter().map(|n| n.op.as_str()).collect();
+        assert_eq!(
+            ops,
+            ["Exp", "Sum", "ScalarMul", "Max", "BinarySub", "Reshape"]
+        );
+        assert_eq!(graph.nodes[2].attrs, [("scalar".into(), Attr::Float(2.0))]);
+        assert_eq!(graph.nodes[1].attrs, [("axes".into(), Attr::Ints(vec![1]))]);
+        assert_eq!(graph.node_inputs[3], [Value::Node(2)]);
+        assert_eq!(graph.node_inputs[4], [Value::Node(1), Value::Node(3)]);
+        assert_eq!(graph.output, Value::Node(5));
+        assert_eq!(graph.nodes[5].output.shape, [1, 2]);
+        assert_eq!(
+            graph.nodes[0].output.dtype,
+            core::any::type_name::<TestDtype>()
+        );
+    }
+
+    #[test]
+    fn test_capture_export() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let (_, mut graph) = x.clone().capture().powi(2).split_graph();
+        graph.name(&x, "x \"quoted\"");
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("s0 [shape=ellipse, label=\"x \\\"quoted\\\"\\n[3]"));
+        assert!(dot.contains("n0 [label=\"Powi\\nexponent=2\"];"));
+        assert!(dot.contains("s0 -> n0 [label=\"[3]\"];"));
+
+        let json = graph.to_json();
+        let dtype = core::any::type_name::<TestDtype>();
+        assert_eq!(
+            json,
+            format!(
+                "{{\"sources\": [{{\"name\": \"x \\\"quoted\\\"\", \"tensor\": {{\"shape\": [3], \"strides\": [1], \"dtype\": \"{dtype}\"}}}}], \
+                \"nodes\": [{{\"op\": \"Powi\", \"inputs\": [{{\"source\": 0, \"tensor\": {{\"shape\": [3], \"strides\": [1], \"dtype\": \"{dtype}\"}}}}], \
+                \"output\": {{\"shape\": [3], \"strides\": [1], \"dtype\": \"{dtype}\"}}, \"attrs\": {{\"exponent\": 2}}, \"flops\": 3}}], \
+                \"output\": {{\"node\": 0}}}}"
+            )
+        );
+    }
+}
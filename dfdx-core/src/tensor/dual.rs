@@ -0,0 +1,405 @@
+//! Forward mode differentiation, where every tensor carries a tangent along with it.
+
+use std::collections::BTreeMap;
+
+use super::*;
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor_ops::{reshape_to::try_scatter_like, Device, ReshapeTo},
+};
+
+/// The tangents of tensors traced with a [DualTape], keyed by the tensor's [UniqueId].
+/// Like [Gradients], each tangent is laid out like the data of its tensor, so views
+/// like broadcasts & permutes have the tangent of the tensor they view.
+pub struct Tangents<E, D: Storage<E>> {
+    tangent_by_id: BTreeMap<UniqueId, Tensor<(usize,), E, D>>,
+}
+
+impl<E, D: Storage<E>> Default for Tangents<E, D> {
+    fn default() -> Self {
+        Self {
+            tangent_by_id: Default::default(),
+        }
+    }
+}
+
+impl<E: Clone, D: Storage<E>> Clone for Tangents<E, D> {
+    fn clone(&self) -> Self {
+        Self {
+            tangent_by_id: self.tangent_by_id.clone(),
+        }
+    }
+}
+
+impl<E: Dtype, D: Device<E>> Tangents<E, D> {
+    /// The tangent of `t` viewed with its shape & strides.
+    pub(crate) fn get<S: Shape>(
+        &self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Result<Option<Tensor<S, E, D>>, Error> {
+        match self.tangent_by_id.get(&t.id()) {
+            Some(tangent) => Ok(Some(
+                Tensor {
+                    id: tangent.id,
+                    data: tangent.data.clone(),
+                    shape: *t.shape(),
+                    strides: t.strides(),
+                    device: tangent.device.clone(),
+                    tape: NoneTape,
+                }
+                .try_contiguous()?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// The tangent of the data of `t`.
+    pub(crate) fn get_flat<S: Shape>(
+        &self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Option<Tensor<(usize,), E, D>> {
+        self.tangent_by_id.get(&t.id()).cloned()
+    }
+
+    /// Sets the tangent of `t`, where `tangent` has the shape of `t`.
+    pub(crate) fn try_set<S: Shape>(
+        &mut self,
+        t: &impl Tensorlike<S, E, D>,
+        tangent: Tensor<S, E, D>,
+    ) -> Result<(), Error> {
+        let tangent = if t.strides() == t.shape().strides() {
+            tangent.try_reshape_like(&(t.len(),))?
+        } else {
+            try_scatter_like(tangent, t)?
+        };
+        self.set_flat(t, tangent);
+        Ok(())
+    }
+
+    /// Sets the tangent of `t`, where `tangent` is laid out like the data of `t`.
+    pub(crate) fn set_flat<S: Shape>(
+        &mut self,
+        t: &impl Tensorlike<S, E, D>,
+        tangent: Tensor<(usize,), E, D>,
+    ) {
+        self.tangent_by_id.insert(t.id(), tangent);
+    }
+}
+
+/// A [Tape] for forward mode differentiation. Instead of recording backward ops, every op
+/// computes the tangent of its output from the tangents of its inputs right away
+/// (see [GraphBackward::jvp]), so nothing has to be kept alive for a backward pass.
+///
+/// This is cheaper than reverse mode (i.e. [OwnedTape]) when a function has fewer inputs
+/// than outputs, e.g. for Jacobian-vector products. Start with [Tensor::dual], or use [jvp].
+///
+/// The same ops as [Tensor::record_graph] are supported. Using any other op makes
+/// [Tensor::try_split_tangent] return [Error::UnsupportedGraphBackward]. Modules run in
+/// inference mode like they do with [NoneTape], since this doesn't own a tape.
+pub struct DualTape<E, D: Storage<E>> {
+    pub(crate) tangents: Tangents<E, D>,
+    /// Set by every op when it adds its backward op, and cleared when it adds its graph op.
+    missing_jvp: bool,
+    error: Option<Error>,
+}
+
+impl<E, D: Storage<E>> Default for DualTape<E, D> {
+    fn default() -> Self {
+        Self {
+            tangents: Default::default(),
+            missing_jvp: false,
+            error: None,
+        }
+    }
+}
+
+impl<E, D: Storage<E>> std::fmt::Debug for DualTape<E, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DualTape")
+            .field("num_tangents", &self.tangents.tangent_by_id.len())
+            .field("missing_jvp", &self.missing_jvp)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl<E, D: Storage<E>> DualTape<E, D> {
+    fn check_jvp(&mut self) {
+        if self.missing_jvp && self.error.is_none() {
+            self.error = Some(Error::UnsupportedGraphBackward);
+        }
+    }
+}
+
+impl<E: Dtype, D: Device<E>> Tape<E, D> for DualTape<E, D> {
+    const OWNS_TAPE: bool = false;
+    fn add_backward_op<F>(&mut self, _: F)
+    where
+        F: 'static + FnOnce(&mut Gradients<E, D>) -> Result<(), Error>,
+    {
+        self.check_jvp();
+        self.missing_jvp = true;
+    }
+
+    fn records_graph(&self) -> bool {
+        true
+    }
+
+    fn add_graph_op<G: GraphBackward<E, D>>(&mut self, operation: G) {
+        self.missing_jvp = false;
+        if self.error.is_none() {
+            if let Err(e) = operation.jvp(&mut self.tangents) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Keeps the tangents, since they belong to the tensors rather than to any op.
+    fn empty_like(&self) -> Self {
+        Self {
+            tangents: self.tangents.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl<E, D: Storage<E>> Merge<NoneTape> for DualTape<E, D> {
+    fn merge(self, _: NoneTape) -> Self {
+        self
+    }
+}
+
+impl<E, D: Storage<E>> Merge<DualTape<E, D>> for DualTape<E, D> {
+    fn merge(mut self, mut other: Self) -> Self {
+        self.check_jvp();
+        other.check_jvp();
+        self.tangents
+            .tangent_by_id
+            .append(&mut other.tangents.tangent_by_id);
+        self.error = self.error.or(other.error);
+        self
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> Tensor<S, E, D, NoneTape> {
+    /// Starts forward mode differentiation with `tangent` as the tangent of this tensor,
+    /// see [DualTape].
+    pub fn dual(self, tangent: Tensor<S, E, D>) -> Tensor<S, E, D, DualTape<E, D>> {
+        self.try_dual(tangent).unwrap()
+    }
+
+    /// Fallible version of [Tensor::dual]
+    pub fn try_dual(
+        self,
+        tangent: Tensor<S, E, D>,
+    ) -> Result<Tensor<S, E, D, DualTape<E, D>>, Error> {
+        assert_eq!(self.shape, tangent.shape);
+        let mut tape = DualTape::default();
+        tape.tangents.try_set(&self, tangent)?;
+        Ok(self.put_tape(tape))
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>> Tensor<S, E, D, DualTape<E, D>> {
+    /// Splits into the tensor and its tangent, which is zeros if it doesn't depend on
+    /// the tensors passed to [Tensor::dual].
+    pub fn split_tangent(self) -> (Tensor<S, E, D>, Tensor<S, E, D>) {
+        self.try_split_tangent().unwrap()
+    }
+
+    /// Fallible version of [Tensor::split_tangent]. Returns
+    /// [Error::UnsupportedGraphBackward] if an op without forward mode support was used.
+    #[allow(clippy::type_complexity)]
+    pub fn try_split_tangent(self) -> Result<(Tensor<S, E, D>, Tensor<S, E, D>), Error> {
+        let (t, mut tape) = self.split_tape();
+        tape.check_jvp();
+        if let Some(e) = tape.error {
+            return Err(e);
+        }
+        let tangent = match tape.tangents.get(&t)? {
+            Some(tangent) => tangent,
+            None => t.device.try_zeros_like(&t.shape)?,
+        };
+        Ok((t, tangent))
+    }
+}
+
+/// Computes `f(x)` and the Jacobian-vector product `J_f(x) * v`, using forward mode
+/// differentiation (see [DualTape]).
+///
+/// Example:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+/// let v: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 0.0, 0.5]);
+/// let (y, jv) = jvp(|x| x.square() * 0.5, x, v);
+/// assert_eq!(y.array(), [0.5, 2.0, 4.5]);
+/// assert_eq!(jv.array(), [1.0, 0.0, 1.5]);
+/// ```
+pub fn jvp<S: Shape, Out: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: Tensor<S, E, D>,
+    v: Tensor<S, E, D>,
+) -> (Tensor<Out, E, D>, Tensor<Out, E, D>)
+where
+    F: FnOnce(Tensor<S, E, D, DualTape<E, D>>) -> Tensor<Out, E, D, DualTape<E, D>>,
+{
+    try_jvp(|x| Ok(f(x)), x, v).unwrap()
+}
+
+/// Fallible version of [jvp]
+#[allow(clippy::type_complexity)]
+pub fn try_jvp<S: Shape, Out: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: Tensor<S, E, D>,
+    v: Tensor<S, E, D>,
+) -> Result<(Tensor<Out, E, D>, Tensor<Out, E, D>), Error>
+where
+    F: FnOnce(Tensor<S, E, D, DualTape<E, D>>) -> Result<Tensor<Out, E, D, DualTape<E, D>>, Error>,
+{
+    f(x.try_dual(v)?)?.try_split_tangent()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor_ops::*, tests::*};
+
+    /// Checks the tangent against central finite differences.
+    fn check_jvp<S: Shape, Out: Shape>(
+        f: impl Fn(Tensor<S, f64, Cpu, DualTape<f64, Cpu>>) -> Tensor<Out, f64, Cpu, DualTape<f64, Cpu>>,
+        x: Tensor<S, f64, Cpu>,
+        v: Tensor<S, f64, Cpu>,
+    ) {
+        let eps = 1e-6;
+        let (y, jv) = jvp(&f, x.clone(), v.clone());
+        let (y_plus, _) = jvp(&f, x.clone() + v.clone() * eps, v.clone());
+        let (y_minus, _) = jvp(&f, x - v.clone() * eps, v);
+        let fd = (y_plus - y_minus) / (2.0 * eps);
+        for (a, b) in jv.as_vec().into_iter().zip(fd.as_vec()) {
+            assert!((a - b).abs() < 1e-6, "{a} vs {b}");
+        }
+        assert_eq!(y.shape(), jv.shape());
+    }
+
+    #[test]
+    fn test_jvp_unary_and_binary() {
+        let dev: Cpu = Default::default();
+        let x: Tensor<Rank2<2, 3>, f64, _> = dev.sample_normal();
+        let v: Tensor<Rank2<2, 3>, f64, _> = dev.sample_normal();
+        let w: Tensor<Rank2<2, 3>, f64, _> = dev.sample_normal();
+        check_jvp(
+            |x| x.with_empty_tape().exp().sin() * 2.0 - x.square().cos(),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(
+            |x| (x.with_empty_tape() * w.clone()) / (x.square() + 1.0),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(
+            |x| x.with_empty_tape().sigmoid() * x.tanh(),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(
+            |x| (x.abs() + 0.5).sqrt().powf(1.5).ln(),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(|x| x.relu().recip().negate(), x.square() + 1.0, v);
+    }
+
+    #[test]
+    fn test_jvp_matmul_broadcast_and_reductions() {
+        let dev: Cpu = Default::default();
+        let x: Tensor<Rank2<4, 3>, f64, _> = dev.sample_normal();
+        let v: Tensor<Rank2<4, 3>, f64, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 5>, f64, _> = dev.sample_normal();
+        let b: Tensor<Rank1<5>, f64, _> = dev.sample_normal();
+
+        check_jvp(
+            |x| (x.matmul(w.clone()) + b.clone().broadcast()).tanh(),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(
+            |x| x.with_empty_tape().matmul(x.permute::<Rank2<3, 4>, _>()),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(
+            |x| x.with_empty_tape().sum::<Rank1<4>, _>() * x.max::<Rank1<4>, _>(),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(
+            |x| {
+                x.broadcast::<Rank3<2, 4, 3>, _>()
+                    .exp()
+                    .mean::<Rank1<3>, _>()
+            },
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(|x| x.softmax::<Axis<1>>(), x.clone(), v.clone());
+        check_jvp(
+            |x| x.normalize::<Axis<1>>(1e-5).log_softmax::<Axis<0>>(),
+            x.clone(),
+            v.clone(),
+        );
+        check_jvp(
+            |x| {
+                x.permute::<Rank2<3, 4>, _>()
+                    .exp()
+                    .reshape::<Rank1<12>>()
+                    .min::<Rank0, _>()
+            },
+            x,
+            v,
+        );
+    }
+
+    #[test]
+    fn test_jvp_matches_reverse_mode() {
+        // u^T (J v) == (u^T J) v
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let v: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let u: Tensor<Rank1<2>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+
+        let (_, jv) = jvp(
+            |x| x.matmul(w.clone()).sin().sum::<Rank1<2>, _>(),
+            x.clone(),
+            v.clone(),
+        );
+        let grads = x
+            .leaky_trace()
+            .matmul(w.clone())
+            .sin()
+            .sum::<Rank1<2>, _>()
+            .backward_with(u.clone());
+        let lhs = (jv * u).sum::<Rank0, _>();
+        let rhs = (grads.get(&x) * v).sum::<Rank0, _>();
+        assert_close_to_tensor!(lhs, rhs, 1e-4);
+    }
+
+    #[test]
+    fn test_jvp_constants_and_unsupported_ops() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let v: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+
+        // the output doesn't depend on x
+        let (_, jv) = jvp(|x| x.with_empty_tape() - x, x.clone(), v.clone());
+        assert_close_to_literal!(jv, [0.0; 3]);
+
+        let res = try_jvp(|x| Ok(x.fast_gelu().exp()), x.clone(), v.clone());
+        assert!(matches!(res, Err(Error::UnsupportedGraphBackward)));
+        let res = try_jvp(|x| Ok(x.exp().fast_gelu()), x, v);
+        assert!(matches!(res, Err(Error::UnsupportedGraphBackward)));
+    }
+}
@@ -9,6 +9,9 @@ pub enum Error {
     WrongNumElements,
     /// Some tensors were unused by an optimizer in a graph.
     UnusedTensors(std::vec::Vec<crate::tensor::UniqueId>),
+    /// An op that doesn't support [crate::tensor::Tensor::backward_with_graph] or forward mode
+    /// differentiation with [crate::tensor::DualTape] was recorded.
+    UnsupportedGraphBackward,
     #[cfg(feature = "cuda")]
     CublasError(cudarc::cublas::result::CublasError),
     #[cfg(feature = "cuda")]
@@ -5,7 +5,7 @@ use std::collections::{BTreeMap, BTreeSet};
 use std::{boxed::Box, vec::Vec};
 
 use super::tensorlike::Tensorlike;
-use super::{storage_traits::Storage, unique_id, Error, Tensor, UniqueId};
+use super::{storage_traits::Storage, unique_id, Error, GraphBackward, OpNode, Tensor, UniqueId};
 use crate::shapes::Shape;
 
 /// A generic container for keeping gradients of tensors keyed by the
@@ -114,6 +114,25 @@ impl<E, D: Storage<E>> Gradients<E, D> {
         }
     }
 
+    /// Replaces the gradient of `t` with the data of `grad`, which must have the same strides as `t`.
+    pub fn set<S: Shape>(&mut self, t: &impl Tensorlike<S, E, D>, grad: Tensor<S, E, D>) {
+        assert_eq!(t.strides(), grad.strides);
+        let data = std::sync::Arc::try_unwrap(grad.data).unwrap_or_else(|data| (*data).clone());
+        self.gradient_by_id.insert(t.id(), data);
+    }
+
+    /// Copies the gradient of `src` to `dst`, which must have the same shape & strides.
+    pub(crate) fn copy_grad<S: Shape>(
+        &mut self,
+        src: &impl Tensorlike<S, E, D>,
+        dst: &impl Tensorlike<S, E, D>,
+    ) -> Result<(), Error> {
+        self.try_alloc_for(src)?;
+        let grad = self.get_ref(src).clone();
+        self.gradient_by_id.insert(dst.id(), grad);
+        Ok(())
+    }
+
     /// Borrows a pair of a gradients `(&mut L, &R)`.
     /// `l` is the gradient to update, and `r` is the gradient to backprop.
     ///
@@ -181,6 +200,9 @@ pub struct OwnedTape<E, D: Storage<E>> {
     /// from merged tapes are executed in the correct order.
     pub(crate) operations: Vec<(UniqueId, BackwardOp<E, D>)>,
     pub(crate) gradients: Gradients<E, D>,
+    /// The differentiable versions of [OwnedTape::operations], keyed by the same time.
+    /// `None` unless the graph is being recorded, see [Tensor::record_graph].
+    pub(crate) graph_operations: Option<BTreeMap<UniqueId, GraphOp<E, D>>>,
 }
 
 impl<E, D: Storage<E>> Default for OwnedTape<E, D> {
@@ -188,6 +210,7 @@ impl<E, D: Storage<E>> Default for OwnedTape<E, D> {
         Self {
             operations: Default::default(),
             gradients: Gradients::leaky(),
+            graph_operations: None,
         }
     }
 }
@@ -197,6 +220,7 @@ impl<E: std::fmt::Debug, D: Storage<E>> std::fmt::Debug for OwnedTape<E, D> {
         f.debug_struct("OwnedTape")
             .field("num_operations", &self.operations.len())
             .field("gradients", &self.gradients)
+            .field("records_graph", &self.graph_operations.is_some())
             .finish()
     }
 }
@@ -206,6 +230,7 @@ impl<E, D: Storage<E>> From<Gradients<E, D>> for OwnedTape<E, D> {
         Self {
             operations: Default::default(),
             gradients,
+            graph_operations: None,
         }
     }
 }
@@ -215,6 +240,13 @@ impl<E, D: Storage<E>> OwnedTape<E, D> {
     ///
     /// Note that this method takes ownership of self, so it can't be called twice!
     pub(crate) fn execute(&mut self) -> Result<Gradients<E, D>, Error> {
+        let mut gradients = std::mem::replace(&mut self.gradients, Gradients::leaky());
+        self.execute_into(&mut gradients)?;
+        Ok(gradients)
+    }
+
+    /// Runs all the operations on `gradients` instead of the tape's own [Gradients].
+    pub(crate) fn execute_into(&mut self, gradients: &mut Gradients<E, D>) -> Result<(), Error> {
         // We must ensure that the operations are sorted in execution time order.
         // Otherwise an backward operation may not be executed in the right order
         // if multiple tapes were merged together.
@@ -222,13 +254,14 @@ impl<E, D: Storage<E>> OwnedTape<E, D> {
         // In case the same operation is present multiple times, we dedup it.
         self.operations.dedup_by_key(|(k, _)| *k);
         for (_, operation) in self.operations.drain(..).rev() {
-            (operation)(&mut self.gradients)?;
+            (operation)(gradients)?;
         }
-        Ok(std::mem::replace(&mut self.gradients, Gradients::leaky()))
+        Ok(())
     }
 }
 
 type BackwardOp<E, D> = Box<dyn FnOnce(&mut Gradients<E, D>) -> Result<(), Error>>;
+pub(crate) type GraphOp<E, D> = Box<dyn GraphBackward<E, D>>;
 
 /// Contains nothing. When [Tape::add_backward_op] is called, this struct does nothing.
 #[derive(Default, Debug, Clone, Copy)]
@@ -241,6 +274,27 @@ pub trait Tape<E, D: Storage<E>>: Default + Merge<Self> + Merge<NoneTape> {
     fn add_backward_op<F>(&mut self, operation: F)
     where
         F: 'static + FnOnce(&mut Gradients<E, D>) -> Result<(), Error>;
+
+    /// Whether [Tape::add_graph_op] records anything, see [Tensor::record_graph].
+    fn records_graph(&self) -> bool {
+        false
+    }
+
+    /// Attaches a differentiable version of the backward op that was added last.
+    fn add_graph_op<G: GraphBackward<E, D>>(&mut self, _operation: G) {}
+
+    /// A tape without any operations, that records the same things as this one.
+    fn empty_like(&self) -> Self {
+        Default::default()
+    }
+
+    /// Whether ops should describe themselves with [Tape::add_op_node]. See [CaptureTape].
+    fn captures_ops(&self) -> bool {
+        false
+    }
+
+    /// Records the op that was just run.
+    fn add_op_node(&mut self, _node: OpNode) {}
 }
 
 impl<E, D: Storage<E>> Tape<E, D> for OwnedTape<E, D> {
@@ -251,6 +305,24 @@ impl<E, D: Storage<E>> Tape<E, D> for OwnedTape<E, D> {
     {
         self.operations.push((unique_id(), Box::new(operation)));
     }
+
+    fn records_graph(&self) -> bool {
+        self.graph_operations.is_some()
+    }
+
+    fn add_graph_op<G: GraphBackward<E, D>>(&mut self, operation: G) {
+        if let (Some(graph), Some((time, _))) = (&mut self.graph_operations, self.operations.last())
+        {
+            graph.insert(*time, Box::new(operation));
+        }
+    }
+
+    fn empty_like(&self) -> Self {
+        Self {
+            graph_operations: self.graph_operations.as_ref().map(|_| Default::default()),
+            ..Default::default()
+        }
+    }
 }
 
 impl<E, D: Storage<E>> Tape<E, D> for NoneTape {
@@ -292,6 +364,11 @@ impl<E, D: Storage<E>> Merge<OwnedTape<E, D>> for OwnedTape<E, D> {
                 .extend(leafs);
         }
         self.operations.append(&mut other.operations);
+        if let Some(mut graph) = other.graph_operations {
+            self.graph_operations
+                .get_or_insert_with(Default::default)
+                .append(&mut graph);
+        }
         self
     }
 }
@@ -319,6 +396,11 @@ impl<E, D: Storage<E>> Merge<Self> for std::sync::Arc<std::sync::Mutex<OwnedTape
                     .append(leafs);
             }
             lhs.operations.append(&mut rhs.operations);
+            if let Some(graph) = &mut rhs.graph_operations {
+                lhs.graph_operations
+                    .get_or_insert_with(Default::default)
+                    .append(graph);
+            }
         }
         self
     }
@@ -334,4 +416,12 @@ impl<E, D: Storage<E>> Tape<E, D> for std::sync::Arc<std::sync::Mutex<OwnedTape<
         let mut tape = self.lock().unwrap();
         tape.add_backward_op(operation);
     }
+
+    fn records_graph(&self) -> bool {
+        self.lock().unwrap().records_graph()
+    }
+
+    fn add_graph_op<G: GraphBackward<E, D>>(&mut self, operation: G) {
+        self.lock().unwrap().add_graph_op(operation);
+    }
 }
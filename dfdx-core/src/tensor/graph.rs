@@ -0,0 +1,395 @@
+//! Backward passes that are recorded on a tape themselves, so gradients can be differentiated
+//! again.
+
+use std::collections::BTreeMap;
+use std::{boxed::Box, vec::Vec};
+
+use super::*;
+use crate::{
+    shapes::{Dtype, Rank0, Shape},
+    tensor_ops::{reshape_to::try_scatter_like, Device, ReshapeTo, TryAdd},
+};
+
+/// A backward op written with tensor ops, so that running it records backward ops of its
+/// own. Ops attach one of these to their regular backward op with [Tape::add_graph_op]
+/// when the tape [Tape::records_graph].
+///
+/// This also knows the forward mode derivative of the op, which [DualTape] uses.
+pub trait GraphBackward<E, D: Storage<E>>: 'static {
+    /// Reads the gradient of the op's output from `grads`, and adds the gradients
+    /// of its inputs.
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>;
+
+    /// Reads the tangents of the op's inputs from `tangents`, and sets the tangent of
+    /// its output (the Jacobian-vector product). Inputs without a tangent are constants.
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>;
+}
+
+/// Gradients computed by [Tensor::backward_with_graph]. Unlike [Gradients], these are
+/// traced tensors, so they can be used in another loss and differentiated again.
+///
+/// Example of a gradient penalty:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let w: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+/// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([0.5, -1.0, 2.0]);
+/// let y = (x.leaky_trace().record_graph() * w.clone()).tanh().sum();
+/// let mut grads = y.backward_with_graph();
+/// let grad_x = grads.get(&x);
+/// let penalty = (grad_x.square().sum().sqrt() - 1.0).square();
+/// let grads = penalty.backward();
+/// let grad_w: Tensor<Rank1<3>, f32, _> = grads.get(&w);
+/// ```
+pub struct GraphGradients<E, D: Storage<E>> {
+    /// Gradients of each tensor's data, laid out like the data (see [Gradients]).
+    gradient_by_id: BTreeMap<UniqueId, Tensor<(usize,), E, D>>,
+    tape: OwnedTape<E, D>,
+}
+
+impl<E: std::fmt::Debug, D: Storage<E>> std::fmt::Debug for GraphGradients<E, D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GraphGradients")
+            .field("num_gradients", &self.gradient_by_id.len())
+            .field("tape", &self.tape)
+            .finish()
+    }
+}
+
+impl<E: Dtype, D: Device<E>> GraphGradients<E, D> {
+    /// The gradient of `t`, traced so that it can be differentiated again.
+    ///
+    /// The first gradient that's retrieved holds the tape of both the forward & the backward
+    /// pass. The ones retrieved after it have empty tapes (like [WithEmptyTape]), which
+    /// are merged in when they are combined with the first one.
+    ///
+    /// **Panics** if `t` has no gradient.
+    pub fn get<S: Shape>(
+        &mut self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Tensor<S, E, D, OwnedTape<E, D>> {
+        self.try_get(t).unwrap().unwrap()
+    }
+
+    /// Fallible version of [GraphGradients::get], which returns `None` if `t` has no gradient.
+    #[allow(clippy::type_complexity)]
+    pub fn try_get<S: Shape>(
+        &mut self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Result<Option<Tensor<S, E, D, OwnedTape<E, D>>>, Error> {
+        Ok(self.grad_out(t)?.map(|grad| {
+            let (grad, tape) = grad.split_tape();
+            grad.put_tape(std::mem::take(&mut self.tape).merge(tape))
+        }))
+    }
+
+    /// The gradient of `t` viewed with its shape & strides, with an empty tape.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn grad_out<S: Shape>(
+        &self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Result<Option<Tensor<S, E, D, OwnedTape<E, D>>>, Error> {
+        match self.gradient_by_id.get(&t.id()) {
+            Some(grad) => Ok(Some(
+                Tensor {
+                    id: grad.id,
+                    data: grad.data.clone(),
+                    shape: *t.shape(),
+                    strides: t.strides(),
+                    device: grad.device.clone(),
+                    tape: OwnedTape::default(),
+                }
+                .try_contiguous()?,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// The gradient of the data of `t`, with an empty tape.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn flat_grad<S: Shape>(
+        &self,
+        t: &impl Tensorlike<S, E, D>,
+    ) -> Option<Tensor<(usize,), E, D, OwnedTape<E, D>>> {
+        self.gradient_by_id
+            .get(&t.id())
+            .map(|grad| grad.clone().put_tape(Default::default()))
+    }
+
+    /// Adds `grad` to the gradient of `t`, where `grad` has the shape of `t`.
+    pub(crate) fn accumulate<S: Shape>(
+        &mut self,
+        t: &impl Tensorlike<S, E, D>,
+        grad: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<(), Error> {
+        let grad = if t.strides() == t.shape().strides() {
+            grad.try_reshape_like(&(t.len(),))?
+        } else {
+            try_scatter_like(grad, t)?
+        };
+        self.accumulate_flat(t, grad)
+    }
+
+    /// Adds `grad` to the gradient of `t`, where `grad` is laid out like the data of `t`.
+    pub(crate) fn accumulate_flat<S: Shape>(
+        &mut self,
+        t: &impl Tensorlike<S, E, D>,
+        grad: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<(), Error> {
+        let grad = match self.gradient_by_id.remove(&t.id()) {
+            Some(prev) => grad.try_add(prev)?,
+            None => grad,
+        };
+        let (grad, tape) = grad.split_tape();
+        self.tape = std::mem::take(&mut self.tape).merge(tape);
+        self.gradient_by_id.insert(t.id(), grad);
+        Ok(())
+    }
+}
+
+impl<S: Shape, E, D: Storage<E>, T> Tensor<S, E, D, T> {
+    /// The data of the tensor as a 1d tensor with the same id.
+    pub(crate) fn flattened(&self) -> Tensor<(usize,), E, D> {
+        Tensor {
+            id: self.id,
+            data: self.data.clone(),
+            shape: (self.device.len(&self.data),),
+            strides: [1],
+            device: self.device.clone(),
+            tape: NoneTape,
+        }
+    }
+}
+
+impl<S: Shape, E, D: Storage<E>> Tensor<S, E, D, OwnedTape<E, D>> {
+    /// Records a differentiable version of every backward op from now on, so that
+    /// [Tensor::backward_with_graph] can be used on the loss. Call this right after tracing.
+    ///
+    /// This is supported by elementwise arithmetic and most unary math ops,
+    /// matmul, sum/max/min reductions, reshapes, and by views like broadcasts & permutes.
+    /// [Tensor::backward_with_graph] returns [Error::UnsupportedGraphBackward] if any other
+    /// op is recorded.
+    pub fn record_graph(mut self) -> Self {
+        self.tape
+            .graph_operations
+            .get_or_insert_with(Default::default);
+        self
+    }
+}
+
+impl<E: Dtype, D: Device<E>> Tensor<Rank0, E, D, OwnedTape<E, D>> {
+    /// Like [crate::tensor_ops::Backward::backward], except the gradients are traced
+    /// tensors that can be differentiated again. The graph must have been recorded with
+    /// [Tensor::record_graph].
+    ///
+    /// Only second order gradients are supported, i.e. the backward pass of the
+    /// gradients isn't recorded again.
+    pub fn backward_with_graph(self) -> GraphGradients<E, D> {
+        self.try_backward_with_graph().unwrap()
+    }
+
+    /// Fallible version of [Tensor::backward_with_graph]
+    pub fn try_backward_with_graph(self) -> Result<GraphGradients<E, D>, Error> {
+        let (t, mut tape) = self.split_tape();
+        let mut graph = tape.graph_operations.take().unwrap_or_default();
+        tape.operations.sort_by_key(|(k, _)| *k);
+        tape.operations.dedup_by_key(|(k, _)| *k);
+        let times: Vec<UniqueId> = tape.operations.iter().map(|(k, _)| *k).collect();
+
+        // The regular backward ops of the forward pass stay on the tape, so that the next
+        // backward pass continues through them.
+        let mut grads = GraphGradients {
+            gradient_by_id: Default::default(),
+            tape,
+        };
+        let ones = t.device.try_ones_like(&t.shape)?;
+        grads.accumulate(&t, ones.put_tape(Default::default()))?;
+        for time in times.iter().rev() {
+            let op = graph.remove(time).ok_or(Error::UnsupportedGraphBackward)?;
+            op.backward(&mut grads)?;
+        }
+        Ok(grads)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+    use num_traits::{FromPrimitive, ToPrimitive};
+
+    #[test]
+    fn test_second_derivative_unary() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<4>, TestDtype, _> = dev.tensor([-1.0, 0.5, 1.0, 2.0]);
+
+        // d/dx sum(x^3) = 3x^2, d^2/dx^2 = 6x
+        let y = x.leaky_trace().record_graph().powi(3).sum();
+        let mut grads = y.backward_with_graph();
+        let g = grads.get(&x);
+        assert_close_to_tensor!(g.retaped::<NoneTape>(), x.clone().square() * 3.0);
+        let g2 = g.sum().backward();
+        assert_close_to_tensor!(g2.get(&x), x.clone() * 6.0);
+
+        // d/dx sum(sin(x)) = cos(x), d^2/dx^2 = -sin(x)
+        let y = x.leaky_trace().record_graph().sin().sum();
+        let g2 = y.backward_with_graph().get(&x).sum().backward();
+        assert_close_to_tensor!(g2.get(&x), x.clone().sin().negate());
+
+        // d/dx sum(exp(x) / 2) = exp(x) / 2, d^2/dx^2 = exp(x) / 2
+        let y = (x.leaky_trace().record_graph().exp() / 2.0).sum();
+        let g2 = y.backward_with_graph().get(&x).sum().backward();
+        assert_close_to_tensor!(g2.get(&x), x.clone().exp() / 2.0);
+
+        // d/dx sum(tanh(x)) = 1 - tanh(x)^2, d^2/dx^2 = -2 tanh(x) (1 - tanh(x)^2)
+        let y = x.leaky_trace().record_graph().tanh().sum();
+        let g2 = y.backward_with_graph().get(&x).sum().backward();
+        let t = x.clone().tanh();
+        let expected = t.clone() * (t.square().negate() + 1.0) * -2.0;
+        assert_close_to_tensor!(g2.get(&x), expected);
+    }
+
+    #[test]
+    fn test_second_derivative_binary() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([0.5, 1.0, 2.0]);
+        let w: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([3.0, -1.0, 0.5]);
+
+        // y = sum(w * x^2 / (x + 1)), dy/dx = w * (x^2 + 2x) / (x + 1)^2,
+        // d/dw sum(dy/dx) = (x^2 + 2x) / (x + 1)^2
+        let xt = x.leaky_trace().record_graph();
+        let y = (xt.with_empty_tape().square() * w.clone() / (xt + 1.0)).sum();
+        let mut grads = y.backward_with_graph();
+        let g = grads.get(&x);
+        let x1 = x.clone() + 1.0;
+        let dy_dw = (x.clone().square() + x.clone() * 2.0) / x1.square();
+        assert_close_to_tensor!(g.retaped::<NoneTape>(), dy_dw.clone() * w.clone());
+        let g2 = g.sum().backward();
+        assert_close_to_tensor!(g2.get(&w), dy_dw);
+        // d^2y/dx^2 = w * 2 / (x + 1)^3
+        let expected = w.clone() * 2.0 / (x.clone() + 1.0).powi(3);
+        assert_close_to_tensor!(g2.get(&x), expected);
+    }
+
+    #[test]
+    fn test_second_derivative_matmul_and_broadcast() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank1<4>, TestDtype, _> = dev.sample_normal();
+
+        // y = sum((x @ w + b)^2), dy/dx = 2 (x @ w + b) @ w^T
+        let z = x.leaky_trace().record_graph().matmul(w.clone()) + b.clone().broadcast();
+        let y = z.square().sum();
+        let mut grads = y.backward_with_graph();
+        let gx = grads.get(&x);
+        let z = x.clone().matmul(w.clone()) + b.clone().broadcast();
+        let expected = (z * 2.0).matmul(w.clone().permute());
+        assert_close_to_tensor!(gx.retaped::<NoneTape>(), expected, 1e-4);
+        let gb = grads.get(&b);
+
+        // d/db sum(dy/dx) = 2 * sum(w^T, axis 1), repeated for each row of x
+        // d/db sum(dy/db) = 2 * 2 (there are 2 rows)
+        let g2 = (gx.sum() + gb.sum()).backward();
+        let expected = w.clone().sum::<Rank1<4>, _>() * 4.0 + 4.0;
+        assert_close_to_tensor!(g2.get(&b), expected, 1e-4);
+        // d/dx sum(dy/dx) = 2 * broadcast(sum_j (w @ w^T)[:, j]), d/dx sum(dy/db) = 2 * sum(w, axis 1)
+        let ww = w
+            .clone()
+            .matmul(w.clone().permute())
+            .sum::<Rank1<3>, Axis<1>>();
+        let expected = (ww + w.clone().sum::<Rank1<3>, _>()).broadcast::<Rank2<2, 3>, _>() * 2.0;
+        assert_close_to_tensor!(g2.get(&x), expected, 1e-4);
+    }
+
+    #[test]
+    fn test_second_derivative_reductions() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.tensor([[1.0, 3.0, 2.0], [-1.0, -2.0, 0.5]]);
+
+        // y = sum(max(x, axis 1)^2), dy/dx = 2 * max at the argmax, d^2y/dx^2 = 2 at the argmax
+        let y = x
+            .leaky_trace()
+            .record_graph()
+            .max::<Rank1<2>, _>()
+            .square()
+            .sum();
+        let g = y.backward_with_graph().get(&x);
+        assert_close_to_literal!(g, [[0.0, 6.0, 0.0], [0.0, 0.0, 1.0]]);
+        let g2 = g.sum().backward();
+        assert_close_to_literal!(g2.get(&x), [[0.0, 2.0, 0.0], [0.0, 0.0, 2.0]]);
+
+        // y = mean(x)^2, dy/dx = 2 mean(x) / 6, d^2y/dx^2 = 2 / 36
+        let y = x.leaky_trace().record_graph().mean().square();
+        let g = y.backward_with_graph().get(&x);
+        let g2 = g.sum().backward();
+        assert_close_to_literal!(g2.get(&x), [[1.0 / 3.0; 3]; 2]);
+    }
+
+    #[test]
+    fn test_gradient_penalty() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 2>, TestDtype, _> = dev.sample_normal();
+
+        // a WGAN-GP style penalty, (|d critic(x) / dx| - 1)^2. The epsilon keeps sqrt
+        // differentiable for rows where relu zeroes out the whole gradient.
+        let critic = |x: Tensor<Rank2<4, 3>, TestDtype, TestDevice, OwnedTape<_, _>>| {
+            x.matmul(w.clone()).relu().sum::<Rank0, _>()
+        };
+        let mut grads = critic(x.leaky_trace().record_graph()).backward_with_graph();
+        let gx = grads.get(&x);
+        let penalty = ((gx.square().sum::<Rank1<4>, _>() + 1e-6).sqrt() - 1.0)
+            .square()
+            .mean();
+        let grads = penalty.backward();
+
+        // compare against finite differences in w
+        let penalty_of = |w: &Tensor<Rank2<3, 2>, TestDtype, TestDevice>| {
+            let mask = x.clone().matmul(w.clone()).gt(0.0);
+            let grad_z = mask.choose(dev.ones(), dev.zeros());
+            let gx = grad_z.matmul(w.clone().permute());
+            let penalty = ((gx.square().sum::<Rank1<4>, _>() + 1e-6).sqrt() - 1.0)
+                .square()
+                .mean();
+            penalty.array().to_f64().unwrap()
+        };
+        let grad_w = grads.get(&w).array();
+        let eps = 1e-3;
+        for i in 0..3 {
+            for j in 0..2 {
+                let mut w_plus = w.array();
+                w_plus[i][j] += TestDtype::from_f64(eps).unwrap();
+                let mut w_minus = w.array();
+                w_minus[i][j] -= TestDtype::from_f64(eps).unwrap();
+                let fd = (penalty_of(&dev.tensor(w_plus)) - penalty_of(&dev.tensor(w_minus)))
+                    / (2.0 * eps);
+                let g = grad_w[i][j].to_f64().unwrap();
+                assert!((g - fd).abs() < 1e-2, "{g} vs {fd}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported_graph_backward() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let y = x.leaky_trace().record_graph().fast_gelu().sum();
+        assert!(matches!(
+            y.try_backward_with_graph(),
+            Err(Error::UnsupportedGraphBackward)
+        ));
+
+        // ops recorded before record_graph
+        let y = x.leaky_trace().exp().record_graph().sum();
+        assert!(matches!(
+            y.try_backward_with_graph(),
+            Err(Error::UnsupportedGraphBackward)
+        ));
+    }
+}
@@ -117,6 +117,21 @@
 //! If you re-use the same gradients object without zero-ing out the gradients, you can
 //! implement gradient accumulation!
 //!
+//! ## Higher order gradients
+//!
+//! Use [Tensor::record_graph] and [Tensor::backward_with_graph] to get gradients that can be
+//! differentiated again. See [GraphGradients].
+//!
+//! ## Forward mode
+//!
+//! [Tensor::dual] attaches a tangent to a tensor, which ops then propagate with a
+//! [DualTape]. See [jvp] for Jacobian-vector products.
+//!
+//! ## Capturing ops
+//!
+//! [Tensor::capture] records the ops of a forward pass with a [CaptureTape], which
+//! [Tensor::split_graph] turns into an [OpGraph] that can be exported to DOT or JSON.
+//!
 //! # Serialization using numpy
 //!
 //! See [Tensor::save_to_npy] and [Tensor::load_from_npy].
@@ -137,11 +152,14 @@
 //! empty out any existing allocations and prevent any new ones from being cached.
 
 pub(crate) mod cache;
+mod capture;
 pub(crate) mod cpu;
 #[cfg(feature = "cuda")]
 pub(crate) mod cuda;
+mod dual;
 mod ghost;
 mod gradients;
+mod graph;
 mod masks;
 #[cfg(feature = "numpy")]
 pub(crate) mod numpy;
@@ -184,12 +202,17 @@ pub use storage_traits::{Cache, RandomU64, Storage, Synchronize};
 pub use storage_traits::{OnesTensor, SampleTensor, TriangleTensor, ZerosTensor};
 
 pub use tensor_impls::{PutTape, SplitTape, Tensor, Trace, WithEmptyTape};
-pub use tensor_impls::{Tensor0D, Tensor1D, Tensor2D, Tensor3D, Tensor4D, Tensor5D, Tensor6D};
+pub use tensor_impls::{
+    Tensor0D, Tensor1D, Tensor2D, Tensor3D, Tensor4D, Tensor5D, Tensor6D, Tensor7D, Tensor8D,
+};
 
 pub(crate) use unique_id::unique_id;
 pub use unique_id::UniqueId;
 
+pub use capture::{Attr, CaptureTape, OpGraph, OpNode, Source, TensorRef, Value};
+pub use dual::{jvp, try_jvp, DualTape, Tangents};
 pub use gradients::{Gradients, Merge, NoneTape, OwnedTape, Tape};
+pub use graph::{GraphBackward, GraphGradients};
 
 #[cfg(test)]
 mod tests {
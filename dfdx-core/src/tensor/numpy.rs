@@ -1,6 +1,6 @@
-use crate::shapes::{Dtype, HasShape, Shape};
+use crate::shapes::{Dtype, DynShape, HasShape, Shape};
 
-use super::{CopySlice, Tensor};
+use super::{CopySlice, Tensor, TensorFromVec};
 
 use std::{
     fs::File,
@@ -23,7 +23,7 @@ impl<S: Shape, E: Dtype + NumpyDtype, D: CopySlice<E>, T> Tensor<S, E, D, T> {
         filename: String,
     ) -> ZipResult<()> {
         let buf = self.as_vec();
-        write_to_npz(w, self.shape().concrete().as_ref(), &buf, filename)?;
+        write_to_npz(w, &self.shape().dims(), &buf, filename)?;
         Ok(())
     }
 
@@ -33,7 +33,7 @@ impl<S: Shape, E: Dtype + NumpyDtype, D: CopySlice<E>, T> Tensor<S, E, D, T> {
         r: &mut zip::ZipArchive<R>,
         filename: String,
     ) -> Result<(), NpzError> {
-        let buf = read_from_npz(r, self.shape().concrete().as_ref(), filename)?;
+        let buf = read_from_npz(r, &self.shape().dims(), filename)?;
         self.copy_from(&buf);
         Ok(())
     }
@@ -41,7 +41,7 @@ impl<S: Shape, E: Dtype + NumpyDtype, D: CopySlice<E>, T> Tensor<S, E, D, T> {
     /// Attemps to load the data from a `.npy` file at `path`
     pub fn load_from_npy<P: AsRef<Path>>(&mut self, path: P) -> Result<(), NpyError> {
         let mut f = BufReader::new(File::open(path)?);
-        let buf = read_from_npy(&mut f, self.shape().concrete().as_ref())?;
+        let buf = read_from_npy(&mut f, &self.shape().dims())?;
         self.copy_from(&buf);
         Ok(())
     }
@@ -50,7 +50,26 @@ impl<S: Shape, E: Dtype + NumpyDtype, D: CopySlice<E>, T> Tensor<S, E, D, T> {
     pub fn save_to_npy<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
         let mut f = BufWriter::new(File::create(path)?);
         let buf = self.as_vec();
-        write_to_npy(&mut f, self.shape().concrete().as_ref(), &buf)
+        write_to_npy(&mut f, &self.shape().dims(), &buf)
+    }
+}
+
+impl<E: Dtype + NumpyDtype, D: TensorFromVec<E>> Tensor<DynShape, E, D> {
+    /// Reads a file in a zip archive named `filename`, with whatever shape it was saved with.
+    pub fn from_npz<R: Read + Seek>(
+        device: &D,
+        r: &mut zip::ZipArchive<R>,
+        filename: String,
+    ) -> Result<Self, NpzError> {
+        let (shape, buf) = read_dyn_from_npy(&mut open_npz_file(r, filename)?)?;
+        Ok(device.tensor_from_vec(buf, shape))
+    }
+
+    /// Loads a `.npy` file at `path`, with whatever shape it was saved with.
+    pub fn from_npy<P: AsRef<Path>>(device: &D, path: P) -> Result<Self, NpyError> {
+        let mut f = BufReader::new(File::open(path)?);
+        let (shape, buf) = read_dyn_from_npy(&mut f)?;
+        Ok(device.tensor_from_vec(buf, shape))
     }
 }
 
@@ -70,24 +89,27 @@ pub(crate) fn write_to_npz<W: Write + Seek, E: Dtype + NumpyDtype>(
 pub(crate) fn read_from_npz<R: Read + Seek, E: Dtype + NumpyDtype>(
     r: &mut zip::ZipArchive<R>,
     shape: &[usize],
-    mut filename: String,
+    filename: String,
 ) -> Result<Vec<E>, NpyError> {
+    read_from_npy(&mut open_npz_file(r, filename)?, shape)
+}
+
+fn open_npz_file<R: Read + Seek>(
+    r: &mut zip::ZipArchive<R>,
+    mut filename: String,
+) -> Result<zip::read::ZipFile<'_>, NpyError> {
     if !filename.ends_with(".npy") {
         filename.push_str(".npy");
     }
 
-    let mut f = match r.by_name(&filename) {
-        Ok(f) => f,
-        Err(ZipError::FileNotFound) => {
-            return Err(NpyError::IoError(io::Error::new(
-                io::ErrorKind::NotFound,
-                ZipError::FileNotFound,
-            )))
-        }
+    match r.by_name(&filename) {
+        Ok(f) => Ok(f),
+        Err(ZipError::FileNotFound) => Err(NpyError::IoError(io::Error::new(
+            io::ErrorKind::NotFound,
+            ZipError::FileNotFound,
+        ))),
         Err(e) => panic!("Uncaught zip error: {e}"),
-    };
-
-    read_from_npy(&mut f, shape)
+    }
 }
 
 fn write_to_npy<W: Write, E: Dtype + NumpyDtype>(
@@ -110,7 +132,21 @@ fn read_from_npy<R: Read, E: Dtype + NumpyDtype>(
     shape: &[usize],
 ) -> Result<Vec<E>, NpyError> {
     let endian = read_header::<R, E>(r, shape)?;
-    let numel = shape.iter().product::<usize>();
+    read_data(r, endian, shape.iter().product())
+}
+
+fn read_dyn_from_npy<R: Read, E: Dtype + NumpyDtype>(
+    r: &mut R,
+) -> Result<(DynShape, Vec<E>), NpyError> {
+    let (endian, shape) = read_dyn_header::<R, E>(r)?;
+    Ok((shape, read_data(r, endian, shape.num_elements())?))
+}
+
+fn read_data<R: Read, E: NumpyDtype>(
+    r: &mut R,
+    endian: Endian,
+    numel: usize,
+) -> Result<Vec<E>, NpyError> {
     let mut out = Vec::new();
 
     for _ in 0..numel {
@@ -160,6 +196,50 @@ fn write_header<W: Write, E: NumpyDtype>(
 }
 
 fn read_header<R: Read, E: NumpyDtype>(r: &mut R, shape: &[usize]) -> Result<Endian, NpyError> {
+    let (endian, header, i) = read_header_start::<R, E>(r)?;
+    let shape_str = to_shape_str(shape);
+    let i = expect(&header, i, shape_str.as_bytes())?;
+    expect(&header, i, b"), }")?;
+
+    Ok(endian)
+}
+
+/// Like [read_header], but reads the shape from the header instead of checking it.
+fn read_dyn_header<R: Read, E: NumpyDtype>(r: &mut R) -> Result<(Endian, DynShape), NpyError> {
+    let (endian, header, i) = read_header_start::<R, E>(r)?;
+    let end = match header[i..].iter().position(|&c| c == b')') {
+        Some(len) => i + len,
+        None => {
+            return Err(NpyError::InvalidShape(String::from_utf8(
+                header[i..].to_vec(),
+            )?))
+        }
+    };
+    let shape_str = String::from_utf8(header[i..end].to_vec())?;
+    expect(&header, end, b"), }")?;
+
+    let mut dims = Vec::new();
+    for dim in shape_str
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+    {
+        match dim.parse() {
+            Ok(dim) => dims.push(dim),
+            Err(_) => return Err(NpyError::InvalidShape(shape_str)),
+        }
+    }
+    match DynShape::try_new(&dims) {
+        Some(shape) => Ok((endian, shape)),
+        None => Err(NpyError::InvalidShape(shape_str)),
+    }
+}
+
+/// Reads the header up to the start of the shape, returning the header and the index
+/// the shape starts at.
+fn read_header_start<R: Read, E: NumpyDtype>(
+    r: &mut R,
+) -> Result<(Endian, Vec<u8>, usize), NpyError> {
     let mut magic = [0; 6];
     r.read_exact(&mut magic)?;
     if magic != MAGIC_NUMBER {
@@ -198,11 +278,8 @@ fn read_header<R: Read, E: NumpyDtype>(r: &mut R, shape: &[usize]) -> Result<End
 
     // shape
     i = expect(&header, i, b"'shape': (")?;
-    let shape_str = to_shape_str(shape);
-    i = expect(&header, i, shape_str.as_bytes())?;
-    expect(&header, i, b"), }")?;
 
-    Ok(endian)
+    Ok((endian, header, i))
 }
 
 fn expect(buf: &[u8], i: usize, chars: &[u8]) -> Result<usize, NpyError> {
@@ -465,6 +542,10 @@ pub enum NpyError {
 
     /// Unexpected alignment for [Endian].
     InvalidAlignment,
+
+    /// The shape in the header could not be parsed, or has more than
+    /// [crate::shapes::MAX_DYN_RANK] dimensions.
+    InvalidShape(String),
 }
 
 impl std::fmt::Display for NpyError {
@@ -483,6 +564,7 @@ impl std::fmt::Display for NpyError {
                 "error while parsing: expected {expected_str} found {found_str}"
             ),
             NpyError::InvalidAlignment => write!(fmt, "invalid alignment"),
+            NpyError::InvalidShape(shape) => write!(fmt, "invalid shape: ({shape})"),
         }
     }
 }
@@ -811,4 +893,50 @@ mod tests {
             .load_from_npy(file.path())
             .expect_err("");
     }
+
+    #[test]
+    fn test_dyn_load() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor([[[0.0f32, 1.0], [2.0, 3.0], [4.0, 5.0]]]);
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+
+        x.save_to_npy(file.path()).expect("Saving failed");
+
+        let v = Tensor::<DynShape, f32, _>::from_npy(&dev, file.path()).expect("Loading failed");
+        assert_eq!(v.shape().dims(), [1, 3, 2]);
+        assert_eq!(v.as_vec(), x.as_vec());
+
+        let file2 = NamedTempFile::new().expect("failed to create tempfile");
+        v.save_to_npy(file2.path()).expect("Saving failed");
+        let mut found = dev.tensor([[[0.0f32; 2]; 3]; 1]);
+        found.load_from_npy(file2.path()).expect("Loading failed");
+        assert_eq!(found.array(), x.array());
+
+        Tensor::<DynShape, f64, _>::from_npy(&dev, file.path()).expect_err("");
+
+        let v = Tensor::<DynShape, i8, _>::from_npy(&dev, file.path());
+        assert!(v.is_err());
+    }
+
+    #[test]
+    fn test_dyn_load_npz() {
+        let dev: TestDevice = Default::default();
+        let x = dev.tensor(1.5f64);
+        let y = dev.tensor([1.0f64, 2.0]);
+
+        let file = NamedTempFile::new().expect("failed to create tempfile");
+        let mut zip = zip::ZipWriter::new(file.reopen().unwrap());
+        x.write_to_npz(&mut zip, "x".into()).unwrap();
+        y.write_to_npz(&mut zip, "y".into()).unwrap();
+        zip.finish().unwrap();
+
+        let mut zip = zip::ZipArchive::new(file.reopen().unwrap()).unwrap();
+        let v = Tensor::<DynShape, f64, _>::from_npz(&dev, &mut zip, "x".into()).unwrap();
+        assert_eq!(v.shape().rank(), 0);
+        assert_eq!(v.as_vec(), [1.5]);
+        let v = Tensor::<DynShape, f64, _>::from_npz(&dev, &mut zip, "y".into()).unwrap();
+        assert_eq!(v.shape().dims(), [2]);
+        assert_eq!(v.as_vec(), [1.0, 2.0]);
+    }
 }
@@ -1,5 +1,5 @@
-use super::{CopySlice, Tensor};
-use crate::shapes::{Dtype, Shape};
+use super::{CopySlice, Tensor, ZerosTensor};
+use crate::shapes::{Dtype, DynShape, Shape, MAX_DYN_RANK};
 use safetensors::tensor::{SafeTensorError, SafeTensors};
 use std::vec::Vec;
 
@@ -15,7 +15,7 @@ impl<S: Shape, E: Dtype, D: CopySlice<E>, T> Tensor<S, E, D, T> {
         let num_bytes = std::mem::size_of::<E>();
         assert_eq!(
             tensor_view.shape(),
-            self.shape.concrete().into(),
+            self.shape.dims(),
             "SafeTensors shape did not match tensor shape"
         );
         if (v.as_ptr() as usize) % num_bytes == 0 {
@@ -36,3 +36,78 @@ impl<S: Shape, E: Dtype, D: CopySlice<E>, T> Tensor<S, E, D, T> {
         Ok(())
     }
 }
+
+impl<E: Dtype, D: CopySlice<E> + ZerosTensor<E>> Tensor<DynShape, E, D> {
+    /// Creates a tensor with the shape & data stored under `key` in [SafeTensors].
+    ///
+    /// Returns [SafeTensorError::InvalidTensorView] if the tensor has more than
+    /// [MAX_DYN_RANK] dimensions.
+    pub fn from_safetensor(
+        device: &D,
+        tensors: &SafeTensors,
+        key: &str,
+    ) -> Result<Self, SafeTensorError> {
+        let view = tensors.tensor(key)?;
+        if view.shape().len() > MAX_DYN_RANK {
+            return Err(SafeTensorError::InvalidTensorView(
+                view.dtype(),
+                view.shape().to_vec(),
+                view.data().len(),
+            ));
+        }
+        let shape = DynShape::new(view.shape());
+        let mut tensor = device.zeros_like(&shape);
+        tensor.load_safetensor(tensors, key)?;
+        Ok(tensor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn_traits::SaveSafeTensors, shapes::*, tensor::*, tests::*};
+
+    #[test]
+    fn test_dyn_from_safetensor() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank3<2, 1, 3>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank1<4>, TestDtype, _> = dev.sample_normal();
+
+        let mut tensors = Vec::new();
+        a.write_safetensors("a", &mut tensors);
+        b.write_safetensors("b", &mut tensors);
+        let data = tensors.iter().map(|(k, dtype, shape, data)| {
+            (
+                k.clone(),
+                ::safetensors::tensor::TensorView::new(*dtype, shape.clone(), data).unwrap(),
+            )
+        });
+        let buffer = ::safetensors::serialize(data, &None).unwrap();
+        let tensors = SafeTensors::deserialize(&buffer).unwrap();
+
+        let a2 = Tensor::<DynShape, TestDtype, _>::from_safetensor(&dev, &tensors, "a").unwrap();
+        assert_eq!(a2.shape().dims(), [2, 1, 3]);
+        assert_eq!(a2.as_vec(), a.as_vec());
+        let b2 = Tensor::<DynShape, TestDtype, _>::from_safetensor(&dev, &tensors, "b").unwrap();
+        assert_eq!(b2.shape().dims(), [4]);
+        assert_eq!(b2.as_vec(), b.as_vec());
+
+        let mut b3: Tensor<DynShape, TestDtype, _> = dev.zeros_like(&DynShape::new(&[4]));
+        b3.load_safetensor(&tensors, "b").unwrap();
+        assert_eq!(b3.as_vec(), b.as_vec());
+    }
+
+    #[test]
+    fn test_dyn_from_safetensor_too_many_dims() {
+        let dev: TestDevice = Default::default();
+        let shape = std::vec![1; MAX_DYN_RANK + 1];
+        let data = std::vec![0u8; 4];
+        let view = ::safetensors::tensor::TensorView::new(::safetensors::Dtype::F32, shape, &data)
+            .unwrap();
+        let buffer = ::safetensors::serialize([("a", view)], &None).unwrap();
+        let tensors = SafeTensors::deserialize(&buffer).unwrap();
+
+        let r = Tensor::<DynShape, f32, _>::from_safetensor(&dev, &tensors, "a");
+        assert!(matches!(r, Err(SafeTensorError::InvalidTensorView(..))));
+    }
+}
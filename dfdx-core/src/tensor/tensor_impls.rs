@@ -85,6 +85,7 @@ impl<S: Shape, E: Unit, F: Unit, D: Storage<F> + Storage<E>> Trace<E, D>
         self.put_tape(OwnedTape {
             gradients,
             operations: std::vec::Vec::new(),
+            graph_operations: None,
         })
     }
 }
@@ -175,7 +176,7 @@ pub trait WithEmptyTape {
     fn with_empty_tape(&self) -> Self;
 }
 
-impl<S: Shape, E, D: Storage<E>, T: Default> WithEmptyTape for Tensor<S, E, D, T> {
+impl<S: Shape, E, D: Storage<E>, T: Tape<E, D>> WithEmptyTape for Tensor<S, E, D, T> {
     fn with_empty_tape(&self) -> Self {
         Tensor {
             id: self.id,
@@ -183,7 +184,7 @@ impl<S: Shape, E, D: Storage<E>, T: Default> WithEmptyTape for Tensor<S, E, D, T
             shape: self.shape,
             strides: self.strides,
             device: self.device.clone(),
-            tape: Default::default(),
+            tape: self.tape.empty_like(),
         }
     }
 }
@@ -253,3 +254,24 @@ pub type Tensor6D<
     const R: usize,
     Tape = NoneTape,
 > = Tensor<Rank6<M, N, O, P, Q, R>, f32, Cpu, Tape>;
+pub type Tensor7D<
+    const M: usize,
+    const N: usize,
+    const O: usize,
+    const P: usize,
+    const Q: usize,
+    const R: usize,
+    const S: usize,
+    Tape = NoneTape,
+> = Tensor<Rank7<M, N, O, P, Q, R, S>, f32, Cpu, Tape>;
+pub type Tensor8D<
+    const M: usize,
+    const N: usize,
+    const O: usize,
+    const P: usize,
+    const Q: usize,
+    const R: usize,
+    const S: usize,
+    const T: usize,
+    Tape = NoneTape,
+> = Tensor<Rank8<M, N, O, P, Q, R, S, T>, f32, Cpu, Tape>;
@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::{ChooseFrom, Device, TryGt, TryLt, TryMul};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -42,6 +43,27 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<AbsKernelOp, E>, T: Tape<E, D>> Tensor<S
     }
 }
 
+impl KernelOpInfo for AbsKernelOp {
+    const NAME: &'static str = "Abs";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for AbsKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        let dev = inp.device();
+        let sign = inp.try_lt(E::default())?.try_choose(
+            dev.try_ones_like(inp.shape())?.try_negate()?,
+            dev.try_zeros_like(inp.shape())?,
+        )?;
+        let sign = inp.try_gt(E::default())?.try_choose(dev.try_ones_like(inp.shape())?, sign)?;
+        grad_out.try_mul(sign)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -55,6 +55,12 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<AccurateGeLUKernelOp, E>, T: Tape<E, D>>
     }
 }
 
+impl KernelOpInfo for AccurateGeLUKernelOp {
+    const NAME: &'static str = "AccurateGeLU";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for AccurateGeLUKernelOp {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -0,0 +1,108 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    None,
+    L2,
+    Decoupled
+};
+
+struct AdadeltaConfig {
+    double lr;
+    double rho;
+    double eps;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void adadelta_update(
+    const AdadeltaConfig cfg,
+    const size_t numel,
+    T* param,
+    T* square_avg,
+    T* acc_delta,
+    const T* grad
+) {
+    T lr = cfg.lr;
+    T rho = cfg.rho;
+    T eps = cfg.eps;
+    T weight_decay = cfg.weight_decay;
+    T one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T s_avg = square_avg[i];
+        T a_delta = acc_delta[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        s_avg = s_avg * rho + g * g * (one - rho);
+        T delta = sqrtg(a_delta + eps) / sqrtg(s_avg + eps) * g;
+        a_delta = a_delta * rho + delta * delta * (one - rho);
+        g = lr * delta;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        square_avg[i] = s_avg;
+        acc_delta[i] = a_delta;
+        param[i] -= g;
+    }
+}
+
+#define ADADELTA(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const AdadeltaConfig cfg, \
+    const size_t numel, \
+    TYPENAME* param, \
+    TYPENAME* square_avg, \
+    TYPENAME* acc_delta, \
+    const TYPENAME* grad \
+) { \
+    adadelta_update(cfg, numel, param, square_avg, acc_delta, grad); \
+}
+
+ADADELTA(__half, adadelta_update_f16);
+ADADELTA(float, adadelta_update_f32);
+ADADELTA(double, adadelta_update_f64);
+
+extern "C" __global__ void adadelta_update_amp_f16(
+    const AdadeltaConfig cfg,
+    const size_t numel,
+    __half* param,
+    __half* square_avg,
+    __half* acc_delta,
+    const __half* grad
+) {
+    float lr = cfg.lr;
+    float rho = cfg.rho;
+    float eps = cfg.eps;
+    float weight_decay = cfg.weight_decay;
+    float one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float s_avg = square_avg[i];
+        float a_delta = acc_delta[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        s_avg = s_avg * rho + g * g * (one - rho);
+        float delta = sqrtg(a_delta + eps) / sqrtg(s_avg + eps) * g;
+        a_delta = a_delta * rho + delta * delta * (one - rho);
+        g = lr * delta;
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        square_avg[i] = s_avg;
+        acc_delta[i] = a_delta;
+        param[i] -= g;
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaAdadeltaConfig {
+    lr: f64,
+    rho: f64,
+    eps: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaAdadeltaConfig {}
+
+fn adadelta_config_to_cuda(config: &super::AdadeltaConfig) -> CudaAdadeltaConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaAdadeltaConfig {
+        lr: config.lr,
+        rho: config.rho,
+        eps: config.eps,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/adadelta.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FWD: &'static str;
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "adadelta_amp_f16";
+    const FWD: &'static str = "adadelta_update_amp_f16";
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "adadelta_f16";
+    const FWD: &'static str = "adadelta_update_f16";
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "adadelta_f32";
+    const FWD: &'static str = "adadelta_update_f32";
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "adadelta_f64";
+    const FWD: &'static str = "adadelta_update_f64";
+}
+
+impl<E: Dtype> super::AdadeltaKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn adadelta_kernel(
+        &self,
+        cfg: &super::AdadeltaConfig,
+        param: &mut Self::Vec,
+        square_avg: &mut Self::Vec,
+        acc_delta: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FWD) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, &[Self::FWD])?;
+        }
+
+        let opt_cfg = adadelta_config_to_cuda(cfg);
+        let numel = param.len();
+        let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
+        let cfg = launch_cfg::<128>(numel as u32);
+        let params = (opt_cfg, numel, param, square_avg, acc_delta, grad);
+        unsafe { func.launch(cfg, params) }?;
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    None,
+    L2,
+    Decoupled
+};
+
+struct AdagradConfig {
+    double lr;
+    double eps;
+    double initial_accumulator_value;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void adagrad_update(
+    const AdagradConfig cfg,
+    const size_t numel,
+    const int t,
+    const double clr_double,
+    T* param,
+    T* sum,
+    const T* grad
+) {
+    T lr = cfg.lr;
+    T clr = clr_double;
+    T eps = cfg.eps;
+    T weight_decay = cfg.weight_decay;
+    T initial = cfg.initial_accumulator_value;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T s = t == 1 ? initial : sum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        s += g * g;
+        g = clr * g / (sqrtg(s) + eps);
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        sum[i] = s;
+        param[i] -= g;
+    }
+}
+
+#define ADAGRAD(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const AdagradConfig cfg, \
+    const size_t numel, \
+    const int t, \
+    const double clr, \
+    TYPENAME* param, \
+    TYPENAME* sum, \
+    const TYPENAME* grad \
+) { \
+    adagrad_update(cfg, numel, t, clr, param, sum, grad); \
+}
+
+ADAGRAD(__half, adagrad_update_f16);
+ADAGRAD(float, adagrad_update_f32);
+ADAGRAD(double, adagrad_update_f64);
+
+extern "C" __global__ void adagrad_update_amp_f16(
+    const AdagradConfig cfg,
+    const size_t numel,
+    const int t,
+    const double clr_double,
+    __half* param,
+    __half* sum,
+    const __half* grad
+) {
+    float lr = cfg.lr;
+    float clr = clr_double;
+    float eps = cfg.eps;
+    float weight_decay = cfg.weight_decay;
+    float initial = cfg.initial_accumulator_value;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float s = t == 1 ? initial : (float)sum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        s += g * g;
+        g = clr * g / (sqrtg(s) + eps);
+
+        if (cfg.weight_decay_type == Decoupled) {
+            g += (weight_decay * lr) * p;
+        }
+
+        sum[i] = s;
+        param[i] -= g;
+    }
+}
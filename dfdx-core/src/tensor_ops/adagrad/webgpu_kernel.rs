@@ -0,0 +1,14 @@
+use crate::prelude::{Dtype, Webgpu};
+
+impl<E: Dtype> super::AdagradKernel<E> for Webgpu {
+    fn adagrad_kernel(
+        &self,
+        t: i32,
+        cfg: &crate::prelude::AdagradConfig,
+        param: &mut Self::Vec,
+        sum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), crate::prelude::Error> {
+        todo!()
+    }
+}
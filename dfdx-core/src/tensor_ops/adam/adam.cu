@@ -13,6 +13,7 @@ struct AdamConfig {
     double eps;
     WeightDecayType weight_decay_type;
     double weight_decay;
+    bool amsgrad;
 };
 
 template<typename T>
@@ -23,6 +24,7 @@ __device__ void adam_update(
     T* param,
     T* moment1,
     T* moment2,
+    T* max_moment2,
     const T* grad
 ) {
     T beta1 = cfg.beta1;
@@ -45,7 +47,12 @@ __device__ void adam_update(
         m = m * beta1 + g * (one - beta1);
         v = v * beta2 + g * g * (one - beta2);
         T m_hat = m * one / (one - powg(beta1, t));
-        T v_hat = v * one / (one - powg(beta2, t));
+        T v_max = v;
+        if (cfg.amsgrad) {
+            v_max = maxg(max_moment2[i], v);
+            max_moment2[i] = v_max;
+        }
+        T v_hat = v_max * one / (one - powg(beta2, t));
         g = lr * m_hat / (sqrtg(v_hat) + eps);
     
         if (cfg.weight_decay_type == Decoupled) {
@@ -66,9 +73,10 @@ extern "C" __global__ void FN( \
     TYPENAME* param, \
     TYPENAME* moment1, \
     TYPENAME* moment2, \
+    TYPENAME* max_moment2, \
     const TYPENAME* grad \
 ) { \
-    adam_update(cfg, numel, t, param, moment1, moment2, grad); \
+    adam_update(cfg, numel, t, param, moment1, moment2, max_moment2, grad); \
 }
 
 ADAM(__half, adam_update_f16);
@@ -82,6 +90,7 @@ extern "C" __global__ void adam_update_amp_f16(
     __half* param,
     __half* moment1,
     __half* moment2,
+    __half* max_moment2,
     const __half* grad
 ) {
     float beta1 = cfg.beta1;
@@ -104,7 +113,12 @@ extern "C" __global__ void adam_update_amp_f16(
         m = m * beta1 + g * (one - beta1);
         v = v * beta2 + g * g * (one - beta2);
         float m_hat = m * one / (one - powg(beta1, t));
-        float v_hat = v * one / (one - powg(beta2, t));
+        float v_max = v;
+        if (cfg.amsgrad) {
+            v_max = maxg((float)max_moment2[i], v);
+            max_moment2[i] = v_max;
+        }
+        float v_hat = v_max * one / (one - powg(beta2, t));
         g = lr * m_hat / (sqrtg(v_hat) + eps);
     
         if (cfg.weight_decay_type == Decoupled) {
@@ -1,4 +1,4 @@
-use super::{AdamConfig, AdamKernel, WeightDecay};
+use super::{AdamConfig, AdamKernel, AdamMoments, WeightDecay};
 use crate::{
     dtypes::{Dtype, NotMixedPrecision},
     tensor::{Cpu, Error},
@@ -11,13 +11,18 @@ impl AdamKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
         t: i32,
         cfg: &AdamConfig,
         param: &mut Self::Vec,
-        moment1: &mut Self::Vec,
-        moment2: &mut Self::Vec,
+        moments: AdamMoments<Self::Vec>,
         grad: &Self::Vec,
     ) -> Result<(), Error> {
+        let AdamMoments {
+            moment1,
+            moment2,
+            max_moment2,
+        } = moments;
         let betas = cfg.betas.map(|x| x as f32);
         let eps = cfg.eps as f32;
         let lr = cfg.lr as f32;
+        let mut max_moment2 = max_moment2.filter(|_| cfg.amsgrad).map(|v| v.iter_mut());
 
         for ((p, g), (m, v)) in param
             .iter_mut()
@@ -36,7 +41,15 @@ impl AdamKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
             m_f32 = m_f32 * betas[0] + g_f32 * (1.0 - betas[0]);
             v_f32 = v_f32 * betas[1] + g_f32.powi(2) * (1.0 - betas[1]);
             let m_hat = m_f32 * (1.0 - betas[0].powi(t)).recip();
-            let v_hat = v_f32 * (1.0 - betas[1].powi(t)).recip();
+            let v_max_f32 = match max_moment2.as_mut().and_then(Iterator::next) {
+                Some(v_max) => {
+                    let v_max_f32 = v_max.0.to_f32().max(v_f32);
+                    v_max.0 = crate::dtypes::f16::from_f32(v_max_f32);
+                    v_max_f32
+                }
+                None => v_f32,
+            };
+            let v_hat = v_max_f32 * (1.0 - betas[1].powi(t)).recip();
             g_f32 = lr * m_hat / (v_hat.sqrt() + eps);
 
             if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
@@ -57,13 +70,18 @@ impl<E: num_traits::Float + Dtype + NotMixedPrecision> AdamKernel<E> for Cpu {
         t: i32,
         cfg: &AdamConfig,
         param: &mut Self::Vec,
-        moment1: &mut Self::Vec,
-        moment2: &mut Self::Vec,
+        moments: AdamMoments<Self::Vec>,
         grad: &Self::Vec,
     ) -> Result<(), Error> {
+        let AdamMoments {
+            moment1,
+            moment2,
+            max_moment2,
+        } = moments;
         let betas = cfg.betas.map(E::from_f64).map(Option::unwrap);
         let eps = E::from_f64(cfg.eps).unwrap();
         let lr = E::from_f64(cfg.lr).unwrap();
+        let mut max_moment2 = max_moment2.filter(|_| cfg.amsgrad).map(|v| v.iter_mut());
 
         for ((p, mut g), (m, v)) in param
             .iter_mut()
@@ -77,7 +95,14 @@ impl<E: num_traits::Float + Dtype + NotMixedPrecision> AdamKernel<E> for Cpu {
             *m = *m * betas[0] + g * (E::one() - betas[0]);
             *v = *v * betas[1] + g.powi(2) * (E::one() - betas[1]);
             let m_hat = *m * (E::one() - betas[0].powi(t)).recip();
-            let v_hat = *v * (E::one() - betas[1].powi(t)).recip();
+            let v_max = match max_moment2.as_mut().and_then(Iterator::next) {
+                Some(v_max) => {
+                    *v_max = v_max.max(*v);
+                    *v_max
+                }
+                None => *v,
+            };
+            let v_hat = v_max * (E::one() - betas[1].powi(t)).recip();
             g = lr * m_hat / (v_hat.sqrt() + eps);
 
             if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
@@ -1,10 +1,10 @@
 use crate::{
     dtypes::*,
-    tensor::{launch_cfg, Cuda, Error},
+    tensor::{launch_cfg, Cuda, Error, Storage},
     tensor_ops::optim::*,
 };
 
-use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+use cudarc::driver::{DevicePtrMut, DeviceRepr, DeviceSlice, LaunchAsync};
 
 #[repr(C)]
 struct CudaAdamConfig {
@@ -14,6 +14,7 @@ struct CudaAdamConfig {
     eps: f64,
     weight_decay_type: WeightDecayType,
     weight_decay: f64,
+    amsgrad: bool,
 }
 
 unsafe impl DeviceRepr for CudaAdamConfig {}
@@ -28,6 +29,7 @@ fn adam_config_to_cuda(config: &super::AdamConfig) -> CudaAdamConfig {
         eps: config.eps,
         weight_decay_type,
         weight_decay,
+        amsgrad: config.amsgrad,
     }
 }
 
@@ -69,19 +71,37 @@ where
         t: i32,
         cfg: &super::AdamConfig,
         param: &mut Self::Vec,
-        moment1: &mut Self::Vec,
-        moment2: &mut Self::Vec,
+        moments: super::AdamMoments<Self::Vec>,
         grad: &Self::Vec,
     ) -> Result<(), Error> {
+        let super::AdamMoments {
+            moment1,
+            moment2,
+            max_moment2,
+        } = moments;
         if !self.dev.has_func(Self::MOD, Self::FWD) {
             self.dev.load_ptx(PTX_SRC.into(), Self::MOD, &[Self::FWD])?;
         }
 
         let opt_cfg = adam_config_to_cuda(cfg);
         let numel = param.len();
+        // the kernel only reads `max_moment2` if amsgrad is enabled, so null is fine otherwise
+        let max_moment2 = match max_moment2 {
+            Some(v) => *v.device_ptr_mut(),
+            None => 0,
+        };
         let func = self.dev.get_func(Self::MOD, Self::FWD).unwrap();
         let cfg = launch_cfg::<128>(numel as u32);
-        let params = (opt_cfg, numel, t, param, moment1, moment2, grad);
+        let params = (
+            opt_cfg,
+            numel,
+            t,
+            param,
+            moment1,
+            moment2,
+            max_moment2,
+            grad,
+        );
         unsafe { func.launch(cfg, params) }?;
         Ok(())
     }
@@ -23,6 +23,7 @@ use super::WeightDecay;
 ///     betas: [0.1, 0.2],
 ///     eps: 1e-6,
 ///     weight_decay: Some(WeightDecay::L2(1e-1)),
+///     amsgrad: true,
 /// };
 /// ```
 #[derive(Debug, Clone, Copy)]
@@ -37,7 +38,14 @@ pub struct AdamConfig {
     pub eps: f64,
 
     /// Optional weight decay. Defaults to `None`.
+    ///
+    /// Use [WeightDecay::Decoupled] for AdamW.
     pub weight_decay: Option<WeightDecay>,
+
+    /// Whether to use the AMSGrad variant from
+    /// [On the Convergence of Adam and Beyond](https://openreview.net/forum?id=ryQu7f-RZ),
+    /// which normalizes by the maximum of all second moments seen so far. Defaults to `false`.
+    pub amsgrad: bool,
 }
 
 impl Default for AdamConfig {
@@ -47,37 +55,50 @@ impl Default for AdamConfig {
             betas: [0.9, 0.999],
             eps: 1e-8,
             weight_decay: None,
+            amsgrad: false,
         }
     }
 }
 
+/// The moment buffers of a single tensor updated by [AdamKernel].
+pub struct AdamMoments<'a, V> {
+    pub moment1: &'a mut V,
+    pub moment2: &'a mut V,
+    /// The running maximum of `moment2`. Only used if [AdamConfig::amsgrad] is set.
+    pub max_moment2: Option<&'a mut V>,
+}
+
 pub trait AdamKernel<E: Dtype>: Storage<E> {
     fn adam_kernel(
         &self,
         t: i32,
         cfg: &AdamConfig,
         param: &mut Self::Vec,
-        moment1: &mut Self::Vec,
-        moment2: &mut Self::Vec,
+        moments: AdamMoments<Self::Vec>,
         grad: &Self::Vec,
     ) -> Result<(), Error>;
 }
 
 impl AdamConfig {
+    /// Update a single tensor using Adam. `max_moment2` is only used if [AdamConfig::amsgrad] is set.
     pub fn try_update<S: Shape, E: Dtype, D: AdamKernel<E>>(
         &self,
         t: i32,
         param: &mut Tensor<S, E, D>,
         moment1: &mut D::Vec,
         moment2: &mut D::Vec,
+        max_moment2: Option<&mut D::Vec>,
         grad: &D::Vec,
     ) -> Result<(), crate::tensor::Error> {
         param.device.adam_kernel(
             t,
             self,
             std::sync::Arc::make_mut(&mut param.data),
-            moment1,
-            moment2,
+            AdamMoments {
+                moment1,
+                moment2,
+                max_moment2,
+            },
             grad,
         )
     }
@@ -6,8 +6,7 @@ impl<E: Dtype> super::AdamKernel<E> for Webgpu {
         t: i32,
         cfg: &crate::prelude::AdamConfig,
         param: &mut Self::Vec,
-        moment1: &mut Self::Vec,
-        moment2: &mut Self::Vec,
+        moments: super::AdamMoments<Self::Vec>,
         grad: &Self::Vec,
     ) -> Result<(), crate::prelude::Error> {
         todo!()
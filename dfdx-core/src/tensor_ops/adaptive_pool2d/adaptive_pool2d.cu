@@ -0,0 +1,186 @@
+#include "cuda_utils.cuh"
+
+enum AdaptivePool2dKind {
+    AVG,
+    MIN,
+    MAX,
+};
+
+struct AdaptivePool2dOp {
+    AdaptivePool2dKind kind;
+    size_t batch;
+    size_t chan;
+    size_t h_in;
+    size_t h_out;
+    size_t w_in;
+    size_t w_out;
+};
+
+__device__ double init(const AdaptivePool2dOp op) {
+    switch(op.kind) {
+        case AVG:
+            return 0.0;
+        case MIN:
+            return INFINITY;
+        case MAX:
+            return -INFINITY;
+    }
+}
+
+template<typename T>
+__device__ T accum(const AdaptivePool2dOp op, const T accum, const T item) {
+    switch(op.kind) {
+        case AVG:
+            return accum + item;
+        case MIN:
+            return ming(accum, item);
+        case MAX:
+            return maxg(accum, item);
+    }
+}
+
+template<typename T>
+__device__ T normalize(const AdaptivePool2dOp op, const T item, const size_t num_elements) {
+    double num_f64 = num_elements;
+    double scale_f64 = 1.0 / num_f64;
+    T scale = scale_f64;
+    switch(op.kind) {
+        case AVG:
+            return item * scale;
+        case MIN:
+            return item;
+        case MAX:
+            return item;
+    }
+}
+
+template<typename T>
+__device__ T filter(const AdaptivePool2dOp op, const T item, const T needle, const T haystack) {
+    T zero = 0.0;
+    switch(op.kind){
+        case AVG:
+            return item;
+        case MIN:
+            return (needle == haystack) ? item : zero;
+        case MAX:
+            return (needle == haystack) ? item : zero;
+    }
+}
+
+// input indices [start, end) that output index `o` pools over
+__device__ size_t window_start(const size_t o, const size_t dim_in, const size_t dim_out) {
+    return (o * dim_in) / dim_out;
+}
+
+__device__ size_t window_end(const size_t o, const size_t dim_in, const size_t dim_out) {
+    return ((o + 1) * dim_in + dim_out - 1) / dim_out;
+}
+
+template<typename T>
+__device__ void adaptive_pool2d_fwd(
+    const AdaptivePool2dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const T *inp, // 4d (Batch, Channels, Height, Width)
+    T *out // 4d (Batch, Channels, HeightOut, WidthOut)
+) {
+    const size_t numel = op.batch * op.chan * op.h_out * op.w_out;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t ow = idx % op.w_out;
+        idx /= op.w_out;
+        const size_t oh = idx % op.h_out;
+        idx /= op.h_out;
+        const size_t c = idx % op.chan;
+        idx /= op.chan;
+        const size_t b = idx % op.batch;
+
+        const size_t y0 = window_start(oh, op.h_in, op.h_out);
+        const size_t y1 = window_end(oh, op.h_in, op.h_out);
+        const size_t x0 = window_start(ow, op.w_in, op.w_out);
+        const size_t x1 = window_end(ow, op.w_in, op.w_out);
+
+        T tmp = init(op);
+        for(size_t y = y0; y < y1; y++) {
+            for(size_t x = x0; x < x1; x++) {
+                auto inp_i = b * inp_strides[0] + c * inp_strides[1] + y * inp_strides[2] + x * inp_strides[3];
+                tmp = accum(op, tmp, inp[inp_i]);
+            }
+        }
+
+        out[i] = normalize(op, tmp, (y1 - y0) * (x1 - x0));
+    }
+}
+
+template<typename T>
+__device__ void adaptive_pool2d_bwd(
+    const AdaptivePool2dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const T *inp, // 4d (Batch, Channels, Height, Width)
+    T *grad_inp,
+    const T *out, // 4d (Batch, Channels, HeightOut, WidthOut)
+    const T *grad_out
+) {
+    const size_t numel = op.batch * op.chan * op.h_in * op.w_in;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t x = idx % op.w_in;
+        idx /= op.w_in;
+        const size_t y = idx % op.h_in;
+        idx /= op.h_in;
+        const size_t c = idx % op.chan;
+        idx /= op.chan;
+        const size_t b = idx % op.batch;
+
+        const T inp_v = inp[i];
+
+        // the outputs whose windows can contain (y, x), with the roles of in & out swapped
+        const size_t oh0 = window_start(y, op.h_out, op.h_in);
+        const size_t oh1 = window_end(y, op.h_out, op.h_in);
+        const size_t ow0 = window_start(x, op.w_out, op.w_in);
+        const size_t ow1 = window_end(x, op.w_out, op.w_in);
+
+        T tmp = 0.0;
+        for(size_t oh = oh0; oh < oh1; oh++) {
+            const size_t y0 = window_start(oh, op.h_in, op.h_out);
+            const size_t y1 = window_end(oh, op.h_in, op.h_out);
+            if (y < y0 || y >= y1) { continue; }
+            for(size_t ow = ow0; ow < ow1; ow++) {
+                const size_t x0 = window_start(ow, op.w_in, op.w_out);
+                const size_t x1 = window_end(ow, op.w_in, op.w_out);
+                if (x < x0 || x >= x1) { continue; }
+
+                auto out_i = b * out_strides[0] + c * out_strides[1] + oh * out_strides[2] + ow * out_strides[3];
+                tmp += normalize(op, filter(op, grad_out[out_i], out[out_i], inp_v), (y1 - y0) * (x1 - x0));
+            }
+        }
+        grad_inp[i] += tmp;
+    }
+}
+
+#define POOL_OP(TYPENAME, fwd, bwd) \
+extern "C" __global__ void fwd( \
+    const AdaptivePool2dOp op, \
+    const size_t *inp_strides, \
+    const size_t *out_strides, \
+    const TYPENAME *inp, \
+    TYPENAME *out \
+) { \
+    adaptive_pool2d_fwd(op, inp_strides, out_strides, inp, out); \
+} \
+extern "C" __global__ void bwd( \
+    const AdaptivePool2dOp op, \
+    const size_t *inp_strides, \
+    const size_t *out_strides, \
+    const TYPENAME *inp, \
+    TYPENAME *grad_inp, \
+    const TYPENAME *out, \
+    const TYPENAME *grad_out \
+) { \
+    adaptive_pool2d_bwd(op, inp_strides, out_strides, inp, grad_inp, out, grad_out); \
+}
+
+POOL_OP(__half, adaptive_pool2d_fwd_f16, adaptive_pool2d_bwd_f16);
+POOL_OP(float, adaptive_pool2d_fwd_f32, adaptive_pool2d_bwd_f32);
+POOL_OP(double, adaptive_pool2d_fwd_f64, adaptive_pool2d_bwd_f64);
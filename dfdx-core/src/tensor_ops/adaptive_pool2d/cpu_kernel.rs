@@ -0,0 +1,88 @@
+use crate::{shapes::*, tensor::*};
+
+use std::sync::Arc;
+
+use num_traits::Float;
+
+use super::window;
+
+fn make_4d<S: Shape>(strides: S::Concrete) -> [usize; 4] {
+    match S::NUM_DIMS {
+        3 => [0, strides[0], strides[1], strides[2]],
+        4 => [strides[0], strides[1], strides[2], strides[3]],
+        _ => panic!("Only implemented for 3d & 4d arrays"),
+    }
+}
+
+impl<E: Float + Dtype> super::AdaptivePool2DKernel<E> for Cpu {
+    fn alloc<S: Shape>(&self, s: S) -> Result<Tensor<S, E, Self>, Error> {
+        self.try_zeros_like(&s)
+    }
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: super::AdaptivePool2DOp,
+        inp: &Tensor<I, E, Self>,
+        out: &mut Tensor<O, E, Self>,
+    ) -> Result<(), Error> {
+        let istr = make_4d::<I>(inp.strides);
+        let ostr = make_4d::<O>(out.strides);
+
+        let buf = inp.data.as_ref();
+        let out_buf = Arc::make_mut(&mut out.data);
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    let ys = window(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let xs = window(ow, op.w_in, op.w_out);
+                        let mut tmp = op.kind.init();
+                        for y in ys.clone() {
+                            for x in xs.clone() {
+                                let inp_idx = b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3];
+                                tmp = op.kind.accum(&tmp, &buf[inp_idx]);
+                            }
+                        }
+                        tmp = op.kind.normalize(tmp, ys.len() * xs.len());
+                        out_buf[b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3]] = tmp;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: super::AdaptivePool2DOp,
+        inp: &Tensor<I, E, Self>,
+        grad_inp: &mut Self::Vec,
+        out: &Tensor<O, E, Self>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error> {
+        let istr = make_4d::<I>(inp.strides);
+        let ostr = make_4d::<O>(out.strides);
+
+        let inp_buf = inp.data.as_ref();
+        let out_buf = out.data.as_ref();
+
+        for b in 0..op.batch {
+            for c in 0..op.chan {
+                for oh in 0..op.h_out {
+                    let ys = window(oh, op.h_in, op.h_out);
+                    for ow in 0..op.w_out {
+                        let xs = window(ow, op.w_in, op.w_out);
+                        let out_idx = b * ostr[0] + c * ostr[1] + oh * ostr[2] + ow * ostr[3];
+                        let go = op.kind.normalize(grad_out[out_idx], ys.len() * xs.len());
+                        let vo = out_buf[out_idx];
+                        for y in ys.clone() {
+                            for x in xs.clone() {
+                                let inp_idx = b * istr[0] + c * istr[1] + y * istr[2] + x * istr[3];
+                                grad_inp[inp_idx] += op.kind.filter(go, inp_buf[inp_idx], vo);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
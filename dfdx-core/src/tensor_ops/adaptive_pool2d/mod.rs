@@ -0,0 +1,229 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+use super::{Pool2DKind, ReshapeTo};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct AdaptivePool2DOp {
+    pub kind: Pool2DKind,
+    pub batch: usize,
+    pub chan: usize,
+    pub h_in: usize,
+    pub h_out: usize,
+    pub w_in: usize,
+    pub w_out: usize,
+}
+
+/// The range of input indices that output index `o` pools over, when `dim_in`
+/// is pooled down to `dim_out`. Neighboring windows may overlap when `dim_out`
+/// doesn't divide `dim_in`.
+#[inline(always)]
+pub(super) fn window(o: usize, dim_in: usize, dim_out: usize) -> std::ops::Range<usize> {
+    let start = (o * dim_in) / dim_out;
+    let end = ((o + 1) * dim_in + dim_out - 1) / dim_out;
+    start..end
+}
+
+pub(super) trait AdaptivePool2DKernel<E: Dtype>: Storage<E> {
+    fn alloc<S: Shape>(&self, s: S) -> Result<Tensor<S, E, Self>, Error>;
+
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: AdaptivePool2DOp,
+        inp: &Tensor<I, E, Self>,
+        out: &mut Tensor<O, E, Self>,
+    ) -> Result<(), Error>;
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: AdaptivePool2DOp,
+        inp: &Tensor<I, E, Self>,
+        grad_inp: &mut Self::Vec,
+        out: &Tensor<O, E, Self>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+/// Pools images (3d) and batches of images (4d) down to a fixed output height & width,
+/// no matter the size of the input.
+///
+/// Output index `i` of an axis pools over input indices `floor(i * in / out)..ceil((i + 1) * in / out)`,
+/// the same as pytorch's `AdaptiveAvgPool2d` & `AdaptiveMaxPool2d`.
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank4<2, 3, 13, 9>, f32, _> = dev.sample_normal();
+/// let y: Tensor<Rank4<2, 3, 4, 4>, f32, _> =
+///     x.adaptive_pool2d(Pool2DKind::Avg, Const::<4>, Const::<4>);
+/// ```
+pub trait TryAdaptivePool2D<OutH, OutW>: Sized {
+    type Pooled;
+
+    fn adaptive_pool2d(self, kind: Pool2DKind, out_h: OutH, out_w: OutW) -> Self::Pooled {
+        self.try_adaptive_pool2d(kind, out_h, out_w).unwrap()
+    }
+
+    fn try_adaptive_pool2d(
+        self,
+        kind: Pool2DKind,
+        out_h: OutH,
+        out_w: OutW,
+    ) -> Result<Self::Pooled, Error>;
+}
+
+impl<Chan, H, W, OutH, OutW, E, D, T> TryAdaptivePool2D<OutH, OutW>
+    for Tensor<(Chan, H, W), E, D, T>
+where
+    Chan: Dim,
+    H: Dim,
+    W: Dim,
+    OutH: Dim,
+    OutW: Dim,
+    E: Dtype,
+    D: AdaptivePool2DKernel<E> + crate::tensor_ops::reshape_to::ReshapeKernel<E>,
+    T: Tape<E, D>,
+{
+    type Pooled = Tensor<(Chan, OutH, OutW), E, D, T>;
+
+    fn try_adaptive_pool2d(
+        self,
+        kind: Pool2DKind,
+        out_h: OutH,
+        out_w: OutW,
+    ) -> Result<Self::Pooled, Error> {
+        let (chan, h, w) = self.shape;
+        let img = self.try_reshape_like(&(Const::<1>, chan, h, w))?;
+        let out = img.try_adaptive_pool2d(kind, out_h, out_w)?;
+        out.try_reshape_like(&(chan, out_h, out_w))
+    }
+}
+
+impl<Batch, Chan, H, W, OutH, OutW, E, D, T> TryAdaptivePool2D<OutH, OutW>
+    for Tensor<(Batch, Chan, H, W), E, D, T>
+where
+    Batch: Dim,
+    Chan: Dim,
+    H: Dim,
+    W: Dim,
+    OutH: Dim,
+    OutW: Dim,
+    E: Dtype,
+    D: AdaptivePool2DKernel<E>,
+    T: Tape<E, D>,
+{
+    type Pooled = Tensor<(Batch, Chan, OutH, OutW), E, D, T>;
+
+    fn try_adaptive_pool2d(
+        self,
+        kind: Pool2DKind,
+        out_h: OutH,
+        out_w: OutW,
+    ) -> Result<Self::Pooled, Error> {
+        let (batch, chan, h, w) = self.shape;
+        assert!(out_h.size() > 0 && out_w.size() > 0);
+        if self.strides != self.shape.strides() {
+            panic!("Image input to adaptive_pool2d must be contiguous");
+        }
+        let op = AdaptivePool2DOp {
+            kind,
+            batch: batch.size(),
+            chan: chan.size(),
+            h_in: h.size(),
+            h_out: out_h.size(),
+            w_in: w.size(),
+            w_out: out_w.size(),
+        };
+        let (img, mut tape) = self.split_tape();
+        let mut out = img.device.alloc((batch, chan, out_h, out_w))?;
+        img.device.forward(op, &img, &mut out)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("AdaptivePool2D")
+                .attr("kind", Attr::String(format!("{kind:?}")))
+                .input(&img)
+                .output(&out);
+            tape.add_op_node(node.flops(img.shape.num_elements()));
+        }
+        let img_ghost = img.ghost();
+        let out_ghost = out.ghost();
+        let out_clone = out.clone();
+        tape.add_backward_op(move |grads| {
+            grads.try_alloc_for(&img_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_img, grad_out) = grads.mut_and_ref(&img_ghost, &out_ghost);
+            img.device
+                .backward(op, &img, grad_img, &out_clone, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_adaptive_window() {
+        let windows: std::vec::Vec<_> = (0..3).map(|o| window(o, 5, 3)).collect();
+        assert_eq!(windows, [0..2, 1..4, 3..5]);
+        let windows: std::vec::Vec<_> = (0..2).map(|o| window(o, 4, 2)).collect();
+        assert_eq!(windows, [0..2, 2..4]);
+        let windows: std::vec::Vec<_> = (0..3).map(|o| window(o, 2, 3)).collect();
+        assert_eq!(windows, [0..1, 0..2, 1..2]);
+    }
+
+    #[test]
+    fn test_adaptive_pool2d_divisible_matches_pool2d() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank4<2, 3, 6, 4>, TestDtype, _> = dev.sample_normal();
+        for kind in [Pool2DKind::Avg, Pool2DKind::Min, Pool2DKind::Max] {
+            let a = x.clone().adaptive_pool2d(kind, Const::<3>, Const::<2>);
+            let b = x
+                .clone()
+                .pool2d(kind, Const::<2>, Const::<2>, Const::<0>, Const::<1>);
+            assert_close_to_tensor!(a, b.realize::<Rank4<2, 3, 3, 2>>());
+        }
+    }
+
+    #[test]
+    fn test_adaptive_pool2d_3d_avg_overlapping() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[[1.0, 2.0, 3.0, 4.0, 5.0], [6.0, 7.0, 8.0, 9.0, 10.0]]])
+            .to_dtype::<TestDtype>();
+        let r = x
+            .leaky_trace()
+            .adaptive_pool2d(Pool2DKind::Avg, Const::<1>, Const::<3>);
+        assert_close_to_literal!(r, [[[4.0, 5.5, 7.0]]]);
+        let g = r.sum().backward();
+        let (a, b) = (1.0 / 4.0, 1.0 / 6.0);
+        assert_close_to_literal!(
+            g.get(&x),
+            [[[a, a + b, b, b + a, a], [a, a + b, b, b + a, a]]]
+        );
+    }
+
+    #[test]
+    fn test_adaptive_pool2d_4d_max() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[[[0.5, -1.0, 2.0], [3.0, 0.0, -2.0], [1.0, 4.0, 0.5]]]])
+            .to_dtype::<TestDtype>();
+        let r = x
+            .leaky_trace()
+            .adaptive_pool2d(Pool2DKind::Max, 2, 2)
+            .realize::<Rank4<1, 1, 2, 2>>();
+        assert_close_to_literal!(r, [[[[3.0, 2.0], [4.0, 4.0]]]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(
+            g.get(&x),
+            [[[[0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 2.0, 0.0]]]]
+        );
+    }
+}
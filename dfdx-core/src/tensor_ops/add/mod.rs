@@ -6,10 +6,10 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::*;
+use super::{ops::*, Device};
 use crate::{
     shapes::*,
-    tensor::{Error, Merge, Storage, Tape, Tensor},
+    tensor::{Attr, Error, Merge, OwnedTape, Storage, Tape, Tensor, WithEmptyTape},
 };
 
 #[repr(C)]
@@ -95,6 +95,40 @@ where
     }
 }
 
+impl KernelOpInfo for BinaryAddKernelOp {
+    const NAME: &'static str = "BinaryAdd";
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for BinaryAddKernelOp {
+    fn try_grads<S: Shape, D: Device<E>>(
+        &self,
+        _lhs: &Tensor<S, E, D>,
+        _rhs: &Tensor<S, E, D>,
+        grad_out: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<(Tensor<S, E, D, OwnedTape<E, D>>, Tensor<S, E, D, OwnedTape<E, D>>), Error> {
+        Ok((grad_out.with_empty_tape(), grad_out))
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for ScalarAddKernelOp<E> {
+    const NAME: &'static str = "ScalarAdd";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("scalar", Attr::Float(self.scalar.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ScalarAddKernelOp<E> {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        Ok(grad_out)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_binary_op, BinaryKernel};
+use super::ops::{try_binary_op, BinaryGraphDerivative, BinaryKernel, KernelOpInfo};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -60,6 +60,12 @@ impl<S: Shape, E: Dtype, D: BinaryKernel<BCEKernelOp, E>, LTape: Tape<E, D>>
     }
 }
 
+impl KernelOpInfo for BCEKernelOp {
+    const NAME: &'static str = "BCE";
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for BCEKernelOp {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tests::*};
@@ -65,14 +65,23 @@ impl<S: Shape, E, D: Storage<E>, T: Tape<E, D>> BroadcastTo for Tensor<S, E, D,
     {
         self.shape().check(dst.shape());
 
-        Ok(Tensor {
+        let node = self
+            .tape
+            .captures_ops()
+            .then(|| OpNode::new("Broadcast").axes::<Ax>().view_input(&self));
+        let mut out = Tensor {
             id: self.id,
             data: self.data,
             shape: *dst.shape(),
             strides: self.shape.broadcast_strides(self.strides),
             device: self.device,
             tape: self.tape,
-        })
+        };
+        if let Some(node) = node {
+            let node = node.output(&out);
+            out.tape.add_op_node(node);
+        }
+        Ok(out)
     }
 }
 
@@ -171,4 +180,22 @@ mod tests {
             .backward();
         assert_close_to_tensor!(g.get(&a), a.exp() / 3.0);
     }
+
+    #[test]
+    fn test_broadcast_8d() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank3<2, 3, 4>, TestDtype, _> = dev.sample_normal();
+        let b = a
+            .leaky_trace()
+            .broadcast::<Rank8<2, 2, 3, 1, 2, 4, 1, 2>, Axes5<1, 3, 4, 6, 7>>();
+        let b_vec = b.as_vec();
+        let a_vec = a.as_vec();
+        for (i, v) in b_vec.iter().enumerate() {
+            // dims of `b` are [2, 2, 3, 1, 2, 4, 1, 2], & `a` is at dims 0, 2 and 5
+            let (i0, i2, i5) = (i / 96, (i / 16) % 3, (i / 2) % 4);
+            assert_eq!(*v, a_vec[i0 * 12 + i2 * 4 + i5]);
+        }
+        let g = b.sum().backward();
+        assert_close_to_literal!(g.get(&a), [[[8.0; 4]; 3]; 2]);
+    }
 }
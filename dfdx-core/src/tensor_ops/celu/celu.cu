@@ -0,0 +1,32 @@
+#include "unary_op_macros.cuh"
+
+template<typename F>
+struct CELUKernelOp {
+    F alpha;
+};
+
+template<typename T>
+__device__ __forceinline__ T celu_fwd(T x, T alpha) {
+    T zero = 0.0;
+    T one = 1.0;
+    return x > zero ? x : alpha * (expg(x / alpha) - one);
+}
+
+template<typename T>
+__device__ __forceinline__ T celu_bwd(T x, T alpha) {
+    T zero = 0.0;
+    T one = 1.0;
+    return x > zero ? one : expg(x / alpha);
+}
+
+UNARY_OP(__half, celu_fwd_f16, celu_bwd_f16, CELUKernelOp<__half>,
+    celu_fwd(x, op.alpha),
+    celu_bwd(x, op.alpha))
+
+UNARY_OP(float, celu_fwd_f32, celu_bwd_f32, CELUKernelOp<float>,
+    celu_fwd(x, op.alpha),
+    celu_bwd(x, op.alpha))
+
+UNARY_OP(double, celu_fwd_f64, celu_bwd_f64, CELUKernelOp<double>,
+    celu_fwd(x, op.alpha),
+    celu_bwd(x, op.alpha))
@@ -0,0 +1,26 @@
+use super::CELUKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for CELUKernelOp<AMP<f16>> {}
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for CELUKernelOp<f16> {}
+unsafe impl cudarc::driver::DeviceRepr for CELUKernelOp<f32> {}
+unsafe impl cudarc::driver::DeviceRepr for CELUKernelOp<f64> {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/celu.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(CELUKernelOp<f16>, f16, PTX, "celu_fwd_f16", "celu_bwd_f16");
+#[cfg(feature = "f16")]
+cuda_unary!(
+    CELUKernelOp<AMP<f16>>,
+    AMP<f16>,
+    PTX,
+    "celu_fwd_f16",
+    "celu_bwd_f16"
+);
+cuda_unary!(CELUKernelOp<f32>, f32, PTX, "celu_fwd_f32", "celu_bwd_f32");
+cuda_unary!(CELUKernelOp<f64>, f64, PTX, "celu_fwd_f64", "celu_bwd_f64");
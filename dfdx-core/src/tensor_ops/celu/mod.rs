@@ -0,0 +1,81 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct CELUKernelOp<E> {
+    pub alpha: E,
+}
+
+/// [Continuously Differentiable Exponential Linear Unit (CELU)](https://arxiv.org/abs/1704.07483).
+/// `t` if `t > 0`, otherwise `alpha * (exp(t / alpha) - 1)`
+///
+/// Arguments:
+/// - `alpha`: The scale of the negative part, must not be `0`.
+///
+/// The derivative is `1` if `t > 0`, otherwise `exp(t / alpha)`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.celu(2.0);
+/// ```
+pub fn celu<S: Shape, E: Dtype, D: UnaryKernel<CELUKernelOp<E>, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+    alpha: impl Into<f64>,
+) -> Tensor<S, E, D, T> {
+    t.celu(alpha)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<CELUKernelOp<E>, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [celu]
+    pub fn celu(self, alpha: impl Into<f64>) -> Self {
+        self.try_celu(alpha).unwrap()
+    }
+    /// See [celu]
+    pub fn try_celu(self, alpha: impl Into<f64>) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(
+            CELUKernelOp {
+                alpha: E::from_f64(alpha.into()).unwrap(),
+            },
+            self,
+        )
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for CELUKernelOp<E> {
+    const NAME: &'static str = "CELU";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("alpha", Attr::Float(self.alpha.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for CELUKernelOp<E> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_celu() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().celu(2.0);
+        assert_close_to_literal!(r, [-1.7293294, -1.0552669, 0.5, 2.0, 7.0]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&x), [0.027067057, 0.094473311, 0.2, 0.2, 0.2]);
+    }
+}
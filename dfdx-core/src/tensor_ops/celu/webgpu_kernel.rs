@@ -0,0 +1,5 @@
+use crate::prelude::webgpu_kernels::webgpu_unary;
+
+const WGSL: &[u8] = b"TODO";
+
+webgpu_unary!(super::CELUKernelOp<f32>, f32, WGSL, WGSL);
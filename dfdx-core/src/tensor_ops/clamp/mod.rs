@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -55,6 +55,19 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<ClampKernelOp<E>, E>, T: Tape<E, D>> Ten
     }
 }
 
+impl<E: Dtype> KernelOpInfo for ClampKernelOp<E> {
+    const NAME: &'static str = "Clamp";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![
+            ("min", Attr::Float(self.min.to_f64().unwrap())),
+            ("max", Attr::Float(self.max.to_f64().unwrap())),
+        ]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ClampKernelOp<E> {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -132,6 +132,8 @@ impl_concat!([D1 1, D2 2]);
 impl_concat!([D1 1, D2 2, D3 3]);
 impl_concat!([D1 1, D2 2, D3 3, D4 4]);
 impl_concat!([D1 1, D2 2, D3 3, D4 4, D5 5]);
+impl_concat!([D1 1, D2 2, D3 3, D4 4, D5 5, D6 6]);
+impl_concat!([D1 1, D2 2, D3 3, D4 4, D5 5, D6 6, D7 7]);
 
 impl<const N: usize> ConcatShape<[usize; N]> for [usize; N]
 where
@@ -93,26 +93,43 @@ impl_concat!(0, 3, [], [D1, D2]);
 impl_concat!(0, 4, [], [D1, D2, D3]);
 impl_concat!(0, 5, [], [D1, D2, D3, D4]);
 impl_concat!(0, 6, [], [D1, D2, D3, D4, D5]);
+impl_concat!(0, 7, [], [D1, D2, D3, D4, D5, D6]);
+impl_concat!(0, 8, [], [D1, D2, D3, D4, D5, D6, D7]);
 
 impl_concat!(1, 2, [D0], []);
 impl_concat!(1, 3, [D0], [D2]);
 impl_concat!(1, 4, [D0], [D2, D3]);
 impl_concat!(1, 5, [D0], [D2, D3, D4]);
 impl_concat!(1, 6, [D0], [D2, D3, D4, D5]);
+impl_concat!(1, 7, [D0], [D2, D3, D4, D5, D6]);
+impl_concat!(1, 8, [D0], [D2, D3, D4, D5, D6, D7]);
 
 impl_concat!(2, 3, [D0, D1], []);
 impl_concat!(2, 4, [D0, D1], [D3]);
 impl_concat!(2, 5, [D0, D1], [D3, D4]);
 impl_concat!(2, 6, [D0, D1], [D3, D4, D5]);
+impl_concat!(2, 7, [D0, D1], [D3, D4, D5, D6]);
+impl_concat!(2, 8, [D0, D1], [D3, D4, D5, D6, D7]);
 
 impl_concat!(3, 4, [D0, D1, D2], []);
 impl_concat!(3, 5, [D0, D1, D2], [D4]);
 impl_concat!(3, 6, [D0, D1, D2], [D4, D5]);
+impl_concat!(3, 7, [D0, D1, D2], [D4, D5, D6]);
+impl_concat!(3, 8, [D0, D1, D2], [D4, D5, D6, D7]);
 
 impl_concat!(4, 5, [D0, D1, D2, D3], []);
 impl_concat!(4, 6, [D0, D1, D2, D3], [D5]);
+impl_concat!(4, 7, [D0, D1, D2, D3], [D5, D6]);
+impl_concat!(4, 8, [D0, D1, D2, D3], [D5, D6, D7]);
 
 impl_concat!(5, 6, [D0, D1, D2, D3, D4], []);
+impl_concat!(5, 7, [D0, D1, D2, D3, D4], [D6]);
+impl_concat!(5, 8, [D0, D1, D2, D3, D4], [D6, D7]);
+
+impl_concat!(6, 7, [D0, D1, D2, D3, D4, D5], []);
+impl_concat!(6, 8, [D0, D1, D2, D3, D4, D5], [D7]);
+
+impl_concat!(7, 8, [D0, D1, D2, D3, D4, D5, D6], []);
 
 #[cfg(test)]
 mod tests {
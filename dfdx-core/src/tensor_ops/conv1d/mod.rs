@@ -48,7 +48,8 @@ pub trait Conv1DKernel<E: Dtype>: Storage<E> {
 
 /// Applies a 1d convolution to a tensor.
 ///
-/// [Const] dims **require nightly**:
+/// Convolved [Const] dims **require nightly**. On stable the same call compiles,
+/// but the output length is a [usize]:
 /// ```ignore
 /// #![feature(generic_const_exprs)]
 /// # use dfdx_core::prelude::*;
@@ -133,6 +134,22 @@ where
     }
 }
 
+#[cfg(not(feature = "nightly"))]
+impl<const DIM: usize, Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
+    TryConv1D<Stride, Padding, Dilation, Groups> for (Const<DIM>, Kernel)
+{
+    type Convolved = usize;
+    fn try_conv1d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        (DIM, self.1).try_conv1d(stride, padding, dilation, groups)
+    }
+}
+
 impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
     TryConv1D<Stride, Padding, Dilation, Groups> for (usize, Kernel)
 {
@@ -153,11 +170,23 @@ impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
     }
 }
 
-impl<InpChan, OutChan, Kernel, Stride, Padding, Dilation, Groups, L, E, D, T>
-    TryConv1D<Stride, Padding, Dilation, Groups>
+impl<
+        InpChan,
+        InpChanOverGroups,
+        OutChan,
+        Kernel,
+        Stride,
+        Padding,
+        Dilation,
+        Groups,
+        L,
+        E,
+        D,
+        T,
+    > TryConv1D<Stride, Padding, Dilation, Groups>
     for (
         Tensor<(InpChan, L), E, D, T>,
-        Tensor<(OutChan, <InpChan as std::ops::Div<Groups>>::Output, Kernel), E, D>,
+        Tensor<(OutChan, InpChanOverGroups, Kernel), E, D>,
     )
 where
     InpChan: Dim,
@@ -171,8 +200,7 @@ where
     E: Dtype,
     D: Conv1DKernel<E> + crate::tensor_ops::reshape_to::ReshapeKernel<E>,
     T: Tape<E, D>,
-    InpChan: std::ops::Div<Groups>,
-    <InpChan as std::ops::Div<Groups>>::Output: Dim,
+    InpChanOverGroups: Dim,
     (L, Kernel): TryConv1D<Stride, Padding, Dilation, Groups>,
     <(L, Kernel) as TryConv1D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
 {
@@ -201,11 +229,24 @@ where
     }
 }
 
-impl<InpChan, OutChan, Kernel, Stride, Padding, Dilation, Groups, Batch, L, E, D, T>
-    TryConv1D<Stride, Padding, Dilation, Groups>
+impl<
+        InpChan,
+        InpChanOverGroups,
+        OutChan,
+        Kernel,
+        Stride,
+        Padding,
+        Dilation,
+        Groups,
+        Batch,
+        L,
+        E,
+        D,
+        T,
+    > TryConv1D<Stride, Padding, Dilation, Groups>
     for (
         Tensor<(Batch, InpChan, L), E, D, T>,
-        Tensor<(OutChan, <InpChan as std::ops::Div<Groups>>::Output, Kernel), E, D>,
+        Tensor<(OutChan, InpChanOverGroups, Kernel), E, D>,
     )
 where
     InpChan: Dim,
@@ -220,8 +261,7 @@ where
     E: Dtype,
     D: Conv1DKernel<E>,
     T: Tape<E, D>,
-    InpChan: std::ops::Div<Groups>,
-    <InpChan as std::ops::Div<Groups>>::Output: Dim,
+    InpChanOverGroups: Dim,
     (L, Kernel): TryConv1D<Stride, Padding, Dilation, Groups>,
     <(L, Kernel) as TryConv1D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
 {
@@ -245,8 +285,7 @@ where
         let (img, filters) = self;
         assert_eq!(img.shape.1.size(), filters.shape.1.size() * groups.size());
         let (batch, inp_chan, l) = img.shape;
-        let (out_chan, inp_chan_over_groups, kernel) = filters.shape;
-        assert_eq!(inp_chan / groups, inp_chan_over_groups);
+        let (out_chan, _, kernel) = filters.shape;
         assert!(out_chan.size() % groups.size() == 0);
         if img.strides != img.shape.strides() || filters.strides != filters.shape.strides() {
             panic!("Image & filter inputs to conv1d must be contiguous");
@@ -269,6 +308,18 @@ where
         let mut out = lhs.device.alloc((batch, out_chan, l_out))?;
         let mut tape = ltape.merge(rtape);
         lhs.device.forward(op, &lhs, &rhs, &mut out)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Conv1D")
+                .attr("stride", Attr::Int(op.stride as i64))
+                .attr("padding", Attr::Int(op.padding as i64))
+                .attr("dilation", Attr::Int(op.dilation as i64))
+                .attr("groups", Attr::Int(op.groups as i64))
+                .input(&lhs)
+                .input(&rhs)
+                .output(&out);
+            let flops_per_out = 2 * op.kernel * op.chan_in / op.groups;
+            tape.add_op_node(node.flops(flops_per_out * out.shape.num_elements()));
+        }
         let lhs_ghost = lhs.ghost();
         let rhs_ghost = rhs.ghost();
         let out_ghost = out.ghost();
@@ -10,8 +10,9 @@ fn test_conv1d_default_stride_and_padding() {
     let x = dev
         .tensor([[1.33830595, 0.79225832]])
         .to_dtype::<TestDtype>();
-    let result =
-        (x.leaky_trace(), weight.clone()).conv1d(Const::<1>, Const::<0>, Const::<1>, Const::<1>);
+    let result = (x.leaky_trace(), weight.clone())
+        .conv1d(Const::<1>, Const::<0>, Const::<1>, Const::<1>)
+        .realize::<Rank2<2, 1>>();
     assert_close_to_literal!(result, [[0.89424950], [-0.83389091]]);
     let g = result.exp().mean().backward();
     assert_close_to_literal!(g.get(&x), [[0.42308712, 0.43687853]]);
@@ -44,8 +45,9 @@ fn test_conv1d_all() {
         ])
         .to_dtype::<TestDtype>();
 
-    let result =
-        (x.leaky_trace(), weight.clone()).conv1d(Const::<2>, Const::<1>, Const::<2>, Const::<1>);
+    let result = (x.leaky_trace(), weight.clone())
+        .conv1d(Const::<2>, Const::<1>, Const::<2>, Const::<1>)
+        .realize::<Rank2<3, 3>>();
     assert_close_to_literal!(
         result,
         [
@@ -99,7 +101,9 @@ fn test_conv1d_all_grouped() {
         ])
         .to_dtype::<TestDtype>();
 
-    let y = (x.leaky_trace(), w.clone()).conv1d(Const::<2>, Const::<1>, Const::<2>, Const::<2>);
+    let y = (x.leaky_trace(), w.clone())
+        .conv1d(Const::<2>, Const::<1>, Const::<2>, Const::<2>)
+        .realize::<Rank2<4, 3>>();
     assert_close_to_literal!(
         y,
         [
@@ -136,8 +140,9 @@ fn test_batched_conv1d() {
     let x: Tensor<Rank2<3, 28>, TestDtype, _> = dev.sample_normal();
     let w: Tensor<Rank3<5, 3, 6>, TestDtype, _> = dev.sample_normal();
 
-    let y: Tensor<Rank2<5, 9>, _, _, _> =
-        (x.leaky_trace(), w.clone()).conv1d(Const::<3>, Const::<2>, Const::<1>, Const::<1>);
+    let y: Tensor<Rank2<5, 9>, _, _, _> = (x.leaky_trace(), w.clone())
+        .conv1d(Const::<3>, Const::<2>, Const::<1>, Const::<1>)
+        .realize();
     let y0 = y.retaped::<NoneTape>();
     let grads0 = y.square().mean().backward();
     let x0 = grads0.get(&x);
@@ -148,8 +153,9 @@ fn test_batched_conv1d() {
         .reshape::<Rank3<10, 3, 28>>();
     assert_eq!(x.strides, x.shape.strides());
 
-    let y: Tensor<Rank3<10, 5, 9>, _, _, _> =
-        (x.leaky_trace(), w.clone()).conv1d(Const::<3>, Const::<2>, Const::<1>, Const::<1>);
+    let y: Tensor<Rank3<10, 5, 9>, _, _, _> = (x.leaky_trace(), w.clone())
+        .conv1d(Const::<3>, Const::<2>, Const::<1>, Const::<1>)
+        .realize();
     for i in 0..10 {
         assert_close_to_tensor!(y0, y.retaped::<NoneTape>().select(dev.tensor(i)));
     }
@@ -54,7 +54,8 @@ pub(super) trait Conv2DKernel<E: Dtype>: Storage<E> {
 
 /// Apply the 2d convolution to a tensor.
 ///
-/// [Const] dims **require nightly**:
+/// Convolved [Const] dims **require nightly**. On stable the same call compiles,
+/// but the output height & width are [usize]:
 /// ```ignore
 /// #![feature(generic_const_exprs)]
 /// # use dfdx_core::prelude::*;
@@ -116,6 +117,7 @@ pub trait TryConv2D<Stride, Padding, Dilation, Groups>: Sized {
     ) -> Result<Self::Convolved, Error>;
 }
 
+#[cfg(feature = "nightly")]
 impl<
         const KERNEL: usize,
         const STRIDE: usize,
@@ -140,6 +142,22 @@ where
     }
 }
 
+#[cfg(not(feature = "nightly"))]
+impl<const DIM: usize, Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
+    TryConv2D<Stride, Padding, Dilation, Groups> for (Const<DIM>, Kernel)
+{
+    type Convolved = usize;
+    fn try_conv2d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        (DIM, self.1).try_conv2d(stride, padding, dilation, groups)
+    }
+}
+
 impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
     TryConv2D<Stride, Padding, Dilation, Groups> for (usize, Kernel)
 {
@@ -160,20 +178,24 @@ impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
     }
 }
 
-impl<InpChan, OutChan, Kernel, Stride, Padding, Dilation, Groups, H, W, E, D, T>
-    TryConv2D<Stride, Padding, Dilation, Groups>
+impl<
+        InpChan,
+        InpChanOverGroups,
+        OutChan,
+        Kernel,
+        Stride,
+        Padding,
+        Dilation,
+        Groups,
+        H,
+        W,
+        E,
+        D,
+        T,
+    > TryConv2D<Stride, Padding, Dilation, Groups>
     for (
         Tensor<(InpChan, H, W), E, D, T>,
-        Tensor<
-            (
-                OutChan,
-                <InpChan as std::ops::Div<Groups>>::Output,
-                Kernel,
-                Kernel,
-            ),
-            E,
-            D,
-        >,
+        Tensor<(OutChan, InpChanOverGroups, Kernel, Kernel), E, D>,
     )
 where
     InpChan: Dim,
@@ -188,8 +210,7 @@ where
     E: Dtype,
     D: Conv2DKernel<E> + crate::tensor_ops::reshape_to::ReshapeKernel<E>,
     T: Tape<E, D>,
-    InpChan: std::ops::Div<Groups>,
-    <InpChan as std::ops::Div<Groups>>::Output: Dim,
+    InpChanOverGroups: Dim,
     (H, Kernel): TryConv2D<Stride, Padding, Dilation, Groups>,
     (W, Kernel): TryConv2D<Stride, Padding, Dilation, Groups>,
     <(H, Kernel) as TryConv2D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
@@ -222,20 +243,25 @@ where
     }
 }
 
-impl<InpChan, OutChan, Kernel, Stride, Padding, Dilation, Groups, Batch, H, W, E, D, T>
-    TryConv2D<Stride, Padding, Dilation, Groups>
+impl<
+        InpChan,
+        InpChanOverGroups,
+        OutChan,
+        Kernel,
+        Stride,
+        Padding,
+        Dilation,
+        Groups,
+        Batch,
+        H,
+        W,
+        E,
+        D,
+        T,
+    > TryConv2D<Stride, Padding, Dilation, Groups>
     for (
         Tensor<(Batch, InpChan, H, W), E, D, T>,
-        Tensor<
-            (
-                OutChan,
-                <InpChan as std::ops::Div<Groups>>::Output,
-                Kernel,
-                Kernel,
-            ),
-            E,
-            D,
-        >,
+        Tensor<(OutChan, InpChanOverGroups, Kernel, Kernel), E, D>,
     )
 where
     InpChan: Dim,
@@ -251,8 +277,7 @@ where
     E: Dtype,
     D: Conv2DKernel<E>,
     T: Tape<E, D>,
-    InpChan: std::ops::Div<Groups>,
-    <InpChan as std::ops::Div<Groups>>::Output: Dim,
+    InpChanOverGroups: Dim,
     (H, Kernel): TryConv2D<Stride, Padding, Dilation, Groups>,
     (W, Kernel): TryConv2D<Stride, Padding, Dilation, Groups>,
     <(H, Kernel) as TryConv2D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
@@ -281,8 +306,7 @@ where
         assert_eq!(img.shape.1.size(), filters.shape.1.size() * groups.size());
         assert_eq!(filters.shape.2, filters.shape.3);
         let (batch, inp_chan, h, w) = img.shape;
-        let (out_chan, inp_chan_over_groups, kernel, _) = filters.shape;
-        assert_eq!(inp_chan / groups, inp_chan_over_groups);
+        let (out_chan, _, kernel, _) = filters.shape;
         assert!(out_chan.size() % groups.size() == 0);
         if img.strides != img.shape.strides() || filters.strides != filters.shape.strides() {
             panic!("Image & filter inputs to conv2d must be contiguous");
@@ -308,6 +332,18 @@ where
         let mut out = lhs.device.alloc((batch, out_chan, h_out, w_out))?;
         let mut tape = ltape.merge(rtape);
         lhs.device.forward(op, &lhs, &rhs, &mut out)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Conv2D")
+                .attr("stride", Attr::Int(op.stride as i64))
+                .attr("padding", Attr::Int(op.padding as i64))
+                .attr("dilation", Attr::Int(op.dilation as i64))
+                .attr("groups", Attr::Int(op.groups as i64))
+                .input(&lhs)
+                .input(&rhs)
+                .output(&out);
+            let flops_per_out = 2 * op.kernel * op.kernel * op.chan_in / op.groups;
+            tape.add_op_node(node.flops(flops_per_out * out.shape.num_elements()));
+        }
         let lhs_ghost = lhs.ghost();
         let rhs_ghost = rhs.ghost();
         let out_ghost = out.ghost();
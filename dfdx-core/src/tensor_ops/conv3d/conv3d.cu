@@ -0,0 +1,250 @@
+#include "cuda_fp16.h"
+
+struct Conv3DOp {
+    size_t kernel;
+    size_t stride;
+    size_t padding;
+    size_t dilation;
+    size_t groups;
+    size_t batch;
+    size_t chan_in;
+    size_t chan_out;
+    size_t d_in;
+    size_t d_out;
+    size_t h_in;
+    size_t h_out;
+    size_t w_in;
+    size_t w_out;
+};
+
+template<typename T>
+__device__ void unfold_input_into_patches(
+    const Conv3DOp op,
+    const T *image, // 5d (Batch, Groups * Channels, Depth, Height, Width)
+    const size_t *strides, // 5d image strides
+    T *patches // 8d (Batch, Groups * Channels, KernelSize, KernelSize, KernelSize, DepthOut, HeightOut, WidthOut)
+) {
+    const size_t n = op.batch * op.chan_in * op.d_out * op.h_out * op.w_out;
+    const size_t out_numel = op.d_out * op.h_out * op.w_out;
+    const size_t k_numel = op.kernel * op.kernel * op.kernel;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < n; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t ow = idx % op.w_out;
+        idx /= op.w_out;
+        const size_t oh = idx % op.h_out;
+        idx /= op.h_out;
+        const size_t od = idx % op.d_out;
+        idx /= op.d_out;
+        const size_t c = idx % op.chan_in;
+        idx /= op.chan_in;
+        const size_t b = idx % op.batch;
+
+        const T *image_i = image + b * strides[0] + c * strides[1];
+        T *patches_i = patches + od * (op.h_out * op.w_out) + oh * op.w_out + ow;
+        patches_i += c * (k_numel * out_numel);
+        patches_i += b * (op.chan_in * k_numel * out_numel);
+
+        T zero = 0.0;
+
+        for (int k1 = 0;k1 < op.kernel;k1++) {
+            const size_t z = od * op.stride + op.dilation * k1 - op.padding;
+            for (int k2 = 0;k2 < op.kernel;k2++) {
+                const size_t y = oh * op.stride + op.dilation * k2 - op.padding;
+                for (int k3 = 0;k3 < op.kernel;k3++) {
+                    const size_t x = ow * op.stride + op.dilation * k3 - op.padding;
+                    const bool invalid = z >= op.d_in || y >= op.h_in || x >= op.w_in;
+                    *patches_i = invalid ? zero : image_i[z * strides[2] + y * strides[3] + x * strides[4]];
+                    patches_i += out_numel;
+                }
+            }
+        }
+    }
+}
+
+__device__ bool unfold_dim(const Conv3DOp op, const size_t i, const size_t k, const size_t dim_out, size_t *o) {
+    const size_t o_ks = i + op.padding;
+    if (o_ks < op.dilation * k) { return false; }
+    const size_t o_s = o_ks - op.dilation * k;
+    if (o_s % op.stride != 0) { return false; }
+    *o = o_s / op.stride;
+    return *o < dim_out;
+}
+
+template<typename T>
+__device__ void unfold_output_into_patches(
+    const Conv3DOp op,
+    const T *image_out, // 5d (Batch, ChanOut, DepthOut, HeightOut, WidthOut)
+    T *patches // 8d (Batch, ChanOut, KernelSize, KernelSize, KernelSize, DepthIn, HeightIn, WidthIn)
+) {
+    const size_t n = op.batch * op.chan_out * op.d_in * op.h_in * op.w_in;
+    const size_t inp_numel = op.d_in * op.h_in * op.w_in;
+    const size_t out_numel = op.d_out * op.h_out * op.w_out;
+    const size_t k_numel = op.kernel * op.kernel * op.kernel;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < n; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t x = idx % op.w_in;
+        idx /= op.w_in;
+        const size_t y = idx % op.h_in;
+        idx /= op.h_in;
+        const size_t z = idx % op.d_in;
+        idx /= op.d_in;
+        const size_t o = idx % op.chan_out;
+        idx /= op.chan_out;
+        const size_t b = idx % op.batch;
+
+        const T *image_i = image_out + b * (op.chan_out * out_numel) + o * out_numel;
+        T *patches_i = patches + z * (op.h_in * op.w_in) + y * op.w_in + x;
+        patches_i += o * (k_numel * inp_numel);
+        patches_i += b * (op.chan_out * k_numel * inp_numel);
+
+        T zero = 0.0;
+
+        for (int k1 = 0;k1 < op.kernel;k1++) {
+            size_t od;
+            const bool k1_valid = unfold_dim(op, z, k1, op.d_out, &od);
+            for (int k2 = 0;k2 < op.kernel;k2++) {
+                size_t oh;
+                const bool k2_valid = k1_valid && unfold_dim(op, y, k2, op.h_out, &oh);
+                for (int k3 = 0;k3 < op.kernel;k3++) {
+                    size_t ow;
+                    const bool valid = k2_valid && unfold_dim(op, x, k3, op.w_out, &ow);
+                    *patches_i = valid ? image_i[od * (op.h_out * op.w_out) + oh * op.w_out + ow] : zero;
+                    patches_i += inp_numel;
+                }
+            }
+        }
+    }
+}
+
+template<typename T>
+__device__ void transpose_filters(
+    const Conv3DOp op,
+    const T *filters, // 5d (ChanOut, ChanIn/Groups, KernelSize, KernelSize, KernelSize)
+    const size_t *strides, // 5d filters strides
+    T *filters_tr // 6d (Groups, ChanIn/Groups, ChanOut/Groups, KernelSize, KernelSize, KernelSize)
+) {
+    const size_t c_per_g = op.chan_in / op.groups;
+    const size_t o_per_g = op.chan_out / op.groups;
+    const size_t k_numel = op.kernel * op.kernel * op.kernel;
+    const size_t n = c_per_g * op.chan_out * k_numel;
+
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < n; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t kx = idx % op.kernel;
+        idx /= op.kernel;
+        const size_t ky = idx % op.kernel;
+        idx /= op.kernel;
+        const size_t kz = idx % op.kernel;
+        idx /= op.kernel;
+        const size_t cg = idx % c_per_g;
+        idx /= c_per_g;
+        const size_t o = idx % op.chan_out;
+        const size_t og = o % o_per_g;
+        const size_t g = o / o_per_g;
+
+        auto i_no = o * strides[0] + cg * strides[1] + kz * strides[2] + ky * strides[3] + kx * strides[4];
+        T *filters_tr_i = filters_tr + kz * (op.kernel * op.kernel) + ky * op.kernel + kx;
+        filters_tr_i += og * k_numel;
+        filters_tr_i += cg * (o_per_g * k_numel);
+        filters_tr_i += g * (c_per_g * o_per_g * k_numel);
+        *filters_tr_i = filters[i_no];
+    }
+}
+
+template<typename T>
+__device__ void sum_transposed_filters(
+    const Conv3DOp op,
+    const T *filters_tr, // 7d (Batch, Groups, ChanIn/Groups, ChanOut/Groups, KernelSize, KernelSize, KernelSize)
+    T *filters, // 5d (ChanOut, ChanIn/Groups, KernelSize, KernelSize, KernelSize)
+    const size_t *strides // 5d filter strides
+) {
+    const size_t o_per_g = op.chan_out / op.groups;
+    const size_t c_per_g = op.chan_in / op.groups;
+    const size_t k_numel = op.kernel * op.kernel * op.kernel;
+    const size_t n = op.chan_out * c_per_g * k_numel;
+
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < n; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t kx = idx % op.kernel;
+        idx /= op.kernel;
+        const size_t ky = idx % op.kernel;
+        idx /= op.kernel;
+        const size_t kz = idx % op.kernel;
+        idx /= op.kernel;
+        const size_t cg = idx % c_per_g;
+        idx /= c_per_g;
+        const size_t o = idx % op.chan_out;
+        const size_t og = o % o_per_g;
+        const size_t g = o / o_per_g;
+
+        auto i_no = o * strides[0] + cg * strides[1] + kz * strides[2] + ky * strides[3] + kx * strides[4];
+
+        const T *filters_tr_i = filters_tr + kz * (op.kernel * op.kernel) + ky * op.kernel + kx;
+        filters_tr_i += og * k_numel;
+        filters_tr_i += cg * (o_per_g * k_numel);
+        filters_tr_i += g * (c_per_g * o_per_g * k_numel);
+
+        T tmp = 0.0;
+        for (int b = 0; b < op.batch; b++) {
+            tmp += *filters_tr_i;
+            filters_tr_i += n;
+        }
+
+        filters[i_no] += tmp;
+    }
+}
+
+#define CONV_OP(TYPENAME, UNFOLD_INPUT, UNFOLD_OUTPUT, TR_FILTERS, SUM_TR_FILTERS) \
+extern "C" __global__ void UNFOLD_INPUT( \
+    const Conv3DOp op, \
+    const TYPENAME *image, \
+    const size_t *strides, \
+    TYPENAME *patches \
+) { \
+    unfold_input_into_patches(op, image, strides, patches); \
+} \
+extern "C" __global__ void UNFOLD_OUTPUT( \
+    const Conv3DOp op, \
+    const TYPENAME *image_out, \
+    TYPENAME *patches \
+) { \
+    unfold_output_into_patches(op, image_out, patches); \
+} \
+extern "C" __global__ void TR_FILTERS( \
+    const Conv3DOp op, \
+    const TYPENAME *filters, \
+    const size_t *strides, \
+    TYPENAME *filters_tr \
+) { \
+    transpose_filters(op, filters, strides, filters_tr); \
+} \
+extern "C" __global__ void SUM_TR_FILTERS( \
+    const Conv3DOp op, \
+    const TYPENAME *filters_tr, \
+    TYPENAME *filters, \
+    const size_t *strides \
+) { \
+    sum_transposed_filters(op, filters_tr, filters, strides); \
+}
+
+CONV_OP(
+    __half,
+    unfold_input_into_patches_f16,
+    unfold_output_into_patches_f16,
+    transpose_filters_f16,
+    sum_transposed_filters_f16
+);
+CONV_OP(
+    float,
+    unfold_input_into_patches_f32,
+    unfold_output_into_patches_f32,
+    transpose_filters_f32,
+    sum_transposed_filters_f32
+);
+CONV_OP(
+    double,
+    unfold_input_into_patches_f64,
+    unfold_output_into_patches_f64,
+    transpose_filters_f64,
+    sum_transposed_filters_f64
+);
@@ -0,0 +1,376 @@
+use crate::{shapes::*, tensor::*, tensor_ops::ReshapeTo};
+
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(test)]
+mod tests;
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Conv3DOp {
+    pub kernel: usize,
+    pub stride: usize,
+    pub padding: usize,
+    pub dilation: usize,
+    pub groups: usize,
+    pub batch: usize,
+    pub chan_in: usize,
+    pub chan_out: usize,
+    pub d_in: usize,
+    pub d_out: usize,
+    pub h_in: usize,
+    pub h_out: usize,
+    pub w_in: usize,
+    pub w_out: usize,
+}
+
+pub(super) trait Conv3DKernel<E: Dtype>: Storage<E> {
+    fn alloc<S: Shape>(&self, s: S) -> Result<Tensor<S, E, Self>, Error>;
+
+    fn forward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv3DOp,
+        lhs: &Tensor<L, E, Self>,
+        rhs: &Tensor<R, E, Self>,
+        out: &mut Tensor<O, E, Self>,
+    ) -> Result<(), Error>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn backward<L: Shape, R: Shape, O: Shape>(
+        &self,
+        op: Conv3DOp,
+        lhs: &Tensor<L, E, Self>,
+        grad_lhs: &mut Self::Vec,
+        rhs: &Tensor<R, E, Self>,
+        grad_rhs: &mut Self::Vec,
+        out: &impl Tensorlike<O, E, Self>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+/// Apply the 3d convolution to a tensor.
+///
+/// Convolved [Const] dims **require nightly**. On stable the same call compiles,
+/// but the output depth, height & width are [usize]:
+/// ```ignore
+/// #![feature(generic_const_exprs)]
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank5<2, 3, 8, 16, 16>, f32, _> = dev.sample_normal();
+/// let w: Tensor<Rank5<6, 3, 3, 3, 3>, f32, _> = dev.sample_normal();
+/// let y = (x, w).conv3d(
+///     Const::<1>, // stride
+///     Const::<0>, // padding
+///     Const::<1>, // dilation
+///     Const::<1>, // groups
+/// );
+/// ```
+///
+/// [usize] dims can be used on stable:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<_, f32, _> = dev.sample_normal_like(&(
+///     2,  // batch size
+///     3,  // input channels
+///     8,  // depth
+///     16, // height
+///     16, // width
+/// ));
+/// let w: Tensor<_, f32, _> = dev.sample_normal_like(&(
+///     6, // output channels
+///     3, // input channels
+///     3, // kernel size
+///     3, // kernel size
+///     3, // kernel size
+/// ));
+/// let y = (x, w).conv3d(
+///     1, // stride
+///     0, // padding
+///     1, // dilation
+///     1, // groups
+/// );
+/// ```
+pub trait TryConv3D<Stride, Padding, Dilation, Groups>: Sized {
+    type Convolved;
+
+    /// Applies a 3D convolution to the input tensor.
+    fn conv3d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Self::Convolved {
+        self.try_conv3d(stride, padding, dilation, groups).unwrap()
+    }
+
+    /// Fallibly applies a 3D convolution to the input tensor.
+    fn try_conv3d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Result<Self::Convolved, Error>;
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const KERNEL: usize,
+        const STRIDE: usize,
+        const PADDING: usize,
+        const DILATION: usize,
+        Groups: Dim,
+        const DIM: usize,
+    > TryConv3D<Const<STRIDE>, Const<PADDING>, Const<DILATION>, Groups>
+    for (Const<DIM>, Const<KERNEL>)
+where
+    Const<{ (DIM + 2 * PADDING - DILATION * (KERNEL - 1) - 1) / STRIDE + 1 }>: Sized,
+{
+    type Convolved = Const<{ (DIM + 2 * PADDING - DILATION * (KERNEL - 1) - 1) / STRIDE + 1 }>;
+    fn try_conv3d(
+        self,
+        _: Const<STRIDE>,
+        _: Const<PADDING>,
+        _: Const<DILATION>,
+        _: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        Ok(Const)
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<const DIM: usize, Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
+    TryConv3D<Stride, Padding, Dilation, Groups> for (Const<DIM>, Kernel)
+{
+    type Convolved = usize;
+    fn try_conv3d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        (DIM, self.1).try_conv3d(stride, padding, dilation, groups)
+    }
+}
+
+impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
+    TryConv3D<Stride, Padding, Dilation, Groups> for (usize, Kernel)
+{
+    type Convolved = usize;
+    fn try_conv3d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        _: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        let (dim, kernel) = self;
+        Ok((dim + 2 * padding.size() - 1)
+            .checked_sub(dilation.size() * (kernel.size() - 1))
+            .unwrap()
+            / stride.size()
+            + 1)
+    }
+}
+
+impl<
+        InpChan,
+        InpChanOverGroups,
+        OutChan,
+        Kernel,
+        Stride,
+        Padding,
+        Dilation,
+        Groups,
+        Z,
+        H,
+        W,
+        E,
+        D,
+        T,
+    > TryConv3D<Stride, Padding, Dilation, Groups>
+    for (
+        Tensor<(InpChan, Z, H, W), E, D, T>,
+        Tensor<(OutChan, InpChanOverGroups, Kernel, Kernel, Kernel), E, D>,
+    )
+where
+    InpChan: Dim,
+    OutChan: Dim,
+    Kernel: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    Groups: Dim,
+    Z: Dim,
+    H: Dim,
+    W: Dim,
+    E: Dtype,
+    D: Conv3DKernel<E> + crate::tensor_ops::reshape_to::ReshapeKernel<E>,
+    T: Tape<E, D>,
+    InpChanOverGroups: Dim,
+    (Z, Kernel): TryConv3D<Stride, Padding, Dilation, Groups>,
+    (H, Kernel): TryConv3D<Stride, Padding, Dilation, Groups>,
+    (W, Kernel): TryConv3D<Stride, Padding, Dilation, Groups>,
+    <(Z, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
+    <(H, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
+    <(W, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
+{
+    type Convolved = Tensor<
+        (
+            OutChan,
+            <(Z, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved,
+            <(H, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved,
+            <(W, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved,
+        ),
+        E,
+        D,
+        T,
+    >;
+
+    fn try_conv3d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        let (img, filters) = self;
+        let (inp_chan, z, h, w) = img.shape;
+        let img = img.try_reshape_like(&(Const::<1>, inp_chan, z, h, w))?;
+        let out = (img, filters).try_conv3d(stride, padding, dilation, groups)?;
+        let (_, out_chan, out_z, out_h, out_w) = out.shape;
+        out.try_reshape_like(&(out_chan, out_z, out_h, out_w))
+    }
+}
+
+impl<
+        InpChan,
+        InpChanOverGroups,
+        OutChan,
+        Kernel,
+        Stride,
+        Padding,
+        Dilation,
+        Groups,
+        Batch,
+        Z,
+        H,
+        W,
+        E,
+        D,
+        T,
+    > TryConv3D<Stride, Padding, Dilation, Groups>
+    for (
+        Tensor<(Batch, InpChan, Z, H, W), E, D, T>,
+        Tensor<(OutChan, InpChanOverGroups, Kernel, Kernel, Kernel), E, D>,
+    )
+where
+    InpChan: Dim,
+    OutChan: Dim,
+    Kernel: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    Groups: Dim,
+    Batch: Dim,
+    Z: Dim,
+    H: Dim,
+    W: Dim,
+    E: Dtype,
+    D: Conv3DKernel<E>,
+    T: Tape<E, D>,
+    InpChanOverGroups: Dim,
+    (Z, Kernel): TryConv3D<Stride, Padding, Dilation, Groups>,
+    (H, Kernel): TryConv3D<Stride, Padding, Dilation, Groups>,
+    (W, Kernel): TryConv3D<Stride, Padding, Dilation, Groups>,
+    <(Z, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
+    <(H, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
+    <(W, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved: Dim,
+{
+    type Convolved = Tensor<
+        (
+            Batch,
+            OutChan,
+            <(Z, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved,
+            <(H, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved,
+            <(W, Kernel) as TryConv3D<Stride, Padding, Dilation, Groups>>::Convolved,
+        ),
+        E,
+        D,
+        T,
+    >;
+
+    fn try_conv3d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        let (img, filters) = self;
+        assert_eq!(img.shape.1.size(), filters.shape.1.size() * groups.size());
+        assert_eq!(filters.shape.2, filters.shape.3);
+        assert_eq!(filters.shape.3, filters.shape.4);
+        let (batch, inp_chan, z, h, w) = img.shape;
+        let (out_chan, _, kernel, _, _) = filters.shape;
+        assert!(out_chan.size() % groups.size() == 0);
+        if img.strides != img.shape.strides() || filters.strides != filters.shape.strides() {
+            panic!("Image & filter inputs to conv3d must be contiguous");
+        }
+        let z_out = (z, kernel).conv3d(stride, padding, dilation, groups);
+        let h_out = (h, kernel).conv3d(stride, padding, dilation, groups);
+        let w_out = (w, kernel).conv3d(stride, padding, dilation, groups);
+        let op = Conv3DOp {
+            stride: stride.size(),
+            padding: padding.size(),
+            kernel: kernel.size(),
+            dilation: dilation.size(),
+            groups: groups.size(),
+            batch: batch.size(),
+            chan_in: inp_chan.size(),
+            chan_out: out_chan.size(),
+            d_in: z.size(),
+            d_out: z_out.size(),
+            h_in: h.size(),
+            h_out: h_out.size(),
+            w_in: w.size(),
+            w_out: w_out.size(),
+        };
+        let (lhs, ltape) = img.split_tape();
+        let (rhs, rtape) = filters.split_tape();
+        let mut out = lhs.device.alloc((batch, out_chan, z_out, h_out, w_out))?;
+        let mut tape = ltape.merge(rtape);
+        lhs.device.forward(op, &lhs, &rhs, &mut out)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Conv3D")
+                .attr("stride", Attr::Int(op.stride as i64))
+                .attr("padding", Attr::Int(op.padding as i64))
+                .attr("dilation", Attr::Int(op.dilation as i64))
+                .attr("groups", Attr::Int(op.groups as i64))
+                .input(&lhs)
+                .input(&rhs)
+                .output(&out);
+            let flops_per_out = 2 * op.kernel * op.kernel * op.kernel * op.chan_in / op.groups;
+            tape.add_op_node(node.flops(flops_per_out * out.shape.num_elements()));
+        }
+        let lhs_ghost = lhs.ghost();
+        let rhs_ghost = rhs.ghost();
+        let out_ghost = out.ghost();
+        tape.add_backward_op(move |grads| {
+            grads.try_alloc_for(&rhs_ghost)?;
+            grads.try_alloc_for(&lhs_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_lhs, grad_rhs, grad_out) =
+                grads.muts_and_ref(&lhs_ghost, &rhs_ghost, &out_ghost);
+            lhs.device
+                .backward(op, &lhs, grad_lhs, &rhs, grad_rhs, &out_ghost, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
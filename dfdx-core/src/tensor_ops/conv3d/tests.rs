@@ -0,0 +1,483 @@
+use super::*;
+use crate::{tensor_ops::*, tests::*};
+
+#[test]
+/// Checked against a direct (non-unfolded) 3d convolution with unbatched input, stride 1, no padding.
+fn test_conv3d_default_stride_and_padding() {
+    let dev: TestDevice = Default::default();
+    let x = dev
+        .tensor([
+            [
+                [[1.09, 0.56, -1.24], [-0.24, 1.3, -0.1], [-1.27, 0.43, 1.16]],
+                [
+                    [-0.73, -0.98, 0.98],
+                    [0.72, -1.16, -0.42],
+                    [1.27, 0.1, -1.3],
+                ],
+                [
+                    [0.24, 1.24, -0.56],
+                    [-1.09, 0.84, 0.88],
+                    [-1.06, -0.6, 1.22],
+                ],
+            ],
+            [
+                [
+                    [0.29, -1.29, 0.04],
+                    [1.28, -0.38, -1.19],
+                    [0.68, 1.01, -0.94],
+                ],
+                [
+                    [-0.77, 1.14, 0.47],
+                    [-1.26, -0.15, 1.3],
+                    [-0.19, -1.25, 0.51],
+                ],
+                [[1.12, -0.8, -0.92], [1.03, 0.65, -1.2], [-0.34, 1.29, 0.01]],
+            ],
+        ])
+        .to_dtype::<TestDtype>();
+    let w = dev
+        .tensor([
+            [
+                [[0.59, -0.34], [-0.5, 0.47]],
+                [[0.38, -0.57], [-0.23, 0.63]],
+            ],
+            [[[0.07, -0.65], [0.1, 0.62]], [[-0.26, -0.56], [0.4, 0.46]]],
+            [[[-0.52, -0.32], [0.6, 0.17]], [[-0.65, 0.0], [0.65, -0.17]]],
+            [[[-0.6, 0.32], [0.52, -0.46]], [[-0.4, 0.56], [0.26, -0.62]]],
+        ])
+        .to_dtype::<TestDtype>()
+        .reshape::<Rank5<2, 2, 2, 2, 2>>();
+    let y = (x.leaky_trace(), w.clone())
+        .conv3d(Const::<1>, Const::<0>, Const::<1>, Const::<1>)
+        .realize::<Rank4<2, 2, 2, 2>>();
+    assert_close_to_literal!(
+        y,
+        [
+            [
+                [[0.3085, -1.7875], [1.7506, -0.6015]],
+                [[-0.9845, 1.6193], [-1.7551, -0.1191]]
+            ],
+            [
+                [[1.4361, 0.9218], [-0.4946, 1.8028]],
+                [[-0.8699, -1.4728], [1.1453, -1.5482]]
+            ]
+        ]
+    );
+    let g = y.exp().mean().backward();
+    assert_close_to_literal!(
+        g.get(&x),
+        [
+            [
+                [
+                    [-0.08643774, -0.1885415, -0.053833008],
+                    [0.3076262, -0.13781446, -0.101351],
+                    [-0.15707121, 0.38599542, 0.080554911]
+                ],
+                [
+                    [-0.13830537, 0.015783647, -0.11785153],
+                    [0.17146038, -0.50441134, 0.08800931],
+                    [0.054475761, 0.47753241, -0.014544721]
+                ],
+                [
+                    [-0.0081479914, 0.097301885, -0.17989038],
+                    [-0.11194311, -0.046727007, 0.16476509],
+                    [0.12521469, -0.030713768, 0.032694739]
+                ]
+            ],
+            [
+                [
+                    [-0.15170397, -0.064756421, 0.043476489],
+                    [0.14747076, -0.43219935, 0.033283979],
+                    [0.055806833, 0.40618228, -0.15318047]
+                ],
+                [
+                    [-0.14130677, 0.04063084, -0.11842734],
+                    [-0.10762797, -0.32630278, 0.25782106],
+                    [0.25710145, 0.18298186, -0.19104047]
+                ],
+                [
+                    [-0.016546259, -0.086199463, -0.16870951],
+                    [-0.065244774, 0.20869656, 0.11266189],
+                    [0.055402243, -0.091187334, 0.017282495]
+                ]
+            ]
+        ]
+    );
+    assert_close_to_literal!(
+        g.get(&w).reshape::<Rank4<4, 2, 2, 2>>(),
+        [
+            [
+                [[-0.32615586, 0.74965718], [-0.77914792, 0.073357812]],
+                [[0.57879037, -0.59485364], [0.70450283, 0.24691597]]
+            ],
+            [
+                [[0.77867251, -0.041395625], [0.23607576, 0.70806397]],
+                [[-0.69130566, -0.27612846], [0.077122228, -0.78058844]]
+            ],
+            [
+                [[0.9510404, -0.28113657], [0.50888603, 0.74807764]],
+                [[-0.93710385, -0.10583108], [-0.13946836, -0.92695379]]
+            ],
+            [
+                [[-0.47513309, -0.7739763], [0.59642556, -0.828833]],
+                [[0.10074018, 0.93821159], [-0.84921478, 0.56395724]]
+            ]
+        ]
+    );
+}
+
+#[test]
+/// Checked against a direct (non-unfolded) 3d convolution with batched input, stride 2, padding 1.
+fn test_conv3d_batched_stride_2_padding_1() {
+    let dev: TestDevice = Default::default();
+    let x = dev
+        .tensor([
+            [
+                [[1.09, 0.56, -1.24], [-0.24, 1.3, -0.1], [-1.27, 0.43, 1.16]],
+                [
+                    [-0.73, -0.98, 0.98],
+                    [0.72, -1.16, -0.42],
+                    [1.27, 0.1, -1.3],
+                ],
+                [
+                    [0.24, 1.24, -0.56],
+                    [-1.09, 0.84, 0.88],
+                    [-1.06, -0.6, 1.22],
+                ],
+                [
+                    [0.29, -1.29, 0.04],
+                    [1.28, -0.38, -1.19],
+                    [0.68, 1.01, -0.94],
+                ],
+            ],
+            [
+                [
+                    [-0.77, 1.14, 0.47],
+                    [-1.26, -0.15, 1.3],
+                    [-0.19, -1.25, 0.51],
+                ],
+                [[1.12, -0.8, -0.92], [1.03, 0.65, -1.2], [-0.34, 1.29, 0.01]],
+                [
+                    [-1.29, 0.32, 1.21],
+                    [-0.64, -1.04, 0.9],
+                    [0.81, -1.11, -0.52],
+                ],
+                [[1.25, 0.2, -1.3], [0.13, 1.27, -0.46], [-1.15, 0.75, 0.95]],
+            ],
+        ])
+        .to_dtype::<TestDtype>()
+        .reshape::<Rank5<2, 1, 4, 3, 3>>();
+    let w = dev
+        .tensor([
+            [
+                [[0.59, -0.34], [-0.5, 0.47]],
+                [[0.38, -0.57], [-0.23, 0.63]],
+            ],
+            [[[0.07, -0.65], [0.1, 0.62]], [[-0.26, -0.56], [0.4, 0.46]]],
+        ])
+        .to_dtype::<TestDtype>()
+        .reshape::<Rank5<2, 1, 2, 2, 2>>();
+    let y = (x.leaky_trace(), w.clone())
+        .conv3d(Const::<2>, Const::<1>, Const::<1>, Const::<1>)
+        .realize::<Rank5<2, 2, 3, 2, 2>>();
+    assert_close_to_literal!(
+        y.retaped::<NoneTape>().reshape::<Rank4<4, 3, 2, 2>>(),
+        [
+            [
+                [[0.6867, -0.91], [-0.6633, 1.1829]],
+                [[-0.1919, 0.3126], [0.3056, -0.4784]],
+                [[0.1363, 0.6638], [-0.1156, -0.7664]]
+            ],
+            [
+                [[0.5014, -0.3464], [-0.4498, 0.4236]],
+                [[-0.3422, 0.748], [0.4422, -0.9942]],
+                [[0.1798, -0.1042], [-0.4104, 0.2651]]
+            ],
+            [
+                [[-0.4851, 0.0339], [0.5985, -0.1892]],
+                [[-0.2863, 0.6563], [0.3651, -0.8293]],
+                [[0.5875, -0.711], [-0.5847, 0.9772]]
+            ],
+            [
+                [[-0.3542, 0.6722], [0.6182, -0.9544]],
+                [[0.101, 0.0342], [-0.1493, 0.0439]],
+                [[0.775, -0.786], [-0.7975, 1.0519]]
+            ]
+        ]
+    );
+    let g = y.exp().mean().backward();
+    assert_close_to_literal!(
+        g.get(&x).reshape::<Rank4<2, 4, 3, 3>>(),
+        [
+            [
+                [
+                    [0.041903687, 0.0039648176, 0.012060747],
+                    [-0.013557872, 0.017564929, -0.056578185],
+                    [0.01287315, -0.0029104102, 0.057475778]
+                ],
+                [
+                    [0.017255434, -0.0098377144, 0.040674909],
+                    [-0.030687747, 0.0081576699, -0.0094007461],
+                    [0.033391565, -0.005685108, 0.010848039]
+                ],
+                [
+                    [0.017639378, 0.011056332, 0.038188924],
+                    [-0.034274405, 0.0029022746, -0.011676711],
+                    [0.032729273, 0.00011374296, 0.011680556]
+                ],
+                [
+                    [0.026682466, -0.018353641, 0.030655468],
+                    [-0.015293393, 0.0076127545, -0.020943897],
+                    [0.017291446, -0.0021246943, 0.021387687]
+                ]
+            ],
+            [
+                [
+                    [0.014805174, 0.011364289, 0.03234692],
+                    [-0.04325372, 0.0044663492, -0.014320168],
+                    [0.041662153, -0.00075699129, 0.014552522]
+                ],
+                [
+                    [0.021643313, -0.017923839, 0.032240933],
+                    [-0.021868243, 0.0068873058, -0.017240233],
+                    [0.025231707, -0.0023685318, 0.017768978]
+                ],
+                [
+                    [0.020459182, -0.00061337819, 0.035217117],
+                    [-0.027156439, -0.0022052809, -0.017371953],
+                    [0.027162865, 0.0066164496, 0.015740567]
+                ],
+                [
+                    [0.045656763, -0.0041668754, 0.010694862],
+                    [-0.010047244, 0.036834368, -0.057591414],
+                    [0.011274992, -0.021712386, 0.062998044]
+                ]
+            ]
+        ]
+    );
+    assert_close_to_literal!(
+        g.get(&w).reshape::<Rank4<2, 2, 2, 2>>(),
+        [
+            [
+                [[0.057552307, 0.023225366], [-0.045873901, 0.08980738]],
+                [[0.08720066, -0.065209351], [0.067277463, 0.12376456]]
+            ],
+            [
+                [[0.070639872, -0.028380542], [0.018317546, 0.14549274]],
+                [[0.024001509, -0.065097866], [0.091113082, -0.0025605209]]
+            ]
+        ]
+    );
+}
+
+#[test]
+/// Checked against a direct (non-unfolded) 3d convolution with 2 groups, padding 1, dilation 2.
+fn test_conv3d_grouped_dilated() {
+    let dev: TestDevice = Default::default();
+    let x = dev
+        .tensor([
+            [
+                [[1.09, 0.56, -1.24], [-0.24, 1.3, -0.1], [-1.27, 0.43, 1.16]],
+                [
+                    [-0.73, -0.98, 0.98],
+                    [0.72, -1.16, -0.42],
+                    [1.27, 0.1, -1.3],
+                ],
+                [
+                    [0.24, 1.24, -0.56],
+                    [-1.09, 0.84, 0.88],
+                    [-1.06, -0.6, 1.22],
+                ],
+            ],
+            [
+                [
+                    [0.29, -1.29, 0.04],
+                    [1.28, -0.38, -1.19],
+                    [0.68, 1.01, -0.94],
+                ],
+                [
+                    [-0.77, 1.14, 0.47],
+                    [-1.26, -0.15, 1.3],
+                    [-0.19, -1.25, 0.51],
+                ],
+                [[1.12, -0.8, -0.92], [1.03, 0.65, -1.2], [-0.34, 1.29, 0.01]],
+            ],
+            [
+                [
+                    [-1.29, 0.32, 1.21],
+                    [-0.64, -1.04, 0.9],
+                    [0.81, -1.11, -0.52],
+                ],
+                [[1.25, 0.2, -1.3], [0.13, 1.27, -0.46], [-1.15, 0.75, 0.95]],
+                [
+                    [-1.0, -0.69, 1.18],
+                    [0.39, -1.28, -0.06],
+                    [1.3, -0.27, -1.23],
+                ],
+            ],
+            [
+                [
+                    [0.59, 1.07, -0.87],
+                    [-0.85, 1.08, 0.57],
+                    [-1.23, -0.25, 1.3],
+                ],
+                [
+                    [-0.08, -1.28, 0.41],
+                    [1.17, -0.71, -0.99],
+                    [0.97, 0.74, -1.16],
+                ],
+                [[-0.44, 1.27, 0.11], [-1.3, 0.22, 1.24], [-0.54, -1.1, 0.83]],
+            ],
+        ])
+        .to_dtype::<TestDtype>()
+        .reshape::<Rank5<1, 4, 3, 3, 3>>();
+    let w = dev
+        .tensor([
+            [
+                [[0.59, -0.34], [-0.5, 0.47]],
+                [[0.38, -0.57], [-0.23, 0.63]],
+            ],
+            [[[0.07, -0.65], [0.1, 0.62]], [[-0.26, -0.56], [0.4, 0.46]]],
+            [[[-0.52, -0.32], [0.6, 0.17]], [[-0.65, 0.0], [0.65, -0.17]]],
+            [[[-0.6, 0.32], [0.52, -0.46]], [[-0.4, 0.56], [0.26, -0.62]]],
+        ])
+        .to_dtype::<TestDtype>()
+        .reshape::<Rank5<2, 2, 2, 2, 2>>();
+    let y = (x.leaky_trace(), w.clone())
+        .conv3d(Const::<1>, Const::<1>, Const::<2>, Const::<2>)
+        .realize::<Rank5<1, 2, 3, 3, 3>>();
+    assert_close_to_literal!(
+        y.retaped::<NoneTape>().reshape::<Rank4<2, 3, 3, 3>>(),
+        [
+            [
+                [
+                    [-0.7998, -0.3362, 0.2068],
+                    [-0.5918, -1.8515, -1.1918],
+                    [0.7452, 0.1126, -0.4018]
+                ],
+                [
+                    [1.2036, 0.1283, -0.6212],
+                    [1.433, 3.2398, 1.4593],
+                    [-1.0378, 0.2439, 0.8906]
+                ],
+                [
+                    [-0.6382, 0.1226, 0.565],
+                    [-1.1358, -2.0721, -0.6734],
+                    [0.4919, -0.3656, -0.6949]
+                ]
+            ],
+            [
+                [
+                    [0.2243, 1.0807, 0.6409],
+                    [-1.3031, -0.4885, 1.0619],
+                    [-0.3976, -1.1069, -0.5415]
+                ],
+                [
+                    [-0.5924, -1.7783, -0.8372],
+                    [1.6054, 0.0979, -2.1254],
+                    [0.8016, 1.6981, 0.6368]
+                ],
+                [
+                    [0.5425, 1.0636, 0.3928],
+                    [-0.6865, 0.4547, 1.4988],
+                    [-0.6336, -0.9392, -0.2344]
+                ]
+            ]
+        ]
+    );
+    let g = y.exp().mean().backward();
+    assert_close_to_literal!(
+        g.get(&x).reshape::<Rank4<4, 3, 3, 3>>(),
+        [
+            [
+                [
+                    [0.27892376, 0.020624296, -0.16073573],
+                    [0.0034170527, 0.048418196, 0.0018597502],
+                    [-0.23637607, -0.0033626625, 0.22219351]
+                ],
+                [
+                    [0.0024806296, -0.00015398068, -0.0024500743],
+                    [0.0019458113, -0.034062285, 0.0019926921],
+                    [-0.0018346459, 0.0032354596, 0.0029276637]
+                ],
+                [
+                    [0.17964581, -0.013961022, -0.26946872],
+                    [0.0041384578, 0.049993151, -0.00020741274],
+                    [-0.10873299, 0.0305706, 0.29783385]
+                ]
+            ],
+            [
+                [
+                    [0.03309265, -0.044872387, -0.30728889],
+                    [0.0037597143, 0.038146965, -0.0023086401],
+                    [0.047275214, 0.056090226, 0.29310633]
+                ],
+                [
+                    [-0.00059270207, -0.010405178, -0.0031438802],
+                    [0.002896519, -0.02184843, -0.00089206435],
+                    [0.0013961611, 0.011594836, 0.0027831805]
+                ],
+                [
+                    [-0.12291556, -0.064182748, -0.2647412],
+                    [0.0022766901, 0.016959209, -0.0035501871],
+                    [0.18910086, 0.067576953, 0.21746598]
+                ]
+            ],
+            [
+                [
+                    [-0.010620061, -0.030659864, -0.0065354222],
+                    [-0.050735083, -0.024862417, -0.031844832],
+                    [0.012253917, 0.017003811, 0.003471943]
+                ],
+                [
+                    [-0.022558689, -0.080897402, -0.009337485],
+                    [0.059912647, 0.023015225, -0.0024739685],
+                    [0.024893061, 0.085275512, 0.0030290052]
+                ],
+                [
+                    [-0.013275076, -0.0014370441, 0.0],
+                    [-0.063731678, -0.019284913, -0.00053180116],
+                    [0.013275076, -0.014240265, -0.003471943]
+                ]
+            ],
+            [
+                [
+                    [-0.012253917, 0.028183727, 0.0065354222],
+                    [-0.0590795, -0.0083370371, 0.030937641],
+                    [0.010620061, -0.041271319, -0.0093946694]
+                ],
+                [
+                    [-0.02205257, -0.065357781, 0.015700185],
+                    [0.0352904, -0.008607449, -0.052764294],
+                    [0.018127524, 0.049621971, -0.020467052]
+                ],
+                [
+                    [-0.0081692777, 0.050758566, 0.011436989],
+                    [-0.039657448, 0.0048487085, 0.054719597],
+                    [0.0053100305, -0.056601251, -0.01266238]
+                ]
+            ]
+        ]
+    );
+    assert_close_to_literal!(
+        g.get(&w).reshape::<Rank4<4, 2, 2, 2>>(),
+        [
+            [
+                [[0.60048075, -0.58065397], [-0.57706614, 0.63730256]],
+                [[0.2173261, -0.20334562], [-0.57615357, 0.58258158]]
+            ],
+            [
+                [[0.038806058, -0.091795691], [0.38160803, -0.39499521]],
+                [[0.49560626, -0.48698224], [-0.057459473, 0.1243224]]
+            ],
+            [
+                [[-0.054200982, 0.075431864], [0.07417092, -0.070324493]],
+                [[0.012094077, -0.099218271], [0.095746467, -0.044417986]]
+            ],
+            [
+                [[-0.14414672, 0.1690477], [0.11326208, -0.086338914]],
+                [[-0.19995151, 0.23722933], [0.073775297, -0.15829272]]
+            ]
+        ]
+    );
+}
@@ -76,6 +76,7 @@ pub trait TryConvTrans2D<Stride, Padding, Dilation, Groups>: Sized {
     ) -> Result<Self::Convolved, Error>;
 }
 
+#[cfg(feature = "nightly")]
 impl<
         const KERNEL: usize,
         const STRIDE: usize,
@@ -101,6 +102,22 @@ where
     }
 }
 
+#[cfg(not(feature = "nightly"))]
+impl<const DIM: usize, Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
+    TryConvTrans2D<Stride, Padding, Dilation, Groups> for (Const<DIM>, Kernel)
+{
+    type Convolved = usize;
+    fn try_convtrans2d(
+        self,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+        groups: Groups,
+    ) -> Result<Self::Convolved, Error> {
+        (DIM, self.1).try_convtrans2d(stride, padding, dilation, groups)
+    }
+}
+
 impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
     TryConvTrans2D<Stride, Padding, Dilation, Groups> for (usize, Kernel)
 {
@@ -122,13 +139,27 @@ impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim, Groups: Dim>
     }
 }
 
-impl<InpChan, OutChanOverGroups, Kernel, Stride, Padding, Dilation, Groups, H, W, E, D, T>
-    TryConvTrans2D<Stride, Padding, Dilation, Groups>
+impl<
+        ImgChan,
+        InpChan,
+        OutChanOverGroups,
+        Kernel,
+        Stride,
+        Padding,
+        Dilation,
+        Groups,
+        H,
+        W,
+        E,
+        D,
+        T,
+    > TryConvTrans2D<Stride, Padding, Dilation, Groups>
     for (
-        Tensor<(InpChan, H, W), E, D, T>,
+        Tensor<(ImgChan, H, W), E, D, T>,
         Tensor<(InpChan, OutChanOverGroups, Kernel, Kernel), E, D>,
     )
 where
+    ImgChan: Dim,
     InpChan: Dim,
     OutChanOverGroups: Dim,
     Kernel: Dim,
@@ -175,6 +206,7 @@ where
     }
 }
 impl<
+        ImgChan,
         InpChan,
         OutChanOverGroups,
         Kernel,
@@ -190,10 +222,11 @@ impl<
         T,
     > TryConvTrans2D<Stride, Padding, Dilation, Groups>
     for (
-        Tensor<(Batch, InpChan, H, W), E, D, T>,
+        Tensor<(Batch, ImgChan, H, W), E, D, T>,
         Tensor<(InpChan, OutChanOverGroups, Kernel, Kernel), E, D>,
     )
 where
+    ImgChan: Dim,
     InpChan: Dim,
     OutChanOverGroups: Dim,
     Kernel: Dim,
@@ -234,7 +267,7 @@ where
         groups: Groups,
     ) -> Result<Self::Convolved, Error> {
         let (img, filters) = self;
-        assert_eq!(img.shape.1, filters.shape.0);
+        assert_eq!(img.shape.1.size(), filters.shape.0.size());
         assert_eq!(filters.shape.2, filters.shape.3);
         let (batch, _, h, w) = img.shape;
         let (inp_chan, out_chan_over_groups, kernel, _) = filters.shape;
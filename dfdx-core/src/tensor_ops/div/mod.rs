@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::*;
+use super::{ops::*, Device, TryMul};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -93,6 +93,45 @@ where
     }
 }
 
+impl KernelOpInfo for BinaryDivKernelOp {
+    const NAME: &'static str = "BinaryDiv";
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for BinaryDivKernelOp {
+    fn try_grads<S: Shape, D: Device<E>>(
+        &self,
+        lhs: &Tensor<S, E, D>,
+        rhs: &Tensor<S, E, D>,
+        grad_out: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<(Tensor<S, E, D, OwnedTape<E, D>>, Tensor<S, E, D, OwnedTape<E, D>>), Error> {
+        let grad_lhs = grad_out.with_empty_tape().try_div(rhs.retaped::<OwnedTape<E, D>>())?;
+        let grad_rhs = grad_out
+            .try_mul(lhs.retaped::<OwnedTape<E, D>>())?
+            .try_div(rhs.retaped::<OwnedTape<E, D>>().try_square()?)?
+            .try_negate()?;
+        Ok((grad_lhs, grad_rhs))
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for ScalarDivKernelOp<E> {
+    const NAME: &'static str = "ScalarDiv";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("scalar", Attr::Float(self.scalar.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ScalarDivKernelOp<E> {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        grad_out.try_div(self.scalar.to_f64().unwrap())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tensor::*;
@@ -0,0 +1,26 @@
+use super::ELUKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for ELUKernelOp<AMP<f16>> {}
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for ELUKernelOp<f16> {}
+unsafe impl cudarc::driver::DeviceRepr for ELUKernelOp<f32> {}
+unsafe impl cudarc::driver::DeviceRepr for ELUKernelOp<f64> {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/elu.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(ELUKernelOp<f16>, f16, PTX, "elu_fwd_f16", "elu_bwd_f16");
+#[cfg(feature = "f16")]
+cuda_unary!(
+    ELUKernelOp<AMP<f16>>,
+    AMP<f16>,
+    PTX,
+    "elu_fwd_f16",
+    "elu_bwd_f16"
+);
+cuda_unary!(ELUKernelOp<f32>, f32, PTX, "elu_fwd_f32", "elu_bwd_f32");
+cuda_unary!(ELUKernelOp<f64>, f64, PTX, "elu_fwd_f64", "elu_bwd_f64");
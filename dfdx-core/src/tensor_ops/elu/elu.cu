@@ -0,0 +1,32 @@
+#include "unary_op_macros.cuh"
+
+template<typename F>
+struct ELUKernelOp {
+    F alpha;
+};
+
+template<typename T>
+__device__ __forceinline__ T elu_fwd(T x, T alpha) {
+    T zero = 0.0;
+    T one = 1.0;
+    return x > zero ? x : alpha * (expg(x) - one);
+}
+
+template<typename T>
+__device__ __forceinline__ T elu_bwd(T x, T alpha) {
+    T zero = 0.0;
+    T one = 1.0;
+    return x > zero ? one : alpha * expg(x);
+}
+
+UNARY_OP(__half, elu_fwd_f16, elu_bwd_f16, ELUKernelOp<__half>,
+    elu_fwd(x, op.alpha),
+    elu_bwd(x, op.alpha))
+
+UNARY_OP(float, elu_fwd_f32, elu_bwd_f32, ELUKernelOp<float>,
+    elu_fwd(x, op.alpha),
+    elu_bwd(x, op.alpha))
+
+UNARY_OP(double, elu_fwd_f64, elu_bwd_f64, ELUKernelOp<double>,
+    elu_fwd(x, op.alpha),
+    elu_bwd(x, op.alpha))
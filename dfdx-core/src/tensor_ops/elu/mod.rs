@@ -0,0 +1,80 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ELUKernelOp<E> {
+    pub alpha: E,
+}
+
+/// [Exponential Linear Unit (ELU)](https://arxiv.org/abs/1511.07289). `t` if `t > 0`, otherwise `alpha * (exp(t) - 1)`
+///
+/// Arguments:
+/// - `alpha`: The scale of the negative part.
+///
+/// The derivative is `1` if `t > 0`, otherwise `alpha * exp(t)`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.elu(1.5);
+/// ```
+pub fn elu<S: Shape, E: Dtype, D: UnaryKernel<ELUKernelOp<E>, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+    alpha: impl Into<f64>,
+) -> Tensor<S, E, D, T> {
+    t.elu(alpha)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<ELUKernelOp<E>, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [elu]
+    pub fn elu(self, alpha: impl Into<f64>) -> Self {
+        self.try_elu(alpha).unwrap()
+    }
+    /// See [elu]
+    pub fn try_elu(self, alpha: impl Into<f64>) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(
+            ELUKernelOp {
+                alpha: E::from_f64(alpha.into()).unwrap(),
+            },
+            self,
+        )
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for ELUKernelOp<E> {
+    const NAME: &'static str = "ELU";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("alpha", Attr::Float(self.alpha.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ELUKernelOp<E> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_elu() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().elu(1.5);
+        assert_close_to_literal!(r, [-1.4725265, -1.1653048, 0.5, 2.0, 7.0]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&x), [0.0054946917, 0.066939048, 0.2, 0.2, 0.2]);
+    }
+}
@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::{Device, TryMul};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -41,6 +42,21 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<ExpKernelOp, E>, T: Tape<E, D>> Tensor<S
     }
 }
 
+impl KernelOpInfo for ExpKernelOp {
+    const NAME: &'static str = "Exp";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ExpKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        grad_out.try_mul(out.retaped::<OwnedTape<E, D>>())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
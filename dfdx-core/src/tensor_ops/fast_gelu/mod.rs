@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
 use crate::{shapes::*, tensor::*};
 
 #[allow(unused)]
@@ -68,6 +68,12 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<FastGeLUKernelOp, E>, T: Tape<E, D>> Ten
     }
 }
 
+impl KernelOpInfo for FastGeLUKernelOp {
+    const NAME: &'static str = "FastGeLU";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for FastGeLUKernelOp {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
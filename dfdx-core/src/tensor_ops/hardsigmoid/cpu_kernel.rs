@@ -0,0 +1,21 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+use num_traits::Float;
+
+impl<F: Float> UnaryDerivative<F> for super::HardSigmoidKernelOp {
+    const DF_USES_FX: bool = false;
+    const HAS_CONST_DF: bool = false;
+    #[inline(always)]
+    fn f(&self, &x: &F) -> F {
+        let six = F::from(6.0).unwrap();
+        (x + F::from(3.0).unwrap()).max(F::zero()).min(six) / six
+    }
+    #[inline(always)]
+    fn df(&self, &x: &F) -> F {
+        let three = F::from(3.0).unwrap();
+        if x > -three && x < three {
+            F::from(6.0).unwrap().recip()
+        } else {
+            F::zero()
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use super::HardSigmoidKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+unsafe impl cudarc::driver::DeviceRepr for HardSigmoidKernelOp {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/hardsigmoid.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(
+    HardSigmoidKernelOp,
+    f16,
+    PTX,
+    "hardsigmoid_fwd_f16",
+    "hardsigmoid_bwd_f16"
+);
+#[cfg(feature = "f16")]
+cuda_unary!(
+    HardSigmoidKernelOp,
+    AMP<f16>,
+    PTX,
+    "hardsigmoid_fwd_f16",
+    "hardsigmoid_bwd_f16"
+);
+cuda_unary!(
+    HardSigmoidKernelOp,
+    f32,
+    PTX,
+    "hardsigmoid_fwd_f32",
+    "hardsigmoid_bwd_f32"
+);
+cuda_unary!(
+    HardSigmoidKernelOp,
+    f64,
+    PTX,
+    "hardsigmoid_fwd_f64",
+    "hardsigmoid_bwd_f64"
+);
@@ -0,0 +1,32 @@
+#include "unary_op_macros.cuh"
+
+struct HardSigmoidKernelOp {};
+
+template<typename T>
+__device__ __forceinline__ T hardsigmoid_fwd(T x) {
+    T zero = 0.0;
+    T three = 3.0;
+    T six = 6.0;
+    return ming(maxg(x + three, zero), six) / six;
+}
+
+template<typename T>
+__device__ __forceinline__ T hardsigmoid_bwd(T x) {
+    T zero = 0.0;
+    T one = 1.0;
+    T three = 3.0;
+    T six = 6.0;
+    return (x > -three && x < three) ? one / six : zero;
+}
+
+UNARY_OP(__half, hardsigmoid_fwd_f16, hardsigmoid_bwd_f16, HardSigmoidKernelOp,
+    hardsigmoid_fwd(x),
+    hardsigmoid_bwd(x))
+
+UNARY_OP(float, hardsigmoid_fwd_f32, hardsigmoid_bwd_f32, HardSigmoidKernelOp,
+    hardsigmoid_fwd(x),
+    hardsigmoid_bwd(x))
+
+UNARY_OP(double, hardsigmoid_fwd_f64, hardsigmoid_bwd_f64, HardSigmoidKernelOp,
+    hardsigmoid_fwd(x),
+    hardsigmoid_bwd(x))
@@ -0,0 +1,65 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct HardSigmoidKernelOp;
+
+/// A piecewise linear approximation of [sigmoid](super::sigmoid()). `relu6(t + 3) / 6`
+///
+/// The derivative is `1 / 6` for `-3 < t < 3`, otherwise `0`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.hardsigmoid();
+/// ```
+pub fn hardsigmoid<S: Shape, E: Dtype, D: UnaryKernel<HardSigmoidKernelOp, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.hardsigmoid()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<HardSigmoidKernelOp, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [hardsigmoid]
+    pub fn hardsigmoid(self) -> Self {
+        self.try_hardsigmoid().unwrap()
+    }
+    /// See [hardsigmoid]
+    pub fn try_hardsigmoid(self) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(HardSigmoidKernelOp, self)
+    }
+}
+
+impl KernelOpInfo for HardSigmoidKernelOp {
+    const NAME: &'static str = "HardSigmoid";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for HardSigmoidKernelOp {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_hardsigmoid() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().hardsigmoid();
+        assert_close_to_literal!(r, [0.0, 0.25, 0.58333333, 0.83333333, 1.0]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&x), [0.0, 0.033333333, 0.033333333, 0.033333333, 0.0]);
+    }
+}
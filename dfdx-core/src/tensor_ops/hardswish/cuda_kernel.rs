@@ -0,0 +1,39 @@
+use super::HardSwishKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+unsafe impl cudarc::driver::DeviceRepr for HardSwishKernelOp {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/hardswish.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(
+    HardSwishKernelOp,
+    f16,
+    PTX,
+    "hardswish_fwd_f16",
+    "hardswish_bwd_f16"
+);
+#[cfg(feature = "f16")]
+cuda_unary!(
+    HardSwishKernelOp,
+    AMP<f16>,
+    PTX,
+    "hardswish_fwd_f16",
+    "hardswish_bwd_f16"
+);
+cuda_unary!(
+    HardSwishKernelOp,
+    f32,
+    PTX,
+    "hardswish_fwd_f32",
+    "hardswish_bwd_f32"
+);
+cuda_unary!(
+    HardSwishKernelOp,
+    f64,
+    PTX,
+    "hardswish_fwd_f64",
+    "hardswish_bwd_f64"
+);
@@ -0,0 +1,32 @@
+#include "unary_op_macros.cuh"
+
+struct HardSwishKernelOp {};
+
+template<typename T>
+__device__ __forceinline__ T hardswish_fwd(T x) {
+    T zero = 0.0;
+    T three = 3.0;
+    T six = 6.0;
+    return x * ming(maxg(x + three, zero), six) / six;
+}
+
+template<typename T>
+__device__ __forceinline__ T hardswish_bwd(T x) {
+    T zero = 0.0;
+    T one = 1.0;
+    T three = 3.0;
+    T six = 6.0;
+    return x < -three ? zero : (x > three ? one : (x + x + three) / six);
+}
+
+UNARY_OP(__half, hardswish_fwd_f16, hardswish_bwd_f16, HardSwishKernelOp,
+    hardswish_fwd(x),
+    hardswish_bwd(x))
+
+UNARY_OP(float, hardswish_fwd_f32, hardswish_bwd_f32, HardSwishKernelOp,
+    hardswish_fwd(x),
+    hardswish_bwd(x))
+
+UNARY_OP(double, hardswish_fwd_f64, hardswish_bwd_f64, HardSwishKernelOp,
+    hardswish_fwd(x),
+    hardswish_bwd(x))
@@ -0,0 +1,66 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct HardSwishKernelOp;
+
+/// [Hard Swish](https://arxiv.org/abs/1905.02244), a piecewise approximation of [silu](super::silu()).
+/// `t * relu6(t + 3) / 6`
+///
+/// The derivative is `0` for `t < -3`, `1` for `t > 3`, otherwise `(2 * t + 3) / 6`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.hardswish();
+/// ```
+pub fn hardswish<S: Shape, E: Dtype, D: UnaryKernel<HardSwishKernelOp, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.hardswish()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<HardSwishKernelOp, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [hardswish]
+    pub fn hardswish(self) -> Self {
+        self.try_hardswish().unwrap()
+    }
+    /// See [hardswish]
+    pub fn try_hardswish(self) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(HardSwishKernelOp, self)
+    }
+}
+
+impl KernelOpInfo for HardSwishKernelOp {
+    const NAME: &'static str = "HardSwish";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for HardSwishKernelOp {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_hardswish() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().hardswish();
+        assert_close_to_literal!(r, [0.0, -0.375, 0.29166667, 1.6666667, 7.0]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&x), [0.0, 0.0, 0.13333333, 0.23333333, 0.2]);
+    }
+}
@@ -0,0 +1,19 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+use num_traits::Float;
+
+impl<F: Float> UnaryDerivative<F> for super::HardTanhKernelOp<F> {
+    const DF_USES_FX: bool = false;
+    const HAS_CONST_DF: bool = false;
+    #[inline(always)]
+    fn f(&self, &x: &F) -> F {
+        x.max(self.min_val).min(self.max_val)
+    }
+    #[inline(always)]
+    fn df(&self, &x: &F) -> F {
+        if x > self.min_val && x < self.max_val {
+            F::one()
+        } else {
+            F::zero()
+        }
+    }
+}
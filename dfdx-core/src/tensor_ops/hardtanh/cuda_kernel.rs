@@ -0,0 +1,44 @@
+use super::HardTanhKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for HardTanhKernelOp<AMP<f16>> {}
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for HardTanhKernelOp<f16> {}
+unsafe impl cudarc::driver::DeviceRepr for HardTanhKernelOp<f32> {}
+unsafe impl cudarc::driver::DeviceRepr for HardTanhKernelOp<f64> {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/hardtanh.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(
+    HardTanhKernelOp<f16>,
+    f16,
+    PTX,
+    "hardtanh_fwd_f16",
+    "hardtanh_bwd_f16"
+);
+#[cfg(feature = "f16")]
+cuda_unary!(
+    HardTanhKernelOp<AMP<f16>>,
+    AMP<f16>,
+    PTX,
+    "hardtanh_fwd_f16",
+    "hardtanh_bwd_f16"
+);
+cuda_unary!(
+    HardTanhKernelOp<f32>,
+    f32,
+    PTX,
+    "hardtanh_fwd_f32",
+    "hardtanh_bwd_f32"
+);
+cuda_unary!(
+    HardTanhKernelOp<f64>,
+    f64,
+    PTX,
+    "hardtanh_fwd_f64",
+    "hardtanh_bwd_f64"
+);
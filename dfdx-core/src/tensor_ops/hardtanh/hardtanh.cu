@@ -0,0 +1,31 @@
+#include "unary_op_macros.cuh"
+
+template<typename F>
+struct HardTanhKernelOp {
+    F min_val;
+    F max_val;
+};
+
+template<typename T>
+__device__ __forceinline__ T hardtanh_fwd(T x, T min_val, T max_val) {
+    return ming(maxg(x, min_val), max_val);
+}
+
+template<typename T>
+__device__ __forceinline__ T hardtanh_bwd(T x, T min_val, T max_val) {
+    T zero = 0.0;
+    T one = 1.0;
+    return (x > min_val && x < max_val) ? one : zero;
+}
+
+UNARY_OP(__half, hardtanh_fwd_f16, hardtanh_bwd_f16, HardTanhKernelOp<__half>,
+    hardtanh_fwd(x, op.min_val, op.max_val),
+    hardtanh_bwd(x, op.min_val, op.max_val))
+
+UNARY_OP(float, hardtanh_fwd_f32, hardtanh_bwd_f32, HardTanhKernelOp<float>,
+    hardtanh_fwd(x, op.min_val, op.max_val),
+    hardtanh_bwd(x, op.min_val, op.max_val))
+
+UNARY_OP(double, hardtanh_fwd_f64, hardtanh_bwd_f64, HardTanhKernelOp<double>,
+    hardtanh_fwd(x, op.min_val, op.max_val),
+    hardtanh_bwd(x, op.min_val, op.max_val))
@@ -0,0 +1,91 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct HardTanhKernelOp<E> {
+    pub min_val: E,
+    pub max_val: E,
+}
+
+/// A piecewise linear approximation of [tanh](super::tanh()). `t` clamped to `[min_val, max_val]`
+///
+/// Arguments:
+/// - `min_val`: The lower end of the linear region.
+/// - `max_val`: The upper end of the linear region.
+///
+/// The derivative is `1` for `min_val < t < max_val`, otherwise `0`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.hardtanh(-2.0, 1.0);
+/// ```
+pub fn hardtanh<S: Shape, E: Dtype, D: UnaryKernel<HardTanhKernelOp<E>, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+    min_val: impl Into<f64>,
+    max_val: impl Into<f64>,
+) -> Tensor<S, E, D, T> {
+    t.hardtanh(min_val, max_val)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<HardTanhKernelOp<E>, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [hardtanh]
+    pub fn hardtanh(self, min_val: impl Into<f64>, max_val: impl Into<f64>) -> Self {
+        self.try_hardtanh(min_val, max_val).unwrap()
+    }
+    /// See [hardtanh]
+    pub fn try_hardtanh(
+        self,
+        min_val: impl Into<f64>,
+        max_val: impl Into<f64>,
+    ) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(
+            HardTanhKernelOp {
+                min_val: E::from_f64(min_val.into()).unwrap(),
+                max_val: E::from_f64(max_val.into()).unwrap(),
+            },
+            self,
+        )
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for HardTanhKernelOp<E> {
+    const NAME: &'static str = "HardTanh";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![
+            ("min_val", Attr::Float(self.min_val.to_f64().unwrap())),
+            ("max_val", Attr::Float(self.max_val.to_f64().unwrap())),
+        ]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for HardTanhKernelOp<E> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_hardtanh() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().hardtanh(-2.0, 1.0);
+        assert_close_to_literal!(r, [-2.0, -1.5, 0.5, 1.0, 1.0]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&x), [0.0, 0.2, 0.2, 0.0, 0.0]);
+    }
+}
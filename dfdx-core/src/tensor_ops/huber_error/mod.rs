@@ -6,7 +6,10 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::{ops::try_binary_op, Device};
+use super::{
+    ops::{try_binary_op, BinaryGraphDerivative, KernelOpInfo},
+    Device,
+};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -62,6 +65,16 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Tensor<S, E, D, T> {
     }
 }
 
+impl<E: Dtype> KernelOpInfo for HuberErrorKernelOp<E> {
+    const NAME: &'static str = "HuberError";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("delta", Attr::Float(self.delta.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for HuberErrorKernelOp<E> {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tests::*};
@@ -0,0 +1,114 @@
+use super::{trust_ratio, LambConfig, LambKernel, WeightDecay};
+use crate::{
+    dtypes::{Dtype, NotMixedPrecision},
+    tensor::{Cpu, Error},
+};
+
+#[cfg(feature = "f16")]
+impl LambKernel<crate::dtypes::AMP<crate::dtypes::f16>> for Cpu {
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(|x| x as f32);
+        let eps = cfg.eps as f32;
+
+        let mut updates = Vec::with_capacity(param.len());
+        let mut param_norm = 0.0f64;
+        let mut update_norm = 0.0f64;
+
+        for (((p, g), m), v) in param
+            .iter()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut())
+            .zip(moment2.iter_mut())
+        {
+            let p_f32 = p.0.to_f32();
+            let mut g_f32 = g.0.to_f32();
+            let mut m_f32 = m.0.to_f32();
+            let mut v_f32 = v.0.to_f32();
+
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g_f32 += (wd as f32) * p_f32;
+            }
+
+            m_f32 = m_f32 * betas[0] + g_f32 * (1.0 - betas[0]);
+            v_f32 = v_f32 * betas[1] + g_f32 * g_f32 * (1.0 - betas[1]);
+            let m_hat = m_f32 / (1.0 - betas[0].powi(t));
+            let v_hat = v_f32 / (1.0 - betas[1].powi(t));
+            let mut u = m_hat / (v_hat.sqrt() + eps);
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                u += (wd as f32) * p_f32;
+            }
+
+            param_norm += (p_f32 as f64).powi(2);
+            update_norm += (u as f64).powi(2);
+            updates.push(u);
+            m.0 = crate::dtypes::f16::from_f32(m_f32);
+            v.0 = crate::dtypes::f16::from_f32(v_f32);
+        }
+
+        let scale = (cfg.lr * trust_ratio(param_norm.sqrt(), update_norm.sqrt())) as f32;
+        for (p, u) in param.iter_mut().zip(updates) {
+            p.0 = crate::dtypes::f16::from_f32(p.0.to_f32() - scale * u);
+        }
+        Ok(())
+    }
+}
+
+impl<E: num_traits::Float + Dtype + NotMixedPrecision> LambKernel<E> for Cpu {
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        let betas = cfg.betas.map(E::from_f64).map(Option::unwrap);
+        let eps = E::from_f64(cfg.eps).unwrap();
+
+        let mut updates = Vec::with_capacity(param.len());
+        let mut param_norm = 0.0f64;
+        let mut update_norm = 0.0f64;
+
+        for (((p, mut g), m), v) in param
+            .iter()
+            .zip(grad.iter().cloned())
+            .zip(moment1.iter_mut())
+            .zip(moment2.iter_mut())
+        {
+            if let Some(WeightDecay::L2(wd)) = cfg.weight_decay {
+                g += E::from_f64(wd).unwrap() * *p;
+            }
+
+            *m = *m * betas[0] + g * (E::one() - betas[0]);
+            *v = *v * betas[1] + g.powi(2) * (E::one() - betas[1]);
+            let m_hat = *m / (E::one() - betas[0].powi(t));
+            let v_hat = *v / (E::one() - betas[1].powi(t));
+            let mut u = m_hat / (v_hat.sqrt() + eps);
+
+            if let Some(WeightDecay::Decoupled(wd)) = cfg.weight_decay {
+                u += E::from_f64(wd).unwrap() * *p;
+            }
+
+            param_norm += p.to_f64().unwrap().powi(2);
+            update_norm += u.to_f64().unwrap().powi(2);
+            updates.push(u);
+        }
+
+        let scale = E::from_f64(cfg.lr * trust_ratio(param_norm.sqrt(), update_norm.sqrt()));
+        let scale = scale.unwrap();
+        for (p, u) in param.iter_mut().zip(updates) {
+            *p -= scale * u;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{
+    dtypes::*,
+    tensor::{launch_cfg, Cuda, Error, Storage},
+    tensor_ops::optim::*,
+};
+
+use cudarc::driver::{DeviceRepr, DeviceSlice, LaunchAsync};
+
+#[repr(C)]
+struct CudaLambConfig {
+    beta1: f64,
+    beta2: f64,
+    eps: f64,
+    weight_decay_type: WeightDecayType,
+    weight_decay: f64,
+}
+
+unsafe impl DeviceRepr for CudaLambConfig {}
+
+fn lamb_config_to_cuda(config: &super::LambConfig) -> CudaLambConfig {
+    let (weight_decay_type, weight_decay) = weight_decay_to_cuda(config.weight_decay);
+
+    CudaLambConfig {
+        beta1: config.betas[0],
+        beta2: config.betas[1],
+        eps: config.eps,
+        weight_decay_type,
+        weight_decay,
+    }
+}
+
+const PTX_SRC: &str = include_str!(concat!(env!("OUT_DIR"), "/lamb.ptx"));
+
+trait HasCudaKernel<E> {
+    const MOD: &'static str;
+    const FNS: &'static [&'static str];
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<AMP<f16>> for Cuda {
+    const MOD: &'static str = "lamb_amp_f16";
+    const FNS: &'static [&'static str] = &["lamb_update_amp_f16", "lamb_apply_amp_f16"];
+}
+
+#[cfg(feature = "f16")]
+impl HasCudaKernel<f16> for Cuda {
+    const MOD: &'static str = "lamb_f16";
+    const FNS: &'static [&'static str] = &["lamb_update_f16", "lamb_apply_f16"];
+}
+
+impl HasCudaKernel<f32> for Cuda {
+    const MOD: &'static str = "lamb_f32";
+    const FNS: &'static [&'static str] = &["lamb_update_f32", "lamb_apply_f32"];
+}
+
+impl HasCudaKernel<f64> for Cuda {
+    const MOD: &'static str = "lamb_f64";
+    const FNS: &'static [&'static str] = &["lamb_update_f64", "lamb_apply_f64"];
+}
+
+impl<E: Dtype> super::LambKernel<E> for Cuda
+where
+    Self: HasCudaKernel<E>,
+{
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &super::LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error> {
+        if !self.dev.has_func(Self::MOD, Self::FNS[0]) {
+            self.dev.load_ptx(PTX_SRC.into(), Self::MOD, Self::FNS)?;
+        }
+
+        let opt_cfg = lamb_config_to_cuda(cfg);
+        let numel = param.len();
+        let mut update: Self::Vec = self.try_alloc_len(numel)?;
+        // sum of squares of `param` and `update`
+        let mut norms = self.dev.alloc_zeros::<f64>(2)?;
+
+        let update_fn = self.dev.get_func(Self::MOD, Self::FNS[0]).unwrap();
+        let launch = launch_cfg::<128>(numel as u32);
+        let params = (
+            opt_cfg,
+            numel,
+            t,
+            &*param,
+            moment1,
+            moment2,
+            grad,
+            &mut update,
+            &mut norms,
+        );
+        unsafe { update_fn.launch(launch, params) }?;
+
+        let norms = self.dev.dtoh_sync_copy(&norms)?;
+        let scale = cfg.lr * super::trust_ratio(norms[0].sqrt(), norms[1].sqrt());
+
+        let apply_fn = self.dev.get_func(Self::MOD, Self::FNS[1]).unwrap();
+        let params = (numel, scale, param, &update);
+        unsafe { apply_fn.launch(launch, params) }?;
+        Ok(())
+    }
+}
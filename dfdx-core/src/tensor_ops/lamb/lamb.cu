@@ -0,0 +1,158 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    None,
+    L2,
+    Decoupled
+};
+
+struct LambConfig {
+    double beta1;
+    double beta2;
+    double eps;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+// Updates the moments, writes the un-scaled update, and accumulates
+// the squared norms of `param` and `update` into `norms`.
+template<typename T>
+__device__ void lamb_update(
+    const LambConfig cfg,
+    const size_t numel,
+    const int t,
+    const T* param,
+    T* moment1,
+    T* moment2,
+    const T* grad,
+    T* update,
+    double* norms
+) {
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T eps = cfg.eps;
+    T weight_decay = cfg.weight_decay;
+    T one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T m = moment1[i];
+        T v = moment2[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        v = v * beta2 + g * g * (one - beta2);
+        T m_hat = m / (one - powg(beta1, t));
+        T v_hat = v / (one - powg(beta2, t));
+        T u = m_hat / (sqrtg(v_hat) + eps);
+
+        if (cfg.weight_decay_type == Decoupled) {
+            u += weight_decay * p;
+        }
+
+        moment1[i] = m;
+        moment2[i] = v;
+        update[i] = u;
+        atomicAdd(norms, (double)p * (double)p);
+        atomicAdd(norms + 1, (double)u * (double)u);
+    }
+}
+
+template<typename T>
+__device__ void lamb_apply(
+    const size_t numel,
+    const double scale,
+    T* param,
+    const T* update
+) {
+    T s = scale;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        param[i] -= s * update[i];
+    }
+}
+
+#define LAMB(TYPENAME, UPDATE, APPLY) \
+extern "C" __global__ void UPDATE( \
+    const LambConfig cfg, \
+    const size_t numel, \
+    const int t, \
+    const TYPENAME* param, \
+    TYPENAME* moment1, \
+    TYPENAME* moment2, \
+    const TYPENAME* grad, \
+    TYPENAME* update, \
+    double* norms \
+) { \
+    lamb_update(cfg, numel, t, param, moment1, moment2, grad, update, norms); \
+} \
+extern "C" __global__ void APPLY( \
+    const size_t numel, \
+    const double scale, \
+    TYPENAME* param, \
+    const TYPENAME* update \
+) { \
+    lamb_apply(numel, scale, param, update); \
+}
+
+LAMB(__half, lamb_update_f16, lamb_apply_f16);
+LAMB(float, lamb_update_f32, lamb_apply_f32);
+LAMB(double, lamb_update_f64, lamb_apply_f64);
+
+extern "C" __global__ void lamb_update_amp_f16(
+    const LambConfig cfg,
+    const size_t numel,
+    const int t,
+    const __half* param,
+    __half* moment1,
+    __half* moment2,
+    const __half* grad,
+    __half* update,
+    double* norms
+) {
+    float beta1 = cfg.beta1;
+    float beta2 = cfg.beta2;
+    float eps = cfg.eps;
+    float weight_decay = cfg.weight_decay;
+    float one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float m = moment1[i];
+        float v = moment2[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        m = m * beta1 + g * (one - beta1);
+        v = v * beta2 + g * g * (one - beta2);
+        float m_hat = m / (one - powg(beta1, t));
+        float v_hat = v / (one - powg(beta2, t));
+        float u = m_hat / (sqrtg(v_hat) + eps);
+
+        if (cfg.weight_decay_type == Decoupled) {
+            u += weight_decay * p;
+        }
+
+        moment1[i] = m;
+        moment2[i] = v;
+        update[i] = u;
+        atomicAdd(norms, (double)p * (double)p);
+        atomicAdd(norms + 1, (double)u * (double)u);
+    }
+}
+
+extern "C" __global__ void lamb_apply_amp_f16(
+    const size_t numel,
+    const double scale,
+    __half* param,
+    const __half* update
+) {
+    float s = scale;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        param[i] = (float)param[i] - s * (float)update[i];
+    }
+}
@@ -0,0 +1,99 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::WeightDecay;
+
+/// Configuration of hyperparameters for LAMB.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// LambConfig {
+///     lr: 1e-2,
+///     betas: [0.1, 0.2],
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LambConfig {
+    /// Learning rate. Defaults to `1e-3`.
+    pub lr: f64,
+
+    /// Betas from Adam paper. Defaults to `[0.9, 0.999]`.
+    pub betas: [f64; 2],
+
+    /// Epsilon for numerical stability. Defaults to `1e-6`.
+    pub eps: f64,
+
+    /// Optional weight decay. Defaults to `None`.
+    ///
+    /// [WeightDecay::Decoupled] is added to the adam update before computing
+    /// the trust ratio, as in the LAMB paper.
+    pub weight_decay: Option<WeightDecay>,
+}
+
+impl Default for LambConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-3,
+            betas: [0.9, 0.999],
+            eps: 1e-6,
+            weight_decay: None,
+        }
+    }
+}
+
+pub trait LambKernel<E: Dtype>: Storage<E> {
+    /// Updates a whole tensor at once, since the trust ratio `||param|| / ||update||`
+    /// is computed per tensor.
+    fn lamb_kernel(
+        &self,
+        t: i32,
+        cfg: &LambConfig,
+        param: &mut Self::Vec,
+        moment1: &mut Self::Vec,
+        moment2: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl LambConfig {
+    /// Update a single tensor using LAMB.
+    pub fn try_update<S: Shape, E: Dtype, D: LambKernel<E>>(
+        &self,
+        t: i32,
+        param: &mut Tensor<S, E, D>,
+        moment1: &mut D::Vec,
+        moment2: &mut D::Vec,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        param.device.lamb_kernel(
+            t,
+            self,
+            std::sync::Arc::make_mut(&mut param.data),
+            moment1,
+            moment2,
+            grad,
+        )
+    }
+}
+
+/// The trust ratio from the LAMB paper. Falls back to `1.0` if either norm is zero.
+pub(super) fn trust_ratio(param_norm: f64, update_norm: f64) -> f64 {
+    if param_norm > 0.0 && update_norm > 0.0 {
+        param_norm / update_norm
+    } else {
+        1.0
+    }
+}
@@ -0,0 +1,99 @@
+#include "cuda_utils.cuh"
+
+enum WeightDecayType {
+    None,
+    L2,
+    Decoupled
+};
+
+struct LionConfig {
+    double lr;
+    double beta1;
+    double beta2;
+    WeightDecayType weight_decay_type;
+    double weight_decay;
+};
+
+template<typename T>
+__device__ void lion_update(
+    const LionConfig cfg,
+    const size_t numel,
+    T* param,
+    T* momentum,
+    const T* grad
+) {
+    T lr = cfg.lr;
+    T beta1 = cfg.beta1;
+    T beta2 = cfg.beta2;
+    T weight_decay = cfg.weight_decay;
+    T zero = 0.0;
+    T one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        T p = param[i];
+        T g = grad[i];
+        T m = momentum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        T c = m * beta1 + g * (one - beta1);
+        T update = lr * (c > zero ? one : (c < zero ? -one : zero));
+
+        if (cfg.weight_decay_type == Decoupled) {
+            update += (weight_decay * lr) * p;
+        }
+
+        momentum[i] = m * beta2 + g * (one - beta2);
+        param[i] -= update;
+    }
+}
+
+#define LION(TYPENAME, FN) \
+extern "C" __global__ void FN( \
+    const LionConfig cfg, \
+    const size_t numel, \
+    TYPENAME* param, \
+    TYPENAME* momentum, \
+    const TYPENAME* grad \
+) { \
+    lion_update(cfg, numel, param, momentum, grad); \
+}
+
+LION(__half, lion_update_f16);
+LION(float, lion_update_f32);
+LION(double, lion_update_f64);
+
+extern "C" __global__ void lion_update_amp_f16(
+    const LionConfig cfg,
+    const size_t numel,
+    __half* param,
+    __half* momentum,
+    const __half* grad
+) {
+    float lr = cfg.lr;
+    float beta1 = cfg.beta1;
+    float beta2 = cfg.beta2;
+    float weight_decay = cfg.weight_decay;
+    float zero = 0.0;
+    float one = 1.0;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        float p = param[i];
+        float g = grad[i];
+        float m = momentum[i];
+
+        if (cfg.weight_decay_type == L2) {
+            g += weight_decay * p;
+        }
+
+        float c = m * beta1 + g * (one - beta1);
+        float update = lr * (c > zero ? one : (c < zero ? -one : zero));
+
+        if (cfg.weight_decay_type == Decoupled) {
+            update += (weight_decay * lr) * p;
+        }
+
+        momentum[i] = m * beta2 + g * (one - beta2);
+        param[i] -= update;
+    }
+}
@@ -0,0 +1,75 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Storage, Tensor},
+};
+
+use super::WeightDecay;
+
+/// Configuration of hyperparameters for Lion.
+///
+/// Changing all default parameters:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// LionConfig {
+///     lr: 3e-4,
+///     betas: [0.95, 0.98],
+///     weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct LionConfig {
+    /// Learning rate. Defaults to `1e-4`.
+    pub lr: f64,
+
+    /// Betas from Lion paper. `betas[0]` interpolates the update, and `betas[1]`
+    /// the momentum. Defaults to `[0.9, 0.99]`.
+    pub betas: [f64; 2],
+
+    /// Optional weight decay. Defaults to `None`.
+    pub weight_decay: Option<WeightDecay>,
+}
+
+impl Default for LionConfig {
+    fn default() -> Self {
+        Self {
+            lr: 1e-4,
+            betas: [0.9, 0.99],
+            weight_decay: None,
+        }
+    }
+}
+
+pub trait LionKernel<E: Dtype>: Storage<E> {
+    fn lion_kernel(
+        &self,
+        cfg: &LionConfig,
+        param: &mut Self::Vec,
+        momentum: &mut Self::Vec,
+        grad: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+impl LionConfig {
+    /// Update a single tensor using Lion.
+    pub fn try_update<S: Shape, E: Dtype, D: LionKernel<E>>(
+        &self,
+        param: &mut Tensor<S, E, D>,
+        momentum: &mut D::Vec,
+        grad: &D::Vec,
+    ) -> Result<(), crate::tensor::Error> {
+        param.device.lion_kernel(
+            self,
+            std::sync::Arc::make_mut(&mut param.data),
+            momentum,
+            grad,
+        )
+    }
+}
@@ -61,6 +61,18 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Tensor<S, E, D, T> {
         */
         let shape = *self.shape();
         let (t, tape) = self.split_tape();
+        if tape.captures_ops() {
+            // resetting the id below hides the subtraction from a captured graph
+            let t = t.put_tape(tape);
+            let max = t.with_empty_tape().try_max::<_, Ax>()?;
+            let tm = t.try_sub(max.try_broadcast_like(&shape)?)?;
+            let logsumexp = tm
+                .with_empty_tape()
+                .try_exp()?
+                .try_sum::<_, Ax>()?
+                .try_ln()?;
+            return tm.try_sub(logsumexp.try_broadcast_like(&shape)?);
+        }
         let max = t.clone().try_max::<_, Ax>()?;
         let tm = {
             // Do this calculation off of the tape
@@ -69,7 +81,11 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Tensor<S, E, D, T> {
             t.id = keep_id;
             t.put_tape(tape)
         };
-        let logsumexp = tm.retaped::<T>().try_exp()?.try_sum::<_, Ax>()?.try_ln()?;
+        let logsumexp = tm
+            .with_empty_tape()
+            .try_exp()?
+            .try_sum::<_, Ax>()?
+            .try_ln()?;
         tm.try_sub(logsumexp.try_broadcast_like(&shape)?)
     }
 }
@@ -43,6 +43,13 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> LogSumExpTo for Tensor<S,
     {
         let shape = *self.shape();
         let (t, tape) = self.split_tape();
+        if tape.captures_ops() {
+            // resetting the ids below hides the max from a captured graph
+            let t = t.put_tape(tape);
+            let max = t.with_empty_tape().try_max::<Dst, Ax>()?;
+            let tm = t.try_sub(max.with_empty_tape().try_broadcast_like::<_, Ax>(&shape)?)?;
+            return tm.try_exp()?.try_sum::<Dst, Ax>()?.try_ln()?.try_add(max);
+        }
         let max: Tensor<Dst, E, D> = t.clone().try_max()?;
         let t = {
             // does normalization outside of backprop graph.
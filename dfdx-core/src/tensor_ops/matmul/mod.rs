@@ -9,11 +9,15 @@ pub(super) mod cuda_kernel;
 pub(super) mod webgpu_kernel;
 
 use crate::{
-    shapes::{Const, Dim, Dtype, Shape},
-    tensor::{Error, Merge, PutTape, SplitTape, Storage, Tape, Tensor},
+    shapes::{Axes3, Axes4, Axis, Const, Dim, Dtype, Shape},
+    tensor::{
+        Error, GhostTensor, GraphBackward, GraphGradients, Merge, OpNode, OwnedTape, PutTape,
+        SplitTape, Storage, Tangents, Tape, Tensor, WithEmptyTape,
+    },
 };
 
 use super::reshape_to::{ReshapeKernel, ReshapeTo};
+use super::{Device, PermuteTo, SumTo, TryAdd};
 
 /// Matrix * Matrix, Vector * Matrix, Vector * Vector, and broadcasted/batched versions.
 ///
@@ -54,6 +58,15 @@ use super::reshape_to::{ReshapeKernel, ReshapeTo};
 /// let _: Tensor<Rank3<10, 3, 4>, f32, _> = x.matmul(y);
 /// ```
 ///
+/// Tensors with up to 6 batch dimensions (8d in total) can be multiplied the same way:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank6<2, 3, 4, 5, 3, 2>, f32, _> = dev.zeros();
+/// let y: Tensor<Rank6<2, 3, 4, 5, 2, 4>, f32, _> = dev.zeros();
+/// let _: Tensor<Rank6<2, 3, 4, 5, 3, 4>, f32, _> = x.matmul(y);
+/// ```
+///
 /// 5. Broadcasted matmul
 /// ```rust
 /// # use dfdx_core::prelude::*;
@@ -81,7 +94,7 @@ pub trait TryMatMul<Rhs>: Sized {
 
 #[rustfmt::skip]
 fn try_binary_op<
-    Lhs: Shape,
+    Lhs: MatMulGraphDerivative<Rhs, Out>,
     Rhs: Shape,
     Out: Shape,
     E: Dtype,
@@ -102,7 +115,17 @@ fn try_binary_op<
     let lhs_ghost = lhs.ghost();
     let rhs_ghost = rhs.ghost();
     let out = fwd(&lhs.device, &lhs, &rhs)?;
+    if tape.captures_ops() {
+        let k = lhs.shape.dims().last().copied().unwrap_or(1);
+        let node = OpNode::new("MatMul").input(&lhs).input(&rhs).output(&out);
+        tape.add_op_node(node.flops(2 * k * out.shape.num_elements()));
+    }
     let out_ghost = out.ghost();
+    let graph_op = tape.records_graph().then(|| MatMulGraphOp {
+        lhs: lhs.clone(),
+        rhs: rhs.clone(),
+        out: out.ghost(),
+    });
     tape.add_backward_op(move |grads| {
         grads.try_alloc_for(&lhs_ghost)?;
         grads.try_alloc_for(&rhs_ghost)?;
@@ -110,9 +133,210 @@ fn try_binary_op<
         let (grad_lhs, grad_rhs, grad_out) = grads.muts_and_ref(&lhs_ghost, &rhs_ghost, &out_ghost);
         bwd(&lhs.device, &lhs, grad_lhs, &rhs, grad_rhs, grad_out)
     });
+    if let Some(graph_op) = graph_op {
+        tape.add_graph_op(graph_op);
+    }
     Ok(out.put_tape(tape))
 }
 
+/// The backward pass of a matmul written with tensor ops, see [GraphBackward].
+trait MatMulGraphDerivative<Rhs: Shape, Out: Shape>: Shape {
+    fn try_grads<E: Dtype, D: Device<E>>(
+        lhs: Tensor<Self, E, D, OwnedTape<E, D>>,
+        rhs: Tensor<Rhs, E, D, OwnedTape<E, D>>,
+        grad_out: Tensor<Out, E, D, OwnedTape<E, D>>,
+    ) -> Result<
+        (
+            Tensor<Self, E, D, OwnedTape<E, D>>,
+            Tensor<Rhs, E, D, OwnedTape<E, D>>,
+        ),
+        Error,
+    >;
+
+    /// `lhs_tangent * rhs + lhs * rhs_tangent`
+    fn try_tangent<E: Dtype, D: Device<E>>(
+        lhs: &Tensor<Self, E, D>,
+        rhs: &Tensor<Rhs, E, D>,
+        lhs_tangent: Option<Tensor<Self, E, D>>,
+        rhs_tangent: Option<Tensor<Rhs, E, D>>,
+    ) -> Result<Option<Tensor<Out, E, D>>, Error>;
+}
+
+macro_rules! matmul_tangent {
+    ($lhs:ident, $rhs:ident, $lhs_tangent:ident, $rhs_tangent:ident) => {
+        match ($lhs_tangent, $rhs_tangent) {
+            (Some(l), Some(r)) => {
+                let l = l.try_matmul($rhs.clone())?;
+                Ok(Some(l.try_add($lhs.clone().try_matmul(r)?)?))
+            }
+            (Some(l), None) => Ok(Some(l.try_matmul($rhs.clone())?)),
+            (None, Some(r)) => Ok(Some($lhs.clone().try_matmul(r)?)),
+            (None, None) => Ok(None),
+        }
+    };
+}
+
+impl<M: Dim, K: Dim, N: Dim> MatMulGraphDerivative<(K, N), (M, N)> for (M, K) {
+    fn try_grads<E: Dtype, D: Device<E>>(
+        lhs: Tensor<Self, E, D, OwnedTape<E, D>>,
+        rhs: Tensor<(K, N), E, D, OwnedTape<E, D>>,
+        grad_out: Tensor<(M, N), E, D, OwnedTape<E, D>>,
+    ) -> Result<
+        (
+            Tensor<Self, E, D, OwnedTape<E, D>>,
+            Tensor<(K, N), E, D, OwnedTape<E, D>>,
+        ),
+        Error,
+    > {
+        let grad_lhs = grad_out.with_empty_tape().try_matmul(rhs.try_permute()?)?;
+        let grad_rhs = lhs.try_permute()?.try_matmul(grad_out)?;
+        Ok((grad_lhs, grad_rhs))
+    }
+
+    fn try_tangent<E: Dtype, D: Device<E>>(
+        lhs: &Tensor<Self, E, D>,
+        rhs: &Tensor<(K, N), E, D>,
+        lhs_tangent: Option<Tensor<Self, E, D>>,
+        rhs_tangent: Option<Tensor<(K, N), E, D>>,
+    ) -> Result<Option<Tensor<(M, N), E, D>>, Error> {
+        matmul_tangent!(lhs, rhs, lhs_tangent, rhs_tangent)
+    }
+}
+
+impl<B: Dim, M: Dim, K: Dim, N: Dim> MatMulGraphDerivative<(K, N), (B, M, N)> for (B, M, K) {
+    fn try_grads<E: Dtype, D: Device<E>>(
+        lhs: Tensor<Self, E, D, OwnedTape<E, D>>,
+        rhs: Tensor<(K, N), E, D, OwnedTape<E, D>>,
+        grad_out: Tensor<(B, M, N), E, D, OwnedTape<E, D>>,
+    ) -> Result<
+        (
+            Tensor<Self, E, D, OwnedTape<E, D>>,
+            Tensor<(K, N), E, D, OwnedTape<E, D>>,
+        ),
+        Error,
+    > {
+        let grad_lhs = grad_out.with_empty_tape().try_matmul(rhs.try_permute()?)?;
+        let grad_rhs = lhs
+            .try_permute::<_, Axes3<0, 2, 1>>()?
+            .try_matmul(grad_out)?
+            .try_sum::<_, Axis<0>>()?;
+        Ok((grad_lhs, grad_rhs))
+    }
+
+    fn try_tangent<E: Dtype, D: Device<E>>(
+        lhs: &Tensor<Self, E, D>,
+        rhs: &Tensor<(K, N), E, D>,
+        lhs_tangent: Option<Tensor<Self, E, D>>,
+        rhs_tangent: Option<Tensor<(K, N), E, D>>,
+    ) -> Result<Option<Tensor<(B, M, N), E, D>>, Error> {
+        matmul_tangent!(lhs, rhs, lhs_tangent, rhs_tangent)
+    }
+}
+
+impl<B: Dim, M: Dim, K: Dim, N: Dim> MatMulGraphDerivative<(B, K, N), (B, M, N)> for (B, M, K) {
+    fn try_grads<E: Dtype, D: Device<E>>(
+        lhs: Tensor<Self, E, D, OwnedTape<E, D>>,
+        rhs: Tensor<(B, K, N), E, D, OwnedTape<E, D>>,
+        grad_out: Tensor<(B, M, N), E, D, OwnedTape<E, D>>,
+    ) -> Result<
+        (
+            Tensor<Self, E, D, OwnedTape<E, D>>,
+            Tensor<(B, K, N), E, D, OwnedTape<E, D>>,
+        ),
+        Error,
+    > {
+        let grad_lhs = grad_out
+            .with_empty_tape()
+            .try_matmul(rhs.try_permute::<_, Axes3<0, 2, 1>>()?)?;
+        let grad_rhs = lhs
+            .try_permute::<_, Axes3<0, 2, 1>>()?
+            .try_matmul(grad_out)?;
+        Ok((grad_lhs, grad_rhs))
+    }
+
+    fn try_tangent<E: Dtype, D: Device<E>>(
+        lhs: &Tensor<Self, E, D>,
+        rhs: &Tensor<(B, K, N), E, D>,
+        lhs_tangent: Option<Tensor<Self, E, D>>,
+        rhs_tangent: Option<Tensor<(B, K, N), E, D>>,
+    ) -> Result<Option<Tensor<(B, M, N), E, D>>, Error> {
+        matmul_tangent!(lhs, rhs, lhs_tangent, rhs_tangent)
+    }
+}
+
+impl<B: Dim, S: Dim, M: Dim, K: Dim, N: Dim> MatMulGraphDerivative<(B, S, K, N), (B, S, M, N)>
+    for (B, S, M, K)
+{
+    fn try_grads<E: Dtype, D: Device<E>>(
+        lhs: Tensor<Self, E, D, OwnedTape<E, D>>,
+        rhs: Tensor<(B, S, K, N), E, D, OwnedTape<E, D>>,
+        grad_out: Tensor<(B, S, M, N), E, D, OwnedTape<E, D>>,
+    ) -> Result<
+        (
+            Tensor<Self, E, D, OwnedTape<E, D>>,
+            Tensor<(B, S, K, N), E, D, OwnedTape<E, D>>,
+        ),
+        Error,
+    > {
+        let grad_lhs = grad_out
+            .with_empty_tape()
+            .try_matmul(rhs.try_permute::<_, Axes4<0, 1, 3, 2>>()?)?;
+        let grad_rhs = lhs
+            .try_permute::<_, Axes4<0, 1, 3, 2>>()?
+            .try_matmul(grad_out)?;
+        Ok((grad_lhs, grad_rhs))
+    }
+
+    fn try_tangent<E: Dtype, D: Device<E>>(
+        lhs: &Tensor<Self, E, D>,
+        rhs: &Tensor<(B, S, K, N), E, D>,
+        lhs_tangent: Option<Tensor<Self, E, D>>,
+        rhs_tangent: Option<Tensor<(B, S, K, N), E, D>>,
+    ) -> Result<Option<Tensor<(B, S, M, N), E, D>>, Error> {
+        matmul_tangent!(lhs, rhs, lhs_tangent, rhs_tangent)
+    }
+}
+
+struct MatMulGraphOp<Lhs: Shape, Rhs: Shape, Out: Shape, E, D: Storage<E>> {
+    lhs: Tensor<Lhs, E, D>,
+    rhs: Tensor<Rhs, E, D>,
+    out: GhostTensor<Out, E, D>,
+}
+
+impl<Lhs: MatMulGraphDerivative<Rhs, Out>, Rhs: Shape, Out: Shape, E: Dtype, D: Storage<E>>
+    GraphBackward<E, D> for MatMulGraphOp<Lhs, Rhs, Out, E, D>
+{
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(grad_out) = grads.grad_out(&self.out)? {
+            let (grad_lhs, grad_rhs) = Lhs::try_grads(
+                self.lhs.retaped::<OwnedTape<E, D>>(),
+                self.rhs.retaped::<OwnedTape<E, D>>(),
+                grad_out,
+            )?;
+            grads.accumulate(&self.lhs, grad_lhs)?;
+            grads.accumulate(&self.rhs, grad_rhs)?;
+        }
+        Ok(())
+    }
+
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        let lhs_tangent = tangents.get(&self.lhs)?;
+        let rhs_tangent = tangents.get(&self.rhs)?;
+        if let Some(tangent) = Lhs::try_tangent(&self.lhs, &self.rhs, lhs_tangent, rhs_tangent)? {
+            tangents.try_set(&self.out, tangent)?;
+        }
+        Ok(())
+    }
+}
+
 pub trait MatMatKernel<E: Dtype>: Storage<E> {
     fn forward<M: Dim, K: Dim, N: Dim>(
         &self,
@@ -310,6 +534,37 @@ where
     }
 }
 
+/// Batched matmul over more than 2 batch dimensions, which flattens all the batch dimensions
+/// into one and uses [MatMatBatch3Kernel].
+macro_rules! impl_batched_matmul {
+    ([$($B:ident $BIdx:tt),+], $MIdx:tt, $KIdx:tt) => {
+        impl<$($B: Dim, )+ M: Dim, K: Dim, N: Dim, E: Dtype, D, T, R>
+            TryMatMul<Tensor<($($B, )+ K, N), E, D, R>> for Tensor<($($B, )+ M, K), E, D, T>
+        where
+            D: MatMatBatch3Kernel<E> + ReshapeKernel<E>,
+            T: Tape<E, D> + Merge<R>,
+            R: Tape<E, D>,
+        {
+            type Output = Tensor<($($B, )+ M, N), E, D, T>;
+            fn try_matmul(self, rhs: Tensor<($($B, )+ K, N), E, D, R>) -> Result<Self::Output, Error> {
+                $(assert_eq!(self.shape.$BIdx, rhs.shape.$BIdx);)+
+                assert_eq!(self.shape.$KIdx, rhs.shape.$MIdx);
+                let batch = 1 $(* self.shape.$BIdx.size())+;
+                let (m, k, n) = (self.shape.$MIdx, self.shape.$KIdx, rhs.shape.$KIdx);
+                let out_shape = ($(self.shape.$BIdx, )+ m, n);
+                let lhs = self.try_reshape_like(&(batch, m, k))?;
+                let rhs = rhs.try_reshape_like(&(batch, k, n))?;
+                lhs.try_matmul(rhs)?.try_reshape_like(&out_shape)
+            }
+        }
+    };
+}
+
+impl_batched_matmul!([B0 0, B1 1, B2 2], 3, 4);
+impl_batched_matmul!([B0 0, B1 1, B2 2, B3 3], 4, 5);
+impl_batched_matmul!([B0 0, B1 1, B2 2, B3 3, B4 4], 5, 6);
+impl_batched_matmul!([B0 0, B1 1, B2 2, B3 3, B4 4, B5 5], 6, 7);
+
 /// Utility function returning the ld and whether the matrix is transposed
 /// for cublas & cblas.
 #[allow(unused)]
@@ -526,6 +781,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_matmul_batched_6d_and_8d() {
+        let dev: TestDevice = Default::default();
+
+        let a: Tensor<Rank6<2, 3, 1, 2, 3, 2>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank6<2, 3, 1, 2, 2, 4>, TestDtype, _> = dev.sample_normal();
+        let c = a.leaky_trace().matmul(b.clone());
+        let g = c.exp().sum().backward();
+
+        let a3 = a.clone().reshape::<Rank3<12, 3, 2>>();
+        let b3 = b.clone().reshape::<Rank3<12, 2, 4>>();
+        let c = a.clone().matmul(b.clone()).reshape::<Rank3<12, 3, 4>>();
+        let c3 = a3.leaky_trace().matmul(b3.clone());
+        assert_close_to_tensor!(c, c3);
+        let g3 = c3.exp().sum().backward();
+        assert_close_to_tensor!(g.get(&a).reshape::<Rank3<12, 3, 2>>(), g3.get(&a3));
+        assert_close_to_tensor!(g.get(&b).reshape::<Rank3<12, 2, 4>>(), g3.get(&b3), 1e-5);
+
+        let a: Tensor<Rank8<2, 1, 2, 1, 2, 1, 3, 2>, TestDtype, _> = dev.sample_normal();
+        let b: Tensor<Rank8<2, 1, 2, 1, 2, 1, 2, 4>, TestDtype, _> = dev.sample_normal();
+        let c = a.clone().matmul(b.clone());
+        let c3 = a
+            .reshape::<Rank3<8, 3, 2>>()
+            .matmul(b.reshape::<Rank3<8, 2, 4>>());
+        assert_close_to_tensor!(c.reshape::<Rank3<8, 3, 4>>(), c3);
+    }
+
     #[test]
     fn test_matmul_vec_normal() {
         let dev: TestDevice = Default::default();
@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::{BroadcastTo, ChooseFrom, Device, SumTo, TryEq};
 use crate::{shapes::*, tensor::*};
 
 pub trait MaxReduceKernel<E: Dtype>: Storage<E> {
@@ -71,19 +72,77 @@ impl<S: Shape, E: Dtype, D: MaxReduceKernel<E>, T: Tape<E, D>> MaxTo for Tensor<
         let dst: Dst = self.shape().reduced();
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.forward(dst, &inp)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Max").axes::<Ax>().input(&inp).output(&out);
+            tape.add_op_node(node.flops(inp.shape().num_elements()));
+        }
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
+        let graph_op = tape.records_graph().then(|| MaxGraphOp::<_, _, Ax, _, _> {
+            inp: inp.clone(),
+            out: out.clone(),
+            marker: std::marker::PhantomData,
+        });
         tape.add_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             inp.device.backward(&inp, grad_inp, &out_clone, grad_out)
         });
+        if let Some(graph_op) = graph_op {
+            tape.add_graph_op(graph_op);
+        }
         Ok(out.put_tape(tape))
     }
 }
 
+struct MaxGraphOp<Src: Shape, Dst: Shape, Ax, E, D: Storage<E>> {
+    inp: Tensor<Src, E, D>,
+    out: Tensor<Dst, E, D>,
+    marker: std::marker::PhantomData<Ax>,
+}
+
+impl<Src: Shape, Dst: Shape, Ax: Axes + 'static, E: Dtype, D: Storage<E>> GraphBackward<E, D>
+    for MaxGraphOp<Src, Dst, Ax, E, D>
+where
+    Src: ReduceShapeTo<Dst, Ax>,
+{
+    /// Like the regular backward op, every element equal to the max gets the gradient.
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(grad_out) = grads.grad_out(&self.out)? {
+            let shape = self.inp.shape;
+            let out = self.out.try_broadcast_like::<_, Ax>(&shape)?;
+            let mask = self.inp.try_eq(&out)?;
+            let zeros = self.inp.device.try_zeros_like(&shape)?;
+            let grad_inp = mask.try_choose(grad_out.try_broadcast_like::<_, Ax>(&shape)?, zeros)?;
+            grads.accumulate(&self.inp, grad_inp)?;
+        }
+        Ok(())
+    }
+
+    /// Sums the tangents of every element equal to the max, to match the backward pass.
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(tangent) = tangents.get(&self.inp)? {
+            let shape = self.inp.shape;
+            let out = self.out.clone().try_broadcast_like::<_, Ax>(&shape)?;
+            let mask = self.inp.try_eq(&out)?;
+            let zeros = self.inp.device.try_zeros_like(&shape)?;
+            let tangent = mask.try_choose(tangent, zeros)?.try_sum::<Dst, Ax>()?;
+            tangents.try_set(&self.out, tangent)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,4 +199,31 @@ mod tests {
         let g = r.sum().backward();
         assert_close_to_literal!(g.get(&t), [[1.0, 1.0], [1.0, 1.0], [0.0, 1.0], [0.0, 1.0]]);
     }
+
+    #[test]
+    fn test_max_axes_7d() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank7<2, 1, 3, 2, 1, 2, 3>, TestDtype, _> = dev.sample_normal();
+        let r = t.leaky_trace().max::<Rank1<3>, Axes6<0, 1, 2, 3, 4, 5>>();
+        let r2 = t
+            .leaky_trace()
+            .max::<_, Axes3<3, 4, 5>>()
+            .max::<_, Axes3<0, 1, 2>>();
+        assert_close_to_tensor!(r, r2);
+        let t_vec = t.as_vec();
+        let r_vec = r.as_vec();
+        for (i, m) in r_vec.iter().enumerate() {
+            let expected = t_vec
+                .iter()
+                .skip(i)
+                .step_by(3)
+                .copied()
+                .reduce(|a, b| if b > a { b } else { a })
+                .unwrap();
+            assert_eq!(*m, expected);
+        }
+        let g = r.mean().backward();
+        let g2 = r2.mean().backward();
+        assert_eq!(g.get(&t).as_vec(), g2.get(&t).as_vec());
+    }
 }
@@ -6,7 +6,10 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::{ops::try_binary_op, Device};
+use super::{
+    ops::{try_binary_op, BinaryGraphDerivative, KernelOpInfo},
+    Device,
+};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -53,6 +56,12 @@ impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<E, D>> Tensor<S, E, D, LTape>
     }
 }
 
+impl KernelOpInfo for MaximumKernelOp {
+    const NAME: &'static str = "Maximum";
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for MaximumKernelOp {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
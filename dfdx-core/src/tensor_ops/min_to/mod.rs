@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::{BroadcastTo, ChooseFrom, Device, SumTo, TryEq};
 use crate::{shapes::*, tensor::*};
 
 pub trait MinReduceKernel<E: Dtype>: Storage<E> {
@@ -71,19 +72,77 @@ impl<S: Shape, E: Dtype, D: MinReduceKernel<E>, T: Tape<E, D>> MinTo for Tensor<
         let dst: Dst = self.shape().reduced();
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.forward(dst, &inp)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Min").axes::<Ax>().input(&inp).output(&out);
+            tape.add_op_node(node.flops(inp.shape().num_elements()));
+        }
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
+        let graph_op = tape.records_graph().then(|| MinGraphOp::<_, _, Ax, _, _> {
+            inp: inp.clone(),
+            out: out.clone(),
+            marker: std::marker::PhantomData,
+        });
         tape.add_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             inp.device.backward(&inp, grad_inp, &out_clone, grad_out)
         });
+        if let Some(graph_op) = graph_op {
+            tape.add_graph_op(graph_op);
+        }
         Ok(out.put_tape(tape))
     }
 }
 
+struct MinGraphOp<Src: Shape, Dst: Shape, Ax, E, D: Storage<E>> {
+    inp: Tensor<Src, E, D>,
+    out: Tensor<Dst, E, D>,
+    marker: std::marker::PhantomData<Ax>,
+}
+
+impl<Src: Shape, Dst: Shape, Ax: Axes + 'static, E: Dtype, D: Storage<E>> GraphBackward<E, D>
+    for MinGraphOp<Src, Dst, Ax, E, D>
+where
+    Src: ReduceShapeTo<Dst, Ax>,
+{
+    /// Like the regular backward op, every element equal to the min gets the gradient.
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(grad_out) = grads.grad_out(&self.out)? {
+            let shape = self.inp.shape;
+            let out = self.out.try_broadcast_like::<_, Ax>(&shape)?;
+            let mask = self.inp.try_eq(&out)?;
+            let zeros = self.inp.device.try_zeros_like(&shape)?;
+            let grad_inp = mask.try_choose(grad_out.try_broadcast_like::<_, Ax>(&shape)?, zeros)?;
+            grads.accumulate(&self.inp, grad_inp)?;
+        }
+        Ok(())
+    }
+
+    /// Sums the tangents of every element equal to the min, to match the backward pass.
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(tangent) = tangents.get(&self.inp)? {
+            let shape = self.inp.shape;
+            let out = self.out.clone().try_broadcast_like::<_, Ax>(&shape)?;
+            let mask = self.inp.try_eq(&out)?;
+            let zeros = self.inp.device.try_zeros_like(&shape)?;
+            let tangent = mask.try_choose(tangent, zeros)?.try_sum::<Dst, Ax>()?;
+            tangents.try_set(&self.out, tangent)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -6,7 +6,10 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::{ops::try_binary_op, Device};
+use super::{
+    ops::{try_binary_op, BinaryGraphDerivative, KernelOpInfo},
+    Device,
+};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -52,6 +55,13 @@ impl<S: Shape, E: Dtype, D: Device<E>, LTape: Tape<E, D>> Tensor<S, E, D, LTape>
         try_binary_op(MinimumKernelOp, self, rhs)
     }
 }
+
+impl KernelOpInfo for MinimumKernelOp {
+    const NAME: &'static str = "Minimum";
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for MinimumKernelOp {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -0,0 +1,17 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+use num_traits::Float;
+
+impl<F: Float> UnaryDerivative<F> for super::MishKernelOp {
+    const DF_USES_FX: bool = false;
+    const HAS_CONST_DF: bool = false;
+    #[inline(always)]
+    fn f(&self, &x: &F) -> F {
+        x * x.exp().ln_1p().tanh()
+    }
+    #[inline(always)]
+    fn df(&self, &x: &F) -> F {
+        let t = x.exp().ln_1p().tanh();
+        let s = F::one() / (F::one() + x.neg().exp());
+        t + x * s * (F::one() - t * t)
+    }
+}
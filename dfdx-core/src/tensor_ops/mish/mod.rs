@@ -0,0 +1,77 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct MishKernelOp;
+
+/// [Mish](https://arxiv.org/abs/1908.08681). `t * tanh(softplus(t))`
+///
+/// The derivative is `tanh(softplus(t)) + t * sigmoid(t) * (1 - tanh(softplus(t))^2)`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.mish();
+/// ```
+pub fn mish<S: Shape, E: Dtype, D: UnaryKernel<MishKernelOp, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.mish()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<MishKernelOp, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [mish]
+    pub fn mish(self) -> Self {
+        self.try_mish().unwrap()
+    }
+    /// See [mish]
+    pub fn try_mish(self) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(MishKernelOp, self)
+    }
+}
+
+impl KernelOpInfo for MishKernelOp {
+    const NAME: &'static str = "Mish";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for MishKernelOp {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_mish() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().mish();
+        assert_close_to_literal!(
+            r,
+            [-0.072591741, -0.29809974, 0.37524521, 1.943959, 6.9999884]
+        );
+        let g = r.mean().backward();
+        assert_close_to_literal!(
+            g.get(&x),
+            [
+                -0.010754642,
+                -0.012819563,
+                0.17728488,
+                0.21386359,
+                0.20000431
+            ]
+        );
+    }
+}
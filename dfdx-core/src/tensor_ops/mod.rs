@@ -151,6 +151,8 @@ pub use utilities::*;
 
 mod abs;
 mod accurate_gelu;
+mod adadelta;
+mod adagrad;
 mod adam;
 mod add;
 mod attention_reshape;
@@ -158,6 +160,7 @@ pub(crate) mod axpy;
 mod bce;
 mod boolean;
 mod broadcast_to;
+mod celu;
 mod choose;
 mod clamp;
 mod cmp;
@@ -168,9 +171,15 @@ mod concat_tensor_along;
 mod cos;
 mod div;
 mod dropout;
+mod elu;
 mod exp;
 mod fast_gelu;
+mod hardsigmoid;
+mod hardswish;
+mod hardtanh;
 mod huber_error;
+mod lamb;
+mod lion;
 mod ln;
 mod log_softmax;
 mod logsumexp_to;
@@ -180,6 +189,7 @@ mod maximum;
 mod mean_to;
 mod min_to;
 mod minimum;
+mod mish;
 mod mul;
 mod nans_to;
 mod negate;
@@ -191,15 +201,20 @@ mod prelu;
 mod realize_to;
 mod recip;
 mod relu;
-mod reshape_to;
+mod relu6;
+pub(crate) mod reshape_to;
 mod rmsprop;
 mod roll;
 mod select_and_gather;
+mod selu;
 mod sgd;
 mod sigmoid;
+mod silu;
 mod sin;
 mod slice;
 mod softmax;
+mod softplus;
+mod softsign;
 mod sqrt;
 mod square;
 mod stack;
@@ -214,6 +229,8 @@ mod var_to;
 
 pub use abs::abs;
 pub use accurate_gelu::accurate_gelu;
+pub use adadelta::AdadeltaConfig;
+pub use adagrad::AdagradConfig;
 pub use adam::AdamConfig;
 pub use add::{add, TryAdd};
 pub use attention_reshape::TryAttentionReshape;
@@ -221,6 +238,7 @@ pub use axpy::axpy;
 pub use bce::bce_with_logits;
 pub use boolean::{bool_and, bool_not, bool_or, bool_xor};
 pub use broadcast_to::BroadcastTo;
+pub use celu::celu;
 pub use choose::ChooseFrom;
 pub use clamp::clamp;
 pub use cmp::{eq, ge, gt, le, lt, ne, TryEq, TryGe, TryGt, TryLe, TryLt, TryNe};
@@ -233,11 +251,17 @@ pub use concat_tensor_along::TryConcatTensorAlong;
 pub use cos::cos;
 pub use div::{div, TryDiv};
 pub use dropout::dropout;
+pub use elu::elu;
 pub use exp::exp;
 pub use fast_gelu::fast_gelu;
 #[allow(deprecated)]
 pub use fast_gelu::gelu;
+pub use hardsigmoid::hardsigmoid;
+pub use hardswish::hardswish;
+pub use hardtanh::hardtanh;
 pub use huber_error::huber_error;
+pub use lamb::LambConfig;
+pub use lion::LionConfig;
 pub use ln::ln;
 pub use log_softmax::log_softmax;
 pub use logsumexp_to::LogSumExpTo;
@@ -247,6 +271,7 @@ pub use maximum::maximum;
 pub use mean_to::MeanTo;
 pub use min_to::MinTo;
 pub use minimum::minimum;
+pub use mish::mish;
 pub use mul::{mul, TryMul};
 pub use nans_to::nans_to;
 pub use negate::negate;
@@ -258,15 +283,20 @@ pub use prelu::{leakyrelu, prelu, TryPReLU};
 pub use realize_to::RealizeTo;
 pub use recip::recip;
 pub use relu::relu;
+pub use relu6::relu6;
 pub use reshape_to::ReshapeTo;
 pub use rmsprop::RMSpropConfig;
 pub use roll::Roll;
 pub use select_and_gather::{GatherTo, SelectTo};
+pub use selu::selu;
 pub use sgd::SgdConfig;
 pub use sigmoid::sigmoid;
+pub use silu::silu;
 pub use sin::sin;
 pub use slice::slice;
 pub use softmax::softmax;
+pub use softplus::softplus;
+pub use softsign::softsign;
 pub use sqrt::sqrt;
 pub use square::square;
 pub use stack::{AddDim, TryStack};
@@ -281,20 +311,56 @@ pub use upscale2d::{
 };
 pub use var_to::VarTo;
 
+/// The elementwise kernel ops that are exported to ONNX, to match recorded ops on their [KernelOpInfo::NAME].
+#[cfg(feature = "onnx")]
+pub(crate) mod kernel_ops {
+    pub(crate) use super::{
+        abs::AbsKernelOp,
+        accurate_gelu::AccurateGeLUKernelOp,
+        add::{BinaryAddKernelOp, ScalarAddKernelOp},
+        clamp::ClampKernelOp,
+        cos::CosKernelOp,
+        div::{BinaryDivKernelOp, ScalarDivKernelOp},
+        exp::ExpKernelOp,
+        fast_gelu::FastGeLUKernelOp,
+        ln::LnKernelOp,
+        maximum::MaximumKernelOp,
+        minimum::MinimumKernelOp,
+        mul::{BinaryMulKernelOp, ScalarMulKernelOp},
+        nans_to::NansToKernelOp,
+        negate::NegateKernelOp,
+        pow::{PowfKernelOp, PowiKernelOp},
+        recip::RecipKernelOp,
+        relu::ReLUKernelOp,
+        sigmoid::SigmoidKernelOp,
+        sin::SinKernelOp,
+        sqrt::SqrtKernelOp,
+        square::SquareKernelOp,
+        sub::{BinarySubKernelOp, ScalarSubKernelOp},
+        tanh::TanhKernelOp,
+    };
+}
+
 mod conv1d;
 pub use conv1d::TryConv1D;
 
-#[cfg(feature = "nightly")]
 mod conv2d;
-#[cfg(feature = "nightly")]
 pub use conv2d::TryConv2D;
 
-#[cfg(feature = "nightly")]
+mod conv3d;
+pub use conv3d::TryConv3D;
+
 mod convtrans2d;
-#[cfg(feature = "nightly")]
 pub use convtrans2d::TryConvTrans2D;
 
-#[cfg(feature = "nightly")]
+mod pool1d;
+pub use pool1d::TryPool1D;
+
 mod pool2d;
-#[cfg(feature = "nightly")]
 pub use pool2d::{Pool2DKind, TryPool2D};
+
+mod pool3d;
+pub use pool3d::TryPool3D;
+
+mod adaptive_pool2d;
+pub use adaptive_pool2d::TryAdaptivePool2D;
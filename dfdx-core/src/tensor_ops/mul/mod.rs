@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::*;
+use super::{ops::*, Device};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -87,6 +87,43 @@ where
         self.try_mul(rhs).unwrap()
     }
 }
+
+impl KernelOpInfo for BinaryMulKernelOp {
+    const NAME: &'static str = "BinaryMul";
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for BinaryMulKernelOp {
+    fn try_grads<S: Shape, D: Device<E>>(
+        &self,
+        lhs: &Tensor<S, E, D>,
+        rhs: &Tensor<S, E, D>,
+        grad_out: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<(Tensor<S, E, D, OwnedTape<E, D>>, Tensor<S, E, D, OwnedTape<E, D>>), Error> {
+        let grad_lhs = grad_out.with_empty_tape().try_mul(rhs.retaped::<OwnedTape<E, D>>())?;
+        let grad_rhs = grad_out.try_mul(lhs.retaped::<OwnedTape<E, D>>())?;
+        Ok((grad_lhs, grad_rhs))
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for ScalarMulKernelOp<E> {
+    const NAME: &'static str = "ScalarMul";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("scalar", Attr::Float(self.scalar.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ScalarMulKernelOp<E> {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        grad_out.try_mul(self.scalar.to_f64().unwrap())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -44,6 +44,16 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<NansToKernelOp<E>, E>, T: Tape<E, D>> Te
     }
 }
 
+impl<E: Dtype> KernelOpInfo for NansToKernelOp<E> {
+    const NAME: &'static str = "NansTo";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("value", Attr::Float(self.0.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for NansToKernelOp<E> {}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
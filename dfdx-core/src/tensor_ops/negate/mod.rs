@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::Device;
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -47,6 +48,21 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<NegateKernelOp, E>, T: Tape<E, D>> std::
     }
 }
 
+impl KernelOpInfo for NegateKernelOp {
+    const NAME: &'static str = "Negate";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for NegateKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        grad_out.try_negate()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -1,6 +1,6 @@
 use crate::{
     shapes::{Axes, Dtype, ReduceShape, Shape},
-    tensor::{Error, Tape, Tensor},
+    tensor::{Error, Tape, Tensor, WithEmptyTape},
 };
 
 use super::{BroadcastTo, Device, MeanTo, TryAdd, TryDiv, TrySub};
@@ -37,10 +37,10 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Tensor<S, E, D, T> {
         S: ReduceShape<Ax>,
     {
         let shape = self.shape;
-        let mean = self.retaped::<T>().try_mean::<_, Ax>()?;
+        let mean = self.with_empty_tape().try_mean::<_, Ax>()?;
         let centered = self.try_sub(mean.try_broadcast_like(&shape)?)?;
         let std = centered
-            .retaped::<T>()
+            .with_empty_tape()
             .try_square()?
             .try_mean::<_, Ax>()?
             .try_add(epsilon)?
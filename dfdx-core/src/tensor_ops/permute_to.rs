@@ -40,14 +40,23 @@ impl<S: Shape, E, D: Storage<E>, T: Tape<E, D>> PermuteTo for Tensor<S, E, D, T>
     where
         Self::Shape: PermuteShapeTo<Dst, Ax>,
     {
-        Ok(Tensor {
+        let node = self
+            .tape
+            .captures_ops()
+            .then(|| OpNode::new("Permute").axes::<Ax>().view_input(&self));
+        let mut out = Tensor {
             id: self.id,
             data: self.data,
             shape: self.shape.permuted(),
             strides: self.shape.permute_strides(self.strides),
             device: self.device,
             tape: self.tape,
-        })
+        };
+        if let Some(node) = node {
+            let node = node.output(&out);
+            out.tape.add_op_node(node);
+        }
+        Ok(out)
     }
 }
 
@@ -183,4 +192,45 @@ mod tests {
         x.clone().permute::<_, Axes4<3, 2, 0, 1>>();
         x.permute::<_, Axes4<3, 2, 1, 0>>();
     }
+
+    #[test]
+    fn test_permute_7d() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank7<2, 3, 1, 4, 2, 1, 3>, TestDtype, _> = dev.sample_normal();
+        let r = t.clone().permute::<_, Axes7<6, 0, 3, 1, 5, 4, 2>>();
+        assert_eq!(
+            r.shape(),
+            &(Const::<3>, Const::<2>, Const::<4>, Const::<3>, Const::<1>, Const::<2>, Const::<1>)
+        );
+        let t_vec = t.as_vec();
+        let r_vec = r.as_vec();
+        let t_strides = t.shape().strides();
+        let r_strides = r.shape().strides();
+        let mut idx = [0; 7];
+        for (i, v) in t_vec.iter().enumerate() {
+            for (d, stride) in t_strides.iter().enumerate() {
+                idx[d] = (i / stride) % t.shape().concrete()[d];
+            }
+            let j = [6, 0, 3, 1, 5, 4, 2]
+                .iter()
+                .zip(r_strides.iter())
+                .map(|(&src, stride)| idx[src] * stride)
+                .sum::<usize>();
+            assert_eq!(r_vec[j], *v);
+        }
+    }
+
+    #[test]
+    fn test_permute_8d_backwards() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank8<2, 1, 3, 2, 1, 2, 3, 2>, TestDtype, _> = dev.sample_normal();
+        let g1 = t.leaky_trace().exp().sum().backward();
+        let g2 = t
+            .leaky_trace()
+            .permute::<_, Axes8<7, 6, 5, 4, 3, 2, 1, 0>>()
+            .exp()
+            .sum()
+            .backward();
+        assert_eq!(g1.get(&t).as_vec(), g2.get(&t).as_vec());
+    }
 }
@@ -0,0 +1,339 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+use super::{Pool2DKind, ReshapeTo};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Pool1DOp {
+    pub kind: Pool2DKind,
+    pub kernel: usize,
+    pub stride: usize,
+    pub padding: usize,
+    pub dilation: usize,
+    pub batch: usize,
+    pub chan: usize,
+    pub l_in: usize,
+    pub l_out: usize,
+}
+
+pub(super) trait Pool1DKernel<E: Dtype>: Storage<E> {
+    fn alloc<S: Shape>(&self, s: S) -> Result<Tensor<S, E, Self>, Error>;
+
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Pool1DOp,
+        inp: &Tensor<I, E, Self>,
+        out: &mut Tensor<O, E, Self>,
+    ) -> Result<(), Error>;
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Pool1DOp,
+        inp: &Tensor<I, E, Self>,
+        grad_inp: &mut Self::Vec,
+        out: &Tensor<O, E, Self>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+/// Pools sequences (2d) and batches of sequences (3d) along their last axis with a 1d kernel.
+/// The kinds of pooling are the same as [super::TryPool2D], see [Pool2DKind].
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank3<2, 3, 10>, f32, _> = dev.sample_normal();
+/// let y = x.pool1d(
+///     Pool2DKind::Max,
+///     Const::<2>, // kernel
+///     Const::<2>, // stride
+///     Const::<0>, // padding
+///     Const::<1>, // dilation
+/// );
+/// assert_eq!(y.shape().concrete(), [2, 3, 5]);
+/// ```
+pub trait TryPool1D<Kernel, Stride, Padding, Dilation>: Sized {
+    type Pooled;
+
+    fn pool1d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Self::Pooled {
+        self.try_pool1d(kind, kernel, stride, padding, dilation)
+            .unwrap()
+    }
+
+    fn try_pool1d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error>;
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const KERNEL: usize,
+        const STRIDE: usize,
+        const PADDING: usize,
+        const DILATION: usize,
+        const DIM: usize,
+    > TryPool1D<Const<KERNEL>, Const<STRIDE>, Const<PADDING>, Const<DILATION>> for Const<DIM>
+where
+    Const<{ (DIM + 2 * PADDING - DILATION * (KERNEL - 1) - 1) / STRIDE + 1 }>: Sized,
+{
+    type Pooled = Const<{ (DIM + 2 * PADDING - DILATION * (KERNEL - 1) - 1) / STRIDE + 1 }>;
+    fn try_pool1d(
+        self,
+        _: Pool2DKind,
+        _: Const<KERNEL>,
+        _: Const<STRIDE>,
+        _: Const<PADDING>,
+        _: Const<DILATION>,
+    ) -> Result<Self::Pooled, Error> {
+        Ok(Const)
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<const DIM: usize, Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim>
+    TryPool1D<Kernel, Stride, Padding, Dilation> for Const<DIM>
+{
+    type Pooled = usize;
+    fn try_pool1d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        DIM.try_pool1d(kind, kernel, stride, padding, dilation)
+    }
+}
+
+impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim>
+    TryPool1D<Kernel, Stride, Padding, Dilation> for usize
+{
+    type Pooled = usize;
+    fn try_pool1d(
+        self,
+        _: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        Ok((self + 2 * padding.size() - 1)
+            .checked_sub(dilation.size() * (kernel.size() - 1))
+            .unwrap()
+            / stride.size()
+            + 1)
+    }
+}
+
+impl<Chan, Kernel, Stride, Padding, Dilation, L, E, D, T>
+    TryPool1D<Kernel, Stride, Padding, Dilation> for Tensor<(Chan, L), E, D, T>
+where
+    Chan: Dim,
+    Kernel: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    L: Dim + TryPool1D<Kernel, Stride, Padding, Dilation>,
+    L::Pooled: Dim,
+    E: Dtype,
+    D: Pool1DKernel<E> + crate::tensor_ops::reshape_to::ReshapeKernel<E>,
+    T: Tape<E, D>,
+{
+    type Pooled = Tensor<(Chan, L::Pooled), E, D, T>;
+
+    fn try_pool1d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        let (chan, l) = self.shape;
+        let seq = self.try_reshape_like(&(Const::<1>, chan, l))?;
+        let out = seq.try_pool1d(kind, kernel, stride, padding, dilation)?;
+        let (_, _, out_l) = out.shape;
+        out.try_reshape_like(&(chan, out_l))
+    }
+}
+
+impl<Chan, Kernel, Stride, Padding, Dilation, Batch, L, E, D, T>
+    TryPool1D<Kernel, Stride, Padding, Dilation> for Tensor<(Batch, Chan, L), E, D, T>
+where
+    Chan: Dim,
+    Kernel: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    Batch: Dim,
+    L: Dim + TryPool1D<Kernel, Stride, Padding, Dilation>,
+    L::Pooled: Dim,
+    E: Dtype,
+    D: Pool1DKernel<E>,
+    T: Tape<E, D>,
+{
+    type Pooled = Tensor<(Batch, Chan, L::Pooled), E, D, T>;
+
+    fn try_pool1d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        let (batch, chan, l) = self.shape;
+        if self.strides != self.shape.strides() {
+            panic!("Input to pool1d must be contiguous");
+        }
+        let l_out = l.pool1d(kind, kernel, stride, padding, dilation);
+        let op = Pool1DOp {
+            kind,
+            stride: stride.size(),
+            padding: padding.size(),
+            kernel: kernel.size(),
+            dilation: dilation.size(),
+            batch: batch.size(),
+            chan: chan.size(),
+            l_in: l.size(),
+            l_out: l_out.size(),
+        };
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.alloc((batch, chan, l_out))?;
+        inp.device.forward(op, &inp, &mut out)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Pool1D")
+                .attr("kind", Attr::String(format!("{kind:?}")))
+                .attr("kernel", Attr::Int(op.kernel as i64))
+                .attr("stride", Attr::Int(op.stride as i64))
+                .attr("padding", Attr::Int(op.padding as i64))
+                .attr("dilation", Attr::Int(op.dilation as i64))
+                .input(&inp)
+                .output(&out);
+            tape.add_op_node(node.flops(op.kernel * out.shape.num_elements()));
+        }
+        let inp_ghost = inp.ghost();
+        let out_ghost = out.ghost();
+        let out_clone = out.clone();
+        tape.add_backward_op(move |grads| {
+            grads.try_alloc_for(&inp_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
+            inp.device
+                .backward(op, &inp, grad_inp, &out_clone, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_pool1d_2d_max_eq_grads() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[1.0, 1., 0.5, 0.2], [0.2, 0.2, 0.5, 1.2]])
+            .to_dtype::<TestDtype>();
+        let r = x
+            .leaky_trace()
+            .pool1d(
+                Pool2DKind::Max,
+                Const::<2>,
+                Const::<1>,
+                Const::<0>,
+                Const::<1>,
+            )
+            .realize::<Rank2<2, 3>>();
+        assert_close_to_literal!(r, [[1., 1., 0.5], [0.2, 0.5, 1.2]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&x), [[1., 2., 1., 0.], [1., 1., 1., 1.]]);
+    }
+
+    #[test]
+    fn test_pool1d_3d_min() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[[0.3, -1.0, 2.0, 0.5, -0.2]], [[1.5, 0.1, -0.7, -0.7, 3.0]]])
+            .to_dtype::<TestDtype>();
+        let r = x
+            .leaky_trace()
+            .pool1d(
+                Pool2DKind::Min,
+                Const::<3>,
+                Const::<2>,
+                Const::<1>,
+                Const::<1>,
+            )
+            .realize::<Rank3<2, 1, 3>>();
+        assert_close_to_literal!(r, [[[-1.0, -1.0, -0.2]], [[0.1, -0.7, -0.7]]]);
+        let g = r.mean().backward();
+        let v = 1.0 / 6.0;
+        assert_close_to_literal!(
+            g.get(&x),
+            [[[0.0, 2.0 * v, 0.0, 0.0, v]], [[0.0, v, v, 2.0 * v, 0.0]]]
+        );
+    }
+
+    #[test]
+    fn test_pool1d_3d_avg_padded() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[[1.0, 2.0, 3.0, 4.0], [-1.0, 0.5, 0.0, 2.5]]])
+            .to_dtype::<TestDtype>();
+        let r = x
+            .leaky_trace()
+            .pool1d(
+                Pool2DKind::Avg,
+                Const::<2>,
+                Const::<2>,
+                Const::<1>,
+                Const::<1>,
+            )
+            .realize::<Rank3<1, 2, 3>>();
+        assert_close_to_literal!(r, [[[0.5, 2.5, 2.0], [-0.5, 0.25, 1.25]]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&x), [[[0.5; 4], [0.5; 4]]]);
+    }
+
+    #[test]
+    fn test_pool1d_dilated() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[0., 5., 1., 4., 2., 3.]])
+            .to_dtype::<TestDtype>();
+        let r = x
+            .leaky_trace()
+            .pool1d(
+                Pool2DKind::Max,
+                Const::<2>,
+                Const::<1>,
+                Const::<0>,
+                Const::<2>,
+            )
+            .realize::<Rank2<1, 4>>();
+        assert_close_to_literal!(r, [[1., 5., 2., 4.]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&x), [[0., 1., 1., 1., 1., 0.]]);
+    }
+}
@@ -0,0 +1,165 @@
+#include "cuda_utils.cuh"
+
+enum Pool1dKind {
+    AVG,
+    MIN,
+    MAX,
+};
+
+struct Pool1dOp {
+    Pool1dKind kind;
+    size_t kernel;
+    size_t stride;
+    size_t padding;
+    size_t dilation;
+    size_t batch;
+    size_t chan;
+    size_t l_in;
+    size_t l_out;
+};
+
+__device__ double init(const Pool1dOp op) {
+    switch(op.kind) {
+        case AVG:
+            return 0.0;
+        case MIN:
+            return INFINITY;
+        case MAX:
+            return -INFINITY;
+    }
+}
+
+template<typename T>
+__device__ T accum(const Pool1dOp op, const T accum, const T item) {
+    switch(op.kind) {
+        case AVG:
+            return accum + item;
+        case MIN:
+            return ming(accum, item);
+        case MAX:
+            return maxg(accum, item);
+    }
+}
+
+template<typename T>
+__device__ T normalize(const Pool1dOp op, const T item, const size_t num_elements) {
+    double num_f64 = num_elements;
+    double scale_f64 = 1.0 / num_f64;
+    T scale = scale_f64;
+    switch(op.kind) {
+        case AVG:
+            return item * scale;
+        case MIN:
+            return item;
+        case MAX:
+            return item;
+    }
+}
+
+template<typename T>
+__device__ T filter(const Pool1dOp op, const T item, const T needle, const T haystack) {
+    T zero = 0.0;
+    switch(op.kind){
+        case AVG:
+            return item;
+        case MIN:
+            return (needle == haystack) ? item : zero;
+        case MAX:
+            return (needle == haystack) ? item : zero;
+    }
+}
+
+template<typename T>
+__device__ void pool1d_fwd(
+    const Pool1dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const T *inp, // 3d (Batch, Channels, Length)
+    T *out // 3d (Batch, Channels, LengthOut)
+) {
+    const size_t numel = op.batch * op.chan * op.l_out;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t ol = idx % op.l_out;
+        idx /= op.l_out;
+        const size_t c = idx % op.chan;
+        idx /= op.chan;
+        const size_t b = idx % op.batch;
+
+        T tmp = init(op);
+        for(size_t k = 0; k < op.kernel; k++) {
+            const size_t l_plus_p = ol * op.stride + op.dilation * k;
+            if (l_plus_p < op.padding) { continue; }
+            const size_t l = l_plus_p - op.padding;
+            if (l >= op.l_in) { continue; }
+
+            auto inp_i = b * inp_strides[0] + c * inp_strides[1] + l * inp_strides[2];
+            tmp = accum(op, tmp, inp[inp_i]);
+        }
+
+        out[i] = normalize(op, tmp, op.kernel);
+    }
+}
+
+template<typename T>
+__device__ void pool1d_bwd(
+    const Pool1dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const T *inp, // 3d (Batch, Channels, Length)
+    T *grad_inp,
+    const T *out, // 3d (Batch, Channels, LengthOut)
+    const T *grad_out
+) {
+    const size_t numel = op.batch * op.chan * op.l_in;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t l = idx % op.l_in;
+        idx /= op.l_in;
+        const size_t c = idx % op.chan;
+        idx /= op.chan;
+        const size_t b = idx % op.batch;
+
+        const T inp_v = inp[i];
+
+        T tmp = 0.0;
+        for(size_t k = 0; k < op.kernel; k++) {
+            size_t ol = l + op.padding;
+            if (ol < op.dilation * k) { continue; }
+            ol -= op.dilation * k;
+            if (ol % op.stride != 0) { continue; }
+            ol /= op.stride;
+            if (ol >= op.l_out) { continue; }
+
+            auto out_i = b * out_strides[0] + c * out_strides[1] + ol * out_strides[2];
+            tmp += filter(op, grad_out[out_i], out[out_i], inp_v);
+        }
+        grad_inp[i] += normalize(op, tmp, op.kernel);
+    }
+}
+
+#define POOL_OP(TYPENAME, fwd, bwd) \
+extern "C" __global__ void fwd( \
+    const Pool1dOp op, \
+    const size_t *inp_strides, \
+    const size_t *out_strides, \
+    const TYPENAME *inp, \
+    TYPENAME *out \
+) { \
+    pool1d_fwd(op, inp_strides, out_strides, inp, out); \
+} \
+extern "C" __global__ void bwd( \
+    const Pool1dOp op, \
+    const size_t *inp_strides, \
+    const size_t *out_strides, \
+    const TYPENAME *inp, \
+    TYPENAME *grad_inp, \
+    const TYPENAME *out, \
+    const TYPENAME *grad_out \
+) { \
+    pool1d_bwd(op, inp_strides, out_strides, inp, grad_inp, out, grad_out); \
+}
+
+POOL_OP(__half, pool1d_fwd_f16, pool1d_bwd_f16);
+POOL_OP(float, pool1d_fwd_f32, pool1d_bwd_f32);
+POOL_OP(double, pool1d_fwd_f64, pool1d_bwd_f64);
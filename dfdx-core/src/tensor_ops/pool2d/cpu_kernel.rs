@@ -13,7 +13,7 @@ fn make_4d<S: Shape>(strides: S::Concrete) -> [usize; 4] {
 }
 
 impl super::Pool2DKind {
-    fn init<E: Float>(&self) -> E {
+    pub(in crate::tensor_ops) fn init<E: Float>(&self) -> E {
         match self {
             super::Pool2DKind::Avg => E::zero(),
             super::Pool2DKind::Min => E::infinity(),
@@ -21,7 +21,7 @@ impl super::Pool2DKind {
         }
     }
 
-    fn accum<E: Float>(&self, accum: &E, item: &E) -> E {
+    pub(in crate::tensor_ops) fn accum<E: Float>(&self, accum: &E, item: &E) -> E {
         match self {
             super::Pool2DKind::Avg => *accum + *item,
             super::Pool2DKind::Min => accum.min(*item),
@@ -29,7 +29,11 @@ impl super::Pool2DKind {
         }
     }
 
-    fn normalize<E: Float + FromPrimitive>(&self, item: E, num_elements: usize) -> E {
+    pub(in crate::tensor_ops) fn normalize<E: Float + FromPrimitive>(
+        &self,
+        item: E,
+        num_elements: usize,
+    ) -> E {
         match self {
             super::Pool2DKind::Avg => item * E::from_f64(1.0 / num_elements as f64).unwrap(),
             super::Pool2DKind::Min => item,
@@ -37,7 +41,7 @@ impl super::Pool2DKind {
         }
     }
 
-    fn filter<E: Float>(&self, item: E, needle: E, haystack: E) -> E {
+    pub(in crate::tensor_ops) fn filter<E: Float>(&self, item: E, needle: E, haystack: E) -> E {
         match self {
             super::Pool2DKind::Avg => item,
             super::Pool2DKind::Min => {
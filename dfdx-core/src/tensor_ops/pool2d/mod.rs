@@ -77,6 +77,7 @@ pub trait TryPool2D<Kernel, Stride, Padding, Dilation>: Sized {
     ) -> Result<Self::Pooled, Error>;
 }
 
+#[cfg(feature = "nightly")]
 impl<
         const KERNEL: usize,
         const STRIDE: usize,
@@ -100,6 +101,23 @@ where
     }
 }
 
+#[cfg(not(feature = "nightly"))]
+impl<const DIM: usize, Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim>
+    TryPool2D<Kernel, Stride, Padding, Dilation> for Const<DIM>
+{
+    type Pooled = usize;
+    fn try_pool2d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        DIM.try_pool2d(kind, kernel, stride, padding, dilation)
+    }
+}
+
 impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim>
     TryPool2D<Kernel, Stride, Padding, Dilation> for usize
 {
@@ -203,6 +221,18 @@ where
         let (img, mut tape) = self.split_tape();
         let mut out = img.device.alloc((batch, chan, h_out, w_out))?;
         img.device.forward(op, &img, &mut out)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Pool2D")
+                .attr("kind", Attr::String(format!("{kind:?}")))
+                .attr("kernel", Attr::Int(op.kernel as i64))
+                .attr("stride", Attr::Int(op.stride as i64))
+                .attr("padding", Attr::Int(op.padding as i64))
+                .attr("dilation", Attr::Int(op.dilation as i64))
+                .input(&img)
+                .output(&out);
+            let flops_per_out = op.kernel * op.kernel;
+            tape.add_op_node(node.flops(flops_per_out * out.shape.num_elements()));
+        }
         let img_ghost = img.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
@@ -228,13 +258,16 @@ mod tests {
         let x = dev
             .tensor([[[1.0, 1., 0.5, 0.2], [0.2, 0.2, 0.5, 1.2]]])
             .to_dtype::<TestDtype>();
-        let r = x.leaky_trace().pool2d(
-            Pool2DKind::Max,
-            Const::<2>,
-            Const::<1>,
-            Const::<0>,
-            Const::<1>,
-        );
+        let r = x
+            .leaky_trace()
+            .pool2d(
+                Pool2DKind::Max,
+                Const::<2>,
+                Const::<1>,
+                Const::<0>,
+                Const::<1>,
+            )
+            .realize::<Rank3<1, 1, 3>>();
         assert_close_to_literal!(r, [[[1., 1., 1.2]]]);
         let g = r.sum().backward();
         assert_close_to_literal!(g.get(&x), [[[1., 2., 0., 0.], [0., 0., 0., 1.]]]);
@@ -246,13 +279,16 @@ mod tests {
         let x = dev
             .tensor([[[1., 1., 0.5, 0.2], [0.2, 0.2, 0.5, 1.2]]])
             .to_dtype::<TestDtype>();
-        let r = x.leaky_trace().pool2d(
-            Pool2DKind::Min,
-            Const::<2>,
-            Const::<1>,
-            Const::<0>,
-            Const::<1>,
-        );
+        let r = x
+            .leaky_trace()
+            .pool2d(
+                Pool2DKind::Min,
+                Const::<2>,
+                Const::<1>,
+                Const::<0>,
+                Const::<1>,
+            )
+            .realize::<Rank3<1, 1, 3>>();
         assert_close_to_literal!(r, [[[0.2, 0.2, 0.2]]]);
         let g = r.sum().backward();
         assert_close_to_literal!(g.get(&x), [[[0., 0., 0., 1.], [1., 2., 0., 0.]]]);
@@ -262,13 +298,16 @@ mod tests {
     fn test_pool2d_3d_max2d() {
         let dev = TestDevice::seed_from_u64(234);
         let x: Tensor<Rank3<2, 3, 4>, TestDtype, _> = dev.sample_normal();
-        let r = x.leaky_trace().pool2d(
-            Pool2DKind::Max,
-            Const::<2>,
-            Const::<2>,
-            Const::<0>,
-            Const::<1>,
-        );
+        let r = x
+            .leaky_trace()
+            .pool2d(
+                Pool2DKind::Max,
+                Const::<2>,
+                Const::<2>,
+                Const::<0>,
+                Const::<1>,
+            )
+            .realize::<Rank3<2, 1, 2>>();
         assert_close_to_literal!(r, [[[1.79155397, 1.10126066]], [[1.14464748, 2.26301837]]]);
         let g = r.exp().mean().backward();
         #[rustfmt::skip]
@@ -285,13 +324,16 @@ mod tests {
     fn test_pool2d_3d_min2d() {
         let dev = TestDevice::seed_from_u64(234);
         let x: Tensor<Rank3<2, 3, 4>, TestDtype, _> = dev.sample_normal();
-        let r = x.leaky_trace().pool2d(
-            Pool2DKind::Min,
-            Const::<2>,
-            Const::<2>,
-            Const::<0>,
-            Const::<1>,
-        );
+        let r = x
+            .leaky_trace()
+            .pool2d(
+                Pool2DKind::Min,
+                Const::<2>,
+                Const::<2>,
+                Const::<0>,
+                Const::<1>,
+            )
+            .realize::<Rank3<2, 1, 2>>();
         assert_close_to_literal!(
             r,
             [[[-1.09635627, -1.07717276]], [[-0.01996479, -1.82562149]]]
@@ -311,13 +353,16 @@ mod tests {
     fn test_pool2d_4d_avg2d() {
         let dev = TestDevice::seed_from_u64(234);
         let x: Tensor<Rank4<2, 4, 2, 2>, TestDtype, _> = dev.sample_normal();
-        let r = x.leaky_trace().pool2d(
-            Pool2DKind::Avg,
-            Const::<1>,
-            Const::<2>,
-            Const::<0>,
-            Const::<1>,
-        );
+        let r = x
+            .leaky_trace()
+            .pool2d(
+                Pool2DKind::Avg,
+                Const::<1>,
+                Const::<2>,
+                Const::<0>,
+                Const::<1>,
+            )
+            .realize::<Rank4<2, 4, 1, 1>>();
         assert_close_to_literal!(
             r,
             [
@@ -347,21 +392,27 @@ mod tests {
                 [16., 17., 18., 19., 20.],
             ]])
             .to_dtype::<TestDtype>();
-        let y_max = x.leaky_trace().pool2d(
-            Pool2DKind::Max,
-            Const::<2>,
-            Const::<1>,
-            Const::<0>,
-            Const::<2>,
-        );
+        let y_max = x
+            .leaky_trace()
+            .pool2d(
+                Pool2DKind::Max,
+                Const::<2>,
+                Const::<1>,
+                Const::<0>,
+                Const::<2>,
+            )
+            .realize::<Rank3<1, 2, 3>>();
         assert_close_to_literal!(y_max, [[[13., 14., 15.], [18., 19., 20.]]]);
-        let y_min = x.clone().pool2d(
-            Pool2DKind::Min,
-            Const::<2>,
-            Const::<1>,
-            Const::<0>,
-            Const::<2>,
-        );
+        let y_min = x
+            .clone()
+            .pool2d(
+                Pool2DKind::Min,
+                Const::<2>,
+                Const::<1>,
+                Const::<0>,
+                Const::<2>,
+            )
+            .realize::<Rank3<1, 2, 3>>();
         assert_close_to_literal!(y_min, [[[0., 1., 2.], [6., 7., 8.]]]);
 
         let grads = y_max.mean().backward();
@@ -0,0 +1,90 @@
+use crate::{
+    shapes::*,
+    tensor::{cpu::NdIndex, *},
+};
+
+use std::sync::Arc;
+
+use num_traits::Float;
+
+fn make_5d<S: Shape>(strides: S::Concrete) -> [usize; 5] {
+    match S::NUM_DIMS {
+        4 => [0, strides[0], strides[1], strides[2], strides[3]],
+        5 => [strides[0], strides[1], strides[2], strides[3], strides[4]],
+        _ => panic!("Only implemented for 4d & 5d arrays"),
+    }
+}
+
+impl super::Pool3DOp {
+    /// The input index at kernel position `k` of output index `o`, if it isn't padding.
+    #[inline(always)]
+    fn inp_idx(&self, o: usize, k: usize, dim_in: usize) -> Option<usize> {
+        (o * self.stride + self.dilation * k)
+            .checked_sub(self.padding)
+            .filter(|&i| i < dim_in)
+    }
+}
+
+impl<E: Float + Dtype> super::Pool3DKernel<E> for Cpu {
+    fn alloc<S: Shape>(&self, s: S) -> Result<Tensor<S, E, Self>, Error> {
+        self.try_zeros_like(&s)
+    }
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool3DOp,
+        inp: &Tensor<I, E, Self>,
+        out: &mut Tensor<O, E, Self>,
+    ) -> Result<(), Error> {
+        let istr = make_5d::<I>(inp.strides);
+        let ostr = make_5d::<O>(out.strides);
+        let kernel_numel = op.kernel * op.kernel * op.kernel;
+
+        let buf = inp.data.as_ref();
+        let out_buf = Arc::make_mut(&mut out.data);
+        let out_shape = [op.batch, op.chan, op.d_out, op.h_out, op.w_out];
+        let mut out_idx = NdIndex::new(out_shape, ostr);
+        while let Some((o, [b, c, od, oh, ow])) = out_idx.next_with_idx() {
+            let mut tmp = op.kind.init();
+            for z in (0..op.kernel).filter_map(|k| op.inp_idx(od, k, op.d_in)) {
+                for y in (0..op.kernel).filter_map(|k| op.inp_idx(oh, k, op.h_in)) {
+                    for x in (0..op.kernel).filter_map(|k| op.inp_idx(ow, k, op.w_in)) {
+                        let i = b * istr[0] + c * istr[1] + z * istr[2] + y * istr[3] + x * istr[4];
+                        tmp = op.kind.accum(&tmp, &buf[i]);
+                    }
+                }
+            }
+            out_buf[o] = op.kind.normalize(tmp, kernel_numel);
+        }
+        Ok(())
+    }
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: super::Pool3DOp,
+        inp: &Tensor<I, E, Self>,
+        grad_inp: &mut Self::Vec,
+        out: &Tensor<O, E, Self>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error> {
+        let istr = make_5d::<I>(inp.strides);
+        let ostr = make_5d::<O>(out.strides);
+        let kernel_numel = op.kernel * op.kernel * op.kernel;
+
+        let inp_buf = inp.data.as_ref();
+        let out_buf = out.data.as_ref();
+        let out_shape = [op.batch, op.chan, op.d_out, op.h_out, op.w_out];
+        let mut out_idx = NdIndex::new(out_shape, ostr);
+        while let Some((o, [b, c, od, oh, ow])) = out_idx.next_with_idx() {
+            let go = op.kind.normalize(grad_out[o], kernel_numel);
+            let vo = out_buf[o];
+            for z in (0..op.kernel).filter_map(|k| op.inp_idx(od, k, op.d_in)) {
+                for y in (0..op.kernel).filter_map(|k| op.inp_idx(oh, k, op.h_in)) {
+                    for x in (0..op.kernel).filter_map(|k| op.inp_idx(ow, k, op.w_in)) {
+                        let i = b * istr[0] + c * istr[1] + z * istr[2] + y * istr[3] + x * istr[4];
+                        grad_inp[i] += op.kind.filter(go, inp_buf[i], vo);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
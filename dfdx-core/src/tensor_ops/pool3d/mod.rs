@@ -0,0 +1,329 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+use crate::{shapes::*, tensor::*};
+
+use super::{Pool2DKind, ReshapeTo};
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(super) struct Pool3DOp {
+    pub kind: Pool2DKind,
+    pub kernel: usize,
+    pub stride: usize,
+    pub padding: usize,
+    pub dilation: usize,
+    pub batch: usize,
+    pub chan: usize,
+    pub d_in: usize,
+    pub d_out: usize,
+    pub h_in: usize,
+    pub h_out: usize,
+    pub w_in: usize,
+    pub w_out: usize,
+}
+
+pub(super) trait Pool3DKernel<E: Dtype>: Storage<E> {
+    fn alloc<S: Shape>(&self, s: S) -> Result<Tensor<S, E, Self>, Error>;
+
+    fn forward<I: Shape, O: Shape>(
+        &self,
+        op: Pool3DOp,
+        inp: &Tensor<I, E, Self>,
+        out: &mut Tensor<O, E, Self>,
+    ) -> Result<(), Error>;
+
+    fn backward<I: Shape, O: Shape>(
+        &self,
+        op: Pool3DOp,
+        inp: &Tensor<I, E, Self>,
+        grad_inp: &mut Self::Vec,
+        out: &Tensor<O, E, Self>,
+        grad_out: &Self::Vec,
+    ) -> Result<(), Error>;
+}
+
+/// Pools volumes (4d) and batches of volumes (5d) along their last three axes with a cubic kernel.
+/// The kinds of pooling are the same as [super::TryPool2D], see [Pool2DKind].
+///
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank5<2, 3, 4, 6, 8>, f32, _> = dev.sample_normal();
+/// let y = x.pool3d(
+///     Pool2DKind::Max,
+///     Const::<2>, // kernel
+///     Const::<2>, // stride
+///     Const::<0>, // padding
+///     Const::<1>, // dilation
+/// );
+/// assert_eq!(y.shape().concrete(), [2, 3, 2, 3, 4]);
+/// ```
+pub trait TryPool3D<Kernel, Stride, Padding, Dilation>: Sized {
+    type Pooled;
+
+    fn pool3d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Self::Pooled {
+        self.try_pool3d(kind, kernel, stride, padding, dilation)
+            .unwrap()
+    }
+
+    fn try_pool3d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error>;
+}
+
+#[cfg(feature = "nightly")]
+impl<
+        const KERNEL: usize,
+        const STRIDE: usize,
+        const PADDING: usize,
+        const DILATION: usize,
+        const DIM: usize,
+    > TryPool3D<Const<KERNEL>, Const<STRIDE>, Const<PADDING>, Const<DILATION>> for Const<DIM>
+where
+    Const<{ (DIM + 2 * PADDING - DILATION * (KERNEL - 1) - 1) / STRIDE + 1 }>: Sized,
+{
+    type Pooled = Const<{ (DIM + 2 * PADDING - DILATION * (KERNEL - 1) - 1) / STRIDE + 1 }>;
+    fn try_pool3d(
+        self,
+        _: Pool2DKind,
+        _: Const<KERNEL>,
+        _: Const<STRIDE>,
+        _: Const<PADDING>,
+        _: Const<DILATION>,
+    ) -> Result<Self::Pooled, Error> {
+        Ok(Const)
+    }
+}
+
+#[cfg(not(feature = "nightly"))]
+impl<const DIM: usize, Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim>
+    TryPool3D<Kernel, Stride, Padding, Dilation> for Const<DIM>
+{
+    type Pooled = usize;
+    fn try_pool3d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        DIM.try_pool3d(kind, kernel, stride, padding, dilation)
+    }
+}
+
+impl<Kernel: Dim, Stride: Dim, Padding: Dim, Dilation: Dim>
+    TryPool3D<Kernel, Stride, Padding, Dilation> for usize
+{
+    type Pooled = usize;
+    fn try_pool3d(
+        self,
+        _: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        Ok((self + 2 * padding.size() - 1)
+            .checked_sub(dilation.size() * (kernel.size() - 1))
+            .unwrap()
+            / stride.size()
+            + 1)
+    }
+}
+
+impl<Chan, Kernel, Stride, Padding, Dilation, Z, H, W, E, D, T>
+    TryPool3D<Kernel, Stride, Padding, Dilation> for Tensor<(Chan, Z, H, W), E, D, T>
+where
+    Chan: Dim,
+    Kernel: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    Z: Dim + TryPool3D<Kernel, Stride, Padding, Dilation>,
+    Z::Pooled: Dim,
+    H: Dim + TryPool3D<Kernel, Stride, Padding, Dilation>,
+    H::Pooled: Dim,
+    W: Dim + TryPool3D<Kernel, Stride, Padding, Dilation>,
+    W::Pooled: Dim,
+    E: Dtype,
+    D: Pool3DKernel<E> + crate::tensor_ops::reshape_to::ReshapeKernel<E>,
+    T: Tape<E, D>,
+{
+    type Pooled = Tensor<(Chan, Z::Pooled, H::Pooled, W::Pooled), E, D, T>;
+
+    fn try_pool3d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        let (chan, z, h, w) = self.shape;
+        let vol = self.try_reshape_like(&(Const::<1>, chan, z, h, w))?;
+        let out = vol.try_pool3d(kind, kernel, stride, padding, dilation)?;
+        let (_, _, out_z, out_h, out_w) = out.shape;
+        out.try_reshape_like(&(chan, out_z, out_h, out_w))
+    }
+}
+
+impl<Chan, Kernel, Stride, Padding, Dilation, Batch, Z, H, W, E, D, T>
+    TryPool3D<Kernel, Stride, Padding, Dilation> for Tensor<(Batch, Chan, Z, H, W), E, D, T>
+where
+    Chan: Dim,
+    Kernel: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    Batch: Dim,
+    Z: Dim + TryPool3D<Kernel, Stride, Padding, Dilation>,
+    Z::Pooled: Dim,
+    H: Dim + TryPool3D<Kernel, Stride, Padding, Dilation>,
+    H::Pooled: Dim,
+    W: Dim + TryPool3D<Kernel, Stride, Padding, Dilation>,
+    W::Pooled: Dim,
+    E: Dtype,
+    D: Pool3DKernel<E>,
+    T: Tape<E, D>,
+{
+    type Pooled = Tensor<(Batch, Chan, Z::Pooled, H::Pooled, W::Pooled), E, D, T>;
+
+    fn try_pool3d(
+        self,
+        kind: Pool2DKind,
+        kernel: Kernel,
+        stride: Stride,
+        padding: Padding,
+        dilation: Dilation,
+    ) -> Result<Self::Pooled, Error> {
+        let (batch, chan, z, h, w) = self.shape;
+        if self.strides != self.shape.strides() {
+            panic!("Input to pool3d must be contiguous");
+        }
+        let z_out = z.pool3d(kind, kernel, stride, padding, dilation);
+        let h_out = h.pool3d(kind, kernel, stride, padding, dilation);
+        let w_out = w.pool3d(kind, kernel, stride, padding, dilation);
+        let op = Pool3DOp {
+            kind,
+            stride: stride.size(),
+            padding: padding.size(),
+            kernel: kernel.size(),
+            dilation: dilation.size(),
+            batch: batch.size(),
+            chan: chan.size(),
+            d_in: z.size(),
+            d_out: z_out.size(),
+            h_in: h.size(),
+            h_out: h_out.size(),
+            w_in: w.size(),
+            w_out: w_out.size(),
+        };
+        let (inp, mut tape) = self.split_tape();
+        let mut out = inp.device.alloc((batch, chan, z_out, h_out, w_out))?;
+        inp.device.forward(op, &inp, &mut out)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Pool3D")
+                .attr("kind", Attr::String(format!("{kind:?}")))
+                .attr("kernel", Attr::Int(op.kernel as i64))
+                .attr("stride", Attr::Int(op.stride as i64))
+                .attr("padding", Attr::Int(op.padding as i64))
+                .attr("dilation", Attr::Int(op.dilation as i64))
+                .input(&inp)
+                .output(&out);
+            let flops_per_out = op.kernel * op.kernel * op.kernel;
+            tape.add_op_node(node.flops(flops_per_out * out.shape.num_elements()));
+        }
+        let inp_ghost = inp.ghost();
+        let out_ghost = out.ghost();
+        let out_clone = out.clone();
+        tape.add_backward_op(move |grads| {
+            grads.try_alloc_for(&inp_ghost)?;
+            grads.try_alloc_for(&out_ghost)?;
+            let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
+            inp.device
+                .backward(op, &inp, grad_inp, &out_clone, grad_out)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_pool3d_4d_max_grads() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[
+                [[1.0, 2.0, 0.5], [3.0, -1.0, 0.0]],
+                [[0.2, 4.0, 1.5], [-2.0, 0.1, 5.0]],
+            ]])
+            .to_dtype::<TestDtype>();
+        let r = x
+            .leaky_trace()
+            .pool3d(
+                Pool2DKind::Max,
+                Const::<2>,
+                Const::<1>,
+                Const::<0>,
+                Const::<1>,
+            )
+            .realize::<Rank4<1, 1, 1, 2>>();
+        assert_close_to_literal!(r, [[[[4.0, 5.0]]]]);
+        let g = r.sum().backward();
+        assert_close_to_literal!(
+            g.get(&x),
+            [[
+                [[0.0, 0.0, 0.0], [0.0, 0.0, 0.0]],
+                [[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_pool3d_5d_avg_strided_padded() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[[[1.0, 2.0], [3.0, 4.0]], [[5.0, 6.0], [7.0, 8.0]]]])
+            .to_dtype::<TestDtype>()
+            .reshape::<Rank5<1, 1, 2, 2, 2>>();
+        let r = x
+            .leaky_trace()
+            .pool3d(
+                Pool2DKind::Avg,
+                Const::<2>,
+                Const::<2>,
+                Const::<1>,
+                Const::<1>,
+            )
+            .realize::<Rank5<1, 1, 2, 2, 2>>();
+        let v = 1.0 / 8.0;
+        assert_close_to_literal!(
+            r.retaped::<NoneTape>().reshape::<Rank4<1, 2, 2, 2>>(),
+            [[
+                [[1.0 * v, 2.0 * v], [3.0 * v, 4.0 * v]],
+                [[5.0 * v, 6.0 * v], [7.0 * v, 8.0 * v]],
+            ]]
+        );
+        let g = r.sum().backward();
+        assert_close_to_literal!(g.get(&x).reshape::<Rank4<1, 2, 2, 2>>(), [[[[v; 2]; 2]; 2]]);
+    }
+}
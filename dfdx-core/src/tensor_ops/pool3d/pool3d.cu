@@ -0,0 +1,203 @@
+#include "cuda_utils.cuh"
+
+enum Pool3dKind {
+    AVG,
+    MIN,
+    MAX,
+};
+
+struct Pool3dOp {
+    Pool3dKind kind;
+    size_t kernel;
+    size_t stride;
+    size_t padding;
+    size_t dilation;
+    size_t batch;
+    size_t chan;
+    size_t d_in;
+    size_t d_out;
+    size_t h_in;
+    size_t h_out;
+    size_t w_in;
+    size_t w_out;
+};
+
+__device__ double init(const Pool3dOp op) {
+    switch(op.kind) {
+        case AVG:
+            return 0.0;
+        case MIN:
+            return INFINITY;
+        case MAX:
+            return -INFINITY;
+    }
+}
+
+template<typename T>
+__device__ T accum(const Pool3dOp op, const T accum, const T item) {
+    switch(op.kind) {
+        case AVG:
+            return accum + item;
+        case MIN:
+            return ming(accum, item);
+        case MAX:
+            return maxg(accum, item);
+    }
+}
+
+template<typename T>
+__device__ T normalize(const Pool3dOp op, const T item, const size_t num_elements) {
+    double num_f64 = num_elements;
+    double scale_f64 = 1.0 / num_f64;
+    T scale = scale_f64;
+    switch(op.kind) {
+        case AVG:
+            return item * scale;
+        case MIN:
+            return item;
+        case MAX:
+            return item;
+    }
+}
+
+template<typename T>
+__device__ T filter(const Pool3dOp op, const T item, const T needle, const T haystack) {
+    T zero = 0.0;
+    switch(op.kind){
+        case AVG:
+            return item;
+        case MIN:
+            return (needle == haystack) ? item : zero;
+        case MAX:
+            return (needle == haystack) ? item : zero;
+    }
+}
+
+__device__ bool inp_idx(const Pool3dOp op, const size_t o, const size_t k, const size_t dim_in, size_t *i) {
+    const size_t i_plus_p = o * op.stride + op.dilation * k;
+    if (i_plus_p < op.padding) { return false; }
+    *i = i_plus_p - op.padding;
+    return *i < dim_in;
+}
+
+__device__ bool out_idx(const Pool3dOp op, const size_t i, const size_t k, const size_t dim_out, size_t *o) {
+    size_t o_s = i + op.padding;
+    if (o_s < op.dilation * k) { return false; }
+    o_s -= op.dilation * k;
+    if (o_s % op.stride != 0) { return false; }
+    *o = o_s / op.stride;
+    return *o < dim_out;
+}
+
+template<typename T>
+__device__ void pool3d_fwd(
+    const Pool3dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const T *inp, // 5d (Batch, Channels, Depth, Height, Width)
+    T *out // 5d (Batch, Channels, DepthOut, HeightOut, WidthOut)
+) {
+    const size_t numel = op.batch * op.chan * op.d_out * op.h_out * op.w_out;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t ow = idx % op.w_out;
+        idx /= op.w_out;
+        const size_t oh = idx % op.h_out;
+        idx /= op.h_out;
+        const size_t od = idx % op.d_out;
+        idx /= op.d_out;
+        const size_t c = idx % op.chan;
+        idx /= op.chan;
+        const size_t b = idx % op.batch;
+
+        T tmp = init(op);
+        for(size_t k1 = 0; k1 < op.kernel; k1++) {
+            size_t z;
+            if (!inp_idx(op, od, k1, op.d_in, &z)) { continue; }
+            for(size_t k2 = 0; k2 < op.kernel; k2++) {
+                size_t y;
+                if (!inp_idx(op, oh, k2, op.h_in, &y)) { continue; }
+                for(size_t k3 = 0; k3 < op.kernel; k3++) {
+                    size_t x;
+                    if (!inp_idx(op, ow, k3, op.w_in, &x)) { continue; }
+
+                    auto inp_i = b * inp_strides[0] + c * inp_strides[1] + z * inp_strides[2] + y * inp_strides[3] + x * inp_strides[4];
+                    tmp = accum(op, tmp, inp[inp_i]);
+                }
+            }
+        }
+
+        out[i] = normalize(op, tmp, op.kernel * op.kernel * op.kernel);
+    }
+}
+
+template<typename T>
+__device__ void pool3d_bwd(
+    const Pool3dOp op,
+    const size_t *inp_strides,
+    const size_t *out_strides,
+    const T *inp, // 5d (Batch, Channels, Depth, Height, Width)
+    T *grad_inp,
+    const T *out, // 5d (Batch, Channels, DepthOut, HeightOut, WidthOut)
+    const T *grad_out
+) {
+    const size_t numel = op.batch * op.chan * op.d_in * op.h_in * op.w_in;
+    for (unsigned int i = blockIdx.x * blockDim.x + threadIdx.x; i < numel; i += blockDim.x * gridDim.x) {
+        unsigned int idx = i;
+        const size_t x = idx % op.w_in;
+        idx /= op.w_in;
+        const size_t y = idx % op.h_in;
+        idx /= op.h_in;
+        const size_t z = idx % op.d_in;
+        idx /= op.d_in;
+        const size_t c = idx % op.chan;
+        idx /= op.chan;
+        const size_t b = idx % op.batch;
+
+        const T inp_v = inp[i];
+
+        T tmp = 0.0;
+        for(size_t k1 = 0; k1 < op.kernel; k1++) {
+            size_t od;
+            if (!out_idx(op, z, k1, op.d_out, &od)) { continue; }
+            for(size_t k2 = 0; k2 < op.kernel; k2++) {
+                size_t oh;
+                if (!out_idx(op, y, k2, op.h_out, &oh)) { continue; }
+                for(size_t k3 = 0; k3 < op.kernel; k3++) {
+                    size_t ow;
+                    if (!out_idx(op, x, k3, op.w_out, &ow)) { continue; }
+
+                    auto out_i = b * out_strides[0] + c * out_strides[1] + od * out_strides[2] + oh * out_strides[3] + ow * out_strides[4];
+                    tmp += filter(op, grad_out[out_i], out[out_i], inp_v);
+                }
+            }
+        }
+        grad_inp[i] += normalize(op, tmp, op.kernel * op.kernel * op.kernel);
+    }
+}
+
+#define POOL_OP(TYPENAME, fwd, bwd) \
+extern "C" __global__ void fwd( \
+    const Pool3dOp op, \
+    const size_t *inp_strides, \
+    const size_t *out_strides, \
+    const TYPENAME *inp, \
+    TYPENAME *out \
+) { \
+    pool3d_fwd(op, inp_strides, out_strides, inp, out); \
+} \
+extern "C" __global__ void bwd( \
+    const Pool3dOp op, \
+    const size_t *inp_strides, \
+    const size_t *out_strides, \
+    const TYPENAME *inp, \
+    TYPENAME *grad_inp, \
+    const TYPENAME *out, \
+    const TYPENAME *grad_out \
+) { \
+    pool3d_bwd(op, inp_strides, out_strides, inp, grad_inp, out, grad_out); \
+}
+
+POOL_OP(__half, pool3d_fwd_f16, pool3d_bwd_f16);
+POOL_OP(float, pool3d_fwd_f32, pool3d_bwd_f32);
+POOL_OP(double, pool3d_fwd_f64, pool3d_bwd_f64);
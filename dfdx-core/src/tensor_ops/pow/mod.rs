@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::{Device, TryMul};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -68,6 +69,47 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<PowiKernelOp, E>, T: Tape<E, D>> Tensor<
     }
 }
 
+impl KernelOpInfo for PowiKernelOp {
+    const NAME: &'static str = "Powi";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("exponent", Attr::Int(self.0 as i64))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for PowiKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        let dx = inp.retaped::<OwnedTape<E, D>>().try_powi(self.0 - 1)?.try_mul(self.0)?;
+        grad_out.try_mul(dx)
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for PowfKernelOp<E> {
+    const NAME: &'static str = "Powf";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("exponent", Attr::Float(self.0.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for PowfKernelOp<E> {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        let exponent = self.0.to_f64().unwrap();
+        let dx = inp.retaped::<OwnedTape<E, D>>().try_powf(exponent - 1.0)?.try_mul(exponent)?;
+        grad_out.try_mul(dx)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
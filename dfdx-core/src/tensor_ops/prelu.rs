@@ -65,6 +65,20 @@ where
 {
     /// See [prelu]
     fn try_prelu(self, rhs: Tensor<S, E, D, R>) -> Result<Self, Error> {
+        if self.tape.captures_ops() {
+            // the comparison isn't recorded, so this is captured as one op
+            let (lhs, ltape) = self.split_tape();
+            let (rhs, rtape) = rhs.split_tape();
+            let mut tape = ltape.merge(rtape);
+            let out = lhs.clone().try_prelu(rhs.clone())?;
+            tape.add_op_node(
+                OpNode::new("PReLU")
+                    .input(&lhs)
+                    .input(&rhs)
+                    .elementwise(&out),
+            );
+            return Ok(out.put_tape(tape));
+        }
         let scaled = self.with_empty_tape().try_mul(rhs)?;
         self.try_lt(E::default())?.try_choose(scaled, self)
     }
@@ -73,6 +87,14 @@ where
 impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> TryPReLU<E> for Tensor<S, E, D, T> {
     /// See [prelu]
     fn try_prelu(self, rhs: E) -> Result<Self, Error> {
+        if self.tape.captures_ops() {
+            // the comparison isn't recorded, so this is captured as one op
+            let (inp, mut tape) = self.split_tape();
+            let out = inp.clone().try_prelu(rhs)?;
+            let node = OpNode::new("LeakyReLU").attr("alpha", Attr::Float(rhs.to_f64().unwrap()));
+            tape.add_op_node(node.input(&inp).elementwise(&out));
+            return Ok(out.put_tape(tape));
+        }
         let dev = self.device.clone();
         let scale = dev.tensor(rhs).retaped::<T>().broadcast_like(self.shape());
         let scaled = self.with_empty_tape().try_mul(scale)?;
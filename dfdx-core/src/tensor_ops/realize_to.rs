@@ -16,9 +16,7 @@ use crate::{shapes::*, tensor::*};
 pub trait RealizeTo: Sized + HasShape {
     /// Realizes the concrete shape of the tensor as another compatable shape,
     /// or returns the original tensor if the new shape's dimensions are incompatable.
-    fn realize<Dst: Shape<Concrete = <<Self as HasShape>::Shape as Shape>::Concrete>>(
-        self,
-    ) -> Self::WithShape<Dst>
+    fn realize<Dst: Shape>(self) -> Self::WithShape<Dst>
     where
         Self::Shape: RealizeShapeTo<Dst>,
         Self: std::fmt::Debug,
@@ -28,15 +26,13 @@ pub trait RealizeTo: Sized + HasShape {
 
     /// Realizes the concrete shape of the tensor as another compatable shape,
     /// or returns the original tensor if the new shape's dimensions are incompatable.
-    fn try_realize<Dst: Shape<Concrete = <<Self as HasShape>::Shape as Shape>::Concrete>>(
-        self,
-    ) -> Result<Self::WithShape<Dst>, Self>
+    fn try_realize<Dst: Shape>(self) -> Result<Self::WithShape<Dst>, Self>
     where
         Self::Shape: RealizeShapeTo<Dst>;
 }
 
 impl<S: Shape, E, D: Storage<E>, T: Tape<E, D>> RealizeTo for Tensor<S, E, D, T> {
-    fn try_realize<Dst: Shape<Concrete = S::Concrete>>(self) -> Result<Self::WithShape<Dst>, Self>
+    fn try_realize<Dst: Shape>(self) -> Result<Self::WithShape<Dst>, Self>
     where
         Self::Shape: RealizeShapeTo<Dst>,
     {
@@ -44,7 +40,7 @@ impl<S: Shape, E, D: Storage<E>, T: Tape<E, D>> RealizeTo for Tensor<S, E, D, T>
             Ok(Tensor {
                 id: self.id,
                 data: self.data,
-                strides: self.strides,
+                strides: self.shape.realized_strides(&self.strides),
                 shape: dst_shape,
                 device: self.device,
                 tape: self.tape,
@@ -224,4 +220,64 @@ mod tests {
         let x = x.try_realize::<(usize, usize, usize, Const<9>)>().unwrap();
         let _ = x.try_realize::<(usize, usize, usize, usize)>().unwrap();
     }
+
+    #[test]
+    fn test_realize_dyn() {
+        let dev: TestDevice = Default::default();
+        let src: Tensor<Rank3<2, 3, 4>, TestDtype, _> = dev.sample_normal();
+        let dst: Tensor<DynShape, TestDtype, _> = src.clone().realize();
+        assert_eq!(dst.shape().dims(), [2, 3, 4]);
+        assert_eq!(src.as_vec(), dst.as_vec());
+
+        let mut dst = dst
+            .try_realize::<Rank2<6, 4>>()
+            .unwrap_err()
+            .try_realize::<(usize, usize, Const<3>)>()
+            .unwrap_err();
+        dst = dst.try_realize::<Rank4<1, 2, 3, 4>>().unwrap_err();
+        let src2: Tensor<(usize, Const<3>, usize), TestDtype, _> = dst.realize();
+        assert_eq!(src2.shape(), &(2, Const, 4));
+        assert_eq!(src.as_vec(), src2.as_vec());
+    }
+
+    #[test]
+    fn test_realize_dyn_keeps_strides() {
+        let dev: TestDevice = Default::default();
+        let src: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let dst: Tensor<DynShape, TestDtype, _> = src.clone().permute::<_, Axes2<1, 0>>().realize();
+        assert_eq!(dst.shape().dims(), [3, 2]);
+        let dst: Tensor<Rank2<3, 2>, TestDtype, _> = dst.realize();
+        assert_close_to_tensor!(dst, src.permute());
+
+        let src: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let dst: Tensor<DynShape, TestDtype, _> =
+            src.clone().broadcast::<Rank2<2, 3>, _>().realize();
+        assert_eq!(dst.as_vec(), [src.as_vec(), src.as_vec()].concat());
+    }
+
+    #[test]
+    fn test_dyn_ops() {
+        let dev: TestDevice = Default::default();
+        let src: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let dyn_src: Tensor<DynShape, TestDtype, _> = src.clone().realize();
+
+        let r = dyn_src.leaky_trace().exp() * dyn_src.clone();
+        let expected = src.leaky_trace().exp() * src.clone();
+        assert_eq!(r.shape().dims(), [2, 3]);
+        let r: Tensor<Rank2<2, 3>, _, _, _> = r.realize();
+        assert_close_to_tensor!(r, expected);
+
+        let g = r.sum().backward();
+        let expected_g = expected.sum().backward();
+        let dyn_g: Tensor<Rank2<2, 3>, _, _> = g.get(&dyn_src).realize();
+        assert_close_to_tensor!(dyn_g, expected_g.get(&src));
+
+        let r = dyn_src.clone().softmax::<Axis<7>>();
+        let r: Tensor<Rank2<2, 3>, _, _> = r.realize();
+        assert_close_to_tensor!(r, src.clone().softmax::<Axis<1>>());
+
+        let r = dyn_src.clone().max::<[usize; MAX_DYN_RANK - 1], _>();
+        assert_eq!(r.as_vec(), src.clone().max::<Rank1<2>, _>().as_vec());
+        assert_close_to_tensor!(dyn_src.mean::<Rank0, _>(), src.mean::<Rank0, _>());
+    }
 }
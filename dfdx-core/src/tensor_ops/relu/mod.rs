@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::{ChooseFrom, Device, TryGt};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -42,6 +43,23 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<ReLUKernelOp, E>, T: Tape<E, D>> Tensor<
     }
 }
 
+impl KernelOpInfo for ReLUKernelOp {
+    const NAME: &'static str = "ReLU";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ReLUKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        let mask = inp.try_gt(E::default())?;
+        let zeros = inp.device().try_zeros_like(inp.shape())?;
+        mask.try_choose(grad_out, zeros)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
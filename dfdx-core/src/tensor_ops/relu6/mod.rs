@@ -0,0 +1,65 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct ReLU6KernelOp;
+
+/// [ReLU](super::relu()) capped at 6. `min(max(0, t), 6)`
+///
+/// The derivative is `1` for `0 < t < 6`, otherwise `0`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.relu6();
+/// ```
+pub fn relu6<S: Shape, E: Dtype, D: UnaryKernel<ReLU6KernelOp, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.relu6()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<ReLU6KernelOp, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [relu6]
+    pub fn relu6(self) -> Self {
+        self.try_relu6().unwrap()
+    }
+    /// See [relu6]
+    pub fn try_relu6(self) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(ReLU6KernelOp, self)
+    }
+}
+
+impl KernelOpInfo for ReLU6KernelOp {
+    const NAME: &'static str = "ReLU6";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ReLU6KernelOp {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_relu6() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().relu6();
+        assert_close_to_literal!(r, [0.0, 0.0, 0.5, 2.0, 6.0]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&x), [0.0, 0.0, 0.2, 0.2, 0.0]);
+    }
+}
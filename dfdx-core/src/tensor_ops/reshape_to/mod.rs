@@ -90,19 +90,34 @@ impl<S: Shape, E: Dtype, D: ReshapeKernel<E>, T: Tape<E, D>> ReshapeTo for Tenso
     fn try_reshape_like<Dst: Shape>(self, dst: &Dst) -> Result<Self::WithShape<Dst>, Error> {
         assert_eq!(self.shape().num_elements(), dst.num_elements());
         if self.shape.strides() == self.strides {
-            Ok(Tensor {
-                id: self.id,
-                data: self.data,
+            let (inp, mut tape) = self.split_tape();
+            let node = tape
+                .captures_ops()
+                .then(|| OpNode::new("Reshape").input(&inp));
+            let out = Tensor {
+                id: inp.id,
+                data: inp.data,
                 shape: *dst,
                 strides: dst.strides(),
-                device: self.device,
-                tape: self.tape,
-            })
+                device: inp.device,
+                tape: NoneTape,
+            };
+            if let Some(node) = node {
+                tape.add_op_node(node.output(&out));
+            }
+            Ok(out.put_tape(tape))
         } else {
             let (inp, mut tape) = self.split_tape();
             let out = inp.device.forward(dst, &inp)?;
+            if tape.captures_ops() {
+                tape.add_op_node(OpNode::new("Reshape").input(&inp).output(&out));
+            }
             let inp_ghost = inp.ghost();
             let out_ghost = out.ghost();
+            let graph_op = ReshapeGraphOp {
+                inp: inp.ghost(),
+                out: out.ghost(),
+            };
             let dst = *dst;
             tape.add_backward_op(move |grads| {
                 grads.try_alloc_for(&inp_ghost)?;
@@ -110,11 +125,87 @@ impl<S: Shape, E: Dtype, D: ReshapeKernel<E>, T: Tape<E, D>> ReshapeTo for Tenso
                 let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
                 inp.device.backward(&dst, &inp, grad_inp, grad_out)
             });
+            tape.add_graph_op(graph_op);
             Ok(out.put_tape(tape))
         }
     }
 }
 
+struct ReshapeGraphOp<Src: Shape, Dst: Shape, E, D: Storage<E>> {
+    inp: GhostTensor<Src, E, D>,
+    out: GhostTensor<Dst, E, D>,
+}
+
+impl<Src: Shape, Dst: Shape, E: Dtype, D: Storage<E>> GraphBackward<E, D>
+    for ReshapeGraphOp<Src, Dst, E, D>
+{
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: super::Device<E>,
+    {
+        if let Some(grad_out) = grads.grad_out(&self.out)? {
+            let grad_inp = grad_out.try_reshape_like(&self.inp.shape)?;
+            grads.accumulate(&self.inp, grad_inp)?;
+        }
+        Ok(())
+    }
+
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: super::Device<E>,
+    {
+        if let Some(tangent) = tangents.get(&self.inp)? {
+            tangents.try_set(&self.out, tangent.try_reshape_like(&self.out.shape)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sums `grad` into a new buffer laid out like the data of `dst`. This is the opposite of
+/// viewing that data with the shape & strides of `dst`, e.g. it sums over broadcasted axes.
+pub(crate) fn try_scatter_like<S: Shape, E: Dtype, D: ReshapeKernel<E>, T: Tape<E, D>>(
+    grad: Tensor<S, E, D, T>,
+    dst: &impl Tensorlike<S, E, D>,
+) -> Result<Tensor<(usize,), E, D, T>, Error> {
+    let (grad, mut tape) = grad.try_contiguous()?.split_tape();
+    let view = Tensor {
+        id: unique_id(),
+        data: std::sync::Arc::new(dst.try_alloc_grad()?),
+        shape: *dst.shape(),
+        strides: dst.strides(),
+        device: grad.device.clone(),
+        tape: NoneTape,
+    };
+    let mut data = dst.try_alloc_grad()?;
+    grad.device
+        .backward(&grad.shape, &view, &mut data, grad.data.as_ref())?;
+    let out = Tensor {
+        id: unique_id(),
+        data: std::sync::Arc::new(data),
+        shape: (dst.len(),),
+        strides: [1],
+        device: grad.device.clone(),
+        tape: NoneTape,
+    };
+    let grad_ghost = grad.ghost();
+    let out_ghost = out.ghost();
+    tape.add_backward_op(move |grads| {
+        grads.try_alloc_for(&grad_ghost)?;
+        grads.try_alloc_for(&out_ghost)?;
+        let (grad_inp, grad_out) = grads.mut_and_ref(&grad_ghost, &out_ghost);
+        let view = Tensor {
+            data: std::sync::Arc::new(grad_out.clone()),
+            ..view
+        };
+        let viewed = view.device.forward(&view.shape, &view)?;
+        view.device
+            .backward(&viewed.shape, &viewed, grad_inp, viewed.data.as_ref())
+    });
+    Ok(out.put_tape(tape))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tensor::*;
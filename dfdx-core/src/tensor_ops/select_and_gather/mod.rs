@@ -107,6 +107,10 @@ impl<Src: Shape, E: Dtype, D: RemoveDimKernel<E>, T: Tape<E, D>> SelectTo<E, D>
         self.shape().check(idx.shape());
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.forward(&inp, &idx)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Select").axes::<<Src as RemoveDimTo<Dst, Idx>>::Ax>();
+            tape.add_op_node(node.input(&inp).input(&idx).output(&out));
+        }
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
         let out_clone = out.clone();
@@ -179,6 +183,10 @@ impl<Src: Shape, E: Dtype, D: ReplaceDimKernel<E>, T: Tape<E, D>> GatherTo<E, D>
         self.shape().check(idx.shape());
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.forward(&inp, &idx)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Gather").axes::<<Src as ReplaceDimTo<Dst, Idx>>::Ax>();
+            tape.add_op_node(node.input(&inp).input(&idx).output(&out));
+        }
 
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
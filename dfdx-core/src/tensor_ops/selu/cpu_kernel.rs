@@ -0,0 +1,26 @@
+use super::{ALPHA, SCALE};
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+use num_traits::Float;
+
+impl<F: Float> UnaryDerivative<F> for super::SELUKernelOp {
+    const DF_USES_FX: bool = false;
+    const HAS_CONST_DF: bool = false;
+    #[inline(always)]
+    fn f(&self, &x: &F) -> F {
+        let scale = F::from(SCALE).unwrap();
+        if x > F::zero() {
+            scale * x
+        } else {
+            scale * F::from(ALPHA).unwrap() * x.exp_m1()
+        }
+    }
+    #[inline(always)]
+    fn df(&self, &x: &F) -> F {
+        let scale = F::from(SCALE).unwrap();
+        if x > F::zero() {
+            scale
+        } else {
+            scale * F::from(ALPHA).unwrap() * x.exp()
+        }
+    }
+}
@@ -0,0 +1,15 @@
+use super::SELUKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+unsafe impl cudarc::driver::DeviceRepr for SELUKernelOp {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/selu.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(SELUKernelOp, f16, PTX, "selu_fwd_f16", "selu_bwd_f16");
+#[cfg(feature = "f16")]
+cuda_unary!(SELUKernelOp, AMP<f16>, PTX, "selu_fwd_f16", "selu_bwd_f16");
+cuda_unary!(SELUKernelOp, f32, PTX, "selu_fwd_f32", "selu_bwd_f32");
+cuda_unary!(SELUKernelOp, f64, PTX, "selu_fwd_f64", "selu_bwd_f64");
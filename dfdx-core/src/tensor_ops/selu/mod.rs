@@ -0,0 +1,74 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+/// The `alpha` of [selu], from the paper.
+pub(super) const ALPHA: f64 = 1.6732632423543772848170429916717;
+/// The `scale` of [selu], from the paper.
+pub(super) const SCALE: f64 = 1.0507009873554804934193349852946;
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SELUKernelOp;
+
+/// [Scaled Exponential Linear Unit (SELU)](https://arxiv.org/abs/1706.02515). `scale * elu(t, alpha)`
+/// with `alpha = 1.6732632` and `scale = 1.0507010`
+///
+/// The derivative is `scale` if `t > 0`, otherwise `scale * alpha * exp(t)`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.selu();
+/// ```
+pub fn selu<S: Shape, E: Dtype, D: UnaryKernel<SELUKernelOp, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.selu()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<SELUKernelOp, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [selu]
+    pub fn selu(self) -> Self {
+        self.try_selu().unwrap()
+    }
+    /// See [selu]
+    pub fn try_selu(self) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(SELUKernelOp, self)
+    }
+}
+
+impl KernelOpInfo for SELUKernelOp {
+    const NAME: &'static str = "SELU";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for SELUKernelOp {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_selu() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().selu();
+        assert_close_to_literal!(r, [-1.7258986, -1.3658144, 0.52535049, 2.101402, 7.3549069]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(
+            g.get(&x),
+            [0.0064401425, 0.078456997, 0.2101402, 0.2101402, 0.2101402]
+        );
+    }
+}
@@ -0,0 +1,35 @@
+#include "unary_op_macros.cuh"
+
+#define SELU_ALPHA 1.6732632423543772848170429916717
+#define SELU_SCALE 1.0507009873554804934193349852946
+
+struct SELUKernelOp {};
+
+template<typename T>
+__device__ __forceinline__ T selu_fwd(T x) {
+    T zero = 0.0;
+    T one = 1.0;
+    T alpha = SELU_ALPHA;
+    T scale = SELU_SCALE;
+    return x > zero ? scale * x : scale * alpha * (expg(x) - one);
+}
+
+template<typename T>
+__device__ __forceinline__ T selu_bwd(T x) {
+    T zero = 0.0;
+    T alpha = SELU_ALPHA;
+    T scale = SELU_SCALE;
+    return x > zero ? scale : scale * alpha * expg(x);
+}
+
+UNARY_OP(__half, selu_fwd_f16, selu_bwd_f16, SELUKernelOp,
+    selu_fwd(x),
+    selu_bwd(x))
+
+UNARY_OP(float, selu_fwd_f32, selu_bwd_f32, SELUKernelOp,
+    selu_fwd(x),
+    selu_bwd(x))
+
+UNARY_OP(double, selu_fwd_f64, selu_bwd_f64, SELUKernelOp,
+    selu_fwd(x),
+    selu_bwd(x))
@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::{Device, TryAdd, TryMul};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -41,6 +42,22 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<SigmoidKernelOp, E>, T: Tape<E, D>> Tens
     }
 }
 
+impl KernelOpInfo for SigmoidKernelOp {
+    const NAME: &'static str = "Sigmoid";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for SigmoidKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        let dx = out.retaped::<OwnedTape<E, D>>().try_negate()?.try_add(1.0)?;
+        grad_out.try_mul(dx.try_mul(out.clone())?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
@@ -0,0 +1,16 @@
+use crate::tensor_ops::cpu_kernels::UnaryDerivative;
+use num_traits::Float;
+
+impl<F: Float> UnaryDerivative<F> for super::SiLUKernelOp {
+    const DF_USES_FX: bool = false;
+    const HAS_CONST_DF: bool = false;
+    #[inline(always)]
+    fn f(&self, &x: &F) -> F {
+        x / (F::one() + x.neg().exp())
+    }
+    #[inline(always)]
+    fn df(&self, &x: &F) -> F {
+        let s = F::one() / (F::one() + x.neg().exp());
+        s * (F::one() + x * (F::one() - s))
+    }
+}
@@ -0,0 +1,28 @@
+#include "unary_op_macros.cuh"
+
+struct SiLUKernelOp {};
+
+template<typename T>
+__device__ __forceinline__ T silu_fwd(T x) {
+    T one = 1.0;
+    return x / (one + expg(-x));
+}
+
+template<typename T>
+__device__ __forceinline__ T silu_bwd(T x) {
+    T one = 1.0;
+    T s = one / (one + expg(-x));
+    return s * (one + x * (one - s));
+}
+
+UNARY_OP(__half, silu_fwd_f16, silu_bwd_f16, SiLUKernelOp,
+    silu_fwd(x),
+    silu_bwd(x))
+
+UNARY_OP(float, silu_fwd_f32, silu_bwd_f32, SiLUKernelOp,
+    silu_fwd(x),
+    silu_bwd(x))
+
+UNARY_OP(double, silu_fwd_f64, silu_bwd_f64, SiLUKernelOp,
+    silu_fwd(x),
+    silu_bwd(x))
@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::{Device, TryMul};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -41,6 +42,21 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<SinKernelOp, E>, T: Tape<E, D>> Tensor<S
     }
 }
 
+impl KernelOpInfo for SinKernelOp {
+    const NAME: &'static str = "Sin";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for SinKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        grad_out.try_mul(inp.retaped::<OwnedTape<E, D>>().try_cos()?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tests::*;
@@ -69,6 +69,14 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Tensor<S, E, D, T> {
         */
         let shape = *self.shape();
         let (t, tape) = self.split_tape();
+        if tape.captures_ops() {
+            // resetting the id below hides the subtraction from a captured graph
+            let t = t.put_tape(tape);
+            let max = t.with_empty_tape().try_max::<_, Ax>()?;
+            let t_exp = t.try_sub(max.try_broadcast_like(&shape)?)?.try_exp()?;
+            let t_expsum = t_exp.with_empty_tape().try_sum::<_, Ax>()?;
+            return t_exp.try_div(t_expsum.try_broadcast_like(&shape)?);
+        }
         let max = t.clone().try_max::<_, Ax>()?;
         let t = {
             // in place subtraction of max since we don't want to record this
@@ -79,7 +87,7 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Tensor<S, E, D, T> {
             t
         };
         let t_exp = t.put_tape(tape).try_exp()?;
-        let t_expsum = t_exp.retaped::<T>().try_sum::<_, Ax>()?;
+        let t_expsum = t_exp.with_empty_tape().try_sum::<_, Ax>()?;
         t_exp.try_div(t_expsum.try_broadcast_like(&shape)?)
     }
 }
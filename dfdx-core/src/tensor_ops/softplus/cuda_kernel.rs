@@ -0,0 +1,44 @@
+use super::SoftplusKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for SoftplusKernelOp<AMP<f16>> {}
+#[cfg(feature = "f16")]
+unsafe impl cudarc::driver::DeviceRepr for SoftplusKernelOp<f16> {}
+unsafe impl cudarc::driver::DeviceRepr for SoftplusKernelOp<f32> {}
+unsafe impl cudarc::driver::DeviceRepr for SoftplusKernelOp<f64> {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/softplus.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(
+    SoftplusKernelOp<f16>,
+    f16,
+    PTX,
+    "softplus_fwd_f16",
+    "softplus_bwd_f16"
+);
+#[cfg(feature = "f16")]
+cuda_unary!(
+    SoftplusKernelOp<AMP<f16>>,
+    AMP<f16>,
+    PTX,
+    "softplus_fwd_f16",
+    "softplus_bwd_f16"
+);
+cuda_unary!(
+    SoftplusKernelOp<f32>,
+    f32,
+    PTX,
+    "softplus_fwd_f32",
+    "softplus_bwd_f32"
+);
+cuda_unary!(
+    SoftplusKernelOp<f64>,
+    f64,
+    PTX,
+    "softplus_fwd_f64",
+    "softplus_bwd_f64"
+);
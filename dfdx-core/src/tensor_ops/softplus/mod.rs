@@ -0,0 +1,95 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SoftplusKernelOp<E> {
+    pub beta: E,
+    pub threshold: E,
+}
+
+/// [Softplus](https://en.wikipedia.org/wiki/Softplus), a smooth version of relu.
+/// `ln(1 + exp(beta * t)) / beta`, or `t` where `beta * t > threshold`
+///
+/// Arguments:
+/// - `beta`: Scales `t` before it goes through `ln(1 + exp(t))`.
+/// - `threshold`: Above `beta * t > threshold` the output is just `t`, to avoid overflow.
+///
+/// The derivative is `sigmoid(beta * t)`, or `1` where `beta * t > threshold`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.softplus(2.0, 5.0);
+/// ```
+pub fn softplus<S: Shape, E: Dtype, D: UnaryKernel<SoftplusKernelOp<E>, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+    beta: impl Into<f64>,
+    threshold: impl Into<f64>,
+) -> Tensor<S, E, D, T> {
+    t.softplus(beta, threshold)
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<SoftplusKernelOp<E>, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [softplus]
+    pub fn softplus(self, beta: impl Into<f64>, threshold: impl Into<f64>) -> Self {
+        self.try_softplus(beta, threshold).unwrap()
+    }
+    /// See [softplus]
+    pub fn try_softplus(
+        self,
+        beta: impl Into<f64>,
+        threshold: impl Into<f64>,
+    ) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(
+            SoftplusKernelOp {
+                beta: E::from_f64(beta.into()).unwrap(),
+                threshold: E::from_f64(threshold.into()).unwrap(),
+            },
+            self,
+        )
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for SoftplusKernelOp<E> {
+    const NAME: &'static str = "Softplus";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![
+            ("beta", Attr::Float(self.beta.to_f64().unwrap())),
+            ("threshold", Attr::Float(self.threshold.to_f64().unwrap())),
+        ]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for SoftplusKernelOp<E> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_softplus() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().softplus(2.0, 5.0);
+        assert_close_to_literal!(r, [0.00016770319, 0.024293676, 0.65663084, 2.009075, 7.0]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(
+            g.get(&x),
+            [0.00006707, 0.0094851746, 0.14621172, 0.19640276, 0.2]
+        );
+    }
+}
@@ -0,0 +1,33 @@
+#include "unary_op_macros.cuh"
+
+template<typename F>
+struct SoftplusKernelOp {
+    F beta;
+    F threshold;
+};
+
+template<typename T>
+__device__ __forceinline__ T softplus_fwd(T x, T beta, T threshold) {
+    T one = 1.0;
+    T bx = beta * x;
+    return bx > threshold ? x : logg(one + expg(bx)) / beta;
+}
+
+template<typename T>
+__device__ __forceinline__ T softplus_bwd(T x, T beta, T threshold) {
+    T one = 1.0;
+    T bx = beta * x;
+    return bx > threshold ? one : one / (one + expg(-bx));
+}
+
+UNARY_OP(__half, softplus_fwd_f16, softplus_bwd_f16, SoftplusKernelOp<__half>,
+    softplus_fwd(x, op.beta, op.threshold),
+    softplus_bwd(x, op.beta, op.threshold))
+
+UNARY_OP(float, softplus_fwd_f32, softplus_bwd_f32, SoftplusKernelOp<float>,
+    softplus_fwd(x, op.beta, op.threshold),
+    softplus_bwd(x, op.beta, op.threshold))
+
+UNARY_OP(double, softplus_fwd_f64, softplus_bwd_f64, SoftplusKernelOp<double>,
+    softplus_fwd(x, op.beta, op.threshold),
+    softplus_bwd(x, op.beta, op.threshold))
@@ -0,0 +1,39 @@
+use super::SoftsignKernelOp;
+#[allow(unused_imports)]
+use crate::dtypes::*;
+use crate::tensor_ops::cuda_kernels::cuda_unary;
+
+unsafe impl cudarc::driver::DeviceRepr for SoftsignKernelOp {}
+
+const PTX: &str = include_str!(concat!(env!("OUT_DIR"), "/softsign.ptx"));
+
+#[cfg(feature = "f16")]
+cuda_unary!(
+    SoftsignKernelOp,
+    f16,
+    PTX,
+    "softsign_fwd_f16",
+    "softsign_bwd_f16"
+);
+#[cfg(feature = "f16")]
+cuda_unary!(
+    SoftsignKernelOp,
+    AMP<f16>,
+    PTX,
+    "softsign_fwd_f16",
+    "softsign_bwd_f16"
+);
+cuda_unary!(
+    SoftsignKernelOp,
+    f32,
+    PTX,
+    "softsign_fwd_f32",
+    "softsign_bwd_f32"
+);
+cuda_unary!(
+    SoftsignKernelOp,
+    f64,
+    PTX,
+    "softsign_fwd_f64",
+    "softsign_bwd_f64"
+);
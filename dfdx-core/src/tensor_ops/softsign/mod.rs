@@ -0,0 +1,68 @@
+mod cpu_kernel;
+
+#[cfg(feature = "cuda")]
+mod cuda_kernel;
+
+#[cfg(feature = "webgpu")]
+mod webgpu_kernel;
+
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use crate::{shapes::*, tensor::*};
+
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SoftsignKernelOp;
+
+/// Softsign. `t / (1 + |t|)`
+///
+/// The derivative is `1 / (1 + |t|)^2`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let t = dev.tensor([-1.0, 0.0, 1.0, 2.0]);
+/// let r = t.softsign();
+/// ```
+pub fn softsign<S: Shape, E: Dtype, D: UnaryKernel<SoftsignKernelOp, E>, T: Tape<E, D>>(
+    t: Tensor<S, E, D, T>,
+) -> Tensor<S, E, D, T> {
+    t.softsign()
+}
+
+impl<S: Shape, E: Dtype, D: UnaryKernel<SoftsignKernelOp, E>, T: Tape<E, D>> Tensor<S, E, D, T> {
+    /// See [softsign]
+    pub fn softsign(self) -> Self {
+        self.try_softsign().unwrap()
+    }
+    /// See [softsign]
+    pub fn try_softsign(self) -> Result<Self, crate::tensor::Error> {
+        try_unary_op(SoftsignKernelOp, self)
+    }
+}
+
+impl KernelOpInfo for SoftsignKernelOp {
+    const NAME: &'static str = "Softsign";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for SoftsignKernelOp {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_softsign() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([-4.0, -1.5, 0.5, 2.0, 7.0])
+            .to_dtype::<TestDtype>();
+        let r = x.leaky_trace().softsign();
+        assert_close_to_literal!(r, [-0.8, -0.6, 0.33333333, 0.66666667, 0.875]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(
+            g.get(&x),
+            [0.008, 0.032, 0.088888889, 0.022222222, 0.003125]
+        );
+    }
+}
@@ -0,0 +1,28 @@
+#include "unary_op_macros.cuh"
+
+struct SoftsignKernelOp {};
+
+template<typename T>
+__device__ __forceinline__ T softsign_fwd(T x) {
+    T one = 1.0;
+    return x / (one + absg(x));
+}
+
+template<typename T>
+__device__ __forceinline__ T softsign_bwd(T x) {
+    T one = 1.0;
+    T d = one + absg(x);
+    return one / (d * d);
+}
+
+UNARY_OP(__half, softsign_fwd_f16, softsign_bwd_f16, SoftsignKernelOp,
+    softsign_fwd(x),
+    softsign_bwd(x))
+
+UNARY_OP(float, softsign_fwd_f32, softsign_bwd_f32, SoftsignKernelOp,
+    softsign_fwd(x),
+    softsign_bwd(x))
+
+UNARY_OP(double, softsign_fwd_f64, softsign_bwd_f64, SoftsignKernelOp,
+    softsign_fwd(x),
+    softsign_bwd(x))
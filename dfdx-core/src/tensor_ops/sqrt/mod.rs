@@ -6,7 +6,8 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::{try_unary_op, UnaryKernel};
+use super::ops::{try_unary_op, KernelOpInfo, UnaryGraphDerivative, UnaryKernel};
+use super::{Device, TryDiv, TryMul};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -41,6 +42,21 @@ impl<S: Shape, E: Dtype, D: UnaryKernel<SqrtKernelOp, E>, T: Tape<E, D>> Tensor<
     }
 }
 
+impl KernelOpInfo for SqrtKernelOp {
+    const NAME: &'static str = "Sqrt";
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for SqrtKernelOp {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        grad_out.try_div(out.retaped::<OwnedTape<E, D>>().try_mul(2.0)?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{tensor::*, tensor_ops::*, tests::*};
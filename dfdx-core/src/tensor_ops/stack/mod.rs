@@ -108,6 +108,28 @@ impl<D1: Dim, D2: Dim, D3: Dim, D4: Dim, New: Dim> AddDim<New> for (D1, D2, D3,
         (dim, self.0, self.1, self.2, self.3)
     }
 }
+impl<D1: Dim, D2: Dim, D3: Dim, D4: Dim, D5: Dim, New: Dim> AddDim<New> for (D1, D2, D3, D4, D5) {
+    type Larger = (New, D1, D2, D3, D4, D5);
+    fn add_dim(&self, dim: New) -> Self::Larger {
+        (dim, self.0, self.1, self.2, self.3, self.4)
+    }
+}
+impl<D1: Dim, D2: Dim, D3: Dim, D4: Dim, D5: Dim, D6: Dim, New: Dim> AddDim<New>
+    for (D1, D2, D3, D4, D5, D6)
+{
+    type Larger = (New, D1, D2, D3, D4, D5, D6);
+    fn add_dim(&self, dim: New) -> Self::Larger {
+        (dim, self.0, self.1, self.2, self.3, self.4, self.5)
+    }
+}
+impl<D1: Dim, D2: Dim, D3: Dim, D4: Dim, D5: Dim, D6: Dim, D7: Dim, New: Dim> AddDim<New>
+    for (D1, D2, D3, D4, D5, D6, D7)
+{
+    type Larger = (New, D1, D2, D3, D4, D5, D6, D7);
+    fn add_dim(&self, dim: New) -> Self::Larger {
+        (dim, self.0, self.1, self.2, self.3, self.4, self.5, self.6)
+    }
+}
 
 pub trait StackKernel<E: Dtype>: Storage<E> {
     fn forward<S: Shape, Num: Dim>(
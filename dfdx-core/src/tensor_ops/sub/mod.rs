@@ -6,7 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
-use super::ops::*;
+use super::{ops::*, Device};
 use crate::{shapes::*, tensor::*};
 
 #[repr(C)]
@@ -89,6 +89,40 @@ where
     }
 }
 
+impl KernelOpInfo for BinarySubKernelOp {
+    const NAME: &'static str = "BinarySub";
+}
+
+impl<E: Dtype> BinaryGraphDerivative<E> for BinarySubKernelOp {
+    fn try_grads<S: Shape, D: Device<E>>(
+        &self,
+        _lhs: &Tensor<S, E, D>,
+        _rhs: &Tensor<S, E, D>,
+        grad_out: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<(Tensor<S, E, D, OwnedTape<E, D>>, Tensor<S, E, D, OwnedTape<E, D>>), Error> {
+        Ok((grad_out.with_empty_tape(), grad_out.try_negate()?))
+    }
+}
+
+impl<E: Dtype> KernelOpInfo for ScalarSubKernelOp<E> {
+    const NAME: &'static str = "ScalarSub";
+
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        vec![("scalar", Attr::Float(self.scalar.to_f64().unwrap()))]
+    }
+}
+
+impl<E: Dtype> UnaryGraphDerivative<E> for ScalarSubKernelOp<E> {
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        Ok(grad_out)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::tensor::*;
@@ -6,6 +6,7 @@ mod cuda_kernel;
 #[cfg(feature = "webgpu")]
 mod webgpu_kernel;
 
+use super::{BroadcastTo, Device};
 use crate::{shapes::*, tensor::*};
 
 pub trait SumKernel<E: Dtype>: Storage<E> {
@@ -68,18 +69,63 @@ impl<S: Shape, E: Dtype, D: SumKernel<E>, T: Tape<E, D>> SumTo for Tensor<S, E,
         let dst: Dst = self.shape().reduced();
         let (inp, mut tape) = self.split_tape();
         let out = inp.device.forward(dst, &inp)?;
+        if tape.captures_ops() {
+            let node = OpNode::new("Sum").axes::<Ax>().input(&inp).output(&out);
+            tape.add_op_node(node.flops(inp.shape().num_elements()));
+        }
         let inp_ghost = inp.ghost();
         let out_ghost = out.ghost();
+        let graph_op = SumGraphOp::<_, _, Ax, _, _> {
+            inp: inp.ghost(),
+            out: out.ghost(),
+            marker: std::marker::PhantomData,
+        };
         tape.add_backward_op(move |grads| {
             grads.try_alloc_for(&inp_ghost)?;
             grads.try_alloc_for(&out_ghost)?;
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             inp.device.backward(dst, &inp_ghost, grad_inp, grad_out)
         });
+        tape.add_graph_op(graph_op);
         Ok(out.put_tape(tape))
     }
 }
 
+struct SumGraphOp<Src: Shape, Dst: Shape, Ax, E, D: Storage<E>> {
+    inp: GhostTensor<Src, E, D>,
+    out: GhostTensor<Dst, E, D>,
+    marker: std::marker::PhantomData<Ax>,
+}
+
+impl<Src: Shape, Dst: Shape, Ax: Axes + 'static, E: Dtype, D: Storage<E>> GraphBackward<E, D>
+    for SumGraphOp<Src, Dst, Ax, E, D>
+where
+    Src: ReduceShapeTo<Dst, Ax>,
+{
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(grad_out) = grads.grad_out(&self.out)? {
+            let grad_inp = grad_out.try_broadcast_like::<_, Ax>(&self.inp.shape)?;
+            grads.accumulate(&self.inp, grad_inp)?;
+        }
+        Ok(())
+    }
+
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(tangent) = tangents.get(&self.inp)? {
+            tangents.try_set(&self.out, tangent.try_sum::<Dst, Ax>()?)?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,4 +223,26 @@ mod tests {
         let g = c.backward();
         assert_close_to_literal!(g.get(&a), [8.0; 3]);
     }
+
+    #[test]
+    fn test_sum_axes_8d_to_2d() {
+        let dev: TestDevice = Default::default();
+        let t: Tensor<Rank8<2, 3, 1, 2, 3, 2, 1, 4>, TestDtype, _> = dev.sample_normal();
+        let r = t
+            .leaky_trace()
+            .sum::<Rank2<3, 4>, Axes6<0, 2, 3, 4, 5, 6>>();
+        let r2 = t
+            .leaky_trace()
+            .sum::<_, Axes2<5, 6>>()
+            .sum::<_, Axes3<2, 3, 4>>()
+            .sum::<_, Axis<0>>();
+        assert_close_to_tensor!(r, r2, 1e-5);
+        let g = r.square().mean().backward();
+        let g2 = r2.square().mean().backward();
+        assert_close_to_tensor!(
+            g.get(&t).reshape::<Rank2<12, 24>>(),
+            g2.get(&t).reshape::<Rank2<12, 24>>(),
+            1e-5
+        );
+    }
 }
@@ -1,5 +1,7 @@
-use crate::shapes::Rank0;
+use crate::shapes::{Dtype, Rank0, Shape};
 use crate::tensor::*;
+use crate::tensor_ops::{reshape_to::ReshapeKernel, ReshapeTo};
+use std::vec::Vec;
 
 /// Runs backprop algorithm with all operations contained in the tape that `t` has.
 ///
@@ -46,3 +48,203 @@ impl<E: 'static + Clone, D: OneFillStorage<E>> Backward<E, D>
         Ok(grads)
     }
 }
+
+/// Runs backprop from tensors of any shape, with the gradient of the outputs supplied
+/// by `grad_output` instead of filled with ones. This computes the vector-Jacobian
+/// product `grad_output^T * J`.
+///
+/// Implemented for single tensors, tuples of up to 6 tensors, and `Vec`s of tensors,
+/// in which case all the tapes are combined into one backward pass:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+/// let y = x.leaky_trace().square();
+/// let grads = y.backward_with(dev.tensor([1.0, 0.0, -1.0]));
+/// assert_eq!(grads.get(&x).array(), [2.0, 0.0, -6.0]);
+///
+/// let a = x.leaky_trace().square();
+/// let b = x.leaky_trace().sum::<Rank0, _>();
+/// let grads = (a, b).backward_with((dev.tensor([1.0, 0.0, -1.0]), dev.tensor(0.5)));
+/// assert_eq!(grads.get(&x).array(), [2.5, 0.5, -5.5]);
+/// ```
+pub trait BackwardWith<E, D: Storage<E>, Seed>: Sized {
+    /// Runs backprop, seeded with `grad_output`.
+    ///
+    /// **Panics** if the shapes of `grad_output` don't match the outputs.
+    fn backward_with(self, grad_output: Seed) -> Gradients<E, D> {
+        self.try_backward_with(grad_output).unwrap()
+    }
+    /// Fallible version of [BackwardWith::backward_with]
+    fn try_backward_with(self, grad_output: Seed) -> Result<Gradients<E, D>, Error>;
+}
+
+/// Adds an op to `tape` that adds `grad_output` to the gradient of `t`. The gradient is laid
+/// out like the data of `t`, so this sums over broadcasted axes.
+fn try_add_seed<S: Shape, E: Dtype, D: ReshapeKernel<E>>(
+    tape: &mut OwnedTape<E, D>,
+    t: Tensor<S, E, D>,
+    grad_output: Tensor<S, E, D>,
+) -> Result<(), Error> {
+    assert_eq!(t.shape, grad_output.shape);
+    let grad_output = grad_output.try_contiguous()?;
+    let t_ghost = t.ghost();
+    tape.add_backward_op(move |grads| {
+        grads.try_alloc_for(&t_ghost)?;
+        t.device.backward(
+            &t.shape,
+            &t,
+            grads.get_mut(&t_ghost),
+            grad_output.data.as_ref(),
+        )
+    });
+    Ok(())
+}
+
+fn try_execute<E, D: Storage<E>>(tape: &mut OwnedTape<E, D>) -> Result<Gradients<E, D>, Error> {
+    let mut grads = tape.execute()?;
+    grads.drop_non_leafs();
+    Ok(grads)
+}
+
+impl<S: Shape, E: Dtype, D: ReshapeKernel<E>> BackwardWith<E, D, Tensor<S, E, D>>
+    for Tensor<S, E, D, OwnedTape<E, D>>
+{
+    fn try_backward_with(self, grad_output: Tensor<S, E, D>) -> Result<Gradients<E, D>, Error> {
+        let (t, mut tape) = self.split_tape();
+        try_add_seed(&mut tape, t, grad_output)?;
+        try_execute(&mut tape)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Shape, E: Dtype, D: ReshapeKernel<E>> BackwardWith<E, D, Tensor<S, E, D>>
+    for Tensor<S, E, D, std::sync::Arc<std::sync::Mutex<OwnedTape<E, D>>>>
+{
+    fn try_backward_with(self, grad_output: Tensor<S, E, D>) -> Result<Gradients<E, D>, Error> {
+        let (t, tape) = self.split_tape();
+        let mut tape = tape.lock().unwrap();
+        try_add_seed(&mut tape, t, grad_output)?;
+        try_execute(&mut tape)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: ReshapeKernel<E>> BackwardWith<E, D, Vec<Tensor<S, E, D>>>
+    for Vec<Tensor<S, E, D, OwnedTape<E, D>>>
+{
+    fn try_backward_with(
+        self,
+        grad_output: Vec<Tensor<S, E, D>>,
+    ) -> Result<Gradients<E, D>, Error> {
+        assert_eq!(self.len(), grad_output.len());
+        let mut tape = OwnedTape::default();
+        for (t, grad) in self.into_iter().zip(grad_output) {
+            let (t, t_tape) = t.split_tape();
+            tape = tape.merge(t_tape);
+            try_add_seed(&mut tape, t, grad)?;
+        }
+        try_execute(&mut tape)
+    }
+}
+
+macro_rules! tuple_backward_with {
+    ([$($S:ident),+] [$($Idx:tt),+]) => {
+        impl<$($S: Shape, )+ E: Dtype, D: ReshapeKernel<E>>
+            BackwardWith<E, D, ($(Tensor<$S, E, D>, )+)> for ($(Tensor<$S, E, D, OwnedTape<E, D>>, )+)
+        {
+            fn try_backward_with(
+                self,
+                grad_output: ($(Tensor<$S, E, D>, )+),
+            ) -> Result<Gradients<E, D>, Error> {
+                let mut tape = OwnedTape::default();
+                $(
+                    let (t, t_tape) = self.$Idx.split_tape();
+                    tape = tape.merge(t_tape);
+                    try_add_seed(&mut tape, t, grad_output.$Idx)?;
+                )+
+                try_execute(&mut tape)
+            }
+        }
+    };
+}
+
+tuple_backward_with!([S0, S1] [0, 1]);
+tuple_backward_with!([S0, S1, S2] [0, 1, 2]);
+tuple_backward_with!([S0, S1, S2, S3] [0, 1, 2, 3]);
+tuple_backward_with!([S0, S1, S2, S3, S4] [0, 1, 2, 3, 4]);
+tuple_backward_with!([S0, S1, S2, S3, S4, S5] [0, 1, 2, 3, 4, 5]);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_backward_with_matches_weighted_sum() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+
+        let g1 = x.leaky_trace().exp().backward_with(w.clone());
+        let g2 = (x.leaky_trace().exp() * w.clone()).sum().backward();
+        assert_close_to_tensor!(g1.get(&x), g2.get(&x));
+    }
+
+    #[test]
+    fn test_backward_with_strided_outputs() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let seed: Tensor<Rank2<2, 3>, TestDtype, _> =
+            dev.tensor([[1.0, 2.0, 3.0], [-4.0, 5.0, 0.5]]);
+
+        // the gradient of a broadcast is summed over the broadcasted axis
+        let g = x
+            .leaky_trace()
+            .broadcast::<Rank2<2, 3>, _>()
+            .backward_with(seed.clone());
+        assert_close_to_literal!(g.get(&x), [-3.0, 7.0, 3.5]);
+
+        // a unary op of a permuted tensor keeps the permuted strides
+        let y: Tensor<Rank2<3, 2>, TestDtype, _> = dev.sample_normal();
+        let g1 = y
+            .leaky_trace()
+            .permute::<Rank2<2, 3>, _>()
+            .square()
+            .backward_with(seed.clone());
+        let g2 = (y.leaky_trace().permute::<Rank2<2, 3>, _>().square() * seed)
+            .sum()
+            .backward();
+        assert_close_to_tensor!(g1.get(&y), g2.get(&y));
+    }
+
+    #[test]
+    fn test_backward_with_multiple_outputs() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 2>, TestDtype, _> = dev.sample_normal();
+        let seed_a: Tensor<Rank1<2>, TestDtype, _> = dev.tensor([1.0, -2.0]);
+        let seed_b: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([0.5, 1.0, 0.0]);
+
+        let a = x.leaky_trace().matmul(w.clone());
+        let b = x.leaky_trace().sin();
+        let g1 = (a, b).backward_with((seed_a.clone(), seed_b.clone()));
+
+        let a = x.leaky_trace().matmul(w.clone()) * seed_a;
+        let b = x.leaky_trace().sin() * seed_b.clone();
+        let g2 = (a.sum() + b.sum()).backward();
+        assert_close_to_tensor!(g1.get(&x), g2.get(&x));
+        assert_close_to_tensor!(g1.get(&w), g2.get(&w));
+
+        let outs = std::vec![x.leaky_trace().sin(), x.leaky_trace().sin()];
+        let g3 = outs.backward_with(std::vec![seed_b.clone(), seed_b.clone()]);
+        assert_close_to_tensor!(g3.get(&x), x.clone().cos() * seed_b * 2.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_backward_with_wrong_shape() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<(usize,), TestDtype, _> = dev.zeros_like(&(3,));
+        let _ = x.leaky_trace().exp().backward_with(dev.zeros_like(&(4,)));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::shapes::{Dtype, Shape};
+use crate::tensor::*;
+
+impl<S: Shape, E: Dtype, D: Storage<E>> Tensor<S, E, D, OwnedTape<E, D>> {
+    /// Runs `forward` without keeping the intermediate tensors it creates alive, and runs
+    /// `recompute` during the backward pass to create them again. This trades compute for
+    /// memory, since the tape only holds on to this tensor instead of everything `forward`
+    /// would otherwise record.
+    ///
+    /// `recompute` must compute exactly the same thing as `forward`. They are separate
+    /// so that `forward` can borrow what `recompute` has to own, e.g. a module
+    /// and a clone of it. The gradients are identical to those of just calling `forward`.
+    ///
+    /// Example:
+    /// ```rust
+    /// # use dfdx_core::prelude::*;
+    /// # let dev: Cpu = Default::default();
+    /// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+    /// let y = x
+    ///     .leaky_trace()
+    ///     .checkpoint(|x| x.exp().square(), |x| Ok(x.exp().square()));
+    /// let g = y.sum().backward();
+    /// assert_eq!(g.get(&x).array(), (x.clone().exp().square() * 2.0).array());
+    /// ```
+    pub fn checkpoint<Out: Shape, Fwd, Re>(
+        self,
+        forward: Fwd,
+        recompute: Re,
+    ) -> Tensor<Out, E, D, OwnedTape<E, D>>
+    where
+        Fwd: FnOnce(Self) -> Tensor<Out, E, D, OwnedTape<E, D>>,
+        Re: 'static + FnOnce(Self) -> Result<Tensor<Out, E, D, OwnedTape<E, D>>, Error>,
+    {
+        self.try_checkpoint(|x| Ok(forward(x)), recompute).unwrap()
+    }
+
+    /// Fallible version of [Tensor::checkpoint]
+    pub fn try_checkpoint<Out: Shape, Fwd, Re>(
+        self,
+        forward: Fwd,
+        recompute: Re,
+    ) -> Result<Tensor<Out, E, D, OwnedTape<E, D>>, Error>
+    where
+        Fwd: FnOnce(Self) -> Result<Tensor<Out, E, D, OwnedTape<E, D>>, Error>,
+        Re: 'static + FnOnce(Self) -> Result<Tensor<Out, E, D, OwnedTape<E, D>>, Error>,
+    {
+        let (inp, mut tape) = self.split_tape();
+        // the inner tape is dropped right away, along with the tensors its ops hold on to
+        let (out, _) = forward(inp.clone().put_tape(Default::default()))?.split_tape();
+        let out_ghost = out.ghost();
+        tape.add_backward_op(move |grads| {
+            let (recomputed, mut inner) = recompute(inp.put_tape(Default::default()))?.split_tape();
+            grads.copy_grad(&out_ghost, &recomputed)?;
+            inner.execute_into(grads)
+        });
+        Ok(out.put_tape(tape))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_checkpoint_same_gradients() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+
+        let f = |w: Tensor<Rank2<3, 4>, TestDtype, TestDevice>| {
+            move |x: Tensor<Rank2<2, 3>, TestDtype, TestDevice, OwnedTape<_, _>>| {
+                x.try_matmul(w.clone())?.try_tanh()?.try_square()
+            }
+        };
+
+        let y1 = x
+            .leaky_trace()
+            .sin()
+            .try_checkpoint(f(w.clone()), f(w.clone()));
+        let g1 = y1.unwrap().mean().backward();
+        let y2 = f(w.clone())(x.leaky_trace().sin()).unwrap();
+        let g2 = y2.mean().backward();
+        assert_close_to_tensor!(g1.get(&x), g2.get(&x));
+        assert_close_to_tensor!(g1.get(&w), g2.get(&w));
+    }
+
+    #[test]
+    fn test_checkpoint_drops_inner_tape() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let y = x
+            .leaky_trace()
+            .exp()
+            .checkpoint(|x| x.sin().cos().exp(), |x| Ok(x.sin().cos().exp()));
+        // one op for the exp before, one for the checkpoint
+        assert_eq!(y.tape.operations.len(), 2);
+    }
+
+    #[test]
+    fn test_checkpoint_identity() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, 2.0, 3.0]);
+        let y = x.leaky_trace().checkpoint(|x| x, Ok);
+        let g = y.square().sum().backward();
+        assert_close_to_tensor!(g.get(&x), x.clone() * 2.0);
+    }
+}
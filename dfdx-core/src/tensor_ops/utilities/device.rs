@@ -25,6 +25,10 @@ pub trait Device<E: Dtype>:
     + super::super::adam::AdamKernel<E>
     + super::super::sgd::SgdKernel<E>
     + super::super::rmsprop::RMSpropKernel<E>
+    + super::super::adagrad::AdagradKernel<E>
+    + super::super::adadelta::AdadeltaKernel<E>
+    + super::super::lamb::LambKernel<E>
+    + super::super::lion::LionKernel<E>
 
     // allocation
     + crate::tensor::ZerosTensor<E>
@@ -99,6 +103,17 @@ pub trait Device<E: Dtype>:
     + UnaryKernel<super::super::pow::PowfKernelOp<E>, E>
     + UnaryKernel<super::super::pow::PowiKernelOp, E>
     + UnaryKernel<super::super::recip::RecipKernelOp, E>
+    + UnaryKernel<super::super::silu::SiLUKernelOp, E>
+    + UnaryKernel<super::super::mish::MishKernelOp, E>
+    + UnaryKernel<super::super::elu::ELUKernelOp<E>, E>
+    + UnaryKernel<super::super::selu::SELUKernelOp, E>
+    + UnaryKernel<super::super::celu::CELUKernelOp<E>, E>
+    + UnaryKernel<super::super::softplus::SoftplusKernelOp<E>, E>
+    + UnaryKernel<super::super::softsign::SoftsignKernelOp, E>
+    + UnaryKernel<super::super::hardsigmoid::HardSigmoidKernelOp, E>
+    + UnaryKernel<super::super::hardswish::HardSwishKernelOp, E>
+    + UnaryKernel<super::super::hardtanh::HardTanhKernelOp<E>, E>
+    + UnaryKernel<super::super::relu6::ReLU6KernelOp, E>
 
     // to_dtype
     + super::super::to_dtype::ToDtypeKernel<f32, E>
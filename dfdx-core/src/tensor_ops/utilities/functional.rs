@@ -0,0 +1,552 @@
+//! Function transforms built on top of the tape, like [grad()] & [jacobian()], and
+//! per example gradients of linear & convolution layers.
+
+use crate::shapes::{Axes2, Axes3, Axis, Const, Dim, Dtype, Rank0, Shape};
+use crate::tensor::*;
+use crate::tensor_ops::{
+    Backward, BackwardWith, Device, PermuteTo, ReshapeTo, SumTo, TryConv2D, TryMatMul, TryMul,
+    TryStack,
+};
+use std::vec::Vec;
+
+/// Traces `x` with gradients that only keep the gradient of `x`, which is allocated up front
+/// so that it's zeros if the output doesn't depend on `x`.
+fn try_trace_leaf<S: Shape, E: Dtype, D: Device<E>>(
+    x: &Tensor<S, E, D>,
+) -> Result<Tensor<S, E, D, OwnedTape<E, D>>, Error> {
+    let mut grads = Gradients::leaky();
+    grads.try_alloc_for(x)?;
+    grads.retain_current_grads_as_leafs();
+    Ok(x.trace(grads))
+}
+
+/// Turns a scalar function `f` into a function that computes the gradient of `f` at `x`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let df = grad(|x: Tensor<Rank1<3>, f32, Cpu, OwnedTape<f32, Cpu>>| x.square().sum());
+/// let x = dev.tensor([1.0, 2.0, 3.0]);
+/// assert_eq!(df(x).array(), [2.0, 4.0, 6.0]);
+/// ```
+pub fn grad<S: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+) -> impl Fn(Tensor<S, E, D>) -> Tensor<S, E, D>
+where
+    F: Fn(Tensor<S, E, D, OwnedTape<E, D>>) -> Tensor<Rank0, E, D, OwnedTape<E, D>>,
+{
+    let df = try_grad(move |x| Ok(f(x)));
+    move |x| df(x).unwrap()
+}
+
+/// Fallible version of [grad()]
+#[allow(clippy::type_complexity)]
+pub fn try_grad<S: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+) -> impl Fn(Tensor<S, E, D>) -> Result<Tensor<S, E, D>, Error>
+where
+    F: Fn(Tensor<S, E, D, OwnedTape<E, D>>) -> Result<Tensor<Rank0, E, D, OwnedTape<E, D>>, Error>,
+{
+    move |x| {
+        let grads = f(try_trace_leaf(&x)?)?.try_backward()?;
+        Ok(grads.get(&x))
+    }
+}
+
+/// Computes the full Jacobian of `f` at `x`, with one seeded backward pass
+/// (see [BackwardWith]) per element of the output. `f` is called again for each of them,
+/// since a backward pass consumes the tape.
+///
+/// The Jacobian is returned as a matrix, where row `i` is the gradient of the `i`th
+/// element of the output, and column `j` is the `j`th element of `x`. Both are counted
+/// in row major order. For a function with more outputs than inputs, forward mode (see
+/// [crate::tensor::jvp]) needs fewer passes.
+///
+/// Example:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let w: Tensor<Rank2<3, 2>, f32, _> = dev.tensor([[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]);
+/// let x: Tensor<Rank1<3>, f32, _> = dev.zeros();
+/// let j = jacobian(|x| x.matmul(w.clone()), x);
+/// assert_eq!(j.as_vec(), w.permute::<Rank2<2, 3>, _>().as_vec());
+/// ```
+pub fn jacobian<S: Shape, Out: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: Tensor<S, E, D>,
+) -> Tensor<(usize, usize), E, D>
+where
+    F: Fn(Tensor<S, E, D, OwnedTape<E, D>>) -> Tensor<Out, E, D, OwnedTape<E, D>>,
+{
+    try_jacobian(|x| Ok(f(x)), x).unwrap()
+}
+
+/// Fallible version of [jacobian()]
+pub fn try_jacobian<S: Shape, Out: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: Tensor<S, E, D>,
+) -> Result<Tensor<(usize, usize), E, D>, Error>
+where
+    F: Fn(Tensor<S, E, D, OwnedTape<E, D>>) -> Result<Tensor<Out, E, D, OwnedTape<E, D>>, Error>,
+{
+    let num_inputs = x.shape.num_elements();
+    let mut rows = Vec::new();
+    loop {
+        let y = f(try_trace_leaf(&x)?)?;
+        let num_outputs = y.shape.num_elements();
+        if num_outputs == 0 {
+            return x.device.try_zeros_like(&(0, num_inputs));
+        }
+        let mut seed = std::vec![Default::default(); num_outputs];
+        seed[rows.len()] = E::ONE;
+        let seed = x.device.try_tensor_from_vec(seed, y.shape)?;
+        let grads = y.try_backward_with(seed)?;
+        rows.push(grads.get(&x).try_reshape_like(&(num_inputs,))?);
+        if rows.len() == num_outputs {
+            return rows.try_stack();
+        }
+    }
+}
+
+/// Computes the Hessian of the scalar function `f` at `x`, multiplied by `v`, without
+/// computing the Hessian itself. This differentiates the gradient of `f` along `v` again,
+/// so `f` must only use ops supported by [Tensor::record_graph].
+///
+/// Example:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let x: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 2.0, 3.0]);
+/// let v: Tensor<Rank1<3>, f32, _> = dev.tensor([1.0, 0.0, -1.0]);
+/// // the Hessian of sum(x^3) is diag(6x)
+/// let hv = hessian_vector_product(|x| x.powi(3).sum(), x, v);
+/// assert_eq!(hv.array(), [6.0, 0.0, -18.0]);
+/// ```
+pub fn hessian_vector_product<S: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: Tensor<S, E, D>,
+    v: Tensor<S, E, D>,
+) -> Tensor<S, E, D>
+where
+    F: FnOnce(Tensor<S, E, D, OwnedTape<E, D>>) -> Tensor<Rank0, E, D, OwnedTape<E, D>>,
+{
+    try_hessian_vector_product(|x| Ok(f(x)), x, v).unwrap()
+}
+
+/// Fallible version of [hessian_vector_product()]. Returns
+/// [Error::UnsupportedGraphBackward] if `f` uses an op that [Tensor::record_graph] doesn't
+/// support.
+pub fn try_hessian_vector_product<S: Shape, E: Dtype, D: Device<E>, F>(
+    f: F,
+    x: Tensor<S, E, D>,
+    v: Tensor<S, E, D>,
+) -> Result<Tensor<S, E, D>, Error>
+where
+    F: FnOnce(
+        Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<Rank0, E, D, OwnedTape<E, D>>, Error>,
+{
+    assert_eq!(x.shape, v.shape);
+    let y = f(try_trace_leaf(&x)?.record_graph())?;
+    match y.try_backward_with_graph()?.try_get(&x)? {
+        Some(grad) => Ok(grad.try_mul(v)?.try_sum()?.try_backward()?.get(&x)),
+        None => x.device.try_zeros_like(&x.shape),
+    }
+}
+
+/// The shapes of the input of a linear layer (e.g. `dfdx::nn::Linear`), which has
+/// a batch dimension, and optionally a sequence dimension that gradients are summed over.
+pub trait LinearPerExampleShape<Out: Dim>: Shape {
+    type Batch: Dim;
+    type In: Dim;
+    /// The shape of the output, with `Out` features instead of `Self::In`.
+    type Output: Shape;
+    /// The batch size, sequence length and number of input & output features.
+    fn per_example_dims(&self, out: &Self::Output) -> (Self::Batch, usize, Self::In, Out);
+}
+
+impl<B: Dim, I: Dim, O: Dim> LinearPerExampleShape<O> for (B, I) {
+    type Batch = B;
+    type In = I;
+    type Output = (B, O);
+    fn per_example_dims(&self, out: &Self::Output) -> (B, usize, I, O) {
+        assert_eq!(self.0, out.0);
+        (self.0, 1, self.1, out.1)
+    }
+}
+
+impl<B: Dim, S: Dim, I: Dim, O: Dim> LinearPerExampleShape<O> for (B, S, I) {
+    type Batch = B;
+    type In = I;
+    type Output = (B, S, O);
+    fn per_example_dims(&self, out: &Self::Output) -> (B, usize, I, O) {
+        assert_eq!((self.0, self.1), (out.0, out.1));
+        (self.0, self.1.size(), self.2, out.2)
+    }
+}
+
+/// The gradients of the weight & bias of a linear layer for each example of a batch,
+/// e.g. for differentially private SGD. This only takes the input of the layer & the gradient
+/// of its output, so a single backward pass of the whole batch is enough.
+///
+/// The weight gradients have shape `(Batch, Out, In)`, like a batch of weights of
+/// `dfdx::nn::Linear`, and the bias gradients have shape `(Batch, Out)`. If there is
+/// a sequence dimension, the gradients of each example are summed over it.
+///
+/// Example:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let weight: Tensor<Rank2<2, 3>, f32, _> = dev.sample_normal();
+/// let x: Tensor<Rank2<4, 3>, f32, _> = dev.sample_normal();
+/// let y = x.leaky_trace().matmul(weight.clone().permute());
+/// // keeps the id of `y`, to get its gradient
+/// let y_ghost = y.retaped::<NoneTape>();
+/// let grads = y.square().sum().backward();
+/// let (grad_weight, grad_bias) = linear_per_example_grads(x, grads.get(&y_ghost));
+/// assert_eq!(grad_weight.shape(), &(Const::<4>, Const::<2>, Const::<3>));
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn linear_per_example_grads<S, O: Dim, E: Dtype, D: Device<E>>(
+    x: Tensor<S, E, D>,
+    grad_out: Tensor<S::Output, E, D>,
+) -> (
+    Tensor<(S::Batch, O, S::In), E, D>,
+    Tensor<(S::Batch, O), E, D>,
+)
+where
+    S: LinearPerExampleShape<O>,
+{
+    try_linear_per_example_grads(x, grad_out).unwrap()
+}
+
+/// Fallible version of [linear_per_example_grads()]
+#[allow(clippy::type_complexity)]
+pub fn try_linear_per_example_grads<S, O: Dim, E: Dtype, D: Device<E>>(
+    x: Tensor<S, E, D>,
+    grad_out: Tensor<S::Output, E, D>,
+) -> Result<
+    (
+        Tensor<(S::Batch, O, S::In), E, D>,
+        Tensor<(S::Batch, O), E, D>,
+    ),
+    Error,
+>
+where
+    S: LinearPerExampleShape<O>,
+{
+    let (batch, seq, inp, out) = x.shape.per_example_dims(&grad_out.shape);
+    let x = x.try_reshape_like(&(batch, seq, inp))?;
+    let grad_out = grad_out.try_reshape_like(&(batch, seq, out))?;
+    let grad_bias = grad_out.clone().try_sum::<_, Axis<1>>()?;
+    let grad_weight = grad_out.try_permute::<_, Axes3<0, 2, 1>>()?.try_matmul(x)?;
+    Ok((grad_weight, grad_bias))
+}
+
+/// The gradients of the weight & bias of a 2d convolution layer (e.g. `dfdx::nn::Conv2D`)
+/// for each example of a batch, from the input of the layer & the gradient of its output.
+/// See [linear_per_example_grads()].
+///
+/// The weight gradients have shape `(Batch, OutChan, InpChan / Groups, Kernel, Kernel)`
+/// and the bias gradients have shape `(Batch, OutChan)`. They are computed with a single
+/// convolution, which treats every example as a separate group.
+///
+/// Example:
+/// ```rust
+/// # use dfdx_core::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let weight: Tensor<Rank4<4, 3, 3, 3>, f32, _> = dev.sample_normal();
+/// let x: Tensor<Rank4<2, 3, 8, 8>, f32, _> = dev.sample_normal();
+/// let y = (x.leaky_trace(), weight.clone()).conv2d(1, 0, 1, 1);
+/// let y_ghost = y.retaped::<NoneTape>();
+/// let grads = y.square().sum().backward();
+/// let (grad_weight, grad_bias) =
+///     conv2d_per_example_grads(x, grads.get(&y_ghost), Const::<3>, 1, 0, 1, 1);
+/// assert_eq!(grad_weight.shape(), &(Const::<2>, Const::<4>, 3, Const::<3>, Const::<3>));
+/// ```
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn conv2d_per_example_grads<
+    B: Dim,
+    C: Dim,
+    H: Dim,
+    W: Dim,
+    O: Dim,
+    OutH: Dim,
+    OutW: Dim,
+    K: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    Groups: Dim,
+    E: Dtype,
+    D: Device<E>,
+>(
+    x: Tensor<(B, C, H, W), E, D>,
+    grad_out: Tensor<(B, O, OutH, OutW), E, D>,
+    kernel: K,
+    stride: Stride,
+    padding: Padding,
+    dilation: Dilation,
+    groups: Groups,
+) -> (Tensor<(B, O, usize, K, K), E, D>, Tensor<(B, O), E, D>)
+where
+    (
+        Tensor<(Const<1>, usize, usize, usize), E, D, OwnedTape<E, D>>,
+        Tensor<(usize, usize, usize, usize), E, D>,
+    ): TryConv2D<
+        usize,
+        usize,
+        usize,
+        usize,
+        Convolved = Tensor<(Const<1>, usize, usize, usize), E, D, OwnedTape<E, D>>,
+    >,
+{
+    try_conv2d_per_example_grads(x, grad_out, kernel, stride, padding, dilation, groups).unwrap()
+}
+
+/// Fallible version of [conv2d_per_example_grads()]
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn try_conv2d_per_example_grads<
+    B: Dim,
+    C: Dim,
+    H: Dim,
+    W: Dim,
+    O: Dim,
+    OutH: Dim,
+    OutW: Dim,
+    K: Dim,
+    Stride: Dim,
+    Padding: Dim,
+    Dilation: Dim,
+    Groups: Dim,
+    E: Dtype,
+    D: Device<E>,
+>(
+    x: Tensor<(B, C, H, W), E, D>,
+    grad_out: Tensor<(B, O, OutH, OutW), E, D>,
+    kernel: K,
+    stride: Stride,
+    padding: Padding,
+    dilation: Dilation,
+    groups: Groups,
+) -> Result<(Tensor<(B, O, usize, K, K), E, D>, Tensor<(B, O), E, D>), Error>
+where
+    (
+        Tensor<(Const<1>, usize, usize, usize), E, D, OwnedTape<E, D>>,
+        Tensor<(usize, usize, usize, usize), E, D>,
+    ): TryConv2D<
+        usize,
+        usize,
+        usize,
+        usize,
+        Convolved = Tensor<(Const<1>, usize, usize, usize), E, D, OwnedTape<E, D>>,
+    >,
+{
+    let (batch, inp_chan, h, w) = x.shape;
+    let (_, out_chan, out_h, out_w) = grad_out.shape;
+    assert_eq!(batch, grad_out.shape.0);
+    assert_eq!(inp_chan.size() % groups.size(), 0);
+    let (b, k, g) = (batch.size(), kernel.size(), groups.size());
+    let inp_chan_over_groups = inp_chan.size() / g;
+
+    // every example is convolved with its own copy of the filters, so the gradients of
+    // the copies are the gradients of each example
+    let x = x.try_reshape_like(&(Const::<1>, b * inp_chan.size(), h.size(), w.size()))?;
+    let filters = x
+        .device
+        .try_zeros_like(&(b * out_chan.size(), inp_chan_over_groups, k, k))?;
+    let y = (x.leaky_traced(), filters.clone()).try_conv2d(
+        stride.size(),
+        padding.size(),
+        dilation.size(),
+        b * g,
+    )?;
+    assert_eq!(
+        (y.shape.2, y.shape.3),
+        (out_h.size(), out_w.size()),
+        "The output of the convolution doesn't match the shape of grad_out"
+    );
+    let shape = y.shape;
+    let grads = y.try_backward_with(grad_out.clone().try_reshape_like(&shape)?)?;
+    let grad_filters = grads.get(&filters).try_reshape_like(&(
+        batch,
+        out_chan,
+        inp_chan_over_groups,
+        kernel,
+        kernel,
+    ))?;
+    let grad_bias = grad_out.try_sum::<_, Axes2<2, 3>>()?;
+    Ok((grad_filters, grad_bias))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{shapes::*, tensor_ops::*, tests::*};
+
+    #[test]
+    fn test_grad_matches_backward() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let w: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+
+        let df = grad(
+            |x: Tensor<Rank2<2, 3>, TestDtype, TestDevice, OwnedTape<_, _>>| {
+                x.matmul(w.clone()).tanh().mean()
+            },
+        );
+        let g = x.leaky_trace().matmul(w.clone()).tanh().mean().backward();
+        assert_close_to_tensor!(df(x.clone()), g.get(&x));
+    }
+
+    #[test]
+    fn test_jacobian() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.tensor([1.0, -2.0, 0.5]);
+
+        // the jacobian of an elementwise function is diagonal
+        let j: Tensor<Rank2<3, 3>, _, _> = jacobian(|x| x.square(), x.clone()).realize();
+        assert_close_to_literal!(j, [[2.0, 0.0, 0.0], [0.0, -4.0, 0.0], [0.0, 0.0, 1.0]]);
+
+        let w: Tensor<Rank2<3, 2>, TestDtype, _> = dev.sample_normal();
+        let j = jacobian(|x| x.matmul(w.clone()), x.clone());
+        assert_eq!(j.shape, (2, 3));
+        let j: Tensor<Rank2<2, 3>, _, _> = j.realize();
+        assert_close_to_tensor!(j, w.clone().permute::<Rank2<2, 3>, _>());
+
+        // rows & columns are in row major order
+        let x: Tensor<Rank2<2, 2>, TestDtype, _> = dev.tensor([[1.0, 2.0], [3.0, 4.0]]);
+        let j: Tensor<Rank2<2, 4>, _, _> = jacobian(|x| x.sum::<Rank1<2>, Axis<0>>(), x).realize();
+        assert_close_to_literal!(j, [[1.0, 0.0, 1.0, 0.0], [0.0, 1.0, 0.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_hessian_vector_product() {
+        let dev: TestDevice = Default::default();
+        let a: Tensor<Rank2<3, 3>, TestDtype, _> = dev.sample_normal();
+        let x: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+        let v: Tensor<Rank1<3>, TestDtype, _> = dev.sample_normal();
+
+        // the hessian of x^T A x is A + A^T
+        let hv = hessian_vector_product(
+            |x| {
+                let xa = x.with_empty_tape().matmul(a.clone());
+                (xa * x).sum()
+            },
+            x.clone(),
+            v.clone(),
+        );
+        let h = a.clone() + a.clone().permute();
+        assert_close_to_tensor!(hv, h.matmul(v.clone()), 1e-4);
+
+        // the hessian of a linear function is zero
+        let hv = hessian_vector_product(|x| (x * 2.0).sum(), x.clone(), v.clone());
+        assert_close_to_literal!(hv, [0.0; 3]);
+
+        let res = try_hessian_vector_product(|x| Ok(x.fast_gelu().sum()), x, v);
+        assert!(matches!(res, Err(Error::UnsupportedGraphBackward)));
+    }
+
+    #[test]
+    fn test_linear_per_example_grads() {
+        let dev: TestDevice = Default::default();
+        let weight: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let bias: Tensor<Rank1<2>, TestDtype, _> = dev.sample_normal();
+        let x: Tensor<Rank2<4, 3>, TestDtype, _> = dev.sample_normal();
+
+        let linear = |x: Tensor<(usize, Const<3>), TestDtype, TestDevice, OwnedTape<_, _>>| {
+            let y = x.matmul(weight.clone().permute());
+            let shape = y.shape;
+            y + bias.clone().broadcast_like(&shape)
+        };
+        let y = linear(x.leaky_trace().realize());
+        let y_ghost = y.retaped::<NoneTape>();
+        let grads = y.tanh().sum().backward();
+        let grad_out: Tensor<Rank2<4, 2>, _, _> = grads.get(&y_ghost).realize();
+        let (grad_weight, grad_bias) = linear_per_example_grads(x.clone(), grad_out);
+
+        assert_close_to_tensor!(
+            grad_weight.clone().sum::<Rank2<2, 3>, _>(),
+            grads.get(&weight)
+        );
+        assert_close_to_tensor!(
+            grad_bias.clone().sum::<Rank1<2>, Axis<0>>(),
+            grads.get(&bias)
+        );
+        for i in 0..4 {
+            let xi = x.clone().slice((i..i + 1, ..));
+            let grads = linear(xi.leaky_trace()).tanh().sum().backward();
+            let gw = grad_weight.clone().select(dev.tensor(i));
+            let gb = grad_bias.clone().select(dev.tensor(i));
+            assert_close_to_tensor!(gw, grads.get(&weight));
+            assert_close_to_tensor!(gb, grads.get(&bias));
+        }
+    }
+
+    #[test]
+    fn test_linear_per_example_grads_with_sequence() {
+        let dev: TestDevice = Default::default();
+        let weight: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let x: Tensor<Rank3<4, 5, 3>, TestDtype, _> = dev.sample_normal();
+
+        let y = x.leaky_trace().matmul(weight.clone().permute());
+        let y_ghost = y.retaped::<NoneTape>();
+        let grads = y.square().sum().backward();
+        let (grad_weight, grad_bias) = linear_per_example_grads(x.clone(), grads.get(&y_ghost));
+        assert_eq!(grad_weight.shape, (Const::<4>, Const::<2>, Const::<3>));
+        assert_eq!(grad_bias.shape, (Const::<4>, Const::<2>));
+        assert_close_to_tensor!(
+            grad_weight.clone().sum::<Rank2<2, 3>, _>(),
+            grads.get(&weight)
+        );
+
+        let x0 = x.select(dev.tensor(0));
+        let grads = x0
+            .leaky_trace()
+            .matmul(weight.clone().permute())
+            .square()
+            .sum()
+            .backward();
+        let gw = grad_weight.select(dev.tensor(0));
+        assert_close_to_tensor!(gw, grads.get(&weight));
+    }
+
+    #[test]
+    fn test_conv2d_per_example_grads() {
+        let dev: TestDevice = Default::default();
+        let weight: Tensor<Rank4<4, 1, 3, 3>, TestDtype, _> = dev.sample_normal();
+        let x: Tensor<Rank4<3, 2, 6, 5>, TestDtype, _> = dev.sample_normal();
+
+        let conv = |x: Tensor<(usize, Const<2>, Const<6>, Const<5>), _, _, _>| {
+            (x, weight.clone()).conv2d(2, 1, 1, 2)
+        };
+        let y = conv(x.leaky_trace().realize());
+        let y_ghost = y.retaped::<NoneTape>();
+        let grads = y.square().sum().backward();
+        let grad_out: Tensor<Rank4<3, 4, 3, 3>, _, _> = grads.get(&y_ghost).realize();
+        let (grad_weight, grad_bias) =
+            conv2d_per_example_grads(x.clone(), grad_out.clone(), Const::<3>, 2, 1, 1, 2);
+        assert_eq!(
+            grad_weight.shape,
+            (Const::<3>, Const::<4>, 1, Const::<3>, Const::<3>)
+        );
+        assert_eq!(grad_bias.shape, (Const::<3>, Const::<4>));
+
+        let grad_weight: Tensor<Rank5<3, 4, 1, 3, 3>, _, _> = grad_weight.realize();
+        for i in 0..3 {
+            let xi = x.clone().slice((i..i + 1, .., .., ..));
+            let grads = conv(xi.leaky_trace()).square().sum().backward();
+            let gw = grad_weight
+                .clone()
+                .select(dev.tensor(i))
+                .reshape::<Rank4<4, 1, 3, 3>>();
+            assert_close_to_tensor!(gw, grads.get(&weight), 1e-4);
+            let gb = grad_out
+                .clone()
+                .slice((i..i + 1, .., .., ..))
+                .sum::<Rank1<4>, _>();
+            assert_close_to_tensor!(grad_bias.clone().select(dev.tensor(i)), gb);
+        }
+    }
+}
@@ -1,12 +1,19 @@
 mod backward;
+mod checkpoint;
 pub(crate) mod cpu_kernels;
 #[cfg(feature = "cuda")]
 pub(crate) mod cuda_kernels;
 mod device;
+mod functional;
 pub(crate) mod ops;
 pub(crate) mod reduction_utils;
 #[cfg(feature = "webgpu")]
 pub(crate) mod webgpu_kernels;
 
-pub use backward::Backward;
+pub use backward::{Backward, BackwardWith};
 pub use device::Device;
+pub use functional::{
+    conv2d_per_example_grads, grad, hessian_vector_product, jacobian, linear_per_example_grads,
+    try_conv2d_per_example_grads, try_grad, try_hessian_vector_product, try_jacobian,
+    try_linear_per_example_grads, LinearPerExampleShape,
+};
@@ -1,8 +1,10 @@
+use super::Device;
+use crate::tensor_ops::TryAdd;
 use crate::{
     shapes::{Dtype, HasShape, Shape},
     tensor::*,
 };
-use std::borrow::Cow;
+use std::{borrow::Cow, vec::Vec};
 
 pub trait UnaryKernel<Op, E: Dtype>: Storage<E> {
     const BACKWARD_WITHOUT_INP: bool;
@@ -41,8 +43,150 @@ pub trait BinaryKernel<Op, E: Dtype>: Storage<E> {
     ) -> Result<(), Error>;
 }
 
+/// How an elementwise kernel op is recorded by a [CaptureTape], as an [OpNode].
+pub trait KernelOpInfo {
+    /// The name of the op, e.g. `Exp` or `ScalarAdd`.
+    const NAME: &'static str;
+
+    /// The attributes of the op, like the `scalar` of `ScalarAdd`.
+    fn attrs(&self) -> Vec<(&'static str, Attr)> {
+        Vec::new()
+    }
+}
+
+/// The backward pass of a unary op written with tensor ops, see [GraphBackward].
+///
+/// Ops that don't override this can't be differentiated twice.
+pub trait UnaryGraphDerivative<E: Dtype> {
+    /// The gradient of the input given the gradient of the output. Unary ops work on
+    /// the data buffer directly, so all of these are flat views of the data.
+    #[allow(clippy::type_complexity)]
+    fn try_grad_inp<D: Device<E>>(
+        &self,
+        _inp: &Tensor<(usize,), E, D>,
+        _out: &Tensor<(usize,), E, D>,
+        _grad_out: Tensor<(usize,), E, D, OwnedTape<E, D>>,
+    ) -> Result<Tensor<(usize,), E, D, OwnedTape<E, D>>, Error> {
+        Err(Error::UnsupportedGraphBackward)
+    }
+}
+
+/// The backward pass of a binary op written with tensor ops, see [GraphBackward].
+///
+/// Ops that don't override this can't be differentiated twice.
+pub trait BinaryGraphDerivative<E: Dtype> {
+    /// The gradients of the inputs given the gradient of the output.
+    #[allow(clippy::type_complexity)]
+    fn try_grads<S: Shape, D: Device<E>>(
+        &self,
+        _lhs: &Tensor<S, E, D>,
+        _rhs: &Tensor<S, E, D>,
+        _grad_out: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<
+        (
+            Tensor<S, E, D, OwnedTape<E, D>>,
+            Tensor<S, E, D, OwnedTape<E, D>>,
+        ),
+        Error,
+    > {
+        Err(Error::UnsupportedGraphBackward)
+    }
+}
+
+struct UnaryGraphOp<Op, E, D: Storage<E>> {
+    op: Op,
+    inp: Tensor<(usize,), E, D>,
+    out: Tensor<(usize,), E, D>,
+}
+
+impl<Op: 'static + UnaryGraphDerivative<E>, E: Dtype, D: Storage<E>> GraphBackward<E, D>
+    for UnaryGraphOp<Op, E, D>
+{
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(grad_out) = grads.flat_grad(&self.out) {
+            let grad_inp = self.op.try_grad_inp(&self.inp, &self.out, grad_out)?;
+            grads.accumulate_flat(&self.inp, grad_inp)?;
+        }
+        Ok(())
+    }
+
+    /// The Jacobian is diagonal, so this is the same as the backward pass.
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(tangent) = tangents.get_flat(&self.inp) {
+            let tangent =
+                self.op
+                    .try_grad_inp(&self.inp, &self.out, tangent.put_tape(Default::default()))?;
+            tangents.set_flat(&self.out, tangent.split_tape().0);
+        }
+        Ok(())
+    }
+}
+
+struct BinaryGraphOp<Op, S: Shape, E, D: Storage<E>> {
+    op: Op,
+    lhs: Tensor<S, E, D>,
+    rhs: Tensor<S, E, D>,
+    out: GhostTensor<S, E, D>,
+}
+
+impl<Op: 'static + BinaryGraphDerivative<E>, S: Shape, E: Dtype, D: Storage<E>> GraphBackward<E, D>
+    for BinaryGraphOp<Op, S, E, D>
+{
+    fn backward(self: Box<Self>, grads: &mut GraphGradients<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        if let Some(grad_out) = grads.grad_out(&self.out)? {
+            let (grad_lhs, grad_rhs) = self.op.try_grads(&self.lhs, &self.rhs, grad_out)?;
+            grads.accumulate(&self.lhs, grad_lhs)?;
+            grads.accumulate(&self.rhs, grad_rhs)?;
+        }
+        Ok(())
+    }
+
+    /// The Jacobians are diagonal, so the backward pass with each input's tangent gives
+    /// its part of the output's tangent.
+    fn jvp(&self, tangents: &mut Tangents<E, D>) -> Result<(), Error>
+    where
+        E: Dtype,
+        D: Device<E>,
+    {
+        let lhs_part = match tangents.get(&self.lhs)? {
+            Some(t) => Some(
+                self.op
+                    .try_grads(&self.lhs, &self.rhs, t.put_tape(Default::default()))?
+                    .0,
+            ),
+            None => None,
+        };
+        let rhs_part = match tangents.get(&self.rhs)? {
+            Some(t) => Some(
+                self.op
+                    .try_grads(&self.lhs, &self.rhs, t.put_tape(Default::default()))?
+                    .1,
+            ),
+            None => None,
+        };
+        let tangent = match (lhs_part, rhs_part) {
+            (Some(l), Some(r)) => l.try_add(r)?,
+            (Some(t), None) | (None, Some(t)) => t,
+            (None, None) => return Ok(()),
+        };
+        tangents.try_set(&self.out, tangent.split_tape().0)
+    }
+}
+
 pub(crate) fn try_unary_op<
-    Op: 'static + Clone,
+    Op: 'static + Clone + KernelOpInfo + UnaryGraphDerivative<E>,
     S: Shape,
     E: Dtype,
     D: UnaryKernel<Op, E>,
@@ -52,9 +196,13 @@ pub(crate) fn try_unary_op<
     inp: Tensor<S, E, D, T>,
 ) -> Result<Tensor<S, E, D, T>, crate::tensor::Error> {
     let (inp, mut tape) = inp.split_tape();
+    let graph_op = tape.records_graph().then(|| (op.clone(), inp.clone()));
+    let node = tape
+        .captures_ops()
+        .then(|| OpNode::from_kernel_op(&op).input(&inp));
     let inp_ghost = inp.ghost();
     let dev = inp.device.clone();
-    if !T::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
+    let out = if !T::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
         let out = inp_ghost.dev.forward(op.clone(), Cow::Owned(inp))?;
         let out_ghost = out.ghost();
         tape.add_backward_op(move |grads| {
@@ -63,7 +211,7 @@ pub(crate) fn try_unary_op<
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             dev.backward(op, &inp_ghost, grad_inp, &out_ghost, grad_out)
         });
-        Ok(out.put_tape(tape))
+        out
     } else if D::BACKWARD_WITHOUT_INP {
         let out = inp_ghost.dev.forward(op.clone(), Cow::Owned(inp))?;
         let out_ghost = out.ghost();
@@ -74,7 +222,7 @@ pub(crate) fn try_unary_op<
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             dev.backward(op, &inp_ghost, grad_inp, &out_clone, grad_out)
         });
-        Ok(out.put_tape(tape))
+        out
     } else {
         let out = inp.device.forward(op.clone(), Cow::Borrowed(&inp))?;
         let out_ghost = out.ghost();
@@ -84,12 +232,23 @@ pub(crate) fn try_unary_op<
             let (grad_inp, grad_out) = grads.mut_and_ref(&inp_ghost, &out_ghost);
             dev.backward(op, &inp, grad_inp, &out_ghost, grad_out)
         });
-        Ok(out.put_tape(tape))
+        out
+    };
+    if let Some((op, inp)) = graph_op {
+        tape.add_graph_op(UnaryGraphOp {
+            op,
+            inp: inp.flattened(),
+            out: out.flattened(),
+        });
+    }
+    if let Some(node) = node {
+        tape.add_op_node(node.elementwise(&out));
     }
+    Ok(out.put_tape(tape))
 }
 
 pub(crate) fn try_binary_op<
-    Op: 'static + Copy,
+    Op: 'static + Copy + KernelOpInfo + BinaryGraphDerivative<E>,
     S: Shape,
     E: Dtype,
     D: BinaryKernel<Op, E>,
@@ -106,7 +265,11 @@ pub(crate) fn try_binary_op<
     let lhs_ghost = lhs.ghost();
     let rhs_ghost = rhs.ghost();
     let mut tape = ltape.merge(rtape);
-    if !LhsTape::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
+    let graph_inps = tape.records_graph().then(|| (lhs.clone(), rhs.clone()));
+    let node = tape
+        .captures_ops()
+        .then(|| OpNode::from_kernel_op(&op).input(&lhs).input(&rhs));
+    let out = if !LhsTape::OWNS_TAPE || D::BACKWARD_WITHOUT_DATA {
         let out = lhs_ghost
             .dev
             .forward(op, Cow::Owned(lhs), Cow::Owned(rhs))?;
@@ -121,7 +284,7 @@ pub(crate) fn try_binary_op<
                 .dev
                 .backward(op, &lhs_ghost, grad_lhs, &rhs_ghost, grad_rhs, grad_out)
         });
-        Ok(out.put_tape(tape))
+        out
     } else {
         let out = lhs
             .device
@@ -136,6 +299,18 @@ pub(crate) fn try_binary_op<
             lhs.device
                 .backward(op, &lhs, grad_lhs, &rhs, grad_rhs, grad_out)
         });
-        Ok(out.put_tape(tape))
+        out
+    };
+    if let Some((lhs, rhs)) = graph_inps {
+        tape.add_graph_op(BinaryGraphOp {
+            op,
+            lhs,
+            rhs,
+            out: out.ghost(),
+        });
+    }
+    if let Some(node) = node {
+        tape.add_op_node(node.elementwise(&out));
     }
+    Ok(out.put_tape(tape))
 }
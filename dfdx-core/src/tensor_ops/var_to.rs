@@ -33,7 +33,7 @@ impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> VarTo for Tensor<S, E, D,
         Self::Shape: HasAxes<Ax> + ReduceShapeTo<Dst, Ax>,
     {
         let mean = self
-            .retaped::<T>()
+            .with_empty_tape()
             .try_mean::<Dst, Ax>()?
             .try_broadcast_like(self.shape())?;
         mean.try_sub(self)?.try_square()?.try_mean()
@@ -13,8 +13,10 @@ macro_rules! has_attr {
 /// 2. [dfdx::nn_traits::ResetParams]
 /// 3. [dfdx::nn_traits::UpdateParams]
 /// 4. [dfdx::nn_traits::ZeroGrads]
-/// 5. [dfdx::nn_traits::SaveSafeTensors]
-/// 6. [dfdx::nn_traits::LoadSafeTensors]
+/// 5. [dfdx::nn_traits::WithGrads]
+/// 6. [dfdx::nn_traits::VisitParams]
+/// 7. [dfdx::nn_traits::SaveSafeTensors]
+/// 8. [dfdx::nn_traits::LoadSafeTensors]
 ///
 /// If your struct contains sub module configs, then you must add the `#[module]` attribute to those items. Any field that is marked with `#[module]` will be expected to implement [dfdx::nn_traits::BuildOnDevice].
 ///
@@ -175,11 +177,11 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                 quote!()
             };
             quote! {
-                #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, #safetensors_derive)]
+                #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, ::dfdx::WithGrads, ::dfdx::VisitParams, #safetensors_derive)]
                 pub struct #built_name #built_impl #built_where #fields
             }
         } else {
-            // there are no fields to build - we still have to derive ResetParams/UpdateParams/ZeroGrads, but since
+            // there are no fields to build - we still have to derive ResetParams/UpdateParams/ZeroGrads/WithGrads/VisitParams, but since
             // there aren't any fields, they will just be passthrough impls
             let mut build_generics = built_generics.clone();
             if !has_fields_to_build {
@@ -242,6 +244,26 @@ pub fn custom_module(input: proc_macro::TokenStream) -> proc_macro::TokenStream
                         Ok(())
                     }
                 }
+
+                impl #build_impl ::dfdx::nn_traits::WithGrads<Elem, Dev> for #builder_name #built_ty #built_where {
+                    fn try_visit_grads<_Visitor: ::dfdx::nn_traits::GradsVisitor<Elem, Dev>>(
+                        &self,
+                        visitor: &mut _Visitor,
+                        gradients: &mut ::dfdx::tensor::Gradients<Elem, Dev>,
+                    ) -> Result<(), ::dfdx::tensor::Error> {
+                        Ok(())
+                    }
+                }
+
+                impl #build_impl ::dfdx::nn_traits::VisitParams<Elem, Dev> for #builder_name #built_ty #built_where {
+                    fn try_visit_params<_Visitor: ::dfdx::nn_traits::ParamsVisitor<Elem, Dev>>(
+                        &self,
+                        location: &str,
+                        visitor: &mut _Visitor,
+                    ) -> Result<(), ::dfdx::tensor::Error> {
+                        Ok(())
+                    }
+                }
             }
         };
         (built_name, def)
@@ -431,7 +453,7 @@ pub fn sequential(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         };
 
         quote! {
-            #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, #safetensors_derive)]
+            #[derive(Clone, Debug, ::dfdx::ResetParams, ::dfdx::UpdateParams, ::dfdx::ZeroGrads, ::dfdx::WithGrads, ::dfdx::VisitParams, #safetensors_derive)]
             pub struct #built_name #built_impl #built_where {
                 #fields
             }
@@ -831,6 +853,175 @@ pub fn zero_grads(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     })
 }
 
+#[proc_macro_derive(WithGrads, attributes(param, module))]
+pub fn with_grads(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+
+    let name = input.ident;
+
+    let mut custom_generics = input.generics.clone();
+    if !custom_generics.params.iter().any(
+        |param| matches!(param, syn::GenericParam::Type(type_param) if type_param.ident == "Elem"),
+    ) {
+        custom_generics
+            .params
+            .push(parse_quote!(Elem: ::dfdx::prelude::Dtype));
+    }
+
+    if !custom_generics.params.iter().any(
+        |param| matches!(param, syn::GenericParam::Type(type_param) if type_param.ident == "Dev"),
+    ) {
+        custom_generics
+            .params
+            .push(parse_quote!(Dev: ::dfdx::prelude::Device<Elem>));
+    }
+
+    let where_clause = input.generics.make_where_clause();
+    let visits = match &input.data {
+        Data::Struct(ref obj) => match obj.fields {
+            Fields::Named(ref fields) => {
+                let visits = fields.named.iter().map(|f| {
+                    let name = &f.ident;
+                    let ty = &f.ty;
+                    if has_attr!(f, "module") {
+                        where_clause
+                            .predicates
+                            .push(parse_quote!(#ty: ::dfdx::nn_traits::WithGrads<Elem, Dev>));
+                        quote_spanned!(f.span()=>self.#name.try_visit_grads(visitor, gradients)?;)
+                    } else if has_attr!(f, "param") {
+                        quote_spanned!(f.span()=>visitor.visit_grad(&self.#name, gradients)?;)
+                    } else {
+                        Default::default()
+                    }
+                });
+                quote! { #(#visits)* }
+            }
+            Fields::Unnamed(ref fields) => {
+                let visits = fields.unnamed.iter().enumerate().map(|(i, f)| {
+                    let index = Index::from(i);
+                    let ty = &f.ty;
+                    if has_attr!(f, "module") {
+                        where_clause
+                            .predicates
+                            .push(parse_quote!(#ty: ::dfdx::nn_traits::WithGrads<Elem, Dev>));
+                        quote_spanned!(f.span()=>self.#index.try_visit_grads(visitor, gradients)?;)
+                    } else if has_attr!(f, "param") {
+                        quote_spanned!(f.span()=>visitor.visit_grad(&self.#index, gradients)?;)
+                    } else {
+                        Default::default()
+                    }
+                });
+                quote! { #(#visits)* }
+            }
+            Fields::Unit => Default::default(),
+        },
+        Data::Enum(_) => unimplemented!("WithGrads not implemented for enums."),
+        Data::Union(_) => unimplemented!("WithGrads not implemented for unions."),
+    };
+
+    let (impl_generics, _, _) = custom_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    proc_macro::TokenStream::from(quote! {
+        impl #impl_generics ::dfdx::nn_traits::WithGrads<Elem, Dev> for #name #ty_generics #where_clause {
+            fn try_visit_grads<_Visitor: ::dfdx::nn_traits::GradsVisitor<Elem, Dev>>(
+                &self,
+                visitor: &mut _Visitor,
+                gradients: &mut ::dfdx::prelude::Gradients<Elem, Dev>,
+            ) -> Result<(), ::dfdx::tensor::Error> {
+                #visits
+                Ok(())
+            }
+        }
+    })
+}
+
+#[proc_macro_derive(VisitParams, attributes(param, module))]
+pub fn visit_params(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+
+    let name = input.ident;
+
+    let mut custom_generics = input.generics.clone();
+    if !custom_generics.params.iter().any(
+        |param| matches!(param, syn::GenericParam::Type(type_param) if type_param.ident == "Elem"),
+    ) {
+        custom_generics
+            .params
+            .push(parse_quote!(Elem: ::dfdx::prelude::Dtype));
+    }
+
+    if !custom_generics.params.iter().any(
+        |param| matches!(param, syn::GenericParam::Type(type_param) if type_param.ident == "Dev"),
+    ) {
+        custom_generics
+            .params
+            .push(parse_quote!(Dev: ::dfdx::prelude::Device<Elem>));
+    }
+
+    let where_clause = input.generics.make_where_clause();
+    let visits = match &input.data {
+        Data::Struct(ref obj) => match obj.fields {
+            Fields::Named(ref fields) => {
+                let visits = fields.named.iter().map(|f| {
+                    let name = &f.ident;
+                    let ty = &f.ty;
+                    let name_str = name.as_ref().map(|n| n.to_string());
+                    if has_attr!(f, "module") {
+                        where_clause
+                            .predicates
+                            .push(parse_quote!(#ty: ::dfdx::nn_traits::VisitParams<Elem, Dev>));
+                        quote_spanned!(f.span()=>self.#name.try_visit_params(&format!("{location}{}", #name_str), visitor)?;)
+                    } else if has_attr!(f, "param") {
+                        quote_spanned!(f.span()=>visitor.visit_param(&format!("{location}{}", #name_str), &self.#name)?;)
+                    } else {
+                        Default::default()
+                    }
+                });
+                quote! { #(#visits)* }
+            }
+            Fields::Unnamed(ref fields) => {
+                let visits = fields.unnamed.iter().enumerate().map(|(i, f)| {
+                    let index = Index::from(i);
+                    let ty = &f.ty;
+                    if has_attr!(f, "module") {
+                        where_clause
+                            .predicates
+                            .push(parse_quote!(#ty: ::dfdx::nn_traits::VisitParams<Elem, Dev>));
+                        quote_spanned!(f.span()=>self.#index.try_visit_params(&format!("{location}{}", #index), visitor)?;)
+                    } else if has_attr!(f, "param") {
+                        quote_spanned!(f.span()=>visitor.visit_param(&format!("{location}{}", #index), &self.#index)?;)
+                    } else {
+                        Default::default()
+                    }
+                });
+                quote! { #(#visits)* }
+            }
+            Fields::Unit => Default::default(),
+        },
+        Data::Enum(_) => unimplemented!("VisitParams not implemented for enums."),
+        Data::Union(_) => unimplemented!("VisitParams not implemented for unions."),
+    };
+
+    let (impl_generics, _, _) = custom_generics.split_for_impl();
+    let (_, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    proc_macro::TokenStream::from(quote! {
+        impl #impl_generics ::dfdx::nn_traits::VisitParams<Elem, Dev> for #name #ty_generics #where_clause {
+            fn try_visit_params<_Visitor: ::dfdx::nn_traits::ParamsVisitor<Elem, Dev>>(
+                &self,
+                location: &str,
+                visitor: &mut _Visitor,
+            ) -> Result<(), ::dfdx::tensor::Error> {
+                visitor.enter_module(location, ::core::any::type_name::<Self>());
+                #visits
+                visitor.exit_module();
+                Ok(())
+            }
+        }
+    })
+}
+
 #[proc_macro_derive(SaveSafeTensors, attributes(serialize))]
 pub fn save_safetensors(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut input = parse_macro_input!(input as DeriveInput);
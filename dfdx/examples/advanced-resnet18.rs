@@ -1,11 +1,5 @@
 #![cfg_attr(feature = "nightly", feature(generic_const_exprs))]
 
-#[cfg(not(feature = "nightly"))]
-fn main() {
-    panic!("Run this example with `cargo +nightly`.")
-}
-
-#[cfg(feature = "nightly")]
 fn main() {
     use std::time::Instant;
 
@@ -69,6 +69,9 @@ fn main() {
             // run backprop
             grads = ppo_loss.backward();
 
+            // keep the policy update from exploding
+            pi_net.clip_grad_norm(&mut grads, 0.5);
+
             // update weights with optimizer
             sgd.update(&mut pi_net, &grads).expect("Unused params");
             pi_net.zero_grads(&mut grads);
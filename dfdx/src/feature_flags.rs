@@ -92,6 +92,36 @@
 //! dfdx = { version = "...", features = ["safetensors"] }
 //! ```
 //!
+//! # "onnx"
+//!
+//! Enables exporting nn to .onnx files with `SaveOnnx`, and running .onnx files on the
+//! `Cpu` with `OnnxModel`.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["onnx"] }
+//! ```
+//!
+//! # "pytorch"
+//!
+//! Enables loading PyTorch `state_dict` checkpoints (.pt/.pth files) into nn with
+//! `LoadPyTorch`. Also enables "safetensors".
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["pytorch"] }
+//! ```
+//!
+//! # "gguf"
+//!
+//! Enables reading GGUF files (the checkpoints of ggml and llama.cpp) with `gguf::Gguf`,
+//! including dequantizing their quantized tensors to `f32`.
+//!
+//! Example:
+//! ```toml
+//! dfdx = { version = "...", features = ["gguf"] }
+//! ```
+//!
 //! # "nightly"
 //!
 //! Enables using all features that currently require the nightly rust compiler.
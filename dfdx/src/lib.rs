@@ -86,6 +86,7 @@
 //! | Binary Operations | `a + b` | `a + b` | `a + b` |
 //! | gemm/gemv | [tensor_ops::matmul] | `a @ b` | `a @ b` |
 //! | 2d Convolution | [tensor_ops::TryConv2D] | - | `torch.conv2d` |
+//! | 3d Convolution | [tensor_ops::TryConv3D] | - | `torch.conv3d` |
 //! | 2d Transposed Convolution | [tensor_ops::TryConvTrans2D] | - | `torch.conv_transpose2d` |
 //! | Slicing | [tensor_ops::slice] | `a[...]` | `a[...]` |
 //! | Select | [tensor_ops::SelectTo] | `a[...]` | `torch.select` |
@@ -226,7 +227,12 @@
 //! | SGD | [nn::optim::Sgd] | `torch.optim.SGD` |
 //! | Adam | [nn::optim::Adam] | `torch.optim.Adam` |
 //! | AdamW | [nn::optim::Adam] with [nn::optim::WeightDecay::Decoupled] | `torch.optim.AdamW` |
+//! | AMSGrad | [nn::optim::Adam] with [nn::optim::AdamConfig::amsgrad] | `torch.optim.Adam(amsgrad=True)` |
 //! | RMSprop | [nn::optim::RMSprop] | `torch.optim.RMSprop` |
+//! | Adagrad | [nn::optim::Adagrad] | `torch.optim.Adagrad` |
+//! | Adadelta | [nn::optim::Adadelta] | `torch.optim.Adadelta` |
+//! | LAMB | [nn::optim::Lamb] | - |
+//! | Lion | [nn::optim::Lion] | - |
 //!
 //! You can use optimizers to optimize neural networks (or even tensors!). Here's
 //! a simple example of how to do this:
@@ -262,7 +268,9 @@ pub use dfdx_core::*;
 #[cfg(feature = "safetensors")]
 pub use safetensors;
 
-pub use dfdx_derives::{CustomModule, ResetParams, Sequential, UpdateParams, ZeroGrads};
+pub use dfdx_derives::{
+    CustomModule, ResetParams, Sequential, UpdateParams, VisitParams, WithGrads, ZeroGrads,
+};
 #[cfg(feature = "safetensors")]
 pub use dfdx_derives::{LoadSafeTensors, SaveSafeTensors};
 
@@ -442,4 +450,25 @@ pub(crate) mod tests {
         }};
     }
     pub(crate) use assert_close_to_literal;
+
+    macro_rules! assert_close_to_tensor {
+        ($Lhs:expr, $Rhs:expr) => {
+            let lhs = $Lhs.array();
+            let tol = AssertClose::get_default_tol(&lhs);
+            let far_pair = AssertClose::get_far_pair(&lhs, &$Rhs.array(), tol);
+            if let Some((l, r)) = far_pair {
+                panic!("lhs != rhs | {l} != {r}");
+            }
+        };
+        ($Lhs:expr, $Rhs:expr, $Tolerance:expr) => {{
+            let far_pair = $Lhs.array().get_far_pair(
+                &$Rhs.array(),
+                num_traits::FromPrimitive::from_f64($Tolerance).unwrap(),
+            );
+            if let Some((l, r)) = far_pair {
+                panic!("lhs != rhs | {l} != {r}");
+            }
+        }};
+    }
+    pub(crate) use assert_close_to_tensor;
 }
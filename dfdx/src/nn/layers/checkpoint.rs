@@ -0,0 +1,143 @@
+use crate::prelude::*;
+
+/// Activation checkpointing around `M`: the intermediate tensors of `M`'s forward pass
+/// are dropped right after it, and are recomputed by running it again during the backward
+/// pass. This trades compute for memory, since only the input of `M` is kept alive until
+/// backward. The gradients are identical to those of `M` without checkpointing.
+///
+/// Only the tensors `M` creates are dropped, so wrap large chunks of a model, e.g.
+/// each block of a transformer.
+///
+/// **Modules that sample random numbers, like [Dropout], sample again during the
+/// recomputation**, so their gradients won't match the forward pass.
+///
+/// # Generics
+/// - `M`: The underlying module to checkpoint.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// type Block = (LinearConstConfig<5, 5>, Tanh, LinearConstConfig<5, 5>);
+/// type Model = (Checkpoint<Block>, Checkpoint<Block>);
+/// let model = dev.build_module::<f32>(Model::default());
+/// let x: Tensor<Rank2<10, 5>, f32, _> = dev.sample_normal();
+/// let grads = model.forward(x.leaky_trace()).square().mean().backward();
+/// ```
+#[derive(Default, Clone, Debug, ResetParams, ZeroGrads, UpdateParams, WithGrads, VisitParams)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+#[repr(transparent)]
+pub struct Checkpoint<M>(
+    #[module]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub M,
+);
+
+impl<E: Dtype, D: Device<E>, M: BuildOnDevice<E, D>> BuildOnDevice<E, D> for Checkpoint<M> {
+    type Built = Checkpoint<M::Built>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        let m = self.0.try_build_on_device(device)?;
+        Ok(Checkpoint(m))
+    }
+}
+
+/// Without a tape there is nothing to drop, so this just calls `M`.
+impl<S: Shape, E: Dtype, D: Device<E>, M: Module<Tensor<S, E, D>>> Module<Tensor<S, E, D>>
+    for Checkpoint<M>
+{
+    type Output = M::Output;
+    fn try_forward(&self, x: Tensor<S, E, D>) -> Result<Self::Output, Error> {
+        self.0.try_forward(x)
+    }
+    fn try_forward_mut(&mut self, x: Tensor<S, E, D>) -> Result<Self::Output, Error> {
+        self.0.try_forward_mut(x)
+    }
+}
+
+impl<S: Shape, Out: Shape, E: Dtype, D: Device<E>, M> Module<Tensor<S, E, D, OwnedTape<E, D>>>
+    for Checkpoint<M>
+where
+    M: 'static
+        + Clone
+        + Module<Tensor<S, E, D, OwnedTape<E, D>>, Output = Tensor<Out, E, D, OwnedTape<E, D>>>,
+{
+    type Output = Tensor<Out, E, D, OwnedTape<E, D>>;
+    fn try_forward(&self, x: Tensor<S, E, D, OwnedTape<E, D>>) -> Result<Self::Output, Error> {
+        let m = self.0.clone();
+        x.try_checkpoint(|x| self.0.try_forward(x), move |x| m.try_forward(x))
+    }
+    /// The recomputation runs `try_forward_mut` on a copy of `M` from before the forward
+    /// pass, so e.g. batch norms see the same running statistics and only update them once.
+    fn try_forward_mut(
+        &mut self,
+        x: Tensor<S, E, D, OwnedTape<E, D>>,
+    ) -> Result<Self::Output, Error> {
+        let mut m = self.0.clone();
+        x.try_checkpoint(|x| self.0.try_forward_mut(x), move |x| m.try_forward_mut(x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_checkpoint_same_gradients() {
+        let dev: TestDevice = Default::default();
+        type Block = (LinearConstConfig<3, 4>, Tanh, LinearConstConfig<4, 3>);
+        let model = dev.build_module::<TestDtype>(<(Block, Block)>::default());
+        let checkpointed = (Checkpoint(model.0.clone()), Checkpoint(model.1.clone()));
+
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+        let y1 = checkpointed.forward(x.leaky_trace());
+        let y2 = model.forward(x.leaky_trace());
+        assert_close_to_tensor!(y1.retaped::<NoneTape>(), y2.retaped::<NoneTape>());
+
+        let g1 = y1.square().mean().backward();
+        let g2 = y2.square().mean().backward();
+        assert_close_to_tensor!(g1.get(&x), g2.get(&x));
+        assert_close_to_tensor!(g1.get(&model.0 .0.weight), g2.get(&model.0 .0.weight));
+        assert_close_to_tensor!(g1.get(&model.0 .2.bias), g2.get(&model.0 .2.bias));
+        assert_close_to_tensor!(g1.get(&model.1 .0.weight), g2.get(&model.1 .0.weight));
+
+        // no tape
+        let y = checkpointed.forward(x.clone());
+        assert_close_to_tensor!(y, model.forward(x));
+    }
+
+    #[test]
+    fn test_checkpoint_forward_mut() {
+        let dev: TestDevice = Default::default();
+        type Block = (LinearConstConfig<3, 4>, BatchNorm1DConstConfig<4>);
+        let mut model = dev.build_module::<TestDtype>(Block::default());
+        let mut checkpointed = Checkpoint(model.clone());
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+
+        let g1 = checkpointed
+            .forward_mut(x.leaky_trace())
+            .exp()
+            .mean()
+            .backward();
+        let g2 = model.forward_mut(x.leaky_trace()).exp().mean().backward();
+        assert_close_to_tensor!(g1.get(&x), g2.get(&x));
+        assert_close_to_tensor!(g1.get(&model.0.weight), g2.get(&model.0.weight));
+        assert_close_to_tensor!(g1.get(&model.1.scale), g2.get(&model.1.scale));
+        // the running statistics are only updated once
+        assert_close_to_tensor!(checkpointed.0 .1.running_mean, model.1.running_mean);
+        assert_close_to_tensor!(checkpointed.0 .1.running_var, model.1.running_var);
+    }
+
+    #[test]
+    fn test_checkpoint_with_alloc_grads() {
+        let dev: TestDevice = Default::default();
+        type Block = (LinearConstConfig<3, 4>, ReLU);
+        let model = dev.build_module::<TestDtype>(Checkpoint::<Block>::default());
+        let mut grads = model.alloc_grads();
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+        grads = model.forward(x.trace(grads)).square().mean().backward();
+        let g = model.0.forward(x.leaky_trace()).square().mean().backward();
+        assert_close_to_tensor!(grads.get(&model.0 .0.weight), g.get(&model.0 .0.weight));
+        assert_close_to_tensor!(grads.get(&model.0 .0.bias), g.get(&model.0 .0.bias));
+    }
+}
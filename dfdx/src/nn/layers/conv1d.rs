@@ -1,6 +1,8 @@
 use crate::prelude::*;
 
-/// **Requires Nightly** Performs *unbiased* 1d convolutions on 2d and 3d images.
+/// Performs *unbiased* 1d convolutions on 2d and 3d images.
+///
+/// The output length is only a [Const] with the `nightly` feature, otherwise it is a [usize].
 ///
 /// **Pytorch Equivalent**: `torch.nn.Conv1d(..., bias=False)`
 ///
@@ -12,7 +14,7 @@ use crate::prelude::*;
 /// - `PADDING`: How much zero padding to add around the images. Defaults to `0`.
 /// - `DILATION`: Controls the spacing between kernel points. Defaults to `1`.
 /// - `GROUPS`: Controls the connections between inputs and outputs.
-///     `IN_CHAN` and `OUT_CHAN` must both be divisible by `GROUPS`. For example,
+///   `IN_CHAN` and `OUT_CHAN` must both be divisible by `GROUPS`. For example,
 ///
 /// See [conv animations](https://github.com/vdumoulin/conv_arithmetic/blob/master/README.md) for helpful
 /// visualization of all of these parameters.
@@ -78,7 +80,7 @@ where
 }
 
 /// The module built with [Conv1DConfig]. See [Conv1DConfig] for usage.
-#[derive(Debug, Clone, UpdateParams, ZeroGrads)]
+#[derive(Debug, Clone, UpdateParams, ZeroGrads, WithGrads, VisitParams)]
 #[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
 pub struct Conv1D<InChan, OutChan, KernelSize, Stride, Padding, Dilation, Groups, Elem, Dev>
 where
@@ -159,15 +161,15 @@ mod tests {
     fn test_forward_3d_sizes() {
         let dev: TestDevice = Default::default();
         let x = dev.zeros::<Rank2<3, 10>>();
-        let _: Tensor<Rank2<2, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<4, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<4, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<4, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 4>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<2, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<2, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<2, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<2, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank2<2, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone());
+        let _: Tensor<Rank2<2, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<4, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<4, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<4, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 4>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<2, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<2, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<2, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<2, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank2<2, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone()).realize();
     }
 
     #[test]
@@ -177,28 +179,28 @@ mod tests {
         let x = dev.ones::<Rank2<16, 10>>();
 
         let m = dev.build_module::<TestDtype>(<Conv1DConstConfig<16, 32, 3, 1, 0, 1>>::default());
-        let _: Tensor<Rank3<32, 16, 3>, _, _> = m.weight;
-        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank3<32, 16, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv1DConstConfig<16, 32, 3, 1, 0, 1, 2>>::default());
-        let _: Tensor<Rank3<32, 8, 3>, _, _> = m.weight;
-        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank3<32, 8, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv1DConstConfig<16, 32, 3, 1, 0, 1, 4>>::default());
-        let _: Tensor<Rank3<32, 4, 3>, _, _> = m.weight;
-        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank3<32, 4, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv1DConstConfig<16, 32, 3, 1, 0, 1, 8>>::default());
-        let _: Tensor<Rank3<32, 2, 3>, _, _> = m.weight;
-        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank3<32, 2, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv1DConstConfig<16, 32, 3, 1, 0, 1, 16>>::default());
-        let _: Tensor<Rank3<32, 1, 3>, _, _> = m.weight;
-        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x);
+        let _: Tensor<Rank3<32, 1, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank2<32, 8>, _, _> = m.forward(x).realize();
     }
 
     #[rustfmt::skip]
@@ -206,15 +208,15 @@ mod tests {
     fn test_forward_4d_sizes() {
         let dev: TestDevice = Default::default();
         let x = dev.zeros::<Rank3<5, 3, 10>>();
-        let _: Tensor<Rank3<5, 2, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 4, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 4, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 4, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 4>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 2, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 2, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 2, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 2, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<5, 2, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone());
+        let _: Tensor<Rank3<5, 2, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 4, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 4, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 4, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 4, 4>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 2, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 2, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 2, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 2, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<5, 2, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv1DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone()).realize();
     }
 
     #[test]
@@ -224,7 +226,8 @@ mod tests {
         type B = Conv1DConstConfig<2, 4, 3>;
         let _: Tensor<Rank2<4, 6>, _, _> = dev
             .build_module::<TestDtype>(<(A, B)>::default())
-            .forward(dev.zeros::<Rank2<1, 10>>());
+            .forward(dev.zeros::<Rank2<1, 10>>())
+            .realize();
     }
 
     #[test]
@@ -236,7 +239,8 @@ mod tests {
         let dev = Cpu::default();
         let _: Tensor<Rank2<1, 8>, _, _> = dev
             .build_module::<TestDtype>(<(A, B, C)>::default())
-            .forward_mut(dev.zeros::<Rank2<1, 10>>());
+            .forward_mut(dev.zeros::<Rank2<1, 10>>())
+            .realize();
     }
 
     #[test]
@@ -251,10 +255,10 @@ mod tests {
         let out = m.forward(dev.sample_normal::<Rank3<8, 2, 28>>().leaky_trace());
         let g = out.square().mean().backward();
 
-        assert_ne!(g.get(&m.weight).array(), [[[TestDtype::zero(); 3]; 2]; 4]);
+        assert_ne!(g.get(&m.weight).as_vec(), [TestDtype::zero(); 24]);
 
         opt.update(&mut m, &g).expect("unused params");
 
-        assert_ne!(weight_init.array(), m.weight.array());
+        assert_ne!(weight_init.as_vec(), m.weight.as_vec());
     }
 }
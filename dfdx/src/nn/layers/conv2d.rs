@@ -1,6 +1,8 @@
 use crate::prelude::*;
 
-/// **Requires Nightly** Performs *unbiased* 2d convolutions on 3d and 4d images.
+/// Performs *unbiased* 2d convolutions on 3d and 4d images.
+///
+/// The output height & width are only [Const] with the `nightly` feature, otherwise they are [usize].
 ///
 /// **Pytorch Equivalent**: `torch.nn.Conv2d(..., bias=False)`
 ///
@@ -29,7 +31,7 @@ use crate::prelude::*;
 /// - `Padding`: How much zero padding to add around the images. Defaults to `Const<0>`.
 /// - `Dilation`: Controls the spacing between kernel points. Defaults to `Const<1>`.
 /// - `Groups`: Controls the connections between inputs and outputs.
-///     `InChan` and `OutChan` must both be divisible by `Groups`.
+///   `InChan` and `OutChan` must both be divisible by `Groups`.
 ///
 /// See [conv animations](https://github.com/vdumoulin/conv_arithmetic/blob/master/README.md) for helpful
 /// visualization of all of these parameters.
@@ -99,7 +101,7 @@ where
 }
 
 /// The module built with [Conv2DConfig]. See [Conv2DConfig] for usage.
-#[derive(Debug, Clone, UpdateParams, ZeroGrads)]
+#[derive(Debug, Clone, UpdateParams, ZeroGrads, WithGrads, VisitParams)]
 #[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
 pub struct Conv2D<InChan, OutChan, KernelSize, Stride, Padding, Dilation, Groups, Elem, Dev>
 where
@@ -182,15 +184,15 @@ mod tests {
     fn test_forward_3d_sizes() {
         let dev: TestDevice = Default::default();
         let x = dev.zeros::<Rank3<3, 10, 10>>();
-        let _: Tensor<Rank3<2, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<4, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<4, 9, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<4, 7, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 4>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<2, 4, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<2, 3, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<2, 10, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<2, 12, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank3<2, 6, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone());
+        let _: Tensor<Rank3<2, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<4, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<4, 9, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<4, 7, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 4>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<2, 4, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<2, 3, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<2, 10, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<2, 12, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank3<2, 6, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone()).realize();
     }
 
     #[test]
@@ -201,28 +203,28 @@ mod tests {
 
         let m =
             dev.build_module::<TestDtype>(<Conv2DConstConfig<16, 32, 3, 1, 0, 1, 1>>::default());
-        let _: Tensor<Rank4<32, 16, 3, 3>, _, _> = m.weight;
-        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank4<32, 16, 3, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv2DConstConfig<16, 32, 3, 1, 0, 1, 2>>::default());
-        let _: Tensor<Rank4<32, 8, 3, 3>, _, _> = m.weight;
-        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank4<32, 8, 3, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv2DConstConfig<16, 32, 3, 1, 0, 1, 4>>::default());
-        let _: Tensor<Rank4<32, 4, 3, 3>, _, _> = m.weight;
-        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank4<32, 4, 3, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv2DConstConfig<16, 32, 3, 1, 0, 1, 8>>::default());
-        let _: Tensor<Rank4<32, 2, 3, 3>, _, _> = m.weight;
-        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone());
+        let _: Tensor<Rank4<32, 2, 3, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x.clone()).realize();
 
         let m =
             dev.build_module::<TestDtype>(<Conv2DConstConfig<16, 32, 3, 1, 0, 1, 16>>::default());
-        let _: Tensor<Rank4<32, 1, 3, 3>, _, _> = m.weight;
-        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x);
+        let _: Tensor<Rank4<32, 1, 3, 3>, _, _> = m.weight.clone().realize();
+        let _: Tensor<Rank3<32, 8, 8>, _, _> = m.forward(x).realize();
     }
 
     #[rustfmt::skip]
@@ -230,15 +232,15 @@ mod tests {
     fn test_forward_4d_sizes() {
         let dev: TestDevice = Default::default();
         let x = dev.zeros::<Rank4<5, 3, 10, 10>>();
-        let _: Tensor<Rank4<5, 2, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 4, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 4, 9, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 4, 7, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 4>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 2, 4, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 2, 3, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 2, 10, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 2, 12, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone());
-        let _: Tensor<Rank4<5, 2, 6, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone());
+        let _: Tensor<Rank4<5, 2, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 4, 8, 8>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 4, 9, 9>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 4, 7, 7>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 4, 4>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 2, 4, 4>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 2, 3, 3>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 3>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 2, 10, 10>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 1>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 2, 12, 12>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 1, 2>>::default()).forward(x.clone()).realize();
+        let _: Tensor<Rank4<5, 2, 6, 6>, _, _, _> = dev.build_module::<TestDtype>(<Conv2DConstConfig<3, 2, 3, 2, 2>>::default()).forward(x.clone()).realize();
     }
 
     #[test]
@@ -248,7 +250,8 @@ mod tests {
         type B = Conv2DConstConfig<2, 4, 3>;
         let _: Tensor<Rank3<4, 6, 6>, _, _> = dev
             .build_module::<TestDtype>(<(A, B)>::default())
-            .forward(dev.zeros::<Rank3<1, 10, 10>>());
+            .forward(dev.zeros::<Rank3<1, 10, 10>>())
+            .realize();
     }
 
     #[test]
@@ -260,7 +263,8 @@ mod tests {
         let dev = Cpu::default();
         let _: Tensor<Rank3<1, 8, 8>, _, _> = dev
             .build_module::<TestDtype>(<(A, B, C)>::default())
-            .forward_mut(dev.zeros::<Rank3<1, 10, 10>>());
+            .forward_mut(dev.zeros::<Rank3<1, 10, 10>>())
+            .realize();
     }
 
     #[test]
@@ -275,13 +279,10 @@ mod tests {
         let out = m.forward(dev.sample_normal::<Rank4<8, 2, 28, 28>>().leaky_trace());
         let g = out.square().mean().backward();
 
-        assert_ne!(
-            g.get(&m.weight).array(),
-            [[[[TestDtype::zero(); 3]; 3]; 2]; 4]
-        );
+        assert_ne!(g.get(&m.weight).as_vec(), [TestDtype::zero(); 72]);
 
         opt.update(&mut m, &g).expect("unused params");
 
-        assert_ne!(weight_init.array(), m.weight.array());
+        assert_ne!(weight_init.as_vec(), m.weight.as_vec());
     }
 }
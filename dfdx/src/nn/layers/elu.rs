@@ -0,0 +1,18 @@
+use crate::prelude::*;
+
+/// Calls [crate::tensor_ops::elu()] with `alpha` set to the wrapped value. Defaults to `1.0`.
+#[derive(Debug, Clone, Copy, CustomModule)]
+pub struct ELU(pub f64);
+
+impl Default for ELU {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<S, E, D, T>> for ELU {
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        x.try_elu(self.0)
+    }
+}
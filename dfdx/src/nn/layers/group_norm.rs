@@ -0,0 +1,217 @@
+use crate::prelude::*;
+
+/// Implements group normalization as described in [Group Normalization](https://arxiv.org/abs/1803.08494).
+///
+/// The channels are split into `Groups` groups, and each group of each batch item is normalized to 0 mean and unit
+/// std dev with [normalize()], and then an element-wise affine transform is done per channel using learnable parameters.
+///
+/// Epsilon is passed to [normalize()] and added to the variance to ensure big enough numbers. It defaults to `1e-5`.
+///
+/// **Pytorch Equivalent**: `torch.nn.GroupNorm(groups, chan)`
+///
+/// Generics:
+/// - `Groups` The number of groups to split the channels into. `Chan` must be divisible by `Groups`.
+/// - `Chan` The number of channels, which is also the size of the affine transform tensors.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = GroupNormConstConfig<2, 4>;
+/// let model = dev.build_module::<f32>(Model::default());
+/// let _: Tensor<Rank3<3, 4, 5>, f32, _> = model.forward(dev.zeros::<Rank3<3, 4, 5>>());
+/// let _: Tensor<Rank4<3, 4, 5, 5>, f32, _> = model.forward(dev.zeros::<Rank4<3, 4, 5, 5>>());
+/// // runtime groups/channels
+/// let model = dev.build_module::<f32>(GroupNormConfig { groups: 2, chan: 4 });
+/// ```
+#[derive(Default, Clone, Copy, Debug)]
+pub struct GroupNormConfig<Groups: Dim, Chan: Dim> {
+    pub groups: Groups,
+    pub chan: Chan,
+}
+
+/// Compile time sugar alias around [GroupNormConfig]
+pub type GroupNormConstConfig<const GROUPS: usize, const CHAN: usize> =
+    GroupNormConfig<Const<GROUPS>, Const<CHAN>>;
+
+impl<G: Dim, C: Dim, E: Dtype, D: Device<E>> BuildOnDevice<E, D> for GroupNormConfig<G, C> {
+    type Built = GroupNorm<G, C, E, D>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        assert_eq!(self.chan.size() % self.groups.size(), 0);
+        Ok(GroupNorm {
+            gamma: device.try_ones_like(&(self.chan,))?,
+            beta: device.try_zeros_like(&(self.chan,))?,
+            groups: self.groups,
+            epsilon: 1e-5,
+        })
+    }
+}
+
+/// See [GroupNormConfig]
+#[derive(Clone, Debug, UpdateParams, ZeroGrads, WithGrads, VisitParams)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct GroupNorm<Groups: Dim, Chan: Dim, Elem: Dtype, Dev: Device<Elem>> {
+    #[param]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub gamma: Tensor<(Chan,), Elem, Dev>,
+    #[param]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub beta: Tensor<(Chan,), Elem, Dev>,
+    pub groups: Groups,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub epsilon: f64,
+}
+
+impl<G: Dim, C: Dim, E: Dtype, D: Device<E>> ResetParams<E, D> for GroupNorm<G, C, E, D> {
+    fn try_reset_params(&mut self) -> Result<(), crate::tensor::Error> {
+        self.gamma.try_fill_with_ones()?;
+        self.beta.try_fill_with_zeros()
+    }
+}
+
+impl<Batch: Dim, G: Dim, C: Dim, L: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(Batch, C, L), E, D, T>> for GroupNorm<G, C, E, D>
+{
+    type Output = Tensor<(Batch, C, L), E, D, T>;
+    fn try_forward(&self, x: Tensor<(Batch, C, L), E, D, T>) -> Result<Self::Output, Error> {
+        let batch = x.shape().0.size();
+        self.group_fwd(x, batch)
+    }
+}
+
+impl<Batch: Dim, G: Dim, C: Dim, H: Dim, W: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(Batch, C, H, W), E, D, T>> for GroupNorm<G, C, E, D>
+{
+    type Output = Tensor<(Batch, C, H, W), E, D, T>;
+    fn try_forward(&self, x: Tensor<(Batch, C, H, W), E, D, T>) -> Result<Self::Output, Error> {
+        let batch = x.shape().0.size();
+        self.group_fwd(x, batch)
+    }
+}
+
+impl<G: Dim, C: Dim, E: Dtype, D: Device<E>> GroupNorm<G, C, E, D> {
+    /// Normalizes each group of `x` by viewing it as `(batch, groups, rest)`.
+    fn group_fwd<S: Shape, T: Tape<E, D>, Ax: Axes>(
+        &self,
+        x: Tensor<S, E, D, T>,
+        batch: usize,
+    ) -> Result<Tensor<S, E, D, T>, Error>
+    where
+        (C,): BroadcastShapeTo<S, Ax>,
+    {
+        let shape = *x.shape();
+        let groups = self.groups.size();
+        let grouped = (batch, groups, shape.num_elements() / (batch * groups));
+        let x = x
+            .try_reshape_like(&grouped)?
+            .try_normalize::<Axis<2>>(self.epsilon)?
+            .try_reshape_like(&shape)?;
+        let x = self
+            .gamma
+            .retaped::<T>()
+            .try_broadcast_like(&shape)?
+            .try_mul(x)?;
+        self.beta
+            .retaped::<T>()
+            .try_broadcast_like(&shape)?
+            .try_add(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_group_norm_reset() {
+        let dev: TestDevice = Default::default();
+
+        let mut m = dev.build_module::<TestDtype>(<GroupNormConstConfig<2, 4>>::default());
+        assert_close_to_literal!(m.gamma, [1.0; 4]);
+        assert_close_to_literal!(m.beta, [0.0; 4]);
+
+        m.gamma = dev.sample_normal();
+        m.beta = dev.sample_normal();
+        m.reset_params();
+
+        assert_close_to_literal!(m.gamma, [1.0; 4]);
+        assert_close_to_literal!(m.beta, [0.0; 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_group_norm_indivisible_groups() {
+        let dev: TestDevice = Default::default();
+        dev.build_module::<TestDtype>(GroupNormConfig { groups: 3, chan: 4 });
+    }
+
+    #[test]
+    fn test_group_norm_3d_forward() {
+        let dev: TestDevice = Default::default();
+        let mut m = dev.build_module::<TestDtype>(<GroupNormConstConfig<2, 4>>::default());
+        m.gamma = dev.tensor([1.0, 2.0, 0.5, -1.0]).to_dtype::<TestDtype>();
+        m.beta = dev.tensor([0.0, 0.1, -0.2, 0.3]).to_dtype::<TestDtype>();
+        let x = dev
+            .tensor([
+                [
+                    [0.1, -1.2, 0.3],
+                    [2.0, 0.5, -0.4],
+                    [1.5, 1.0, -2.0],
+                    [0.0, 0.7, 0.2],
+                ],
+                [
+                    [-0.3, 0.8, 1.1],
+                    [-1.5, 0.2, 0.6],
+                    [0.4, -0.9, 1.3],
+                    [2.2, -0.5, 0.0],
+                ],
+            ])
+            .to_dtype::<TestDtype>();
+        let r = m.forward(x.leaky_trace());
+        assert_close_to_literal!(
+            r,
+            [
+                [
+                    [-0.12003072, -1.4575159, 0.08573623],
+                    [3.7695107, 0.68300637, -1.1688962],
+                    [0.36823889, 0.14393406, -1.2018949],
+                    [0.50935117, -0.11870234, 0.32990731],
+                ],
+                [
+                    [-0.52222945, 0.75433143, 1.1024844],
+                    [-3.7296826, 0.21605099, 1.1444589],
+                    [-0.20788107, -0.82260444, 0.21769665],
+                    [-1.3865487, 1.1669176, 0.69405344],
+                ]
+            ]
+        );
+        let g = r.mean().backward();
+        assert_close_to_literal!(
+            g.get(&m.gamma),
+            [-0.006551002, 0.006551002, -0.025209232, 0.025209232]
+        );
+        assert_close_to_literal!(g.get(&m.beta), [1.0 / 4.0; 4]);
+    }
+
+    #[test]
+    fn test_group_norm_4d_forward() {
+        let dev: TestDevice = Default::default();
+        let m = dev.build_module::<TestDtype>(GroupNormConfig {
+            groups: 1,
+            chan: Const::<2>,
+        });
+        let x = dev
+            .tensor([[[[0.5, -1.0], [2.0, 0.0]], [[1.0, 3.0], [-2.0, 0.5]]]])
+            .to_dtype::<TestDtype>();
+        let r = m.forward(x);
+        assert_close_to_literal!(
+            r,
+            [[
+                [[0.0, -1.0141828], [1.0141828, -0.33806093]],
+                [[0.33806093, 1.6903046], [-1.6903046, 0.0]]
+            ]]
+        );
+    }
+}
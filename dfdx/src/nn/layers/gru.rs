@@ -0,0 +1,295 @@
+use crate::prelude::*;
+
+use super::recurrent::{self, Cell, RecurrentWeights};
+
+/// A multi-layer gated recurrent unit (GRU) network, as introduced in
+/// [Learning Phrase Representations using RNN Encoder-Decoder](https://arxiv.org/abs/1406.1078).
+///
+/// Each timestep computes:
+/// ```text
+/// r = sigmoid(W_ir x + b_ir + W_hr h + b_hr)
+/// z = sigmoid(W_iz x + b_iz + W_hz h + b_hz)
+/// n = tanh(W_in x + b_in + r * (W_hn h + b_hn))
+/// h' = (1 - z) * n + z * h
+/// ```
+///
+/// Inputs are batch first `(Batch, Seq, I)`. The forward pass returns the hidden states
+/// of the last layer for every timestep `(Batch, Seq, H * num_directions)`, and the final
+/// `h_n` of every layer & direction `(num_layers * num_directions, Batch, H)`.
+/// The initial state can be passed as `(x, h_0)`, otherwise it is zero.
+///
+/// See [LSTMConfig] for notes on the output type & tapes.
+///
+/// Generics:
+/// - `I`: The number of input features.
+/// - `H`: The number of features in the hidden state.
+///
+/// **Pytorch equivalent**:
+/// ```python
+/// torch.nn.GRU(I, H, num_layers=num_layers, bidirectional=bidirectional, batch_first=True)
+/// ```
+/// Parameters are stored in the same layout, and saved/loaded with the same safetensors keys
+/// (`weight_ih_l0`, `weight_hh_l0_reverse`, ...) as pytorch's `state_dict()`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let gru = dev.build_module::<f32>(GRUConfig::new(Const::<3>, Const::<5>, 1, false));
+/// let x: Tensor<Rank3<4, 7, 3>, f32, _> = dev.sample_normal();
+/// let (y, h) = gru.forward(x.clone());
+/// assert_eq!(y.shape(), &(Const::<4>, Const::<7>, 5));
+/// assert_eq!(h.shape(), &(1, Const::<4>, Const::<5>));
+/// // continue from a previous state
+/// let _ = gru.forward((x, h));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct GRUConfig<I: Dim, H: Dim> {
+    pub input: I,
+    pub hidden: H,
+    pub num_layers: usize,
+    pub bidirectional: bool,
+}
+
+/// Compile time sugar alias around [GRUConfig].
+pub type GRUConstConfig<const I: usize, const H: usize> = GRUConfig<Const<I>, Const<H>>;
+
+impl<I: Dim, H: Dim> GRUConfig<I, H> {
+    pub fn new(input: I, hidden: H, num_layers: usize, bidirectional: bool) -> Self {
+        Self {
+            input,
+            hidden,
+            num_layers,
+            bidirectional,
+        }
+    }
+}
+
+/// A single layer, unidirectional GRU.
+impl<I: ConstDim, H: ConstDim> Default for GRUConfig<I, H> {
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default(), 1, false)
+    }
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> BuildOnDevice<E, D> for GRUConfig<I, H> {
+    type Built = GRU<I, H, E, D>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        Ok(GRU {
+            weights: recurrent::try_build_weights(
+                device,
+                Cell::Gru,
+                self.input.size(),
+                self.hidden,
+                self.num_layers,
+                self.bidirectional,
+            )?,
+            input: self.input,
+            hidden: self.hidden,
+            bidirectional: self.bidirectional,
+        })
+    }
+}
+
+/// See [GRUConfig].
+#[derive(Clone, Debug, ResetParams, UpdateParams, ZeroGrads, WithGrads)]
+pub struct GRU<I: Dim, H: Dim, Elem: Dtype, Dev: Device<Elem>> {
+    /// The weights of every layer & direction: `l0, l0_reverse, l1, l1_reverse, ...`
+    #[module]
+    pub weights: Vec<RecurrentWeights<H, Elem, Dev>>,
+    pub input: I,
+    pub hidden: H,
+    pub bidirectional: bool,
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> VisitParams<E, D> for GRU<I, H, E, D> {
+    fn try_visit_params<V: ParamsVisitor<E, D>>(
+        &self,
+        location: &str,
+        visitor: &mut V,
+    ) -> Result<(), Error> {
+        recurrent::try_visit_params(
+            &self.weights,
+            self.bidirectional,
+            std::any::type_name::<Self>(),
+            location,
+            visitor,
+        )
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> SaveSafeTensors for GRU<I, H, E, D> {
+    fn write_safetensors(
+        &self,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        recurrent::write_safetensors(&self.weights, self.bidirectional, location, tensors)
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> LoadSafeTensors for GRU<I, H, E, D> {
+    fn read_safetensors(
+        &mut self,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        recurrent::read_safetensors(&mut self.weights, self.bidirectional, location, tensors)
+    }
+}
+
+impl<B: Dim, S: Dim, I: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(B, S, I), E, D, T>> for GRU<I, H, E, D>
+{
+    type Output = (
+        Tensor<(B, S, usize), E, D, T>,
+        Tensor<(usize, B, H), E, D, T>,
+    );
+
+    fn try_forward(&self, x: Tensor<(B, S, I), E, D, T>) -> Result<Self::Output, Error> {
+        self.try_forward_with(x, None)
+    }
+}
+
+impl<B: Dim, S: Dim, I: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<(Tensor<(B, S, I), E, D, T>, Tensor<(usize, B, H), E, D>)> for GRU<I, H, E, D>
+{
+    type Output = (
+        Tensor<(B, S, usize), E, D, T>,
+        Tensor<(usize, B, H), E, D, T>,
+    );
+
+    fn try_forward(
+        &self,
+        (x, h0): (Tensor<(B, S, I), E, D, T>, Tensor<(usize, B, H), E, D>),
+    ) -> Result<Self::Output, Error> {
+        self.try_forward_with(x, Some(h0))
+    }
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> GRU<I, H, E, D> {
+    #[allow(clippy::type_complexity)]
+    fn try_forward_with<B: Dim, S: Dim, T: Tape<E, D>>(
+        &self,
+        x: Tensor<(B, S, I), E, D, T>,
+        h0: Option<Tensor<(usize, B, H), E, D>>,
+    ) -> Result<
+        (
+            Tensor<(B, S, usize), E, D, T>,
+            Tensor<(usize, B, H), E, D, T>,
+        ),
+        Error,
+    > {
+        let (b, s, i) = *x.shape();
+        assert_eq!(i.size(), self.input.size());
+        let x = x.try_reshape_like(&(b, s, i.size()))?;
+        let (y, h, _) = recurrent::try_forward(
+            Cell::Gru,
+            &self.weights,
+            self.bidirectional,
+            self.hidden,
+            x,
+            h0,
+            None,
+        )?;
+        Ok((y, h))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::excessive_precision)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_gru_forward() {
+        let dev: TestDevice = Default::default();
+        let mut gru = dev.build_module::<TestDtype>(GRUConstConfig::<3, 2>::default());
+        let w = &mut gru.weights[0];
+        w.weight_ih = dev
+            .tensor([
+                [0.1, -0.2, 0.3],
+                [-0.4, 0.5, 0.6],
+                [0.7, 0.8, -0.9],
+                [0.2, 0.1, 0.0],
+                [-0.3, 0.4, -0.5],
+                [0.6, -0.7, 0.8],
+            ])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.weight_hh = dev
+            .tensor([
+                [0.5, -0.1],
+                [0.2, 0.3],
+                [-0.6, 0.4],
+                [0.7, -0.8],
+                [0.1, 0.9],
+                [-0.2, -0.3],
+            ])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.bias_ih = dev
+            .tensor([0.1, -0.1, 0.2, -0.2, 0.3, -0.3])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.bias_hh = dev
+            .tensor([0.05, 0.05, -0.05, -0.05, 0.1, 0.1])
+            .to_dtype::<TestDtype>()
+            .realize();
+
+        let x = dev
+            .tensor([[[1.0, -1.0, 0.5], [0.2, 0.3, -0.4], [-0.6, 0.7, 0.8]]])
+            .to_dtype::<TestDtype>();
+        let (y, h) = gru.forward(x);
+
+        // This expected y was generated by evaluating pytorch's `torch.nn.GRU` equations
+        // (gate order `r, z, n`) with the same weights & input.
+        assert_close_to_literal!(
+            y.realize::<Rank3<1, 3, 2>>(),
+            [[
+                [-0.31527133, 0.47967990],
+                [-0.10280049, -0.26513983],
+                [0.12845736, -0.31165421],
+            ]]
+        );
+        assert_close_to_literal!(h.realize::<Rank3<1, 1, 2>>(), [[[0.12845736, -0.31165421]]]);
+    }
+
+    #[test]
+    fn test_gru_bidirectional_initial_state() {
+        let dev: TestDevice = Default::default();
+        let gru = dev.build_module::<TestDtype>(GRUConfig::new(Const::<3>, Const::<4>, 2, true));
+
+        let x: Tensor<Rank3<2, 5, 3>, TestDtype, _> = dev.sample_normal();
+        let (y, h) = gru.forward(x.clone());
+        assert_eq!(y.shape(), &(Const::<2>, Const::<5>, 8));
+        assert_eq!(h.shape(), &(4, Const::<2>, Const::<4>));
+
+        // an explicit zero state is the same as the default
+        let (y2, h2) = gru.forward((x, dev.zeros_like(h.shape())));
+        assert_eq!(y.as_vec(), y2.as_vec());
+        assert_eq!(h.as_vec(), h2.as_vec());
+    }
+
+    #[test]
+    fn test_gru_backward_updates_all() {
+        let dev: TestDevice = Default::default();
+        let mut gru = dev.build_module::<TestDtype>(GRUConfig::new(3, 4, 2, false));
+
+        let x = dev.sample_normal_like(&(2, 5, 3));
+        let (y, _) = gru.forward(x.leaky_trace());
+        let g = y.square().mean().backward();
+        for w in gru.weights.iter() {
+            assert!(g.get(&w.weight_ih).as_vec().iter().any(|v| *v != 0.0));
+            assert!(g.get(&w.weight_hh).as_vec().iter().any(|v| *v != 0.0));
+            assert!(g.get(&w.bias_ih).as_vec().iter().any(|v| *v != 0.0));
+            assert!(g.get(&w.bias_hh).as_vec().iter().any(|v| *v != 0.0));
+        }
+
+        let mut opt = crate::nn::optim::Sgd::new(&gru, Default::default());
+        opt.update(&mut gru, &g).expect("");
+    }
+}
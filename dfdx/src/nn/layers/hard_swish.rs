@@ -0,0 +1,11 @@
+use crate::prelude::*;
+
+/// Calls [crate::tensor_ops::hardswish()].
+#[derive(Default, Debug, Clone, Copy, CustomModule)]
+pub struct HardSwish;
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<S, E, D, T>> for HardSwish {
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        x.try_hardswish()
+    }
+}
@@ -0,0 +1,24 @@
+use crate::prelude::*;
+
+/// Calls [crate::tensor_ops::hardtanh()]. Defaults to clamping between `-1.0` and `1.0`.
+#[derive(Debug, Clone, Copy, CustomModule)]
+pub struct HardTanh {
+    pub min_val: f64,
+    pub max_val: f64,
+}
+
+impl Default for HardTanh {
+    fn default() -> Self {
+        Self {
+            min_val: -1.0,
+            max_val: 1.0,
+        }
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<S, E, D, T>> for HardTanh {
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        x.try_hardtanh(self.min_val, self.max_val)
+    }
+}
@@ -0,0 +1,154 @@
+use crate::prelude::*;
+
+/// Implements instance normalization for images as described in
+/// [Instance Normalization: The Missing Ingredient for Fast Stylization](https://arxiv.org/abs/1607.08022).
+///
+/// This calls [normalize()] on the height & width axes, so each channel of each image is normalized to 0 mean and unit
+/// std dev, and then does an element-wise affine transform per channel using learnable parameters.
+///
+/// Unlike [crate::nn::BatchNorm2D], no running statistics are kept, so training and inference behave the same.
+///
+/// Epsilon is passed to [normalize()] and added to the variance to ensure big enough numbers. It defaults to `1e-5`.
+///
+/// **Pytorch Equivalent**: `torch.nn.InstanceNorm2d(chan, affine=True)`
+///
+/// Generics:
+/// - `C` The number of channels, which is also the size of the affine transform tensors.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = InstanceNorm2DConstConfig<3>;
+/// let model = dev.build_module::<f32>(Model::default());
+/// let _: Tensor<Rank3<3, 4, 4>, f32, _> = model.forward(dev.zeros::<Rank3<3, 4, 4>>());
+/// let _: Tensor<Rank4<2, 3, 4, 4>, f32, _> = model.forward(dev.zeros::<Rank4<2, 3, 4, 4>>());
+/// ```
+#[derive(Default, Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct InstanceNorm2DConfig<C: Dim>(pub C);
+
+/// Compile time sugar alias around [InstanceNorm2DConfig]
+pub type InstanceNorm2DConstConfig<const C: usize> = InstanceNorm2DConfig<Const<C>>;
+
+impl<C: Dim, E: Dtype, D: Device<E>> BuildOnDevice<E, D> for InstanceNorm2DConfig<C> {
+    type Built = InstanceNorm2D<C, E, D>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        Ok(InstanceNorm2D {
+            gamma: device.try_ones_like(&(self.0,))?,
+            beta: device.try_zeros_like(&(self.0,))?,
+            epsilon: 1e-5,
+        })
+    }
+}
+
+/// See [InstanceNorm2DConfig]
+#[derive(Clone, Debug, UpdateParams, ZeroGrads, WithGrads, VisitParams)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct InstanceNorm2D<C: Dim, Elem: Dtype, Dev: Device<Elem>> {
+    #[param]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub gamma: Tensor<(C,), Elem, Dev>,
+    #[param]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub beta: Tensor<(C,), Elem, Dev>,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub epsilon: f64,
+}
+
+impl<C: Dim, E: Dtype, D: Device<E>> ResetParams<E, D> for InstanceNorm2D<C, E, D> {
+    fn try_reset_params(&mut self) -> Result<(), crate::tensor::Error> {
+        self.gamma.try_fill_with_ones()?;
+        self.beta.try_fill_with_zeros()
+    }
+}
+
+impl<C: Dim, H: Dim, W: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(C, H, W), E, D, T>> for InstanceNorm2D<C, E, D>
+{
+    type Output = Tensor<(C, H, W), E, D, T>;
+    fn try_forward(&self, x: Tensor<(C, H, W), E, D, T>) -> Result<Self::Output, Error> {
+        let x = x.try_normalize::<Axes2<1, 2>>(self.epsilon)?;
+        let x = self.gamma.retaped::<T>().broadcast_like(&x).try_mul(x)?;
+        self.beta.retaped::<T>().broadcast_like(&x).try_add(x)
+    }
+}
+
+impl<Batch: Dim, C: Dim, H: Dim, W: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(Batch, C, H, W), E, D, T>> for InstanceNorm2D<C, E, D>
+{
+    type Output = Tensor<(Batch, C, H, W), E, D, T>;
+    fn try_forward(&self, x: Tensor<(Batch, C, H, W), E, D, T>) -> Result<Self::Output, Error> {
+        let x = x.try_normalize::<Axes2<2, 3>>(self.epsilon)?;
+        let x = self.gamma.retaped::<T>().broadcast_like(&x).try_mul(x)?;
+        self.beta.retaped::<T>().broadcast_like(&x).try_add(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_instance_norm_reset() {
+        let dev: TestDevice = Default::default();
+
+        let mut m = dev.build_module::<TestDtype>(<InstanceNorm2DConstConfig<3>>::default());
+        m.gamma = dev.sample_normal();
+        m.beta = dev.sample_normal();
+        m.reset_params();
+
+        assert_close_to_literal!(m.gamma, [1.0; 3]);
+        assert_close_to_literal!(m.beta, [0.0; 3]);
+    }
+
+    #[test]
+    fn test_instance_norm_3d_forward() {
+        let dev: TestDevice = Default::default();
+        let mut m = dev.build_module::<TestDtype>(<InstanceNorm2DConstConfig<2>>::default());
+        m.gamma = dev.tensor([2.0, -0.5]).to_dtype::<TestDtype>();
+        m.beta = dev.tensor([0.1, 0.2]).to_dtype::<TestDtype>();
+        let x = dev
+            .tensor([[[0.5, -1.0], [2.0, 0.0]], [[1.0, 3.0], [-2.0, 0.5]]])
+            .to_dtype::<TestDtype>();
+        let r = m.forward(x.leaky_trace());
+        assert_close_to_literal!(
+            r,
+            [
+                [[0.33093912, -2.4403303], [3.1022086, -0.59281737]],
+                [[0.094720805, -0.46676823], [0.93695436, 0.23509306]]
+            ]
+        );
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&m.gamma), [0.0; 2]);
+        assert_close_to_literal!(g.get(&m.beta), [0.5; 2]);
+    }
+
+    #[test]
+    fn test_instance_norm_4d_forward() {
+        let dev: TestDevice = Default::default();
+        let m = dev.build_module::<TestDtype>(InstanceNorm2DConfig(Const::<2>));
+        let x = dev
+            .tensor([
+                [[[0.1, -1.2, 0.3]], [[2.0, 0.5, -0.4]]],
+                [[[1.5, 1.0, -2.0]], [[0.0, 0.7, 0.2]]],
+            ])
+            .to_dtype::<TestDtype>();
+        let r = m.forward(x);
+        assert_close_to_literal!(
+            r,
+            [
+                [
+                    [[0.55137394, -1.4034973, 0.85212337]],
+                    [[1.3131916, -0.20202948, -1.1111621]]
+                ],
+                [
+                    [[0.86266038, 0.53916274, -1.4018231]],
+                    [[-1.0189905, 1.3586541, -0.33966351]]
+                ]
+            ]
+        );
+    }
+}
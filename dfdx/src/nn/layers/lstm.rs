@@ -0,0 +1,411 @@
+use crate::prelude::*;
+
+use super::recurrent::{self, Cell, RecurrentWeights};
+
+/// A multi-layer long short-term memory (LSTM) network, as introduced in
+/// [Long Short-Term Memory](https://www.bioinf.jku.at/publications/older/2604.pdf).
+///
+/// Each timestep computes:
+/// ```text
+/// i = sigmoid(W_ii x + b_ii + W_hi h + b_hi)
+/// f = sigmoid(W_if x + b_if + W_hf h + b_hf)
+/// g = tanh(W_ig x + b_ig + W_hg h + b_hg)
+/// o = sigmoid(W_io x + b_io + W_ho h + b_ho)
+/// c' = f * c + i * g
+/// h' = o * tanh(c')
+/// ```
+///
+/// Inputs are batch first `(Batch, Seq, I)`. The forward pass returns the hidden states
+/// of the last layer for every timestep `(Batch, Seq, H * num_directions)`, and the final
+/// `(h_n, c_n)` of every layer & direction `(num_layers * num_directions, Batch, H)`.
+/// The initial state can be passed as `(x, (h_0, c_0))`, otherwise it is zero.
+///
+/// The output features are a [usize] because they depend on `bidirectional`.
+///
+/// All operations are recorded on the output's tape; `h_n` and `c_n` have an empty tape
+/// (like [SplitInto]), so combine them with the output if you need to backprop through them.
+///
+/// Generics:
+/// - `I`: The number of input features.
+/// - `H`: The number of features in the hidden state.
+///
+/// **Pytorch equivalent**:
+/// ```python
+/// torch.nn.LSTM(I, H, num_layers=num_layers, bidirectional=bidirectional, batch_first=True)
+/// ```
+/// Parameters are stored in the same layout, and saved/loaded with the same safetensors keys
+/// (`weight_ih_l0`, `weight_hh_l0_reverse`, ...) as pytorch's `state_dict()`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let lstm = dev.build_module::<f32>(LSTMConfig::new(Const::<3>, Const::<5>, 2, true));
+/// let x: Tensor<Rank3<4, 7, 3>, f32, _> = dev.sample_normal();
+/// let (y, (h, c)) = lstm.forward(x.clone());
+/// assert_eq!(y.shape(), &(Const::<4>, Const::<7>, 10));
+/// assert_eq!(h.shape(), &(4, Const::<4>, Const::<5>));
+/// // continue from a previous state
+/// let _ = lstm.forward((x, (h, c)));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LSTMConfig<I: Dim, H: Dim> {
+    pub input: I,
+    pub hidden: H,
+    pub num_layers: usize,
+    pub bidirectional: bool,
+}
+
+/// Compile time sugar alias around [LSTMConfig].
+pub type LSTMConstConfig<const I: usize, const H: usize> = LSTMConfig<Const<I>, Const<H>>;
+
+impl<I: Dim, H: Dim> LSTMConfig<I, H> {
+    pub fn new(input: I, hidden: H, num_layers: usize, bidirectional: bool) -> Self {
+        Self {
+            input,
+            hidden,
+            num_layers,
+            bidirectional,
+        }
+    }
+}
+
+/// A single layer, unidirectional LSTM.
+impl<I: ConstDim, H: ConstDim> Default for LSTMConfig<I, H> {
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default(), 1, false)
+    }
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> BuildOnDevice<E, D> for LSTMConfig<I, H> {
+    type Built = LSTM<I, H, E, D>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        Ok(LSTM {
+            weights: recurrent::try_build_weights(
+                device,
+                Cell::Lstm,
+                self.input.size(),
+                self.hidden,
+                self.num_layers,
+                self.bidirectional,
+            )?,
+            input: self.input,
+            hidden: self.hidden,
+            bidirectional: self.bidirectional,
+        })
+    }
+}
+
+/// See [LSTMConfig].
+#[derive(Clone, Debug, ResetParams, UpdateParams, ZeroGrads, WithGrads)]
+pub struct LSTM<I: Dim, H: Dim, Elem: Dtype, Dev: Device<Elem>> {
+    /// The weights of every layer & direction: `l0, l0_reverse, l1, l1_reverse, ...`
+    #[module]
+    pub weights: Vec<RecurrentWeights<H, Elem, Dev>>,
+    pub input: I,
+    pub hidden: H,
+    pub bidirectional: bool,
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> VisitParams<E, D> for LSTM<I, H, E, D> {
+    fn try_visit_params<V: ParamsVisitor<E, D>>(
+        &self,
+        location: &str,
+        visitor: &mut V,
+    ) -> Result<(), Error> {
+        recurrent::try_visit_params(
+            &self.weights,
+            self.bidirectional,
+            std::any::type_name::<Self>(),
+            location,
+            visitor,
+        )
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> SaveSafeTensors for LSTM<I, H, E, D> {
+    fn write_safetensors(
+        &self,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        recurrent::write_safetensors(&self.weights, self.bidirectional, location, tensors)
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> LoadSafeTensors for LSTM<I, H, E, D> {
+    fn read_safetensors(
+        &mut self,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        recurrent::read_safetensors(&mut self.weights, self.bidirectional, location, tensors)
+    }
+}
+
+impl<B: Dim, S: Dim, I: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(B, S, I), E, D, T>> for LSTM<I, H, E, D>
+{
+    type Output = (
+        Tensor<(B, S, usize), E, D, T>,
+        (
+            Tensor<(usize, B, H), E, D, T>,
+            Tensor<(usize, B, H), E, D, T>,
+        ),
+    );
+
+    fn try_forward(&self, x: Tensor<(B, S, I), E, D, T>) -> Result<Self::Output, Error> {
+        self.try_forward_with(x, None, None)
+    }
+}
+
+impl<B: Dim, S: Dim, I: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<(
+        Tensor<(B, S, I), E, D, T>,
+        (Tensor<(usize, B, H), E, D>, Tensor<(usize, B, H), E, D>),
+    )> for LSTM<I, H, E, D>
+{
+    type Output = (
+        Tensor<(B, S, usize), E, D, T>,
+        (
+            Tensor<(usize, B, H), E, D, T>,
+            Tensor<(usize, B, H), E, D, T>,
+        ),
+    );
+
+    fn try_forward(
+        &self,
+        (x, (h0, c0)): (
+            Tensor<(B, S, I), E, D, T>,
+            (Tensor<(usize, B, H), E, D>, Tensor<(usize, B, H), E, D>),
+        ),
+    ) -> Result<Self::Output, Error> {
+        self.try_forward_with(x, Some(h0), Some(c0))
+    }
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> LSTM<I, H, E, D> {
+    #[allow(clippy::type_complexity)]
+    fn try_forward_with<B: Dim, S: Dim, T: Tape<E, D>>(
+        &self,
+        x: Tensor<(B, S, I), E, D, T>,
+        h0: Option<Tensor<(usize, B, H), E, D>>,
+        c0: Option<Tensor<(usize, B, H), E, D>>,
+    ) -> Result<
+        (
+            Tensor<(B, S, usize), E, D, T>,
+            (
+                Tensor<(usize, B, H), E, D, T>,
+                Tensor<(usize, B, H), E, D, T>,
+            ),
+        ),
+        Error,
+    > {
+        let (b, s, i) = *x.shape();
+        assert_eq!(i.size(), self.input.size());
+        let x = x.try_reshape_like(&(b, s, i.size()))?;
+        let (y, h, c) = recurrent::try_forward(
+            Cell::Lstm,
+            &self.weights,
+            self.bidirectional,
+            self.hidden,
+            x,
+            h0,
+            c0,
+        )?;
+        Ok((y, (h, c)))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::excessive_precision)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    fn build(dev: &TestDevice) -> LSTM<Const<3>, Const<2>, TestDtype, TestDevice> {
+        let mut lstm = dev.build_module::<TestDtype>(LSTMConstConfig::<3, 2>::default());
+        let w = &mut lstm.weights[0];
+        w.weight_ih = dev
+            .tensor([
+                [0.1, -0.2, 0.3],
+                [-0.4, 0.5, 0.6],
+                [0.7, 0.8, -0.9],
+                [0.2, 0.1, 0.0],
+                [-0.3, 0.4, -0.5],
+                [0.6, -0.7, 0.8],
+                [0.9, 0.1, -0.2],
+                [-0.3, -0.4, 0.5],
+            ])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.weight_hh = dev
+            .tensor([
+                [0.5, -0.1],
+                [0.2, 0.3],
+                [-0.6, 0.4],
+                [0.7, -0.8],
+                [0.1, 0.9],
+                [-0.2, -0.3],
+                [0.4, 0.5],
+                [-0.6, 0.7],
+            ])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.bias_ih = dev
+            .tensor([0.1, -0.1, 0.2, -0.2, 0.3, -0.3, 0.4, -0.4])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.bias_hh = dev
+            .tensor([0.05, 0.05, -0.05, -0.05, 0.1, 0.1, -0.1, -0.1])
+            .to_dtype::<TestDtype>()
+            .realize();
+        lstm
+    }
+
+    #[test]
+    fn test_lstm_forward() {
+        let dev: TestDevice = Default::default();
+        let lstm = build(&dev);
+
+        let x = dev
+            .tensor([[[1.0, -1.0, 0.5], [0.2, 0.3, -0.4], [-0.6, 0.7, 0.8]]])
+            .to_dtype::<TestDtype>();
+        let (y, (h, c)) = lstm.forward(x);
+
+        // This expected y was generated by evaluating pytorch's `torch.nn.LSTM` equations
+        // (gate order `i, f, g, o`) with the same weights & input.
+        assert_close_to_literal!(
+            y.realize::<Rank3<1, 3, 2>>(),
+            [[
+                [-0.22835715, 0.13916526],
+                [0.03679111, -0.04098273],
+                [0.10006716, -0.14125059],
+            ]]
+        );
+        assert_close_to_literal!(h.realize::<Rank3<1, 1, 2>>(), [[[0.10006716, -0.14125059]]]);
+        assert_close_to_literal!(c.realize::<Rank3<1, 1, 2>>(), [[[0.24484152, -0.33472311]]]);
+    }
+
+    #[test]
+    fn test_lstm_initial_state() {
+        let dev: TestDevice = Default::default();
+        let lstm = build(&dev);
+
+        let x: Tensor<Rank3<2, 5, 3>, TestDtype, _> = dev.sample_normal();
+        let (y, (h, c)) = lstm.forward(x.clone());
+
+        // running the first part of the sequence, and then continuing from its state
+        // is the same as running the whole sequence at once.
+        let x1 = x.clone().slice((.., ..2, ..));
+        let x2 = x.slice((.., 2.., ..));
+        let (_, (h1, c1)) = lstm.forward(x1);
+        let (y2, (h2, c2)) = lstm.forward((x2, (h1, c1)));
+        assert_close_to_tensor!(
+            y.slice((.., 2.., ..)).realize::<Rank3<2, 3, 2>>(),
+            y2.realize::<Rank3<2, 3, 2>>()
+        );
+        assert_close_to_tensor!(
+            h.realize::<Rank3<1, 2, 2>>(),
+            h2.realize::<Rank3<1, 2, 2>>()
+        );
+        assert_close_to_tensor!(
+            c.realize::<Rank3<1, 2, 2>>(),
+            c2.realize::<Rank3<1, 2, 2>>()
+        );
+    }
+
+    #[test]
+    fn test_lstm_bidirectional_multi_layer() {
+        let dev: TestDevice = Default::default();
+        let lstm = dev.build_module::<TestDtype>(LSTMConfig::new(3, Const::<4>, 3, true));
+        assert_eq!(lstm.weights.len(), 6);
+        assert_eq!(lstm.weights[0].weight_ih.shape(), &(16, 3));
+        assert_eq!(lstm.weights[2].weight_ih.shape(), &(16, 8));
+
+        let x = dev.sample_normal_like(&(2, 5, 3));
+        let (y, (h, c)) = lstm.forward(x);
+        assert_eq!(y.shape(), &(2, 5, 8));
+        assert_eq!(h.shape(), &(6, 2, Const::<4>));
+        assert_eq!(c.shape(), &(6, 2, Const::<4>));
+
+        // the last timestep of the forward direction & the first timestep of the
+        // reverse direction are the final hidden states of the last layer.
+        let y = y.as_vec();
+        let h = h.as_vec();
+        assert_eq!(y[4 * 8..4 * 8 + 4], h[4 * 8..4 * 8 + 4]);
+        assert_eq!(y[4..8], h[5 * 8..5 * 8 + 4]);
+    }
+
+    #[test]
+    fn test_lstm_backward_updates_all() {
+        let dev: TestDevice = Default::default();
+        let mut lstm =
+            dev.build_module::<TestDtype>(LSTMConfig::new(Const::<3>, Const::<4>, 2, true));
+
+        let x: Tensor<Rank3<2, 5, 3>, TestDtype, _> = dev.sample_normal();
+        let (y, _) = lstm.forward(x.leaky_trace());
+        let g = y.square().mean().backward();
+        for w in lstm.weights.iter() {
+            assert!(g.get(&w.weight_ih).as_vec().iter().any(|v| *v != 0.0));
+            assert!(g.get(&w.weight_hh).as_vec().iter().any(|v| *v != 0.0));
+            assert!(g.get(&w.bias_ih).as_vec().iter().any(|v| *v != 0.0));
+            assert!(g.get(&w.bias_hh).as_vec().iter().any(|v| *v != 0.0));
+        }
+
+        let mut opt = crate::nn::optim::Sgd::new(&lstm, Default::default());
+        opt.update(&mut lstm, &g).expect("");
+    }
+
+    #[cfg(feature = "safetensors")]
+    #[test]
+    fn test_lstm_safetensors_pytorch_keys() {
+        let dev: TestDevice = Default::default();
+        let lstm = dev.build_module::<TestDtype>(LSTMConfig::new(Const::<3>, Const::<4>, 2, true));
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        lstm.save_safetensors(file.path()).unwrap();
+
+        let buffer = std::fs::read(file.path()).unwrap();
+        let tensors = ::safetensors::SafeTensors::deserialize(&buffer).unwrap();
+        let mut names = tensors.names();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "bias_hh_l0",
+                "bias_hh_l0_reverse",
+                "bias_hh_l1",
+                "bias_hh_l1_reverse",
+                "bias_ih_l0",
+                "bias_ih_l0_reverse",
+                "bias_ih_l1",
+                "bias_ih_l1_reverse",
+                "weight_hh_l0",
+                "weight_hh_l0_reverse",
+                "weight_hh_l1",
+                "weight_hh_l1_reverse",
+                "weight_ih_l0",
+                "weight_ih_l0_reverse",
+                "weight_ih_l1",
+                "weight_ih_l1_reverse",
+            ]
+        );
+        assert_eq!(
+            tensors.tensor("weight_ih_l1_reverse").unwrap().shape(),
+            [16, 8]
+        );
+
+        let mut loaded =
+            dev.build_module::<TestDtype>(LSTMConfig::new(Const::<3>, Const::<4>, 2, true));
+        loaded.load_safetensors(file.path()).unwrap();
+        for (a, b) in lstm.weights.iter().zip(loaded.weights.iter()) {
+            assert_eq!(a.weight_ih.as_vec(), b.weight_ih.as_vec());
+            assert_eq!(a.weight_hh.as_vec(), b.weight_hh.as_vec());
+            assert_eq!(a.bias_ih.as_vec(), b.bias_ih.as_vec());
+            assert_eq!(a.bias_hh.as_vec(), b.bias_hh.as_vec());
+        }
+    }
+}
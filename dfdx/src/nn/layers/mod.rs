@@ -4,14 +4,15 @@ mod batch_norm1d;
 mod batch_norm2d;
 mod bias1d;
 mod bias2d;
-#[cfg(feature = "nightly")]
+mod celu;
+mod checkpoint;
 mod conv1d;
-#[cfg(feature = "nightly")]
 mod conv2d;
-#[cfg(feature = "nightly")]
+mod conv3d;
 mod conv_trans2d;
 mod cos;
 mod dropout;
+mod elu;
 mod embedding;
 mod exp;
 #[cfg(feature = "nightly")]
@@ -19,31 +20,54 @@ mod flatten2d;
 mod gelu;
 mod generalized_add;
 mod generalized_mul;
+mod group_norm;
+mod gru;
+mod hard_sigmoid;
+mod hard_swish;
+mod hard_tanh;
+mod instance_norm2d;
 mod layer_norm1d;
 mod leaky_relu;
 mod linear;
 mod ln;
 mod log_softmax;
+mod lstm;
 mod matmul;
+mod mish;
 mod multi_head_attention;
-#[cfg(feature = "nightly")]
+mod pool_1d_avg;
+mod pool_1d_max;
+mod pool_1d_min;
+mod pool_2d_adaptive_avg;
+mod pool_2d_adaptive_max;
 mod pool_2d_avg;
-#[cfg(feature = "nightly")]
 mod pool_2d_max;
-#[cfg(feature = "nightly")]
 mod pool_2d_min;
+mod pool_3d_avg;
+mod pool_3d_max;
+mod pool_3d_min;
 mod pool_global_avg;
 mod pool_global_max;
 mod pool_global_min;
 mod prelu;
 mod prelu1d;
+mod recurrent;
 mod relu;
+mod relu6;
 mod reshape;
 mod residual_add;
 mod residual_mul;
+mod rms_norm;
+mod rnn;
+mod rotary_embedding;
+mod selu;
 mod sigmoid;
+mod silu;
 mod sin;
+mod sinusoidal_encoding;
 mod softmax;
+mod softplus;
+mod softsign;
 mod split_into;
 mod sqrt;
 mod square;
@@ -57,14 +81,15 @@ pub use batch_norm1d::{BatchNorm1D, BatchNorm1DConfig, BatchNorm1DConstConfig};
 pub use batch_norm2d::{BatchNorm2D, BatchNorm2DConfig, BatchNorm2DConstConfig};
 pub use bias1d::{Bias1D, Bias1DConfig, Bias1DConstConfig};
 pub use bias2d::{Bias2D, Bias2DConfig, Bias2DConstConfig};
-#[cfg(feature = "nightly")]
+pub use celu::CELU;
+pub use checkpoint::Checkpoint;
 pub use conv1d::{Conv1D, Conv1DConfig, Conv1DConstConfig};
-#[cfg(feature = "nightly")]
 pub use conv2d::{Conv2D, Conv2DConfig, Conv2DConstConfig};
-#[cfg(feature = "nightly")]
+pub use conv3d::{Conv3D, Conv3DConfig, Conv3DConstConfig};
 pub use conv_trans2d::{ConvTrans2D, ConvTrans2DConfig, ConvTrans2DConstConfig};
 pub use cos::Cos;
 pub use dropout::{Dropout, DropoutOneIn};
+pub use elu::ELU;
 pub use embedding::{Embedding, EmbeddingConfig, EmbeddingConstConfig};
 pub use exp::Exp;
 #[cfg(feature = "nightly")]
@@ -72,37 +97,64 @@ pub use flatten2d::Flatten2D;
 pub use gelu::{AccurateGeLU, FastGeLU};
 pub use generalized_add::GeneralizedAdd;
 pub use generalized_mul::GeneralizedMul;
+pub use group_norm::{GroupNorm, GroupNormConfig, GroupNormConstConfig};
+pub use gru::{GRUConfig, GRUConstConfig, GRU};
+pub use hard_sigmoid::HardSigmoid;
+pub use hard_swish::HardSwish;
+pub use hard_tanh::HardTanh;
+pub use instance_norm2d::{InstanceNorm2D, InstanceNorm2DConfig, InstanceNorm2DConstConfig};
 pub use layer_norm1d::{LayerNorm1D, LayerNorm1DConfig, LayerNorm1DConstConfig};
 pub use leaky_relu::LeakyReLU;
 pub use linear::{Linear, LinearConfig, LinearConstConfig};
 pub use ln::Ln;
 pub use log_softmax::LogSoftmax;
+pub use lstm::{LSTMConfig, LSTMConstConfig, LSTM};
 pub use matmul::{MatMul, MatMulConfig, MatMulConstConfig};
-pub use multi_head_attention::{MultiHeadAttention, MultiHeadAttentionConfig};
-#[cfg(feature = "nightly")]
+pub use mish::Mish;
+pub use multi_head_attention::{
+    ALiBi, AttentionMask, CausalMask, KeyPaddingMask, MultiHeadAttention, MultiHeadAttentionConfig,
+};
+pub use pool_1d_avg::{AvgPool1D, AvgPool1DConst};
+pub use pool_1d_max::{MaxPool1D, MaxPool1DConst};
+pub use pool_1d_min::{MinPool1D, MinPool1DConst};
+pub use pool_2d_adaptive_avg::{AdaptiveAvgPool2D, AdaptiveAvgPool2DConst};
+pub use pool_2d_adaptive_max::{AdaptiveMaxPool2D, AdaptiveMaxPool2DConst};
 pub use pool_2d_avg::{AvgPool2D, AvgPool2DConst};
-#[cfg(feature = "nightly")]
 pub use pool_2d_max::{MaxPool2D, MaxPool2DConst};
-#[cfg(feature = "nightly")]
 pub use pool_2d_min::{MinPool2D, MinPool2DConst};
+pub use pool_3d_avg::{AvgPool3D, AvgPool3DConst};
+pub use pool_3d_max::{MaxPool3D, MaxPool3DConst};
+pub use pool_3d_min::{MinPool3D, MinPool3DConst};
 pub use pool_global_avg::AvgPoolGlobal;
 pub use pool_global_max::MaxPoolGlobal;
 pub use pool_global_min::MinPoolGlobal;
 pub use prelu::{PReLU, PReLUConfig};
 pub use prelu1d::{PReLU1D, PReLU1DConfig};
+pub use recurrent::RecurrentWeights;
 pub use relu::ReLU;
+pub use relu6::ReLU6;
 pub use reshape::Reshape;
 pub use residual_add::ResidualAdd;
 pub use residual_mul::ResidualMul;
+pub use rms_norm::{RMSNorm, RMSNormConfig, RMSNormConstConfig};
+pub use rnn::{RNNConfig, RNNConstConfig, RNNNonlinearity, RNN};
+pub use rotary_embedding::RotaryEmbedding;
+pub use selu::SELU;
 pub use sigmoid::Sigmoid;
+pub use silu::SiLU;
 pub use sin::Sin;
+pub use sinusoidal_encoding::SinusoidalPositionalEncoding;
 pub use softmax::Softmax;
+pub use softplus::Softplus;
+pub use softsign::Softsign;
 pub use split_into::SplitInto;
 pub use sqrt::Sqrt;
 pub use square::Square;
 pub use tanh::Tanh;
 pub use transformer::{
-    DecoderBlock, DecoderBlockConfig, EncoderBlock, EncoderBlockConfig, Transformer,
-    TransformerConfig,
+    DecoderBlock, DecoderBlockConfig, EncoderBlock, EncoderBlockConfig, FeedForward,
+    FeedForwardConfig, GatedFeedForward, GatedFeedForwardConfig, GeGLUConfig, SwiGLUConfig,
+    Transformer, TransformerConfig, TransformerDecoderBlock, TransformerDecoderBlockConfig,
+    TransformerEncoderBlock, TransformerEncoderBlockConfig,
 };
 pub use upscale2d::{Upscale2D, Upscale2DBy, Upscale2DByConst, Upscale2DConst};
@@ -12,6 +12,11 @@ use num_traits::Float;
 ///
 /// **Pytorch equivalent**: `torch.nn.MultiheadAttention(Embed, NumHeads, batch_first=True)`
 ///
+/// Set `rotary` to apply a [RotaryEmbedding] to the queries & keys of every head. The queries
+/// are taken to be the last `S1` positions of the `S2` keys, so a single query can be
+/// attended against all of the keys decoded so far. If there are more queries than keys, the
+/// queries start at position 0.
+///
 /// Examples
 /// - `MultiHeadAttention<8, 2>` is an attention layer with 2 heads and 8 token, key and value dims.
 /// - `MultiHeadAttention<8, 2, 6, 4>` is an attention layer with the key and value dimension different
@@ -30,6 +35,7 @@ pub struct MultiHeadAttentionConfig<Embed: Dim, NumHeads: Dim, K: Dim = Embed, V
     pub num_heads: NumHeads,
     pub k_dim: K,
     pub v_dim: V,
+    pub rotary: Option<RotaryEmbedding>,
 }
 
 impl<Embed: Dim, NumHeads: Dim, K: Dim, V: Dim> MultiHeadAttentionConfig<Embed, NumHeads, K, V> {
@@ -46,10 +52,281 @@ impl<Embed: Dim, NumHeads: Dim, K: Dim, V: Dim> MultiHeadAttentionConfig<Embed,
             num_heads,
             k_dim: k,
             v_dim: v,
+            rotary: None,
         }
     }
 }
 
+/// An attention mask that can be applied to the attention weights of [MultiHeadAttention].
+///
+/// The mask is turned into an additive bias of shape `(B, S1, S2)` which is added to the
+/// scaled attention weights of every head before the softmax.
+///
+/// Implemented for:
+/// 1. Additive float masks `Tensor<(S1, S2), E, D>` and `Tensor<(B, S1, S2), E, D>`.
+/// 2. Boolean masks `Tensor<(S1, S2), bool, D>` and `Tensor<(B, S1, S2), bool, D>`, where
+///    `true` means the query is **not** allowed to attend to that key (same as pytorch).
+/// 3. [KeyPaddingMask], which masks out padded keys.
+/// 4. [CausalMask], which prevents queries from attending to future keys.
+/// 5. [ALiBi], which biases each head towards nearby keys.
+/// 6. Tuples `(M1, M2)` of masks, whose biases are summed.
+pub trait AttentionMask<B: Dim, S1: Dim, S2: Dim, E: Dtype, D: Device<E>> {
+    /// Builds the additive bias for attention weights of shape `(B, S1, S2)`.
+    #[allow(clippy::type_complexity)]
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        device: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error>;
+
+    /// Builds the additive bias for the attention weights of every head, of shape `(B, H, S1, S2)`.
+    /// Defaults to the same bias for every head.
+    #[allow(clippy::type_complexity)]
+    fn try_head_attention_bias<H: Dim>(
+        self,
+        shape: &(B, H, S1, S2),
+        device: &D,
+    ) -> Result<Tensor<(B, H, S1, S2), E, D>, crate::tensor::Error>
+    where
+        Self: Sized,
+    {
+        let (b, _, s1, s2) = *shape;
+        self.try_attention_bias(&(b, s1, s2), device)?
+            .try_broadcast_like::<_, Axis<1>>(shape)
+    }
+}
+
+/// Masks out future keys, so that query `i` can only attend to keys `0..=i`.
+///
+/// The mask is built with [TriangleTensor::upper_tri_like], and is equivalent to
+/// `torch.nn.Transformer.generate_square_subsequent_mask`.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mha = dev.build_module::<f32>(<MultiHeadAttentionConfig<Const<8>, Const<2>>>::default());
+/// let x: Tensor<Rank2<3, 8>, f32, _> = dev.sample_normal();
+/// let y = mha.forward((x.clone(), x.clone(), x, CausalMask));
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct CausalMask;
+
+/// Masks out padded keys. `true` means the key at that position is padding and
+/// will be ignored.
+///
+/// Accepts either `Tensor<(B, S2), bool, D>` or `Tensor<(S2,), bool, D>`.
+///
+/// **Pytorch equivalent**: the `key_padding_mask` argument of `torch.nn.MultiheadAttention`.
+#[derive(Debug, Clone)]
+pub struct KeyPaddingMask<T>(pub T);
+
+/// Attention with Linear Biases, as described in
+/// [Train Short, Test Long](https://arxiv.org/abs/2108.12409).
+///
+/// Head `h` of `H` adds `-slope_h * |i - j|` to the weight of query `i` for key `j`, where
+/// the slopes are the geometric sequence `2^(-8 / H), 2^(-16 / H), ...`. When `H` isn't a power of 2,
+/// the slopes of the largest power of 2 below `H` are followed by every other slope of twice that
+/// many heads, the same as the reference implementation.
+///
+/// Like [MultiHeadAttentionConfig::rotary], query `i` is at position `S2 - S1 + i`, so a single
+/// query decoded after `S2 - 1` keys is biased correctly. If there are more queries than keys,
+/// query `i` is at position `i`. Combine it with [CausalMask] for causal models,
+/// e.g. `(CausalMask, ALiBi)`.
+///
+/// When used outside of [MultiHeadAttention] without heads, this is the bias of a single head.
+///
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let mha = dev.build_module::<f32>(<MultiHeadAttentionConfig<Const<8>, Const<2>>>::default());
+/// let x: Tensor<Rank2<3, 8>, f32, _> = dev.sample_normal();
+/// let y = mha.forward((x.clone(), x.clone(), x, (CausalMask, ALiBi)));
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct ALiBi;
+
+impl ALiBi {
+    /// The slope of every head.
+    pub fn slopes(num_heads: usize) -> std::vec::Vec<f64> {
+        if num_heads == 0 {
+            return std::vec::Vec::new();
+        }
+        let pow2_slopes = |n: usize| (1..=n).map(move |h| 2f64.powf(-8.0 * h as f64 / n as f64));
+        let closest = 1 << (usize::BITS - 1 - num_heads.leading_zeros());
+        let mut slopes: std::vec::Vec<f64> = pow2_slopes(closest).collect();
+        slopes.extend(
+            pow2_slopes(2 * closest)
+                .step_by(2)
+                .take(num_heads - closest),
+        );
+        slopes
+    }
+}
+
+impl<B: Dim, S1: Dim, S2: Dim, E: Dtype, D: Device<E>> AttentionMask<B, S1, S2, E, D> for ALiBi {
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        device: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error> {
+        let (b, s1, s2) = *shape;
+        let bias = self.try_head_attention_bias(&(b, Const::<1>, s1, s2), device)?;
+        bias.try_reshape_like(shape)
+    }
+
+    fn try_head_attention_bias<H: Dim>(
+        self,
+        shape: &(B, H, S1, S2),
+        device: &D,
+    ) -> Result<Tensor<(B, H, S1, S2), E, D>, crate::tensor::Error> {
+        let (b, h, s1, s2) = *shape;
+        let (h_size, s1_size, s2_size) = (h.size(), s1.size(), s2.size());
+        let offset = s2_size.saturating_sub(s1_size);
+        let mut bias = std::vec::Vec::with_capacity(h_size * s1_size * s2_size);
+        for slope in Self::slopes(h_size) {
+            for i in offset..offset + s1_size {
+                for j in 0..s2_size {
+                    let dist = (i as f64 - j as f64).abs();
+                    bias.push(E::from_f64(-slope * dist).unwrap());
+                }
+            }
+        }
+        let bias = device.try_tensor_from_vec(bias, (h, s1, s2))?;
+        bias.try_broadcast_like::<_, Axis<0>>(&(b, h, s1, s2))
+    }
+}
+
+impl<B: Dim, S1: Dim, S2: Dim, E: Dtype + Float, D: Device<E>> AttentionMask<B, S1, S2, E, D>
+    for CausalMask
+{
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        device: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error> {
+        device.try_upper_tri_like(shape, E::neg_infinity(), 1)
+    }
+}
+
+fn try_bool_bias<S: Shape, E: Dtype + Float, D: Device<E>>(
+    mask: Tensor<S, bool, D>,
+) -> Result<Tensor<S, E, D>, crate::tensor::Error> {
+    let zeros = mask.device().try_zeros_like(mask.shape())?;
+    let neg_inf = zeros.clone().try_add(f64::NEG_INFINITY)?;
+    mask.try_choose(neg_inf, zeros)
+}
+
+impl<B: Dim, S1: Dim, S2: Dim, E: Dtype + Float, D: Device<E>> AttentionMask<B, S1, S2, E, D>
+    for Tensor<(S1, S2), bool, D>
+{
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        _: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error> {
+        try_bool_bias(self)?.try_broadcast_like::<_, Axis<0>>(shape)
+    }
+}
+
+impl<B: Dim, S1: Dim, S2: Dim, E: Dtype + Float, D: Device<E>> AttentionMask<B, S1, S2, E, D>
+    for Tensor<(B, S1, S2), bool, D>
+{
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        _: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error> {
+        assert_eq!(self.shape(), shape);
+        try_bool_bias(self)
+    }
+}
+
+impl<B: Dim, S1: Dim, S2: Dim, E: Dtype + Float, D: Device<E>> AttentionMask<B, S1, S2, E, D>
+    for KeyPaddingMask<Tensor<(S2,), bool, D>>
+{
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        _: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error> {
+        try_bool_bias(self.0)?.try_broadcast_like::<_, Axes2<0, 1>>(shape)
+    }
+}
+
+impl<B: Dim, S1: Dim, S2: Dim, E: Dtype + Float, D: Device<E>> AttentionMask<B, S1, S2, E, D>
+    for KeyPaddingMask<Tensor<(B, S2), bool, D>>
+{
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        _: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error> {
+        try_bool_bias(self.0)?.try_broadcast_like::<_, Axis<1>>(shape)
+    }
+}
+
+impl<B: Dim, S1: Dim, S2: Dim, E: Dtype + Float, D: Device<E>, M1, M2>
+    AttentionMask<B, S1, S2, E, D> for (M1, M2)
+where
+    M1: AttentionMask<B, S1, S2, E, D>,
+    M2: AttentionMask<B, S1, S2, E, D>,
+{
+    fn try_attention_bias(
+        self,
+        shape: &(B, S1, S2),
+        device: &D,
+    ) -> Result<Tensor<(B, S1, S2), E, D>, crate::tensor::Error> {
+        let a = self.0.try_attention_bias(shape, device)?;
+        let b = self.1.try_attention_bias(shape, device)?;
+        a.try_add(b)
+    }
+
+    fn try_head_attention_bias<H: Dim>(
+        self,
+        shape: &(B, H, S1, S2),
+        device: &D,
+    ) -> Result<Tensor<(B, H, S1, S2), E, D>, crate::tensor::Error> {
+        let a = self.0.try_head_attention_bias(shape, device)?;
+        let b = self.1.try_head_attention_bias(shape, device)?;
+        a.try_add(b)
+    }
+}
+
+// Boolean masks are implemented for any `D`, so additive masks are implemented per
+// float type to keep them from overlapping with `Tensor<_, bool, D>`.
+macro_rules! additive_mask {
+    ($($Float:ty),*) => {$(
+        impl<B: Dim, S1: Dim, S2: Dim, D: Device<$Float>> AttentionMask<B, S1, S2, $Float, D>
+            for Tensor<(S1, S2), $Float, D>
+        {
+            fn try_attention_bias(
+                self,
+                shape: &(B, S1, S2),
+                _: &D,
+            ) -> Result<Tensor<(B, S1, S2), $Float, D>, crate::tensor::Error> {
+                self.try_broadcast_like::<_, Axis<0>>(shape)
+            }
+        }
+
+        impl<B: Dim, S1: Dim, S2: Dim, D: Device<$Float>> AttentionMask<B, S1, S2, $Float, D>
+            for Tensor<(B, S1, S2), $Float, D>
+        {
+            fn try_attention_bias(
+                self,
+                shape: &(B, S1, S2),
+                _: &D,
+            ) -> Result<Tensor<(B, S1, S2), $Float, D>, crate::tensor::Error> {
+                assert_eq!(self.shape(), shape);
+                Ok(self)
+            }
+        }
+    )*};
+}
+
+additive_mask!(f32, f64);
+#[cfg(feature = "f16")]
+additive_mask!(crate::dtypes::f16, crate::dtypes::AMP<crate::dtypes::f16>);
+
 impl<M: Dim, H: Dim, K: Dim, V: Dim, E, D, S1, S2, T>
     Module<(
         Tensor<(S1, M), E, D, T>,
@@ -80,7 +357,47 @@ where
         let q = q.broadcast_like(&(Const::<1>, s1, m));
         let k = k.broadcast_like(&(Const::<1>, s2, m));
         let v = v.broadcast_like(&(Const::<1>, s2, m));
-        let out = self.try_forward((q, k, v))?;
+        let out = self.try_attend(q, k, v, None)?;
+        out.try_reshape_like(&(s1, m))
+    }
+}
+
+impl<M: Dim, H: Dim, K: Dim, V: Dim, E, D, S1, S2, T, Mask>
+    Module<(
+        Tensor<(S1, M), E, D, T>,
+        Tensor<(S2, M), E, D>,
+        Tensor<(S2, M), E, D>,
+        Mask,
+    )> for MultiHeadAttention<M, H, K, V, E, D>
+where
+    E: Dtype + Float,
+    D: Device<E>,
+    S1: Dim,
+    S2: Dim,
+    T: Tape<E, D>,
+    Mask: AttentionMask<Const<1>, S1, S2, E, D>,
+{
+    type Output = Tensor<(S1, M), E, D, T>;
+
+    /// Masked Encoder-Decoder style attention. See [AttentionMask] for the accepted masks.
+    fn try_forward(
+        &self,
+        (q, k, v, mask): (
+            Tensor<(S1, M), E, D, T>,
+            Tensor<(S2, M), E, D>,
+            Tensor<(S2, M), E, D>,
+            Mask,
+        ),
+    ) -> Result<Self::Output, crate::tensor::Error> {
+        assert_eq!(k.shape().0, v.shape().0);
+        let (s1, m) = *q.shape();
+        let s2 = k.shape().0;
+        let bias =
+            mask.try_head_attention_bias(&(Const::<1>, self.num_heads.size(), s1, s2), q.device())?;
+        let q = q.broadcast_like(&(Const::<1>, s1, m));
+        let k = k.broadcast_like(&(Const::<1>, s2, m));
+        let v = v.broadcast_like(&(Const::<1>, s2, m));
+        let out = self.try_attend(q, k, v, Some(bias))?;
         out.try_reshape_like(&(s1, m))
     }
 }
@@ -110,6 +427,56 @@ where
             Tensor<(B, S2, M), E, D>,
         ),
     ) -> Result<Self::Output, crate::tensor::Error> {
+        self.try_attend(q, k, v, None)
+    }
+}
+
+impl<M: Dim, H: Dim, K: Dim, V: Dim, E, D, B, S1, S2, T, Mask>
+    Module<(
+        Tensor<(B, S1, M), E, D, T>,
+        Tensor<(B, S2, M), E, D>,
+        Tensor<(B, S2, M), E, D>,
+        Mask,
+    )> for MultiHeadAttention<M, H, K, V, E, D>
+where
+    E: Dtype + Float,
+    D: Device<E>,
+    B: Dim,
+    S1: Dim,
+    S2: Dim,
+    T: Tape<E, D>,
+    Mask: AttentionMask<B, S1, S2, E, D>,
+{
+    type Output = Tensor<(B, S1, M), E, D, T>;
+
+    /// Batched masked Encoder-Decoder style attention. See [AttentionMask] for the accepted masks.
+    fn try_forward(
+        &self,
+        (q, k, v, mask): (
+            Tensor<(B, S1, M), E, D, T>,
+            Tensor<(B, S2, M), E, D>,
+            Tensor<(B, S2, M), E, D>,
+            Mask,
+        ),
+    ) -> Result<Self::Output, crate::tensor::Error> {
+        let (b, s1, _) = *q.shape();
+        let s2 = k.shape().1;
+        let bias = mask.try_head_attention_bias(&(b, self.num_heads.size(), s1, s2), q.device())?;
+        self.try_attend(q, k, v, Some(bias))
+    }
+}
+
+impl<M: Dim, H: Dim, K: Dim, V: Dim, E: Dtype + Float, D: Device<E>>
+    MultiHeadAttention<M, H, K, V, E, D>
+{
+    #[allow(clippy::type_complexity)]
+    fn try_attend<B: Dim, S1: Dim, S2: Dim, T: Tape<E, D>>(
+        &self,
+        q: Tensor<(B, S1, M), E, D, T>,
+        k: Tensor<(B, S2, M), E, D>,
+        v: Tensor<(B, S2, M), E, D>,
+        bias: Option<Tensor<(B, usize, S1, S2), E, D>>,
+    ) -> Result<Tensor<(B, S1, M), E, D, T>, crate::tensor::Error> {
         assert_eq!(q.shape().0, k.shape().0);
         assert_eq!(q.shape().0, v.shape().0);
         assert_eq!(k.shape().1, v.shape().1);
@@ -126,15 +493,30 @@ where
 
         let k = self.w_k.try_forward(k.retaped::<T>())?;
         let k = k.try_reshape_like(&(b, s2, h_dim, k_dim / h_dim))?;
-        let k = k.try_permute::<_, Axes4<0, 2, 3, 1>>()?;
+        let k = match self.rotary {
+            Some(rope) => {
+                let k = k.try_permute::<_, Axes4<0, 2, 1, 3>>()?;
+                let k = rope.try_forward((k, 0))?;
+                k.try_permute::<_, Axes4<0, 1, 3, 2>>()?
+            }
+            None => k.try_permute::<_, Axes4<0, 2, 3, 1>>()?,
+        };
 
         let q = self.w_q.try_forward(q)?;
         let q = q.try_reshape_like(&(b, s1, h_dim, k_dim / h_dim))?;
         let q = q.try_permute::<_, Axes4<0, 2, 1, 3>>()?;
+        let q = match self.rotary {
+            Some(rope) => rope.try_forward((q, s2.size().saturating_sub(s1.size())))?,
+            None => q,
+        };
 
         // Get weights
         let scalar = 1.0 / ((k_dim / h_dim) as f64).sqrt();
         let weights = q.try_matmul(k)?.try_mul(scalar)?;
+        let weights = match bias {
+            Some(bias) => weights.try_add(bias)?,
+            None => weights,
+        };
         let weights = weights.try_softmax::<Axis<3>>()?;
 
         // Get new tokens
@@ -264,6 +646,184 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mha_masks_agree() {
+        let dev = TestDevice::seed_from_u64(2);
+
+        let mha = dev
+            .build_module::<TestDtype>(<MultiHeadAttentionConfig<Const<8>, Const<2>>>::default());
+
+        let x: Tensor<Rank3<2, 4, 8>, TestDtype, _> = dev.sample_normal();
+        let causal = mha.forward((x.clone(), x.clone(), x.clone(), CausalMask));
+
+        let bool_mask: Tensor<Rank2<4, 4>, bool, _> = dev.tensor([
+            [false, true, true, true],
+            [false, false, true, true],
+            [false, false, false, true],
+            [false, false, false, false],
+        ]);
+        let y = mha.forward((x.clone(), x.clone(), x.clone(), bool_mask));
+        assert_close_to_tensor!(causal, y);
+
+        let additive: Tensor<Rank2<4, 4>, TestDtype, _> =
+            dev.upper_tri(TestDtype::neg_infinity(), 1);
+        let y = mha.forward((x.clone(), x.clone(), x.clone(), additive));
+        assert_close_to_tensor!(causal, y);
+
+        // unbatched masks apply to each batch item
+        let y: Tensor<Rank2<4, 8>, TestDtype, _> = mha.forward((
+            x.clone().select(dev.tensor(1)),
+            x.clone().select(dev.tensor(1)),
+            x.clone().select(dev.tensor(1)),
+            CausalMask,
+        ));
+        assert_close_to_tensor!(causal.select(dev.tensor(1)), y);
+    }
+
+    #[test]
+    fn test_mha_causal_mask_ignores_future() {
+        let dev = TestDevice::seed_from_u64(3);
+
+        let mha = dev
+            .build_module::<TestDtype>(<MultiHeadAttentionConfig<Const<8>, Const<2>>>::default());
+
+        let x: Tensor<Rank2<4, 8>, TestDtype, _> = dev.sample_normal();
+        let y = mha.forward((x.clone(), x.clone(), x.clone(), CausalMask));
+
+        // changing the last token must not change any of the earlier outputs
+        let mut data = x.as_vec();
+        for v in data[24..].iter_mut() {
+            *v += TestDtype::ONE;
+        }
+        let x2: Tensor<Rank2<4, 8>, TestDtype, _> = dev.tensor(data);
+        let y2 = mha.forward((x2.clone(), x2.clone(), x2, CausalMask));
+
+        assert_close_to_tensor!(
+            y.clone().slice((..3, ..)).realize::<Rank2<3, 8>>(),
+            y2.clone().slice((..3, ..)).realize::<Rank2<3, 8>>()
+        );
+        assert_ne!(y.as_vec()[24..], y2.as_vec()[24..]);
+    }
+
+    #[test]
+    fn test_mha_key_padding_mask() {
+        let dev = TestDevice::seed_from_u64(4);
+
+        let mha = dev
+            .build_module::<TestDtype>(<MultiHeadAttentionConfig<Const<8>, Const<2>>>::default());
+
+        let q: Tensor<Rank3<2, 3, 8>, TestDtype, _> = dev.sample_normal();
+        let k: Tensor<Rank3<2, 5, 8>, TestDtype, _> = dev.sample_normal();
+        let v: Tensor<Rank3<2, 5, 8>, TestDtype, _> = dev.sample_normal();
+
+        // padding the last two keys is the same as removing them
+        let pad = dev.tensor([false, false, false, true, true]);
+        let y = mha.forward((q.clone(), k.clone(), v.clone(), KeyPaddingMask(pad)));
+        let k2 = k.clone().slice((.., ..3, ..)).realize::<Rank3<2, 3, 8>>();
+        let v2 = v.clone().slice((.., ..3, ..)).realize::<Rank3<2, 3, 8>>();
+        let expected = mha.forward((q.clone(), k2.clone(), v2.clone()));
+        assert_close_to_tensor!(y, expected);
+
+        // masks can be combined
+        let pad = dev.tensor([[false, false, false, true, true]; 2]);
+        let y = mha.forward((
+            q.clone(),
+            k.clone(),
+            v.clone(),
+            (CausalMask, KeyPaddingMask(pad)),
+        ));
+        let expected = mha.forward((q, k2, v2, CausalMask));
+        assert_close_to_tensor!(y, expected);
+    }
+
+    #[test]
+    fn test_alibi_slopes() {
+        assert_eq!(ALiBi::slopes(4), [0.25, 0.0625, 0.015625, 0.00390625]);
+        let s8 = ALiBi::slopes(8);
+        let s6 = ALiBi::slopes(6);
+        assert_eq!(s6[..4], ALiBi::slopes(4));
+        assert_eq!(s6[4..], [s8[0], s8[2]]);
+        assert!(ALiBi::slopes(0).is_empty());
+    }
+
+    #[test]
+    fn test_alibi_bias() {
+        let dev: TestDevice = Default::default();
+        let bias: Tensor<Rank4<1, 2, 2, 3>, TestDtype, _> = ALiBi
+            .try_head_attention_bias(&Default::default(), &dev)
+            .unwrap();
+        // queries are the last 2 of the 3 key positions
+        assert_close_to_literal!(
+            bias,
+            [[
+                [[-0.0625, 0.0, -0.0625], [-0.125, -0.0625, 0.0]],
+                [
+                    [-0.00390625, 0.0, -0.00390625],
+                    [-0.0078125, -0.00390625, 0.0]
+                ],
+            ]]
+        );
+
+        // with more queries than keys, the queries start at position 0
+        let bias: Tensor<Rank3<1, 3, 2>, TestDtype, _> =
+            ALiBi.try_attention_bias(&Default::default(), &dev).unwrap();
+        assert_close_to_literal!(
+            bias,
+            [[
+                [0.0, -0.00390625],
+                [-0.00390625, 0.0],
+                [-0.0078125, -0.00390625]
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_mha_alibi() {
+        let dev = TestDevice::seed_from_u64(5);
+
+        let mha = dev
+            .build_module::<TestDtype>(<MultiHeadAttentionConfig<Const<8>, Const<2>>>::default());
+
+        let x: Tensor<Rank3<2, 4, 8>, TestDtype, _> = dev.sample_normal();
+        let y = mha.forward((x.clone(), x.clone(), x.clone(), (CausalMask, ALiBi)));
+        let y_causal = mha.forward((x.clone(), x.clone(), x.clone(), CausalMask));
+        assert_ne!(y.as_vec(), y_causal.as_vec());
+
+        // the last query on its own matches the last output of the whole sequence
+        let q = x.clone().slice((.., 3.., ..)).realize::<Rank3<2, 1, 8>>();
+        let y_last = mha.forward((q, x.clone(), x, ALiBi));
+        assert_close_to_tensor!(y_last, y.slice((.., 3.., ..)).realize::<Rank3<2, 1, 8>>());
+    }
+
+    #[test]
+    fn test_mha_rotary() {
+        let dev = TestDevice::seed_from_u64(6);
+
+        let mut mha = dev.build_module::<TestDtype>(MultiHeadAttentionConfig {
+            rotary: Some(RotaryEmbedding::default()),
+            ..<MultiHeadAttentionConfig<Const<8>, Const<2>>>::default()
+        });
+
+        let x: Tensor<Rank3<2, 4, 8>, TestDtype, _> = dev.sample_normal();
+        let y = mha.forward((x.clone(), x.clone(), x.clone(), CausalMask));
+
+        // incremental decoding: the last query attends to all the keys so far
+        let q = x.clone().slice((.., 3.., ..)).realize::<Rank3<2, 1, 8>>();
+        let y_last = mha.forward((q, x.clone(), x.clone()));
+        assert_close_to_tensor!(
+            y_last,
+            y.clone().slice((.., 3.., ..)).realize::<Rank3<2, 1, 8>>()
+        );
+
+        // more queries than keys
+        let kv = x.clone().slice((.., ..2, ..)).realize::<Rank3<2, 2, 8>>();
+        let _: Tensor<Rank3<2, 4, 8>, _, _> = mha.forward((x.clone(), kv.clone(), kv));
+
+        mha.rotary = None;
+        let y_plain = mha.forward((x.clone(), x.clone(), x, CausalMask));
+        assert_ne!(y.as_vec(), y_plain.as_vec());
+    }
+
     #[test]
     fn test_backward_updates_all() {
         let dev: TestDevice = Default::default();
@@ -280,4 +840,27 @@ mod tests {
         let mut opt = crate::nn::optim::Sgd::new(&mha, Default::default());
         opt.update(&mut mha, &g).expect("");
     }
+
+    #[test]
+    fn test_masked_backward_updates_all() {
+        let dev: TestDevice = Default::default();
+
+        let mut mha = dev
+            .build_module::<TestDtype>(<MultiHeadAttentionConfig<Const<12>, Const<4>>>::default());
+
+        let x: Tensor<Rank3<2, 4, 12>, TestDtype, _> = dev.sample_normal();
+        let y = mha.forward((x.leaky_trace(), x.clone(), x, CausalMask));
+        let g = y.square().mean().backward();
+        for w in [
+            &mha.w_q.weight,
+            &mha.w_k.weight,
+            &mha.w_v.weight,
+            &mha.w_o.weight,
+        ] {
+            assert!(g.get(w).as_vec().iter().all(|v| v.is_finite()));
+        }
+
+        let mut opt = crate::nn::optim::Sgd::new(&mha, Default::default());
+        opt.update(&mut mha, &g).expect("");
+    }
 }
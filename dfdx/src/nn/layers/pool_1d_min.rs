@@ -0,0 +1,44 @@
+use crate::prelude::*;
+
+/// Minimum pool with 1d kernel that operates on sequences (2d) and batches of sequences (3d).
+/// Each patch reduces to the minimum of the values in the patch.
+///
+/// Generics:
+/// - `KERNEL_SIZE`: The length of the kernel.
+/// - `STRIDE`: How far to move the kernel each step. Defaults to `1`
+/// - `PADDING`: How much zero padding to add to both ends of the sequences. Defaults to `0`.
+/// - `DILATION` How dilated the kernel should be. Defaults to `1`.
+#[derive(Debug, Default, Clone, CustomModule)]
+pub struct MinPool1D<
+    KernelSize: Dim,
+    Stride: Dim = Const<1>,
+    Padding: Dim = Const<0>,
+    Dilation: Dim = Const<1>,
+> {
+    pub kernel_size: KernelSize,
+    pub stride: Stride,
+    pub padding: Padding,
+    pub dilation: Dilation,
+}
+
+pub type MinPool1DConst<
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    const DILATION: usize = 1,
+> = MinPool1D<Const<KERNEL_SIZE>, Const<STRIDE>, Const<PADDING>, Const<DILATION>>;
+
+impl<K: Dim, S: Dim, P: Dim, L: Dim, Seq: TryPool1D<K, S, P, L>> Module<Seq>
+    for MinPool1D<K, S, P, L>
+{
+    type Output = Seq::Pooled;
+    fn try_forward(&self, x: Seq) -> Result<Self::Output, Error> {
+        x.try_pool1d(
+            crate::tensor_ops::Pool2DKind::Min,
+            self.kernel_size,
+            self.stride,
+            self.padding,
+            self.dilation,
+        )
+    }
+}
@@ -0,0 +1,40 @@
+use crate::prelude::*;
+
+/// Max pool that reduces images (3d) and batches of images (4d) to a fixed height & width,
+/// whatever the size of the input. Neighboring patches may overlap when the output size doesn't
+/// divide the input size.
+///
+/// **Pytorch equivalent**: `torch.nn.AdaptiveMaxPool2d((OUT_H, OUT_W))`
+///
+/// Generics:
+/// - `OutHeight`: The height of the output images.
+/// - `OutWidth`: The width of the output images.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let m: AdaptiveMaxPool2DConst<2, 3> = Default::default();
+/// let _: Tensor<Rank3<5, 2, 3>, f32, _> = m.forward(dev.zeros::<Rank3<5, 16, 8>>());
+/// let _: Tensor<Rank4<10, 5, 2, 3>, f32, _> = m.forward(dev.zeros::<Rank4<10, 5, 7, 7>>());
+/// ```
+#[derive(Debug, Default, Clone, CustomModule)]
+pub struct AdaptiveMaxPool2D<OutHeight: Dim, OutWidth: Dim> {
+    pub out_height: OutHeight,
+    pub out_width: OutWidth,
+}
+
+pub type AdaptiveMaxPool2DConst<const OUT_HEIGHT: usize, const OUT_WIDTH: usize> =
+    AdaptiveMaxPool2D<Const<OUT_HEIGHT>, Const<OUT_WIDTH>>;
+
+impl<H: Dim, W: Dim, Img: TryAdaptivePool2D<H, W>> Module<Img> for AdaptiveMaxPool2D<H, W> {
+    type Output = Img::Pooled;
+
+    fn try_forward(&self, x: Img) -> Result<Self::Output, Error> {
+        x.try_adaptive_pool2d(
+            crate::tensor_ops::Pool2DKind::Max,
+            self.out_height,
+            self.out_width,
+        )
+    }
+}
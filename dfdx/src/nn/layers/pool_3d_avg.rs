@@ -0,0 +1,45 @@
+use crate::prelude::*;
+
+/// Average pool with 3d kernel that operates on volumes (4d) and batches of volumes (5d).
+/// Each patch reduces to the average of the values in the patch.
+///
+/// Generics:
+/// - `KernelSize`: The size of the kernel applied to the depth, height and width of the volumes.
+/// - `Stride`: How far to move the kernel each step. Defaults to `1`
+/// - `Padding`: How much zero padding to add around the volumes. Defaults to `0`.
+/// - `Dilation` How dilated the kernel should be. Defaults to `1`.
+#[derive(Debug, Default, Clone, CustomModule)]
+pub struct AvgPool3D<
+    KernelSize: Dim,
+    Stride: Dim = Const<1>,
+    Padding: Dim = Const<0>,
+    Dilation: Dim = Const<1>,
+> {
+    pub kernel_size: KernelSize,
+    pub stride: Stride,
+    pub padding: Padding,
+    pub dilation: Dilation,
+}
+
+pub type AvgPool3DConst<
+    const KERNEL_SIZE: usize,
+    const STRIDE: usize = 1,
+    const PADDING: usize = 0,
+    const DILATION: usize = 1,
+> = AvgPool3D<Const<KERNEL_SIZE>, Const<STRIDE>, Const<PADDING>, Const<DILATION>>;
+
+impl<K: Dim, S: Dim, P: Dim, L: Dim, Vol: TryPool3D<K, S, P, L>> Module<Vol>
+    for AvgPool3D<K, S, P, L>
+{
+    type Output = Vol::Pooled;
+
+    fn try_forward(&self, x: Vol) -> Result<Self::Output, Error> {
+        x.try_pool3d(
+            crate::tensor_ops::Pool2DKind::Avg,
+            self.kernel_size,
+            self.stride,
+            self.padding,
+            self.dilation,
+        )
+    }
+}
@@ -0,0 +1,387 @@
+use crate::prelude::*;
+
+use rand_distr::Uniform;
+
+/// The weights of a single layer & direction of [LSTM], [GRU] or [RNN].
+///
+/// The layout is the same as pytorch, where the weights of each gate are
+/// stacked along the first axis:
+/// - [LSTM]: `(input, forget, cell, output)`, so `weight_ih` is `(4 * H, I)`
+/// - [GRU]: `(reset, update, new)`, so `weight_ih` is `(3 * H, I)`
+/// - [RNN]: a single gate, so `weight_ih` is `(H, I)`
+///
+/// The input size `I` is `usize` because every layer after the first receives
+/// `H * num_directions` features.
+#[derive(Clone, Debug, UpdateParams, ZeroGrads, WithGrads)]
+pub struct RecurrentWeights<H: Dim, Elem: Dtype, Dev: Device<Elem>> {
+    #[param]
+    pub weight_ih: Tensor<(usize, usize), Elem, Dev>,
+    #[param]
+    pub weight_hh: Tensor<(usize, H), Elem, Dev>,
+    #[param]
+    pub bias_ih: Tensor<(usize,), Elem, Dev>,
+    #[param]
+    pub bias_hh: Tensor<(usize,), Elem, Dev>,
+}
+
+impl<H: Dim, E, D: Device<E>> ResetParams<E, D> for RecurrentWeights<H, E, D>
+where
+    E: Dtype + num_traits::Float + rand_distr::uniform::SampleUniform,
+{
+    /// Same as pytorch, all weights & biases are sampled from `U(-1/sqrt(H), 1/sqrt(H))`.
+    fn try_reset_params(&mut self) -> Result<(), crate::tensor::Error> {
+        let h = self.weight_hh.shape().1;
+        let b = E::from_f64(1.0 / (h.size() as f64).sqrt()).unwrap();
+        self.weight_ih.try_fill_with_distr(Uniform::new(-b, b))?;
+        self.weight_hh.try_fill_with_distr(Uniform::new(-b, b))?;
+        self.bias_ih.try_fill_with_distr(Uniform::new(-b, b))?;
+        self.bias_hh.try_fill_with_distr(Uniform::new(-b, b))
+    }
+}
+
+/// Which recurrent cell a set of [RecurrentWeights] belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Cell {
+    RnnTanh,
+    RnnReLU,
+    Lstm,
+    Gru,
+}
+
+impl Cell {
+    fn num_gates(&self) -> usize {
+        match self {
+            Cell::RnnTanh | Cell::RnnReLU => 1,
+            Cell::Lstm => 4,
+            Cell::Gru => 3,
+        }
+    }
+}
+
+/// Builds `num_layers * num_directions` [RecurrentWeights], ordered the same as
+/// pytorch: `l0, l0_reverse, l1, l1_reverse, ...`.
+pub(crate) fn try_build_weights<H: Dim, E: Dtype, D: Device<E>>(
+    device: &D,
+    cell: Cell,
+    input: usize,
+    hidden: H,
+    num_layers: usize,
+    bidirectional: bool,
+) -> Result<Vec<RecurrentWeights<H, E, D>>, crate::tensor::Error> {
+    assert!(num_layers > 0, "Recurrent layers need at least one layer");
+    let dirs = if bidirectional { 2 } else { 1 };
+    let gates = cell.num_gates() * hidden.size();
+    let mut weights = Vec::with_capacity(num_layers * dirs);
+    for layer in 0..num_layers {
+        let inp = if layer == 0 {
+            input
+        } else {
+            dirs * hidden.size()
+        };
+        for _ in 0..dirs {
+            weights.push(RecurrentWeights {
+                weight_ih: device.try_zeros_like(&(gates, inp))?,
+                weight_hh: device.try_zeros_like(&(gates, hidden))?,
+                bias_ih: device.try_zeros_like(&(gates,))?,
+                bias_hh: device.try_zeros_like(&(gates,))?,
+            });
+        }
+    }
+    Ok(weights)
+}
+
+/// Runs a (possibly multi-layer, bidirectional) recurrent network over `x`.
+///
+/// Returns the output of the last layer `(B, S, H * num_directions)`, and the
+/// final hidden (and cell, for [Cell::Lstm]) state of every layer & direction
+/// `(num_layers * num_directions, B, H)`.
+///
+/// All operations are recorded on the output's tape. The returned states have
+/// an empty tape, so they need to be combined with the output to backprop through them.
+#[allow(clippy::type_complexity)]
+pub(crate) fn try_forward<B: Dim, S: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>(
+    cell: Cell,
+    weights: &[RecurrentWeights<H, E, D>],
+    bidirectional: bool,
+    hidden: H,
+    x: Tensor<(B, S, usize), E, D, T>,
+    h0: Option<Tensor<(usize, B, H), E, D>>,
+    c0: Option<Tensor<(usize, B, H), E, D>>,
+) -> Result<
+    (
+        Tensor<(B, S, usize), E, D, T>,
+        Tensor<(usize, B, H), E, D, T>,
+        Tensor<(usize, B, H), E, D, T>,
+    ),
+    crate::tensor::Error,
+> {
+    let (b, s, _) = *x.shape();
+    let dirs = if bidirectional { 2 } else { 1 };
+    let state_shape = (weights.len(), b, hidden);
+    if let Some(h0) = &h0 {
+        assert_eq!(h0.shape(), &state_shape);
+    }
+    if let Some(c0) = &c0 {
+        assert_eq!(c0.shape(), &state_shape);
+    }
+    let zeros = x.device().try_zeros_like(&state_shape)?;
+    let h0 = h0.unwrap_or_else(|| zeros.clone());
+    let c0 = c0.unwrap_or(zeros);
+
+    let mut x = x;
+    let mut h_n = Vec::with_capacity(weights.len());
+    let mut c_n = Vec::with_capacity(weights.len());
+    for (layer, weights) in weights.chunks(dirs).enumerate() {
+        let mut outputs = Vec::with_capacity(dirs);
+        let mut tape: Option<T> = None;
+        for (dir, w) in weights.iter().enumerate() {
+            let k = layer * dirs + dir;
+            // the last direction takes the input's tape
+            let inp = x.with_empty_tape();
+            let inp = if dir + 1 == dirs {
+                std::mem::replace(&mut x, inp)
+            } else {
+                inp
+            };
+            let h = h0.clone().try_slice((k..k + 1, .., ..))?;
+            let c = c0.clone().try_slice((k..k + 1, .., ..))?;
+            let (out, h, c, t) = try_forward_direction(
+                cell,
+                w,
+                hidden,
+                inp,
+                h.try_reshape_like(&(b, hidden))?,
+                c.try_reshape_like(&(b, hidden))?,
+                dir == 1,
+            )?;
+            outputs.push(out);
+            h_n.push(h);
+            c_n.push(c);
+            tape = Some(match tape {
+                Some(tape) => tape.merge(t),
+                None => t,
+            });
+        }
+
+        // (S, B, H * num_directions)
+        let tape = tape.unwrap();
+        let seq_shape = (s.size(), b, hidden.size());
+        let out = if bidirectional {
+            let rev = outputs.pop().unwrap().retaped::<T>();
+            let fwd = outputs.pop().unwrap().put_tape(tape);
+            let rev = rev.try_reshape_like(&seq_shape)?;
+            let fwd = fwd.try_reshape_like(&seq_shape)?;
+            (fwd, rev).try_concat_tensor_along(Axis::<2>)?
+        } else {
+            let out = outputs.pop().unwrap().put_tape(tape);
+            out.try_reshape_like(&seq_shape)?
+        };
+        let out = out.try_permute::<_, Axes3<1, 0, 2>>()?;
+        x = out.try_reshape_like(&(b, s, dirs * hidden.size()))?;
+    }
+
+    let h_n = h_n.try_stack()?.retaped::<T>();
+    let c_n = c_n.try_stack()?.retaped::<T>();
+    Ok((x, h_n, c_n))
+}
+
+/// Runs a single direction of a single layer. Returns the hidden state of
+/// every timestep `(S, B, H)` in time order, the final hidden & cell state, and the tape.
+#[allow(clippy::type_complexity)]
+fn try_forward_direction<B: Dim, S: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>(
+    cell: Cell,
+    w: &RecurrentWeights<H, E, D>,
+    hidden: H,
+    x: Tensor<(B, S, usize), E, D, T>,
+    h0: Tensor<(B, H), E, D>,
+    c0: Tensor<(B, H), E, D>,
+    reverse: bool,
+) -> Result<
+    (
+        Tensor<(usize, B, H), E, D>,
+        Tensor<(B, H), E, D>,
+        Tensor<(B, H), E, D>,
+        T,
+    ),
+    crate::tensor::Error,
+> {
+    let (b, s, _) = *x.shape();
+    let gates = w.weight_hh.shape().0;
+
+    // the input projection is done for all timesteps at once
+    let xw = x.try_matmul(w.weight_ih.retaped::<T>().try_permute()?)?;
+    let bias_ih = w.bias_ih.retaped::<T>();
+    let xw = xw.try_add(bias_ih.try_broadcast_like::<_, Axes2<0, 1>>(&(b, s, gates))?)?;
+    let (xw, tape) = xw.split_tape();
+
+    let mut h = h0.put_tape(tape);
+    let mut c = c0.retaped::<T>();
+    let mut outputs = Vec::with_capacity(s.size());
+    for i in 0..s.size() {
+        let t = if reverse { s.size() - 1 - i } else { i };
+        let gx = xw.clone().retaped::<T>().try_slice((.., t..t + 1, ..))?;
+        let gx = gx.try_reshape_like(&(b, gates))?;
+        (h, c) = try_step(cell, w, hidden, gx, h, c)?;
+        outputs.push(h.retaped::<NoneTape>());
+    }
+    if reverse {
+        outputs.reverse();
+    }
+
+    let (h, h_tape) = h.split_tape();
+    let (c, c_tape) = c.split_tape();
+    let mut tape = Some(h_tape.merge(c_tape));
+    let outputs: Vec<_> = outputs
+        .into_iter()
+        .map(|h| match tape.take() {
+            Some(tape) => h.put_tape(tape),
+            None => h.retaped::<T>(),
+        })
+        .collect();
+    let (outputs, tape) = outputs.try_stack()?.split_tape();
+    Ok((outputs, h, c, tape))
+}
+
+fn try_chunk<B: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>(
+    x: Tensor<(B, usize), E, D, T>,
+    i: usize,
+    hidden: H,
+) -> Result<Tensor<(B, H), E, D, T>, crate::tensor::Error> {
+    let b = x.shape().0;
+    let h = hidden.size();
+    x.try_slice((.., i * h..(i + 1) * h))?
+        .try_reshape_like(&(b, hidden))
+}
+
+#[allow(clippy::type_complexity)]
+fn try_step<B: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>(
+    cell: Cell,
+    w: &RecurrentWeights<H, E, D>,
+    hidden: H,
+    gx: Tensor<(B, usize), E, D, T>,
+    h: Tensor<(B, H), E, D, T>,
+    c: Tensor<(B, H), E, D, T>,
+) -> Result<(Tensor<(B, H), E, D, T>, Tensor<(B, H), E, D, T>), crate::tensor::Error> {
+    let b = h.shape().0;
+    let gates = w.weight_hh.shape().0;
+    let weight_hh = w.weight_hh.retaped::<T>().try_permute()?;
+    let bias_hh = w.bias_hh.retaped::<T>();
+    let bias_hh = bias_hh.try_broadcast_like::<_, Axis<0>>(&(b, gates))?;
+
+    match cell {
+        Cell::RnnTanh | Cell::RnnReLU => {
+            let gh = h.try_matmul(weight_hh)?.try_add(bias_hh)?;
+            let x = try_chunk(gx.try_add(gh)?, 0, hidden)?;
+            let h = if cell == Cell::RnnTanh {
+                x.try_tanh()?
+            } else {
+                x.try_relu()?
+            };
+            Ok((h, c))
+        }
+        Cell::Lstm => {
+            let gh = h.try_matmul(weight_hh)?.try_add(bias_hh)?;
+            let g = gx.try_add(gh)?;
+            let i = try_chunk(g.with_empty_tape(), 0, hidden)?.try_sigmoid()?;
+            let f = try_chunk(g.with_empty_tape(), 1, hidden)?.try_sigmoid()?;
+            let cell = try_chunk(g.with_empty_tape(), 2, hidden)?.try_tanh()?;
+            let o = try_chunk(g, 3, hidden)?.try_sigmoid()?;
+            let c = f.try_mul(c)?.try_add(i.try_mul(cell)?)?;
+            let h = o.try_mul(c.with_empty_tape().try_tanh()?)?;
+            Ok((h, c))
+        }
+        Cell::Gru => {
+            let gh = h
+                .with_empty_tape()
+                .try_matmul(weight_hh)?
+                .try_add(bias_hh)?;
+            let r = try_chunk(gx.with_empty_tape(), 0, hidden)?
+                .try_add(try_chunk(gh.with_empty_tape(), 0, hidden)?)?
+                .try_sigmoid()?;
+            let z = try_chunk(gx.with_empty_tape(), 1, hidden)?
+                .try_add(try_chunk(gh.with_empty_tape(), 1, hidden)?)?
+                .try_sigmoid()?;
+            let n = try_chunk(gx, 2, hidden)?
+                .try_add(r.try_mul(try_chunk(gh, 2, hidden)?)?)?
+                .try_tanh()?;
+            let h = z
+                .with_empty_tape()
+                .try_negate()?
+                .try_add(1.0)?
+                .try_mul(n)?
+                .try_add(z.try_mul(h)?)?;
+            Ok((h, c))
+        }
+    }
+}
+
+/// Visits the weights with the same locations as [write_safetensors].
+pub(crate) fn try_visit_params<H: Dim, E: Dtype, D: Device<E>, V: ParamsVisitor<E, D>>(
+    weights: &[RecurrentWeights<H, E, D>],
+    bidirectional: bool,
+    type_name: &'static str,
+    location: &str,
+    visitor: &mut V,
+) -> Result<(), Error> {
+    visitor.enter_module(location, type_name);
+    for (k, w) in weights.iter().enumerate() {
+        let suffix = key_suffix(k, bidirectional);
+        visitor.visit_param(&format!("{location}weight_ih_{suffix}"), &w.weight_ih)?;
+        visitor.visit_param(&format!("{location}weight_hh_{suffix}"), &w.weight_hh)?;
+        visitor.visit_param(&format!("{location}bias_ih_{suffix}"), &w.bias_ih)?;
+        visitor.visit_param(&format!("{location}bias_hh_{suffix}"), &w.bias_hh)?;
+    }
+    visitor.exit_module();
+    Ok(())
+}
+
+#[cfg(feature = "safetensors")]
+pub(crate) fn write_safetensors<H: Dim, E: Dtype, D: Device<E>>(
+    weights: &[RecurrentWeights<H, E, D>],
+    bidirectional: bool,
+    location: &str,
+    tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+) {
+    for (k, w) in weights.iter().enumerate() {
+        let suffix = key_suffix(k, bidirectional);
+        w.weight_ih
+            .write_safetensors(&format!("{location}weight_ih_{suffix}"), tensors);
+        w.weight_hh
+            .write_safetensors(&format!("{location}weight_hh_{suffix}"), tensors);
+        w.bias_ih
+            .write_safetensors(&format!("{location}bias_ih_{suffix}"), tensors);
+        w.bias_hh
+            .write_safetensors(&format!("{location}bias_hh_{suffix}"), tensors);
+    }
+}
+
+#[cfg(feature = "safetensors")]
+pub(crate) fn read_safetensors<H: Dim, E: Dtype, D: Device<E>>(
+    weights: &mut [RecurrentWeights<H, E, D>],
+    bidirectional: bool,
+    location: &str,
+    tensors: &::safetensors::SafeTensors,
+) -> Result<(), ::safetensors::SafeTensorError> {
+    for (k, w) in weights.iter_mut().enumerate() {
+        let suffix = key_suffix(k, bidirectional);
+        w.weight_ih
+            .read_safetensors(&format!("{location}weight_ih_{suffix}"), tensors)?;
+        w.weight_hh
+            .read_safetensors(&format!("{location}weight_hh_{suffix}"), tensors)?;
+        w.bias_ih
+            .read_safetensors(&format!("{location}bias_ih_{suffix}"), tensors)?;
+        w.bias_hh
+            .read_safetensors(&format!("{location}bias_hh_{suffix}"), tensors)?;
+    }
+    Ok(())
+}
+
+/// The pytorch parameter suffix of the `k`th [RecurrentWeights], e.g. `l1_reverse`.
+fn key_suffix(k: usize, bidirectional: bool) -> String {
+    if bidirectional {
+        let reverse = if k % 2 == 1 { "_reverse" } else { "" };
+        format!("l{}{reverse}", k / 2)
+    } else {
+        format!("l{k}")
+    }
+}
@@ -0,0 +1,161 @@
+use crate::prelude::*;
+
+/// Implements root mean square layer normalization as described in
+/// [Root Mean Square Layer Normalization](https://arxiv.org/abs/1910.07467), as used in LLaMA.
+///
+/// The last axis of the input is divided by its root mean square, and then scaled element-wise by a learnable
+/// parameter. Unlike [crate::nn::LayerNorm1D], the input is not centered and there is no bias.
+///
+/// Epsilon is added to the mean square to ensure big enough numbers. It defaults to `1e-6`.
+///
+/// **Pytorch Equivalent**: `torch.nn.RMSNorm(m, eps=1e-6)`
+///
+/// Generics:
+/// - `M` The size of the affine transform tensor.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = RMSNormConstConfig<5>;
+/// let model = dev.build_module::<f32>(Model::default());
+/// let _: Tensor<Rank3<2, 3, 5>, f32, _> = model.forward(dev.zeros::<Rank3<2, 3, 5>>());
+/// ```
+#[derive(Default, Clone, Copy, Debug)]
+#[repr(transparent)]
+pub struct RMSNormConfig<M: Dim>(pub M);
+
+/// Compile time sugar alias around [RMSNormConfig]
+pub type RMSNormConstConfig<const M: usize> = RMSNormConfig<Const<M>>;
+
+impl<M: Dim, E: Dtype, D: Device<E>> BuildOnDevice<E, D> for RMSNormConfig<M> {
+    type Built = RMSNorm<M, E, D>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        Ok(RMSNorm {
+            gamma: device.try_ones_like(&(self.0,))?,
+            epsilon: 1e-6,
+        })
+    }
+}
+
+/// See [RMSNormConfig]
+#[derive(Clone, Debug, UpdateParams, ZeroGrads, WithGrads, VisitParams)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct RMSNorm<M: Dim, Elem: Dtype, Dev: Device<Elem>> {
+    #[param]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub gamma: Tensor<(M,), Elem, Dev>,
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub epsilon: f64,
+}
+
+impl<M: Dim, E: Dtype, D: Device<E>> ResetParams<E, D> for RMSNorm<M, E, D> {
+    fn try_reset_params(&mut self) -> Result<(), crate::tensor::Error> {
+        self.gamma.try_fill_with_ones()
+    }
+}
+
+/// Divides `x` by its root mean square along `Ax`.
+fn try_rms_normalize<
+    Ax: Axes,
+    S: Shape + ReduceShape<Ax>,
+    E: Dtype,
+    D: Device<E>,
+    T: Tape<E, D>,
+>(
+    x: Tensor<S, E, D, T>,
+    epsilon: f64,
+) -> Result<Tensor<S, E, D, T>, Error> {
+    let shape = *x.shape();
+    let rms = x
+        .with_empty_tape()
+        .try_square()?
+        .try_mean::<_, Ax>()?
+        .try_add(epsilon)?
+        .try_sqrt()?;
+    x.try_div(rms.try_broadcast_like(&shape)?)
+}
+
+impl<M: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<(M,), E, D, T>>
+    for RMSNorm<M, E, D>
+{
+    type Output = Tensor<(M,), E, D, T>;
+    fn try_forward(&self, x: Tensor<(M,), E, D, T>) -> Result<Self::Output, Error> {
+        try_rms_normalize::<Axis<0>, _, _, _, _>(x, self.epsilon)?.try_mul(self.gamma.clone())
+    }
+}
+
+impl<Batch: Dim, M: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<(Batch, M), E, D, T>>
+    for RMSNorm<M, E, D>
+{
+    type Output = Tensor<(Batch, M), E, D, T>;
+    fn try_forward(&self, x: Tensor<(Batch, M), E, D, T>) -> Result<Self::Output, Error> {
+        let x = try_rms_normalize::<Axis<1>, _, _, _, _>(x, self.epsilon)?;
+        self.gamma.retaped::<T>().broadcast_like(&x).try_mul(x)
+    }
+}
+
+impl<Batch: Dim, Seq: Dim, M: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(Batch, Seq, M), E, D, T>> for RMSNorm<M, E, D>
+{
+    type Output = Tensor<(Batch, Seq, M), E, D, T>;
+    fn try_forward(&self, x: Tensor<(Batch, Seq, M), E, D, T>) -> Result<Self::Output, Error> {
+        let x = try_rms_normalize::<Axis<2>, _, _, _, _>(x, self.epsilon)?;
+        self.gamma.retaped::<T>().broadcast_like(&x).try_mul(x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_rms_norm_reset() {
+        let dev: TestDevice = Default::default();
+
+        let mut m = dev.build_module::<TestDtype>(<RMSNormConstConfig<5>>::default());
+        assert_close_to_literal!(m.gamma, [1.0; 5]);
+        m.gamma = dev.sample_normal();
+        m.reset_params();
+        assert_close_to_literal!(m.gamma, [1.0; 5]);
+    }
+
+    #[test]
+    fn test_rms_norm_1d_forward() {
+        let dev: TestDevice = Default::default();
+        let m = dev.build_module::<TestDtype>(<RMSNormConstConfig<4>>::default());
+        let x = dev.tensor([1.0, -2.0, 3.0, 0.5]).to_dtype::<TestDtype>();
+        let r = m.forward(x.leaky_trace());
+        assert_close_to_literal!(r, [0.52981287, -1.0596257, 1.5894386, 0.26490643]);
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&x), [0.10921582, 0.17892802, 0.06274102, 0.12083452]);
+    }
+
+    #[test]
+    fn test_rms_norm_3d_forward() {
+        let dev: TestDevice = Default::default();
+        let mut m = dev.build_module::<TestDtype>(RMSNormConfig(Const::<3>));
+        m.gamma = dev.tensor([0.5, 1.0, -2.0]).to_dtype::<TestDtype>();
+        let x = dev
+            .tensor([
+                [[0.1, -1.2, 0.3], [2.0, 0.5, -0.4]],
+                [[1.5, 1.0, -2.0], [0.0, 0.0, 0.0]],
+            ])
+            .to_dtype::<TestDtype>();
+        let r = m.forward(x.leaky_trace());
+        assert_close_to_literal!(
+            r,
+            [
+                [
+                    [0.069786248, -1.6748699, -0.83743497],
+                    [0.82478582, 0.41239291, 0.65982865]
+                ],
+                [[0.48245054, 0.64326739, 2.5730696], [0.0, 0.0, 0.0]]
+            ]
+        );
+        let g = r.mean().backward();
+        assert_close_to_literal!(g.get(&m.gamma), [0.22950377, -0.051600804, -0.09981097]);
+    }
+}
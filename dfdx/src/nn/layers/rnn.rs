@@ -0,0 +1,280 @@
+use crate::prelude::*;
+
+use super::recurrent::{self, Cell, RecurrentWeights};
+
+/// The non-linearity used by [RNN].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RNNNonlinearity {
+    #[default]
+    Tanh,
+    ReLU,
+}
+
+/// A multi-layer Elman recurrent network, where each timestep computes:
+/// ```text
+/// h' = nonlinearity(W_ih x + b_ih + W_hh h + b_hh)
+/// ```
+///
+/// Inputs are batch first `(Batch, Seq, I)`. The forward pass returns the hidden states
+/// of the last layer for every timestep `(Batch, Seq, H * num_directions)`, and the final
+/// `h_n` of every layer & direction `(num_layers * num_directions, Batch, H)`.
+/// The initial state can be passed as `(x, h_0)`, otherwise it is zero.
+///
+/// See [LSTMConfig] for notes on the output type & tapes.
+///
+/// Generics:
+/// - `I`: The number of input features.
+/// - `H`: The number of features in the hidden state.
+///
+/// **Pytorch equivalent**:
+/// ```python
+/// torch.nn.RNN(
+///     I, H, num_layers=num_layers, nonlinearity=nonlinearity,
+///     bidirectional=bidirectional, batch_first=True,
+/// )
+/// ```
+/// Parameters are stored in the same layout, and saved/loaded with the same safetensors keys
+/// (`weight_ih_l0`, `weight_hh_l0_reverse`, ...) as pytorch's `state_dict()`.
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let cfg = RNNConfig {
+///     nonlinearity: RNNNonlinearity::ReLU,
+///     ..RNNConfig::new(Const::<3>, Const::<5>, 2, false)
+/// };
+/// let rnn = dev.build_module::<f32>(cfg);
+/// let x: Tensor<Rank3<4, 7, 3>, f32, _> = dev.sample_normal();
+/// let (y, h) = rnn.forward(x);
+/// assert_eq!(y.shape(), &(Const::<4>, Const::<7>, 5));
+/// assert_eq!(h.shape(), &(2, Const::<4>, Const::<5>));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RNNConfig<I: Dim, H: Dim> {
+    pub input: I,
+    pub hidden: H,
+    pub num_layers: usize,
+    pub nonlinearity: RNNNonlinearity,
+    pub bidirectional: bool,
+}
+
+/// Compile time sugar alias around [RNNConfig].
+pub type RNNConstConfig<const I: usize, const H: usize> = RNNConfig<Const<I>, Const<H>>;
+
+impl<I: Dim, H: Dim> RNNConfig<I, H> {
+    /// Uses [RNNNonlinearity::Tanh], same as pytorch.
+    pub fn new(input: I, hidden: H, num_layers: usize, bidirectional: bool) -> Self {
+        Self {
+            input,
+            hidden,
+            num_layers,
+            nonlinearity: Default::default(),
+            bidirectional,
+        }
+    }
+}
+
+/// A single layer, unidirectional RNN with [RNNNonlinearity::Tanh].
+impl<I: ConstDim, H: ConstDim> Default for RNNConfig<I, H> {
+    fn default() -> Self {
+        Self::new(Default::default(), Default::default(), 1, false)
+    }
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> BuildOnDevice<E, D> for RNNConfig<I, H> {
+    type Built = RNN<I, H, E, D>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        Ok(RNN {
+            weights: recurrent::try_build_weights(
+                device,
+                Cell::RnnTanh,
+                self.input.size(),
+                self.hidden,
+                self.num_layers,
+                self.bidirectional,
+            )?,
+            input: self.input,
+            hidden: self.hidden,
+            nonlinearity: self.nonlinearity,
+            bidirectional: self.bidirectional,
+        })
+    }
+}
+
+/// See [RNNConfig].
+#[derive(Clone, Debug, ResetParams, UpdateParams, ZeroGrads, WithGrads)]
+pub struct RNN<I: Dim, H: Dim, Elem: Dtype, Dev: Device<Elem>> {
+    /// The weights of every layer & direction: `l0, l0_reverse, l1, l1_reverse, ...`
+    #[module]
+    pub weights: Vec<RecurrentWeights<H, Elem, Dev>>,
+    pub input: I,
+    pub hidden: H,
+    pub nonlinearity: RNNNonlinearity,
+    pub bidirectional: bool,
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> VisitParams<E, D> for RNN<I, H, E, D> {
+    fn try_visit_params<V: ParamsVisitor<E, D>>(
+        &self,
+        location: &str,
+        visitor: &mut V,
+    ) -> Result<(), Error> {
+        recurrent::try_visit_params(
+            &self.weights,
+            self.bidirectional,
+            std::any::type_name::<Self>(),
+            location,
+            visitor,
+        )
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> SaveSafeTensors for RNN<I, H, E, D> {
+    fn write_safetensors(
+        &self,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        recurrent::write_safetensors(&self.weights, self.bidirectional, location, tensors)
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> LoadSafeTensors for RNN<I, H, E, D> {
+    fn read_safetensors(
+        &mut self,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        recurrent::read_safetensors(&mut self.weights, self.bidirectional, location, tensors)
+    }
+}
+
+impl<B: Dim, S: Dim, I: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<Tensor<(B, S, I), E, D, T>> for RNN<I, H, E, D>
+{
+    type Output = (
+        Tensor<(B, S, usize), E, D, T>,
+        Tensor<(usize, B, H), E, D, T>,
+    );
+
+    fn try_forward(&self, x: Tensor<(B, S, I), E, D, T>) -> Result<Self::Output, Error> {
+        self.try_forward_with(x, None)
+    }
+}
+
+impl<B: Dim, S: Dim, I: Dim, H: Dim, E: Dtype, D: Device<E>, T: Tape<E, D>>
+    Module<(Tensor<(B, S, I), E, D, T>, Tensor<(usize, B, H), E, D>)> for RNN<I, H, E, D>
+{
+    type Output = (
+        Tensor<(B, S, usize), E, D, T>,
+        Tensor<(usize, B, H), E, D, T>,
+    );
+
+    fn try_forward(
+        &self,
+        (x, h0): (Tensor<(B, S, I), E, D, T>, Tensor<(usize, B, H), E, D>),
+    ) -> Result<Self::Output, Error> {
+        self.try_forward_with(x, Some(h0))
+    }
+}
+
+impl<I: Dim, H: Dim, E: Dtype, D: Device<E>> RNN<I, H, E, D> {
+    #[allow(clippy::type_complexity)]
+    fn try_forward_with<B: Dim, S: Dim, T: Tape<E, D>>(
+        &self,
+        x: Tensor<(B, S, I), E, D, T>,
+        h0: Option<Tensor<(usize, B, H), E, D>>,
+    ) -> Result<
+        (
+            Tensor<(B, S, usize), E, D, T>,
+            Tensor<(usize, B, H), E, D, T>,
+        ),
+        Error,
+    > {
+        let (b, s, i) = *x.shape();
+        assert_eq!(i.size(), self.input.size());
+        let x = x.try_reshape_like(&(b, s, i.size()))?;
+        let cell = match self.nonlinearity {
+            RNNNonlinearity::Tanh => Cell::RnnTanh,
+            RNNNonlinearity::ReLU => Cell::RnnReLU,
+        };
+        let (y, h, _) = recurrent::try_forward(
+            cell,
+            &self.weights,
+            self.bidirectional,
+            self.hidden,
+            x,
+            h0,
+            None,
+        )?;
+        Ok((y, h))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::excessive_precision)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_rnn_forward() {
+        let dev: TestDevice = Default::default();
+        let mut rnn = dev.build_module::<TestDtype>(RNNConstConfig::<3, 2>::default());
+        let w = &mut rnn.weights[0];
+        w.weight_ih = dev
+            .tensor([[0.1, -0.2, 0.3], [-0.4, 0.5, 0.6]])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.weight_hh = dev
+            .tensor([[0.5, -0.1], [0.2, 0.3]])
+            .to_dtype::<TestDtype>()
+            .realize();
+        w.bias_ih = dev.tensor([0.1, -0.1]).to_dtype::<TestDtype>().realize();
+        w.bias_hh = dev.tensor([0.05, 0.05]).to_dtype::<TestDtype>().realize();
+
+        let x = dev
+            .tensor([[[1.0, -1.0, 0.5], [0.2, 0.3, -0.4], [-0.6, 0.7, 0.8]]])
+            .to_dtype::<TestDtype>();
+
+        // These expected values were generated by evaluating pytorch's `torch.nn.RNN`
+        // equations with the same weights & input.
+        let (y, h) = rnn.forward(x.clone());
+        assert_close_to_literal!(
+            y.realize::<Rank3<1, 3, 2>>(),
+            [[
+                [0.53704957, -0.57166997],
+                [0.30560623, -0.27668722],
+                [0.35440435, 0.76080140],
+            ]]
+        );
+        assert_close_to_literal!(h.realize::<Rank3<1, 1, 2>>(), [[[0.35440435, 0.76080140]]]);
+
+        rnn.nonlinearity = RNNNonlinearity::ReLU;
+        let (y, _) = rnn.forward(x);
+        assert_close_to_literal!(
+            y.realize::<Rank3<1, 3, 2>>(),
+            [[[0.6, 0.0], [0.29, 0.0], [0.335, 1.078]]]
+        );
+    }
+
+    #[test]
+    fn test_rnn_backward_updates_all() {
+        let dev: TestDevice = Default::default();
+        let mut rnn = dev.build_module::<TestDtype>(RNNConfig::new(3, Const::<4>, 2, true));
+
+        let x = dev.sample_normal_like(&(2, 5, 3));
+        let (y, _) = rnn.forward(x.leaky_trace());
+        let g = y.square().mean().backward();
+        for w in rnn.weights.iter() {
+            assert!(g.get(&w.weight_ih).as_vec().iter().any(|v| *v != 0.0));
+            assert!(g.get(&w.weight_hh).as_vec().iter().any(|v| *v != 0.0));
+        }
+
+        let mut opt = crate::nn::optim::Sgd::new(&rnn, Default::default());
+        opt.update(&mut rnn, &g).expect("");
+    }
+}
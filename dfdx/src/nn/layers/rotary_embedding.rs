@@ -0,0 +1,204 @@
+use crate::prelude::*;
+
+/// Rotary position embeddings (RoPE) as described in
+/// [RoFormer: Enhanced Transformer with Rotary Position Embedding](https://arxiv.org/abs/2104.09864).
+///
+/// Feature `i` of the last axis is rotated together with feature `i + HeadDim / 2` by an angle of
+/// `(pos / scaling) * base^(-2i / HeadDim)`, where `pos` is the index along the second to last axis
+/// plus an offset. This is the "rotate half" layout used by GPT-NeoX & LLaMA.
+///
+/// `scaling > 1.0` is linear position interpolation for longer contexts, as described in
+/// [Extending Context Window of Large Language Models via Positional Interpolation](https://arxiv.org/abs/2306.15595).
+/// Defaults to `base = 10000.0` and `scaling = 1.0`.
+///
+/// Forward accepts `x`, or `(x, offset)` where `offset` is the position of the first item of the
+/// sequence (e.g. the number of tokens that were already decoded). `x` must be at least 2d,
+/// `(..., Seq, HeadDim)`, with an even `HeadDim`.
+///
+/// To apply it to queries & keys inside attention, set [MultiHeadAttentionConfig::rotary].
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let rope = RotaryEmbedding::default();
+/// let q: Tensor<Rank4<2, 4, 5, 8>, f32, _> = dev.sample_normal();
+/// let q = rope.forward(q);
+/// // the next token when decoding one token at a time
+/// let q: Tensor<(Const<2>, Const<4>, usize, Const<8>), f32, _> =
+///     dev.sample_normal_like(&(Const, Const, 1, Const));
+/// let q = rope.forward((q, 5));
+/// ```
+#[derive(Debug, Clone, Copy, CustomModule)]
+pub struct RotaryEmbedding {
+    pub base: f64,
+    pub scaling: f64,
+}
+
+impl Default for RotaryEmbedding {
+    fn default() -> Self {
+        Self {
+            base: 10000.0,
+            scaling: 1.0,
+        }
+    }
+}
+
+impl RotaryEmbedding {
+    /// The `(cos, sin)` tables of shape `(seq, dim / 2)`.
+    #[allow(clippy::type_complexity)]
+    fn try_tables<E: Dtype, D: Device<E>>(
+        &self,
+        seq: usize,
+        dim: usize,
+        offset: usize,
+        device: &D,
+    ) -> Result<(Tensor<(usize, usize), E, D>, Tensor<(usize, usize), E, D>), Error> {
+        let half = dim / 2;
+        let mut cos = Vec::with_capacity(seq * half);
+        let mut sin = Vec::with_capacity(seq * half);
+        for pos in offset..offset + seq {
+            let pos = pos as f64 / self.scaling;
+            for i in 0..half {
+                let theta = self.base.powf(-2.0 * i as f64 / dim as f64);
+                cos.push(E::from_f64((pos * theta).cos()).unwrap());
+                sin.push(E::from_f64((pos * theta).sin()).unwrap());
+            }
+        }
+        let cos = device.try_tensor_from_vec(cos, (seq, half))?;
+        let sin = device.try_tensor_from_vec(sin, (seq, half))?;
+        Ok((cos, sin))
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<(Tensor<S, E, D, T>, usize)>
+    for RotaryEmbedding
+{
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, (x, offset): (Tensor<S, E, D, T>, usize)) -> Result<Self::Output, Error> {
+        assert!(
+            S::NUM_DIMS >= 2,
+            "RotaryEmbedding input must be at least 2d"
+        );
+        let shape = *x.shape();
+        let dims = shape.concrete();
+        let seq = dims[S::NUM_DIMS - 2];
+        let dim = dims[S::NUM_DIMS - 1];
+        assert_eq!(dim % 2, 0, "RotaryEmbedding needs an even last dimension");
+        if seq == 0 || dim == 0 {
+            return Ok(x);
+        }
+        let half = dim / 2;
+
+        let flat = (shape.num_elements() / (seq * dim), seq, half);
+        let x = x.try_reshape_like(&(flat.0, seq, dim))?;
+        let (cos, sin) = self.try_tables::<E, D>(seq, dim, offset, x.device())?;
+        let cos = cos.try_broadcast_like::<_, Axis<0>>(&flat)?;
+        let sin = sin.try_broadcast_like::<_, Axis<0>>(&flat)?;
+
+        // rotates each pair (x1[i], x2[i]) of the first & second halves of the last axis
+        let x1 = x.with_empty_tape().try_slice((.., .., ..half))?;
+        let x2 = x.try_slice((.., .., half..))?;
+        let y1 = x1
+            .with_empty_tape()
+            .try_mul(cos.clone())?
+            .try_sub(x2.with_empty_tape().try_mul(sin.clone())?)?;
+        let y2 = x2.try_mul(cos)?.try_add(x1.try_mul(sin)?)?;
+        let y = (y1, y2).try_concat_tensor_along(Axis::<2>)?;
+        y.try_reshape_like(&shape)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<S, E, D, T>>
+    for RotaryEmbedding
+{
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        self.try_forward((x, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_rotary_embedding() {
+        let dev: TestDevice = Default::default();
+        let x = dev
+            .tensor([[1.0, 2.0, 3.0, 4.0], [1.0, 2.0, 3.0, 4.0]])
+            .to_dtype::<TestDtype>();
+        let rope = RotaryEmbedding {
+            base: 100.0,
+            scaling: 1.0,
+        };
+        let y = rope.forward(x.leaky_trace());
+        // position 1 rotates (x0, x2) by 1 radian, and (x1, x3) by 0.1 radians
+        assert_close_to_literal!(
+            y,
+            [
+                [1.0, 2.0, 3.0, 4.0],
+                [-1.9841106, 1.5906747, 2.4623779, 4.1796835],
+            ]
+        );
+        let g = y.sum().backward();
+        assert_close_to_literal!(
+            g.get(&x),
+            [
+                [1.0, 1.0, 1.0, 1.0],
+                [1.3817733, 1.0948376, -0.30116868, 0.89517075],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rotary_embedding_offset_and_scaling() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank3<2, 6, 8>, TestDtype, _> = dev.sample_normal();
+        let rope = RotaryEmbedding::default();
+        let y = rope.forward(x.clone());
+
+        // a slice of the sequence rotated with an offset matches the full sequence
+        let tail = x.clone().slice((.., 4.., ..)).realize::<Rank3<2, 2, 8>>();
+        let y_tail = rope.forward((tail, 4));
+        assert_close_to_tensor!(y_tail, y.slice((.., 4.., ..)).realize::<Rank3<2, 2, 8>>());
+
+        // scaling positions by 2 is the same as halving them
+        let scaled = RotaryEmbedding {
+            scaling: 2.0,
+            ..Default::default()
+        };
+        let x = x.slice((.., 2..3, ..)).realize::<Rank3<2, 1, 8>>();
+        assert_close_to_tensor!(scaled.forward((x.clone(), 2)), rope.forward((x, 1)));
+    }
+
+    #[test]
+    fn test_rotary_embedding_relative() {
+        let dev: TestDevice = Default::default();
+        let rope = RotaryEmbedding::default();
+        let q: Tensor<Rank2<1, 8>, TestDtype, _> = dev.sample_normal();
+        let k: Tensor<Rank2<1, 8>, TestDtype, _> = dev.sample_normal();
+
+        // the dot product of rotated q & k only depends on the distance between their positions
+        let dot = |q_pos: usize, k_pos: usize| {
+            let q = rope.forward((q.clone(), q_pos));
+            let k = rope.forward((k.clone(), k_pos));
+            (q * k).sum::<Rank0, _>()
+        };
+        assert_close_to_tensor!(dot(3, 1), dot(7, 5), 1e-4);
+        assert_ne!(dot(3, 1).array(), dot(1, 3).array());
+    }
+
+    #[test]
+    fn test_rotary_embedding_empty() {
+        let dev: TestDevice = Default::default();
+        let rope = RotaryEmbedding::default();
+        let x: Tensor<(Const<2>, usize, Const<8>), TestDtype, _> =
+            dev.zeros_like(&(Const, 0, Const));
+        assert_eq!(rope.forward(x).shape(), &(Const, 0, Const));
+        let x: Tensor<(Const<2>, Const<3>, usize), TestDtype, _> =
+            dev.zeros_like(&(Const, Const, 0));
+        assert_eq!(rope.forward((x, 2)).shape(), &(Const, Const, 0));
+    }
+}
@@ -0,0 +1,112 @@
+use crate::prelude::*;
+
+/// Adds the fixed sinusoidal position encodings of
+/// [Attention Is All You Need](https://arxiv.org/abs/1706.03762) to the input:
+/// `PE(pos, 2i) = sin(pos / base^(2i / Model))` and `PE(pos, 2i + 1) = cos(pos / base^(2i / Model))`.
+///
+/// `pos` is the index along the second to last axis plus an offset, and `base` defaults to `10000.0`.
+///
+/// Forward accepts `x`, or `(x, offset)` where `offset` is the position of the first item of the
+/// sequence (e.g. the number of tokens that were already decoded). `x` must be at least 2d,
+/// `(..., Seq, Model)`.
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let pe = SinusoidalPositionalEncoding::default();
+/// let x: Tensor<Rank3<2, 5, 8>, f32, _> = dev.sample_normal();
+/// let _ = pe.forward(x);
+/// let x: Tensor<(usize, Const<8>), f32, _> = dev.sample_normal_like(&(3, Const));
+/// let _ = pe.forward((x, 5));
+/// ```
+#[derive(Debug, Clone, Copy, CustomModule)]
+pub struct SinusoidalPositionalEncoding {
+    pub base: f64,
+}
+
+impl Default for SinusoidalPositionalEncoding {
+    fn default() -> Self {
+        Self { base: 10000.0 }
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<(Tensor<S, E, D, T>, usize)>
+    for SinusoidalPositionalEncoding
+{
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, (x, offset): (Tensor<S, E, D, T>, usize)) -> Result<Self::Output, Error> {
+        assert!(
+            S::NUM_DIMS >= 2,
+            "SinusoidalPositionalEncoding input must be at least 2d"
+        );
+        let shape = *x.shape();
+        let dims = shape.concrete();
+        let seq = dims[S::NUM_DIMS - 2];
+        let dim = dims[S::NUM_DIMS - 1];
+        if seq == 0 || dim == 0 {
+            return Ok(x);
+        }
+
+        let mut pe = Vec::with_capacity(seq * dim);
+        for pos in offset..offset + seq {
+            for i in 0..dim {
+                let angle = pos as f64 / self.base.powf((i - i % 2) as f64 / dim as f64);
+                let v = if i % 2 == 0 { angle.sin() } else { angle.cos() };
+                pe.push(E::from_f64(v).unwrap());
+            }
+        }
+        let pe = x.device().try_tensor_from_vec(pe, (seq, dim))?;
+
+        let flat = (shape.num_elements() / (seq * dim), seq, dim);
+        let x = x.try_reshape_like(&flat)?;
+        x.try_add(pe.try_broadcast_like::<_, Axis<0>>(&flat)?)?
+            .try_reshape_like(&shape)
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<S, E, D, T>>
+    for SinusoidalPositionalEncoding
+{
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        self.try_forward((x, 0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::*;
+
+    #[test]
+    fn test_sinusoidal_encoding() {
+        let dev: TestDevice = Default::default();
+        let x: Tensor<Rank3<2, 3, 4>, TestDtype, _> = dev.zeros();
+        let pe = SinusoidalPositionalEncoding::default();
+        let y = pe.forward(x.leaky_trace());
+        let expected = [
+            [0.0, 1.0, 0.0, 1.0],
+            [0.84147098, 0.54030231, 0.00999983, 0.99995000],
+            [0.90929743, -0.41614684, 0.01999867, 0.99980001],
+        ];
+        assert_close_to_literal!(y, [expected; 2]);
+        let g = y.sum().backward();
+        assert_close_to_literal!(g.get(&x), [[[1.0; 4]; 3]; 2]);
+
+        // the last position of the sequence is the same as a single item with an offset
+        let x: Tensor<(usize, Const<4>), TestDtype, _> = dev.zeros_like(&(1, Const));
+        let y = pe.forward((x, 2)).realize::<Rank2<1, 4>>();
+        assert_close_to_literal!(y, [expected[2]]);
+    }
+
+    #[test]
+    fn test_sinusoidal_encoding_empty() {
+        let dev: TestDevice = Default::default();
+        let pe = SinusoidalPositionalEncoding::default();
+        let x: Tensor<(usize, Const<4>), TestDtype, _> = dev.zeros_like(&(0, Const));
+        assert_eq!(pe.forward(x).shape(), &(0, Const));
+        let x: Tensor<(Const<3>, usize), TestDtype, _> = dev.zeros_like(&(Const, 0));
+        assert_eq!(pe.forward(x).shape(), &(Const, 0));
+    }
+}
@@ -0,0 +1,25 @@
+use crate::prelude::*;
+
+/// Calls [crate::tensor_ops::softplus()]. Defaults to the same `beta = 1.0` and
+/// `threshold = 20.0` as pytorch.
+#[derive(Debug, Clone, Copy, CustomModule)]
+pub struct Softplus {
+    pub beta: f64,
+    pub threshold: f64,
+}
+
+impl Default for Softplus {
+    fn default() -> Self {
+        Self {
+            beta: 1.0,
+            threshold: 20.0,
+        }
+    }
+}
+
+impl<S: Shape, E: Dtype, D: Device<E>, T: Tape<E, D>> Module<Tensor<S, E, D, T>> for Softplus {
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        x.try_softplus(self.beta, self.threshold)
+    }
+}
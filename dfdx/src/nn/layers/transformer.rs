@@ -1,13 +1,244 @@
 use crate::prelude::*;
 
-#[derive(Clone, Debug, Sequential)]
-#[built(FeedForward)]
-pub struct FeedForwardConfig<Model: Dim, F: Dim> {
+use std::fmt::Debug;
+
+/// The feedforward network of a transformer block: `Linear -> Act -> Linear`.
+///
+/// Generics
+/// - `Model`: The input & output size.
+/// - `F`: The size of the hidden layer.
+/// - `Act`: The activation applied to the hidden layer, e.g. [ReLU] (the default) or [AccurateGeLU].
+#[derive(Clone, Debug)]
+pub struct FeedForwardConfig<Model: Dim, F: Dim, Act: Clone + Debug = ReLU> {
     pub l1: LinearConfig<Model, F>,
-    pub act1: ReLU,
+    pub act1: Act,
     pub l2: LinearConfig<F, Model>,
 }
 
+impl<Model: Dim, F: Dim, Act: Default + Clone + Debug> FeedForwardConfig<Model, F, Act> {
+    pub fn new(model: Model, f: F) -> Self {
+        FeedForwardConfig {
+            l1: LinearConfig::new(model, f),
+            act1: Default::default(),
+            l2: LinearConfig::new(f, model),
+        }
+    }
+}
+
+impl<Model: Dim, F: Dim, Act: BuildOnDevice<E, D> + Debug, E: Dtype, D: Device<E>>
+    BuildOnDevice<E, D> for FeedForwardConfig<Model, F, Act>
+{
+    type Built = FeedForward<Model, F, E, D, Act>;
+    fn try_build_on_device(&self, device: &D) -> Result<Self::Built, crate::tensor::Error> {
+        Ok(FeedForward {
+            l1: self.l1.try_build_on_device(device)?,
+            act1: self.act1.try_build_on_device(device)?,
+            l2: self.l2.try_build_on_device(device)?,
+        })
+    }
+}
+
+/// See [FeedForwardConfig].
+///
+/// `Act` comes after the dtype & device so that it can default to [ReLU].
+#[derive(Clone, Debug, ResetParams, ZeroGrads, UpdateParams, WithGrads, VisitParams)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct FeedForward<Model: Dim, F: Dim, Elem: Dtype, Dev: Device<Elem>, Act = ReLU>
+where
+    Act: BuildOnDevice<Elem, Dev>,
+{
+    #[module]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub l1: Linear<Model, F, Elem, Dev>,
+    #[module]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub act1: Act::Built,
+    #[module]
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub l2: Linear<F, Model, Elem, Dev>,
+}
+
+impl<Model: Dim, F: Dim, E: Dtype, D: Device<E>, Act, X, H> Module<X>
+    for FeedForward<Model, F, E, D, Act>
+where
+    Act: BuildOnDevice<E, D>,
+    Act::Built: Module<H, Output = H>,
+    Linear<Model, F, E, D>: Module<X, Output = H>,
+    Linear<F, Model, E, D>: Module<H>,
+{
+    type Output = <Linear<F, Model, E, D> as Module<H>>::Output;
+    fn try_forward(&self, x: X) -> Result<Self::Output, Error> {
+        let x = self.l1.try_forward(x)?;
+        let x = self.act1.try_forward(x)?;
+        self.l2.try_forward(x)
+    }
+    fn try_forward_mut(&mut self, x: X) -> Result<Self::Output, Error> {
+        let x = self.l1.try_forward_mut(x)?;
+        let x = self.act1.try_forward_mut(x)?;
+        self.l2.try_forward_mut(x)
+    }
+}
+
+/// A gated feedforward network as described in
+/// [GLU Variants Improve Transformer](https://arxiv.org/abs/2002.05202):
+/// `down(act(gate(x)) * up(x))`.
+///
+/// None of the projections have a bias, the same as LLaMA.
+///
+/// Generics
+/// - `Model`: The input & output size.
+/// - `F`: The size of the hidden layer.
+/// - `Act`: The activation applied to the gate. See [SwiGLUConfig] and [GeGLUConfig].
+///
+/// # Examples
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let ff = dev.build_module::<f32>(SwiGLUConfig::new(Const::<8>, Const::<16>));
+/// let _: Tensor<Rank2<3, 8>, f32, _> = ff.forward(dev.zeros::<Rank2<3, 8>>());
+/// ```
+#[derive(Clone, Debug, CustomModule)]
+#[built(GatedFeedForward)]
+pub struct GatedFeedForwardConfig<Model: Dim, F: Dim, Act: Clone + Debug> {
+    #[module]
+    pub gate: MatMulConfig<Model, F>,
+    #[module]
+    pub act: Act,
+    #[module]
+    pub up: MatMulConfig<Model, F>,
+    #[module]
+    pub down: MatMulConfig<F, Model>,
+}
+
+/// A [GatedFeedForwardConfig] gated by [SiLU].
+pub type SwiGLUConfig<Model, F> = GatedFeedForwardConfig<Model, F, SiLU>;
+
+/// A [GatedFeedForwardConfig] gated by [AccurateGeLU].
+pub type GeGLUConfig<Model, F> = GatedFeedForwardConfig<Model, F, AccurateGeLU>;
+
+impl<Model: Dim, F: Dim, Act: Default + Clone + Debug> GatedFeedForwardConfig<Model, F, Act> {
+    pub fn new(model: Model, f: F) -> Self {
+        GatedFeedForwardConfig {
+            gate: MatMulConfig { inp: model, out: f },
+            act: Default::default(),
+            up: MatMulConfig { inp: model, out: f },
+            down: MatMulConfig { inp: f, out: model },
+        }
+    }
+}
+
+impl<Model: Dim, F: Dim, Act, E: Dtype, D: Device<E>, X, H> Module<X>
+    for GatedFeedForward<Model, F, Act, E, D>
+where
+    Act: BuildOnDevice<E, D> + Debug,
+    Act::Built: Module<H, Output = H>,
+    X: WithEmptyTape,
+    H: TryMul<H, Output = H>,
+    MatMul<Model, F, E, D>: Module<X, Output = H>,
+    MatMul<F, Model, E, D>: Module<H>,
+{
+    type Output = <MatMul<F, Model, E, D> as Module<H>>::Output;
+    fn try_forward(&self, x: X) -> Result<Self::Output, Error> {
+        let gate = self.gate.try_forward(x.with_empty_tape())?;
+        let gate = self.act.try_forward(gate)?;
+        let up = self.up.try_forward(x)?;
+        self.down.try_forward(gate.try_mul(up)?)
+    }
+}
+
+/// One residual sub-layer of a transformer block.
+///
+/// Post-norm is `norm(x + dropout(f(x)))`, and pre-norm is `x + dropout(f(norm(x)))`.
+/// Dropout is only applied when `train` is set.
+fn try_sublayer<X, N>(
+    x: X,
+    norm: &N,
+    norm_first: bool,
+    dropout: f64,
+    train: bool,
+    f: impl FnOnce(X) -> Result<X, Error>,
+) -> Result<X, Error>
+where
+    X: SplitTape + TryAdd<X::NoTape, Output = X>,
+    N: Module<X, Output = X>,
+    Dropout: Module<X, Output = X>,
+{
+    let (x, tape) = x.split_tape();
+    let residual = x.clone();
+    let x = x.put_tape(tape);
+    let x = if norm_first { norm.try_forward(x)? } else { x };
+    let mut x = f(x)?;
+    if train && dropout > 0.0 {
+        x = Dropout { p: dropout }.try_forward_mut(x)?;
+    }
+    let x = x.try_add(residual)?;
+    if norm_first {
+        Ok(x)
+    } else {
+        norm.try_forward(x)
+    }
+}
+
+/// A transformer encoder block, generic over the feedforward network & norm layer.
+///
+/// Forward accepts `x`, or `(x, mask)` where `mask` is any [AttentionMask] applied to the
+/// self attention (e.g. [CausalMask] for GPT style blocks).
+///
+/// Generics
+/// - `Model`: The size of query/key/value tensors. Given to [MultiHeadAttention].
+/// - `NumHeads`: The number of heads in [MultiHeadAttention].
+/// - `Ff`: The feedforward network, e.g. [FeedForwardConfig] or [SwiGLUConfig].
+/// - `Norm`: The norm layer, e.g. [LayerNorm1DConfig] or [RMSNormConfig].
+///
+/// Fields
+/// - `norm_first`: Whether to normalize the input of each sub-layer (pre-norm, as in GPT-2 & LLaMA),
+///   instead of its output (post-norm, as in BERT & the original transformer).
+/// - `attn_dropout`/`ff_dropout`: Dropout probability applied to the output of the attention/feedforward
+///   sub-layer before it is added to the residual. Only applied in [Module::forward_mut()].
+///
+/// See [EncoderBlockConfig] for the post-norm block from the original transformer.
+///
+/// # Examples
+/// A LLaMA style block:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// let (model, num_heads, f) = (Const::<8>, Const::<2>, Const::<16>);
+/// let block = dev.build_module::<f32>(TransformerEncoderBlockConfig {
+///     self_attn: ResidualAdd(MultiHeadAttentionConfig::new(model, num_heads, model, model)),
+///     norm1: RMSNormConfig(model),
+///     ff: ResidualAdd(SwiGLUConfig::new(model, f)),
+///     norm2: RMSNormConfig(model),
+///     norm_first: true,
+///     attn_dropout: 0.0,
+///     ff_dropout: 0.0,
+/// });
+/// let x: Tensor<Rank3<2, 5, 8>, f32, _> = dev.sample_normal();
+/// let _ = block.forward((x, CausalMask));
+/// ```
+#[derive(Clone, Debug, CustomModule)]
+#[built(TransformerEncoderBlock)]
+pub struct TransformerEncoderBlockConfig<
+    Model: Dim,
+    NumHeads: Dim,
+    Ff: Clone + Debug,
+    Norm: Clone + Debug,
+> {
+    // the residual connections are applied by the block itself, the [ResidualAdd]s
+    // keep parameter names the same as before the block was configurable.
+    #[module]
+    pub self_attn: ResidualAdd<MultiHeadAttentionConfig<Model, NumHeads>>,
+    #[module]
+    pub norm1: Norm,
+    #[module]
+    pub ff: ResidualAdd<Ff>,
+    #[module]
+    pub norm2: Norm,
+    pub norm_first: bool,
+    pub attn_dropout: f64,
+    pub ff_dropout: f64,
+}
+
 /// A single transformer encoder block
 ///
 /// Generics
@@ -21,14 +252,22 @@ pub struct FeedForwardConfig<Model: Dim, F: Dim> {
 ///    Model, NumHeads, dim_feedforward=F, batch_first=True, dropout=0.0
 /// )
 /// ```
-#[derive(Clone, Debug, Sequential)]
-#[built(EncoderBlock)]
-pub struct EncoderBlockConfig<Model: Dim, NumHeads: Dim, F: Dim> {
-    pub self_attn: ResidualAdd<MultiHeadAttentionConfig<Model, NumHeads>>,
-    pub norm1: LayerNorm1DConfig<Model>,
-    pub ff: ResidualAdd<FeedForwardConfig<Model, F>>,
-    pub norm2: LayerNorm1DConfig<Model>,
-}
+pub type EncoderBlockConfig<Model, NumHeads, F> = TransformerEncoderBlockConfig<
+    Model,
+    NumHeads,
+    FeedForwardConfig<Model, F, ReLU>,
+    LayerNorm1DConfig<Model>,
+>;
+
+/// See [EncoderBlockConfig]
+pub type EncoderBlock<Model, NumHeads, F, E, D> = TransformerEncoderBlock<
+    Model,
+    NumHeads,
+    FeedForwardConfig<Model, F, ReLU>,
+    LayerNorm1DConfig<Model>,
+    E,
+    D,
+>;
 
 impl<Model: Dim, NumHeads: Dim, F: Dim> EncoderBlockConfig<Model, NumHeads, F> {
     pub fn new(model: Model, num_heads: NumHeads, f: F) -> Self {
@@ -37,19 +276,149 @@ impl<Model: Dim, NumHeads: Dim, F: Dim> EncoderBlockConfig<Model, NumHeads, F> {
                 model, num_heads, model, model,
             )),
             norm1: LayerNorm1DConfig(model),
-            ff: ResidualAdd(FeedForwardConfig {
-                l1: LinearConfig::new(model, f),
-                act1: ReLU,
-                l2: LinearConfig::new(f, model),
-            }),
+            ff: ResidualAdd(FeedForwardConfig::new(model, f)),
             norm2: LayerNorm1DConfig(model),
+            norm_first: false,
+            attn_dropout: 0.0,
+            ff_dropout: 0.0,
         }
     }
 }
 
+impl<M: Dim, H: Dim, Ff, Norm, E: Dtype, D: Device<E>> TransformerEncoderBlock<M, H, Ff, Norm, E, D>
+where
+    Ff: BuildOnDevice<E, D> + Debug,
+    Norm: BuildOnDevice<E, D> + Debug,
+{
+    fn try_encode<X>(
+        &self,
+        x: X,
+        train: bool,
+        self_attn: impl FnOnce(&MultiHeadAttention<M, H, M, M, E, D>, X) -> Result<X, Error>,
+    ) -> Result<X, Error>
+    where
+        X: SplitTape + TryAdd<X::NoTape, Output = X>,
+        Ff::Built: Module<X, Output = X>,
+        Norm::Built: Module<X, Output = X>,
+        Dropout: Module<X, Output = X>,
+    {
+        let x = try_sublayer(
+            x,
+            &self.norm1,
+            self.norm_first,
+            self.attn_dropout,
+            train,
+            |x| self_attn(&self.self_attn.0, x),
+        )?;
+        try_sublayer(
+            x,
+            &self.norm2,
+            self.norm_first,
+            self.ff_dropout,
+            train,
+            |x| self.ff.0.try_forward(x),
+        )
+    }
+}
+
+impl<M: Dim, H: Dim, Ff, Norm, E: Dtype, D: Device<E>, S: Shape, T: Tape<E, D>>
+    Module<Tensor<S, E, D, T>> for TransformerEncoderBlock<M, H, Ff, Norm, E, D>
+where
+    Ff: BuildOnDevice<E, D> + Debug,
+    Norm: BuildOnDevice<E, D> + Debug,
+    Ff::Built: Module<Tensor<S, E, D, T>, Output = Tensor<S, E, D, T>>,
+    Norm::Built: Module<Tensor<S, E, D, T>, Output = Tensor<S, E, D, T>>,
+    MultiHeadAttention<M, H, M, M, E, D>: Module<Tensor<S, E, D, T>, Output = Tensor<S, E, D, T>>,
+{
+    type Output = Tensor<S, E, D, T>;
+    fn try_forward(&self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        self.try_encode(x, false, |attn, x| attn.try_forward(x))
+    }
+    fn try_forward_mut(&mut self, x: Tensor<S, E, D, T>) -> Result<Self::Output, Error> {
+        self.try_encode(x, true, |attn, x| attn.try_forward(x))
+    }
+}
+
+impl<M: Dim, H: Dim, Ff, Norm, E: Dtype, D: Device<E>, S: Shape, T: Tape<E, D>, Mask>
+    Module<(Tensor<S, E, D, T>, Mask)> for TransformerEncoderBlock<M, H, Ff, Norm, E, D>
+where
+    Ff: BuildOnDevice<E, D> + Debug,
+    Norm: BuildOnDevice<E, D> + Debug,
+    Ff::Built: Module<Tensor<S, E, D, T>, Output = Tensor<S, E, D, T>>,
+    Norm::Built: Module<Tensor<S, E, D, T>, Output = Tensor<S, E, D, T>>,
+    MultiHeadAttention<M, H, M, M, E, D>: Module<
+        (Tensor<S, E, D, T>, Tensor<S, E, D>, Tensor<S, E, D>, Mask),
+        Output = Tensor<S, E, D, T>,
+    >,
+{
+    type Output = Tensor<S, E, D, T>;
+
+    /// Same as the `x` forward, but `mask` is applied to the self attention.
+    fn try_forward(&self, (x, mask): (Tensor<S, E, D, T>, Mask)) -> Result<Self::Output, Error> {
+        self.try_encode(x, false, |attn, x| try_masked_self_attend(attn, x, mask))
+    }
+    fn try_forward_mut(
+        &mut self,
+        (x, mask): (Tensor<S, E, D, T>, Mask),
+    ) -> Result<Self::Output, Error> {
+        self.try_encode(x, true, |attn, x| try_masked_self_attend(attn, x, mask))
+    }
+}
+
+fn try_masked_self_attend<M: Dim, H: Dim, E: Dtype, D: Device<E>, X: SplitTape, Mask>(
+    attn: &MultiHeadAttention<M, H, M, M, E, D>,
+    x: X,
+    mask: Mask,
+) -> Result<X, Error>
+where
+    MultiHeadAttention<M, H, M, M, E, D>: Module<(X, X::NoTape, X::NoTape, Mask), Output = X>,
+{
+    let (x, tape) = x.split_tape();
+    attn.try_forward((x.clone().put_tape(tape), x.clone(), x, mask))
+}
+
+/// A transformer decoder block, generic over the feedforward network & norm layer.
+/// Different than [TransformerEncoderBlockConfig] as there is an additional attention
+/// over a sequence from the encoder between the self attention and the feedforward network.
+///
+/// Forward accepts `(tgt, mem)`, or `(tgt, mem, tgt_mask)` where `tgt_mask` is any
+/// [AttentionMask] applied to the self attention (e.g. [CausalMask]).
+///
+/// The generics and `norm_first`/`attn_dropout`/`ff_dropout` are the same as
+/// [TransformerEncoderBlockConfig]. `attn_dropout` applies to both attention sub-layers.
+///
+/// See [DecoderBlockConfig] for the post-norm block from the original transformer.
+#[derive(Clone, Debug, CustomModule)]
+#[built(TransformerDecoderBlock)]
+pub struct TransformerDecoderBlockConfig<
+    Model: Dim,
+    NumHeads: Dim,
+    Ff: Clone + Debug,
+    Norm: Clone + Debug,
+> {
+    #[module]
+    pub self_attn: ResidualAdd<MultiHeadAttentionConfig<Model, NumHeads>>,
+    #[module]
+    pub norm1: Norm,
+    #[module]
+    pub mh_attn: MultiHeadAttentionConfig<Model, NumHeads>,
+    #[module]
+    pub norm2: Norm,
+    #[module]
+    pub ff: ResidualAdd<Ff>,
+    #[module]
+    pub norm3: Norm,
+    pub norm_first: bool,
+    pub attn_dropout: f64,
+    pub ff_dropout: f64,
+}
+
 /// A transformer decoder block. Different than the normal transformer block
 /// as this self attention accepts an additional sequence from the encoder.
 ///
+/// Forward accepts `(tgt, mem)`, or `(tgt, mem, tgt_mask)` where `tgt_mask` is any
+/// [AttentionMask] applied to the self attention (e.g. [CausalMask]).
+///
 /// Generics
 /// - `Model`: The size of query/key/value tensors. Given to [MultiHeadAttention].
 /// - `NumHeads`: The number of heads in [MultiHeadAttention].
@@ -61,22 +430,22 @@ impl<Model: Dim, NumHeads: Dim, F: Dim> EncoderBlockConfig<Model, NumHeads, F> {
 ///    Model, NumHeads, dim_feedforward=F, batch_first=True, dropout=0.0
 /// )
 /// ```
-#[derive(Clone, Debug, CustomModule)]
-#[built(DecoderBlock)]
-pub struct DecoderBlockConfig<Model: Dim, NumHeads: Dim, F: Dim> {
-    #[module]
-    pub self_attn: ResidualAdd<MultiHeadAttentionConfig<Model, NumHeads>>,
-    #[module]
-    pub norm1: LayerNorm1DConfig<Model>,
-    #[module]
-    pub mh_attn: MultiHeadAttentionConfig<Model, NumHeads>,
-    #[module]
-    pub norm2: LayerNorm1DConfig<Model>,
-    #[module]
-    pub ff: ResidualAdd<FeedForwardConfig<Model, F>>,
-    #[module]
-    pub norm3: LayerNorm1DConfig<Model>,
-}
+pub type DecoderBlockConfig<Model, NumHeads, F> = TransformerDecoderBlockConfig<
+    Model,
+    NumHeads,
+    FeedForwardConfig<Model, F, ReLU>,
+    LayerNorm1DConfig<Model>,
+>;
+
+/// See [DecoderBlockConfig]
+pub type DecoderBlock<Model, NumHeads, F, E, D> = TransformerDecoderBlock<
+    Model,
+    NumHeads,
+    FeedForwardConfig<Model, F, ReLU>,
+    LayerNorm1DConfig<Model>,
+    E,
+    D,
+>;
 
 impl<Model: Dim, NumHeads: Dim, F: Dim> DecoderBlockConfig<Model, NumHeads, F> {
     pub fn new(model: Model, num_heads: NumHeads, f: F) -> Self {
@@ -87,40 +456,117 @@ impl<Model: Dim, NumHeads: Dim, F: Dim> DecoderBlockConfig<Model, NumHeads, F> {
             norm1: LayerNorm1DConfig(model),
             mh_attn: MultiHeadAttentionConfig::new(model, num_heads, model, model),
             norm2: LayerNorm1DConfig(model),
-            ff: ResidualAdd(FeedForwardConfig {
-                l1: LinearConfig::new(model, f),
-                act1: ReLU,
-                l2: LinearConfig::new(f, model),
-            }),
+            ff: ResidualAdd(FeedForwardConfig::new(model, f)),
             norm3: LayerNorm1DConfig(model),
+            norm_first: false,
+            attn_dropout: 0.0,
+            ff_dropout: 0.0,
         }
     }
 }
 
-impl<M: Dim, H: Dim, F: Dim, E: Dtype, D: Device<E>, Tgt, Mem> Module<(Tgt, Mem)>
-    for DecoderBlock<M, H, F, E, D>
+impl<M: Dim, H: Dim, Ff, Norm, E: Dtype, D: Device<E>> TransformerDecoderBlock<M, H, Ff, Norm, E, D>
 where
-    Tgt: WithEmptyTape + SplitTape + TryAdd<Tgt::NoTape, Output = Tgt>,
+    Ff: BuildOnDevice<E, D> + Debug,
+    Norm: BuildOnDevice<E, D> + Debug,
+{
+    fn try_decode<Tgt, Mem>(
+        &self,
+        tgt: Tgt,
+        mem: Mem,
+        train: bool,
+        self_attn: impl FnOnce(&MultiHeadAttention<M, H, M, M, E, D>, Tgt) -> Result<Tgt, Error>,
+    ) -> Result<Tgt, Error>
+    where
+        Tgt: SplitTape + TryAdd<Tgt::NoTape, Output = Tgt>,
+        Mem: Clone,
+        MultiHeadAttention<M, H, M, M, E, D>: Module<(Tgt, Mem, Mem), Output = Tgt>,
+        Ff::Built: Module<Tgt, Output = Tgt>,
+        Norm::Built: Module<Tgt, Output = Tgt>,
+        Dropout: Module<Tgt, Output = Tgt>,
+    {
+        let x = try_sublayer(
+            tgt,
+            &self.norm1,
+            self.norm_first,
+            self.attn_dropout,
+            train,
+            |x| self_attn(&self.self_attn.0, x),
+        )?;
+        let x = try_sublayer(
+            x,
+            &self.norm2,
+            self.norm_first,
+            self.attn_dropout,
+            train,
+            |x| self.mh_attn.try_forward((x, mem.clone(), mem)),
+        )?;
+        try_sublayer(
+            x,
+            &self.norm3,
+            self.norm_first,
+            self.ff_dropout,
+            train,
+            |x| self.ff.0.try_forward(x),
+        )
+    }
+}
+
+impl<M: Dim, H: Dim, Ff, Norm, E: Dtype, D: Device<E>, Tgt, Mem> Module<(Tgt, Mem)>
+    for TransformerDecoderBlock<M, H, Ff, Norm, E, D>
+where
+    Ff: BuildOnDevice<E, D> + Debug,
+    Norm: BuildOnDevice<E, D> + Debug,
+    Tgt: SplitTape + TryAdd<Tgt::NoTape, Output = Tgt>,
     Mem: Clone,
-    ResidualAdd<MultiHeadAttention<M, H, M, M, E, D>>: Module<Tgt, Output = Tgt>,
+    MultiHeadAttention<M, H, M, M, E, D>: Module<Tgt, Output = Tgt>,
     MultiHeadAttention<M, H, M, M, E, D>: Module<(Tgt, Mem, Mem), Output = Tgt>,
-    LayerNorm1D<M, E, D>: Module<Tgt, Output = Tgt>,
-    ResidualAdd<FeedForward<M, F, E, D>>: Module<Tgt, Output = Tgt>,
+    Ff::Built: Module<Tgt, Output = Tgt>,
+    Norm::Built: Module<Tgt, Output = Tgt>,
+    Dropout: Module<Tgt, Output = Tgt>,
 {
     type Output = Tgt;
     fn try_forward(&self, (tgt, mem): (Tgt, Mem)) -> Result<Self::Output, crate::tensor::Error> {
-        let x = self.self_attn.try_forward(tgt)?;
-        let x = self.norm1.try_forward(x)?;
-
-        let (x, tape) = x.split_tape();
-        let x_residual = x.clone();
-        let x = self
-            .mh_attn
-            .try_forward((x.put_tape(tape), mem.clone(), mem))?;
-        let x = x.try_add(x_residual)?;
-        let x = self.norm2.try_forward(x)?;
-        let x = self.ff.try_forward(x)?;
-        self.norm3.try_forward(x)
+        self.try_decode(tgt, mem, false, |attn, x| attn.try_forward(x))
+    }
+    fn try_forward_mut(&mut self, (tgt, mem): (Tgt, Mem)) -> Result<Self::Output, Error> {
+        self.try_decode(tgt, mem, true, |attn, x| attn.try_forward(x))
+    }
+}
+
+impl<M: Dim, H: Dim, Ff, Norm, E: Dtype, D: Device<E>, Tgt, Mem, Mask> Module<(Tgt, Mem, Mask)>
+    for TransformerDecoderBlock<M, H, Ff, Norm, E, D>
+where
+    Ff: BuildOnDevice<E, D> + Debug,
+    Norm: BuildOnDevice<E, D> + Debug,
+    Tgt: SplitTape + TryAdd<Tgt::NoTape, Output = Tgt>,
+    Mem: Clone,
+    MultiHeadAttention<M, H, M, M, E, D>:
+        Module<(Tgt, Tgt::NoTape, Tgt::NoTape, Mask), Output = Tgt>,
+    MultiHeadAttention<M, H, M, M, E, D>: Module<(Tgt, Mem, Mem), Output = Tgt>,
+    Ff::Built: Module<Tgt, Output = Tgt>,
+    Norm::Built: Module<Tgt, Output = Tgt>,
+    Dropout: Module<Tgt, Output = Tgt>,
+{
+    type Output = Tgt;
+
+    /// Same as the `(tgt, mem)` forward, but `tgt_mask` is applied to the self attention.
+    /// Pass [CausalMask] to make the self attention causal.
+    fn try_forward(
+        &self,
+        (tgt, mem, tgt_mask): (Tgt, Mem, Mask),
+    ) -> Result<Self::Output, crate::tensor::Error> {
+        self.try_decode(tgt, mem, false, |attn, x| {
+            try_masked_self_attend(attn, x, tgt_mask)
+        })
+    }
+    fn try_forward_mut(
+        &mut self,
+        (tgt, mem, tgt_mask): (Tgt, Mem, Mask),
+    ) -> Result<Self::Output, Error> {
+        self.try_decode(tgt, mem, true, |attn, x| {
+            try_masked_self_attend(attn, x, tgt_mask)
+        })
     }
 }
 
@@ -134,6 +580,10 @@ where
 /// - `NumHeads`: Number of heads for [MultiHeadAttention].
 /// - `F`: Feedforward hidden dimension for both encoder/decoder
 ///
+/// Forward accepts `(src, tgt)`, or `(src, tgt, tgt_mask)` where `tgt_mask` is any
+/// [AttentionMask] applied to the decoder self attention. Use [CausalMask] so the decoder
+/// can't attend to future tokens (pytorch's `tgt_mask=generate_square_subsequent_mask(..)`).
+///
 /// **Pytorch equivalent**:
 /// ```python
 /// torch.nn.Transformer(
@@ -192,6 +642,60 @@ where
         }
         Ok(tgt)
     }
+    fn try_forward_mut(&mut self, (src, tgt): (Src, Tgt)) -> Result<Self::Output, Error> {
+        let (mem, tape) = self.encoder.try_forward_mut(src)?.split_tape();
+        let mut tgt = tgt.put_tape(tape);
+        for block in self.decoder.iter_mut() {
+            tgt = block.try_forward_mut((tgt, mem.clone()))?;
+        }
+        Ok(tgt)
+    }
+}
+
+impl<
+        M: Dim,
+        H: Dim,
+        F: Dim,
+        E: Dtype,
+        D: Device<E>,
+        Src: SplitTape,
+        Tgt: PutTape<Src::Tape>,
+        Mask: Clone,
+    > Module<(Src, Tgt, Mask)> for Transformer<M, H, F, E, D>
+where
+    Vec<EncoderBlock<M, H, F, E, D>>: Module<Src, Output = Src>,
+    DecoderBlock<M, H, F, E, D>: Module<
+        (<Tgt as PutTape<Src::Tape>>::Output, Src::NoTape, Mask),
+        Output = <Tgt as PutTape<Src::Tape>>::Output,
+    >,
+{
+    type Output = <Tgt as PutTape<Src::Tape>>::Output;
+
+    /// Same as the `(src, tgt)` forward, but `tgt_mask` is applied to the self attention
+    /// of every decoder block. Pass [CausalMask] for autoregressive decoding.
+    fn try_forward(
+        &self,
+        (src, tgt, tgt_mask): (Src, Tgt, Mask),
+    ) -> Result<Self::Output, crate::tensor::Error> {
+        let (mem, tape) = self.encoder.try_forward(src)?.split_tape();
+        let mut tgt = tgt.put_tape(tape);
+        for block in self.decoder.iter() {
+            tgt = block.try_forward((tgt, mem.clone(), tgt_mask.clone()))?;
+        }
+        Ok(tgt)
+    }
+
+    fn try_forward_mut(
+        &mut self,
+        (src, tgt, tgt_mask): (Src, Tgt, Mask),
+    ) -> Result<Self::Output, Error> {
+        let (mem, tape) = self.encoder.try_forward_mut(src)?.split_tape();
+        let mut tgt = tgt.put_tape(tape);
+        for block in self.decoder.iter_mut() {
+            tgt = block.try_forward_mut((tgt, mem.clone(), tgt_mask.clone()))?;
+        }
+        Ok(tgt)
+    }
 }
 
 #[cfg(test)]
@@ -242,6 +746,164 @@ mod tests {
         opt.update(&mut t, &g).expect("");
     }
 
+    #[test]
+    fn test_transformer_causal_forward() {
+        let dev = TestDevice::seed_from_u64(1);
+        let t = dev.build_module::<TestDtype>(TransformerConfig::new(
+            Const::<16>,
+            Const::<4>,
+            Const::<8>,
+            2,
+            2,
+        ));
+
+        let src = dev.sample_normal::<Rank3<2, 7, 16>>();
+        let tgt = dev.sample_normal::<Rank3<2, 5, 16>>();
+        let y: Tensor<Rank3<2, 5, 16>, _, _, _> = t.forward((src.clone(), tgt.clone(), CausalMask));
+
+        // changing the last target token must not change any of the earlier outputs
+        let mut data = tgt.as_vec();
+        for (i, v) in data.iter_mut().enumerate() {
+            if (i / 16) % 5 == 4 {
+                *v += TestDtype::ONE;
+            }
+        }
+        let tgt2: Tensor<Rank3<2, 5, 16>, TestDtype, _> = dev.tensor(data);
+        let y2 = t.forward((src.clone(), tgt2, CausalMask));
+        assert_close_to_tensor!(
+            y.clone().slice((.., ..4, ..)).realize::<Rank3<2, 4, 16>>(),
+            y2.slice((.., ..4, ..)).realize::<Rank3<2, 4, 16>>()
+        );
+
+        // without the mask the earlier outputs see the future
+        let y3 = t.forward((src, tgt));
+        assert_ne!(y.as_vec(), y3.as_vec());
+    }
+
+    #[test]
+    fn test_feed_forward_default_act() {
+        let dev = TestDevice::seed_from_u64(3);
+        let cfg: FeedForwardConfig<Const<4>, Const<6>> = FeedForwardConfig::new(Const, Const);
+        let ff: FeedForward<Const<4>, Const<6>, TestDtype, TestDevice> = dev.build_module(cfg);
+        let x: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+        let y = ff.forward(x.clone());
+
+        let hidden = x.matmul(ff.l1.weight.clone().permute()) + ff.l1.bias.clone().broadcast();
+        let expected =
+            hidden.relu().matmul(ff.l2.weight.clone().permute()) + ff.l2.bias.clone().broadcast();
+        assert_close_to_tensor!(y, expected);
+    }
+
+    #[test]
+    fn test_gated_feed_forward() {
+        let dev = TestDevice::seed_from_u64(3);
+        let ff = dev.build_module::<TestDtype>(SwiGLUConfig::new(Const::<4>, Const::<6>));
+        let x: Tensor<Rank2<3, 4>, TestDtype, _> = dev.sample_normal();
+        let y = ff.forward(x.clone());
+
+        let gate = x.clone().matmul(ff.gate.weight.clone().permute()).silu();
+        let up = x.matmul(ff.up.weight.clone().permute());
+        let expected = (gate * up).matmul(ff.down.weight.clone().permute());
+        assert_close_to_tensor!(y, expected);
+
+        let ff = dev.build_module::<TestDtype>(GeGLUConfig::new(Const::<4>, Const::<6>));
+        let x: Tensor<Rank3<2, 3, 4>, TestDtype, _> = dev.sample_normal();
+        let g = ff.forward(x.leaky_trace()).square().mean().backward();
+        assert_ne!(g.get(&ff.gate.weight).array(), [[TestDtype::zero(); 4]; 6]);
+        assert_ne!(g.get(&ff.up.weight).array(), [[TestDtype::zero(); 4]; 6]);
+    }
+
+    #[test]
+    fn test_pre_norm_encoder_block() {
+        let dev = TestDevice::seed_from_u64(4);
+        let (m, h, f) = (Const::<8>, Const::<2>, Const::<12>);
+        let mut block = dev.build_module::<TestDtype>(TransformerEncoderBlockConfig {
+            self_attn: ResidualAdd(MultiHeadAttentionConfig::new(m, h, m, m)),
+            norm1: RMSNormConfig(m),
+            ff: ResidualAdd(SwiGLUConfig::new(m, f)),
+            norm2: RMSNormConfig(m),
+            norm_first: true,
+            attn_dropout: 0.0,
+            ff_dropout: 0.0,
+        });
+
+        let x: Tensor<Rank3<2, 5, 8>, TestDtype, _> = dev.sample_normal();
+        let y = block.forward((x.clone(), CausalMask));
+
+        let n = block.norm1.forward(x.clone());
+        let h = block
+            .self_attn
+            .0
+            .forward((n.clone(), n.clone(), n, CausalMask))
+            + x.clone();
+        let expected = block.ff.0.forward(block.norm2.forward(h.clone())) + h;
+        assert_close_to_tensor!(y, expected);
+
+        let g = block
+            .forward_mut((x.leaky_trace(), CausalMask))
+            .mean()
+            .backward();
+        let mut opt = crate::nn::optim::Sgd::new(&block, Default::default());
+        opt.update(&mut block, &g).expect("");
+    }
+
+    #[test]
+    fn test_encoder_block_dropout() {
+        let dev = TestDevice::seed_from_u64(5);
+        let mut block = dev.build_module::<TestDtype>(EncoderBlockConfig {
+            attn_dropout: 0.5,
+            ff_dropout: 0.5,
+            ..EncoderBlockConfig::new(Const::<8>, Const::<2>, Const::<12>)
+        });
+        let mut no_dropout = block.clone();
+        no_dropout.attn_dropout = 0.0;
+        no_dropout.ff_dropout = 0.0;
+
+        let x: Tensor<Rank3<2, 5, 8>, TestDtype, _> = dev.sample_normal();
+
+        // dropout is only applied in forward_mut
+        let y = block.forward(x.clone());
+        assert_eq!(y.array(), no_dropout.forward(x.clone()).array());
+        assert_eq!(y.array(), no_dropout.forward_mut(x.leaky_trace()).array());
+        assert_ne!(y.array(), block.forward_mut(x.leaky_trace()).array());
+    }
+
+    #[test]
+    fn test_transformer_dropout() {
+        let dev = TestDevice::seed_from_u64(6);
+        let mut t = dev.build_module::<TestDtype>(TransformerConfig::new(
+            Const::<8>,
+            Const::<2>,
+            Const::<12>,
+            2,
+            2,
+        ));
+        for block in t.encoder.iter_mut() {
+            block.attn_dropout = 0.5;
+            block.ff_dropout = 0.5;
+        }
+        for block in t.decoder.iter_mut() {
+            block.attn_dropout = 0.5;
+            block.ff_dropout = 0.5;
+        }
+
+        let src: Tensor<Rank3<2, 5, 8>, TestDtype, _> = dev.sample_normal();
+        let tgt: Tensor<Rank3<2, 4, 8>, TestDtype, _> = dev.sample_normal();
+
+        // dropout is only applied in forward_mut
+        let y = t.forward((src.clone(), tgt.clone()));
+        assert_ne!(
+            y.array(),
+            t.forward_mut((src.leaky_trace(), tgt.clone())).array()
+        );
+
+        let y = t.forward((src.clone(), tgt.clone(), CausalMask));
+        assert_ne!(
+            y.array(),
+            t.forward_mut((src.leaky_trace(), tgt, CausalMask)).array()
+        );
+    }
+
     #[test]
     fn test_encoder_block_forward() {
         let dev = TestDevice::seed_from_u64(2);
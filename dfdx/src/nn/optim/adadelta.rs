@@ -0,0 +1,185 @@
+use std::marker::PhantomData;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{AdadeltaConfig, Device},
+};
+
+/// An implementation of the Adadelta optimizer from
+/// [ADADELTA: An Adaptive Learning Rate Method](https://arxiv.org/abs/1212.5701)
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Adadelta<Model, f32, Cpu> = optim::Adadelta::new(&model, AdadeltaConfig {
+///     lr: 0.5,
+///     rho: 0.95,
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::L2(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Adadelta<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: AdadeltaConfig,
+
+    square_avg: Gradients<E, D>,
+    acc_delta: Gradients<E, D>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Adadelta<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: AdadeltaConfig) -> Self {
+        Self {
+            cfg,
+            square_avg: Gradients::leaky(),
+            acc_delta: Gradients::leaky(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adadelta<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let sa = self.square_avg.get_or_alloc_mut(t)?;
+                let ad = self.acc_delta.get_or_alloc_mut(t)?;
+                self.cfg.try_update(t, sa, ad, g)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::SaveOptimizerState<M>
+    for Adadelta<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        super::state::write_buffers(
+            model,
+            location,
+            &[
+                ("square_avg", &self.square_avg),
+                ("acc_delta", &self.acc_delta),
+            ],
+            tensors,
+        );
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::LoadOptimizerState<M>
+    for Adadelta<M, E, D>
+{
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        super::state::read_buffers(
+            model,
+            location,
+            &mut [
+                ("square_avg", &mut self.square_avg),
+                ("acc_delta", &mut self.acc_delta),
+            ],
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected(cfg: AdadeltaConfig, expected: [[f64; 5]; 10]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Adadelta::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_default_adadelta_params() {
+        // Expected values were generated by evaluating pytorch's `torch.optim.Adadelta`
+        // update rule with the same hyperparameters.
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 10] = [
+            [-0.50316155, -0.25316201, 0.096837789, 0.59683773, 0.99683772],
+            [-0.50639547, -0.25639642, 0.093603167, 0.59360305, 0.99360304],
+            [-0.50967224, -0.25967368, 0.090325684, 0.59032551, 0.99032548],
+            [-0.5129772, -0.26297915, 0.087019998, 0.58701976, 0.98701973],
+            [-0.51630164, -0.26630409, 0.08369483, 0.58369453, 0.98369449],
+            [-0.5196398, -0.26964277, 0.080355935, 0.58035557, 0.98035552],
+            [-0.52298763, -0.2729911, 0.077007371, 0.57700694, 0.97700689],
+            [-0.52634213, -0.27634612, 0.073652134, 0.57365164, 0.97365158],
+            [-0.52970102, -0.27970552, 0.070292508, 0.57029195, 0.97029188],
+            [-0.53306251, -0.28306752, 0.066930281, 0.56692966, 0.96692958],
+        ];
+        test_matches_expected(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_adadelta_decoupled_decay() {
+        let cfg = AdadeltaConfig {
+            lr: 1e-1,
+            rho: 0.5,
+            eps: 1e-6,
+            weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+        };
+        // Expected values were generated by evaluating pytorch's `torch.optim.Adadelta`
+        // update rule with the weight decay applied to the parameters directly.
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 10] = [
+            [-0.49514141, -0.24764142, 0.098858579, 0.59385858, 0.98985858],
+            [-0.49035382, -0.24532856, 0.097706819, 0.58775737, 0.97979781],
+            [-0.48563147, -0.24305582, 0.096550106, 0.58170146, 0.96982255],
+            [-0.48097144, -0.24082047, 0.09539091, 0.57569293, 0.95993457],
+            [-0.47637175, -0.23862076, 0.094230665, 0.56973277, 0.95013448],
+            [-0.4718309, -0.23645541, 0.093070333, 0.56382147, 0.94042242],
+            [-0.46734762, -0.23432338, 0.091910627, 0.55795928, 0.93079824],
+            [-0.46292078, -0.23222378, 0.090752109, 0.55214629, 0.92126165],
+            [-0.45854938, -0.23015581, 0.089595245, 0.54638248, 0.91181225],
+            [-0.45423245, -0.22811877, 0.088440431, 0.54066779, 0.90244959],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Adadelta::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
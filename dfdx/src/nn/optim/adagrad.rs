@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{AdagradConfig, Device},
+};
+
+/// An implementation of the Adagrad optimizer from
+/// [Adaptive Subgradient Methods for Online Learning and Stochastic Optimization](https://jmlr.org/papers/v12/duchi11a.html)
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Adagrad<Model, f32, Cpu> = optim::Adagrad::new(&model, AdagradConfig {
+///     lr: 1e-1,
+///     lr_decay: 1e-3,
+///     initial_accumulator_value: 0.1,
+///     eps: 1e-8,
+///     weight_decay: Some(WeightDecay::L2(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Adagrad<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: AdagradConfig,
+
+    t: i32,
+    sum: Gradients<E, D>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Adagrad<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: AdagradConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            sum: Gradients::leaky(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adagrad<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), crate::tensor::Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let s_t = self.sum.get_or_alloc_mut(t)?;
+                self.cfg.try_update(self.t, t, s_t, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::SaveOptimizerState<M>
+    for Adagrad<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        use crate::nn::SaveSafeTensors;
+        self.t.write_safetensors(&format!("{location}t"), tensors);
+        super::state::write_buffers(model, location, &[("sum", &self.sum)], tensors);
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::LoadOptimizerState<M>
+    for Adagrad<M, E, D>
+{
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        use crate::nn::LoadSafeTensors;
+        self.t.read_safetensors(&format!("{location}t"), tensors)?;
+        super::state::read_buffers(model, location, &mut [("sum", &mut self.sum)], tensors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected(cfg: AdagradConfig, expected: [[f64; 5]; 10]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Adagrad::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_default_adagrad_params() {
+        // Expected values were generated by evaluating pytorch's `torch.optim.Adagrad`
+        // update rule with the same hyperparameters.
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 10] = [
+            [-0.51, -0.26, 0.09, 0.59, 0.99],
+            [-0.51700001, -0.26700001, 0.08299999, 0.58299999, 0.98299999],
+            [-0.52268076, -0.27268076, 0.077319237, 0.57731924, 0.97731924],
+            [-0.5275778, -0.2775778, 0.072422204, 0.5724222, 0.9724222],
+            [-0.53194113, -0.28194113, 0.068058872, 0.56805887, 0.96805887],
+            [-0.53591109, -0.28591109, 0.064088913, 0.56408891, 0.96408891],
+            [-0.53957566, -0.28957566, 0.060424337, 0.56042434, 0.96042434],
+            [-0.5429943, -0.2929943, 0.057005701, 0.5570057, 0.9570057],
+            [-0.54620937, -0.29620937, 0.053790628, 0.55379063, 0.95379063],
+            [-0.54925235, -0.29925235, 0.050747653, 0.55074765, 0.95074765],
+        ];
+        test_matches_expected(Default::default(), EXPECTED);
+    }
+
+    #[test]
+    fn test_custom_adagrad_params() {
+        let cfg = AdagradConfig {
+            lr: 1e-1,
+            lr_decay: 0.1,
+            initial_accumulator_value: 0.1,
+            eps: 1e-10,
+            weight_decay: Some(WeightDecay::L2(1e-1)),
+        };
+        // Expected values were generated by evaluating pytorch's `torch.optim.Adagrad`
+        // update rule with the same hyperparameters.
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 10] = [
+            [-0.52936742, -0.30668926, 0.01555428, 0.5024983, 0.90053125],
+            [-0.55219567, -0.34631947, -0.036656442, 0.44558288, 0.84297684],
+            [-0.57058979, -0.37645952, -0.074537337, 0.40494605, 0.80196872],
+            [-0.58580725, -0.40053295, -0.1041067, 0.37342054, 0.77017493],
+            [-0.5986553, -0.4204005, -0.12820976, 0.34778627, 0.74432534],
+            [-0.60968034, -0.43719042, -0.14843876, 0.32628873, 0.72264482],
+            [-0.61926762, -0.45163896, -0.16578089, 0.30785732, 0.70405254],
+            [-0.62769774, -0.46425273, -0.18089225, 0.29178771, 0.68783838],
+            [-0.63518063, -0.47539475, -0.19423149, 0.27759089, 0.67350977],
+            [-0.64187688, -0.48533355, -0.20613178, 0.26491325, 0.6607107],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Adagrad::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
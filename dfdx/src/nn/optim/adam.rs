@@ -1,6 +1,8 @@
 use std::marker::PhantomData;
 
+use super::{ParamGroup, ParamGroups};
 use crate::{
+    nn_traits::VisitParams,
     shapes::{Dtype, Shape},
     tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
     tensor_ops::{AdamConfig, Device},
@@ -9,6 +11,8 @@ use crate::{
 /// An implementation of the Adam optimizer from
 /// [Adam: A Method for Stochastic Optimization](https://arxiv.org/abs/1412.6980)
 ///
+/// Use [crate::tensor_ops::WeightDecay::Decoupled] for AdamW, and [AdamConfig::amsgrad] for AMSGrad.
+///
 /// # Example Usage
 /// ```rust
 /// # use dfdx::prelude::*;
@@ -20,6 +24,7 @@ use crate::{
 ///     betas: [0.5, 0.25],
 ///     eps: 1e-6,
 ///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///     amsgrad: false,
 /// });
 /// ```
 ///
@@ -32,6 +37,8 @@ pub struct Adam<M, E: Dtype, D: Storage<E>> {
     t: i32,
     moment1: Gradients<E, D>,
     moment2: Gradients<E, D>,
+    max_moment2: Gradients<E, D>,
+    param_groups: ParamGroups,
 
     marker: PhantomData<*const M>,
 }
@@ -44,9 +51,21 @@ impl<M, E: Dtype, D: Storage<E>> Adam<M, E, D> {
             t: 0,
             moment1: Gradients::leaky(),
             moment2: Gradients::leaky(),
+            max_moment2: Gradients::leaky(),
+            param_groups: Default::default(),
             marker: PhantomData,
         }
     }
+
+    /// Overrides the hyperparameters of the params of `model` selected by `group`. If
+    /// multiple groups select a param, later groups take precedence.
+    pub fn add_param_group(&mut self, model: &M, group: ParamGroup)
+    where
+        M: VisitParams<E, D>,
+        D: Device<E>,
+    {
+        self.param_groups.add(model, group);
+    }
 }
 
 impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adam<M, E, D> {
@@ -56,13 +75,22 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adam<M, E, D>
         gradients: &Gradients<E, D>,
         missing_params: &mut Vec<UniqueId>,
     ) -> Result<(), crate::tensor::Error> {
+        let cfg = match self.param_groups.config(&t.id(), &self.cfg) {
+            Some(cfg) => cfg,
+            None => return Ok(()),
+        };
         let g = gradients.get_ref_checked(t);
         match g {
             None => missing_params.push(t.id()),
             Some(g) => {
                 let m_t = self.moment1.get_or_alloc_mut(t)?;
                 let v_t = self.moment2.get_or_alloc_mut(t)?;
-                self.cfg.try_update(self.t, t, m_t, v_t, g)?;
+                let v_max_t = if self.cfg.amsgrad {
+                    Some(self.max_moment2.get_or_alloc_mut(t)?)
+                } else {
+                    None
+                };
+                cfg.try_update(self.t, t, m_t, v_t, v_max_t, g)?;
             }
         }
         Ok(())
@@ -85,6 +113,56 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Adam<M, E, D>
     }
 }
 
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::SaveOptimizerState<M>
+    for Adam<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        use crate::nn::SaveSafeTensors;
+        self.t.write_safetensors(&format!("{location}t"), tensors);
+        super::state::write_buffers(
+            model,
+            location,
+            &[
+                ("moment1", &self.moment1),
+                ("moment2", &self.moment2),
+                ("max_moment2", &self.max_moment2),
+            ],
+            tensors,
+        );
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::LoadOptimizerState<M>
+    for Adam<M, E, D>
+{
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        use crate::nn::LoadSafeTensors;
+        self.t.read_safetensors(&format!("{location}t"), tensors)?;
+        super::state::read_buffers(
+            model,
+            location,
+            &mut [
+                ("moment1", &mut self.moment1),
+                ("moment2", &mut self.moment2),
+                ("max_moment2", &mut self.max_moment2),
+            ],
+            tensors,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -129,6 +207,7 @@ mod tests {
                 betas: [0.5, 0.25],
                 eps: 1e-8,
                 weight_decay: None,
+                amsgrad: false,
             },
         );
         let rate = dev
@@ -224,6 +303,44 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_adam_amsgrad() {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Adam::new(
+            &t,
+            AdamConfig {
+                lr: 1e-1,
+                betas: [0.5, 0.25],
+                amsgrad: true,
+                ..Default::default()
+            },
+        );
+        // Expected values were generated by evaluating pytorch's `torch.optim.Adam(amsgrad=True)`
+        // update rule with the same hyperparameters.
+        #[rustfmt::skip]
+        let expected = [
+            [-0.59999999, -0.35, 2.0468268e-09, 0.5, 0.9],
+            [-0.69829237, -0.44829238, -0.098292383, 0.40170761, 0.80170761],
+            [-0.78549084, -0.53549085, -0.18549085, 0.31450915, 0.71450914],
+            [-0.86115009, -0.6111501, -0.26115011, 0.23884989, 0.63884988],
+            [-0.92674161, -0.67674162, -0.32674163, 0.17325836, 0.57325836],
+            [-0.98400692, -0.73400693, -0.38400694, 0.11599305, 0.51599305],
+            [-1.0345186, -0.78451862, -0.43451863, 0.065481366, 0.46548136],
+            [-1.0795762, -0.82957626, -0.47957627, 0.020423725, 0.42042372],
+            [-1.120211, -0.87021101, -0.52021102, -0.020211028, 0.37978897],
+            [-1.1572254, -0.90722543, -0.55722544, -0.057225446, 0.34277455],
+        ];
+
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
     #[test]
     fn test_unused_tensors() {
         let dev: TestDevice = Default::default();
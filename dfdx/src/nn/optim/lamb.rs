@@ -0,0 +1,247 @@
+use std::marker::PhantomData;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, LambConfig},
+};
+
+/// An implementation of the LAMB optimizer from
+/// [Large Batch Optimization for Deep Learning: Training BERT in 76 minutes](https://arxiv.org/abs/1904.00962)
+///
+/// The adam update of every tensor is scaled by a per tensor trust ratio `||param|| / ||update||`,
+/// so each layer of a model gets its own effective learning rate.
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Lamb<Model, f32, Cpu> = optim::Lamb::new(&model, LambConfig {
+///     lr: 1e-2,
+///     betas: [0.9, 0.999],
+///     eps: 1e-6,
+///     weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Lamb<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: LambConfig,
+
+    t: i32,
+    moment1: Gradients<E, D>,
+    moment2: Gradients<E, D>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Lamb<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: LambConfig) -> Self {
+        Self {
+            cfg,
+            t: 0,
+            moment1: Gradients::leaky(),
+            moment2: Gradients::leaky(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Lamb<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), crate::tensor::Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let m_t = self.moment1.get_or_alloc_mut(t)?;
+                let v_t = self.moment2.get_or_alloc_mut(t)?;
+                self.cfg.try_update(self.t, t, m_t, v_t, g)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, module: &mut M, gradients: &Gradients<E, D>) -> Result<(), Error>
+    where
+        M: crate::nn::UpdateParams<E, D>,
+    {
+        self.t = self.t.checked_add(1).unwrap();
+
+        // NOTE: the rest of this is identical to default implementation of update.
+        let mut missing_tensors = Vec::new();
+        module.try_update_params(self, gradients, &mut missing_tensors)?;
+        if missing_tensors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::UnusedTensors(missing_tensors))
+        }
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::SaveOptimizerState<M>
+    for Lamb<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        use crate::nn::SaveSafeTensors;
+        self.t.write_safetensors(&format!("{location}t"), tensors);
+        super::state::write_buffers(
+            model,
+            location,
+            &[("moment1", &self.moment1), ("moment2", &self.moment2)],
+            tensors,
+        );
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::LoadOptimizerState<M>
+    for Lamb<M, E, D>
+{
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        use crate::nn::LoadSafeTensors;
+        self.t.read_safetensors(&format!("{location}t"), tensors)?;
+        super::state::read_buffers(
+            model,
+            location,
+            &mut [
+                ("moment1", &mut self.moment1),
+                ("moment2", &mut self.moment2),
+            ],
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected(cfg: LambConfig, expected: [[f64; 5]; 10]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Lamb::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_lamb() {
+        let cfg = LambConfig {
+            lr: 1e-2,
+            ..Default::default()
+        };
+        // Expected values were generated by evaluating the LAMB paper's update rule
+        // with the same hyperparameters.
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 10] = [
+            [-0.50580084, -0.25580085, 0.094199134, 0.59419913, 0.99419912],
+            [-0.51158294, -0.26158297, 0.088417007, 0.58841699, 0.98841699],
+            [-0.51734688, -0.26734692, 0.082653041, 0.58265302, 0.98265301],
+            [-0.52309323, -0.2730933, 0.076906655, 0.57690662, 0.97690661],
+            [-0.52882259, -0.27882267, 0.071177272, 0.57117723, 0.97117722],
+            [-0.53453552, -0.28453561, 0.065464316, 0.56546427, 0.96546426],
+            [-0.54023259, -0.2902327, 0.059767216, 0.55976716, 0.95976715],
+            [-0.54591438, -0.29591451, 0.054085397, 0.55408534, 0.95408532],
+            [-0.55158146, -0.3015816, 0.04841829, 0.54841822, 0.9484182],
+            [-0.5572344, -0.30723455, 0.042765326, 0.54276525, 0.94276523],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_lamb_decoupled_decay() {
+        let cfg = LambConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.25],
+            eps: 1e-6,
+            weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+        };
+        // Expected values were generated by evaluating the LAMB paper's update rule
+        // with the same hyperparameters.
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 10] = [
+            [-0.50540024, -0.25554236, 0.094258668, 0.59397444, 0.99374705],
+            [-0.5107803, -0.26106403, 0.088538779, 0.58797138, 0.98751746],
+            [-0.51614074, -0.26656554, 0.082839761, 0.58199022, 0.9813106],
+            [-0.52148207, -0.27204746, 0.077161041, 0.57603036, 0.97512584],
+            [-0.52680485, -0.27751033, 0.071502048, 0.5700912, 0.96896254],
+            [-0.5321096, -0.2829547, 0.065862214, 0.56417214, 0.96282011],
+            [-0.53739685, -0.28838112, 0.060240971, 0.55827259, 0.95669791],
+            [-0.54266715, -0.29379014, 0.054637756, 0.55239196, 0.95059535],
+            [-0.54792102, -0.29918229, 0.049052003, 0.54652965, 0.94451179],
+            [-0.55315899, -0.30455813, 0.043483153, 0.54068508, 0.93844665],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_lamb_trust_ratio_is_per_tensor() {
+        let dev: TestDevice = Default::default();
+        let mut model = (
+            dev.tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+                .to_dtype::<TestDtype>(),
+            dev.tensor([0.01, -0.02, 0.03]).to_dtype::<TestDtype>(),
+        );
+        let cfg = LambConfig {
+            lr: 1e-2,
+            ..Default::default()
+        };
+        let mut opt = Lamb::new(&model, cfg);
+
+        // Each tensor follows the same trajectory as if it was optimized on its own.
+        #[rustfmt::skip]
+        let expected_0 = [
+            [-0.50580084, -0.25580085, 0.094199134, 0.59419913, 0.99419912],
+            [-0.51158294, -0.26158297, 0.088417007, 0.58841699, 0.98841699],
+            [-0.51734688, -0.26734692, 0.082653041, 0.58265302, 0.98265301],
+        ];
+        let expected_1 = [
+            [0.0097839753, -0.020216025, 0.029783975],
+            [0.0095686075, -0.020431392, 0.029568607],
+            [0.0093538749, -0.020646125, 0.029353875],
+        ];
+        for (e0, e1) in expected_0.iter().zip(expected_1.iter()) {
+            let loss = model.0.leaky_trace().exp().square().mean()
+                + model.1.leaky_trace().exp().square().mean();
+            let gradients = loss.backward();
+            opt.update(&mut model, &gradients).expect("");
+            assert_close_to_literal!(model.0, e0);
+            assert_close_to_literal!(model.1, e1);
+        }
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Lamb::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
@@ -0,0 +1,168 @@
+use std::marker::PhantomData;
+
+use crate::{
+    shapes::{Dtype, Shape},
+    tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{Device, LionConfig},
+};
+
+/// An implementation of the Lion optimizer from
+/// [Symbolic Discovery of Optimization Algorithms](https://arxiv.org/abs/2302.06675)
+///
+/// Lion only tracks a momentum, and updates by the sign of the interpolated gradient,
+/// so it typically needs a 3-10x smaller learning rate than [crate::nn::optim::Adam].
+///
+/// # Example Usage
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # type Model = Tensor<Rank0, f32, Cpu>;
+/// # let dev: Cpu = Default::default();
+/// # let model: Model = dev.zeros();
+/// let mut opt: Lion<Model, f32, Cpu> = optim::Lion::new(&model, LionConfig {
+///     lr: 3e-4,
+///     betas: [0.9, 0.99],
+///     weight_decay: Some(WeightDecay::Decoupled(1e-1)),
+/// });
+/// ```
+///
+/// See module level documentation at [crate::nn::optim] for examples of how to actually use an optimizer.
+#[derive(Debug, Clone)]
+pub struct Lion<M, E: Dtype, D: Storage<E>> {
+    /// Hyperparameter configuration
+    pub cfg: LionConfig,
+
+    momentums: Gradients<E, D>,
+
+    marker: PhantomData<*const M>,
+}
+
+impl<M, E: Dtype, D: Storage<E>> Lion<M, E, D> {
+    /// Constructs using hyperparameters from `cfg`.
+    pub fn new(_model: &M, cfg: LionConfig) -> Self {
+        Self {
+            cfg,
+            momentums: Gradients::leaky(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Lion<M, E, D> {
+    fn update_tensor<S: Shape>(
+        &mut self,
+        t: &mut Tensor<S, E, D>,
+        gradients: &Gradients<E, D>,
+        missing_params: &mut Vec<UniqueId>,
+    ) -> Result<(), Error> {
+        let g = gradients.get_ref_checked(t);
+        match g {
+            None => missing_params.push(t.id()),
+            Some(g) => {
+                let m_t = self.momentums.get_or_alloc_mut(t)?;
+                self.cfg.try_update(t, m_t, g)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::SaveOptimizerState<M>
+    for Lion<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        super::state::write_buffers(model, location, &[("momentums", &self.momentums)], tensors);
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::LoadOptimizerState<M>
+    for Lion<M, E, D>
+{
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        super::state::read_buffers(
+            model,
+            location,
+            &mut [("momentums", &mut self.momentums)],
+            tensors,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, shapes::*, tensor::*, tensor_ops::*, tests::*};
+
+    fn test_matches_expected<const N: usize>(cfg: LionConfig, expected: [[f64; 5]; N]) {
+        let dev: TestDevice = Default::default();
+        let mut t = dev
+            .tensor([-0.5, -0.25, 0.1, 0.6, 1.0])
+            .to_dtype::<TestDtype>();
+        let mut opt = Lion::new(&t, cfg);
+        for e in expected.iter() {
+            let gradients = t.leaky_trace().exp().square().mean().backward();
+            opt.update(&mut t, &gradients).expect("");
+            assert_close_to_literal!(t, e);
+        }
+    }
+
+    #[test]
+    fn test_lion() {
+        let cfg = LionConfig {
+            lr: 1e-2,
+            ..Default::default()
+        };
+        const EXPECTED: [[f64; 5]; 5] = [
+            [-0.51, -0.26, 0.09, 0.59, 0.99],
+            [-0.52, -0.27, 0.08, 0.58, 0.98],
+            [-0.53, -0.28, 0.07, 0.57, 0.97],
+            [-0.54, -0.29, 0.06, 0.56, 0.96],
+            [-0.55, -0.3, 0.05, 0.55, 0.95],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_lion_decoupled_decay() {
+        let cfg = LionConfig {
+            lr: 1e-2,
+            betas: [0.5, 0.25],
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+        };
+        // Expected values were generated by evaluating the Lion paper's update rule
+        // with the same hyperparameters.
+        #[rustfmt::skip]
+        const EXPECTED: [[f64; 5]; 10] = [
+            [-0.5075, -0.25875, 0.0895, 0.587, 0.985],
+            [-0.5149625, -0.26745625, 0.0790525, 0.574065, 0.970075],
+            [-0.52238769, -0.27611897, 0.068657238, 0.56119467, 0.95522463],
+            [-0.52977575, -0.28473837, 0.058313951, 0.5483887, 0.9404485],
+            [-0.53712687, -0.29331468, 0.048022382, 0.53564676, 0.92574626],
+            [-0.54444124, -0.30184811, 0.03778227, 0.52296852, 0.91111753],
+            [-0.55171903, -0.31033887, 0.027593358, 0.51035368, 0.89656194],
+            [-0.55896043, -0.31878717, 0.017455392, 0.49780191, 0.88207913],
+            [-0.56616563, -0.32719324, 0.0073681146, 0.4853129, 0.86766874],
+            [-0.5733348, -0.33555727, -0.002668726, 0.47288634, 0.85333039],
+        ];
+        test_matches_expected(cfg, EXPECTED);
+    }
+
+    #[test]
+    fn test_unused_tensors() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<5>, TestDtype, _> = dev.sample_normal();
+        let mut opt = Lion::new(&t, Default::default());
+        opt.update(&mut t, &Gradients::leaky()).expect_err("");
+    }
+}
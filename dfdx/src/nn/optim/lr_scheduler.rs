@@ -0,0 +1,727 @@
+//! Learning rate schedulers that drive the `lr` of an optimizer's config.
+
+use crate::{shapes::Dtype, tensor::Storage};
+
+#[cfg(feature = "safetensors")]
+use crate::{LoadSafeTensors, SaveSafeTensors};
+
+use super::{Adadelta, Adagrad, Adam, Lamb, Lion, RMSprop, Sgd};
+
+/// Something with a learning rate that an [LrScheduler] can drive.
+pub trait HasLearningRate {
+    fn learning_rate(&self) -> f64;
+    fn set_learning_rate(&mut self, lr: f64);
+}
+
+macro_rules! optimizer_learning_rate {
+    ($Opt:ident) => {
+        impl<M, E: Dtype, D: Storage<E>> HasLearningRate for $Opt<M, E, D> {
+            fn learning_rate(&self) -> f64 {
+                self.cfg.lr
+            }
+            fn set_learning_rate(&mut self, lr: f64) {
+                self.cfg.lr = lr;
+            }
+        }
+    };
+}
+
+optimizer_learning_rate!(Sgd);
+optimizer_learning_rate!(Adam);
+optimizer_learning_rate!(RMSprop);
+optimizer_learning_rate!(Adagrad);
+optimizer_learning_rate!(Adadelta);
+optimizer_learning_rate!(Lamb);
+optimizer_learning_rate!(Lion);
+
+/// A learning rate schedule, which maps the number of steps taken to a learning rate.
+///
+/// What a step is is up to the caller: call [LrScheduler::step()] after every batch to
+/// schedule per batch, or after every epoch to schedule per epoch.
+///
+/// All schedulers implement `SaveSafeTensors`/`LoadSafeTensors` (with the `safetensors` feature)
+/// for the state that changes while stepping, so a resumed run continues the schedule.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # let mut model = dev.build_module::<f32>(LinearConstConfig::<5, 2>::default());
+/// let mut opt: Sgd<_, f32, Cpu> = Sgd::new(&model, Default::default());
+/// let mut sched = StepLr::new(1e-1, 2, 0.5);
+/// sched.apply(&mut opt);
+/// for _epoch in 0..4 {
+///     // -- snip training loop --
+///     sched.step(&mut opt);
+/// }
+/// assert_eq!(opt.cfg.lr, 1e-1 * 0.25);
+/// ```
+pub trait LrScheduler {
+    /// The learning rate at the current step.
+    fn lr(&self) -> f64;
+
+    /// Moves the schedule forward by one step, without touching any optimizer.
+    fn advance(&mut self);
+
+    /// Sets the learning rate of `opt` to [LrScheduler::lr()].
+    fn apply<O: HasLearningRate>(&self, opt: &mut O) {
+        opt.set_learning_rate(self.lr());
+    }
+
+    /// [LrScheduler::advance()], and then [LrScheduler::apply()] the new learning rate.
+    fn step<O: HasLearningRate>(&mut self, opt: &mut O) {
+        self.advance();
+        self.apply(opt);
+    }
+}
+
+/// A learning rate that never changes. Mostly useful as the inner schedule of [LinearWarmup].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConstantLr {
+    pub lr: f64,
+}
+
+impl LrScheduler for ConstantLr {
+    fn lr(&self) -> f64 {
+        self.lr
+    }
+    fn advance(&mut self) {}
+}
+
+#[cfg(feature = "safetensors")]
+impl crate::nn_traits::SaveSafeTensors for ConstantLr {
+    fn write_safetensors(
+        &self,
+        _location: &str,
+        _tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl crate::nn_traits::LoadSafeTensors for ConstantLr {
+    fn read_safetensors(
+        &mut self,
+        _location: &str,
+        _tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        Ok(())
+    }
+}
+
+/// Decays the learning rate by `gamma` every `step_size` steps:
+/// `lr = base_lr * gamma ^ (step / step_size)`.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.StepLR`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct StepLr {
+    pub base_lr: f64,
+    pub step_size: usize,
+    pub gamma: f64,
+    /// The number of steps taken so far.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub step: usize,
+}
+
+impl StepLr {
+    pub fn new(base_lr: f64, step_size: usize, gamma: f64) -> Self {
+        assert!(step_size > 0);
+        Self {
+            base_lr,
+            step_size,
+            gamma,
+            step: 0,
+        }
+    }
+}
+
+impl LrScheduler for StepLr {
+    fn lr(&self) -> f64 {
+        self.base_lr * self.gamma.powi((self.step / self.step_size) as i32)
+    }
+    fn advance(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Decays the learning rate by `gamma` every step: `lr = base_lr * gamma ^ step`.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.ExponentialLR`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct ExponentialLr {
+    pub base_lr: f64,
+    pub gamma: f64,
+    /// The number of steps taken so far.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub step: usize,
+}
+
+impl ExponentialLr {
+    pub fn new(base_lr: f64, gamma: f64) -> Self {
+        Self {
+            base_lr,
+            gamma,
+            step: 0,
+        }
+    }
+}
+
+impl LrScheduler for ExponentialLr {
+    fn lr(&self) -> f64 {
+        self.base_lr * self.gamma.powi(self.step as i32)
+    }
+    fn advance(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Cosine annealing from `base_lr` to `eta_min`, restarting after `t_0` steps. Each restart
+/// period is `t_mult` times longer than the previous one. From
+/// [SGDR: Stochastic Gradient Descent with Warm Restarts](https://arxiv.org/abs/1608.03983).
+///
+/// Use a `t_0` larger than the total number of steps for plain cosine annealing.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.CosineAnnealingWarmRestarts`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct CosineAnnealingWarmRestarts {
+    pub base_lr: f64,
+    /// Number of steps until the first restart.
+    pub t_0: usize,
+    /// Factor to grow the restart period by after every restart.
+    pub t_mult: usize,
+    /// Minimum learning rate.
+    pub eta_min: f64,
+    /// The number of steps taken so far.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub step: usize,
+}
+
+impl CosineAnnealingWarmRestarts {
+    pub fn new(base_lr: f64, t_0: usize, t_mult: usize, eta_min: f64) -> Self {
+        assert!(t_0 > 0);
+        assert!(t_mult > 0);
+        Self {
+            base_lr,
+            t_0,
+            t_mult,
+            eta_min,
+            step: 0,
+        }
+    }
+}
+
+impl LrScheduler for CosineAnnealingWarmRestarts {
+    fn lr(&self) -> f64 {
+        let (mut t_cur, mut t_i) = (self.step, self.t_0);
+        if self.t_mult == 1 {
+            t_cur %= t_i;
+        } else {
+            while t_cur >= t_i {
+                t_cur -= t_i;
+                t_i *= self.t_mult;
+            }
+        }
+        let cos = (std::f64::consts::PI * t_cur as f64 / t_i as f64).cos();
+        self.eta_min + (self.base_lr - self.eta_min) * (1.0 + cos) / 2.0
+    }
+    fn advance(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Linearly scales the learning rate of `inner` from `start_factor * inner.lr()` up to
+/// `inner.lr()` over `warmup_steps` steps. `inner` only starts stepping after the warmup.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// let mut sched = LinearWarmup::new(2, 0.0, ExponentialLr::new(1.0, 0.5));
+/// let mut lrs = Vec::new();
+/// for _ in 0..5 {
+///     lrs.push(sched.lr());
+///     sched.advance();
+/// }
+/// assert_eq!(lrs, [0.0, 0.5, 1.0, 0.5, 0.25]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearWarmup<S> {
+    pub warmup_steps: usize,
+    pub start_factor: f64,
+    pub inner: S,
+    /// The number of steps taken so far, including the ones taken by `inner`.
+    pub step: usize,
+}
+
+impl<S: LrScheduler> LinearWarmup<S> {
+    pub fn new(warmup_steps: usize, start_factor: f64, inner: S) -> Self {
+        Self {
+            warmup_steps,
+            start_factor,
+            inner,
+            step: 0,
+        }
+    }
+}
+
+impl<S: LrScheduler> LrScheduler for LinearWarmup<S> {
+    fn lr(&self) -> f64 {
+        if self.step < self.warmup_steps {
+            let pct = self.step as f64 / self.warmup_steps as f64;
+            self.inner.lr() * (self.start_factor + (1.0 - self.start_factor) * pct)
+        } else {
+            self.inner.lr()
+        }
+    }
+    fn advance(&mut self) {
+        if self.step >= self.warmup_steps {
+            self.inner.advance();
+        }
+        self.step += 1;
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<S: crate::nn_traits::SaveSafeTensors> crate::nn_traits::SaveSafeTensors for LinearWarmup<S> {
+    fn write_safetensors(
+        &self,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        self.step
+            .write_safetensors(&format!("{location}step"), tensors);
+        self.inner
+            .write_safetensors(&format!("{location}inner."), tensors);
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<S: crate::nn_traits::LoadSafeTensors> crate::nn_traits::LoadSafeTensors for LinearWarmup<S> {
+    fn read_safetensors(
+        &mut self,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        self.step
+            .read_safetensors(&format!("{location}step"), tensors)?;
+        self.inner
+            .read_safetensors(&format!("{location}inner."), tensors)
+    }
+}
+
+/// How [OneCycleLr] interpolates between learning rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnnealStrategy {
+    #[default]
+    Cos,
+    Linear,
+}
+
+impl AnnealStrategy {
+    fn anneal(&self, start: f64, end: f64, pct: f64) -> f64 {
+        match self {
+            Self::Cos => end + (start - end) / 2.0 * (1.0 + (std::f64::consts::PI * pct).cos()),
+            Self::Linear => start + (end - start) * pct,
+        }
+    }
+}
+
+/// The 1cycle policy from
+/// [Super-Convergence](https://arxiv.org/abs/1708.07120). The learning rate goes from
+/// `max_lr / div_factor` up to `max_lr` over the first `pct_start` of `total_steps`, and then
+/// down to `max_lr / (div_factor * final_div_factor)` at the last step.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.OneCycleLR` with `three_phase=False`
+/// and `cycle_momentum=False`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct OneCycleLr {
+    pub max_lr: f64,
+    pub total_steps: usize,
+    /// Percentage of `total_steps` spent increasing the learning rate. Defaults to `0.3`.
+    pub pct_start: f64,
+    /// Defaults to [AnnealStrategy::Cos].
+    pub anneal_strategy: AnnealStrategy,
+    /// The initial learning rate is `max_lr / div_factor`. Defaults to `25.0`.
+    pub div_factor: f64,
+    /// The final learning rate is `max_lr / (div_factor * final_div_factor)`. Defaults to `1e4`.
+    pub final_div_factor: f64,
+    /// The number of steps taken so far.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub step: usize,
+}
+
+impl OneCycleLr {
+    pub fn new(max_lr: f64, total_steps: usize) -> Self {
+        assert!(total_steps > 1);
+        Self {
+            max_lr,
+            total_steps,
+            pct_start: 0.3,
+            anneal_strategy: Default::default(),
+            div_factor: 25.0,
+            final_div_factor: 1e4,
+            step: 0,
+        }
+    }
+}
+
+impl LrScheduler for OneCycleLr {
+    fn lr(&self) -> f64 {
+        assert!(self.total_steps > 0, "OneCycleLr needs at least 1 step");
+        let initial_lr = self.max_lr / self.div_factor;
+        let min_lr = initial_lr / self.final_div_factor;
+        let warmup_end = self.pct_start * self.total_steps as f64 - 1.0;
+        let end = (self.total_steps - 1) as f64;
+        let step = self.step.min(self.total_steps - 1) as f64;
+        // without any warmup steps, annealing starts at `max_lr`
+        if warmup_end > 0.0 && step <= warmup_end {
+            let pct = step / warmup_end;
+            self.anneal_strategy.anneal(initial_lr, self.max_lr, pct)
+        } else {
+            let pct = (step - warmup_end) / (end - warmup_end);
+            self.anneal_strategy.anneal(self.max_lr, min_lr, pct)
+        }
+    }
+    fn advance(&mut self) {
+        self.step += 1;
+    }
+}
+
+/// Whether [ReduceLrOnPlateau] should look for a decreasing or increasing metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlateauMode {
+    /// E.g. for a loss.
+    #[default]
+    Min,
+    /// E.g. for an accuracy.
+    Max,
+}
+
+/// Multiplies the learning rate by `factor` when a metric has not improved for more than
+/// `patience` steps.
+///
+/// The metric is passed in with [ReduceLrOnPlateau::observe()] before each
+/// [LrScheduler::step()]. Steps without an observed metric leave the learning rate unchanged.
+///
+/// **Pytorch equivalent**: `torch.optim.lr_scheduler.ReduceLROnPlateau` with `threshold_mode="rel"`.
+///
+/// Example:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # let model = dev.build_module::<f32>(LinearConstConfig::<5, 2>::default());
+/// let mut opt: Adam<_, f32, Cpu> = Adam::new(&model, Default::default());
+/// let mut sched = ReduceLrOnPlateau {
+///     patience: 1,
+///     ..ReduceLrOnPlateau::new(1e-3)
+/// };
+/// for val_loss in [1.0, 0.5, 0.6, 0.7] {
+///     sched.observe(val_loss);
+///     sched.step(&mut opt);
+/// }
+/// assert!((opt.cfg.lr - 1e-4).abs() < 1e-12);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "safetensors", derive(SaveSafeTensors, LoadSafeTensors))]
+pub struct ReduceLrOnPlateau {
+    /// Defaults to [PlateauMode::Min].
+    pub mode: PlateauMode,
+    /// Defaults to `0.1`.
+    pub factor: f64,
+    /// Number of steps without improvement to wait before reducing. Defaults to `10`.
+    pub patience: usize,
+    /// Relative amount the metric has to improve by to count. Defaults to `1e-4`.
+    pub threshold: f64,
+    /// Number of steps to wait after a reduction before counting bad steps again. Defaults to `0`.
+    pub cooldown: usize,
+    /// Lower bound on the learning rate. Defaults to `0.0`.
+    pub min_lr: f64,
+    /// The current learning rate.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub current_lr: f64,
+    /// The best metric seen so far, `NaN` before the first one.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub best: f64,
+    /// Number of steps since the metric last improved.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub num_bad_steps: usize,
+    /// Number of cooldown steps left.
+    #[cfg_attr(feature = "safetensors", serialize)]
+    pub cooldown_counter: usize,
+    /// The metric passed to [ReduceLrOnPlateau::observe()], consumed by the next step.
+    pub metric: Option<f64>,
+}
+
+impl ReduceLrOnPlateau {
+    pub fn new(lr: f64) -> Self {
+        Self {
+            mode: Default::default(),
+            factor: 0.1,
+            patience: 10,
+            threshold: 1e-4,
+            cooldown: 0,
+            min_lr: 0.0,
+            current_lr: lr,
+            best: f64::NAN,
+            num_bad_steps: 0,
+            cooldown_counter: 0,
+            metric: None,
+        }
+    }
+
+    /// Records `metric` to be used by the next [LrScheduler::step()].
+    pub fn observe(&mut self, metric: f64) {
+        self.metric = Some(metric);
+    }
+
+    fn is_better(&self, metric: f64) -> bool {
+        if self.best.is_nan() {
+            return true;
+        }
+        match self.mode {
+            PlateauMode::Min => metric < self.best * (1.0 - self.threshold),
+            PlateauMode::Max => metric > self.best * (1.0 + self.threshold),
+        }
+    }
+}
+
+impl LrScheduler for ReduceLrOnPlateau {
+    fn lr(&self) -> f64 {
+        self.current_lr
+    }
+    fn advance(&mut self) {
+        let Some(metric) = self.metric.take() else {
+            return;
+        };
+
+        if self.is_better(metric) {
+            self.best = metric;
+            self.num_bad_steps = 0;
+        } else {
+            self.num_bad_steps += 1;
+        }
+
+        if self.cooldown_counter > 0 {
+            self.cooldown_counter -= 1;
+            self.num_bad_steps = 0;
+        }
+
+        if self.num_bad_steps > self.patience {
+            self.current_lr = (self.current_lr * self.factor).max(self.min_lr);
+            self.cooldown_counter = self.cooldown;
+            self.num_bad_steps = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::excessive_precision)]
+mod tests {
+    use super::*;
+    use crate::{prelude::*, tests::*};
+
+    fn lrs<S: LrScheduler>(mut sched: S, n: usize) -> Vec<f64> {
+        let mut lrs = Vec::new();
+        for _ in 0..n {
+            lrs.push(sched.lr());
+            sched.advance();
+        }
+        lrs
+    }
+
+    fn assert_all_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        for (x, y) in a.iter().zip(b.iter()) {
+            assert!((x - y).abs() < 1e-7, "{a:?} != {b:?}");
+        }
+    }
+
+    #[test]
+    fn test_step_and_exponential_lr() {
+        assert_all_close(
+            &lrs(StepLr::new(1.0, 2, 0.1), 5),
+            &[1.0, 1.0, 0.1, 0.1, 0.01],
+        );
+        assert_all_close(
+            &lrs(ExponentialLr::new(1.0, 0.5), 4),
+            &[1.0, 0.5, 0.25, 0.125],
+        );
+    }
+
+    #[test]
+    fn test_cosine_annealing_warm_restarts() {
+        // Expected values were generated by evaluating pytorch's
+        // `CosineAnnealingWarmRestarts(T_0=2, T_mult=2, eta_min=0.01)` equations.
+        assert_all_close(
+            &lrs(CosineAnnealingWarmRestarts::new(0.1, 2, 2, 0.01), 10),
+            &[
+                0.1,
+                0.055,
+                0.1,
+                0.086819805,
+                0.055,
+                0.023180195,
+                0.1,
+                0.096574579,
+                0.086819805,
+                0.072220754,
+            ],
+        );
+        assert_all_close(
+            &lrs(CosineAnnealingWarmRestarts::new(1.0, 2, 1, 0.0), 4),
+            &[1.0, 0.5, 1.0, 0.5],
+        );
+    }
+
+    #[test]
+    fn test_one_cycle_lr() {
+        // Expected values were generated by evaluating pytorch's `OneCycleLR` equations.
+        let sched = OneCycleLr::new(1.0, 10);
+        assert_all_close(
+            &lrs(sched, 10),
+            &[
+                0.04,
+                0.52,
+                1.0,
+                0.95048463,
+                0.81174565,
+                0.61126202,
+                0.38874198,
+                0.18825835,
+                0.049519368,
+                4e-06,
+            ],
+        );
+        let sched = OneCycleLr {
+            anneal_strategy: AnnealStrategy::Linear,
+            ..sched
+        };
+        assert_all_close(
+            &lrs(sched, 10),
+            &[
+                0.04, 0.52, 1.0, 0.85714343, 0.71428686, 0.57143029, 0.42857371, 0.28571714,
+                0.14286057, 4e-06,
+            ],
+        );
+    }
+
+    #[test]
+    fn test_one_cycle_lr_no_warmup() {
+        // the warmup ends at step 0
+        let sched = OneCycleLr {
+            pct_start: 0.1,
+            anneal_strategy: AnnealStrategy::Linear,
+            ..OneCycleLr::new(1.0, 10)
+        };
+        let expected: Vec<f64> = (0..10)
+            .map(|i| 1.0 - (1.0 - 4e-6) * i as f64 / 9.0)
+            .collect();
+        assert_all_close(&lrs(sched, 10), &expected);
+    }
+
+    #[test]
+    fn test_linear_warmup() {
+        let sched = LinearWarmup::new(4, 0.2, ConstantLr { lr: 1.0 });
+        assert_all_close(&lrs(sched, 6), &[0.2, 0.4, 0.6, 0.8, 1.0, 1.0]);
+
+        let sched = LinearWarmup::new(1, 0.5, StepLr::new(1.0, 1, 0.5));
+        assert_all_close(&lrs(sched, 4), &[0.5, 1.0, 0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_reduce_lr_on_plateau() {
+        let mut sched = ReduceLrOnPlateau {
+            patience: 1,
+            cooldown: 1,
+            factor: 0.5,
+            ..ReduceLrOnPlateau::new(1.0)
+        };
+        let mut lrs = Vec::new();
+        for metric in [1.0, 0.9, 0.95, 0.95, 0.95, 0.95, 0.95, 0.1] {
+            sched.observe(metric);
+            sched.advance();
+            lrs.push(sched.lr());
+        }
+        assert_all_close(&lrs, &[1.0, 1.0, 1.0, 0.5, 0.5, 0.5, 0.25, 0.25]);
+
+        // no metric means no change
+        sched.advance();
+        assert_eq!(sched.lr(), 0.25);
+
+        let mut sched = ReduceLrOnPlateau {
+            mode: PlateauMode::Max,
+            patience: 0,
+            min_lr: 0.5,
+            factor: 0.1,
+            ..ReduceLrOnPlateau::new(1.0)
+        };
+        for metric in [0.5, 0.6, 0.55] {
+            sched.observe(metric);
+            sched.advance();
+        }
+        assert_eq!(sched.lr(), 0.5);
+    }
+
+    #[test]
+    fn test_scheduler_drives_optimizer() {
+        let dev: TestDevice = Default::default();
+        let mut t: Tensor<Rank1<3>, TestDtype, _> = dev.ones();
+        let mut opt = Sgd::new(
+            &t,
+            SgdConfig {
+                lr: 123.0,
+                momentum: None,
+                weight_decay: None,
+            },
+        );
+        let mut sched = StepLr::new(1.0, 1, 0.5);
+        sched.apply(&mut opt);
+        for _ in 0..2 {
+            let g = t.leaky_trace().sum().backward();
+            opt.update(&mut t, &g).expect("");
+            sched.step(&mut opt);
+        }
+        assert_eq!(opt.learning_rate(), 0.25);
+        assert_close_to_literal!(t, [-0.5; 3]);
+    }
+
+    #[cfg(feature = "safetensors")]
+    #[test]
+    fn test_scheduler_state_safetensors() {
+        let file = tempfile::NamedTempFile::new().expect("failed to create tempfile");
+
+        let mut sched = LinearWarmup::new(2, 0.1, CosineAnnealingWarmRestarts::new(1.0, 5, 1, 0.0));
+        for _ in 0..4 {
+            sched.advance();
+        }
+        sched.save_safetensors(file.path()).expect("");
+
+        let mut loaded =
+            LinearWarmup::new(2, 0.1, CosineAnnealingWarmRestarts::new(1.0, 5, 1, 0.0));
+        loaded.load_safetensors(file.path()).expect("");
+        assert_eq!(loaded, sched);
+
+        let mut sched = ReduceLrOnPlateau {
+            patience: 0,
+            ..ReduceLrOnPlateau::new(1.0)
+        };
+        for metric in [1.0, 2.0, 0.5] {
+            sched.observe(metric);
+            sched.advance();
+        }
+        sched.save_safetensors(file.path()).expect("");
+        // only the state is saved, hyperparameters come from the constructor
+        let mut loaded = ReduceLrOnPlateau {
+            patience: 0,
+            ..ReduceLrOnPlateau::new(1.0)
+        };
+        loaded.load_safetensors(file.path()).expect("");
+        assert_eq!(loaded.lr(), sched.lr());
+        loaded.observe(0.6);
+        loaded.advance();
+        assert_all_close(&[loaded.lr()], &[0.01]);
+    }
+}
@@ -1,4 +1,5 @@
-//! Optimizers such as [Sgd], [Adam], and [RMSprop] that can optimize neural networks.
+//! Optimizers such as [Sgd], [Adam], [RMSprop], [Adagrad], [Adadelta], [Lamb] and [Lion]
+//! that can optimize neural networks.
 //!
 //! # Initializing
 //!
@@ -7,6 +8,12 @@
 //! - [Sgd::new()] with [SgdConfig]
 //! - [Adam::new()] with [AdamConfig]
 //! - [RMSprop::new()] with [RMSpropConfig]
+//! - [Adagrad::new()] with [AdagradConfig]
+//! - [Adadelta::new()] with [AdadeltaConfig]
+//! - [Lamb::new()] with [LambConfig]
+//! - [Lion::new()] with [LionConfig]
+//!
+//! AdamW is [Adam] with [WeightDecay::Decoupled], and AMSGrad is enabled with [AdamConfig::amsgrad].
 //!
 //! # Updating network parameters
 //!
@@ -30,14 +37,110 @@
 //! opt.update(&mut model, &grads);
 //! model.zero_grads(&mut grads);
 //! ```
+//!
+//! # Parameter groups
+//!
+//! [Sgd], [Adam] and [RMSprop] can override the learning rate & weight decay of, or freeze,
+//! some parameters with [ParamGroup]s, which select parameters by their location in the model
+//! or the type of the layer that owns them. See [ParamGroup] for an example.
+//!
+//! # Learning rate schedules
+//!
+//! An [LrScheduler] sets the learning rate of any optimizer implementing [HasLearningRate]
+//! each time it is stepped. See [StepLr], [ExponentialLr], [CosineAnnealingWarmRestarts],
+//! [LinearWarmup], [OneCycleLr] and [ReduceLrOnPlateau].
+//!
+//! # Checkpointing
+//!
+//! With the `safetensors` feature, optimizer state can be saved and loaded alongside the model
+//! with `SaveOptimizerState` and `LoadOptimizerState`. The state is keyed by each parameter's
+//! location in the model, so it can be loaded into a freshly built model & optimizer.
+//!
+//! # Gradient clipping
+//!
+//! Gradients can be clipped in place before the update with [crate::nn::WithGrads::clip_grad_norm()]
+//! or [crate::nn::WithGrads::clip_grad_value()]:
+//!
+//! ```rust
+//! # use dfdx::prelude::*;
+//! # let dev: Cpu = Default::default();
+//! # use dfdx::nn::optim::*;
+//! # type Model = LinearConstConfig<5, 2>;
+//! # let mut model = dev.build_module::<f32>(Model::default());
+//! # let mut opt = Sgd::new(&model, Default::default());
+//! # let x: Tensor<Rank1<5>, f32, _> = dev.ones();
+//! # let loss = dfdx::losses::mse_loss(model.forward(x.leaky_trace()), dev.zeros());
+//! let mut grads = loss.backward();
+//! let norm_before_clipping = model.clip_grad_norm(&mut grads, 1.0);
+//! assert!(model.grad_norm(&mut grads) <= 1.0);
+//! opt.update(&mut model, &grads);
+//! ```
 
+mod adadelta;
+mod adagrad;
 mod adam;
+mod lamb;
+mod lion;
+mod lr_scheduler;
+mod param_groups;
 mod rmsprop;
 mod sgd;
+#[cfg(feature = "safetensors")]
+mod state;
 
+use param_groups::ParamGroups;
+
+pub use adadelta::Adadelta;
+pub use adagrad::Adagrad;
 pub use adam::Adam;
+pub use lamb::Lamb;
+pub use lion::Lion;
+pub use lr_scheduler::{
+    AnnealStrategy, ConstantLr, CosineAnnealingWarmRestarts, ExponentialLr, HasLearningRate,
+    LinearWarmup, LrScheduler, OneCycleLr, PlateauMode, ReduceLrOnPlateau, StepLr,
+};
+pub use param_groups::{ParamGroup, ParamSelector};
 pub use rmsprop::RMSprop;
 pub use sgd::Sgd;
+#[cfg(feature = "safetensors")]
+pub use state::{save_safetensors_checkpoint, LoadOptimizerState, SaveOptimizerState};
 // re-exports
 pub use super::Optimizer;
-pub use crate::tensor_ops::{AdamConfig, Momentum, RMSpropConfig, SgdConfig, WeightDecay};
+pub use crate::tensor_ops::{
+    AdadeltaConfig, AdagradConfig, AdamConfig, LambConfig, LionConfig, Momentum, RMSpropConfig,
+    SgdConfig, WeightDecay,
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::{prelude::*, tests::*};
+    use num_traits::ToPrimitive;
+
+    #[test]
+    fn test_clip_grad_norm_visits_all_params() {
+        let dev: TestDevice = Default::default();
+        type Model = (LinearConstConfig<3, 4>, ReLU, LayerNorm1DConstConfig<4>);
+        let model = dev.build_module::<TestDtype>(Model::default());
+
+        let x: Tensor<Rank2<2, 3>, TestDtype, _> = dev.sample_normal();
+        let mut grads = model.forward(x.leaky_trace()).square().sum().backward();
+        let norm_squared = |grads: &Gradients<TestDtype, TestDevice>| {
+            let params = [
+                grads.get(&model.0.weight).as_vec(),
+                grads.get(&model.0.bias).as_vec(),
+                grads.get(&model.2.gamma).as_vec(),
+                grads.get(&model.2.beta).as_vec(),
+            ];
+            params
+                .iter()
+                .flatten()
+                .map(|g| g.to_f64().unwrap().powi(2))
+                .sum::<f64>()
+        };
+
+        let expected = norm_squared(&grads).sqrt();
+        let norm = model.clip_grad_norm(&mut grads, 1e-2).to_f64().unwrap();
+        assert!((norm - expected).abs() < 1e-3 * expected);
+        assert!((norm_squared(&grads).sqrt() - 1e-2).abs() < 1e-4);
+    }
+}
@@ -0,0 +1,320 @@
+//! Per parameter overrides of optimizer hyperparameters.
+
+use std::collections::HashMap;
+
+use crate::{
+    nn_traits::{ParamsVisitor, VisitParams},
+    shapes::{Dtype, Shape},
+    tensor::{Error, Tensor, Tensorlike, UniqueId},
+    tensor_ops::{AdamConfig, Device, RMSpropConfig, SgdConfig, WeightDecay},
+};
+
+/// Selects parameters of a module for a [ParamGroup].
+///
+/// Locations are the same as the keys parameters are saved under with `SaveSafeTensors`,
+/// see [crate::nn::VisitParams].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamSelector {
+    /// Every parameter.
+    All,
+    /// Parameters whose location starts with this, e.g. `"0."` for everything in the
+    /// first module of a tuple.
+    Prefix(String),
+    /// Parameters whose location ends with this, e.g. `"bias"` for all biases.
+    Suffix(String),
+    /// Parameters owned directly by a module with this type name, without generics or
+    /// module path, e.g. `"LayerNorm1D"`.
+    LayerType(String),
+}
+
+impl ParamSelector {
+    fn matches(&self, location: &str, layer_type: Option<&str>) -> bool {
+        match self {
+            Self::All => true,
+            Self::Prefix(prefix) => location.starts_with(prefix.as_str()),
+            Self::Suffix(suffix) => location.ends_with(suffix.as_str()),
+            Self::LayerType(ty) => layer_type == Some(ty.as_str()),
+        }
+    }
+}
+
+/// Hyperparameters that override an optimizer's config for the parameters selected by
+/// [ParamGroup::selector].
+///
+/// Examples:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # use dfdx::nn::optim::*;
+/// # let dev: Cpu = Default::default();
+/// type Model = (LinearConstConfig<5, 3>, LayerNorm1DConstConfig<3>, LinearConstConfig<3, 2>);
+/// let model = dev.build_module::<f32>(Model::default());
+/// let mut opt = Adam::new(
+///     &model,
+///     AdamConfig {
+///         weight_decay: Some(WeightDecay::Decoupled(1e-2)),
+///         ..Default::default()
+///     },
+/// );
+/// // no weight decay for biases & layer norms
+/// opt.add_param_group(&model, ParamGroup::new(ParamSelector::Suffix("bias".into())).with_weight_decay(None));
+/// opt.add_param_group(&model, ParamGroup::new(ParamSelector::LayerType("LayerNorm1D".into())).with_weight_decay(None));
+/// // freeze the first linear layer
+/// opt.add_param_group(&model, ParamGroup::new(ParamSelector::Prefix("0.".into())).frozen());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ParamGroup {
+    pub selector: ParamSelector,
+    /// Overrides the learning rate. Note that learning rate schedulers only change the
+    /// learning rate of the optimizer's config, not this one.
+    pub lr: Option<f64>,
+    /// Overrides the weight decay. `Some(None)` disables weight decay.
+    pub weight_decay: Option<Option<WeightDecay>>,
+    /// Frozen parameters are not updated, and are not reported in
+    /// [crate::tensor::Error::UnusedTensors] if they have no gradient.
+    pub frozen: bool,
+}
+
+impl ParamGroup {
+    /// A group that doesn't override anything yet.
+    pub fn new(selector: ParamSelector) -> Self {
+        Self {
+            selector,
+            lr: None,
+            weight_decay: None,
+            frozen: false,
+        }
+    }
+
+    pub fn with_lr(mut self, lr: f64) -> Self {
+        self.lr = Some(lr);
+        self
+    }
+
+    pub fn with_weight_decay(mut self, weight_decay: Option<WeightDecay>) -> Self {
+        self.weight_decay = Some(weight_decay);
+        self
+    }
+
+    pub fn frozen(mut self) -> Self {
+        self.frozen = true;
+        self
+    }
+}
+
+/// An optimizer config that can be overridden by a [ParamGroup].
+pub(super) trait GroupConfig: Copy {
+    fn set_lr(&mut self, lr: f64);
+    fn set_weight_decay(&mut self, weight_decay: Option<WeightDecay>);
+}
+
+macro_rules! group_config {
+    ($($Cfg:ty),+) => {
+        $(
+            impl GroupConfig for $Cfg {
+                fn set_lr(&mut self, lr: f64) {
+                    self.lr = lr;
+                }
+                fn set_weight_decay(&mut self, weight_decay: Option<WeightDecay>) {
+                    self.weight_decay = weight_decay;
+                }
+            }
+        )+
+    };
+}
+
+group_config!(SgdConfig, AdamConfig, RMSpropConfig);
+
+#[derive(Debug, Clone, Default)]
+struct Overrides {
+    lr: Option<f64>,
+    weight_decay: Option<Option<WeightDecay>>,
+    frozen: bool,
+}
+
+/// The [ParamGroup]s of an optimizer, resolved to the parameters they select.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ParamGroups {
+    overrides: HashMap<UniqueId, Overrides>,
+}
+
+impl ParamGroups {
+    pub(super) fn add<M: VisitParams<E, D>, E: Dtype, D: Device<E>>(
+        &mut self,
+        model: &M,
+        group: ParamGroup,
+    ) {
+        struct Resolver<'a> {
+            group: ParamGroup,
+            layer_types: Vec<&'static str>,
+            overrides: &'a mut HashMap<UniqueId, Overrides>,
+        }
+
+        impl<'a, E: Dtype, D: Device<E>> ParamsVisitor<E, D> for Resolver<'a> {
+            fn visit_param<S: Shape>(
+                &mut self,
+                location: &str,
+                t: &Tensor<S, E, D>,
+            ) -> Result<(), Error> {
+                let layer_type = self.layer_types.last().map(|ty| short_type_name(ty));
+                if self.group.selector.matches(location, layer_type) {
+                    let o = self.overrides.entry(t.id()).or_default();
+                    o.lr = self.group.lr.or(o.lr);
+                    o.weight_decay = self.group.weight_decay.or(o.weight_decay);
+                    o.frozen |= self.group.frozen;
+                }
+                Ok(())
+            }
+
+            fn enter_module(&mut self, _location: &str, type_name: &'static str) {
+                self.layer_types.push(type_name);
+            }
+
+            fn exit_module(&mut self) {
+                self.layer_types.pop();
+            }
+        }
+
+        model.visit_params(&mut Resolver {
+            group,
+            layer_types: Vec::new(),
+            overrides: &mut self.overrides,
+        });
+    }
+
+    /// The config to update the parameter `id` with, or `None` if it is frozen.
+    pub(super) fn config<C: GroupConfig>(&self, id: &UniqueId, cfg: &C) -> Option<C> {
+        let mut cfg = *cfg;
+        if let Some(o) = self.overrides.get(id) {
+            if o.frozen {
+                return None;
+            }
+            if let Some(lr) = o.lr {
+                cfg.set_lr(lr);
+            }
+            if let Some(weight_decay) = o.weight_decay {
+                cfg.set_weight_decay(weight_decay);
+            }
+        }
+        Some(cfg)
+    }
+}
+
+/// `dfdx::nn::layers::linear::Linear<Const<3>, f32, Cpu>` -> `Linear`
+fn short_type_name(type_name: &str) -> &str {
+    let path = type_name.split('<').next().unwrap_or(type_name);
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::optim::*, prelude::*, tests::*};
+
+    type Model = (
+        LinearConstConfig<3, 4>,
+        LayerNorm1DConstConfig<4>,
+        LinearConstConfig<4, 2>,
+    );
+
+    #[test]
+    fn test_short_type_name() {
+        assert_eq!(
+            short_type_name(std::any::type_name::<Linear<Const<3>, Const<4>, f32, Cpu>>()),
+            "Linear"
+        );
+        assert_eq!(short_type_name("Foo"), "Foo");
+    }
+
+    #[test]
+    fn test_no_weight_decay_for_biases_and_layer_norms() {
+        let dev: TestDevice = Default::default();
+        let model = dev.build_module::<TestDtype>(Model::default());
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+        let grads = model.forward(x.leaky_trace()).square().mean().backward();
+
+        let cfg = SgdConfig {
+            lr: 1e-1,
+            momentum: None,
+            weight_decay: Some(WeightDecay::Decoupled(0.5)),
+        };
+        let mut decayed = model.clone();
+        let mut opt = Sgd::new(&decayed, cfg);
+        opt.add_param_group(
+            &decayed,
+            ParamGroup::new(ParamSelector::Suffix("bias".into())).with_weight_decay(None),
+        );
+        opt.add_param_group(
+            &decayed,
+            ParamGroup::new(ParamSelector::LayerType("LayerNorm1D".into())).with_weight_decay(None),
+        );
+        opt.update(&mut decayed, &grads).unwrap();
+
+        let mut undecayed = model.clone();
+        let mut opt = Sgd::new(
+            &undecayed,
+            SgdConfig {
+                weight_decay: None,
+                ..cfg
+            },
+        );
+        opt.update(&mut undecayed, &grads).unwrap();
+
+        assert_eq!(decayed.0.bias.array(), undecayed.0.bias.array());
+        assert_eq!(decayed.1.gamma.array(), undecayed.1.gamma.array());
+        assert_eq!(decayed.1.beta.array(), undecayed.1.beta.array());
+        assert_eq!(decayed.2.bias.array(), undecayed.2.bias.array());
+        assert_ne!(decayed.0.weight.array(), undecayed.0.weight.array());
+        assert_ne!(decayed.2.weight.array(), undecayed.2.weight.array());
+    }
+
+    #[test]
+    fn test_frozen_params_are_not_updated() {
+        let dev: TestDevice = Default::default();
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+        let grads = model.forward(x.leaky_trace()).square().mean().backward();
+
+        let old = model.clone();
+        let mut opt = Adam::new(&model, Default::default());
+        opt.add_param_group(
+            &model,
+            ParamGroup::new(ParamSelector::Prefix("0.".into())).frozen(),
+        );
+        opt.add_param_group(
+            &model,
+            ParamGroup::new(ParamSelector::Prefix("1.".into())).with_lr(0.0),
+        );
+        opt.update(&mut model, &grads).unwrap();
+
+        assert_eq!(model.0.weight.array(), old.0.weight.array());
+        assert_eq!(model.0.bias.array(), old.0.bias.array());
+        assert_eq!(model.1.gamma.array(), old.1.gamma.array());
+        assert_ne!(model.2.weight.array(), old.2.weight.array());
+    }
+
+    #[test]
+    fn test_frozen_params_are_not_unused() {
+        let dev: TestDevice = Default::default();
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let x: Tensor<Rank2<5, 4>, TestDtype, _> = dev.sample_normal();
+        // only the head is used
+        let grads = model.2.forward(x.leaky_trace()).square().mean().backward();
+
+        let mut opt = RMSprop::new(&model, Default::default());
+        assert!(matches!(
+            opt.update(&mut model, &grads),
+            Err(crate::tensor::Error::UnusedTensors(_))
+        ));
+
+        let mut opt = RMSprop::new(&model, Default::default());
+        opt.add_param_group(
+            &model,
+            ParamGroup::new(ParamSelector::Prefix("0.".into())).frozen(),
+        );
+        opt.add_param_group(
+            &model,
+            ParamGroup::new(ParamSelector::Prefix("1.".into())).frozen(),
+        );
+        opt.update(&mut model, &grads).unwrap();
+    }
+}
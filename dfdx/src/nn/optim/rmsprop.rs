@@ -1,6 +1,8 @@
 use std::marker::PhantomData;
 
+use super::{ParamGroup, ParamGroups};
 use crate::{
+    nn_traits::VisitParams,
     shapes::{Dtype, Shape},
     tensor::{Error, Gradients, Storage, Tensor, Tensorlike, UniqueId},
     tensor_ops::{Device, RMSpropConfig},
@@ -42,6 +44,7 @@ pub struct RMSprop<M, E: Dtype, D: Storage<E>> {
     momentums: Gradients<E, D>,
     square_avg: Gradients<E, D>,
     grad_avg: Gradients<E, D>,
+    param_groups: ParamGroups,
 
     marker: PhantomData<*const M>,
 }
@@ -55,9 +58,20 @@ impl<M, E: Dtype, D: Storage<E>> RMSprop<M, E, D> {
             momentums: Gradients::leaky(),
             square_avg: Gradients::leaky(),
             grad_avg: Gradients::leaky(),
+            param_groups: Default::default(),
             marker: PhantomData,
         }
     }
+
+    /// Overrides the hyperparameters of the params of `model` selected by `group`. If
+    /// multiple groups select a param, later groups take precedence.
+    pub fn add_param_group(&mut self, model: &M, group: ParamGroup)
+    where
+        M: VisitParams<E, D>,
+        D: Device<E>,
+    {
+        self.param_groups.add(model, group);
+    }
 }
 
 impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RMSprop<M, E, D> {
@@ -67,6 +81,10 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RMSprop<M, E,
         gradients: &Gradients<E, D>,
         missing_params: &mut Vec<UniqueId>,
     ) -> Result<(), Error> {
+        let cfg = match self.param_groups.config(&t.id(), &self.cfg) {
+            Some(cfg) => cfg,
+            None => return Ok(()),
+        };
         let g = gradients.get_ref_checked(t);
         match g {
             None => missing_params.push(t.id()),
@@ -79,7 +97,7 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RMSprop<M, E,
                     t.device().try_fill_with_ones(sa)?;
                 }
 
-                self.cfg.try_update(t, m, sa, ga, g)?;
+                cfg.try_update(t, m, sa, ga, g)?;
             }
         }
         Ok(())
@@ -102,6 +120,58 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for RMSprop<M, E,
     }
 }
 
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::SaveOptimizerState<M>
+    for RMSprop<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        use crate::nn::SaveSafeTensors;
+        self.step
+            .write_safetensors(&format!("{location}step"), tensors);
+        super::state::write_buffers(
+            model,
+            location,
+            &[
+                ("momentums", &self.momentums),
+                ("square_avg", &self.square_avg),
+                ("grad_avg", &self.grad_avg),
+            ],
+            tensors,
+        );
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::LoadOptimizerState<M>
+    for RMSprop<M, E, D>
+{
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        use crate::nn::LoadSafeTensors;
+        self.step
+            .read_safetensors(&format!("{location}step"), tensors)?;
+        super::state::read_buffers(
+            model,
+            location,
+            &mut [
+                ("momentums", &mut self.momentums),
+                ("square_avg", &mut self.square_avg),
+                ("grad_avg", &mut self.grad_avg),
+            ],
+            tensors,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
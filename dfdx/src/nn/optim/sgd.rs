@@ -1,4 +1,6 @@
+use super::{ParamGroup, ParamGroups};
 use crate::{
+    nn_traits::VisitParams,
     shapes::{Dtype, Shape},
     tensor::{Gradients, Storage, Tensor, Tensorlike, UniqueId},
     tensor_ops::{Device, SgdConfig},
@@ -32,6 +34,7 @@ use crate::{
 pub struct Sgd<M, E: Dtype, D: Storage<E>> {
     pub cfg: SgdConfig,
     velocity: Gradients<E, D>,
+    param_groups: ParamGroups,
     module: std::marker::PhantomData<*const M>,
 }
 
@@ -40,9 +43,20 @@ impl<M, E: Dtype, D: Storage<E>> Sgd<M, E, D> {
         Self {
             cfg,
             velocity: Gradients::leaky(),
+            param_groups: Default::default(),
             module: std::marker::PhantomData,
         }
     }
+
+    /// Overrides the hyperparameters of the params of `model` selected by `group`. If
+    /// multiple groups select a param, later groups take precedence.
+    pub fn add_param_group(&mut self, model: &M, group: ParamGroup)
+    where
+        M: VisitParams<E, D>,
+        D: Device<E>,
+    {
+        self.param_groups.add(model, group);
+    }
 }
 
 impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Sgd<M, E, D> {
@@ -52,18 +66,55 @@ impl<M, E: Dtype, D: Device<E>> crate::nn::Optimizer<M, E, D> for Sgd<M, E, D> {
         gradients: &Gradients<E, D>,
         missing_params: &mut Vec<UniqueId>,
     ) -> Result<(), crate::tensor::Error> {
+        let cfg = match self.param_groups.config(&t.id(), &self.cfg) {
+            Some(cfg) => cfg,
+            None => return Ok(()),
+        };
         let g = gradients.get_ref_checked(t);
         match g {
             None => missing_params.push(t.id()),
             Some(g) => {
                 let v = self.velocity.get_or_alloc_mut(t)?;
-                self.cfg.try_update(t, v, g)?;
+                cfg.try_update(t, v, g)?;
             }
         }
         Ok(())
     }
 }
 
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::SaveOptimizerState<M>
+    for Sgd<M, E, D>
+{
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    ) {
+        super::state::write_buffers(model, location, &[("velocity", &self.velocity)], tensors);
+    }
+}
+
+#[cfg(feature = "safetensors")]
+impl<M: crate::nn::VisitParams<E, D>, E: Dtype, D: Device<E>> super::LoadOptimizerState<M>
+    for Sgd<M, E, D>
+{
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &::safetensors::SafeTensors,
+    ) -> Result<(), ::safetensors::SafeTensorError> {
+        super::state::read_buffers(
+            model,
+            location,
+            &mut [("velocity", &mut self.velocity)],
+            tensors,
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,281 @@
+//! Saving & loading optimizer state with safetensors.
+
+use crate::{
+    nn_traits::{ParamsVisitor, SaveSafeTensors, VisitParams},
+    shapes::{Dtype, HasShape, Shape},
+    tensor::{Error, Gradients, Tensor},
+    tensor_ops::Device,
+};
+
+use ::safetensors::{SafeTensorError, SafeTensors};
+
+/// Something whose state can be saved to a .safetensors file, like [SaveSafeTensors].
+///
+/// Optimizers keep their state keyed by the [crate::tensor::UniqueId] of each parameter, which
+/// changes between processes. So the state is instead saved under the location of each
+/// parameter in `model` (see [VisitParams]), e.g. `moment1.0.weight` for the first moment of
+/// the weight of the first layer in a tuple.
+///
+/// Example of a full training checkpoint:
+/// ```rust
+/// # use dfdx::prelude::*;
+/// # let dev: Cpu = Default::default();
+/// # let file = tempfile::NamedTempFile::new().unwrap();
+/// # let path = file.path();
+/// type Model = LinearConstConfig<2, 3>;
+/// let mut model = dev.build_module::<f32>(Model::default());
+/// let mut opt = Adam::new(&model, Default::default());
+/// // -- snip training --
+/// let mut tensors = Vec::new();
+/// model.write_safetensors("model.", &mut tensors);
+/// opt.write_safetensors(&model, "optim.", &mut tensors);
+/// save_safetensors_checkpoint(&tensors, path).unwrap();
+///
+/// // e.g. in a new process
+/// let mut model = dev.build_module::<f32>(Model::default());
+/// let mut opt = Adam::new(&model, Default::default());
+/// let buffer = std::fs::read(path).unwrap();
+/// let tensors = ::safetensors::SafeTensors::deserialize(&buffer).unwrap();
+/// model.read_safetensors("model.", &tensors).unwrap();
+/// opt.read_safetensors(&model, "optim.", &tensors).unwrap();
+/// ```
+pub trait SaveOptimizerState<M> {
+    /// Saves only the optimizer state to `path`.
+    fn save_safetensors<P: AsRef<std::path::Path>>(
+        &self,
+        model: &M,
+        path: P,
+    ) -> Result<(), SafeTensorError> {
+        let mut tensors = Vec::new();
+        self.write_safetensors(model, "", &mut tensors);
+        save_safetensors_checkpoint(&tensors, path)
+    }
+
+    fn write_safetensors(
+        &self,
+        model: &M,
+        location: &str,
+        tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    );
+}
+
+/// Something whose state can be loaded from a .safetensors file. See [SaveOptimizerState].
+///
+/// Parameters that are missing from the file (e.g. because they never had a gradient)
+/// keep their current state.
+pub trait LoadOptimizerState<M> {
+    /// Loads only the optimizer state from `path`.
+    fn load_safetensors<P: AsRef<std::path::Path>>(
+        &mut self,
+        model: &M,
+        path: P,
+    ) -> Result<(), SafeTensorError> {
+        let buffer = std::fs::read(path)?;
+        let tensors = SafeTensors::deserialize(&buffer)?;
+        self.read_safetensors(model, "", &tensors)
+    }
+
+    fn read_safetensors(
+        &mut self,
+        model: &M,
+        location: &str,
+        tensors: &SafeTensors,
+    ) -> Result<(), SafeTensorError>;
+}
+
+/// Serializes the output of any number of `write_safetensors` calls into a single file.
+pub fn save_safetensors_checkpoint<P: AsRef<std::path::Path>>(
+    tensors: &[(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)],
+    path: P,
+) -> Result<(), SafeTensorError> {
+    let data = tensors.iter().map(|(k, dtype, shape, data)| {
+        (
+            k.clone(),
+            ::safetensors::tensor::TensorView::new(*dtype, shape.clone(), data).unwrap(),
+        )
+    });
+    ::safetensors::serialize_to_file(data, &None, path.as_ref())
+}
+
+/// Writes each of `buffers` under `{location}{name}.{param location}`.
+pub(super) fn write_buffers<M: VisitParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    location: &str,
+    buffers: &[(&str, &Gradients<E, D>)],
+    tensors: &mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+) {
+    struct Writer<'a, 'b, E: Dtype, D: Device<E>> {
+        location: &'a str,
+        buffers: &'a [(&'b str, &'b Gradients<E, D>)],
+        tensors: &'a mut Vec<(String, ::safetensors::Dtype, Vec<usize>, Vec<u8>)>,
+    }
+
+    impl<'a, 'b, E: Dtype, D: Device<E>> ParamsVisitor<E, D> for Writer<'a, 'b, E, D> {
+        fn visit_param<S: Shape>(&mut self, param: &str, t: &Tensor<S, E, D>) -> Result<(), Error> {
+            for (name, buffer) in self.buffers.iter() {
+                if buffer.get_ref_checked(t).is_some() {
+                    let key = format!("{}{name}.{param}", self.location);
+                    buffer.get(t).write_safetensors(&key, self.tensors);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    model
+        .try_visit_params(
+            "",
+            &mut Writer {
+                location,
+                buffers,
+                tensors,
+            },
+        )
+        .unwrap()
+}
+
+/// Reads each of `buffers` from `{location}{name}.{param location}`, skipping missing keys.
+pub(super) fn read_buffers<'b, M: VisitParams<E, D>, E: Dtype, D: Device<E>>(
+    model: &M,
+    location: &str,
+    buffers: &mut [(&'b str, &'b mut Gradients<E, D>)],
+    tensors: &SafeTensors,
+) -> Result<(), SafeTensorError> {
+    struct Reader<'a, 'b, 'c, E: Dtype, D: Device<E>> {
+        location: &'a str,
+        buffers: &'a mut [(&'b str, &'b mut Gradients<E, D>)],
+        tensors: &'a SafeTensors<'c>,
+        result: Result<(), SafeTensorError>,
+    }
+
+    impl<'a, 'b, 'c, E: Dtype, D: Device<E>> ParamsVisitor<E, D> for Reader<'a, 'b, 'c, E, D> {
+        fn visit_param<S: Shape>(&mut self, param: &str, t: &Tensor<S, E, D>) -> Result<(), Error> {
+            for (name, buffer) in self.buffers.iter_mut() {
+                let key = format!("{}{name}.{param}", self.location);
+                if self.result.is_err() || self.tensors.tensor(&key).is_err() {
+                    continue;
+                }
+                let mut state = t.device().try_zeros_like(t.shape())?;
+                self.result = state.load_safetensor(self.tensors, &key);
+                if self.result.is_ok() {
+                    buffer.set(t, state);
+                }
+            }
+            Ok(())
+        }
+    }
+
+    let mut reader = Reader {
+        location,
+        buffers,
+        tensors,
+        result: Ok(()),
+    };
+    model
+        .try_visit_params("", &mut reader)
+        .map_err(|e| SafeTensorError::IoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+    reader.result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{nn::*, prelude::*, tests::*};
+
+    type Model = (LinearConstConfig<3, 4>, ReLU, LinearConstConfig<4, 2>);
+
+    fn train_step<O: Optimizer<M, TestDtype, TestDevice>, M>(
+        model: &mut M,
+        opt: &mut O,
+        x: Tensor<Rank2<5, 3>, TestDtype, TestDevice>,
+    ) where
+        M: Module<
+            Tensor<Rank2<5, 3>, TestDtype, TestDevice, OwnedTape<TestDtype, TestDevice>>,
+            Output = Tensor<Rank2<5, 2>, TestDtype, TestDevice, OwnedTape<TestDtype, TestDevice>>,
+        >,
+        M: ZeroGrads<TestDtype, TestDevice> + UpdateParams<TestDtype, TestDevice>,
+    {
+        let mut grads = model.alloc_grads();
+        grads = model.forward(x.traced(grads)).square().mean().backward();
+        opt.update(model, &grads).unwrap();
+    }
+
+    #[test]
+    fn test_adam_checkpoint_round_trip() {
+        let dev: TestDevice = Default::default();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let mut opt = Adam::new(&model, Default::default());
+        for _ in 0..3 {
+            train_step(&mut model, &mut opt, x.clone());
+        }
+
+        let mut tensors = Vec::new();
+        model.write_safetensors("model.", &mut tensors);
+        opt.write_safetensors(&model, "optim.", &mut tensors);
+        save_safetensors_checkpoint(&tensors, file.path()).unwrap();
+
+        let buffer = std::fs::read(file.path()).unwrap();
+        let tensors = SafeTensors::deserialize(&buffer).unwrap();
+        let mut names = tensors.names();
+        names.sort();
+        assert_eq!(
+            names,
+            [
+                "model.0.bias",
+                "model.0.weight",
+                "model.2.bias",
+                "model.2.weight",
+                "optim.moment1.0.bias",
+                "optim.moment1.0.weight",
+                "optim.moment1.2.bias",
+                "optim.moment1.2.weight",
+                "optim.moment2.0.bias",
+                "optim.moment2.0.weight",
+                "optim.moment2.2.bias",
+                "optim.moment2.2.weight",
+                "optim.t",
+            ]
+        );
+
+        let mut loaded_model = dev.build_module::<TestDtype>(Model::default());
+        let mut loaded_opt = Adam::new(&loaded_model, Default::default());
+        loaded_model.read_safetensors("model.", &tensors).unwrap();
+        loaded_opt
+            .read_safetensors(&loaded_model, "optim.", &tensors)
+            .unwrap();
+
+        train_step(&mut model, &mut opt, x.clone());
+        train_step(&mut loaded_model, &mut loaded_opt, x);
+        assert_eq!(loaded_model.0.weight.array(), model.0.weight.array());
+        assert_eq!(loaded_model.2.bias.array(), model.2.bias.array());
+    }
+
+    #[test]
+    fn test_sgd_momentum_save_load() {
+        let dev: TestDevice = Default::default();
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let x: Tensor<Rank2<5, 3>, TestDtype, _> = dev.sample_normal();
+        let cfg = SgdConfig {
+            momentum: Some(Momentum::Nesterov(0.9)),
+            ..Default::default()
+        };
+
+        let mut model = dev.build_module::<TestDtype>(Model::default());
+        let mut opt = Sgd::new(&model, cfg);
+        train_step(&mut model, &mut opt, x.clone());
+        opt.save_safetensors(&model, file.path()).unwrap();
+
+        let mut loaded_model = model.clone();
+        let mut loaded_opt = Sgd::new(&loaded_model, cfg);
+        loaded_opt
+            .load_safetensors(&loaded_model, file.path())
+            .unwrap();
+
+        train_step(&mut model, &mut opt, x.clone());
+        train_step(&mut loaded_model, &mut loaded_opt, x);
+        assert_eq!(loaded_model.0.weight.array(), model.0.weight.array());
+    }
+}
@@ -0,0 +1,313 @@
+#![cfg(feature = "onnx")]
+
+use dfdx::{
+    onnx::{load_model, proto::*},
+    prelude::*,
+};
+
+/// Saves `model` to a file and parses it back.
+fn export<M, X: dfdx::onnx::OnnxInput, O: Shape>(model: &M, x: X) -> GraphProto
+where
+    M: SaveOnnx<f32, Cpu> + Module<X::Traced, Output = Tensor<O, f32, Cpu, CaptureTape>>,
+{
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.onnx");
+    model.save_onnx(x, &path).unwrap();
+    let model = load_model(&path).unwrap();
+    assert_eq!(model.ir_version, IR_VERSION);
+    assert_eq!(model.opset_import[0].version, OPSET_VERSION);
+    model.graph.unwrap()
+}
+
+fn op_types(graph: &GraphProto) -> Vec<&str> {
+    graph.node.iter().map(|n| n.op_type.as_str()).collect()
+}
+
+fn initializer<'a>(graph: &'a GraphProto, name: &str) -> &'a TensorProto {
+    graph
+        .initializer
+        .iter()
+        .find(|t| t.name == name)
+        .unwrap_or_else(|| panic!("no initializer {name}"))
+}
+
+fn tensor_type(info: &ValueInfoProto) -> type_proto::Tensor {
+    match info.r#type.as_ref().and_then(|t| t.value.clone()) {
+        Some(type_proto::Value::TensorType(t)) => t,
+        None => panic!("{} is not a tensor", info.name),
+    }
+}
+
+fn shape(info: &ValueInfoProto) -> Vec<i64> {
+    let dims = tensor_type(info).shape.unwrap().dim;
+    dims.iter()
+        .map(|d| match d.value {
+            Some(tensor_shape_proto::dimension::Value::DimValue(v)) => v,
+            _ => panic!("dynamic dimension"),
+        })
+        .collect()
+}
+
+#[test]
+fn test_export_linear_relu() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>((LinearConstConfig::<3, 5>::default(), ReLU));
+    let graph = export(&model, dev.sample_normal::<Rank2<2, 3>>());
+
+    assert_eq!(
+        op_types(&graph),
+        ["Transpose", "MatMul", "Unsqueeze", "Expand", "Add", "Relu"]
+    );
+    assert_eq!(graph.node[1].input[1], graph.node[0].output[0]);
+    assert_eq!(graph.node[0].input, ["0.weight"]);
+    assert_eq!(graph.node[2].input[0], "0.bias");
+    assert_eq!(graph.node[5].output, ["output"]);
+
+    let weight = initializer(&graph, "0.weight");
+    assert_eq!(weight.dims, [5, 3]);
+    assert_eq!(weight.data_type, DataType::Float as i32);
+    let data: Vec<f32> = weight
+        .raw_data
+        .chunks(4)
+        .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    assert_eq!(data, model.0.weight.as_vec());
+    assert_eq!(initializer(&graph, "0.bias").dims, [5]);
+
+    assert_eq!(graph.input.len(), 1);
+    assert_eq!(graph.input[0].name, "input");
+    assert_eq!(shape(&graph.input[0]), [2, 3]);
+    assert_eq!(graph.output[0].name, "output");
+    assert_eq!(shape(&graph.output[0]), [2, 5]);
+}
+
+#[test]
+fn test_export_activations() {
+    let dev: Cpu = Default::default();
+    type Arch = (
+        (Sigmoid, Tanh, FastGeLU, AccurateGeLU),
+        (LeakyReLU, PReLUConfig, Abs, Softmax, LogSoftmax),
+    );
+    let model = dev.build_module::<f32>(Arch::default());
+    let graph = export(&model, dev.sample_normal::<Rank2<2, 3>>());
+    let ops = op_types(&graph);
+    for op in [
+        "Sigmoid",
+        "Tanh",
+        "Erf",
+        "LeakyRelu",
+        "PRelu",
+        "Abs",
+        "ReduceMax",
+        "Exp",
+        "ReduceSum",
+        "Div",
+        "Log",
+    ] {
+        assert!(ops.contains(&op), "{op} not in {ops:?}");
+    }
+    assert_eq!(
+        graph
+            .initializer
+            .iter()
+            .filter(|t| t.name == "1.1.a")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_export_conv_and_pooling() {
+    let dev: Cpu = Default::default();
+    type Arch = (
+        Conv2DConstConfig<3, 4, 3, 1, 1>,
+        Bias2DConstConfig<4>,
+        MaxPool2DConst<2, 2>,
+        AvgPool2DConst<2>,
+        MinPool2DConst<2>,
+        AvgPoolGlobal,
+    );
+    let model = dev.build_module::<f32>(Arch::default());
+    let graph = export(&model, dev.sample_normal::<Rank4<2, 3, 8, 8>>());
+    let ops = op_types(&graph);
+    assert_eq!(ops[0], "Conv");
+    assert!(ops.contains(&"MaxPool"));
+    assert!(ops.contains(&"AveragePool"));
+    assert_eq!(ops.iter().filter(|&&op| op == "Neg").count(), 2);
+
+    let conv = &graph.node[0];
+    assert_eq!(conv.input, ["input", "0.weight"]);
+    let attr = |name: &str| conv.attribute.iter().find(|a| a.name == name).unwrap();
+    assert_eq!(attr("kernel_shape").ints, [3, 3]);
+    assert_eq!(attr("pads").ints, [1, 1, 1, 1]);
+    assert_eq!(attr("strides").ints, [1, 1]);
+    assert_eq!(attr("group").i, 1);
+    assert_eq!(initializer(&graph, "0.weight").dims, [4, 3, 3, 3]);
+    assert_eq!(shape(&graph.output[0]), [2, 4]);
+}
+
+#[test]
+fn test_export_unbatched_conv1d() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(<Conv1DConstConfig<2, 4, 3, 2>>::default());
+    let graph = export(&model, dev.sample_normal::<Rank2<2, 9>>());
+    assert_eq!(op_types(&graph), ["Reshape", "Conv", "Reshape"]);
+    assert_eq!(graph.node[1].attribute[0].ints, [3]);
+    assert_eq!(shape(&graph.output[0]), [4, 4]);
+}
+
+#[test]
+fn test_export_conv3d_and_pool1d() {
+    let dev: Cpu = Default::default();
+    type Arch = (Conv3DConstConfig<2, 3, 2, 1, 1>, MaxPool3DConst<2, 2>);
+    let model = dev.build_module::<f32>(Arch::default());
+    let graph = export(&model, dev.sample_normal::<Rank5<1, 2, 4, 4, 4>>());
+    assert_eq!(op_types(&graph), ["Conv", "MaxPool"]);
+    assert_eq!(graph.node[0].attribute[0].ints, [2, 2, 2]);
+    assert_eq!(graph.node[1].attribute[2].ints, [0; 6]);
+    assert_eq!(shape(&graph.output[0]), [1, 3, 2, 2, 2]);
+
+    let model = dev.build_module::<f32>(AvgPool1DConst::<3, 1, 1>::default());
+    let graph = export(&model, dev.sample_normal::<Rank2<2, 9>>());
+    assert_eq!(op_types(&graph), ["Reshape", "AveragePool", "Reshape"]);
+    assert_eq!(graph.node[1].attribute[2].ints, [1, 1]);
+    assert_eq!(shape(&graph.output[0]), [2, 9]);
+}
+
+#[test]
+fn test_export_batch_norm() {
+    let dev: Cpu = Default::default();
+    let mut model = dev.build_module::<f32>(BatchNorm2DConstConfig::<3>::default());
+    model.running_mean = dev.sample_normal();
+    let graph = export(&model, dev.sample_normal::<Rank4<2, 3, 4, 4>>());
+    assert_eq!(
+        op_types(&graph),
+        ["Expand", "Sub", "Expand", "Mul", "Reshape", "Expand", "Add"]
+    );
+    // the running statistics aren't parameters, and the scale is computed outside of
+    // the graph, so they are constants
+    let running_mean = initializer(&graph, &graph.node[0].input[0]);
+    assert_eq!(running_mean.dims, [1, 3, 1, 1]);
+    let scale = initializer(&graph, &graph.node[2].input[0]);
+    assert_eq!(scale.dims, [1, 3, 1, 1]);
+    // the bias is a parameter that is broadcasted
+    assert_eq!(graph.node[4].input[0], "bias");
+    assert_eq!(initializer(&graph, "bias").dims, [3]);
+}
+
+#[test]
+fn test_export_layer_norm() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(LayerNorm1DConstConfig::<5>::default());
+    let graph = export(&model, dev.sample_normal::<Rank2<3, 5>>());
+    let ops = op_types(&graph);
+    assert_eq!(ops.iter().filter(|&&op| op == "ReduceSum").count(), 2);
+    assert!(ops.contains(&"Sqrt"));
+    assert_eq!(initializer(&graph, "gamma").dims, [5]);
+    assert_eq!(initializer(&graph, "beta").dims, [5]);
+}
+
+#[test]
+fn test_export_embedding() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(EmbeddingConstConfig::<10, 4>::default());
+    let x: Tensor<Rank2<2, 3>, usize, _> = dev.tensor([[1, 2, 3], [4, 5, 9]]);
+    let graph = export(&model, x);
+    assert_eq!(op_types(&graph), ["Gather"]);
+    assert_eq!(graph.node[0].input, ["weight", "input"]);
+    assert_eq!(
+        tensor_type(&graph.input[0]).elem_type,
+        DataType::Int64 as i32
+    );
+    assert_eq!(shape(&graph.output[0]), [2, 3, 4]);
+}
+
+#[test]
+fn test_export_multi_head_attention() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(MultiHeadAttentionConfig::new(
+        Const::<8>, Const::<2>, Const::<8>, Const::<8>,
+    ));
+
+    // self attention has a single input
+    let graph = export(&model, dev.sample_normal::<Rank3<2, 3, 8>>());
+    assert_eq!(graph.input.len(), 1);
+    let ops = op_types(&graph);
+    assert_eq!(ops.iter().filter(|&&op| op == "MatMul").count(), 6);
+    assert!(ops.contains(&"Softmax") || ops.contains(&"ReduceMax"));
+    for name in ["w_qweight", "w_kweight", "w_vweight", "w_oweight"] {
+        assert_eq!(initializer(&graph, name).dims, [8, 8]);
+    }
+
+    let q = dev.sample_normal::<Rank3<2, 3, 8>>();
+    let kv = dev.sample_normal::<Rank3<2, 4, 8>>();
+    let graph = export(&model, (q, kv.clone(), kv));
+    let inputs: Vec<_> = graph.input.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(inputs, ["input0", "input1"]);
+    assert_eq!(shape(&graph.output[0]), [2, 3, 8]);
+}
+
+#[test]
+fn test_export_transformer() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(TransformerConfig::new(
+        Const::<8>,
+        Const::<2>,
+        Const::<16>,
+        1,
+        1,
+    ));
+    let src = dev.sample_normal::<Rank3<2, 5, 8>>();
+    let tgt = dev.sample_normal::<Rank3<2, 3, 8>>();
+    let graph = export(&model, (src, tgt));
+
+    let inputs: Vec<_> = graph.input.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(inputs, ["input0", "input1"]);
+    assert_eq!(shape(&graph.input[0]), [2, 5, 8]);
+    assert_eq!(shape(&graph.input[1]), [2, 3, 8]);
+    assert_eq!(shape(&graph.output[0]), [2, 3, 8]);
+    assert_eq!(initializer(&graph, "encoder0.ff0l1weight").dims, [16, 8]);
+    assert_eq!(
+        initializer(&graph, "decoder0.mh_attnw_qweight").dims,
+        [8, 8]
+    );
+
+    // every value is computed before it is used
+    let mut known: Vec<&str> = graph.input.iter().map(|i| i.name.as_str()).collect();
+    known.extend(graph.initializer.iter().map(|t| t.name.as_str()));
+    for node in graph.node.iter() {
+        for input in node.input.iter() {
+            assert!(
+                known.contains(&input.as_str()),
+                "{input} is used before it is computed"
+            );
+        }
+        known.extend(node.output.iter().map(|o| o.as_str()));
+    }
+}
+
+#[test]
+fn test_export_uncaptured_op() {
+    #[derive(Clone, Debug, Default, CustomModule)]
+    struct Upscale;
+
+    impl<E: Dtype, D: Device<E> + Upscale2DKernel<E, NearestNeighbor>, T: Tape<E, D> + 'static>
+        Module<Tensor<Rank4<1, 1, 2, 2>, E, D, T>> for Upscale
+    {
+        type Output = Tensor<Rank4<1, 1, 4, 4>, E, D, T>;
+        fn try_forward(
+            &self,
+            x: Tensor<Rank4<1, 1, 2, 2>, E, D, T>,
+        ) -> Result<Self::Output, Error> {
+            x.try_upscale2d(NearestNeighbor)
+        }
+    }
+
+    let dev: Cpu = Default::default();
+    let x = dev.sample_normal::<Rank4<1, 1, 2, 2>>();
+    let err = SaveOnnx::<f32, Cpu>::to_onnx(&Upscale, x).unwrap_err();
+    assert!(
+        matches!(err, dfdx::onnx::OnnxError::Unsupported(_)),
+        "{err}"
+    );
+}
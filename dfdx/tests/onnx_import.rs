@@ -0,0 +1,229 @@
+#![cfg(feature = "onnx")]
+
+use dfdx::{
+    onnx::{proto::*, OnnxError, OnnxInput, OnnxModel, OnnxValue},
+    prelude::*,
+};
+
+/// Exports `model`, runs the file with an [OnnxModel], and checks it gives the same
+/// output as `model` for `x`.
+fn round_trip<M, X, O>(model: &M, x: X, inputs: Vec<OnnxValue>)
+where
+    M: SaveOnnx<f32, Cpu> + Module<X, Output = Tensor<O, f32, Cpu>>,
+    M: Module<X::Traced, Output = Tensor<O, f32, Cpu, CaptureTape>>,
+    X: OnnxInput + Clone,
+    O: Shape,
+{
+    let dev: Cpu = Default::default();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.onnx");
+    model.save_onnx(x.clone(), &path).unwrap();
+    let imported = OnnxModel::load(&path, &dev).unwrap();
+    assert_eq!(imported.input_names().count(), inputs.len());
+
+    let expected = model.forward(x);
+    let outputs = imported.run(inputs).unwrap();
+    assert_eq!(outputs.len(), 1);
+    let actual = outputs[0].to_tensor(*expected.shape()).unwrap();
+    for (a, e) in actual.as_vec().into_iter().zip(expected.as_vec()) {
+        assert!((a - e).abs() <= 1e-4 + 1e-4 * e.abs(), "{a} != {e}");
+    }
+}
+
+/// A model of a single `op` node from `x` (followed by the `initializer`s) to `y`.
+fn single_op(
+    op: &str,
+    attribute: Vec<AttributeProto>,
+    initializer: Vec<TensorProto>,
+    x: &[usize],
+) -> ModelProto {
+    let mut input = vec!["x".to_string()];
+    input.extend(initializer.iter().map(|t| t.name.clone()));
+    ModelProto {
+        ir_version: IR_VERSION,
+        opset_import: vec![OperatorSetIdProto {
+            domain: String::new(),
+            version: OPSET_VERSION,
+        }],
+        graph: Some(GraphProto {
+            node: vec![NodeProto {
+                input,
+                output: vec!["y".into()],
+                op_type: op.into(),
+                attribute,
+                ..Default::default()
+            }],
+            initializer,
+            input: vec![ValueInfoProto::tensor("x", DataType::Float, x)],
+            output: vec![ValueInfoProto::tensor("y", DataType::Float, &[])],
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+fn floats(name: &str, dims: &[i64], float_data: Vec<f32>) -> TensorProto {
+    TensorProto {
+        name: name.into(),
+        dims: dims.to_vec(),
+        data_type: DataType::Float as i32,
+        float_data,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_import_linear_relu() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>((
+        LinearConstConfig::<3, 5>::default(),
+        ReLU,
+        LinearConstConfig::<5, 2>::default(),
+    ));
+    let x = dev.sample_normal::<Rank2<4, 3>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+}
+
+#[test]
+fn test_import_activations() {
+    let dev: Cpu = Default::default();
+    type Arch = (
+        (Sigmoid, Tanh, FastGeLU, AccurateGeLU),
+        (LeakyReLU, PReLUConfig, Abs, Softmax, LogSoftmax),
+    );
+    let model = dev.build_module::<f32>(Arch::default());
+    let x = dev.sample_normal::<Rank2<2, 3>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+}
+
+#[test]
+fn test_import_conv_and_pooling() {
+    let dev: Cpu = Default::default();
+    type Arch = (
+        Conv2DConstConfig<3, 4, 3, 1, 1>,
+        Bias2DConstConfig<4>,
+        MaxPool2DConst<2, 2>,
+        AvgPool2DConst<2>,
+        MinPool2DConst<2>,
+        AvgPoolGlobal,
+    );
+    let mut model = dev.build_module::<f32>(Arch::default());
+    model.1.bias = dev.sample_normal();
+    let x = dev.sample_normal::<Rank4<2, 3, 8, 8>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+
+    let model = dev.build_module::<f32>(<Conv1DConstConfig<2, 4, 3, 2>>::default());
+    let x = dev.sample_normal::<Rank2<2, 9>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+
+    type Arch1D = (
+        MaxPool1DConst<2>,
+        AvgPool1DConst<3, 2, 1>,
+        MinPool1DConst<2>,
+    );
+    let model = dev.build_module::<f32>(Arch1D::default());
+    let x = dev.sample_normal::<Rank3<2, 3, 12>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+
+    type Arch3D = (
+        Conv3DConstConfig<2, 3, 2, 1, 1, 1, 1>,
+        AvgPool3DConst<2, 2>,
+        MaxPool3DConst<2>,
+    );
+    let model = dev.build_module::<f32>(Arch3D::default());
+    let x = dev.sample_normal::<Rank5<2, 2, 5, 5, 5>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+}
+
+#[test]
+fn test_import_norms() {
+    let dev: Cpu = Default::default();
+    let mut model = dev.build_module::<f32>(BatchNorm2DConstConfig::<3>::default());
+    model.running_mean = dev.sample_normal();
+    model.bias = dev.sample_normal();
+    let x = dev.sample_normal::<Rank4<2, 3, 4, 4>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+
+    let mut model = dev.build_module::<f32>(LayerNorm1DConstConfig::<5>::default());
+    model.gamma = dev.sample_normal();
+    model.beta = dev.sample_normal();
+    let x = dev.sample_normal::<Rank3<2, 3, 5>>();
+    round_trip(&model, x.clone(), vec![x.into()]);
+}
+
+#[test]
+fn test_import_embedding() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(EmbeddingConstConfig::<10, 4>::default());
+    let x: Tensor<Rank2<2, 3>, usize, _> = dev.tensor([[1, 2, 3], [4, 5, 9]]);
+    round_trip(&model, x.clone(), vec![x.into()]);
+}
+
+#[test]
+fn test_import_transformer() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(TransformerConfig::new(
+        Const::<8>,
+        Const::<2>,
+        Const::<16>,
+        1,
+        1,
+    ));
+    let src = dev.sample_normal::<Rank3<2, 5, 8>>();
+    let tgt = dev.sample_normal::<Rank3<2, 3, 8>>();
+    round_trip(
+        &model,
+        (src.clone(), tgt.clone()),
+        vec![src.into(), tgt.into()],
+    );
+}
+
+#[test]
+fn test_import_wrong_inputs() {
+    let dev: Cpu = Default::default();
+    let model = dev.build_module::<f32>(LinearConstConfig::<3, 5>::default());
+    let proto = model.to_onnx(dev.sample_normal::<Rank2<2, 3>>()).unwrap();
+    let model = OnnxModel::from_proto(&proto, &dev).unwrap();
+    assert_eq!(model.input_names().collect::<Vec<_>>(), ["input"]);
+    assert_eq!(model.output_names().collect::<Vec<_>>(), ["output"]);
+
+    let err = model.run(vec![]).unwrap_err();
+    assert!(matches!(err, OnnxError::Invalid(_)), "{err}");
+    let x: Tensor<Rank2<2, 4>, f32, _> = dev.sample_normal();
+    let err = model.run(vec![x.into()]).unwrap_err();
+    assert!(matches!(err, OnnxError::Invalid(_)), "{err}");
+}
+
+#[test]
+fn test_import_zero_sized() {
+    let dev: Cpu = Default::default();
+    let run = |m: &ModelProto, shape: (usize, usize, usize, usize)| {
+        let model = OnnxModel::from_proto(m, &dev).unwrap();
+        let x: Tensor<_, f32, _> = dev.zeros_like(&shape);
+        model.run(vec![x.into()]).unwrap().remove(0)
+    };
+
+    for op in ["GlobalAveragePool", "GlobalMaxPool"] {
+        for shape in [(0, 2, 3, 3), (2, 0, 3, 3)] {
+            let m = single_op(op, vec![], vec![], &[shape.0, shape.1, 3, 3]);
+            let y = run(&m, shape);
+            assert_eq!(y.shape(), [shape.0, shape.1, 1, 1]);
+        }
+    }
+
+    let scale = floats("scale", &[3], vec![1.0; 3]);
+    let m = single_op("LayerNormalization", vec![], vec![scale], &[0, 2, 2, 3]);
+    let y = run(&m, (0, 2, 2, 3));
+    assert_eq!(y.shape(), [0, 2, 2, 3]);
+}
+
+#[test]
+fn test_import_clip_empty_bound() {
+    let dev: Cpu = Default::default();
+    let min = floats("min", &[0], vec![]);
+    let m = single_op("Clip", vec![], vec![min], &[2, 3]);
+    let model = OnnxModel::from_proto(&m, &dev).unwrap();
+    let x: Tensor<Rank2<2, 3>, f32, _> = dev.zeros();
+    let err = model.run(vec![x.into()]).unwrap_err();
+    assert!(matches!(err, OnnxError::Unsupported(_)), "{err}");
+}
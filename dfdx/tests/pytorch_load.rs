@@ -0,0 +1,280 @@
+#![cfg(feature = "pytorch")]
+
+use std::io::Write;
+
+use dfdx::{
+    prelude::*,
+    pytorch::{PyTorchError, StateDict, TorchDtype},
+};
+
+/// A tensor of a checkpoint, as a strided view of a storage in `data/{storage}`.
+struct Saved {
+    key: &'static str,
+    storage_type: &'static str,
+    storage: &'static str,
+    offset: usize,
+    shape: Vec<usize>,
+    strides: Vec<usize>,
+}
+
+impl Saved {
+    fn f32(key: &'static str, storage: &'static str, shape: &[usize]) -> Self {
+        let mut strides = vec![1; shape.len()];
+        for i in (0..shape.len().saturating_sub(1)).rev() {
+            strides[i] = strides[i + 1] * shape[i + 1];
+        }
+        Self {
+            key,
+            storage_type: "FloatStorage",
+            storage,
+            offset: 0,
+            shape: shape.to_vec(),
+            strides,
+        }
+    }
+}
+
+/// Writes a checkpoint the same way `torch.save(state_dict, path)` does.
+fn save(path: &std::path::Path, tensors: &[Saved], storages: &[(&str, Vec<u8>)]) {
+    fn global(p: &mut Vec<u8>, module: &str, name: &str) {
+        p.push(b'c');
+        p.extend(format!("{module}\n{name}\n").bytes());
+    }
+    fn string(p: &mut Vec<u8>, s: &str) {
+        p.push(b'X');
+        p.extend((s.len() as u32).to_le_bytes());
+        p.extend(s.bytes());
+    }
+    fn int(p: &mut Vec<u8>, i: usize) {
+        p.push(b'J');
+        p.extend((i as i32).to_le_bytes());
+    }
+    fn ints(p: &mut Vec<u8>, xs: &[usize]) {
+        p.push(b'(');
+        xs.iter().for_each(|&x| int(p, x));
+        p.push(b't');
+    }
+
+    let mut p = vec![0x80, 2];
+    global(&mut p, "collections", "OrderedDict");
+    p.extend(b")R(");
+    for t in tensors {
+        let size = match t.storage_type {
+            "LongStorage" => 8,
+            "HalfStorage" => 2,
+            _ => 4,
+        };
+        let numel = storages
+            .iter()
+            .find(|(k, _)| *k == t.storage)
+            .unwrap()
+            .1
+            .len()
+            / size;
+        string(&mut p, t.key);
+        global(&mut p, "torch._utils", "_rebuild_tensor_v2");
+        p.extend(b"((");
+        string(&mut p, "storage");
+        global(&mut p, "torch", t.storage_type);
+        string(&mut p, t.storage);
+        string(&mut p, "cpu");
+        int(&mut p, numel);
+        p.extend(b"tQ");
+        int(&mut p, t.offset);
+        ints(&mut p, &t.shape);
+        ints(&mut p, &t.strides);
+        p.push(0x89);
+        global(&mut p, "collections", "OrderedDict");
+        p.extend(b")RtR");
+    }
+    p.extend(b"u.");
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+    let options = zip::write::FileOptions::default();
+    zip.start_file("archive/data.pkl", options).unwrap();
+    zip.write_all(&p).unwrap();
+    for (key, data) in storages {
+        zip.start_file(format!("archive/data/{key}"), options)
+            .unwrap();
+        zip.write_all(data).unwrap();
+    }
+    zip.start_file("archive/version", options).unwrap();
+    zip.write_all(b"3\n").unwrap();
+    zip.finish().unwrap();
+}
+
+fn f32s(xs: &[f32]) -> Vec<u8> {
+    xs.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+#[test]
+fn test_load_state_dict() {
+    let dev: Cpu = Default::default();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.pt");
+    let mut transposed = Saved::f32("t", "0", &[3, 2]);
+    transposed.strides = vec![1, 3];
+    let mut row = Saved::f32("row", "0", &[3]);
+    row.offset = 3;
+    let mut half = Saved::f32("half", "1", &[2]);
+    half.storage_type = "HalfStorage";
+    let mut long = Saved::f32("long", "2", &[1]);
+    long.storage_type = "LongStorage";
+    save(
+        &path,
+        &[transposed, row, half, long],
+        &[
+            ("0", f32s(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0])),
+            ("1", vec![0x00, 0x3c, 0x00, 0xc1]),
+            ("2", 7i64.to_le_bytes().to_vec()),
+        ],
+    );
+
+    let mut state_dict = StateDict::load(&path).unwrap();
+    assert_eq!(
+        state_dict.keys().collect::<Vec<_>>(),
+        ["t", "row", "half", "long"]
+    );
+    assert_eq!(state_dict.shape("t"), Some([3, 2].as_slice()));
+    assert_eq!(state_dict.dtype("half"), Some(TorchDtype::F16));
+
+    let t: Tensor<DynShape, f32, _> = state_dict.tensor("t", &dev).unwrap();
+    assert_eq!(t.shape(), &DynShape::new(&[3, 2]));
+    assert_eq!(t.as_vec(), [1.0, 4.0, 2.0, 5.0, 3.0, 6.0]);
+    let row: Tensor<DynShape, f64, _> = state_dict.tensor("row", &dev).unwrap();
+    assert_eq!(row.as_vec(), [4.0, 5.0, 6.0]);
+    let half: Tensor<DynShape, f32, _> = state_dict.tensor("half", &dev).unwrap();
+    assert_eq!(half.as_vec(), [1.0, -2.5]);
+    let long: Tensor<DynShape, usize, _> = state_dict.tensor("long", &dev).unwrap();
+    assert_eq!(long.as_vec(), [7]);
+
+    state_dict.rename(|k| (k != "t").then(|| format!("a.{k}")));
+    assert_eq!(
+        state_dict.keys().collect::<Vec<_>>(),
+        ["a.row", "a.half", "a.long"]
+    );
+}
+
+#[test]
+fn test_load_linear_and_layer_norm() {
+    let dev: Cpu = Default::default();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.pt");
+    // nn.Sequential(nn.Linear(3, 2), nn.ReLU(), nn.LayerNorm(2))
+    save(
+        &path,
+        &[
+            Saved::f32("0.weight", "0", &[2, 3]),
+            Saved::f32("0.bias", "1", &[2]),
+            Saved::f32("2.weight", "2", &[2]),
+            Saved::f32("2.bias", "3", &[2]),
+        ],
+        &[
+            ("0", f32s(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0])),
+            ("1", f32s(&[-1.0, 1.0])),
+            ("2", f32s(&[0.5, 2.0])),
+            ("3", f32s(&[0.1, 0.2])),
+        ],
+    );
+
+    type Arch = (LinearConstConfig<3, 2>, ReLU, LayerNorm1DConstConfig<2>);
+    let mut model = dev.build_module::<f32>(Arch::default());
+    model.load_pytorch(&path).unwrap();
+    assert_eq!(model.0.weight.array(), [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    assert_eq!(model.0.bias.array(), [-1.0, 1.0]);
+    assert_eq!(model.2.gamma.array(), [0.5, 2.0]);
+    assert_eq!(model.2.beta.array(), [0.1, 0.2]);
+    assert_eq!(model.2.epsilon, 1e-5);
+
+    // only the first layer, renamed to the keys of a lone `Linear`
+    let mut model = dev.build_module::<f64>(LinearConstConfig::<3, 2>::default());
+    let err = model.load_pytorch(&path).unwrap_err();
+    assert!(
+        matches!(&err, PyTorchError::MissingKeys(keys) if keys == &["weight", "bias"]),
+        "{err}"
+    );
+    model
+        .load_pytorch_with(&path, |k| k.strip_prefix("0.").map(String::from))
+        .unwrap();
+    assert_eq!(model.weight.array(), [[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    assert_eq!(model.bias.array(), [-1.0, 1.0]);
+
+    let mut model = dev.build_module::<f32>(LinearConstConfig::<2, 3>::default());
+    let err = model
+        .load_pytorch_with(&path, |k| k.strip_prefix("0.").map(String::from))
+        .unwrap_err();
+    assert!(
+        matches!(&err, PyTorchError::ShapeMismatch { key, .. } if key == "weight"),
+        "{err}"
+    );
+}
+
+#[test]
+fn test_load_batch_norm() {
+    let dev: Cpu = Default::default();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.pt");
+    let mut num_batches = Saved::f32("num_batches_tracked", "4", &[]);
+    num_batches.storage_type = "LongStorage";
+    save(
+        &path,
+        &[
+            Saved::f32("weight", "0", &[2]),
+            Saved::f32("bias", "1", &[2]),
+            Saved::f32("running_mean", "2", &[2]),
+            Saved::f32("running_var", "3", &[2]),
+            num_batches,
+        ],
+        &[
+            ("0", f32s(&[1.5, 2.5])),
+            ("1", f32s(&[0.1, 0.2])),
+            ("2", f32s(&[-1.0, 1.0])),
+            ("3", f32s(&[4.0, 9.0])),
+            ("4", 100i64.to_le_bytes().to_vec()),
+        ],
+    );
+
+    let mut model = dev.build_module::<f32>(BatchNorm2DConstConfig::<2>::default());
+    model.load_pytorch(&path).unwrap();
+    assert_eq!(model.scale.array(), [1.5, 2.5]);
+    assert_eq!(model.bias.array(), [0.1, 0.2]);
+    assert_eq!(model.running_mean.array(), [-1.0, 1.0]);
+    assert_eq!(model.running_var.array(), [4.0, 9.0]);
+    assert_eq!(model.momentum, 0.1);
+}
+
+#[test]
+fn test_load_not_a_checkpoint() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.pt");
+    std::fs::write(&path, b"\x80\x02}q\x00.").unwrap();
+    let err = StateDict::load(&path).unwrap_err();
+    assert!(matches!(err, PyTorchError::Unsupported(_)), "{err}");
+}
+
+#[test]
+fn test_load_out_of_bounds_view() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("model.pt");
+
+    // the last row of the view is past the end of the storage
+    let mut t = Saved::f32("t", "0", &[2, 2]);
+    t.strides = vec![3, 1];
+    save(&path, &[t], &[("0", f32s(&[1.0, 2.0, 3.0, 4.0]))]);
+    let err = StateDict::load(&path).unwrap_err();
+    assert!(matches!(err, PyTorchError::Invalid(_)), "{err}");
+
+    // a broadcasted view that is too large to allocate
+    let mut t = Saved::f32("t", "0", &[1 << 30, 1 << 30]);
+    t.strides = vec![0, 0];
+    save(&path, &[t], &[("0", f32s(&[1.0]))]);
+    let err = StateDict::load(&path).unwrap_err();
+    assert!(matches!(err, PyTorchError::Invalid(_)), "{err}");
+
+    // the number of elements overflows
+    let mut t = Saved::f32("t", "0", &[1 << 30, 1 << 30, 1 << 30]);
+    t.strides = vec![0, 0, 0];
+    save(&path, &[t], &[("0", f32s(&[1.0]))]);
+    let err = StateDict::load(&path).unwrap_err();
+    assert!(matches!(err, PyTorchError::Invalid(_)), "{err}");
+}